@@ -1,31 +1,66 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::consensusdb::ConsensusDB;
 use aptos_consensus_types::{block::Block, common::Round};
 use futures_channel::oneshot;
 use gaptos::{
-    aptos_consensus::counters::BLOCK_RETRIEVAL_LOCAL_FULFILL_COUNT, aptos_crypto::HashValue,
-    aptos_logger::info,
+    aptos_consensus::counters::BLOCK_RETRIEVAL_LOCAL_FULFILL_COUNT,
+    aptos_crypto::HashValue,
+    aptos_logger::{info, warn},
 };
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// Blocks kept in memory; a node lagging many rounds behind spills the rest to the ConsensusDB.
+pub const MAX_PENDING_BLOCKS_IN_MEMORY: usize = 256;
 
 /// A local buffer to hold incoming blocks before it reaches round manager.
 /// Which can be used to fulfill block request from local due to out of order messages.
+///
+/// At most `capacity` blocks are held in memory. Beyond that the highest rounds, the furthest
+/// from being needed, are spilled to the ConsensusDB `pending_block` column family, or dropped
+/// without one, and are reloaded as commits garbage-collect the rounds below them.
 pub struct PendingBlocks {
     blocks_by_hash: HashMap<HashValue, Block>,
     blocks_by_round: BTreeMap<Round, Block>,
+    spilled_by_round: BTreeMap<Round, HashValue>,
+    spilled_by_hash: HashMap<HashValue, Round>,
+    capacity: usize,
+    spill: Option<Arc<ConsensusDB>>,
     pending_request: Option<(HashValue, oneshot::Sender<Block>)>,
 }
 
 impl PendingBlocks {
     pub fn new() -> Self {
+        Self::with_capacity(MAX_PENDING_BLOCKS_IN_MEMORY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
             blocks_by_hash: HashMap::new(),
             blocks_by_round: BTreeMap::new(),
+            spilled_by_round: BTreeMap::new(),
+            spilled_by_hash: HashMap::new(),
+            capacity,
+            spill: None,
             pending_request: None,
         }
     }
 
+    /// Spills overflowing blocks to `consensus_db` from now on, dropping what a previous epoch or
+    /// run left there. Called at the start of every epoch, so spilled blocks never outlive it.
+    pub fn set_spill(&mut self, consensus_db: Arc<ConsensusDB>) {
+        self.spilled_by_round.clear();
+        self.spilled_by_hash.clear();
+        if let Err(e) = consensus_db.clear_pending_blocks() {
+            warn!("Failed to clear spilled pending blocks: {:?}", e);
+        }
+        self.spill = Some(consensus_db);
+    }
+
     pub fn insert_block(&mut self, block: Block) {
         info!("Pending block inserted: {}", block.id());
         self.blocks_by_hash.insert(block.id(), block.clone());
@@ -39,6 +74,7 @@ impl PendingBlocks {
                 self.pending_request = Some((id, tx));
             }
         }
+        self.evict();
     }
 
     pub fn insert_request(&mut self, block_id: HashValue, tx: oneshot::Sender<Block>) {
@@ -46,6 +82,10 @@ impl PendingBlocks {
             info!("FulFill block request from existing buffer: {}", block_id);
             BLOCK_RETRIEVAL_LOCAL_FULFILL_COUNT.inc();
             tx.send(block.clone()).ok();
+        } else if let Some(block) = self.read_spilled(block_id) {
+            info!("FulFill block request from spilled buffer: {}", block_id);
+            BLOCK_RETRIEVAL_LOCAL_FULFILL_COUNT.inc();
+            tx.send(block).ok();
         } else {
             info!("Insert block request for: {}", block_id);
             self.pending_request = Some((block_id, tx));
@@ -62,5 +102,166 @@ impl PendingBlocks {
                 self.blocks_by_hash.remove(&block.id());
             }
         }
+
+        let retained = self.spilled_by_round.split_off(&(round + 1));
+        let removed = std::mem::replace(&mut self.spilled_by_round, retained);
+        let removed: Vec<(Round, HashValue)> = removed.into_iter().collect();
+        for (_, id) in &removed {
+            self.spilled_by_hash.remove(id);
+        }
+        self.delete_spilled(&removed);
+        self.reload();
+    }
+
+    /// Moves the highest rounds beyond `capacity` out of memory.
+    fn evict(&mut self) {
+        while self.blocks_by_round.len() > self.capacity {
+            let Some((round, block)) = self.blocks_by_round.pop_last() else {
+                break;
+            };
+            self.blocks_by_hash.remove(&block.id());
+            let Some(db) = &self.spill else {
+                warn!(
+                    "Dropped pending block {} of round {}, the buffer is full",
+                    block.id(),
+                    round
+                );
+                continue;
+            };
+            match db.put_pending_block(&block) {
+                Ok(()) => {
+                    self.spilled_by_round.insert(round, block.id());
+                    self.spilled_by_hash.insert(block.id(), round);
+                }
+                Err(e) => warn!("Failed to spill pending block {}: {:?}", block.id(), e),
+            }
+        }
+    }
+
+    /// Brings the lowest spilled rounds back into memory while there is room.
+    fn reload(&mut self) {
+        while self.blocks_by_round.len() < self.capacity {
+            let Some((&round, &id)) = self.spilled_by_round.first_key_value() else {
+                break;
+            };
+            let block = self.read_spilled(id);
+            self.spilled_by_round.remove(&round);
+            self.spilled_by_hash.remove(&id);
+            self.delete_spilled(&[(round, id)]);
+            if let Some(block) = block {
+                self.blocks_by_hash.insert(block.id(), block.clone());
+                self.blocks_by_round.insert(block.round(), block);
+            }
+        }
+    }
+
+    fn read_spilled(&self, block_id: HashValue) -> Option<Block> {
+        let round = *self.spilled_by_hash.get(&block_id)?;
+        let db = self.spill.as_ref()?;
+        match db.get_pending_block(round, block_id) {
+            Ok(block) => block,
+            Err(e) => {
+                warn!("Failed to read spilled pending block {}: {:?}", block_id, e);
+                None
+            }
+        }
+    }
+
+    fn delete_spilled(&self, keys: &[(Round, HashValue)]) {
+        let Some(db) = &self.spill else { return };
+        if let Err(e) = db.delete_pending_blocks(keys) {
+            warn!("Failed to delete {} spilled pending blocks: {:?}", keys.len(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_consensus_types::{
+        block::block_test_utils::certificate_for_genesis, common::Payload,
+    };
+    use gaptos::{aptos_temppath::TempPath, aptos_types::validator_signer::ValidatorSigner};
+    use std::path::PathBuf;
+
+    fn block(round: Round, signer: &ValidatorSigner) -> Block {
+        Block::new_proposal(
+            Payload::empty(false, true),
+            round,
+            round,
+            certificate_for_genesis(),
+            signer,
+            Vec::new(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn overflow_spills_highest_rounds_and_reloads_them_after_gc() {
+        let tmp_dir = TempPath::new();
+        let db = Arc::new(ConsensusDB::new(&tmp_dir, &PathBuf::new()));
+        let signer = ValidatorSigner::random(None);
+        let blocks: Vec<Block> = (1..=5).map(|round| block(round, &signer)).collect();
+
+        let mut pending = PendingBlocks::with_capacity(2);
+        pending.set_spill(db.clone());
+        for block in &blocks {
+            pending.insert_block(block.clone());
+        }
+        assert_eq!(pending.blocks_by_round.len(), 2);
+        assert_eq!(pending.spilled_by_round.keys().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert!(db.get_pending_block(5, blocks[4].id()).unwrap().is_some());
+
+        // A request for a spilled block is answered from the DB, which keeps it.
+        let (tx, mut rx) = oneshot::channel();
+        pending.insert_request(blocks[3].id(), tx);
+        assert_eq!(rx.try_recv().unwrap().map(|b| b.id()), Some(blocks[3].id()));
+
+        // Committing round 2 frees memory for the lowest spilled rounds.
+        pending.gc(2);
+        assert_eq!(pending.blocks_by_round.keys().copied().collect::<Vec<_>>(), vec![3, 4]);
+        assert!(pending.blocks_by_hash.contains_key(&blocks[3].id()));
+        assert_eq!(pending.spilled_by_round.keys().copied().collect::<Vec<_>>(), vec![5]);
+        assert!(db.get_pending_block(3, blocks[2].id()).unwrap().is_none());
+
+        pending.gc(5);
+        assert_eq!(pending.blocks_by_round.len(), 0);
+        assert!(db.get_pending_block(5, blocks[4].id()).unwrap().is_none());
+    }
+
+    #[test]
+    fn overflow_without_a_db_drops_highest_rounds() {
+        let signer = ValidatorSigner::random(None);
+        let mut pending = PendingBlocks::with_capacity(2);
+        for round in 1..=4 {
+            pending.insert_block(block(round, &signer));
+        }
+        assert_eq!(pending.blocks_by_round.len(), 2);
+        assert_eq!(pending.blocks_by_round.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert!(pending.spilled_by_round.is_empty());
+    }
+
+    #[test]
+    fn a_new_epoch_drops_the_blocks_spilled_in_the_previous_one() {
+        let tmp_dir = TempPath::new();
+        let db = Arc::new(ConsensusDB::new(&tmp_dir, &PathBuf::new()));
+        let signer = ValidatorSigner::random(None);
+        let blocks: Vec<Block> = (1..=3).map(|round| block(round, &signer)).collect();
+
+        let mut pending = PendingBlocks::with_capacity(1);
+        pending.set_spill(db.clone());
+        for block in &blocks {
+            pending.insert_block(block.clone());
+        }
+        assert!(db.get_pending_block(3, blocks[2].id()).unwrap().is_some());
+
+        // The next epoch starts on the same buffer and DB.
+        pending.set_spill(db.clone());
+        assert!(pending.spilled_by_round.is_empty());
+        assert!(db.get_pending_block(2, blocks[1].id()).unwrap().is_none());
+        assert!(db.get_pending_block(3, blocks[2].id()).unwrap().is_none());
+        let (tx, mut rx) = oneshot::channel();
+        pending.insert_request(blocks[2].id(), tx);
+        assert!(rx.try_recv().unwrap().is_none());
     }
 }
@@ -20,7 +20,10 @@ use gaptos::{
         validator_verifier::ValidatorVerifier,
     },
 };
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
 
 #[test]
 fn test_put_get() {
@@ -219,6 +222,37 @@ fn test_unwind_to_block() {
     assert!(db.get_highest_2chain_timeout_certificate().unwrap().is_none());
 }
 
+#[test]
+fn test_tx_results_prune_and_unwind() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir, &PathBuf::new());
+
+    let (pending, old, committed) = (HashValue::random(), HashValue::random(), HashValue::random());
+    db.put_tx_results(&[
+        (pending, TxResult { status: TxStatus::Pending, updated_at_secs: 200 }),
+        (old, TxResult { status: TxStatus::Committed { block_number: 2 }, updated_at_secs: 100 }),
+        (
+            committed,
+            TxResult { status: TxStatus::Committed { block_number: 5 }, updated_at_secs: 200 },
+        ),
+    ])
+    .unwrap();
+    assert_eq!(
+        db.get_tx_result(&committed).unwrap().map(|result| result.status),
+        Some(TxStatus::Committed { block_number: 5 })
+    );
+
+    // Expired entries go, the others stay
+    assert_eq!(db.prune_tx_results(150).unwrap(), 1);
+    assert!(db.get_tx_result(&old).unwrap().is_none());
+    assert!(db.get_tx_result(&pending).unwrap().is_some());
+
+    // Commits past the unwind target are forgotten; pending transactions are not affected
+    db.unwind_to_block(4).unwrap();
+    assert!(db.get_tx_result(&committed).unwrap().is_none());
+    assert!(db.get_tx_result(&pending).unwrap().is_some());
+}
+
 #[test]
 fn test_reputation_anchor_uses_lagged_commit_and_epoch_boundary() {
     let tmp_dir = TempPath::new();
@@ -359,3 +393,100 @@ fn test_reputation_anchor_skips_epoch_boundary_without_epoch_block_info() {
         })
     );
 }
+
+#[test]
+fn test_snapshot_is_isolated_from_later_writes() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir, &PathBuf::new());
+    db.put_randomness(&vec![(1, vec![1; 32])]).unwrap();
+
+    let snapshot = db.open_snapshot(&tmp_dir.path().join("snapshot")).unwrap();
+    db.put_randomness(&vec![(2, vec![2; 32])]).unwrap();
+
+    assert_eq!(snapshot.get_randomness(1).unwrap(), Some(vec![1; 32]));
+    assert_eq!(snapshot.get_randomness(2).unwrap(), None);
+    assert_eq!(db.get_randomness(2).unwrap(), Some(vec![2; 32]));
+}
+
+#[test]
+fn test_prune_committed_txns_drops_both_indexes() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir, &PathBuf::new());
+    let sender = AccountAddress::random();
+    let txn = |sequence_number| CommittedTxn { hash: HashValue::random(), sender, sequence_number };
+    for block_number in 1..=4 {
+        db.put_committed_txns(block_number, &[txn(block_number * 2), txn(block_number * 2 + 1)])
+            .unwrap();
+    }
+
+    assert_eq!(db.prune_committed_txns(0, 3).unwrap(), 4);
+    let positions = |sender| {
+        db.list_committed_txns(sender, None, false, 10)
+            .unwrap()
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(positions(None), vec![(3, 0), (3, 1), (4, 0), (4, 1)]);
+    assert_eq!(positions(Some(sender)), vec![(3, 0), (3, 1), (4, 0), (4, 1)]);
+    assert_eq!(db.prune_committed_txns(3, 3).unwrap(), 0);
+}
+
+/// Cold store holding the blocks and QCs of one epoch in memory.
+struct MemoryColdStore {
+    blocks: HashMap<HashValue, Vec<u8>>,
+    qcs: BTreeMap<HashValue, Vec<u8>>,
+}
+
+impl ColdBlockStore for MemoryColdStore {
+    fn block_bytes(&self, _epoch: u64, block_id: HashValue) -> Option<Vec<u8>> {
+        self.blocks.get(&block_id).cloned()
+    }
+
+    fn qc_bytes(&self, _epoch: u64, block_id: HashValue) -> Option<Vec<u8>> {
+        self.qcs.get(&block_id).cloned()
+    }
+
+    fn epoch_qc_bytes(&self, _epoch: u64) -> Vec<(HashValue, Vec<u8>)> {
+        self.qcs.iter().map(|(id, bytes)| (*id, bytes.clone())).collect()
+    }
+}
+
+#[test]
+fn test_moved_out_epoch_is_read_from_cold_store_and_not_unwound() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir, &PathBuf::new());
+
+    let genesis = Block::make_genesis_block();
+    let genesis_qc = certificate_for_genesis();
+    let (epoch, block_id) = (genesis.epoch(), genesis.id());
+    db.save_blocks_and_quorum_certificates(vec![genesis.clone()], vec![genesis_qc.clone()])
+        .unwrap();
+    db.save_block_numbers(vec![(epoch, 1, block_id)]).unwrap();
+    db.put::<EpochByBlockNumberSchema>(&1, &epoch).unwrap();
+    db.set_cold_store(Arc::new(MemoryColdStore {
+        blocks: HashMap::from([(block_id, bcs::to_bytes(&genesis).unwrap())]),
+        qcs: BTreeMap::from([(block_id, bcs::to_bytes(&genesis_qc).unwrap())]),
+    }))
+    .unwrap();
+
+    assert_eq!(db.first_hot_epoch().unwrap(), 0);
+    db.move_epoch_out(epoch, vec![(epoch, block_id)]).unwrap();
+    assert_eq!(db.first_hot_epoch().unwrap(), epoch + 1);
+    assert!(db.get::<BlockSchema>(&(epoch, block_id)).unwrap().is_none());
+    assert!(db.get::<QCSchema>(&(epoch, block_id)).unwrap().is_none());
+
+    // Retrieval keeps finding the moved-out block and QC
+    assert_eq!(db.get_block(epoch, block_id).unwrap().map(|block| block.id()), Some(block_id));
+    assert_eq!(db.get_qc(epoch, block_id).unwrap(), Some(genesis_qc.clone()));
+    let end_key = (epoch, HashValue::new([u8::MAX; HashValue::LENGTH]));
+    assert_eq!(db.get_qc_range(&(epoch, HashValue::zero()), &end_key).unwrap(), vec![genesis_qc]);
+
+    // Unwinding into the moved-out epoch would leave its archived blocks behind
+    let mut unwinds = db.subscribe_unwinds();
+    assert!(db.unwind_to_block(0).is_err());
+    assert_eq!(db.get::<EpochByBlockNumberSchema>(&1).unwrap(), Some(epoch));
+    db.unwind_to_block(1).unwrap();
+    assert_eq!(unwinds.try_recv().unwrap(), 1);
+    assert!(unwinds.try_recv().is_err());
+}
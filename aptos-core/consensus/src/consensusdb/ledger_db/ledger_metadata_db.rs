@@ -11,6 +11,7 @@ use gaptos::{
 };
 use rocksdb::ReadOptions;
 use std::sync::Arc;
+use tokio::sync::watch;
 
 const MAX_LEDGER_INFOS: u32 = 256;
 
@@ -97,12 +98,18 @@ pub(crate) struct LedgerMetadataDb {
     /// cache it in memory in order to avoid reading DB and deserializing the object frequently. It
     /// should be updated every time new ledger info and signatures are persisted.
     latest_ledger_info: ArcSwap<Option<LedgerInfoWithSignatures>>,
+    /// Publishes the block number of the latest ledger info whenever the in-memory cache is
+    /// updated, so readers can wait for a height instead of polling the DB.
+    committed_block_number: watch::Sender<u64>,
 }
 impl LedgerMetadataDb {
     pub(super) fn new(db: Arc<DB>) -> Self {
         let latest_ledger_info = get_latest_ledger_info_in_db_impl(&db).expect("DB read failed.");
+        let (committed_block_number, _) = watch::channel(
+            latest_ledger_info.as_ref().map_or(0, |li| li.ledger_info().block_number()),
+        );
         let latest_ledger_info = ArcSwap::from(Arc::new(latest_ledger_info));
-        Self { db, latest_ledger_info }
+        Self { db, latest_ledger_info, committed_block_number }
     }
     pub(super) fn db(&self) -> &DB {
         &self.db
@@ -118,15 +125,25 @@ impl LedgerMetadataDb {
 impl LedgerMetadataDb {
     /// Stores the latest ledger info in memory.
     pub(crate) fn set_latest_ledger_info(&self, ledger_info_with_sigs: LedgerInfoWithSignatures) {
+        let block_number = ledger_info_with_sigs.ledger_info().block_number();
         self.latest_ledger_info.store(Arc::new(Some(ledger_info_with_sigs)));
+        self.committed_block_number.send_replace(block_number);
     }
 
     pub(crate) fn update_latest_ledger_info(&self) -> Result<()> {
         let latest_ledger_info = get_latest_ledger_info_in_db_impl(&self.db)?;
+        let block_number =
+            latest_ledger_info.as_ref().map_or(0, |li| li.ledger_info().block_number());
         self.latest_ledger_info.store(Arc::new(latest_ledger_info));
+        self.committed_block_number.send_replace(block_number);
         Ok(())
     }
 
+    /// Subscribes to updates of the latest committed block number.
+    pub(crate) fn subscribe_committed_block_number(&self) -> watch::Receiver<u64> {
+        self.committed_block_number.subscribe()
+    }
+
     pub(crate) fn get_latest_ledger_info(&self) -> Option<LedgerInfoWithSignatures> {
         let latest_ledger_info = self.latest_ledger_info.load();
         latest_ledger_info.as_ref().clone()
@@ -10,7 +10,8 @@ pub mod schema;
 use crate::error::DbError;
 use anyhow::Result;
 use aptos_consensus_types::{
-    block::Block, pipelined_block::PipelinedBlock, quorum_cert::QuorumCert,
+    block::Block, pipeline::commit_vote::CommitVote, pipelined_block::PipelinedBlock,
+    quorum_cert::QuorumCert,
 };
 use gaptos::{
     aptos_crypto::HashValue,
@@ -21,22 +22,36 @@ use gaptos::{
         Options, DB, DEFAULT_COLUMN_FAMILY_NAME,
     },
     aptos_storage_interface::AptosDbError,
-    aptos_types::randomness::{RandMetadata, Randomness},
+    aptos_types::{
+        ledger_info::LedgerInfoWithSignatures,
+        randomness::{RandMetadata, Randomness},
+    },
+    move_core_types::account_address::AccountAddress,
 };
 use ledger_db::LedgerDb;
+use once_cell::sync::OnceCell;
 use rocksdb::ReadOptions;
 use schema::{
     block::BLOCK_NUMBER_CF_NAME,
+    commit_vote::{CommitProofSchema, CommitVoteSchema},
+    future_round_msg::FutureRoundMsgSchema,
+    pending_block::PendingBlockSchema,
     single_entry::{SingleEntryKey, SingleEntrySchema},
-    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, EPOCH_BY_BLOCK_NUMBER_CF_NAME,
-    LEDGER_INFO_CF_NAME, NODE_CF_NAME, QC_CF_NAME, RANDOMNESS_CF_NAME, SINGLE_ENTRY_CF_NAME,
+    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, COMMITTED_TXN_CF_NAME, COMMIT_PROOF_CF_NAME,
+    COMMIT_VOTE_CF_NAME, DAG_VOTE_CF_NAME, EPOCH_BY_BLOCK_NUMBER_CF_NAME, EVIDENCE_CF_NAME,
+    FUTURE_ROUND_MSG_CF_NAME, LEDGER_INFO_CF_NAME, NODE_CF_NAME, PENDING_BLOCK_CF_NAME, QC_CF_NAME,
+    RANDOMNESS_CF_NAME, SINGLE_ENTRY_CF_NAME, TXN_BY_SENDER_CF_NAME, TX_RESULT_CF_NAME,
 };
+pub(crate) use schema::future_round_msg::{FutureRoundKey, FutureRoundMsg};
 pub use schema::{
-    block::{BlockNumberSchema, BlockSchema},
+    block::{BlockHeader, BlockNumberSchema, BlockSchema, RawBlock, RawBlockSchema},
+    committed_txn::{CommittedTxn, CommittedTxnSchema, TxnBySenderSchema, TxnPosition},
     dag::{CertifiedNodeSchema, DagVoteSchema, NodeSchema},
     epoch_by_block_number::EpochByBlockNumberSchema,
+    evidence::{EquivocationEvidence, EvidenceKind, EvidenceSchema},
     ledger_info::LedgerInfoSchema,
     quorum_certificate::QCSchema,
+    tx_result::{TxResult, TxResultSchema, TxStatus},
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -46,10 +61,13 @@ use std::{
     sync::Arc,
     time::Instant,
 };
+use tokio::sync::broadcast;
 
 /// The name of the consensus db file
 pub const CONSENSUS_DB_NAME: &str = "consensus_db";
 const RECENT_BLOCKS_RANGE: u64 = 256;
+/// Unwinds a slow subscriber of [`ConsensusDB::subscribe_unwinds`] may fall behind by.
+const UNWIND_CHANNEL_CAPACITY: usize = 16;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct CommittedBlockAnchor {
@@ -74,7 +92,7 @@ pub fn create_checkpoint<P: AsRef<Path> + Clone>(db_path: P, checkpoint_path: P)
     Ok(())
 }
 
-#[derive(Default, Deserialize, Serialize)]
+#[derive(Clone, Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct GravityNodeConfig {
     pub consensus_public_key: String,
@@ -93,14 +111,39 @@ pub fn load_file(path: &Path) -> GravityNodeConfigSet {
     serde_yaml::from_str(&contents).unwrap()
 }
 
+/// Holds the blocks and QCs of epochs moved out of the DB, e.g. the API's cold tier. Block and
+/// QC reads of those epochs fall back to it.
+pub trait ColdBlockStore: Send + Sync {
+    /// The stored BCS bytes of the block of `epoch` with id `block_id`.
+    fn block_bytes(&self, epoch: u64, block_id: HashValue) -> Option<Vec<u8>>;
+    /// The BCS bytes of the QC of `epoch` certifying the block with id `block_id`.
+    fn qc_bytes(&self, epoch: u64, block_id: HashValue) -> Option<Vec<u8>>;
+    /// The BCS bytes of every QC of `epoch`, by certified block id in ascending order.
+    fn epoch_qc_bytes(&self, epoch: u64) -> Vec<(HashValue, Vec<u8>)>;
+}
+
 pub struct ConsensusDB {
     db: Arc<DB>,
+    path: PathBuf,
     pub node_config_set: GravityNodeConfigSet,
     pub ledger_db: LedgerDb,
+    cold_store: OnceCell<Arc<dyn ColdBlockStore>>,
+    /// Publishes the target block number of every unwind.
+    unwinds: broadcast::Sender<u64>,
 }
 
 impl ConsensusDB {
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P, node_config_path: &PathBuf) -> Self {
+        let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
+        println!("consensun path : {:?}", path);
+        let mut node_config_set = BTreeMap::new();
+        if node_config_path.to_str().is_some() && !node_config_path.to_str().unwrap().is_empty() {
+            node_config_set = load_file(node_config_path.as_path());
+        }
+        Self::open(path, node_config_set).expect("ConsensusDB open failed; unable to continue")
+    }
+
+    fn open(path: PathBuf, node_config_set: GravityNodeConfigSet) -> Result<Self> {
         let column_families = vec![
             /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
             BLOCK_CF_NAME,
@@ -113,29 +156,131 @@ impl ConsensusDB {
             BLOCK_NUMBER_CF_NAME,
             EPOCH_BY_BLOCK_NUMBER_CF_NAME,
             RANDOMNESS_CF_NAME,
+            COMMITTED_TXN_CF_NAME,
+            TXN_BY_SENDER_CF_NAME,
+            EVIDENCE_CF_NAME,
+            TX_RESULT_CF_NAME,
+            PENDING_BLOCK_CF_NAME,
+            COMMIT_VOTE_CF_NAME,
+            COMMIT_PROOF_CF_NAME,
+            FUTURE_ROUND_MSG_CF_NAME,
             "ordered_anchor_id", // deprecated CF
         ];
 
-        let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
-        println!("consensun path : {:?}", path);
         let instant = Instant::now();
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
-        let db = Arc::new(
-            DB::open(path.clone(), "consensus", column_families, &opts)
-                .expect("ConsensusDB open failed; unable to continue"),
-        );
+        let db = Arc::new(DB::open(path.clone(), "consensus", column_families, &opts)?);
 
         info!("Opened ConsensusDB at {:?} in {} ms", path, instant.elapsed().as_millis());
-        let mut node_config_set = BTreeMap::new();
-        if node_config_path.to_str().is_some() && !node_config_path.to_str().unwrap().is_empty() {
-            node_config_set = load_file(node_config_path.as_path());
+        let ledger_db = LedgerDb::new(db.clone());
+
+        let (unwinds, _) = broadcast::channel(UNWIND_CHANNEL_CAPACITY);
+        Ok(Self { db, path, node_config_set, ledger_db, cold_store: OnceCell::new(), unwinds })
+    }
+
+    /// Opens a point-in-time copy of the DB in `snapshot_path`, which must not exist. The copy
+    /// is a checkpoint opened as a DB of its own, so its readers keep seeing the state as of this
+    /// call while the node goes on committing. Delete the directory once the copy is dropped.
+    pub fn open_snapshot(&self, snapshot_path: &Path) -> Result<Self> {
+        self.create_checkpoint(snapshot_path)?;
+        Self::open(snapshot_path.to_path_buf(), self.node_config_set.clone())
+    }
+
+    /// Directory the DB is stored in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Makes reads of blocks and QCs of moved-out epochs fall back to `store`; only the first
+    /// store set is used.
+    pub fn set_cold_store(&self, store: Arc<dyn ColdBlockStore>) -> Result<()> {
+        self.cold_store
+            .set(store)
+            .map_err(|_| anyhow::anyhow!("ConsensusDB already has a cold store"))
+    }
+
+    /// First epoch whose blocks and QCs are still in the DB; earlier ones were moved out with
+    /// [`Self::move_epoch_out`].
+    pub fn first_hot_epoch(&self) -> Result<u64, DbError> {
+        Ok(match self.db.get::<SingleEntrySchema>(&SingleEntryKey::FirstHotEpoch)? {
+            Some(bytes) => bcs::from_bytes(&bytes).map_err(anyhow::Error::from)?,
+            None => 0,
+        })
+    }
+
+    /// Deletes the blocks and QCs of `epoch`, given by `block_keys`, once a cold store holds
+    /// them, and marks it and every earlier epoch as moved out. Block numbers and ledger infos
+    /// stay.
+    pub fn move_epoch_out(
+        &self,
+        epoch: u64,
+        block_keys: Vec<(u64, HashValue)>,
+    ) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        block_keys.iter().try_for_each(|key| {
+            batch.delete::<BlockSchema>(key)?;
+            batch.delete::<QCSchema>(key)
+        })?;
+        let first_hot_epoch = self.first_hot_epoch()?.max(epoch + 1);
+        batch.put::<SingleEntrySchema>(
+            &SingleEntryKey::FirstHotEpoch,
+            &bcs::to_bytes(&first_hot_epoch).map_err(anyhow::Error::from)?,
+        )?;
+        self.commit(batch)
+    }
+
+    /// The cold store, when `epoch` was moved out to one.
+    fn cold_store_of(&self, epoch: u64) -> Result<Option<&Arc<dyn ColdBlockStore>>, DbError> {
+        match self.cold_store.get() {
+            Some(store) if epoch < self.first_hot_epoch()? => Ok(Some(store)),
+            _ => Ok(None),
         }
+    }
 
-        let ledger_db = LedgerDb::new(db.clone());
+    /// Creates a physical checkpoint of the open DB in `checkpoint_path`, which must not exist.
+    /// SST files are hard-linked when the path is on the same filesystem, so this is cheap and
+    /// consistent while the node keeps writing.
+    pub fn create_checkpoint(&self, checkpoint_path: &Path) -> Result<()> {
+        self.db.create_checkpoint(checkpoint_path)?;
+        Ok(())
+    }
 
-        Self { db, node_config_set, ledger_db }
+    /// Name and contents of the newest `OPTIONS-*` file RocksDB wrote for this database, which
+    /// records the RocksDB version and every option in effect.
+    pub fn rocksdb_options_file(&self) -> Result<Option<(String, String)>> {
+        let mut newest: Option<(u64, PathBuf)> = None;
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            let number = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("OPTIONS-"))
+                .and_then(|number| number.parse::<u64>().ok());
+            if let Some(number) = number {
+                if newest.as_ref().map_or(true, |(newest, _)| number > *newest) {
+                    newest = Some((number, path));
+                }
+            }
+        }
+        let Some((_, path)) = newest else { return Ok(None) };
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        Ok(Some((name, std::fs::read_to_string(path)?)))
+    }
+
+    /// Returns a receiver that is notified with the block number of the latest ledger info every
+    /// time a commit (or an unwind/sync) updates it.
+    pub fn subscribe_commits(&self) -> tokio::sync::watch::Receiver<u64> {
+        self.ledger_db.metadata_db().subscribe_committed_block_number()
+    }
+
+    /// Returns a receiver of the target block number of every later unwind, sent once the
+    /// unwound data is deleted. Unlike the latest block number of [`Self::subscribe_commits`],
+    /// an unwind is never coalesced with the commits that follow it; a receiver that falls
+    /// behind is told it lagged.
+    pub fn subscribe_unwinds(&self) -> broadcast::Receiver<u64> {
+        self.unwinds.subscribe()
     }
 
     /// Returns the newest committed execution block whose consensus round is no newer than
@@ -411,7 +556,17 @@ impl ConsensusDB {
     }
 
     pub fn get_block(&self, epoch: u64, block_id: HashValue) -> Result<Option<Block>, DbError> {
-        let block = self.get::<BlockSchema>(&(epoch, block_id))?;
+        let block = match self.get::<BlockSchema>(&(epoch, block_id))? {
+            Some(block) => Some(block),
+            None => match self.cold_store_of(epoch)? {
+                Some(store) => store
+                    .block_bytes(epoch, block_id)
+                    .map(|bytes| bcs::from_bytes::<Block>(&bytes))
+                    .transpose()
+                    .map_err(anyhow::Error::from)?,
+                None => None,
+            },
+        };
         if let Some(block) = &block {
             if block.block_number().is_none() {
                 let block_number = self.get::<BlockNumberSchema>(&(epoch, block_id))?;
@@ -432,19 +587,37 @@ impl ConsensusDB {
     }
 
     pub fn get_qc(&self, epoch: u64, block_id: HashValue) -> Result<Option<QuorumCert>, DbError> {
-        self.get::<QCSchema>(&(epoch, block_id))
+        if let Some(qc) = self.get::<QCSchema>(&(epoch, block_id))? {
+            return Ok(Some(qc));
+        }
+        let Some(store) = self.cold_store_of(epoch)? else { return Ok(None) };
+        Ok(store
+            .qc_bytes(epoch, block_id)
+            .map(|bytes| bcs::from_bytes::<QuorumCert>(&bytes))
+            .transpose()
+            .map_err(anyhow::Error::from)?)
     }
 
+    /// QCs keyed in `[start_key, end_key)`, those of moved-out epochs read from the cold store.
     pub fn get_qc_range(
         &self,
         start_key: &(u64, HashValue),
         end_key: &(u64, HashValue),
     ) -> Result<Vec<QuorumCert>, DbError> {
-        Ok(self
-            .get_range::<QCSchema>(start_key, end_key)?
-            .into_iter()
-            .map(|(_, value)| value)
-            .collect())
+        let mut qcs = Vec::new();
+        if let Some(store) = self.cold_store.get() {
+            let cold_epochs_end = self.first_hot_epoch()?.min(end_key.0.saturating_add(1));
+            for epoch in start_key.0..cold_epochs_end {
+                for (block_id, bytes) in store.epoch_qc_bytes(epoch) {
+                    let key = (epoch, block_id);
+                    if *start_key <= key && key < *end_key {
+                        qcs.push(bcs::from_bytes(&bytes).map_err(anyhow::Error::from)?);
+                    }
+                }
+            }
+        }
+        qcs.extend(self.get_range::<QCSchema>(start_key, end_key)?.into_iter().map(|(_, qc)| qc));
+        Ok(qcs)
     }
 
     pub fn get_max_epoch(&self) -> u64 {
@@ -477,16 +650,333 @@ impl ConsensusDB {
         Ok(self.get::<schema::randomness::RandomnessSchema>(&block_number)?)
     }
 
+    /// Indexes the user transactions committed in `block_number`, in block order.
+    pub fn put_committed_txns(
+        &self,
+        block_number: u64,
+        txns: &[CommittedTxn],
+    ) -> Result<(), DbError> {
+        if txns.is_empty() {
+            return Ok(());
+        }
+        let mut batch = SchemaBatch::new();
+        for (index, txn) in txns.iter().enumerate() {
+            let position = (block_number, index as u32);
+            batch.put::<CommittedTxnSchema>(&position, txn)?;
+            batch.put::<TxnBySenderSchema>(&(txn.sender, position), &())?;
+        }
+        self.commit(batch)
+    }
+
+    /// Drops the indexed transactions of blocks in `from_block..before_block` and returns how
+    /// many there were.
+    pub fn prune_committed_txns(
+        &self,
+        from_block: u64,
+        before_block: u64,
+    ) -> Result<usize, DbError> {
+        let entries =
+            self.get_range::<CommittedTxnSchema>(&(from_block, 0), &(before_block, 0))?;
+        let mut batch = SchemaBatch::new();
+        for (position, txn) in &entries {
+            batch.delete::<CommittedTxnSchema>(position)?;
+            batch.delete::<TxnBySenderSchema>(&(txn.sender, *position))?;
+        }
+        self.commit(batch)?;
+        Ok(entries.len())
+    }
+
+    /// Stores equivocation evidence unless the same author was already caught equivocating in
+    /// the same way in that round, and returns whether it was new.
+    pub fn put_evidence(&self, evidence: &EquivocationEvidence) -> Result<bool, DbError> {
+        if self.get::<EvidenceSchema>(&evidence.key())?.is_some() {
+            return Ok(false);
+        }
+        let mut batch = SchemaBatch::new();
+        batch.put::<EvidenceSchema>(&evidence.key(), evidence)?;
+        self.commit(batch)?;
+        Ok(true)
+    }
+
+    /// Stored equivocation evidence ordered by epoch and round, optionally of one epoch only.
+    pub fn list_evidence(&self, epoch: Option<u64>) -> Result<Vec<EquivocationEvidence>, DbError> {
+        let evidence = match epoch {
+            Some(epoch) => self.get_range::<EvidenceSchema>(
+                &(epoch, 0, AccountAddress::ZERO, EvidenceKind::Vote),
+                &(epoch.saturating_add(1), 0, AccountAddress::ZERO, EvidenceKind::Vote),
+            )?,
+            None => self.get_all::<EvidenceSchema>()?,
+        };
+        Ok(evidence.into_iter().map(|(_, evidence)| evidence).collect())
+    }
+
+    /// Spills a proposal that overflowed the in-memory pending block buffer.
+    pub fn put_pending_block(&self, block: &Block) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<PendingBlockSchema>(&(block.round(), block.id()), block)?;
+        self.commit(batch)
+    }
+
+    pub fn get_pending_block(
+        &self,
+        round: u64,
+        block_id: HashValue,
+    ) -> Result<Option<Block>, DbError> {
+        self.get::<PendingBlockSchema>(&(round, block_id))
+    }
+
+    pub fn delete_pending_blocks(&self, keys: &[(u64, HashValue)]) -> Result<(), DbError> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut batch = SchemaBatch::new();
+        for key in keys {
+            batch.delete::<PendingBlockSchema>(key)?;
+        }
+        self.commit(batch)
+    }
+
+    /// Drops every spilled proposal, e.g. those a previous run left behind.
+    pub fn clear_pending_blocks(&self) -> Result<(), DbError> {
+        let keys: Vec<(u64, HashValue)> =
+            self.get_all::<PendingBlockSchema>()?.into_iter().map(|(key, _)| key).collect();
+        self.delete_pending_blocks(&keys)
+    }
+
+    /// Spills a vote or proposal that overflowed the in-memory future-round buffer.
+    pub(crate) fn put_future_round_msg(&self, msg: &FutureRoundMsg) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<FutureRoundMsgSchema>(&msg.key(), msg)?;
+        self.commit(batch)
+    }
+
+    pub(crate) fn get_future_round_msg(
+        &self,
+        key: &FutureRoundKey,
+    ) -> Result<Option<FutureRoundMsg>, DbError> {
+        self.get::<FutureRoundMsgSchema>(key)
+    }
+
+    pub(crate) fn delete_future_round_msgs(&self, keys: &[FutureRoundKey]) -> Result<(), DbError> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut batch = SchemaBatch::new();
+        for key in keys {
+            batch.delete::<FutureRoundMsgSchema>(key)?;
+        }
+        self.commit(batch)
+    }
+
+    /// Drops every spilled future-round message, e.g. those of a previous epoch or run.
+    pub(crate) fn clear_future_round_msgs(&self) -> Result<(), DbError> {
+        let keys: Vec<FutureRoundKey> =
+            self.get_all::<FutureRoundMsgSchema>()?.into_iter().map(|(key, _)| key).collect();
+        self.delete_future_round_msgs(&keys)
+    }
+
+    /// Replaces the saved commit votes and proofs of blocks not committed yet with `votes` and
+    /// `proofs`.
+    pub fn save_commit_votes(
+        &self,
+        votes: &[CommitVote],
+        proofs: &[LedgerInfoWithSignatures],
+    ) -> Result<(), DbError> {
+        let mut batch = SchemaBatch::new();
+        for (key, _) in self.get_all::<CommitVoteSchema>()? {
+            batch.delete::<CommitVoteSchema>(&key)?;
+        }
+        for (key, _) in self.get_all::<CommitProofSchema>()? {
+            batch.delete::<CommitProofSchema>(&key)?;
+        }
+        for vote in votes {
+            let key = (vote.epoch(), vote.round(), vote.commit_info().id(), vote.author());
+            batch.put::<CommitVoteSchema>(&key, vote)?;
+        }
+        for proof in proofs {
+            let key = (proof.ledger_info().epoch(), proof.commit_info().round());
+            batch.put::<CommitProofSchema>(&key, proof)?;
+        }
+        self.commit(batch)
+    }
+
+    /// Takes the commit votes and proofs saved for `epoch`, dropping everything saved from the DB.
+    pub fn take_commit_votes(
+        &self,
+        epoch: u64,
+    ) -> Result<(Vec<CommitVote>, Vec<LedgerInfoWithSignatures>), DbError> {
+        let votes = self
+            .get_range::<CommitVoteSchema>(
+                &(epoch, 0, HashValue::zero(), AccountAddress::ZERO),
+                &(epoch.saturating_add(1), 0, HashValue::zero(), AccountAddress::ZERO),
+            )?
+            .into_iter()
+            .map(|(_, vote)| vote)
+            .collect();
+        let proofs = self
+            .get_range::<CommitProofSchema>(&(epoch, 0), &(epoch.saturating_add(1), 0))?
+            .into_iter()
+            .map(|(_, proof)| proof)
+            .collect();
+        self.save_commit_votes(&[], &[])?;
+        Ok((votes, proofs))
+    }
+
+    /// Stores the latest status of each transaction, replacing what was stored before.
+    pub fn put_tx_results(&self, results: &[(HashValue, TxResult)]) -> Result<(), DbError> {
+        if results.is_empty() {
+            return Ok(());
+        }
+        let mut batch = SchemaBatch::new();
+        for (hash, result) in results {
+            batch.put::<TxResultSchema>(hash, result)?;
+        }
+        self.commit(batch)
+    }
+
+    pub fn get_tx_result(&self, hash: &HashValue) -> Result<Option<TxResult>, DbError> {
+        self.get::<TxResultSchema>(hash)
+    }
+
+    /// Deletes the transaction results last updated before `updated_before_secs` and returns
+    /// how many there were.
+    pub fn prune_tx_results(&self, updated_before_secs: u64) -> Result<usize, DbError> {
+        let mut batch = SchemaBatch::new();
+        let mut pruned = 0;
+        let mut iter = self.db.iter::<TxResultSchema>()?;
+        iter.seek_to_first();
+        for entry in iter {
+            let (hash, result) = entry?;
+            if result.updated_at_secs < updated_before_secs {
+                batch.delete::<TxResultSchema>(&hash)?;
+                pruned += 1;
+            }
+        }
+        self.commit(batch)?;
+        Ok(pruned)
+    }
+
+    /// Up to `limit` indexed transactions strictly after `after` (before it when `descending`),
+    /// optionally only those sent by `sender`. Without `after` the scan starts at the oldest
+    /// (newest when `descending`) transaction.
+    pub fn list_committed_txns(
+        &self,
+        sender: Option<AccountAddress>,
+        after: Option<TxnPosition>,
+        descending: bool,
+        limit: usize,
+    ) -> Result<Vec<(TxnPosition, CommittedTxn)>, DbError> {
+        let start = after.unwrap_or(if descending { (u64::MAX, u32::MAX) } else { (0, 0) });
+        let Some(sender) = sender else {
+            let positions = self.scan_positions::<CommittedTxnSchema, _>(
+                start,
+                after.is_some(),
+                descending,
+                limit,
+                |position| Some(*position),
+            )?;
+            return self.committed_txns_at(positions);
+        };
+        let positions = self.scan_positions::<TxnBySenderSchema, _>(
+            (sender, start),
+            after.is_some(),
+            descending,
+            limit,
+            |(key_sender, position)| (*key_sender == sender).then_some(*position),
+        )?;
+        self.committed_txns_at(positions)
+    }
+
+    /// Walks `S` from `start` and maps keys to positions until `position_of` returns `None`
+    /// or `limit` positions are collected. `start` itself is skipped when `exclusive`.
+    fn scan_positions<S, F>(
+        &self,
+        start: S::Key,
+        exclusive: bool,
+        descending: bool,
+        limit: usize,
+        position_of: F,
+    ) -> Result<Vec<TxnPosition>, DbError>
+    where
+        S: Schema,
+        S::Key: PartialEq,
+        F: Fn(&S::Key) -> Option<TxnPosition>,
+    {
+        let mut positions = Vec::with_capacity(limit);
+        if descending {
+            let mut iter = self.db.rev_iter::<S>()?;
+            iter.seek_for_prev(&start)?;
+            for entry in iter {
+                let (key, _) = entry?;
+                if exclusive && key == start {
+                    continue;
+                }
+                let Some(position) = position_of(&key) else { break };
+                positions.push(position);
+                if positions.len() == limit {
+                    break;
+                }
+            }
+        } else {
+            let mut iter = self.db.iter::<S>()?;
+            iter.seek(&start)?;
+            for entry in iter {
+                let (key, _) = entry?;
+                if exclusive && key == start {
+                    continue;
+                }
+                let Some(position) = position_of(&key) else { break };
+                positions.push(position);
+                if positions.len() == limit {
+                    break;
+                }
+            }
+        }
+        Ok(positions)
+    }
+
+    fn committed_txns_at(
+        &self,
+        positions: Vec<TxnPosition>,
+    ) -> Result<Vec<(TxnPosition, CommittedTxn)>, DbError> {
+        let mut txns = Vec::with_capacity(positions.len());
+        for position in positions {
+            if let Some(txn) = self.get::<CommittedTxnSchema>(&position)? {
+                txns.push((position, txn));
+            }
+        }
+        Ok(txns)
+    }
+
     /// Unwind the consensus DB to the given target block number.
     /// All data for blocks with block_number > target_block_number will be deleted.
     /// This includes: blocks, QCs, block numbers, ledger info, epoch-by-block-number,
-    /// randomness, last vote, and highest 2-chain timeout certificate.
+    /// randomness, committed transactions and their cached results, last vote, and highest
+    /// 2-chain timeout certificate.
+    /// Fails without deleting anything when a block past the target is in an epoch moved to
+    /// the cold tier.
     pub fn unwind_to_block(
         &self,
         target_block_number: u64,
     ) -> Result<(Vec<crate::quorum_store::types::BatchKey>, Vec<u64>), DbError> {
         info!("ConsensusDB::unwind_to_block: unwinding to block {}", target_block_number);
 
+        // Blocks of moved-out epochs are not in the DB to delete, so the target must lie past them.
+        let range_start = target_block_number.saturating_add(1);
+        let first_hot_epoch = self.first_hot_epoch()?;
+        let epoch_entries = self.get_range::<EpochByBlockNumberSchema>(&range_start, &u64::MAX)?;
+        let first_unwound_epoch = epoch_entries.iter().map(|(_, epoch)| *epoch).min();
+        if let Some(epoch) = first_unwound_epoch.filter(|epoch| *epoch < first_hot_epoch) {
+            return Err(anyhow::anyhow!(
+                "Cannot unwind to block {}: epoch {} was moved to the cold tier, only epochs from \
+                 {} on can be unwound",
+                target_block_number,
+                epoch,
+                first_hot_epoch
+            )
+            .into());
+        }
+
         let mut batch = SchemaBatch::new();
         let mut deleted_blocks = 0u64;
         let mut batches_to_delete = Vec::new();
@@ -603,8 +1093,6 @@ impl ConsensusDB {
         }
 
         // Step 2: Delete block_number-keyed CFs by range query (target+1, u64::MAX).
-        let range_start = target_block_number.saturating_add(1);
-
         // LedgerInfoSchema
         let ledger_entries = self.get_range::<LedgerInfoSchema>(&range_start, &u64::MAX)?;
         for (bn, _) in &ledger_entries {
@@ -612,7 +1100,6 @@ impl ConsensusDB {
         }
 
         // EpochByBlockNumberSchema
-        let epoch_entries = self.get_range::<EpochByBlockNumberSchema>(&range_start, &u64::MAX)?;
         for (bn, _) in &epoch_entries {
             batch.delete::<EpochByBlockNumberSchema>(bn)?;
         }
@@ -624,6 +1111,27 @@ impl ConsensusDB {
             batch.delete::<schema::randomness::RandomnessSchema>(bn)?;
         }
 
+        // CommittedTxnSchema and its sender index
+        let txn_entries =
+            self.get_range::<CommittedTxnSchema>(&(range_start, 0), &(u64::MAX, u32::MAX))?;
+        for (position, txn) in &txn_entries {
+            batch.delete::<CommittedTxnSchema>(position)?;
+            batch.delete::<TxnBySenderSchema>(&(txn.sender, *position))?;
+        }
+
+        // TxResultSchema: commits past the target are void. The cache is small, so it is scanned.
+        let mut unwound_results = 0usize;
+        let mut iter = self.db.iter::<TxResultSchema>()?;
+        iter.seek_to_first();
+        for entry in iter {
+            let (hash, result) = entry?;
+            let TxStatus::Committed { block_number } = result.status else { continue };
+            if block_number >= range_start {
+                batch.delete::<TxResultSchema>(&hash)?;
+                unwound_results += 1;
+            }
+        }
+
         // Step 3: Clear stale vote and timeout certificate.
         batch.delete::<schema::single_entry::SingleEntrySchema>(
             &schema::single_entry::SingleEntryKey::LastVote,
@@ -648,13 +1156,19 @@ impl ConsensusDB {
             DbError::from(anyhow::anyhow!("Failed to update latest ledger info: {}", e))
         })?;
 
+        // Step 6: Tell the subscribers, if any.
+        let _ = self.unwinds.send(target_block_number);
+
         info!(
             "ConsensusDB::unwind_to_block complete: deleted {} blocks, \
-             {} ledger_infos, {} epoch_entries, {} randomness entries. Target: {}",
+             {} ledger_infos, {} epoch_entries, {} randomness entries, {} transactions, \
+             {} transaction results. Target: {}",
             deleted_blocks,
             ledger_entries.len(),
             epoch_entries.len(),
             randomness_entries.len(),
+            txn_entries.len(),
+            unwound_results,
             target_block_number
         );
 
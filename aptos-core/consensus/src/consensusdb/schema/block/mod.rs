@@ -4,14 +4,15 @@
 
 //! This module defines physical storage schema for consensus block.
 //!
-//! Serialized block bytes identified by block_hash.
+//! Serialized block bytes identified by block_hash. [`RawBlockSchema`] reads the same column
+//! family without decoding, for readers that only need block metadata.
 //! ```text
 //! |<---key---->|<---value--->|
 //! | block_hash |    block    |
 //! ```
 
 use crate::define_schema;
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use aptos_consensus_types::block::Block;
 use byteorder::{BigEndian, ReadBytesExt};
 use gaptos::{
@@ -87,5 +88,65 @@ impl ValueCodec<BlockNumberSchema> for u64 {
     }
 }
 
+define_schema!(RawBlockSchema, (u64, HashValue), RawBlock, BLOCK_CF_NAME);
+
+/// Undecoded BCS bytes of a stored [`Block`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawBlock(pub Vec<u8>);
+
+/// Block metadata read from the fixed-layout prefix of a [`RawBlock`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub epoch: u64,
+    pub round: u64,
+    pub timestamp_usecs: u64,
+    pub parent_id: HashValue,
+}
+
+impl RawBlock {
+    /// Reads the block header without decoding the quorum cert or payload. The BCS layout is
+    /// `block_data { epoch, round, timestamp_usecs, quorum_cert { vote_data { proposed:
+    /// BlockInfo { epoch, round, id, .. }, .. }, .. }, .. }`, and `proposed.id` is the parent id.
+    pub fn header(&self) -> Result<BlockHeader> {
+        let data = &self.0;
+        ensure!(data.len() >= 73, "raw block too short: {} bytes", data.len());
+        let read_u64 =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        // HashValue is serialized as length-prefixed bytes
+        ensure!(data[40] as usize == HashValue::LENGTH, "unexpected parent id length {}", data[40]);
+        Ok(BlockHeader {
+            epoch: read_u64(0),
+            round: read_u64(8),
+            timestamp_usecs: read_u64(16),
+            parent_id: HashValue::from_slice(&data[41..73])?,
+        })
+    }
+
+    /// Fully decodes the block, including its payload.
+    pub fn decode(&self) -> Result<Block> {
+        <Block as ValueCodec<BlockSchema>>::decode_value(&self.0)
+    }
+}
+
+impl KeyCodec<RawBlockSchema> for (u64, HashValue) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        <Self as KeyCodec<BlockSchema>>::encode_key(self)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        <Self as KeyCodec<BlockSchema>>::decode_key(data)
+    }
+}
+
+impl ValueCodec<RawBlockSchema> for RawBlock {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.0.clone())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(RawBlock(data.to_vec()))
+    }
+}
+
 #[cfg(test)]
 mod test;
@@ -11,4 +11,16 @@ fn test_encode_decode() {
     assert_encode_decode::<BlockSchema>(&(block.epoch(), block.id()), &block);
 }
 
+#[test]
+fn test_raw_block_header() {
+    let block = Block::make_genesis_block();
+    let raw = RawBlock(<Block as ValueCodec<BlockSchema>>::encode_value(&block).unwrap());
+    let header = raw.header().unwrap();
+    assert_eq!(header.epoch, block.epoch());
+    assert_eq!(header.round, block.round());
+    assert_eq!(header.timestamp_usecs, block.timestamp_usecs());
+    assert_eq!(header.parent_id, block.parent_id());
+    assert_eq!(raw.decode().unwrap(), block);
+}
+
 // test_no_panic_decoding!(BlockSchema);
@@ -0,0 +1,99 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the schemas of the commit votes and commit proofs the buffer manager
+//! collected for blocks it had not committed yet, saved on reset so a restarted node picks up
+//! the partial aggregates instead of collecting a quorum from scratch. They are taken back, and
+//! dropped from the DB, when the next buffer manager starts.
+//! ```text
+//! |<-----------------key----------------->|<---value--->|
+//! | epoch | round | block_id | author     |  CommitVote  |
+//!
+//! |<----key---->|<-----------value----------->|
+//! | epoch | round | LedgerInfoWithSignatures  |
+//! ```
+
+use crate::define_schema;
+use anyhow::Result;
+use aptos_consensus_types::pipeline::commit_vote::CommitVote;
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_schemadb::{
+        schema::{KeyCodec, ValueCodec},
+        ColumnFamilyName,
+    },
+    aptos_types::ledger_info::LedgerInfoWithSignatures,
+    move_core_types::account_address::AccountAddress,
+};
+
+use super::ensure_slice_len_eq;
+
+pub const COMMIT_VOTE_CF_NAME: ColumnFamilyName = "commit_vote";
+pub const COMMIT_PROOF_CF_NAME: ColumnFamilyName = "commit_proof";
+
+define_schema!(
+    CommitVoteSchema,
+    (u64, u64, HashValue, AccountAddress),
+    CommitVote,
+    COMMIT_VOTE_CF_NAME
+);
+
+define_schema!(CommitProofSchema, (u64, u64), LedgerInfoWithSignatures, COMMIT_PROOF_CF_NAME);
+
+impl KeyCodec<CommitVoteSchema> for (u64, u64, HashValue, AccountAddress) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (epoch, round, block_id, author) = self;
+        let mut key_bytes = Vec::with_capacity(16 + HashValue::LENGTH + AccountAddress::LENGTH);
+        key_bytes.extend_from_slice(&epoch.to_be_bytes());
+        key_bytes.extend_from_slice(&round.to_be_bytes());
+        key_bytes.extend_from_slice(block_id.as_ref());
+        key_bytes.extend_from_slice(author.as_ref());
+        Ok(key_bytes)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 16 + HashValue::LENGTH + AccountAddress::LENGTH)?;
+        let epoch = u64::from_be_bytes(data[0..8].try_into()?);
+        let round = u64::from_be_bytes(data[8..16].try_into()?);
+        let block_id = HashValue::from_slice(&data[16..16 + HashValue::LENGTH])?;
+        let author = AccountAddress::try_from(&data[16 + HashValue::LENGTH..])?;
+        Ok((epoch, round, block_id, author))
+    }
+}
+
+impl ValueCodec<CommitVoteSchema> for CommitVote {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+impl KeyCodec<CommitProofSchema> for (u64, u64) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (epoch, round) = self;
+        let mut key_bytes = Vec::with_capacity(16);
+        key_bytes.extend_from_slice(&epoch.to_be_bytes());
+        key_bytes.extend_from_slice(&round.to_be_bytes());
+        Ok(key_bytes)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 16)?;
+        let epoch = u64::from_be_bytes(data[0..8].try_into()?);
+        let round = u64::from_be_bytes(data[8..16].try_into()?);
+        Ok((epoch, round))
+    }
+}
+
+impl ValueCodec<CommitProofSchema> for LedgerInfoWithSignatures {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
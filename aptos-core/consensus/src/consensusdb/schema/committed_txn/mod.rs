@@ -0,0 +1,134 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the index of committed user transactions, read by the `/tx/list` API.
+//!
+//! [`CommittedTxnSchema`] orders transactions by their position in the chain, so a position is
+//! a stable cursor. [`TxnBySenderSchema`] orders the same positions by sender, to page through
+//! one account's transactions without scanning the others.
+//! ```text
+//! |<--------key-------->|<-----value----->|
+//! | block_num | index   | CommittedTxn    |
+//!
+//! |<-------------key------------->|<-value->|
+//! | sender | block_num | index      |   ()    |
+//! ```
+
+use crate::define_schema;
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt};
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_schemadb::{
+        schema::{KeyCodec, ValueCodec},
+        ColumnFamilyName,
+    },
+    move_core_types::account_address::AccountAddress,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ensure_slice_len_eq;
+
+pub const COMMITTED_TXN_CF_NAME: ColumnFamilyName = "committed_txn";
+pub const TXN_BY_SENDER_CF_NAME: ColumnFamilyName = "txn_by_sender";
+
+/// Position of a transaction in the chain: its block and its index within the block.
+pub type TxnPosition = (u64, u32);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommittedTxn {
+    pub hash: HashValue,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+}
+
+define_schema!(CommittedTxnSchema, TxnPosition, CommittedTxn, COMMITTED_TXN_CF_NAME);
+define_schema!(TxnBySenderSchema, (AccountAddress, TxnPosition), (), TXN_BY_SENDER_CF_NAME);
+
+const POSITION_LEN: usize = std::mem::size_of::<u64>() + std::mem::size_of::<u32>();
+
+fn encode_position((block_number, index): &TxnPosition, out: &mut Vec<u8>) {
+    out.extend_from_slice(&block_number.to_be_bytes());
+    out.extend_from_slice(&index.to_be_bytes());
+}
+
+fn decode_position(mut data: &[u8]) -> Result<TxnPosition> {
+    ensure_slice_len_eq(data, POSITION_LEN)?;
+    let block_number = data.read_u64::<BigEndian>()?;
+    let index = data.read_u32::<BigEndian>()?;
+    Ok((block_number, index))
+}
+
+impl KeyCodec<CommittedTxnSchema> for TxnPosition {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut key = Vec::with_capacity(POSITION_LEN);
+        encode_position(self, &mut key);
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        decode_position(data)
+    }
+}
+
+impl ValueCodec<CommittedTxnSchema> for CommittedTxn {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+impl KeyCodec<TxnBySenderSchema> for (AccountAddress, TxnPosition) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (sender, position) = self;
+        let mut key = Vec::with_capacity(AccountAddress::LENGTH + POSITION_LEN);
+        key.extend_from_slice(sender.as_ref());
+        encode_position(position, &mut key);
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, AccountAddress::LENGTH + POSITION_LEN)?;
+        let (sender, position) = data.split_at(AccountAddress::LENGTH);
+        Ok((AccountAddress::try_from(sender)?, decode_position(position)?))
+    }
+}
+
+impl ValueCodec<TxnBySenderSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sender_keys_sort_by_sender_then_position() {
+        let key = |sender: u8, block_number: u64, index: u32| {
+            <(AccountAddress, TxnPosition) as KeyCodec<TxnBySenderSchema>>::encode_key(&(
+                AccountAddress::new([sender; AccountAddress::LENGTH]),
+                (block_number, index),
+            ))
+            .unwrap()
+        };
+        assert!(key(1, 9, 0) < key(1, 10, 0));
+        assert!(key(1, 10, 0) < key(1, 10, 1));
+        assert!(key(1, u64::MAX, 0) < key(2, 0, 0));
+
+        let decoded = <(AccountAddress, TxnPosition) as KeyCodec<TxnBySenderSchema>>::decode_key(
+            &key(3, 42, 7),
+        )
+        .unwrap();
+        assert_eq!(decoded, (AccountAddress::new([3; AccountAddress::LENGTH]), (42, 7)));
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the schema of equivocation evidence: a validator that signed two
+//! conflicting votes or proposals in the same round, as witnessed by this node. Evidence is
+//! kept until it is acted on, e.g. submitted to the slashing contract.
+//! ```text
+//! |<------------------key------------------>|<--------value-------->|
+//! | epoch | round | author | kind           | EquivocationEvidence  |
+//! ```
+
+use crate::define_schema;
+use anyhow::{bail, Result};
+use byteorder::{BigEndian, ReadBytesExt};
+use gaptos::{
+    aptos_schemadb::{
+        schema::{KeyCodec, ValueCodec},
+        ColumnFamilyName,
+    },
+    move_core_types::account_address::AccountAddress,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ensure_slice_len_eq;
+
+pub const EVIDENCE_CF_NAME: ColumnFamilyName = "equivocation_evidence";
+
+/// What the author signed twice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EvidenceKind {
+    /// Two votes for different ledger infos.
+    Vote,
+    /// Two proposals with different block ids.
+    Proposal,
+}
+
+impl EvidenceKind {
+    fn to_u8(self) -> u8 {
+        match self {
+            EvidenceKind::Vote => 0,
+            EvidenceKind::Proposal => 1,
+        }
+    }
+
+    fn from_u8(kind: u8) -> Result<Self> {
+        Ok(match kind {
+            0 => EvidenceKind::Vote,
+            1 => EvidenceKind::Proposal,
+            _ => bail!("unknown evidence kind {kind}"),
+        })
+    }
+}
+
+pub type EvidenceKey = (u64, u64, AccountAddress, EvidenceKind);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquivocationEvidence {
+    pub kind: EvidenceKind,
+    pub epoch: u64,
+    pub round: u64,
+    pub author: AccountAddress,
+    /// BCS bytes of the signed message received first, a `Vote` or a `Block`.
+    pub first: Vec<u8>,
+    /// BCS bytes of the conflicting signed message.
+    pub second: Vec<u8>,
+    /// Unix timestamp in microseconds at which the conflict was detected.
+    pub detected_at_usecs: u64,
+}
+
+impl EquivocationEvidence {
+    pub fn key(&self) -> EvidenceKey {
+        (self.epoch, self.round, self.author, self.kind)
+    }
+}
+
+define_schema!(EvidenceSchema, EvidenceKey, EquivocationEvidence, EVIDENCE_CF_NAME);
+
+const KEY_LEN: usize = 2 * std::mem::size_of::<u64>() + AccountAddress::LENGTH + 1;
+
+impl KeyCodec<EvidenceSchema> for EvidenceKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (epoch, round, author, kind) = self;
+        let mut key = Vec::with_capacity(KEY_LEN);
+        key.extend_from_slice(&epoch.to_be_bytes());
+        key.extend_from_slice(&round.to_be_bytes());
+        key.extend_from_slice(author.as_ref());
+        key.push(kind.to_u8());
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, KEY_LEN)?;
+        let mut numbers = &data[..16];
+        let epoch = numbers.read_u64::<BigEndian>()?;
+        let round = numbers.read_u64::<BigEndian>()?;
+        let author = AccountAddress::try_from(&data[16..16 + AccountAddress::LENGTH])?;
+        Ok((epoch, round, author, EvidenceKind::from_u8(data[KEY_LEN - 1])?))
+    }
+}
+
+impl ValueCodec<EvidenceSchema> for EquivocationEvidence {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keys_round_trip_and_sort_by_epoch_then_round() {
+        let author = AccountAddress::new([7; AccountAddress::LENGTH]);
+        let key = (3, 42, author, EvidenceKind::Proposal);
+        let encoded = <EvidenceKey as KeyCodec<EvidenceSchema>>::encode_key(&key).unwrap();
+        assert_eq!(<EvidenceKey as KeyCodec<EvidenceSchema>>::decode_key(&encoded).unwrap(), key);
+
+        let earlier = <EvidenceKey as KeyCodec<EvidenceSchema>>::encode_key(&(
+            3,
+            41,
+            AccountAddress::new([9; AccountAddress::LENGTH]),
+            EvidenceKind::Vote,
+        ))
+        .unwrap();
+        assert!(earlier < encoded);
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the scratch schema of votes and proposals received for rounds ahead of
+//! the local one that overflowed the in-memory future-round buffer. Entries are only meaningful
+//! to the running epoch and are dropped when the next one starts and once their round is passed.
+//! ```text
+//! |<---------key--------->|<------value------>|
+//! | round | author | kind |  FutureRoundMsg   |
+//! ```
+
+use crate::define_schema;
+use anyhow::{bail, Result};
+use aptos_consensus_types::{
+    common::{Author, Round},
+    proposal_msg::ProposalMsg,
+    vote_msg::VoteMsg,
+};
+use gaptos::aptos_schemadb::{
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ensure_slice_len_eq;
+
+pub const FUTURE_ROUND_MSG_CF_NAME: ColumnFamilyName = "future_round_msg";
+
+/// A verified vote or proposal for a round the node has not reached yet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FutureRoundMsg {
+    Proposal(Box<ProposalMsg>),
+    Vote(Box<VoteMsg>),
+}
+
+impl FutureRoundMsg {
+    pub fn round(&self) -> Round {
+        match self {
+            FutureRoundMsg::Proposal(proposal_msg) => proposal_msg.proposal().round(),
+            FutureRoundMsg::Vote(vote_msg) => vote_msg.vote().vote_data().proposed().round(),
+        }
+    }
+
+    pub fn author(&self) -> Author {
+        match self {
+            FutureRoundMsg::Proposal(proposal_msg) => proposal_msg.proposer(),
+            FutureRoundMsg::Vote(vote_msg) => vote_msg.vote().author(),
+        }
+    }
+
+    /// One message of each kind is kept per author and round; a later one replaces it.
+    pub fn key(&self) -> FutureRoundKey {
+        let kind = match self {
+            FutureRoundMsg::Proposal(_) => 0,
+            FutureRoundMsg::Vote(_) => 1,
+        };
+        (self.round(), self.author(), kind)
+    }
+}
+
+pub type FutureRoundKey = (Round, Author, u8);
+
+define_schema!(FutureRoundMsgSchema, FutureRoundKey, FutureRoundMsg, FUTURE_ROUND_MSG_CF_NAME);
+
+const KEY_LEN: usize = std::mem::size_of::<Round>() + Author::LENGTH + 1;
+
+impl KeyCodec<FutureRoundMsgSchema> for FutureRoundKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (round, author, kind) = self;
+        let mut key = Vec::with_capacity(KEY_LEN);
+        key.extend_from_slice(&round.to_be_bytes());
+        key.extend_from_slice(author.as_ref());
+        key.push(*kind);
+        Ok(key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, KEY_LEN)?;
+        let round = u64::from_be_bytes(data[0..8].try_into()?);
+        let author = Author::try_from(&data[8..8 + Author::LENGTH])?;
+        let kind = data[KEY_LEN - 1];
+        if kind > 1 {
+            bail!("unknown future round message kind {kind}");
+        }
+        Ok((round, author, kind))
+    }
+}
+
+impl ValueCodec<FutureRoundMsgSchema> for FutureRoundMsg {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
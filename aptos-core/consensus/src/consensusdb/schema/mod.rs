@@ -3,12 +3,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(crate) mod block;
+pub(crate) mod commit_vote;
+pub mod committed_txn;
 pub(crate) mod dag;
 pub mod epoch_by_block_number;
+pub mod evidence;
+pub(crate) mod future_round_msg;
 pub mod ledger_info;
+pub(crate) mod pending_block;
 pub(crate) mod quorum_certificate;
 pub(crate) mod randomness;
 pub(crate) mod single_entry;
+pub mod tx_result;
 
 use anyhow::{ensure, Result};
 
@@ -38,7 +44,13 @@ macro_rules! define_schema {
 }
 
 pub use block::BLOCK_CF_NAME;
+pub use commit_vote::{COMMIT_PROOF_CF_NAME, COMMIT_VOTE_CF_NAME};
+pub use committed_txn::{COMMITTED_TXN_CF_NAME, TXN_BY_SENDER_CF_NAME};
 pub use dag::{CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, NODE_CF_NAME};
+pub use evidence::EVIDENCE_CF_NAME;
+pub use future_round_msg::FUTURE_ROUND_MSG_CF_NAME;
 use gaptos::aptos_schemadb::ColumnFamilyName;
+pub use pending_block::PENDING_BLOCK_CF_NAME;
 pub use quorum_certificate::QC_CF_NAME;
 pub use single_entry::SINGLE_ENTRY_CF_NAME;
+pub use tx_result::TX_RESULT_CF_NAME;
@@ -0,0 +1,53 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the scratch schema of proposals received ahead of the local round that
+//! overflowed the in-memory pending block buffer. Entries are only meaningful to the running
+//! process and are dropped on startup and once their round is committed.
+//! ```text
+//! |<-------key------->|<--value-->|
+//! | round | block_hash |   block   |
+//! ```
+
+use crate::define_schema;
+use anyhow::Result;
+use aptos_consensus_types::{block::Block, common::Round};
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_schemadb::{
+        schema::{KeyCodec, ValueCodec},
+        ColumnFamilyName,
+    },
+};
+
+use super::ensure_slice_len_eq;
+
+pub const PENDING_BLOCK_CF_NAME: ColumnFamilyName = "pending_block";
+
+define_schema!(PendingBlockSchema, (Round, HashValue), Block, PENDING_BLOCK_CF_NAME);
+
+impl KeyCodec<PendingBlockSchema> for (Round, HashValue) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (round, hash_value) = self;
+        let mut key_bytes = Vec::with_capacity(8 + HashValue::LENGTH);
+        key_bytes.extend_from_slice(&round.to_be_bytes());
+        key_bytes.extend_from_slice(hash_value.as_ref());
+        Ok(key_bytes)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 8 + HashValue::LENGTH)?;
+        let round = u64::from_be_bytes(data[0..8].try_into()?);
+        Ok((round, HashValue::from_slice(&data[8..])?))
+    }
+}
+
+impl ValueCodec<PendingBlockSchema> for Block {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
@@ -37,6 +37,8 @@ pub enum SingleEntryKey {
     LastVote = 0,
     // Two chain timeout cert
     Highest2ChainTimeoutCert = 1,
+    // First epoch whose blocks and QCs are still in the DB; earlier ones are in a cold tier
+    FirstHotEpoch = 2,
 }
 
 impl KeyCodec<SingleEntrySchema> for SingleEntryKey {
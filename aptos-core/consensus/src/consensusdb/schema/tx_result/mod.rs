@@ -0,0 +1,64 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the persisted transaction result cache read by `/tx/get_tx_by_hash`: the
+//! last known status of recently submitted and committed transactions, so lookups keep working
+//! across restarts. Entries expire a TTL after their last update and are pruned by the API.
+//! ```text
+//! |<--key-->|<--value-->|
+//! | tx hash | TxResult  |
+//! ```
+
+use crate::define_schema;
+use anyhow::Result;
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_schemadb::{
+        schema::{KeyCodec, ValueCodec},
+        ColumnFamilyName,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::ensure_slice_len_eq;
+
+pub const TX_RESULT_CF_NAME: ColumnFamilyName = "tx_result";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxStatus {
+    /// Accepted into the pool and not committed yet.
+    Pending,
+    Committed {
+        block_number: u64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxResult {
+    pub status: TxStatus,
+    /// Unix timestamp in seconds of the last status change, from which the entry expires.
+    pub updated_at_secs: u64,
+}
+
+define_schema!(TxResultSchema, HashValue, TxResult, TX_RESULT_CF_NAME);
+
+impl KeyCodec<TxResultSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, HashValue::LENGTH)?;
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<TxResultSchema> for TxResult {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
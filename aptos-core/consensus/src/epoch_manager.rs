@@ -46,9 +46,12 @@ use crate::{
         quorum_store_coordinator::CoordinatorCommand,
         quorum_store_db::QuorumStoreStorage,
     },
-    rand::rand_gen::{
-        storage::interface::RandStorage,
-        types::{AugmentedData, RandConfig},
+    rand::{
+        rand_gen::{
+            storage::interface::RandStorage,
+            types::{AugmentedData, RandConfig},
+        },
+        threshold_decrypt::{self, Bases, EpochKeyShares},
     },
     recovery_manager::RecoveryManager,
     round_manager::{self, RoundManager, UnverifiedEvent, VerifiedEvent},
@@ -868,6 +871,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         };
 
         info!(epoch = epoch, "Create BlockStore");
+        self.pending_blocks.lock().set_spill(self.storage.consensus_db());
         // Read the last vote, before "moving" `recovery_data`
         let last_vote = recovery_data.last_vote();
 
@@ -955,6 +959,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             validator_components,
             fullnode_side_network_id(self.node_type),
         );
+        round_manager.set_future_round_spill(self.storage.consensus_db());
 
         round_manager.init(last_vote).await;
 
@@ -1060,6 +1065,25 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             })
             .collect::<Vec<_>>();
 
+        // The same shares answer `/dkg/decrypt_share`, see `threshold_decrypt`.
+        let wconfig = &dkg_pub_params.pvss_config.wconfig;
+        let my_player = Player { id: my_index };
+        threshold_decrypt::publish_key_shares(EpochKeyShares::new(
+            new_epoch,
+            self.author,
+            Bases {
+                h: *dkg_pub_params.pvss_config.pp.get_encryption_public_params().message_base(),
+                g_hat: *dkg_pub_params.pvss_config.pp.get_commitment_base(),
+            },
+            sk.main
+                .iter()
+                .enumerate()
+                .map(|(j, share)| {
+                    (wconfig.get_virtual_player(&my_player, j).id, *share.as_group_element())
+                })
+                .collect(),
+        ));
+
         // Recover existing augmented key pair or generate a new one
         let (augmented_key_pair, fast_augmented_key_pair) = if let Some((_, key_pair)) = self
             .rand_storage
@@ -0,0 +1,253 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensusdb::{ConsensusDB, FutureRoundKey, FutureRoundMsg};
+use aptos_consensus_types::common::{Author, Round};
+use gaptos::aptos_logger::warn;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+/// Messages kept in memory; a node lagging many rounds behind spills the rest to the ConsensusDB.
+pub const MAX_FUTURE_ROUND_MSGS_IN_MEMORY: usize = 256;
+/// Messages kept in the ConsensusDB; beyond that the furthest rounds are dropped.
+pub const MAX_SPILLED_FUTURE_ROUND_MSGS: usize = 16_384;
+
+/// Votes and proposals received for rounds ahead of the local one, replayed when the round
+/// manager enters their round instead of being dropped.
+///
+/// At most `capacity` messages are held in memory. Beyond that the highest rounds, the furthest
+/// from being needed, are spilled to the ConsensusDB `future_round_msg` column family, or dropped
+/// without one, and are reloaded as the rounds below them are taken.
+pub struct FutureRoundBuffer {
+    in_memory: BTreeMap<FutureRoundKey, FutureRoundMsg>,
+    spilled: BTreeSet<FutureRoundKey>,
+    capacity: usize,
+    spill_capacity: usize,
+    spill: Option<Arc<ConsensusDB>>,
+}
+
+impl FutureRoundBuffer {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_FUTURE_ROUND_MSGS_IN_MEMORY, MAX_SPILLED_FUTURE_ROUND_MSGS)
+    }
+
+    pub fn with_capacity(capacity: usize, spill_capacity: usize) -> Self {
+        Self {
+            in_memory: BTreeMap::new(),
+            spilled: BTreeSet::new(),
+            capacity,
+            spill_capacity,
+            spill: None,
+        }
+    }
+
+    /// Spills overflowing messages to `consensus_db` from now on, dropping what a previous epoch
+    /// or run left there. Called by the epoch manager for every new round manager, so spilled
+    /// messages never outlive their epoch.
+    pub fn set_spill(&mut self, consensus_db: Arc<ConsensusDB>) {
+        self.spilled.clear();
+        if let Err(e) = consensus_db.clear_future_round_msgs() {
+            warn!("Failed to clear spilled future round messages: {:?}", e);
+        }
+        self.spill = Some(consensus_db);
+    }
+
+    pub fn insert(&mut self, msg: FutureRoundMsg) {
+        let key = msg.key();
+        if self.spilled.remove(&key) {
+            self.delete_spilled(&[key]);
+        }
+        self.in_memory.insert(key, msg);
+        self.evict();
+    }
+
+    /// Drops the messages of rounds before `round` and returns those of `round`, in author order.
+    pub fn take_round(&mut self, round: Round) -> Vec<FutureRoundMsg> {
+        let first_of_round = (round, Author::ZERO, 0);
+        self.in_memory = self.in_memory.split_off(&first_of_round);
+        let retained = self.spilled.split_off(&first_of_round);
+        let stale: Vec<FutureRoundKey> =
+            std::mem::replace(&mut self.spilled, retained).into_iter().collect();
+        self.delete_spilled(&stale);
+
+        let first_after_round = (round.saturating_add(1), Author::ZERO, 0);
+        let mut ready = vec![];
+        loop {
+            self.reload();
+            let rest = self.in_memory.split_off(&first_after_round);
+            let taken = std::mem::replace(&mut self.in_memory, rest);
+            if taken.is_empty() {
+                break;
+            }
+            ready.extend(taken.into_values());
+        }
+        ready
+    }
+
+    /// Moves the highest rounds beyond `capacity` out of memory.
+    fn evict(&mut self) {
+        while self.in_memory.len() > self.capacity {
+            let Some((key, msg)) = self.in_memory.pop_last() else {
+                break;
+            };
+            let Some(db) = &self.spill else {
+                warn!("Dropped message of round {} from {}, the buffer is full", key.0, key.1);
+                continue;
+            };
+            if self.spilled.len() >= self.spill_capacity {
+                // Everything spilled is at a higher key than what is in memory, so make room by
+                // dropping the furthest message, unless that is this one.
+                match self.spilled.last().copied() {
+                    Some(last) if last > key => {
+                        self.spilled.remove(&last);
+                        self.delete_spilled(&[last]);
+                    }
+                    _ => {
+                        warn!(
+                            "Dropped message of round {} from {}, the spill is full",
+                            key.0, key.1
+                        );
+                        continue;
+                    }
+                }
+            }
+            match db.put_future_round_msg(&msg) {
+                Ok(()) => {
+                    self.spilled.insert(key);
+                }
+                Err(e) => {
+                    warn!("Failed to spill message of round {} from {}: {:?}", key.0, key.1, e)
+                }
+            }
+        }
+    }
+
+    /// Brings the lowest spilled rounds back into memory while there is room.
+    fn reload(&mut self) {
+        let Some(db) = self.spill.clone() else { return };
+        while self.in_memory.len() < self.capacity {
+            let Some(key) = self.spilled.pop_first() else {
+                break;
+            };
+            match db.get_future_round_msg(&key) {
+                Ok(Some(msg)) => {
+                    self.in_memory.insert(key, msg);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to read spilled message of round {}: {:?}", key.0, e),
+            }
+            self.delete_spilled(&[key]);
+        }
+    }
+
+    fn delete_spilled(&self, keys: &[FutureRoundKey]) {
+        let Some(db) = &self.spill else { return };
+        if let Err(e) = db.delete_future_round_msgs(keys) {
+            warn!("Failed to delete {} spilled future round messages: {:?}", keys.len(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{placeholder_ledger_info, placeholder_sync_info};
+    use aptos_consensus_types::{vote::Vote, vote_data::VoteData, vote_msg::VoteMsg};
+    use gaptos::{
+        aptos_temppath::TempPath,
+        aptos_types::{block_info::BlockInfo, validator_signer::ValidatorSigner},
+    };
+    use std::path::PathBuf;
+
+    fn vote(round: Round, signer: &ValidatorSigner) -> FutureRoundMsg {
+        let vote = Vote::new(
+            VoteData::new(BlockInfo::random(round), BlockInfo::random(round - 1)),
+            signer.author(),
+            placeholder_ledger_info(),
+            signer,
+        )
+        .unwrap();
+        FutureRoundMsg::Vote(Box::new(VoteMsg::new(vote, placeholder_sync_info())))
+    }
+
+    fn rounds(msgs: &[FutureRoundMsg]) -> Vec<Round> {
+        msgs.iter().map(|msg| msg.round()).collect()
+    }
+
+    #[test]
+    fn overflow_spills_highest_rounds_and_reloads_them_on_round_entry() {
+        let tmp_dir = TempPath::new();
+        let db = Arc::new(ConsensusDB::new(&tmp_dir, &PathBuf::new()));
+        let signers: Vec<ValidatorSigner> = (0..2).map(|_| ValidatorSigner::random(None)).collect();
+
+        let mut buffer = FutureRoundBuffer::with_capacity(2, 16);
+        buffer.set_spill(db.clone());
+        for round in 2..=4 {
+            for signer in &signers {
+                buffer.insert(vote(round, signer));
+            }
+        }
+        assert_eq!(buffer.in_memory.len(), 2);
+        assert_eq!(buffer.spilled.len(), 4);
+        let spilled = *buffer.spilled.last().unwrap();
+        assert!(db.get_future_round_msg(&spilled).unwrap().is_some());
+
+        // Entering round 3 drops round 2 and reloads both votes of round 3 from the DB.
+        assert_eq!(rounds(&buffer.take_round(3)), vec![3, 3]);
+        assert_eq!(buffer.in_memory.keys().map(|key| key.0).collect::<Vec<_>>(), vec![4, 4]);
+        assert!(buffer.spilled.is_empty());
+        assert!(db.get_future_round_msg(&spilled).unwrap().is_none());
+
+        assert_eq!(rounds(&buffer.take_round(4)), vec![4, 4]);
+        assert!(buffer.take_round(5).is_empty());
+    }
+
+    #[test]
+    fn a_full_spill_drops_the_furthest_rounds() {
+        let tmp_dir = TempPath::new();
+        let db = Arc::new(ConsensusDB::new(&tmp_dir, &PathBuf::new()));
+        let signer = ValidatorSigner::random(None);
+
+        let mut buffer = FutureRoundBuffer::with_capacity(1, 2);
+        buffer.set_spill(db);
+        for round in [5, 6, 7, 2] {
+            buffer.insert(vote(round, &signer));
+        }
+        // Round 2 pushed 5 out of memory, which took the place of 7 in the full spill.
+        assert_eq!(rounds(&buffer.take_round(2)), vec![2]);
+        assert_eq!(rounds(&buffer.take_round(5)), vec![5]);
+        assert_eq!(rounds(&buffer.take_round(6)), vec![6]);
+        assert!(buffer.take_round(7).is_empty());
+    }
+
+    #[test]
+    fn a_later_message_replaces_the_one_of_the_same_author_and_round() {
+        let signer = ValidatorSigner::random(None);
+        let mut buffer = FutureRoundBuffer::with_capacity(4, 0);
+        let (first, second) = (vote(3, &signer), vote(3, &signer));
+        buffer.insert(first);
+        buffer.insert(second.clone());
+        assert_eq!(buffer.take_round(3), vec![second]);
+    }
+
+    #[test]
+    fn a_new_epoch_drops_the_messages_spilled_in_the_previous_one() {
+        let tmp_dir = TempPath::new();
+        let db = Arc::new(ConsensusDB::new(&tmp_dir, &PathBuf::new()));
+        let signer = ValidatorSigner::random(None);
+
+        let mut buffer = FutureRoundBuffer::with_capacity(1, 16);
+        buffer.set_spill(db.clone());
+        buffer.insert(vote(2, &signer));
+        buffer.insert(vote(3, &signer));
+        let spilled = *buffer.spilled.first().unwrap();
+
+        // The next epoch's round manager starts on a fresh buffer and the same DB.
+        let mut next = FutureRoundBuffer::with_capacity(1, 16);
+        next.set_spill(db.clone());
+        assert!(db.get_future_round_msg(&spilled).unwrap().is_none());
+        assert!(next.take_round(3).is_empty());
+    }
+}
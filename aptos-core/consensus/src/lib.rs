@@ -59,6 +59,7 @@ pub mod consensus_provider;
 /// Required by the telemetry service
 pub mod counters;
 mod execution_pipeline;
+mod future_round_buffer;
 pub mod gravity_state_computer;
 /// AptosNet interface.
 pub mod network_interface;
@@ -74,6 +75,8 @@ pub use consensusdb::create_checkpoint;
 pub use consensusdb::CONSENSUS_DB_NAME;
 use gaptos::aptos_metrics_core::IntGauge;
 pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
+/// Served by the `/dkg/decrypt_share` API
+pub use rand::threshold_decrypt;
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
 
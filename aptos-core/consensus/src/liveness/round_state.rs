@@ -7,8 +7,11 @@ use crate::{
     util::time_service::{SendTask, TimeService},
 };
 use aptos_consensus_types::{
-    common::Round, delayed_qc_msg::DelayedQcMsg, sync_info::SyncInfo,
-    timeout_2chain::TwoChainTimeoutWithPartialSignatures, vote::Vote,
+    common::{Author, Round},
+    delayed_qc_msg::DelayedQcMsg,
+    sync_info::SyncInfo,
+    timeout_2chain::TwoChainTimeoutWithPartialSignatures,
+    vote::Vote,
 };
 use futures::future::AbortHandle;
 use futures_channel::mpsc::UnboundedSender;
@@ -316,6 +319,11 @@ impl RoundState {
         }
     }
 
+    /// The vote of `author` received in the current round, if any.
+    pub fn vote_from(&self, author: &Author) -> Option<&Vote> {
+        self.pending_votes.vote_from(author)
+    }
+
     pub fn record_vote(&mut self, vote: Vote) {
         if vote.vote_data().proposed().round() == self.current_round {
             self.vote_sent = Some(vote);
@@ -37,6 +37,13 @@ impl UnequivocalProposerElection {
         Self { proposer_election, already_proposed: Mutex::new((0, HashValue::zero())) }
     }
 
+    // Return the id of the block accepted as the proposal of `round`, if it is the latest
+    // round a proposal was accepted for.
+    pub fn proposed_in(&self, round: Round) -> Option<HashValue> {
+        let already_proposed = self.already_proposed.lock();
+        (already_proposed.0 == round).then_some(already_proposed.1)
+    }
+
     // Return if a given proposed block is valid:
     // - if a given author is a valid candidate for being a proposer
     // - if this is the first block proposer has submitted in this round
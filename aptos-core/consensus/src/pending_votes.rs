@@ -103,6 +103,11 @@ impl PendingVotes {
         }
     }
 
+    /// The vote of `author` seen in this round, if any.
+    pub fn vote_from(&self, author: &Author) -> Option<&Vote> {
+        self.author_to_vote.get(author).map(|(vote, _)| vote)
+    }
+
     /// Insert a vote and if the vote is valid, return a QuorumCertificate preferentially over a
     /// TimeoutCertificate if either can can be formed
     pub fn insert_vote(
@@ -7,6 +7,7 @@ use crate::{
     consensus_observer::{
         network_message::ConsensusObserverMessage, publisher::ConsensusPublisher,
     },
+    consensusdb::ConsensusDB,
     counters::log_executor_error_occurred,
     monitor,
     network::{IncomingCommitRequest, NetworkSender},
@@ -50,7 +51,7 @@ use gaptos::{
     aptos_time_service::TimeService,
     aptos_types::{
         account_address::AccountAddress, epoch_change::EpochChangeProof, epoch_state::EpochState,
-        ledger_info::LedgerInfoWithSignatures,
+        ledger_info::LedgerInfoWithSignatures, validator_verifier::ValidatorVerifier,
     },
 };
 use once_cell::sync::OnceCell;
@@ -174,6 +175,9 @@ pub struct BufferManager {
     // When a CommitMessage::Decision arrives but the block is not yet in the buffer,
     // the proof is cached here and applied when the block finishes execution.
     pending_commit_proofs: BTreeMap<Round, LedgerInfoWithSignatures>,
+
+    // Where the carried over commit votes and proofs are saved across restarts.
+    consensus_db: Option<Arc<ConsensusDB>>,
 }
 
 /// How an incoming commit vote's round relates to the local commit-vote cache window.
@@ -221,6 +225,24 @@ fn can_cache_commit_vote_for_block<V>(
     cached_block_ids_for_author < MAX_CACHED_BLOCK_IDS_PER_AUTHOR_PER_COMMIT_VOTE_ROUND
 }
 
+/// Keeps the carried over commit votes and proofs of `epoch` whose signatures verify against
+/// `verifier`, returning how many were dropped.
+fn retain_verified_carry_over(
+    votes: &mut Vec<CommitVote>,
+    proofs: &mut Vec<LedgerInfoWithSignatures>,
+    epoch: u64,
+    verifier: &ValidatorVerifier,
+) -> usize {
+    let before = votes.len() + proofs.len();
+    votes.retain(|vote| vote.epoch() == epoch && vote.verify(verifier).is_ok());
+    proofs.retain(|proof| {
+        proof.ledger_info().epoch() == epoch
+            && !proof.commit_info().is_ordered_only()
+            && proof.verify_signatures(verifier).is_ok()
+    });
+    before - votes.len() - proofs.len()
+}
+
 impl BufferManager {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -249,6 +271,7 @@ impl BufferManager {
         consensus_observer_config: ConsensusObserverConfig,
         consensus_publisher: Option<Arc<ConsensusPublisher>>,
         max_pending_rounds_in_commit_vote_cache: Round,
+        consensus_db: Option<Arc<ConsensusDB>>,
     ) -> Self {
         let buffer = Buffer::<BufferItem>::new();
 
@@ -257,7 +280,7 @@ impl BufferManager {
 
         let (tx, rx) = unbounded();
 
-        Self {
+        let mut buffer_manager = Self {
             author,
 
             buffer,
@@ -311,6 +334,20 @@ impl BufferManager {
             commit_vote_cache: BTreeMap::new(),
             max_pending_rounds_in_commit_vote_cache,
             pending_commit_proofs: BTreeMap::new(),
+            consensus_db,
+        };
+        buffer_manager.rehydrate();
+        buffer_manager
+    }
+
+    /// Takes back the commit votes and proofs a reset saved before the node restarted.
+    fn rehydrate(&mut self) {
+        let Some(consensus_db) = self.consensus_db.clone() else {
+            return;
+        };
+        match consensus_db.take_commit_votes(self.epoch_state.epoch) {
+            Ok((votes, proofs)) => self.restore_carry_over(votes, proofs, "restart"),
+            Err(e) => warn!(error = ?e, "Failed to load the saved commit votes and proofs"),
         }
     }
 
@@ -480,6 +517,99 @@ impl BufferManager {
         votes
     }
 
+    /// Takes every commit vote and commit proof the buffer manager holds, from buffer items and
+    /// from the caches, so they can outlive a reset.
+    ///
+    /// A commit vote signs a ledger info, not a leader or a round of the ordering protocol, so
+    /// the votes collected for a block stay valid when the same block is ordered and executed
+    /// again after a state sync. Unverified signatures of ordered items are dropped, as they
+    /// carry no commit ledger info to rebuild the vote from. The signatures of executed and
+    /// signed items may not have been verified either, so everything taken here is checked again
+    /// before it is cached, see [`Self::restore_carry_over`].
+    fn take_carry_over(&mut self) -> (Vec<CommitVote>, Vec<LedgerInfoWithSignatures>) {
+        let mut votes: Vec<CommitVote> = std::mem::take(&mut self.commit_vote_cache)
+            .into_values()
+            .flat_map(|round_cache| round_cache.into_values())
+            .flat_map(|votes_by_author| votes_by_author.into_values())
+            .collect();
+        let mut proofs: Vec<LedgerInfoWithSignatures> =
+            std::mem::take(&mut self.pending_commit_proofs).into_values().collect();
+
+        let mut cursor = *self.buffer.head_cursor();
+        while cursor.is_some() {
+            let partial_commit_proof = match self.buffer.get(&cursor) {
+                BufferItem::Executed(executed) => Some(&executed.partial_commit_proof),
+                BufferItem::Signed(signed) => Some(&signed.partial_commit_proof),
+                BufferItem::Aggregated(aggregated) => {
+                    proofs.push(aggregated.commit_proof.clone());
+                    None
+                }
+                BufferItem::Ordered(_) => None,
+            };
+            if let Some(partial_commit_proof) = partial_commit_proof {
+                votes.extend(partial_commit_proof.signatures().iter().map(
+                    |(author, signature)| {
+                        CommitVote::new_with_signature(
+                            *author,
+                            partial_commit_proof.ledger_info().clone(),
+                            signature.clone(),
+                        )
+                    },
+                ));
+            }
+            cursor = self.buffer.get_next(&cursor);
+        }
+        (votes, proofs)
+    }
+
+    /// Caches the carried over commit votes and proofs of rounds above the commit root.
+    ///
+    /// Each of them is verified against the epoch's validator set first and dropped if that
+    /// fails: under optimistic signature verification the signatures collected in buffer items
+    /// may never have been checked, and the ones loaded from the ConsensusDB are not trusted
+    /// either, so neither may be aggregated into a commit unchecked.
+    fn restore_carry_over(
+        &mut self,
+        mut carried_votes: Vec<CommitVote>,
+        mut carried_proofs: Vec<LedgerInfoWithSignatures>,
+        over: &str,
+    ) {
+        carried_votes.retain(|vote| vote.round() > self.highest_committed_round);
+        let dropped = retain_verified_carry_over(
+            &mut carried_votes,
+            &mut carried_proofs,
+            self.epoch_state.epoch,
+            &self.epoch_state.verifier,
+        );
+        let (mut votes, mut proofs) = (0, 0);
+        for vote in carried_votes {
+            if self.cache_commit_vote(vote) {
+                votes += 1;
+            }
+        }
+        for proof in carried_proofs {
+            if self.try_add_pending_commit_proof(proof) {
+                proofs += 1;
+            }
+        }
+        if dropped > 0 {
+            warn!(
+                dropped = dropped,
+                "Dropped carried over commit votes and proofs that failed verification over the {}",
+                over,
+            );
+        }
+        if votes > 0 || proofs > 0 {
+            info!(
+                highest_committed_round = self.highest_committed_round,
+                carried_votes = votes,
+                carried_proofs = proofs,
+                "Carried commit votes and proofs over the {}",
+                over,
+            );
+        }
+    }
+
     /// process incoming ordered blocks
     /// push them into the buffer and update the roots if they are none.
     async fn process_ordered_blocks(&mut self, ordered_blocks: OrderedBlocks) {
@@ -633,12 +763,27 @@ impl BufferManager {
     /// sync. Internal requests are managed with ongoing_tasks.
     /// Incoming ordered blocks are pulled, it should only have existing blocks but no new blocks
     /// until reset finishes.
+    ///
+    /// The commit votes and proofs collected for rounds above the new commit root are saved to
+    /// the ConsensusDB, for the buffer manager of a restarted node to take back, and unless the
+    /// buffer manager is stopping, carried over into the caches, so blocks that are ordered
+    /// again after a leader failure and state sync pick them up when they re-enter the buffer
+    /// instead of collecting a quorum from scratch.
     async fn reset(&mut self) {
+        let (mut carried_votes, mut carried_proofs) = self.take_carry_over();
         self.buffer = Buffer::new();
         self.execution_root = None;
         self.signing_root = None;
-        self.commit_vote_cache.clear();
-        self.pending_commit_proofs.clear();
+        carried_votes.retain(|vote| vote.round() > self.highest_committed_round);
+        carried_proofs.retain(|proof| proof.commit_info().round() > self.highest_committed_round);
+        if let Some(consensus_db) = &self.consensus_db {
+            if let Err(e) = consensus_db.save_commit_votes(&carried_votes, &carried_proofs) {
+                warn!(error = ?e, "Failed to save the carried over commit votes and proofs");
+            }
+        }
+        if !self.stop {
+            self.restore_carry_over(carried_votes, carried_proofs, "reset");
+        }
         self.previous_commit_time = Instant::now();
         self.commit_proof_rb_handle.take();
         // purge the incoming blocks queue
@@ -1207,10 +1352,23 @@ fn reply_nack(protocol: ProtocolId, response_sender: oneshot::Sender<Result<Byte
 #[cfg(test)]
 mod commit_vote_round_class_tests {
     use super::{
-        can_cache_commit_vote_for_block, classify_commit_vote_round, CommitVoteRoundClass,
+        can_cache_commit_vote_for_block, classify_commit_vote_round, retain_verified_carry_over,
+        CommitVoteRoundClass,
+    };
+    use crate::consensusdb::ConsensusDB;
+    use aptos_consensus_types::pipeline::commit_vote::CommitVote;
+    use gaptos::{
+        aptos_crypto::HashValue,
+        aptos_temppath::TempPath,
+        aptos_types::{
+            aggregate_signature::{AggregateSignature, PartialSignatures},
+            block_info::BlockInfo,
+            ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+            validator_signer::ValidatorSigner,
+            validator_verifier::random_validator_verifier,
+        },
     };
-    use gaptos::aptos_crypto::HashValue;
-    use std::collections::HashMap;
+    use std::{collections::HashMap, path::PathBuf};
 
     // highest_committed_round = 10, window = 5  ->  max_cached_round = 15.
     const HCR: u64 = 10;
@@ -1289,4 +1447,76 @@ mod commit_vote_round_class_tests {
             &other_author
         ));
     }
+
+    fn ledger_info(epoch: u64, round: u64) -> LedgerInfo {
+        let block_info =
+            BlockInfo::new(epoch, round, HashValue::random(), HashValue::random(), 0, 0, None);
+        LedgerInfo::new(block_info, HashValue::zero())
+    }
+
+    #[test]
+    fn saved_commit_votes_are_taken_back_once_per_epoch() {
+        let tmp_dir = TempPath::new();
+        let db = ConsensusDB::new(&tmp_dir, &PathBuf::new());
+        let signers = [ValidatorSigner::random(None), ValidatorSigner::random(None)];
+        let pending = ledger_info(2, 7);
+        let votes: Vec<CommitVote> = signers
+            .iter()
+            .map(|signer| CommitVote::new(signer.author(), pending.clone(), signer).unwrap())
+            .collect();
+        let stale = CommitVote::new(signers[0].author(), ledger_info(1, 9), &signers[0]).unwrap();
+        let proof = LedgerInfoWithSignatures::new(ledger_info(2, 8), AggregateSignature::empty());
+
+        // A reset replaces what an earlier reset saved.
+        db.save_commit_votes(&[stale.clone()], &[]).unwrap();
+        db.save_commit_votes(&[votes[0].clone(), votes[1].clone(), stale], &[proof.clone()])
+            .unwrap();
+
+        // The restarted node's buffer manager takes back the votes of its epoch only.
+        let (mut taken, proofs) = db.take_commit_votes(2).unwrap();
+        taken.sort_by_key(|vote| vote.author());
+        let mut expected = votes.clone();
+        expected.sort_by_key(|vote| vote.author());
+        assert_eq!(taken, expected);
+        assert_eq!(proofs, vec![proof]);
+
+        // Taking drops everything saved, including other epochs.
+        assert_eq!(db.take_commit_votes(1).unwrap(), (vec![], vec![]));
+        assert_eq!(db.take_commit_votes(2).unwrap(), (vec![], vec![]));
+    }
+
+    #[test]
+    fn carried_over_signatures_are_verified() {
+        let (signers, verifier) = random_validator_verifier(2, None, false);
+        let pending = ledger_info(2, 7);
+        let valid = CommitVote::new(signers[0].author(), pending.clone(), &signers[0]).unwrap();
+        // Signed by another validator than its author claims.
+        let forged = CommitVote::new_with_signature(
+            signers[0].author(),
+            pending.clone(),
+            signers[1].sign(&pending).unwrap(),
+        );
+        let other_epoch =
+            CommitVote::new(signers[1].author(), ledger_info(1, 7), &signers[1]).unwrap();
+
+        let committed = ledger_info(2, 8);
+        let partial = PartialSignatures::new(
+            signers
+                .iter()
+                .map(|signer| (signer.author(), signer.sign(&committed).unwrap()))
+                .collect(),
+        );
+        let signed = LedgerInfoWithSignatures::new(
+            committed,
+            verifier.aggregate_signatures(partial.signatures_iter()).unwrap(),
+        );
+        let unsigned = LedgerInfoWithSignatures::new(ledger_info(2, 9), AggregateSignature::empty());
+
+        let mut votes = vec![valid.clone(), forged, other_epoch];
+        let mut proofs = vec![signed.clone(), unsigned];
+        let dropped = retain_verified_carry_over(&mut votes, &mut proofs, 2, &verifier);
+        assert_eq!(dropped, 3);
+        assert_eq!(votes, vec![valid]);
+        assert_eq!(proofs, vec![signed]);
+    }
 }
@@ -239,7 +239,7 @@ impl ExecutionProxyClient {
                     self.rand_storage.clone(),
                     self.bounded_executor.clone(),
                     &self.consensus_config.rand_rb_config,
-                    consensus_db.unwrap(),
+                    consensus_db.clone().unwrap(),
                 );
 
                 tokio::spawn(rand_manager.start(
@@ -285,6 +285,7 @@ impl ExecutionProxyClient {
             consensus_observer_config,
             consensus_publisher,
             self.consensus_config.max_pending_rounds_in_commit_vote_cache,
+            consensus_db,
         );
 
         tokio::spawn(execution_schedule_phase.start());
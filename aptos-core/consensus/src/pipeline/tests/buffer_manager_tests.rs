@@ -163,6 +163,7 @@ pub async fn prepare_buffer_manager(
         ConsensusObserverConfig::default(),
         None,
         100,
+        None,
     );
 
     (
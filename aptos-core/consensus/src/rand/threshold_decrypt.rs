@@ -0,0 +1,401 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Threshold decryption with the key dealt by an epoch's DKG session, for encrypted-mempool
+//! experiments.
+//!
+//! The DKG deals every validator the secret key shares `S_j = f(ω^j)·h` in G1, and records the
+//! public shares `V_j = f(ω^j)·ĝ` in G2 in its transcript. A message is encrypted to the dealt
+//! key `pk = f(0)·ĝ` by picking `r`, publishing `U = r·ĝ` and using `H(e(h, pk)^r)` as an AEAD
+//! key. Validators answer `D_j = e(S_j, U)` with a proof that `S_j` is the share behind
+//! `e(h, V_j) = e(S_j, ĝ)`, and any threshold of verified shares interpolates `e(h, pk)^r`.
+//!
+//! The randomness beacon pairs the same key shares with hashes of block data, so `U` must come
+//! with a proof of knowledge of `r`; otherwise asking for the shares of a hash would reveal the
+//! randomness of a block before it is produced.
+
+use anyhow::{anyhow, ensure};
+use blstrs::{pairing, Compress, G1Affine, G1Projective, G2Affine, G2Projective, Gt, Scalar};
+use gaptos::{
+    aptos_dkg::{
+        algebra::{evaluation_domain::BatchEvaluationDomain, lagrange::lagrange_coefficients},
+        utils::random::random_scalar,
+    },
+    aptos_infallible::RwLock,
+    aptos_types::account_address::AccountAddress,
+};
+use group::{prime::PrimeCurveAffine, Group};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::sync::Arc;
+
+const CIPHERTEXT_DOMAIN: &[u8] = b"gravity-dkg-decrypt/ciphertext";
+const SHARE_DOMAIN: &[u8] = b"gravity-dkg-decrypt/share";
+const KEY_DOMAIN: &[u8] = b"gravity-dkg-decrypt/key";
+
+/// Size of a compressed GT element.
+const GT_BYTES: usize = 288;
+
+/// Key shares of the current epoch, published by the epoch manager once it decrypted them from
+/// the DKG transcript.
+static KEY_SHARES: Lazy<RwLock<Option<Arc<EpochKeyShares>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Replaces the key shares served by [`key_shares`].
+pub fn publish_key_shares(shares: EpochKeyShares) {
+    *KEY_SHARES.write() = Some(Arc::new(shares));
+}
+
+/// This validator's key shares for `epoch`, if it is the last epoch this node dealt keys for.
+pub fn key_shares(epoch: u64) -> Option<Arc<EpochKeyShares>> {
+    KEY_SHARES.read().clone().filter(|shares| shares.epoch == epoch)
+}
+
+/// The DKG's message base `h` (G1), of the secret shares, and commitment base `ĝ` (G2), of the
+/// public shares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bases {
+    pub h: G1Projective,
+    pub g_hat: G2Projective,
+}
+
+pub struct EpochKeyShares {
+    pub epoch: u64,
+    pub author: AccountAddress,
+    pub bases: Bases,
+    /// Virtual player ids of the shares this validator holds, with the shares.
+    shares: Vec<(usize, G1Projective)>,
+}
+
+impl EpochKeyShares {
+    pub fn new(
+        epoch: u64,
+        author: AccountAddress,
+        bases: Bases,
+        shares: Vec<(usize, G1Projective)>,
+    ) -> Self {
+        Self { epoch, author, bases, shares }
+    }
+
+    /// Decryption shares of `u`, one per key share held. Fails unless `proof` shows the caller
+    /// knows the discrete log of `u`.
+    pub fn decrypt(
+        &self,
+        u: &G2Projective,
+        proof: &CiphertextProof,
+    ) -> anyhow::Result<Vec<DecryptionShare>> {
+        ensure!(
+            verify_ciphertext(&self.bases, self.epoch, u, proof),
+            "the ciphertext proof does not verify"
+        );
+        let u = G2Affine::from(u);
+        Ok(self
+            .shares
+            .iter()
+            .map(|(index, share)| prove_share(&self.bases, *index, share, &u))
+            .collect())
+    }
+}
+
+/// Schnorr proof of knowledge of `r` in `U = r·ĝ`, bound to the epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CiphertextProof {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+impl CiphertextProof {
+    pub fn to_hex(&self) -> String {
+        hex::encode([self.challenge.to_bytes_le(), self.response.to_bytes_le()].concat())
+    }
+
+    pub fn from_hex(value: &str) -> anyhow::Result<Self> {
+        let bytes = hex::decode(value.trim_start_matches("0x"))?;
+        ensure!(bytes.len() == 64, "expected a 64-byte ciphertext proof");
+        Ok(Self { challenge: decode_scalar(&bytes[..32])?, response: decode_scalar(&bytes[32..])? })
+    }
+}
+
+/// The encryption side: `U`, its proof and the AEAD key.
+pub struct Encapsulation {
+    pub u: G2Projective,
+    pub proof: CiphertextProof,
+    pub key: [u8; 32],
+}
+
+/// Picks `r` and derives the key of a message encrypted to the dealt key `pk` of `epoch`.
+pub fn encapsulate(bases: &Bases, pk: &G2Projective, epoch: u64) -> Encapsulation {
+    let mut rng = rand::thread_rng();
+    let r = random_scalar(&mut rng);
+    let u = bases.g_hat * r;
+    let nonce = random_scalar(&mut rng);
+    let commitment = bases.g_hat * nonce;
+    let challenge = ciphertext_challenge(epoch, &u, &commitment);
+    let proof = CiphertextProof { challenge, response: nonce + challenge * r };
+    let key = derive_key(&(pairing(&G1Affine::from(bases.h), &G2Affine::from(pk)) * r));
+    Encapsulation { u, proof, key }
+}
+
+pub fn verify_ciphertext(
+    bases: &Bases,
+    epoch: u64,
+    u: &G2Projective,
+    proof: &CiphertextProof,
+) -> bool {
+    if bool::from(u.is_identity()) {
+        return false;
+    }
+    let commitment = bases.g_hat * proof.response - u * proof.challenge;
+    ciphertext_challenge(epoch, u, &commitment) == proof.challenge
+}
+
+fn ciphertext_challenge(epoch: u64, u: &G2Projective, commitment: &G2Projective) -> Scalar {
+    hash_to_scalar(
+        CIPHERTEXT_DOMAIN,
+        &[
+            &epoch.to_le_bytes(),
+            &G2Affine::from(u).to_compressed(),
+            &G2Affine::from(commitment).to_compressed(),
+        ],
+    )
+}
+
+/// `D_j = e(S_j, U)` for the virtual player `index`, with a proof of equality of `S_j` in
+/// `e(S_j, ĝ)` and `e(S_j, U)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecryptionShare {
+    pub index: usize,
+    pub share: Gt,
+    pub challenge: Scalar,
+    pub response: G1Projective,
+}
+
+/// [`DecryptionShare`] as sent by the API, hex encoded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EncodedDecryptionShare {
+    pub index: usize,
+    /// Compressed GT element.
+    pub share: String,
+    pub challenge: String,
+    /// Compressed G1 point.
+    pub response: String,
+}
+
+impl DecryptionShare {
+    pub fn encode(&self) -> EncodedDecryptionShare {
+        EncodedDecryptionShare {
+            index: self.index,
+            share: hex::encode(gt_bytes(&self.share)),
+            challenge: hex::encode(self.challenge.to_bytes_le()),
+            response: hex::encode(G1Affine::from(self.response).to_compressed()),
+        }
+    }
+}
+
+impl EncodedDecryptionShare {
+    pub fn decode(&self) -> anyhow::Result<DecryptionShare> {
+        let share = hex::decode(self.share.trim_start_matches("0x"))?;
+        ensure!(share.len() == GT_BYTES, "expected a {GT_BYTES}-byte compressed GT element");
+        let share =
+            Gt::read_compressed(share.as_slice()).map_err(|_| anyhow!("not a valid GT element"))?;
+        let challenge = decode_scalar(&hex::decode(self.challenge.trim_start_matches("0x"))?)?;
+        let response: [u8; 48] = hex::decode(self.response.trim_start_matches("0x"))?
+            .try_into()
+            .map_err(|_| anyhow!("expected a 48-byte compressed G1 point"))?;
+        let response = Option::<G1Affine>::from(G1Affine::from_compressed(&response))
+            .ok_or_else(|| anyhow!("not a valid G1 point"))?;
+        Ok(DecryptionShare { index: self.index, share, challenge, response: response.into() })
+    }
+}
+
+fn prove_share(
+    bases: &Bases,
+    index: usize,
+    secret: &G1Projective,
+    u: &G2Affine,
+) -> DecryptionShare {
+    let g_hat = G2Affine::from(bases.g_hat);
+    let secret_affine = G1Affine::from(secret);
+    let public = pairing(&secret_affine, &g_hat);
+    let share = pairing(&secret_affine, u);
+    let nonce = bases.h * random_scalar(&mut rand::thread_rng());
+    let nonce_affine = G1Affine::from(nonce);
+    let challenge = share_challenge(
+        index,
+        u,
+        &public,
+        &share,
+        &pairing(&nonce_affine, &g_hat),
+        &pairing(&nonce_affine, u),
+    );
+    DecryptionShare { index, share, challenge, response: nonce + secret * challenge }
+}
+
+/// Checks `share` against the public share `V_j` of its index, taken from the DKG transcript.
+pub fn verify_share(
+    bases: &Bases,
+    u: &G2Projective,
+    share: &DecryptionShare,
+    public_share: &G2Projective,
+) -> bool {
+    let u = G2Affine::from(u);
+    let response = G1Affine::from(share.response);
+    let public = pairing(&G1Affine::from(bases.h), &G2Affine::from(public_share));
+    let nonce_g_hat = pairing(&response, &G2Affine::from(bases.g_hat)) - public * share.challenge;
+    let nonce_u = pairing(&response, &u) - share.share * share.challenge;
+    share_challenge(share.index, &u, &public, &share.share, &nonce_g_hat, &nonce_u) ==
+        share.challenge
+}
+
+fn share_challenge(
+    index: usize,
+    u: &G2Affine,
+    public: &Gt,
+    share: &Gt,
+    nonce_g_hat: &Gt,
+    nonce_u: &Gt,
+) -> Scalar {
+    hash_to_scalar(
+        SHARE_DOMAIN,
+        &[
+            &(index as u64).to_le_bytes(),
+            &u.to_compressed(),
+            &gt_bytes(public),
+            &gt_bytes(share),
+            &gt_bytes(nonce_g_hat),
+            &gt_bytes(nonce_u),
+        ],
+    )
+}
+
+/// Interpolates `e(h, pk)^r` from shares with distinct indices, out of `total_weight` dealt, and
+/// derives the AEAD key from it.
+pub fn combine(total_weight: usize, shares: &[(usize, Gt)]) -> [u8; 32] {
+    let indices: Vec<usize> = shares.iter().map(|(index, _)| *index).collect();
+    let coefficients = lagrange_coefficients(
+        &BatchEvaluationDomain::new(total_weight),
+        &indices,
+        &Scalar::from(0u64),
+    );
+    let secret: Gt =
+        shares.iter().zip(coefficients).map(|((_, share), lambda)| share * lambda).sum();
+    derive_key(&secret)
+}
+
+fn derive_key(secret: &Gt) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(KEY_DOMAIN);
+    hasher.update(gt_bytes(secret));
+    hasher.finalize().into()
+}
+
+fn hash_to_scalar(domain: &[u8], parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha3_256::new();
+    hasher.update(domain);
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    let mut bytes: [u8; 32] = hasher.finalize().into();
+    // Below 2^253, so always a canonical scalar.
+    bytes[31] &= 0x1f;
+    Scalar::from_bytes_le(&bytes).unwrap()
+}
+
+fn decode_scalar(bytes: &[u8]) -> anyhow::Result<Scalar> {
+    let bytes: [u8; 32] = bytes.try_into().map_err(|_| anyhow!("expected a 32-byte scalar"))?;
+    Option::from(Scalar::from_bytes_le(&bytes)).ok_or_else(|| anyhow!("not a canonical scalar"))
+}
+
+/// Compressed `gt`; the identity, which has no compressed form, is all zeros.
+fn gt_bytes(gt: &Gt) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(GT_BYTES);
+    if bool::from(gt.is_identity()) {
+        bytes.resize(GT_BYTES, 0);
+    } else {
+        gt.write_compressed(&mut bytes).expect("writing to a Vec cannot fail");
+    }
+    bytes
+}
+
+pub fn decode_g2(value: &str) -> anyhow::Result<G2Projective> {
+    let bytes: [u8; 96] = hex::decode(value.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| anyhow!("expected a 96-byte compressed G2 point"))?;
+    Option::<G2Affine>::from(G2Affine::from_compressed(&bytes))
+        .map(G2Projective::from)
+        .ok_or_else(|| anyhow!("not a valid G2 point"))
+}
+
+pub fn encode_g2(point: &G2Projective) -> String {
+    hex::encode(G2Affine::from(point).to_compressed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gaptos::aptos_dkg::{algebra::fft::fft_assign, utils::random::random_scalars};
+
+    /// Deals `n` shares of a random degree `t - 1` polynomial at the powers of `ω`, the way the
+    /// DKG does; returns the bases, the dealt key, the secret and the public shares.
+    fn deal(n: usize, t: usize) -> (Bases, G2Projective, Vec<G1Projective>, Vec<G2Projective>) {
+        let mut rng = rand::thread_rng();
+        let bases = Bases {
+            h: G1Projective::generator() * random_scalar(&mut rng),
+            g_hat: G2Projective::generator(),
+        };
+        let f = random_scalars(t, &mut rng);
+        let mut evals = f.clone();
+        fft_assign(&mut evals, &BatchEvaluationDomain::new(n).get_subdomain(n));
+        let secret = evals[..n].iter().map(|e| bases.h * e).collect();
+        let public = evals[..n].iter().map(|e| bases.g_hat * e).collect();
+        (bases, bases.g_hat * f[0], secret, public)
+    }
+
+    #[test]
+    fn any_threshold_of_verified_shares_decrypts() {
+        let (n, t, epoch) = (5, 3, 7);
+        let (bases, pk, secret, public) = deal(n, t);
+        let holders: Vec<EpochKeyShares> = (0..n)
+            .map(|j| EpochKeyShares::new(epoch, AccountAddress::ZERO, bases, vec![(j, secret[j])]))
+            .collect();
+        let encapsulation = encapsulate(&bases, &pk, epoch);
+
+        let shares: Vec<DecryptionShare> = holders
+            .iter()
+            .flat_map(|holder| holder.decrypt(&encapsulation.u, &encapsulation.proof).unwrap())
+            .map(|share| share.encode().decode().unwrap())
+            .collect();
+        for share in &shares {
+            assert!(verify_share(&bases, &encapsulation.u, share, &public[share.index]));
+            assert!(!verify_share(&bases, &encapsulation.u, share, &public[(share.index + 1) % n]));
+        }
+
+        for subset in [[0, 1, 2], [0, 2, 4], [1, 3, 4]] {
+            let used: Vec<(usize, Gt)> = subset.iter().map(|j| (*j, shares[*j].share)).collect();
+            assert_eq!(combine(n, &used), encapsulation.key);
+        }
+        let too_few: Vec<(usize, Gt)> =
+            shares[..t - 1].iter().map(|s| (s.index, s.share)).collect();
+        assert_ne!(combine(n, &too_few), encapsulation.key);
+    }
+
+    #[test]
+    fn forged_shares_and_unproven_ciphertexts_are_rejected() {
+        let epoch = 3;
+        let (bases, pk, secret, public) = deal(4, 2);
+        let holder = EpochKeyShares::new(epoch, AccountAddress::ZERO, bases, vec![(1, secret[1])]);
+        let encapsulation = encapsulate(&bases, &pk, epoch);
+
+        let mut share = holder.decrypt(&encapsulation.u, &encapsulation.proof).unwrap()[0];
+        share.share += pairing(&G1Affine::generator(), &G2Affine::generator());
+        assert!(!verify_share(&bases, &encapsulation.u, &share, &public[1]));
+
+        // A point whose discrete log the caller does not know, e.g. a hash, is refused.
+        let other = G2Projective::generator() * random_scalar(&mut rand::thread_rng());
+        assert!(holder.decrypt(&other, &encapsulation.proof).is_err());
+        // As is a proof made for another epoch.
+        let stale = encapsulate(&bases, &pk, epoch - 1);
+        assert!(holder.decrypt(&stale.u, &stale.proof).is_err());
+    }
+}
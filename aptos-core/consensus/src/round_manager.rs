@@ -7,7 +7,9 @@ use crate::{
         tracing::{observe_block, BlockStage},
         BlockReader, BlockRetriever, BlockStore, NeedFetchResult,
     },
+    consensusdb::{ConsensusDB, EquivocationEvidence, EvidenceKind, FutureRoundMsg},
     error::{error_kind, VerifyError},
+    future_round_buffer::FutureRoundBuffer,
     liveness::{
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
@@ -59,7 +61,7 @@ use gaptos::{
     aptos_config::{config::ConsensusConfig, network_id::NetworkId},
     aptos_consensus::counters,
     aptos_crypto::HashValue,
-    aptos_infallible::{checked, Mutex},
+    aptos_infallible::{checked, duration_since_epoch, Mutex},
     aptos_logger::prelude::*,
     aptos_network::application::interface::NetworkClientInterface,
     aptos_types::{
@@ -76,7 +78,13 @@ use gaptos::{
 };
 use lru::LruCache;
 use serde::Serialize;
-use std::{mem::Discriminant, pin::Pin, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeSet, HashMap},
+    mem::Discriminant,
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{
     sync::oneshot as TokioOneshot,
     time::{sleep, Instant},
@@ -94,6 +102,8 @@ pub enum UnverifiedEvent {
 }
 
 pub const BACK_PRESSURE_POLLING_INTERVAL_MS: u64 = 10;
+/// Equivocations recorded per author in an epoch; further ones are only logged.
+const MAX_EVIDENCE_PER_AUTHOR: usize = 16;
 
 impl UnverifiedEvent {
     pub fn verify(
@@ -243,6 +253,10 @@ pub struct RoundManager {
     fast_rand_config: Option<RandConfig>,
     // Stores the order votes from all the rounds above highest_ordered_round
     pending_order_votes: PendingOrderVotes,
+    // Votes and proposals for rounds ahead of the current one, replayed on entering their round
+    future_rounds: FutureRoundBuffer,
+    // Rounds and kinds of the equivocations recorded per author in this epoch
+    recorded_evidence: Mutex<HashMap<Author, BTreeSet<(Round, EvidenceKind)>>>,
     // Round manager broadcasts fast shares when forming a QC or when receiving a proposal.
     // To avoid duplicate broadcasts for the same block, we keep track of blocks for
     // which we recently broadcasted fast shares.
@@ -311,6 +325,8 @@ impl RoundManager {
             jwk_consensus_config,
             fast_rand_config,
             pending_order_votes: PendingOrderVotes::new(),
+            future_rounds: FutureRoundBuffer::new(),
+            recorded_evidence: Mutex::new(HashMap::new()),
             blocks_with_broadcasted_fast_shares: LruCache::new(5),
             futures: FuturesUnordered::new(),
             wait_change_epoch_flag: false,
@@ -616,14 +632,19 @@ impl RoundManager {
             block_parent_hash = proposal_msg.proposal().quorum_cert().certified_block().id(),
         );
 
+        let round = proposal_msg.proposal().round();
+        let result = self
+            .ensure_round_and_sync_up(round, proposal_msg.sync_info(), proposal_msg.proposer())
+            .await;
+        let in_round = match result {
+            Err(e) if round > self.round_state.current_round() => {
+                self.future_rounds.insert(FutureRoundMsg::Proposal(Box::new(proposal_msg)));
+                return Err(e.context("[RoundManager] Buffered proposal for a future round"));
+            }
+            result => result.context("[RoundManager] Process proposal")?,
+        };
         ensure!(
-            self.ensure_round_and_sync_up(
-                proposal_msg.proposal().round(),
-                proposal_msg.sync_info(),
-                proposal_msg.proposer(),
-            )
-            .await
-            .context("[RoundManager] Process proposal")?,
+            in_round,
             "Stale proposal {}, current round {}",
             proposal_msg.proposal(),
             self.round_state.current_round()
@@ -913,12 +934,35 @@ impl RoundManager {
 
         let validator_components = self.validator_components.as_ref().unwrap();
 
-        ensure!(
-            validator_components.proposer_election.is_valid_proposal(&proposal),
-            "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
-            author,
-            proposal,
-        );
+        if !validator_components.proposer_election.is_valid_proposal(&proposal) {
+            let earlier = validator_components.proposer_election.proposed_in(proposal.round());
+            if let Some(earlier_id) = earlier.filter(|id| *id != proposal.id()) {
+                match self.block_store.get_block(earlier_id) {
+                    // The earlier block may be the valid proposer's, with `author` not being the
+                    // proposer of this round at all; that is not equivocation.
+                    Some(earlier_block) => {
+                        if let Some(evidence) = proposal_equivocation(
+                            self.epoch_state.epoch,
+                            earlier_block.block(),
+                            &proposal,
+                        )? {
+                            self.record_equivocation(evidence);
+                        }
+                    }
+                    None => warn!(
+                        "Equivocating proposal {} from {} but block {} is not in the block store, no evidence recorded",
+                        proposal.id(),
+                        author,
+                        earlier_id
+                    ),
+                }
+            }
+            bail!(
+                "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
+                author,
+                proposal,
+            );
+        }
 
         // Validate that failed_authors list is correctly specified in the block.
         let expected_failed_authors =
@@ -1217,15 +1261,18 @@ impl RoundManager {
         });
 
         // Check whether this validator is a valid recipient of the vote.
-        if self
-            .ensure_round_and_sync_up(
-                vote_msg.vote().vote_data().proposed().round(),
-                vote_msg.sync_info(),
-                vote_msg.vote().author(),
-            )
-            .await
-            .context("[RoundManager] Stop processing vote")?
-        {
+        let round = vote_msg.vote().vote_data().proposed().round();
+        let result = self
+            .ensure_round_and_sync_up(round, vote_msg.sync_info(), vote_msg.vote().author())
+            .await;
+        let in_round = match result {
+            Err(e) if round > self.round_state.current_round() => {
+                self.future_rounds.insert(FutureRoundMsg::Vote(Box::new(vote_msg)));
+                return Err(e.context("[RoundManager] Buffered vote for a future round"));
+            }
+            result => result.context("[RoundManager] Stop processing vote")?,
+        };
+        if in_round {
             self.process_vote(vote_msg.vote()).await.context("[RoundManager] Add a new vote")?;
         }
         Ok(())
@@ -1334,10 +1381,72 @@ impl RoundManager {
             VoteReceptionResult::VoteAddedQCDelayed(_) |
             VoteReceptionResult::EchoTimeout(_) |
             VoteReceptionResult::DuplicateVote => Ok(()),
+            VoteReceptionResult::EquivocateVote => {
+                if let Some(previous_vote) = self.round_state.vote_from(&vote.author()) {
+                    if let Some(evidence) =
+                        vote_equivocation(self.epoch_state.epoch, previous_vote, vote)?
+                    {
+                        self.record_equivocation(evidence);
+                    }
+                }
+                Err(anyhow::anyhow!("{:?}", VoteReceptionResult::EquivocateVote))
+            }
             e => Err(anyhow::anyhow!("{:?}", e)),
         }
     }
 
+    /// Keeps the two conflicting messages of `evidence` in the ConsensusDB, so they can be
+    /// submitted to the slashing contract later. The write runs on the blocking pool rather than
+    /// the round manager's loop, and at most `MAX_EVIDENCE_PER_AUTHOR` equivocations are kept per
+    /// author in an epoch, so a validator equivocating every round can't flood the DB.
+    fn record_equivocation(&self, evidence: EquivocationEvidence) {
+        let (kind, author, round) = (evidence.kind, evidence.author, evidence.round);
+        {
+            let mut recorded_evidence = self.recorded_evidence.lock();
+            let recorded = recorded_evidence.entry(author).or_default();
+            if recorded.contains(&(round, kind)) {
+                return;
+            }
+            if recorded.len() >= MAX_EVIDENCE_PER_AUTHOR {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(1)),
+                    warn!(
+                        "Not recording {:?} equivocation evidence against {} for epoch {} round {}, \
+                         {} equivocations are already recorded",
+                        kind,
+                        author,
+                        evidence.epoch,
+                        round,
+                        recorded.len()
+                    );
+                );
+                return;
+            }
+            recorded.insert((round, kind));
+        }
+        let consensus_db = self.storage.consensus_db();
+        tokio::task::spawn_blocking(move || match consensus_db.put_evidence(&evidence) {
+            Ok(true) if kind == EvidenceKind::Proposal => warn!(
+                SecurityEvent::InvalidConsensusProposal,
+                "Recorded {:?} equivocation evidence against {} for epoch {} round {}",
+                kind,
+                author,
+                evidence.epoch,
+                round
+            ),
+            Ok(true) => warn!(
+                SecurityEvent::ConsensusEquivocatingVote,
+                "Recorded {:?} equivocation evidence against {} for epoch {} round {}",
+                kind,
+                author,
+                evidence.epoch,
+                round
+            ),
+            Ok(false) => {}
+            Err(e) => error!("Failed to record {:?} equivocation evidence: {:?}", kind, e),
+        });
+    }
+
     async fn process_order_vote_reception_result(
         &mut self,
         result: OrderVoteReceptionResult,
@@ -1463,6 +1572,29 @@ impl RoundManager {
         self.validator_components.as_ref().unwrap().safety_rules.lock().consensus_state().unwrap()
     }
 
+    /// Spills future-round votes and proposals beyond the in-memory cap to `consensus_db`.
+    pub fn set_future_round_spill(&mut self, consensus_db: Arc<ConsensusDB>) {
+        self.future_rounds.set_spill(consensus_db);
+    }
+
+    /// Replays the buffered votes and proposals of `round`, which was just entered.
+    async fn process_future_round_msgs(&mut self, round: Round) {
+        for msg in self.future_rounds.take_round(round) {
+            let result = match msg {
+                FutureRoundMsg::Proposal(proposal_msg) => {
+                    monitor!("process_proposal", self.process_proposal_msg(*proposal_msg).await)
+                }
+                FutureRoundMsg::Vote(vote_msg) => {
+                    monitor!("process_vote", self.process_vote_msg(*vote_msg).await)
+                }
+            };
+            if let Err(e) = result {
+                counters::ERROR_COUNT.inc();
+                warn!(error = ?e, kind = error_kind(&e), RoundStateLogSchema::new(self.round_state()));
+            }
+        }
+    }
+
     #[cfg(test)]
     pub fn set_safety_rules(&mut self, safety_rules: Arc<Mutex<MetricsSafetyRules>>) {
         self.validator_components.as_mut().unwrap().safety_rules = safety_rules
@@ -1502,6 +1634,7 @@ impl RoundManager {
     ) {
         info!(epoch = self.epoch_state().epoch, "RoundManager started");
         let mut close_rx = close_rx.into_stream();
+        let mut entered_round = self.round_state.current_round();
         loop {
             tokio::select! {
                 biased;
@@ -1624,6 +1757,11 @@ impl RoundManager {
                     }
                 },
             }
+            // Replaying a round's messages can form its certificate and enter the next one.
+            while self.round_state.current_round() > entered_round {
+                entered_round = self.round_state.current_round();
+                self.process_future_round_msgs(entered_round).await;
+            }
         }
         info!(epoch = self.epoch_state().epoch, "RoundManager stopped");
     }
@@ -1663,3 +1801,53 @@ impl RoundManager {
         }
     }
 }
+
+/// Evidence that the author of `proposal` also proposed `earlier` for the same round, or `None`
+/// when the two blocks are the same or have different authors or rounds.
+pub(crate) fn proposal_equivocation(
+    epoch: u64,
+    earlier: &Block,
+    proposal: &Block,
+) -> anyhow::Result<Option<EquivocationEvidence>> {
+    let Some(author) = proposal.author() else { return Ok(None) };
+    if earlier.author() != Some(author) ||
+        earlier.round() != proposal.round() ||
+        earlier.id() == proposal.id()
+    {
+        return Ok(None);
+    }
+    Ok(Some(EquivocationEvidence {
+        kind: EvidenceKind::Proposal,
+        epoch,
+        round: proposal.round(),
+        author,
+        first: bcs::to_bytes(earlier)?,
+        second: bcs::to_bytes(proposal)?,
+        detected_at_usecs: duration_since_epoch().as_micros() as u64,
+    }))
+}
+
+/// Evidence that the author of `vote` also cast `previous` in the same round for something
+/// else, or `None` when the votes agree or have different authors or rounds.
+pub(crate) fn vote_equivocation(
+    epoch: u64,
+    previous: &Vote,
+    vote: &Vote,
+) -> anyhow::Result<Option<EquivocationEvidence>> {
+    let round = vote.vote_data().proposed().round();
+    if previous.author() != vote.author() ||
+        previous.vote_data().proposed().round() != round ||
+        previous.ledger_info() == vote.ledger_info()
+    {
+        return Ok(None);
+    }
+    Ok(Some(EquivocationEvidence {
+        kind: EvidenceKind::Vote,
+        epoch,
+        round,
+        author: vote.author(),
+        first: bcs::to_bytes(previous)?,
+        second: bcs::to_bytes(vote)?,
+        detected_at_usecs: duration_since_epoch().as_micros() as u64,
+    }))
+}
@@ -6,6 +6,7 @@
 
 use crate::{
     block_storage::{pending_blocks::PendingBlocks, BlockReader, BlockStore},
+    consensusdb::EvidenceKind,
     liveness::{
         proposal_generator::{
             ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
@@ -22,11 +23,11 @@ use crate::{
     payload_manager::DirectMempoolPayloadManager,
     persistent_liveness_storage::RecoveryData,
     pipeline::buffer_manager::OrderedBlocks,
-    round_manager::{RoundManager, ValidatorComponents},
+    round_manager::{proposal_equivocation, vote_equivocation, RoundManager, ValidatorComponents},
     test_utils::{
         consensus_runtime, create_vec_signed_transactions,
-        mock_execution_client::MockExecutionClient, timed_block_on, MockPayloadManager,
-        MockStorage, TreeInserter,
+        mock_execution_client::MockExecutionClient, placeholder_ledger_info, timed_block_on,
+        MockPayloadManager, MockStorage, TreeInserter,
     },
     util::time_service::{ClockTimeService, TimeService},
 };
@@ -41,6 +42,8 @@ use aptos_consensus_types::{
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutWithPartialSignatures},
+    vote::Vote,
+    vote_data::VoteData,
     vote_msg::VoteMsg,
 };
 use aptos_safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
@@ -2213,3 +2216,80 @@ async fn no_vote_on_proposal_ext_when_receiving_limit_exceeded() {
         assert!(node.round_manager.process_proposal(valid_block).await.is_ok());
     });
 }
+
+#[test]
+/// A second proposal for a round is only evidence against a proposer who signed both blocks
+fn proposal_evidence_only_against_the_author_of_both_blocks() {
+    let proposer = ValidatorSigner::random([1u8; 32]);
+    let other = ValidatorSigner::random([2u8; 32]);
+    let genesis_qc = certificate_for_genesis();
+    let proposal = |signer: &ValidatorSigner, timestamp: u64| {
+        Block::new_proposal(
+            Payload::empty(false, true),
+            1,
+            timestamp,
+            genesis_qc.clone(),
+            signer,
+            Vec::new(),
+        )
+        .unwrap()
+    };
+    let first = proposal(&proposer, 1);
+    let second = proposal(&proposer, 2);
+
+    let evidence = proposal_equivocation(1, &first, &second).unwrap().unwrap();
+    assert_eq!(evidence.kind, EvidenceKind::Proposal);
+    assert_eq!((evidence.epoch, evidence.round), (1, 1));
+    assert_eq!(evidence.author, proposer.author());
+    assert_eq!(evidence.first, bcs::to_bytes(&first).unwrap());
+    assert_eq!(evidence.second, bcs::to_bytes(&second).unwrap());
+
+    // An invalid proposer's block for the round the valid proposer already used
+    assert!(proposal_equivocation(1, &first, &proposal(&other, 2)).unwrap().is_none());
+    assert!(proposal_equivocation(1, &first, &first).unwrap().is_none());
+}
+
+#[test]
+/// Votes for two different blocks of one round are evidence, a repeated vote is not
+fn vote_evidence_needs_conflicting_votes_of_one_round() {
+    let voter = ValidatorSigner::random([1u8; 32]);
+    let other = ValidatorSigner::random([2u8; 32]);
+    let genesis_qc = certificate_for_genesis();
+    let block = |round: Round, timestamp: u64| {
+        Block::new_proposal(
+            Payload::empty(false, true),
+            round,
+            timestamp,
+            genesis_qc.clone(),
+            &other,
+            Vec::new(),
+        )
+        .unwrap()
+    };
+    let vote = |signer: &ValidatorSigner, block: &Block| {
+        Vote::new(
+            VoteData::new(
+                block.gen_block_info(HashValue::zero(), 0, None),
+                block.quorum_cert().certified_block().clone(),
+            ),
+            signer.author(),
+            placeholder_ledger_info(),
+            signer,
+        )
+        .unwrap()
+    };
+    let (first_block, second_block) = (block(1, 1), block(1, 2));
+    let first = vote(&voter, &first_block);
+    let second = vote(&voter, &second_block);
+
+    let evidence = vote_equivocation(1, &first, &second).unwrap().unwrap();
+    assert_eq!(evidence.kind, EvidenceKind::Vote);
+    assert_eq!((evidence.epoch, evidence.round), (1, 1));
+    assert_eq!(evidence.author, voter.author());
+    assert_eq!(evidence.first, bcs::to_bytes(&first).unwrap());
+    assert_eq!(evidence.second, bcs::to_bytes(&second).unwrap());
+
+    assert!(vote_equivocation(1, &first, &first).unwrap().is_none());
+    assert!(vote_equivocation(1, &first, &vote(&other, &second_block)).unwrap().is_none());
+    assert!(vote_equivocation(1, &first, &vote(&voter, &block(2, 3))).unwrap().is_none());
+}
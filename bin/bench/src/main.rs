@@ -8,7 +8,7 @@ use std::{sync::Arc, thread};
 use api::{
     check_bootstrap_config,
     consensus_api::{ConsensusEngine, ConsensusEngineArgs},
-    NodeConfig,
+    ApiConfig, NodeConfig,
 };
 use block_buffer_manager::block_buffer_manager::EmptyTxPool;
 use clap::Parser;
@@ -36,6 +36,8 @@ impl TestConsensusLayer {
                     chain_id: 1337,
                     latest_block_number: 0,
                     config_storage: None,
+                    api_routes: None,
+                    api_config: ApiConfig::default(),
                 },
                 EmptyTxPool::boxed(),
             )
@@ -1,7 +1,9 @@
 use crate::{
-    completions::CompletionsCommand, dkg::DKGCommand, doctor::DoctorCommand, epoch::EpochCommand,
-    genesis::GenesisCommand, init::InitCommand, node::NodeCommand, output::OutputFormat,
-    stake::StakeCommand, status::StatusCommand, unwind::UnwindCommand, validator::ValidatorCommand,
+    completions::CompletionsCommand, delegation::DelegationCommand, dkg::DKGCommand,
+    doctor::DoctorCommand, epoch::EpochCommand, genesis::GenesisCommand, history::HistoryCommand,
+    i18n::Lang, init::InitCommand, keys::KeysCommand, node::NodeCommand, output::OutputFormat,
+    plugin::PluginsCommand, smoke::SmokeCommand, stake::StakeCommand, status::StatusCommand,
+    top::TopCommand, tx::TxCommand, unwind::UnwindCommand, validator::ValidatorCommand,
 };
 use build_info::{build_information, BUILD_PKG_VERSION};
 use clap::{Parser, Subcommand};
@@ -38,10 +40,14 @@ pub struct Command {
     #[clap(long, global = true, env = "GRAVITY_PROFILE")]
     pub profile: Option<String>,
 
-    /// Output format for query commands
+    /// Output format; with `json`, transaction commands print a single JSON result
     #[clap(long, global = true, value_enum, default_value = "plain", env = "GRAVITY_OUTPUT")]
     pub output: OutputFormat,
 
+    /// Language of messages and hints; defaults to the system locale (LANG), else English
+    #[clap(long, global = true, value_enum, env = "GRAVITY_LANG")]
+    pub lang: Option<Lang>,
+
     #[command(subcommand)]
     pub command: SubCommands,
 }
@@ -52,8 +58,12 @@ pub enum SubCommands {
     Genesis(GenesisCommand),
     /// Validator lifecycle management
     Validator(ValidatorCommand),
+    /// Consensus key backup (Shamir split/recover)
+    Keys(KeysCommand),
     /// Stake pool operations
     Stake(StakeCommand),
+    /// Delegate stake to StakePools run by others
+    Delegation(DelegationCommand),
     /// Node lifecycle management
     Node(NodeCommand),
     /// Distributed key generation queries
@@ -70,8 +80,23 @@ pub enum SubCommands {
     Init(InitCommand),
     /// Diagnose config, connectivity, and deployment issues
     Doctor(DoctorCommand),
+    /// End-to-end smoke test of a network: submit a transaction, await its commit, read the
+    /// block, check randomness and validator set consistency
+    Smoke(SmokeCommand),
+    /// Show the audit trail of state-changing commands (~/.gravity/history.jsonl)
+    History(HistoryCommand),
+    /// Live terminal dashboard of chain, node, validator and DKG health
+    Top(TopCommand),
+    /// Transaction load generation
+    Tx(TxCommand),
+    /// List installed plugins (gravity-cli-<name> executables)
+    Plugins(PluginsCommand),
+    /// Runs the gravity-cli-<name> plugin
+    #[command(external_subcommand)]
+    Plugin(Vec<String>),
 }
 
 pub trait Executable {
-    fn execute(self) -> Result<(), anyhow::Error>;
+    /// Runs the command, printing its results in `format` (the global `--output` flag).
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error>;
 }
@@ -1,4 +1,7 @@
-use crate::command::{Command as GravityCommand, Executable};
+use crate::{
+    command::{Command as GravityCommand, Executable},
+    output::OutputFormat,
+};
 use clap::{CommandFactory, Parser};
 use clap_complete::{generate, Shell};
 
@@ -10,7 +13,7 @@ pub struct CompletionsCommand {
 }
 
 impl Executable for CompletionsCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, _: OutputFormat) -> Result<(), anyhow::Error> {
         let mut cmd = GravityCommand::command();
         generate(self.shell, &mut cmd, "gravity-cli", &mut std::io::stdout());
         Ok(())
@@ -1,3 +1,5 @@
+use crate::i18n::MissingOption;
+use alloy_primitives::Address;
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fs, path::PathBuf};
@@ -16,6 +18,22 @@ pub struct ProfileConfig {
     pub deploy_path: Option<String>,
     pub gas_limit: Option<u64>,
     pub gas_price: Option<u128>,
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Encrypted JSON keystore signing transactions, unless `--kms` or `--keystore` is given.
+    pub keystore: Option<String>,
+    /// Contract addresses replacing the system addresses.
+    #[serde(default)]
+    pub contracts: ContractOverrides,
+}
+
+/// `[profiles.<name>.contracts]`: contracts deployed away from their system address.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct ContractOverrides {
+    pub validator_manager: Option<Address>,
+    pub staking: Option<Address>,
+    pub reconfiguration: Option<Address>,
+    pub epoch_config: Option<Address>,
 }
 
 impl GravityConfig {
@@ -50,6 +68,16 @@ impl GravityConfig {
     }
 }
 
+/// Expands a leading `~/` in a path from the config file to the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => {
+            PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".to_string())).join(rest)
+        }
+        None => PathBuf::from(path),
+    }
+}
+
 /// Resolve a required string parameter: CLI flag > config value.
 /// clap with `env` feature already handles CLI > env var, so `cli_value` reflects both.
 pub fn resolve_required(
@@ -60,11 +88,7 @@ pub fn resolve_required(
 ) -> Result<String, anyhow::Error> {
     cli_value
         .or_else(|| config_value.cloned())
-        .ok_or_else(|| {
-            anyhow!(
-                "--{field_name} is required. Set it via CLI flag, {env_name} env var, or ~/.gravity/config.toml"
-            )
-        })
+        .ok_or_else(|| MissingOption::new(field_name, env_name).into())
 }
 
 /// Resolve an optional parameter with a default: CLI flag > config value > default.
@@ -75,3 +99,38 @@ pub fn resolve_with_default<T: Clone>(
 ) -> T {
     cli_value.or_else(|| config_value.cloned()).unwrap_or(default)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keystore_and_contract_overrides() {
+        let config: GravityConfig = toml::from_str(
+            r#"
+            active_profile = "devnet"
+
+            [profiles.devnet]
+            rpc_url = "http://127.0.0.1:8545"
+            keystore = "~/.gravity/keys/operator.json"
+
+            [profiles.devnet.contracts]
+            staking = "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"
+
+            [profiles.mainnet]
+            rpc_url = "https://rpc.gravity.xyz"
+            "#,
+        )
+        .unwrap();
+        let devnet = config.active_profile(None).unwrap();
+        assert_eq!(devnet.keystore.as_deref(), Some("~/.gravity/keys/operator.json"));
+        assert_eq!(
+            devnet.contracts.staking,
+            Some("0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512".parse().unwrap())
+        );
+        assert!(devnet.contracts.validator_manager.is_none());
+        let mainnet = config.active_profile(Some("mainnet")).unwrap();
+        assert!(mainnet.keystore.is_none() && mainnet.contracts.staking.is_none());
+        assert!(config.active_profile(Some("testnet")).is_none());
+    }
+}
@@ -1,5 +1,9 @@
+use crate::config::ContractOverrides;
 use alloy_primitives::{address, Address};
-use std::fmt::{Debug, Formatter};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::OnceLock,
+};
 
 /// ValidatorManagement contract address (from SystemAddresses.VALIDATOR_MANAGER)
 pub const VALIDATOR_MANAGER_ADDRESS: Address = address!("00000000000000000000000000000001625F2001");
@@ -13,6 +17,38 @@ pub const RECONFIGURATION_ADDRESS: Address = address!("0000000000000000000000000
 /// EpochConfig contract address (from SystemAddresses.EPOCH_CONFIG)
 pub const EPOCH_CONFIG_ADDRESS: Address = address!("00000000000000000000000000000001625F1005");
 
+static OVERRIDES: OnceLock<ContractOverrides> = OnceLock::new();
+
+/// Uses the contract addresses set in the config profile instead of the system addresses, e.g.
+/// on a devnet that deploys the contracts elsewhere. Called once, before any command runs.
+pub fn set_overrides(overrides: ContractOverrides) {
+    let _ = OVERRIDES.set(overrides);
+}
+
+fn overridden(pick: fn(&ContractOverrides) -> Option<Address>, default: Address) -> Address {
+    OVERRIDES.get().and_then(pick).unwrap_or(default)
+}
+
+/// ValidatorManagement contract address, unless the profile overrides it.
+pub fn validator_manager_address() -> Address {
+    overridden(|o| o.validator_manager, VALIDATOR_MANAGER_ADDRESS)
+}
+
+/// Staking contract address, unless the profile overrides it.
+pub fn staking_address() -> Address {
+    overridden(|o| o.staking, STAKING_ADDRESS)
+}
+
+/// Reconfiguration contract address, unless the profile overrides it.
+pub fn reconfiguration_address() -> Address {
+    overridden(|o| o.reconfiguration, RECONFIGURATION_ADDRESS)
+}
+
+/// EpochConfig contract address, unless the profile overrides it.
+pub fn epoch_config_address() -> Address {
+    overridden(|o| o.epoch_config, EPOCH_CONFIG_ADDRESS)
+}
+
 // Define contract interface using alloy_sol_macro
 alloy_sol_macro::sol! {
     // ============================================================================
@@ -84,6 +120,11 @@ alloy_sol_macro::sol! {
             bytes calldata newPop
         ) external;
         function setFeeRecipient(address stakePool, address newRecipient) external;
+        function updateNetworkAddresses(
+            address stakePool,
+            bytes calldata networkAddresses,
+            bytes calldata fullnodeAddresses
+        ) external;
 
         // === View Functions ===
         function getValidator(address stakePool) external view returns (ValidatorRecord memory);
@@ -162,6 +203,86 @@ alloy_sol_macro::sol! {
         );
     }
 
+    // ============================================================================
+    // STAKE POOL CONTRACT (one per pool, deployed at the pool address)
+    // ============================================================================
+
+    contract StakePool {
+        /// Add msg.value to the pool's active stake (staker only)
+        function addStake() external payable;
+
+        /// Move `amount` of active stake to pending withdrawal, claimable once the lockup
+        /// expires (staker only)
+        function unstake(uint256 amount) external;
+
+        /// Send the pending withdrawals whose lockup expired to `recipient` (staker only)
+        function withdrawAvailable(address recipient) external returns (uint256 amount);
+
+        /// Extend the lockup by `durationMicros`, counted from the current expiry or from now if
+        /// it has already passed (staker only)
+        function renewLockUntil(uint64 durationMicros) external;
+
+        /// Unstaked amount still waiting for the lockup to expire
+        function getPendingWithdrawal() external view returns (uint256);
+
+        /// Unstaked amount that can be withdrawn now
+        function getClaimableAmount() external view returns (uint256);
+
+        /// Delegate msg.value to the pool on behalf of msg.sender
+        function delegate() external payable;
+
+        /// Move `amount` of the caller's delegation to pending withdrawal, claimable once the
+        /// pool's lockup expires
+        function undelegate(uint256 amount) external;
+
+        /// Active delegation of `delegator`
+        function getDelegation(address delegator) external view returns (uint256);
+
+        /// Undelegated amount of `delegator` still waiting for the lockup to expire
+        function getPendingUndelegation(address delegator) external view returns (uint256);
+
+        /// Addresses with an active or pending delegation to the pool
+        function getDelegators() external view returns (address[] memory);
+
+        // === Events ===
+        event StakeAdded(address indexed pool, uint256 amount);
+        event StakeUnstaked(address indexed pool, uint256 amount, uint64 lockedUntil);
+        event StakeWithdrawn(address indexed pool, address indexed recipient, uint256 amount);
+        event LockupRenewed(address indexed pool, uint64 oldLockedUntil, uint64 newLockedUntil);
+        event Delegated(address indexed pool, address indexed delegator, uint256 amount);
+        event Undelegated(
+            address indexed pool,
+            address indexed delegator,
+            uint256 amount,
+            uint64 lockedUntil
+        );
+    }
+
+    // ============================================================================
+    // SLASHING CONTRACT
+    // ============================================================================
+
+    contract Slashing {
+        /// `kind` is 0 for a double vote and 1 for conflicting proposals; `first` and `second`
+        /// are the BCS encoded signed messages.
+        function submitEquivocationEvidence(
+            uint8 kind,
+            uint64 epoch,
+            uint64 round,
+            bytes32 author,
+            bytes calldata first,
+            bytes calldata second
+        ) external;
+
+        event EquivocationEvidenceSubmitted(
+            bytes32 indexed author,
+            uint64 epoch,
+            uint64 round,
+            uint8 kind,
+            address reporter
+        );
+    }
+
     // ============================================================================
     // RECONFIGURATION CONTRACT
     // ============================================================================
@@ -0,0 +1,184 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{staking_address, StakePool, Staking},
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{finish_dry_run, send_value, simulate_value, DryRunArgs},
+    util::{format_ether, parse_ether},
+    validator::status::view,
+};
+
+#[derive(Debug, Parser)]
+pub struct DelegateCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address to delegate to
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Stake to delegate in ETH
+    #[clap(long)]
+    pub amount: String,
+
+    #[clap(flatten)]
+    pub dry_run: DryRunArgs,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `delegation delegate` with `--output json`.
+#[derive(Debug, Serialize)]
+struct DelegateResult {
+    stake_pool: String,
+    delegator: String,
+    /// Amount delegated in ETH.
+    amount: String,
+    transaction: TxSummary,
+    /// The delegator's active delegation to the pool in ETH, after this one.
+    delegation: String,
+}
+
+impl Executable for DelegateCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl DelegateCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool).map_err(|e| {
+            anyhow::anyhow!("Invalid stake pool address '{}': {e}", self.stake_pool)
+        })?;
+        let amount = parse_ether(&self.amount)?;
+        if amount.is_zero() {
+            return Err(anyhow::anyhow!("--amount must be greater than zero"));
+        }
+
+        if self.dry_run.enabled {
+            progress!(format, "Dry run: the transaction is simulated, nothing is sent\n");
+        }
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}", fees.describe());
+        let balance = provider.get_balance(wallet_address).await?;
+        progress!(format, "   Wallet balance: {} ETH\n", format_ether(balance));
+        if balance < amount {
+            return Err(anyhow::anyhow!(
+                "Insufficient balance: delegating {} ETH, wallet holds {} ETH",
+                format_ether(amount),
+                format_ether(balance)
+            ));
+        }
+
+        // 2. Validate the StakePool
+        progress!(format, "2. Validating StakePool: {stake_pool:?}");
+        if !view(&provider, staking_address(), Staking::isPoolCall { pool: stake_pool }).await? {
+            return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+        }
+        let owner =
+            view(&provider, staking_address(), Staking::getPoolOwnerCall { pool: stake_pool })
+                .await?;
+        progress!(format, "   Pool owner: {owner:?}");
+        let before =
+            view(&provider, stake_pool, StakePool::getDelegationCall { delegator: wallet_address })
+                .await?;
+        progress!(format, "   Current delegation: {} ETH\n", format_ether(before));
+
+        // 3. Delegate
+        progress!(format, "3. Delegating {} ETH...", format_ether(amount));
+        let input: Bytes = StakePool::delegateCall {}.abi_encode().into();
+        if self.dry_run.enabled {
+            let simulation = simulate_value(
+                &provider,
+                wallet_address,
+                stake_pool,
+                input,
+                Some(amount),
+                "delegate",
+                "Delegated",
+                format,
+            )
+            .await?;
+            return finish_dry_run(stake_pool, &[simulation], format);
+        }
+        let receipt =
+            send_value(&provider, wallet_address, stake_pool, input, Some(amount), &fees, format)
+                .await?;
+        progress!(
+            format,
+            "   Transaction confirmed, block number: {}",
+            receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
+        );
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
+            "   Transaction cost: {} ETH",
+            format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
+        );
+
+        // Check delegation event
+        let mut found = None;
+        for log in receipt.logs() {
+            if let Ok(event) = StakePool::Delegated::decode_log(&log.inner) {
+                progress!(format, "   Delegation successful!");
+                progress!(format, "   - StakePool: {}", event.pool);
+                progress!(format, "   - Delegator: {}", event.delegator);
+                progress!(format, "   - Amount: {} ETH", format_ether(event.amount));
+                found = Some(serde_json::json!({
+                    "pool": format!("{}", event.pool),
+                    "delegator": format!("{}", event.delegator),
+                    "amount": format_ether(event.amount),
+                }));
+                break;
+            }
+        }
+        let Some(event) = found else {
+            progress!(format, "   Delegation event not found\n");
+            return Err(anyhow::anyhow!("Failed to find Delegated event"));
+        };
+        let after =
+            view(&provider, stake_pool, StakePool::getDelegationCall { delegator: wallet_address })
+                .await?;
+        progress!(
+            format,
+            "   Delegation: {} ETH -> {} ETH\n",
+            format_ether(before),
+            format_ether(after)
+        );
+        format.emit(&DelegateResult {
+            stake_pool: format!("{stake_pool}"),
+            delegator: format!("{wallet_address}"),
+            amount: format_ether(amount),
+            transaction: TxSummary::new(&receipt).with_event(event),
+            delegation: format_ether(after),
+        })
+    }
+}
@@ -0,0 +1,129 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{staking_address, StakePool, Staking},
+    i18n::{CatalogError, MissingOption, Msg},
+    output::OutputFormat,
+    util::format_ether,
+    validator::status::view,
+};
+
+#[derive(Debug, Parser)]
+pub struct ListDelegationsCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// List every delegation to this StakePool
+    #[clap(long, required_unless_present = "delegator", conflicts_with = "delegator")]
+    pub stake_pool: Option<String>,
+
+    /// List this address's delegations across all StakePools
+    #[clap(long)]
+    pub delegator: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DelegationInfo {
+    stake_pool: String,
+    delegator: String,
+    /// Active delegation in ETH.
+    active: String,
+    /// Undelegated amount in ETH waiting for the pool's lockup to expire.
+    pending: String,
+}
+
+impl Executable for ListDelegationsCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+fn parse_address(value: &str, label: &str) -> Result<Address, anyhow::Error> {
+    Address::from_str(value).map_err(|e| anyhow::anyhow!("Invalid {label} address '{value}': {e}"))
+}
+
+/// Active and pending delegation of `delegator` to `pool`.
+async fn delegation<P: Provider>(
+    provider: &P,
+    pool: Address,
+    delegator: Address,
+) -> Result<(U256, U256), anyhow::Error> {
+    let active = view(provider, pool, StakePool::getDelegationCall { delegator }).await?;
+    let pending = view(provider, pool, StakePool::getPendingUndelegationCall { delegator }).await?;
+    Ok((active, pending))
+}
+
+impl ListDelegationsCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let is_json = matches!(format, OutputFormat::Json);
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+
+        // Pairs of (pool, delegator) to report
+        let pairs: Vec<(Address, Address)> = match (&self.stake_pool, &self.delegator) {
+            (Some(pool), _) => {
+                let pool = parse_address(pool, "stake pool")?;
+                if !view(&provider, staking_address(), Staking::isPoolCall { pool }).await? {
+                    return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+                }
+                view(&provider, pool, StakePool::getDelegatorsCall {})
+                    .await?
+                    .into_iter()
+                    .map(|delegator| (pool, delegator))
+                    .collect()
+            }
+            (None, Some(delegator)) => {
+                let delegator = parse_address(delegator, "delegator")?;
+                view(&provider, staking_address(), Staking::getAllPoolsCall {})
+                    .await?
+                    .into_iter()
+                    .map(|pool| (pool, delegator))
+                    .collect()
+            }
+            (None, None) => unreachable!("clap requires --stake-pool or --delegator"),
+        };
+
+        let mut delegations = Vec::new();
+        for (pool, delegator) in pairs {
+            let (active, pending) = delegation(&provider, pool, delegator).await?;
+            if active.is_zero() && pending.is_zero() {
+                continue;
+            }
+            delegations.push(DelegationInfo {
+                stake_pool: format!("{pool:?}"),
+                delegator: format!("{delegator:?}"),
+                active: format_ether(active),
+                pending: format_ether(pending),
+            });
+        }
+
+        if is_json {
+            let result = serde_json::json!({ "delegations": delegations });
+            println!("{}", serde_json::to_string_pretty(&result)?);
+            return Ok(());
+        }
+        if delegations.is_empty() {
+            println!("No delegations found.");
+            return Ok(());
+        }
+        println!("Found {} delegation(s):\n", delegations.len());
+        println!(
+            "{:<44} {:<44} {:<20} {:<20}",
+            "Pool Address", "Delegator", "Active (ETH)", "Pending (ETH)"
+        );
+        println!("{}", "-".repeat(131));
+        for d in &delegations {
+            println!("{:<44} {:<44} {:<20} {:<20}", d.stake_pool, d.delegator, d.active, d.pending);
+        }
+        println!();
+        Ok(())
+    }
+}
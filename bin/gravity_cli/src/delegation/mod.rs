@@ -0,0 +1,39 @@
+mod delegate;
+mod list;
+mod undelegate;
+
+use clap::{Parser, Subcommand};
+
+use crate::{
+    command::Executable,
+    delegation::{
+        delegate::DelegateCommand, list::ListDelegationsCommand, undelegate::UndelegateCommand,
+    },
+    output::OutputFormat,
+};
+
+#[derive(Debug, Parser)]
+pub struct DelegationCommand {
+    #[command(subcommand)]
+    pub command: SubCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SubCommands {
+    /// Delegate stake to an existing StakePool
+    Delegate(DelegateCommand),
+    /// Unlock stake delegated to a StakePool
+    Undelegate(UndelegateCommand),
+    /// List the delegations of a StakePool or of a delegator
+    ListDelegations(ListDelegationsCommand),
+}
+
+impl Executable for DelegationCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::Delegate(delegate_cmd) => delegate_cmd.execute(format),
+            SubCommands::Undelegate(undelegate_cmd) => undelegate_cmd.execute(format),
+            SubCommands::ListDelegations(list_cmd) => list_cmd.execute(format),
+        }
+    }
+}
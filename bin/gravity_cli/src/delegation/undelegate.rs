@@ -0,0 +1,205 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{staking_address, StakePool, Staking},
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{finish_dry_run, send_to, simulate, DryRunArgs},
+    util::{format_ether, parse_ether},
+    validator::{
+        status::{format_duration, view},
+        unstake::lockup,
+    },
+};
+
+#[derive(Debug, Parser)]
+pub struct UndelegateCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address to undelegate from
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Stake to unlock in ETH
+    #[clap(long, required_unless_present = "all", conflicts_with = "all")]
+    pub amount: Option<String>,
+
+    /// Unlock the whole delegation
+    #[clap(long)]
+    pub all: bool,
+
+    #[clap(flatten)]
+    pub dry_run: DryRunArgs,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `delegation undelegate` with `--output json`.
+#[derive(Debug, Serialize)]
+struct UndelegateResult {
+    stake_pool: String,
+    delegator: String,
+    /// Amount unlocked in ETH.
+    amount: String,
+    transaction: TxSummary,
+    /// When the unlocked stake is released, in microseconds since the Unix epoch.
+    unlocks_at_micros: u64,
+}
+
+impl Executable for UndelegateCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl UndelegateCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool).map_err(|e| {
+            anyhow::anyhow!("Invalid stake pool address '{}': {e}", self.stake_pool)
+        })?;
+
+        if self.dry_run.enabled {
+            progress!(format, "Dry run: the transaction is simulated, nothing is sent\n");
+        }
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
+
+        // 2. Check the delegation
+        progress!(format, "2. Checking delegation to StakePool: {stake_pool:?}");
+        if !view(&provider, staking_address(), Staking::isPoolCall { pool: stake_pool }).await? {
+            return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+        }
+        let delegation =
+            view(&provider, stake_pool, StakePool::getDelegationCall { delegator: wallet_address })
+                .await?;
+        progress!(format, "   Active delegation: {} ETH", format_ether(delegation));
+        let amount = match &self.amount {
+            Some(amount) => parse_ether(amount)?,
+            None => delegation,
+        };
+        if amount.is_zero() {
+            return Err(anyhow::anyhow!(
+                "Nothing to undelegate: {wallet_address:?} has no active delegation to this pool"
+            ));
+        }
+        if amount > delegation {
+            return Err(anyhow::anyhow!(
+                "Cannot undelegate {} ETH, the active delegation is {} ETH",
+                format_ether(amount),
+                format_ether(delegation)
+            ));
+        }
+        let (locked_until, now_micros) = lockup(&provider, stake_pool).await?;
+        if locked_until > now_micros {
+            progress!(
+                format,
+                "   Pool lockup expires in {}",
+                format_duration((locked_until - now_micros) / 1_000_000)
+            );
+        } else {
+            progress!(
+                format,
+                "   Pool lockup has expired, the stake is released right after undelegating"
+            );
+        }
+        progress!(format);
+
+        // 3. Undelegate
+        progress!(format, "3. Undelegating {} ETH...", format_ether(amount));
+        let input: Bytes = StakePool::undelegateCall { amount }.abi_encode().into();
+        if self.dry_run.enabled {
+            let simulation = simulate(
+                &provider,
+                wallet_address,
+                stake_pool,
+                input,
+                "undelegate",
+                "Undelegated",
+                format,
+            )
+            .await?;
+            return finish_dry_run(stake_pool, &[simulation], format);
+        }
+        let receipt = send_to(&provider, wallet_address, stake_pool, input, &fees, format).await?;
+        progress!(
+            format,
+            "   Transaction confirmed, block number: {}",
+            receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
+        );
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
+            "   Transaction cost: {} ETH",
+            format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
+        );
+
+        // Check undelegation event
+        let mut found = None;
+        for log in receipt.logs() {
+            if let Ok(event) = StakePool::Undelegated::decode_log(&log.inner) {
+                progress!(format, "   Undelegation successful!");
+                progress!(format, "   - StakePool: {}", event.pool);
+                progress!(format, "   - Delegator: {}", event.delegator);
+                progress!(format, "   - Amount: {} ETH", format_ether(event.amount));
+                found = Some((
+                    event.lockedUntil,
+                    serde_json::json!({
+                        "pool": format!("{}", event.pool),
+                        "delegator": format!("{}", event.delegator),
+                        "amount": format_ether(event.amount),
+                        "locked_until": event.lockedUntil,
+                    }),
+                ));
+                break;
+            }
+        }
+        let Some((unlocks_at, event)) = found else {
+            progress!(format, "   Undelegation event not found\n");
+            return Err(anyhow::anyhow!("Failed to find Undelegated event"));
+        };
+        if unlocks_at > now_micros {
+            progress!(
+                format,
+                "   Released in {}\n",
+                format_duration((unlocks_at - now_micros) / 1_000_000)
+            );
+        } else {
+            progress!(format, "   Released now\n");
+        }
+        format.emit(&UndelegateResult {
+            stake_pool: format!("{stake_pool}"),
+            delegator: format!("{wallet_address}"),
+            amount: format_ether(amount),
+            transaction: TxSummary::new(&receipt).with_event(event),
+            unlocks_at_micros: unlocks_at,
+        })
+    }
+}
@@ -0,0 +1,498 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use aptos_consensus::threshold_decrypt::{
+    self, Bases, CiphertextProof, DecryptionShare, EncodedDecryptionShare,
+};
+use blstrs::{G2Affine, G2Projective, Gt};
+use clap::Parser;
+use gaptos::{
+    aptos_dkg::pvss::{traits::Transcript, Player},
+    aptos_types::dkg::{DKGSessionMetadata, DKGTrait, DefaultDKG},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use crate::{
+    command::Executable,
+    i18n::MissingOption,
+    output::OutputFormat,
+    util::{ErrorResponse, API_V1},
+};
+
+#[derive(Debug, Parser)]
+pub struct DecryptRequestCommand {
+    /// JSON ciphertext file: `{"epoch", "u", "proof", "nonce", "ciphertext"}`, all byte fields
+    /// hex encoded
+    #[clap(long)]
+    pub ciphertext: PathBuf,
+
+    /// Validator API addresses to collect decryption shares from (comma separated or repeated)
+    #[clap(long = "api-url", alias = "server-url", value_delimiter = ',')]
+    pub api_urls: Vec<String>,
+
+    /// Write the plaintext to this file instead of printing it hex encoded
+    #[clap(long)]
+    pub out: Option<PathBuf>,
+}
+
+/// A message encrypted to an epoch's DKG key, see `aptos_consensus::threshold_decrypt`.
+#[derive(Deserialize, Debug)]
+struct CiphertextFile {
+    /// Epoch whose dealt key the message was encrypted to.
+    epoch: u64,
+    /// `r·ĝ`, compressed G2.
+    u: String,
+    /// Proof of knowledge of `r`, without which validators refuse to decrypt.
+    proof: String,
+    /// 12-byte AES-256-GCM nonce.
+    nonce: String,
+    /// AES-256-GCM ciphertext and tag, authenticating the epoch and `u`.
+    ciphertext: String,
+}
+
+#[derive(Serialize, Debug)]
+struct DecryptShareRequest<'a> {
+    epoch: u64,
+    u: &'a str,
+    proof: &'a str,
+}
+
+/// Response of a validator's `POST /dkg/decrypt_share`.
+#[derive(Deserialize, Debug)]
+struct DecryptShareResponse {
+    validator: String,
+    /// One entry per DKG share the validator holds; weighted validators hold several.
+    shares: Vec<EncodedDecryptionShare>,
+}
+
+/// Response of `GET /dkg/transcript/:epoch`, as far as it is needed here.
+#[derive(Deserialize, Debug)]
+struct TranscriptResponse {
+    metadata: String,
+    transcript: String,
+}
+
+#[derive(Serialize, Debug)]
+struct DecryptReport {
+    epoch: u64,
+    threshold: usize,
+    shares_used: usize,
+    /// Validators whose shares were collected and verified.
+    validators: Vec<String>,
+    /// Validators that failed to answer or sent shares that did not verify.
+    failures: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plaintext: Option<String>,
+}
+
+impl Executable for DecryptRequestCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl DecryptRequestCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        if self.api_urls.is_empty() {
+            return Err(MissingOption::without_env("api-url").into());
+        }
+        let file: CiphertextFile = serde_json::from_slice(&fs::read(&self.ciphertext)?)
+            .map_err(|e| anyhow::anyhow!("Invalid ciphertext file: {e}"))?;
+        let u = threshold_decrypt::decode_g2(&file.u)
+            .map_err(|e| anyhow::anyhow!("Invalid ciphertext u: {e}"))?;
+        let proof = CiphertextProof::from_hex(&file.proof)
+            .map_err(|e| anyhow::anyhow!("Invalid ciphertext proof: {e}"))?;
+        let nonce = hex::decode(file.nonce.trim_start_matches("0x"))?;
+        let ciphertext = hex::decode(file.ciphertext.trim_start_matches("0x"))?;
+
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .build()?;
+        let request = DecryptShareRequest { epoch: file.epoch, u: &file.u, proof: &file.proof };
+
+        // Shares are checked against the transcript, so every validator must serve the same one.
+        let mut keys: Option<(TranscriptResponse, EpochKeys)> = None;
+        let mut collector = ShareCollector::new(u);
+        let mut validators = Vec::new();
+        let mut failures = Vec::new();
+        for api_url in &self.api_urls {
+            let base_url = Self::normalize_url(api_url);
+            let transcript = match fetch_transcript(&client, &base_url, file.epoch).await {
+                Ok(transcript) => transcript,
+                Err(e) => {
+                    eprintln!("Skipping {api_url}: {e}");
+                    failures.push(api_url.clone());
+                    continue;
+                }
+            };
+            match &keys {
+                Some((first, _)) => {
+                    if first.metadata != transcript.metadata ||
+                        first.transcript != transcript.transcript
+                    {
+                        eprintln!("Skipping {api_url}: it serves a different DKG transcript");
+                        failures.push(api_url.clone());
+                        continue;
+                    }
+                }
+                None => {
+                    let epoch_keys = EpochKeys::from_transcript(&transcript)
+                        .map_err(|e| anyhow::anyhow!("Invalid DKG transcript: {e}"))?;
+                    if !threshold_decrypt::verify_ciphertext(
+                        &epoch_keys.bases,
+                        file.epoch,
+                        &u,
+                        &proof,
+                    ) {
+                        return Err(anyhow::anyhow!(
+                            "The ciphertext proof does not verify against the epoch's DKG key"
+                        ));
+                    }
+                    keys = Some((transcript, epoch_keys));
+                }
+            }
+            let epoch_keys = &keys.as_ref().expect("set above").1;
+
+            let url = format!("{base_url}{API_V1}/dkg/decrypt_share");
+            let response = match fetch_shares(&client, &url, &request).await {
+                Ok(response) => response,
+                Err(e) => {
+                    eprintln!("Skipping {api_url}: {e}");
+                    failures.push(api_url.clone());
+                    continue;
+                }
+            };
+            match collector.add(epoch_keys, &response) {
+                Ok(()) => validators.push(response.validator),
+                Err(e) => {
+                    eprintln!("Rejecting shares of {} from {api_url}: {e}", response.validator);
+                    failures.push(response.validator);
+                }
+            }
+        }
+
+        let Some((_, epoch_keys)) = keys else {
+            return Err(anyhow::anyhow!("No validator served the DKG transcript"));
+        };
+        let threshold = epoch_keys.threshold;
+        if collector.shares.len() < threshold {
+            return Err(anyhow::anyhow!(
+                "Collected {} verified shares, {threshold} are needed",
+                collector.shares.len()
+            ));
+        }
+
+        let used: Vec<(usize, Gt)> = collector.shares.into_iter().take(threshold).collect();
+        let key = threshold_decrypt::combine(epoch_keys.total_weight, &used);
+        let plaintext = open(&key, file.epoch, &u, &nonce, &ciphertext)?;
+
+        let printed = match &self.out {
+            Some(path) => {
+                fs::write(path, &plaintext)?;
+                None
+            }
+            None => Some(hex::encode(&plaintext)),
+        };
+        let report = DecryptReport {
+            epoch: file.epoch,
+            threshold,
+            shares_used: used.len(),
+            validators,
+            failures,
+            plaintext: printed,
+        };
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            OutputFormat::Plain => {
+                println!(
+                    "Decrypted with {} of {} required shares from {} validators (epoch {})",
+                    report.shares_used,
+                    report.threshold,
+                    report.validators.len(),
+                    report.epoch
+                );
+                match (&report.plaintext, &self.out) {
+                    (Some(plaintext), _) => println!("Plaintext: 0x{plaintext}"),
+                    (None, Some(path)) => println!("Plaintext written to {}", path.display()),
+                    (None, None) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What the transcript says about the epoch's key: the bases, the threshold and, for every
+/// share dealt, its owner and public share.
+struct EpochKeys {
+    bases: Bases,
+    threshold: usize,
+    total_weight: usize,
+    /// Hex addresses of the validators the shares were dealt to, by player id.
+    validators: Vec<String>,
+    /// Owning player and public share of every virtual player.
+    public_shares: Vec<(usize, G2Projective)>,
+}
+
+impl EpochKeys {
+    fn from_transcript(response: &TranscriptResponse) -> Result<Self, anyhow::Error> {
+        let metadata: DKGSessionMetadata =
+            bcs::from_bytes(&hex::decode(response.metadata.trim_start_matches("0x"))?)?;
+        let transcript: <DefaultDKG as DKGTrait>::Transcript =
+            bcs::from_bytes(&hex::decode(response.transcript.trim_start_matches("0x"))?)?;
+        let params = DefaultDKG::new_public_params(&metadata);
+        let wconfig = &params.pvss_config.wconfig;
+
+        let mut public_shares = vec![None; wconfig.get_total_weight()];
+        for id in 0..metadata.target_validator_set.len() {
+            let player = Player { id };
+            let shares = transcript.main.get_public_key_share(wconfig, &player);
+            for (j, share) in shares.iter().enumerate() {
+                let index = wconfig.get_virtual_player(&player, j).id;
+                public_shares[index] = Some((id, *share.as_group_element()));
+            }
+        }
+        Ok(Self {
+            bases: Bases {
+                h: *params.pvss_config.pp.get_encryption_public_params().message_base(),
+                g_hat: *params.pvss_config.pp.get_commitment_base(),
+            },
+            threshold: wconfig.get_threshold_weight(),
+            total_weight: wconfig.get_total_weight(),
+            validators: metadata
+                .target_validator_set
+                .iter()
+                .map(|validator| hex::encode(validator.addr.as_ref()))
+                .collect(),
+            public_shares: public_shares
+                .into_iter()
+                .collect::<Option<_>>()
+                .ok_or_else(|| anyhow::anyhow!("the transcript does not cover every share"))?,
+        })
+    }
+}
+
+/// Verified shares keyed by virtual player index.
+struct ShareCollector {
+    u: G2Projective,
+    shares: BTreeMap<usize, Gt>,
+}
+
+impl ShareCollector {
+    fn new(u: G2Projective) -> Self {
+        Self { u, shares: BTreeMap::new() }
+    }
+
+    /// Adds a validator's shares if every one of them is its own, new and verifies against the
+    /// transcript; otherwise adds none.
+    fn add(
+        &mut self,
+        keys: &EpochKeys,
+        response: &DecryptShareResponse,
+    ) -> Result<(), anyhow::Error> {
+        let validator = response.validator.trim_start_matches("0x").to_ascii_lowercase();
+        let player = keys
+            .validators
+            .iter()
+            .position(|address| *address == validator)
+            .ok_or_else(|| anyhow::anyhow!("not a participant of the epoch's DKG session"))?;
+        if response.shares.is_empty() {
+            return Err(anyhow::anyhow!("no shares returned"));
+        }
+        let mut verified = BTreeMap::new();
+        for encoded in &response.shares {
+            let share: DecryptionShare = encoded.decode()?;
+            let (owner, public_share) = keys
+                .public_shares
+                .get(share.index)
+                .ok_or_else(|| anyhow::anyhow!("share {} was never dealt", share.index))?;
+            if *owner != player {
+                return Err(anyhow::anyhow!("share {} belongs to another validator", share.index));
+            }
+            if self.shares.contains_key(&share.index) || verified.contains_key(&share.index) {
+                return Err(anyhow::anyhow!("share {} was already collected", share.index));
+            }
+            if !threshold_decrypt::verify_share(&keys.bases, &self.u, &share, public_share) {
+                return Err(anyhow::anyhow!("share {} does not match the transcript", share.index));
+            }
+            verified.insert(share.index, share.share);
+        }
+        self.shares.extend(verified);
+        Ok(())
+    }
+}
+
+async fn fetch_transcript(
+    client: &reqwest::Client,
+    base_url: &str,
+    epoch: u64,
+) -> Result<TranscriptResponse, anyhow::Error> {
+    let response = client.get(format!("{base_url}{API_V1}/dkg/transcript/{epoch}")).send().await?;
+    let status_code = response.status();
+    if !status_code.is_success() {
+        let error_msg = match response.json::<ErrorResponse>().await {
+            Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
+            Err(_) => format!("HTTP {status_code}"),
+        };
+        return Err(anyhow::anyhow!("cannot read the DKG transcript: {error_msg}"));
+    }
+    Ok(response.json().await?)
+}
+
+async fn fetch_shares(
+    client: &reqwest::Client,
+    url: &str,
+    request: &DecryptShareRequest<'_>,
+) -> Result<DecryptShareResponse, anyhow::Error> {
+    let response = client.post(url).json(request).send().await?;
+    let status_code = response.status();
+    if status_code == reqwest::StatusCode::NOT_FOUND {
+        return Err(anyhow::anyhow!("the node does not serve decryption shares"));
+    }
+    if !status_code.is_success() {
+        let error_msg = match response.json::<ErrorResponse>().await {
+            Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
+            Err(_) => format!("HTTP {status_code}"),
+        };
+        return Err(anyhow::anyhow!("{error_msg}"));
+    }
+    Ok(response.json().await?)
+}
+
+/// The epoch and `u` the AEAD authenticates besides the ciphertext.
+fn associated_data(epoch: u64, u: &G2Projective) -> Vec<u8> {
+    [epoch.to_le_bytes().as_slice(), G2Affine::from(u).to_compressed().as_slice()].concat()
+}
+
+fn open(
+    key: &[u8; 32],
+    epoch: u64,
+    u: &G2Projective,
+    nonce: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, anyhow::Error> {
+    if nonce.len() != 12 {
+        return Err(anyhow::anyhow!("expected a 12-byte nonce"));
+    }
+    let aad = associated_data(epoch, u);
+    Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: &aad })
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Decryption failed: the ciphertext was altered or encrypted to another key"
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blstrs::{G1Projective, Scalar};
+    use ff::Field;
+    use gaptos::aptos_types::account_address::AccountAddress;
+    use group::Group;
+    use rand_core::OsRng;
+
+    /// Two validators holding shares 0 and 1, and 2 respectively, of a random key.
+    fn keys() -> (EpochKeys, Vec<threshold_decrypt::EpochKeyShares>) {
+        let bases = Bases {
+            h: G1Projective::generator() * Scalar::random(OsRng),
+            g_hat: G2Projective::generator(),
+        };
+        let secrets: Vec<Scalar> = (0..3).map(|_| Scalar::random(OsRng)).collect();
+        let owners = [0, 0, 1];
+        let keys = EpochKeys {
+            bases,
+            threshold: 2,
+            total_weight: 3,
+            validators: vec!["aa".repeat(32), "bb".repeat(32)],
+            public_shares: owners
+                .iter()
+                .zip(&secrets)
+                .map(|(owner, secret)| (*owner, bases.g_hat * secret))
+                .collect(),
+        };
+        let holders = (0..2)
+            .map(|player| {
+                threshold_decrypt::EpochKeyShares::new(
+                    1,
+                    AccountAddress::ZERO,
+                    bases,
+                    owners
+                        .iter()
+                        .zip(&secrets)
+                        .enumerate()
+                        .filter(|(_, (owner, _))| **owner == player)
+                        .map(|(index, (_, secret))| (index, bases.h * secret))
+                        .collect(),
+                )
+            })
+            .collect();
+        (keys, holders)
+    }
+
+    fn response(validator: &str, shares: &[DecryptionShare]) -> DecryptShareResponse {
+        DecryptShareResponse {
+            validator: validator.to_string(),
+            shares: shares.iter().map(DecryptionShare::encode).collect(),
+        }
+    }
+
+    #[test]
+    fn collects_each_share_once_from_its_owner() {
+        let (keys, holders) = keys();
+        let pk = keys.public_shares[0].1;
+        let encapsulation = threshold_decrypt::encapsulate(&keys.bases, &pk, 1);
+        let shares: Vec<Vec<DecryptionShare>> = holders
+            .iter()
+            .map(|holder| holder.decrypt(&encapsulation.u, &encapsulation.proof).unwrap())
+            .collect();
+        let (first, second) = (&keys.validators[0], &keys.validators[1]);
+
+        let mut collector = ShareCollector::new(encapsulation.u);
+        // Shares claimed by the wrong validator are refused.
+        assert!(collector.add(&keys, &response(second, &shares[0])).is_err());
+        collector.add(&keys, &response(first, &shares[0])).unwrap();
+        // A repeated answer must not replace or double count what was collected.
+        assert!(collector.add(&keys, &response(first, &shares[0])).is_err());
+        assert_eq!(collector.shares.len(), 2);
+
+        let mut forged = shares[1].clone();
+        forged[0].share += Gt::generator();
+        assert!(collector.add(&keys, &response(second, &forged)).is_err());
+        collector.add(&keys, &response(second, &shares[1])).unwrap();
+        assert_eq!(collector.shares.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn open_authenticates_epoch_and_u() {
+        let key = [7u8; 32];
+        let u = G2Projective::generator() * Scalar::random(OsRng);
+        let nonce = [1u8; 12];
+        let plaintext = b"encrypted mempool transaction".to_vec();
+        let ciphertext = Aes256Gcm::new((&key).into())
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload { msg: &plaintext, aad: &associated_data(4, &u) },
+            )
+            .unwrap();
+
+        assert_eq!(open(&key, 4, &u, &nonce, &ciphertext).unwrap(), plaintext);
+        assert!(open(&key, 5, &u, &nonce, &ciphertext).is_err());
+        assert!(open(&key, 4, &G2Projective::generator(), &nonce, &ciphertext).is_err());
+        assert!(open(&[8u8; 32], 4, &u, &nonce, &ciphertext).is_err());
+    }
+}
@@ -1,9 +1,18 @@
+mod decrypt;
+mod params;
 mod randomness;
 mod status;
 
 use clap::{Parser, Subcommand};
 
-use crate::dkg::{randomness::RandomnessCommand, status::StatusCommand};
+use crate::{
+    command::Executable,
+    dkg::{
+        decrypt::DecryptRequestCommand, params::ParamsCommand, randomness::RandomnessCommand,
+        status::StatusCommand,
+    },
+    output::OutputFormat,
+};
 
 #[derive(Debug, Parser)]
 pub struct DKGCommand {
@@ -15,4 +24,17 @@ pub struct DKGCommand {
 pub enum SubCommands {
     Status(StatusCommand),
     Randomness(RandomnessCommand),
+    DecryptRequest(DecryptRequestCommand),
+    Params(ParamsCommand),
+}
+
+impl Executable for DKGCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::Status(status_cmd) => status_cmd.execute(format),
+            SubCommands::Randomness(randomness_cmd) => randomness_cmd.execute(format),
+            SubCommands::DecryptRequest(decrypt_cmd) => decrypt_cmd.execute(format),
+            SubCommands::Params(params_cmd) => params_cmd.execute(format),
+        }
+    }
 }
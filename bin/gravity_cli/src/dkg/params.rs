@@ -0,0 +1,296 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, time::Duration};
+
+use crate::{
+    command::Executable,
+    i18n::MissingOption,
+    output::OutputFormat,
+    util::{ErrorResponse, API_V1},
+};
+
+#[derive(Debug, Parser)]
+pub struct ParamsCommand {
+    /// Node API address and port (e.g., 127.0.0.1:1024)
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+
+    /// Validators (hex addresses, comma separated) to count as offline, e.g. ones about to
+    /// leave the set
+    #[clap(long, value_delimiter = ',')]
+    pub offline: Vec<String>,
+}
+
+/// The node's `/dkg/params` response.
+#[derive(Deserialize, Serialize, Debug)]
+struct DkgParams {
+    current_epoch: u64,
+    epoch: u64,
+    dealer_epoch: u64,
+    block_number: u64,
+    curve: String,
+    randomness_enabled: bool,
+    secrecy_threshold: Option<f64>,
+    reconstruct_threshold: Option<f64>,
+    fast_path_secrecy_threshold: Option<f64>,
+    participants: usize,
+    total_weight: usize,
+    threshold_weight: usize,
+    fast_threshold_weight: Option<usize>,
+    transcript_bytes: usize,
+    in_progress_dealer_epoch: Option<u64>,
+    weights: Vec<ParticipantWeight>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ParticipantWeight {
+    address: String,
+    voting_power: u64,
+    weight: usize,
+}
+
+/// The node's `/consensus/validator_set/:epoch` response, as far as it is needed here.
+#[derive(Deserialize, Debug)]
+struct ValidatorSet {
+    validators: Vec<ValidatorInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ValidatorInfo {
+    address: String,
+}
+
+/// Headroom of the dealt keys, given the validators that are offline.
+#[derive(Serialize, Debug)]
+struct SafetyMargin {
+    /// Epoch of the validator set the participants were compared with, if it could be read.
+    validator_set_epoch: Option<u64>,
+    /// Participants counted as offline: those given with `--offline` and those no longer in
+    /// the current validator set.
+    offline: Vec<String>,
+    /// Validators of the current set that were not dealt a share.
+    not_dealt: Vec<String>,
+    /// Shares held by the participants that are online.
+    available_weight: usize,
+    /// Further participants, largest first, that can go offline before randomness stalls.
+    tolerated_failures: usize,
+    /// Fewest participants that together hold enough shares to reconstruct the key.
+    min_colluders: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+struct ParamsReport {
+    params: DkgParams,
+    margin: SafetyMargin,
+}
+
+impl Executable for ParamsCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+/// Failures tolerated by shares `weights` with reconstruction `threshold`: how many of the
+/// largest can drop out with the rest still holding `threshold`, and how many of the largest
+/// together reach it (None when all of them don't).
+fn margins(weights: &[usize], threshold: usize) -> (usize, Option<usize>) {
+    let mut weights = weights.to_vec();
+    weights.sort_unstable_by(|a, b| b.cmp(a));
+    let total: usize = weights.iter().sum();
+
+    let mut remaining = total;
+    let mut tolerated = 0;
+    for weight in &weights {
+        if remaining - weight < threshold {
+            break;
+        }
+        remaining -= weight;
+        tolerated += 1;
+    }
+    if total < threshold {
+        tolerated = 0;
+    }
+
+    let mut held = 0;
+    let colluders = weights.iter().position(|weight| {
+        held += weight;
+        held >= threshold
+    });
+    (tolerated, colluders.map(|i| i + 1))
+}
+
+fn normalize_address(address: &str) -> String {
+    address.trim().trim_start_matches("0x").to_ascii_lowercase()
+}
+
+fn percent(ratio: Option<f64>) -> String {
+    ratio.map_or_else(|| "-".to_string(), |ratio| format!("{:.2}% of stake", ratio * 100.0))
+}
+
+impl ParamsCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<T, anyhow::Error> {
+        let response = client.get(url).send().await?;
+        let status_code = response.status();
+        if !status_code.is_success() {
+            let error_msg = match response.json::<ErrorResponse>().await {
+                Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
+                Err(_) => format!("HTTP {status_code}"),
+            };
+            return Err(anyhow::anyhow!("Failed to fetch {url}: {error_msg}"));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let api_url =
+            self.api_url.ok_or_else(|| MissingOption::new("api-url", "GRAVITY_SERVER_URL"))?;
+        let base_url = Self::normalize_url(&api_url);
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        let params: DkgParams = Self::get(&client, &format!("{base_url}{API_V1}/dkg/params")).await?;
+        let current_set = match Self::get::<ValidatorSet>(
+            &client,
+            &format!("{base_url}{API_V1}/consensus/validator_set/{}", params.current_epoch),
+        )
+        .await
+        {
+            Ok(set) => Some(
+                set.validators
+                    .iter()
+                    .map(|v| normalize_address(&v.address))
+                    .collect::<BTreeSet<_>>(),
+            ),
+            Err(e) => {
+                eprintln!("Warning: not comparing with the current validator set: {e}");
+                None
+            }
+        };
+
+        let mut offline: BTreeSet<String> =
+            self.offline.iter().map(|address| normalize_address(address)).collect();
+        let participants: BTreeSet<String> =
+            params.weights.iter().map(|w| normalize_address(&w.address)).collect();
+        let mut not_dealt = Vec::new();
+        if let Some(current_set) = &current_set {
+            offline.extend(participants.difference(current_set).cloned());
+            not_dealt.extend(current_set.difference(&participants).cloned());
+        }
+        let online: Vec<usize> = params
+            .weights
+            .iter()
+            .filter(|w| !offline.contains(&normalize_address(&w.address)))
+            .map(|w| w.weight)
+            .collect();
+        let (tolerated_failures, _) = margins(&online, params.threshold_weight);
+        // Offline participants still hold their shares, so all of them count towards collusion
+        let all: Vec<usize> = params.weights.iter().map(|w| w.weight).collect();
+        let margin = SafetyMargin {
+            validator_set_epoch: current_set.is_some().then_some(params.current_epoch),
+            offline: offline.into_iter().filter(|a| participants.contains(a)).collect(),
+            not_dealt,
+            available_weight: online.iter().sum(),
+            tolerated_failures,
+            min_colluders: margins(&all, params.threshold_weight).1,
+        };
+        let stalled = margin.available_weight < params.threshold_weight;
+
+        if let OutputFormat::Json = format {
+            println!("{}", serde_json::to_string_pretty(&ParamsReport { params, margin })?);
+            return Ok(());
+        }
+
+        println!(
+            "DKG parameters (keys of epoch {}, dealt in epoch {}, read at block {})",
+            params.epoch, params.dealer_epoch, params.block_number
+        );
+        println!("  Curve:                 {}", params.curve);
+        println!(
+            "  Randomness:            {}",
+            if params.randomness_enabled { "enabled" } else { "disabled" }
+        );
+        println!("  Secrecy threshold:     {}", percent(params.secrecy_threshold));
+        println!("  Reconstruct threshold: {}", percent(params.reconstruct_threshold));
+        println!("  Fast path secrecy:     {}", percent(params.fast_path_secrecy_threshold));
+        println!("  Participants:          {}", params.participants);
+        println!(
+            "  Weights:               {} to reconstruct of {} dealt",
+            params.threshold_weight, params.total_weight
+        );
+        if let Some(fast) = params.fast_threshold_weight {
+            println!("  Fast path weights:     {fast} to reconstruct");
+        }
+        println!("  Transcript size:       {} bytes", params.transcript_bytes);
+        match params.in_progress_dealer_epoch {
+            Some(dealer_epoch) => {
+                println!("  In progress:           dealing in epoch {dealer_epoch}")
+            }
+            None => println!("  In progress:           none"),
+        }
+        println!();
+
+        match margin.validator_set_epoch {
+            Some(epoch) => println!("Safety margin (validator set of epoch {epoch})"),
+            None => println!("Safety margin"),
+        }
+        if !margin.offline.is_empty() {
+            println!("  Offline:               {}", margin.offline.join(", "));
+        }
+        if !margin.not_dealt.is_empty() {
+            println!("  Without a share:       {}", margin.not_dealt.join(", "));
+        }
+        println!(
+            "  Online weight:         {} of {} (threshold {})",
+            margin.available_weight, params.total_weight, params.threshold_weight
+        );
+        if stalled {
+            println!("  Tolerated failures:    none, randomness can't be reconstructed");
+        } else {
+            println!(
+                "  Tolerated failures:    {} more participant(s), largest first",
+                margin.tolerated_failures
+            );
+        }
+        match margin.min_colluders {
+            Some(colluders) => {
+                println!("  Colluders to break:    {colluders} participant(s), largest first")
+            }
+            None => println!("  Colluders to break:    -"),
+        }
+        if stalled {
+            return Err(anyhow::anyhow!(
+                "The online participants hold fewer shares than the reconstruction threshold"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn margins_count_largest_first() {
+        // 10 shares, 7 needed: dropping 4 leaves 6, dropping 2 and 1 leaves 7
+        assert_eq!(margins(&[4, 3, 2, 1], 7), (0, Some(2)));
+        assert_eq!(margins(&[1, 1, 1, 1, 1, 1], 4), (2, Some(4)));
+        assert_eq!(margins(&[2, 2], 5), (0, None));
+    }
+}
@@ -1,7 +1,12 @@
 use clap::Parser;
 
-use crate::command::Executable;
-use serde::Deserialize;
+use crate::{
+    command::Executable,
+    i18n::MissingOption,
+    output::{progress, OutputFormat},
+    util::{ErrorResponse, API_V1},
+};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Parser)]
 pub struct RandomnessCommand {
@@ -14,22 +19,17 @@ pub struct RandomnessCommand {
     pub block_number: u64,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct RandomnessResponse {
     block_number: u64,
     randomness: Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
-struct ErrorResponse {
-    error: String,
-}
-
 impl Executable for RandomnessCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         // Use tokio runtime to run async code
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
@@ -43,17 +43,15 @@ impl RandomnessCommand {
         }
     }
 
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
-        let server_url = self.server_url.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--server-url is required. Set via CLI flag, GRAVITY_SERVER_URL env var, or ~/.gravity/config.toml"
-            )
-        })?;
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let server_url = self
+            .server_url
+            .ok_or_else(|| MissingOption::new("server-url", "GRAVITY_SERVER_URL"))?;
 
         let base_url = Self::normalize_url(&server_url);
-        let url = format!("{}/dkg/randomness/{}", base_url, self.block_number);
+        let url = format!("{}{API_V1}/dkg/randomness/{}", base_url, self.block_number);
 
-        println!("Querying Randomness for block {} from: {}", self.block_number, url);
+        progress!(format, "Querying Randomness for block {} from: {}", self.block_number, url);
 
         let client = reqwest::Client::builder()
             .danger_accept_invalid_certs(true)
@@ -66,7 +64,7 @@ impl RandomnessCommand {
         if !status_code.is_success() {
             // Try to parse error message from response
             let error_msg = match response.json::<ErrorResponse>().await {
-                Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.error),
+                Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
                 Err(_) => format!("HTTP {status_code}"),
             };
             return Err(anyhow::anyhow!("Failed to get randomness: {error_msg}"));
@@ -76,7 +74,7 @@ impl RandomnessCommand {
 
         match &result.randomness {
             Some(hex) => {
-                println!("Block {}: {}", result.block_number, hex);
+                progress!(format, "Block {}: {}", result.block_number, hex);
             }
             None => {
                 return Err(anyhow::anyhow!(
@@ -86,6 +84,6 @@ impl RandomnessCommand {
             }
         }
 
-        Ok(())
+        format.emit(&result)
     }
 }
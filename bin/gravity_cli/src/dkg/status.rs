@@ -1,6 +1,11 @@
 use clap::Parser;
 
-use crate::{command::Executable, output::OutputFormat};
+use crate::{
+    command::Executable,
+    i18n::MissingOption,
+    output::OutputFormat,
+    util::{ErrorResponse, API_V1},
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Parser)]
@@ -8,10 +13,6 @@ pub struct StatusCommand {
     /// Server address and port (e.g., 127.0.0.1:1024)
     #[clap(long, env = "GRAVITY_SERVER_URL")]
     pub server_url: Option<String>,
-
-    /// Output format
-    #[clap(skip)]
-    pub output_format: OutputFormat,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -22,16 +23,11 @@ pub struct DKGStatusResponse {
     participating_nodes: usize,
 }
 
-#[derive(Deserialize, Debug)]
-struct ErrorResponse {
-    error: String,
-}
-
 impl Executable for StatusCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         // Use tokio runtime to run async code
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
@@ -45,15 +41,13 @@ impl StatusCommand {
         }
     }
 
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
-        let server_url = self.server_url.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--server-url is required. Set via CLI flag, GRAVITY_SERVER_URL env var, or ~/.gravity/config.toml"
-            )
-        })?;
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let server_url = self
+            .server_url
+            .ok_or_else(|| MissingOption::new("server-url", "GRAVITY_SERVER_URL"))?;
 
         let base_url = Self::normalize_url(&server_url);
-        let url = format!("{base_url}/dkg/status");
+        let url = format!("{base_url}{API_V1}/dkg/status");
 
         println!("Fetching DKG status from: {url}");
 
@@ -68,7 +62,7 @@ impl StatusCommand {
         if !status_code.is_success() {
             // Try to parse error message from response
             let error_msg = match response.json::<ErrorResponse>().await {
-                Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.error),
+                Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
                 Err(_) => format!("HTTP {status_code}"),
             };
             return Err(anyhow::anyhow!("Failed to get DKG status: {error_msg}"));
@@ -77,7 +71,7 @@ impl StatusCommand {
         let status: DKGStatusResponse = response.json().await?;
 
         // Display status
-        match self.output_format {
+        match format {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(&status)?);
             }
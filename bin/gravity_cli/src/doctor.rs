@@ -4,7 +4,7 @@ use colored::Colorize;
 use serde::Serialize;
 use std::{net::TcpListener, path::PathBuf, time::Duration};
 
-use crate::{command::Executable, config::GravityConfig, output::OutputFormat};
+use crate::{command::Executable, config::GravityConfig, output::OutputFormat, util::API_V1};
 
 #[derive(Debug, Parser)]
 pub struct DoctorCommand {
@@ -23,10 +23,6 @@ pub struct DoctorCommand {
     /// Skip port-conflict checks (TCP bind probes)
     #[clap(long)]
     pub skip_ports: bool,
-
-    /// Output format
-    #[clap(skip)]
-    pub output_format: OutputFormat,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -141,14 +137,14 @@ impl DoctorCommand {
 }
 
 impl Executable for DoctorCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
 impl DoctorCommand {
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let resolved = self.resolve();
         let mut checks = Vec::new();
 
@@ -166,7 +162,7 @@ impl DoctorCommand {
         let summary = Summary::from(&checks);
         let report = DoctorReport { checks, summary };
 
-        match self.output_format {
+        match format {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(&report)?);
             }
@@ -327,10 +323,10 @@ async fn check_server(r: &Resolved) -> CheckResult {
             );
         }
     };
-    let endpoint = format!("{base}/dkg/status");
+    let endpoint = format!("{base}{API_V1}/dkg/status");
     match client.get(&endpoint).send().await {
         Ok(resp) if resp.status().is_success() => {
-            CheckResult::ok("consensus-server", format!("{base} reachable (/dkg/status 200)"))
+            CheckResult::ok("consensus-server", format!("{base} reachable ({API_V1}/dkg/status 200)"))
         }
         Ok(resp) => CheckResult::warn(
             "consensus-server",
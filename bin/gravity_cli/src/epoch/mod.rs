@@ -1,6 +1,6 @@
 use clap::Parser;
 
-use crate::command::Executable;
+use crate::{command::Executable, output::OutputFormat};
 
 pub mod status;
 
@@ -16,9 +16,9 @@ pub enum SubCommands {
 }
 
 impl Executable for EpochCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         match self.command {
-            SubCommands::Status(status_cmd) => status_cmd.execute(),
+            SubCommands::Status(status_cmd) => status_cmd.execute(format),
         }
     }
 }
@@ -7,7 +7,8 @@ use serde::Serialize;
 
 use crate::{
     command::Executable,
-    contract::{EpochConfig, Reconfiguration, EPOCH_CONFIG_ADDRESS, RECONFIGURATION_ADDRESS},
+    contract::{epoch_config_address, reconfiguration_address, EpochConfig, Reconfiguration},
+    i18n::MissingOption,
     output::OutputFormat,
 };
 
@@ -25,26 +26,19 @@ pub struct StatusCommand {
     /// RPC URL for gravity node
     #[clap(long, env = "GRAVITY_RPC_URL")]
     pub rpc_url: Option<String>,
-
-    /// Output format
-    #[clap(skip)]
-    pub output_format: OutputFormat,
 }
 
 impl Executable for StatusCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
 impl StatusCommand {
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
-        let rpc_url = self.rpc_url.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--rpc-url is required. Set via CLI flag, GRAVITY_RPC_URL env var, or ~/.gravity/config.toml"
-            )
-        })?;
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
 
         // Initialize Provider
         let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
@@ -54,7 +48,7 @@ impl StatusCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(RECONFIGURATION_ADDRESS)),
+                to: Some(TxKind::Call(reconfiguration_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -67,7 +61,7 @@ impl StatusCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(RECONFIGURATION_ADDRESS)),
+                to: Some(TxKind::Call(reconfiguration_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -81,7 +75,7 @@ impl StatusCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(EPOCH_CONFIG_ADDRESS)),
+                to: Some(TxKind::Call(epoch_config_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -122,7 +116,7 @@ impl StatusCommand {
             (None, Some(running_secs - expected_duration_secs))
         };
 
-        match self.output_format {
+        match format {
             OutputFormat::Json => {
                 let info = EpochStatusInfo {
                     current_epoch,
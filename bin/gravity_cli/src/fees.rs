@@ -0,0 +1,200 @@
+//! Shared gas and fee options for gravity_cli subcommands that submit on-chain transactions.
+//!
+//! Transactions are EIP-1559 by default. Fees that are not given on the command line, through
+//! the environment or in the config profile are estimated from `eth_feeHistory`: the priority
+//! fee is the median of the recent blocks' median tips, and the max fee leaves room for the
+//! base fee to double before the transaction stops being includable. `--gas-price` sends a
+//! legacy transaction instead, as does a chain that reports no base fee.
+//!
+//! Add to a subcommand by flattening, next to the signer:
+//!
+//! ```ignore
+//! #[clap(flatten)]
+//! pub fees: crate::fees::FeeArgs,
+//! ```
+//!
+//! Then resolve the fees once the provider exists, and apply them to each transaction:
+//!
+//! ```ignore
+//! let fees = self.fees.resolve(&provider).await?;
+//! provider.send_transaction(fees.apply(TransactionRequest { .. })).await?;
+//! ```
+
+use alloy_provider::Provider;
+use alloy_rpc_types::eth::{BlockNumberOrTag, TransactionRequest};
+use clap::Args;
+
+use crate::{config::ProfileConfig, util::format_gwei};
+
+const DEFAULT_GAS_LIMIT: u64 = 2_000_000;
+/// Blocks of fee history the estimate looks at.
+const FEE_HISTORY_BLOCKS: u64 = 10;
+/// Percentile of each block's tips taken as its typical tip.
+const REWARD_PERCENTILE: f64 = 50.0;
+
+/// CLI arguments setting the gas limit and fees of an on-chain command.
+#[derive(Debug, Clone, Default, Args)]
+pub struct FeeArgs {
+    /// Gas limit for each transaction
+    #[clap(long, env = "GRAVITY_GAS_LIMIT")]
+    pub gas_limit: Option<u64>,
+
+    /// Send legacy transactions at this gas price in wei instead of EIP-1559 ones; ignored
+    /// when an EIP-1559 fee is given
+    #[clap(long, env = "GRAVITY_GAS_PRICE")]
+    pub gas_price: Option<u128>,
+
+    /// Most the transaction may pay per gas in wei, base fee included; estimated from
+    /// eth_feeHistory when omitted
+    #[clap(long, env = "GRAVITY_MAX_FEE_PER_GAS")]
+    pub max_fee_per_gas: Option<u128>,
+
+    /// Tip per gas in wei for the block proposer; estimated from eth_feeHistory when omitted
+    #[clap(long, env = "GRAVITY_MAX_PRIORITY_FEE_PER_GAS")]
+    pub max_priority_fee_per_gas: Option<u128>,
+}
+
+/// Fees of the transactions a command sends, from [`FeeArgs::resolve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fees {
+    pub gas_limit: u64,
+    pub kind: FeeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeKind {
+    Legacy { gas_price: u128 },
+    Eip1559 { max_fee_per_gas: u128, max_priority_fee_per_gas: u128 },
+}
+
+impl FeeArgs {
+    /// Fills the options still unset after CLI and environment parsing from the config profile.
+    pub fn apply_profile(&mut self, profile: &ProfileConfig) {
+        self.gas_limit = self.gas_limit.or(profile.gas_limit);
+        // A fee given on the command line or in the environment decides the transaction type;
+        // the profile only fills in what is left.
+        if self.max_fee_per_gas.is_none() && self.max_priority_fee_per_gas.is_none() {
+            self.gas_price = self.gas_price.or(profile.gas_price);
+        }
+        if self.gas_price.is_none() {
+            self.max_fee_per_gas = self.max_fee_per_gas.or(profile.max_fee_per_gas);
+            self.max_priority_fee_per_gas =
+                self.max_priority_fee_per_gas.or(profile.max_priority_fee_per_gas);
+        }
+    }
+
+    /// Settles the fees, estimating from the chain's fee history what wasn't given.
+    pub async fn resolve<P: Provider>(&self, provider: &P) -> Result<Fees, anyhow::Error> {
+        let gas_limit = self.gas_limit.unwrap_or(DEFAULT_GAS_LIMIT);
+        if let (Some(gas_price), None, None) =
+            (self.gas_price, self.max_fee_per_gas, self.max_priority_fee_per_gas)
+        {
+            return Ok(Fees { gas_limit, kind: FeeKind::Legacy { gas_price } });
+        }
+        if let (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) =
+            (self.max_fee_per_gas, self.max_priority_fee_per_gas)
+        {
+            check_fee_cap(max_fee_per_gas, max_priority_fee_per_gas)?;
+            return Ok(Fees {
+                gas_limit,
+                kind: FeeKind::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas },
+            });
+        }
+
+        let history = provider
+            .get_fee_history(FEE_HISTORY_BLOCKS, BlockNumberOrTag::Latest, &[REWARD_PERCENTILE])
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch fee history: {e}"))?;
+        let Some(next_base_fee) = history.base_fee_per_gas.last().copied().filter(|fee| *fee > 0)
+        else {
+            // No base fee: the chain predates London, so only legacy transactions fit.
+            let gas_price = provider.get_gas_price().await?;
+            return Ok(Fees { gas_limit, kind: FeeKind::Legacy { gas_price } });
+        };
+        let tips: Vec<u128> = history
+            .reward
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|rewards| rewards.first().copied())
+            .collect();
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            estimate(next_base_fee, &tips, self.max_fee_per_gas, self.max_priority_fee_per_gas);
+        check_fee_cap(max_fee_per_gas, max_priority_fee_per_gas)?;
+        Ok(Fees { gas_limit, kind: FeeKind::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } })
+    }
+}
+
+fn check_fee_cap(max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> anyhow::Result<()> {
+    if max_priority_fee_per_gas > max_fee_per_gas {
+        return Err(anyhow::anyhow!(
+            "--max-priority-fee-per-gas ({max_priority_fee_per_gas}) exceeds --max-fee-per-gas \
+             ({max_fee_per_gas})"
+        ));
+    }
+    Ok(())
+}
+
+/// EIP-1559 fees from the next block's base fee and recent tips, keeping those the user set.
+/// The tip is the median of `tips`; the max fee covers twice the base fee, i.e. six full blocks
+/// in a row, plus the tip. A user-set max fee caps the estimated tip.
+fn estimate(
+    next_base_fee: u128,
+    tips: &[u128],
+    max_fee_per_gas: Option<u128>,
+    max_priority_fee_per_gas: Option<u128>,
+) -> (u128, u128) {
+    let priority_fee = max_priority_fee_per_gas.unwrap_or_else(|| {
+        let mut tips = tips.to_vec();
+        tips.sort_unstable();
+        let median = tips.get(tips.len() / 2).copied().unwrap_or_default();
+        max_fee_per_gas.map_or(median, |max_fee| median.min(max_fee))
+    });
+    let max_fee = max_fee_per_gas
+        .unwrap_or_else(|| next_base_fee.saturating_mul(2).saturating_add(priority_fee));
+    (max_fee, priority_fee)
+}
+
+impl Fees {
+    /// Sets the gas limit and fees of `tx`.
+    pub fn apply(&self, tx: TransactionRequest) -> TransactionRequest {
+        let tx = TransactionRequest { gas: Some(self.gas_limit), ..tx };
+        match self.kind {
+            FeeKind::Legacy { gas_price } => {
+                TransactionRequest { gas_price: Some(gas_price), ..tx }
+            }
+            FeeKind::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => TransactionRequest {
+                max_fee_per_gas: Some(max_fee_per_gas),
+                max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+                ..tx
+            },
+        }
+    }
+
+    /// One line for the progress output, e.g. `EIP-1559, max fee 2.1 gwei, priority fee 1 gwei`.
+    pub fn describe(&self) -> String {
+        match self.kind {
+            FeeKind::Legacy { gas_price } => {
+                format!("legacy, gas price {} gwei", format_gwei(gas_price))
+            }
+            FeeKind::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => format!(
+                "EIP-1559, max fee {} gwei, priority fee {} gwei",
+                format_gwei(max_fee_per_gas),
+                format_gwei(max_priority_fee_per_gas)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_from_base_fee_and_median_tip() {
+        assert_eq!(estimate(100, &[5, 1, 3], None, None), (203, 3));
+        assert_eq!(estimate(100, &[], None, None), (200, 0));
+        // User-set values are kept, and a max fee caps the estimated tip
+        assert_eq!(estimate(100, &[5, 1, 3], None, Some(7)), (207, 7));
+        assert_eq!(estimate(100, &[50], Some(20), None), (20, 20));
+    }
+}
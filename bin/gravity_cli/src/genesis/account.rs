@@ -6,12 +6,14 @@ use sha3::{Digest, Keccak256};
 
 use std::{fs, path::PathBuf};
 
-use crate::command::Executable;
+use crate::{command::Executable, genesis::deterministic, output::OutputFormat};
 
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 struct Account {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    insecure: Option<&'static str>,
     private_key: String,
     public_key: String,
     address: String,
@@ -22,12 +24,25 @@ pub struct GenerateAccount {
     /// Output file path
     #[clap(long, value_parser)]
     pub output_file: PathBuf,
+    /// Devnet master seed (64 hex characters). The account key is derived from this value and
+    /// `--account-index` instead of the OS random generator. Output is marked `insecure`.
+    #[clap(long)]
+    pub deterministic_seed: Option<String>,
+    /// Index of the account derived from `--deterministic-seed`.
+    #[clap(long, default_value_t = 0, requires = "deterministic_seed")]
+    pub account_index: u32,
 }
 
-fn generate_eth_account() -> Result<(String, String, String), anyhow::Error> {
+fn generate_eth_account(
+    deterministic_seed: Option<[u8; 32]>,
+) -> Result<(String, String, String), anyhow::Error> {
     // 1. Generate private key
     // SigningKey::random(&mut OsRng) uses the OS random generator to create a new private key.
-    let signing_key = SigningKey::random(&mut OsRng);
+    // A deterministic seed is used directly as the scalar; it is rejected if out of range.
+    let signing_key = match deterministic_seed {
+        Some(seed) => SigningKey::from_slice(&seed)?,
+        None => SigningKey::random(&mut OsRng),
+    };
     let private_key_bytes = signing_key.to_bytes();
     let private_key_hex = hex::encode(private_key_bytes);
 
@@ -58,9 +73,18 @@ fn generate_eth_account() -> Result<(String, String, String), anyhow::Error> {
 }
 
 impl Executable for GenerateAccount {
-    fn execute(self) -> Result<(), anyhow::Error> {
-        let (private_key, public_key, address) = generate_eth_account()?;
-        let account = Account { private_key, public_key, address };
+    fn execute(self, _: OutputFormat) -> Result<(), anyhow::Error> {
+        let seed = match self.deterministic_seed.as_deref() {
+            Some(seed) => {
+                deterministic::warn_insecure();
+                let master = deterministic::parse_seed(seed)?;
+                Some(deterministic::derive_seed(&master, "account", self.account_index))
+            }
+            None => None,
+        };
+        let insecure = seed.map(|_| deterministic::INSECURE_MARKER);
+        let (private_key, public_key, address) = generate_eth_account(seed)?;
+        let account = Account { insecure, private_key, public_key, address };
         let yaml_string = serde_yaml::to_string(&account)?;
         fs::write(self.output_file, yaml_string)?;
         Ok(())
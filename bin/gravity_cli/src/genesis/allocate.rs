@@ -0,0 +1,212 @@
+use alloy_primitives::{Address, U256};
+use clap::Parser;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::{collections::BTreeMap, fs, path::PathBuf, str::FromStr};
+
+use crate::{
+    command::Executable,
+    output::{progress, OutputFormat},
+};
+
+/// Adds account balances to the `alloc` section of a genesis template, so devnet and testnet
+/// accounts (faucets, team wallets, load-test accounts) are funded from block 0.
+#[derive(Debug, Parser)]
+pub struct Allocate {
+    /// Genesis template JSON with an `alloc` section
+    #[clap(long, value_parser)]
+    pub template: PathBuf,
+
+    /// Output file path; defaults to rewriting the template in place
+    #[clap(long, value_parser)]
+    pub output_file: Option<PathBuf>,
+
+    /// Allocations file (may be repeated): a JSON object mapping address to balance (or to
+    /// `{"balance": ...}`), or a CSV of `address,balance` lines. Balances are in wei, decimal or
+    /// 0x-prefixed hex.
+    #[clap(long, value_parser)]
+    pub allocations_file: Vec<PathBuf>,
+
+    /// Faucet account funded at genesis
+    #[clap(long, requires = "faucet_balance")]
+    pub faucet_address: Option<String>,
+
+    /// Faucet balance in wei, decimal or 0x-prefixed hex
+    #[clap(long, requires = "faucet_address")]
+    pub faucet_balance: Option<String>,
+}
+
+/// Result of `genesis allocate` with `--output json`.
+#[derive(Debug, Serialize)]
+struct AllocateResult {
+    accounts: usize,
+    /// Total allocated balance in wei.
+    total: String,
+    output_file: PathBuf,
+}
+
+fn parse_balance(balance: &str, context: &str) -> Result<U256, anyhow::Error> {
+    U256::from_str(balance.trim())
+        .map_err(|e| anyhow::anyhow!("Invalid balance '{balance}' for {context}: {e}"))
+}
+
+fn parse_address(address: &str, context: &str) -> Result<Address, anyhow::Error> {
+    Address::from_str(address.trim())
+        .map_err(|e| anyhow::anyhow!("Invalid address '{address}' in {context}: {e}"))
+}
+
+/// Parses an allocations file into (address, balance) pairs, in file order.
+fn parse_allocations(content: &str, source: &str) -> Result<Vec<(Address, U256)>, anyhow::Error> {
+    if content.trim_start().starts_with('{') {
+        let entries: Map<String, Value> = serde_json::from_str(content)
+            .map_err(|e| anyhow::anyhow!("Invalid JSON in {source}: {e}"))?;
+        return entries
+            .iter()
+            .map(|(address, value)| {
+                let balance = match value {
+                    Value::String(balance) => balance.as_str(),
+                    Value::Object(account) => {
+                        account.get("balance").and_then(Value::as_str).ok_or_else(|| {
+                            anyhow::anyhow!("Missing balance for {address} in {source}")
+                        })?
+                    }
+                    _ => anyhow::bail!("Balance of {address} in {source} must be a string"),
+                };
+                Ok((parse_address(address, source)?, parse_balance(balance, address)?))
+            })
+            .collect();
+    }
+
+    let mut allocations = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let context = format!("{source} line {}", index + 1);
+        let (address, balance) = line
+            .split_once(',')
+            .ok_or_else(|| anyhow::anyhow!("Expected 'address,balance' at {context}"))?;
+        // Tolerate a header row
+        if index == 0 && address.trim().eq_ignore_ascii_case("address") {
+            continue;
+        }
+        allocations.push((parse_address(address, &context)?, parse_balance(balance, &context)?));
+    }
+    Ok(allocations)
+}
+
+/// Adds `allocations` to the template's `alloc` section. An address may only be allocated once,
+/// and never on top of an account the template already defines (e.g. a system contract).
+fn merge_allocations(
+    template: &mut Value,
+    allocations: &[(Address, U256, String)],
+) -> Result<(), anyhow::Error> {
+    let alloc = template
+        .get_mut("alloc")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| anyhow::anyhow!("Genesis template has no 'alloc' object"))?;
+
+    let existing = alloc
+        .keys()
+        .filter_map(|key| Address::from_str(key).ok())
+        .collect::<std::collections::BTreeSet<_>>();
+    let mut seen: BTreeMap<Address, &str> = BTreeMap::new();
+    for (address, _, source) in allocations {
+        if existing.contains(address) {
+            anyhow::bail!("{address} from {source} is already defined in the genesis template");
+        }
+        if let Some(previous) = seen.insert(*address, source) {
+            anyhow::bail!("{address} is allocated twice ({previous} and {source})");
+        }
+    }
+
+    for (address, balance, _) in allocations {
+        alloc.insert(format!("{address:#x}"), json!({ "balance": format!("{balance:#x}") }));
+    }
+    Ok(())
+}
+
+impl Executable for Allocate {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        progress!(format, "--- Genesis Allocate Start ---");
+        let mut allocations = Vec::new();
+        for path in &self.allocations_file {
+            let source = path.display().to_string();
+            let content = fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Failed to read {source}: {e}"))?;
+            let parsed = parse_allocations(&content, &source)?;
+            progress!(format, "Read {} allocation(s) from {source}", parsed.len());
+            allocations.extend(parsed.into_iter().map(|(a, b)| (a, b, source.clone())));
+        }
+        if let (Some(address), Some(balance)) = (&self.faucet_address, &self.faucet_balance) {
+            let address = parse_address(address, "--faucet-address")?;
+            let balance = parse_balance(balance, "--faucet-address")?;
+            progress!(format, "Faucet account: {address} ({balance} wei)");
+            allocations.push((address, balance, "--faucet-address".to_string()));
+        }
+        if allocations.is_empty() {
+            anyhow::bail!("Nothing to allocate: pass --allocations-file or --faucet-address");
+        }
+
+        let content = fs::read_to_string(&self.template)?;
+        let mut template: Value = serde_json::from_str(&content)?;
+        merge_allocations(&mut template, &allocations)?;
+
+        let total = allocations
+            .iter()
+            .try_fold(U256::ZERO, |sum, (_, balance, _)| sum.checked_add(*balance))
+            .ok_or_else(|| anyhow::anyhow!("Total allocated balance overflows U256"))?;
+        let output = self.output_file.as_ref().unwrap_or(&self.template);
+        fs::write(output, serde_json::to_string_pretty(&template)?)?;
+        progress!(format, "Allocated {} account(s), {total} wei in total", allocations.len());
+        progress!(format, "Genesis template written to: {output:?}");
+        progress!(format, "--- Genesis Allocate Success ---");
+        format.emit(&AllocateResult {
+            accounts: allocations.len(),
+            total: total.to_string(),
+            output_file: output.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const B: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    #[test]
+    fn parses_json_and_csv() {
+        let json = format!(r#"{{"{A}": "1000", "{B}": {{"balance": "0x10"}}}}"#);
+        let parsed = parse_allocations(&json, "json").unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed.contains(&(Address::from_str(B).unwrap(), U256::from(16))));
+
+        let csv = format!("address,balance\n# team\n{A},1000\n\n{B}, 0x10\n");
+        let parsed = parse_allocations(&csv, "csv").unwrap();
+        assert_eq!(parsed[0], (Address::from_str(A).unwrap(), U256::from(1000)));
+        assert_eq!(parsed[1].1, U256::from(16));
+
+        assert!(parse_allocations(&format!("{A},ten"), "csv").is_err());
+    }
+
+    #[test]
+    fn rejects_duplicates_and_template_accounts() {
+        let mut template =
+            json!({ "alloc": { (A.to_lowercase()): { "balance": "0x00", "code": "0x60" } } });
+        let b = Address::from_str(B).unwrap();
+        let a = Address::from_str(A).unwrap();
+
+        assert!(merge_allocations(&mut template, &[(a, U256::from(1), "x".into())]).is_err());
+        assert!(merge_allocations(
+            &mut template,
+            &[(b, U256::from(1), "x".into()), (b, U256::from(2), "y".into())]
+        )
+        .is_err());
+
+        merge_allocations(&mut template, &[(b, U256::from(255), "x".into())]).unwrap();
+        assert_eq!(template["alloc"][format!("{b:#x}")]["balance"], "0xff");
+    }
+}
@@ -0,0 +1,62 @@
+//! Deterministic key derivation for local devnets.
+//!
+//! A single `--deterministic-seed` is expanded into independent per-key seeds
+//! so that every validator identity and genesis account of a devnet can be
+//! regenerated bit-for-bit on any machine. Anything derived this way is
+//! trivially reproducible by whoever knows the seed and MUST NOT be used for
+//! a network holding real value.
+
+use sha3::{Digest, Sha3_256};
+
+/// Domain separator mixed into every derived seed.
+const DOMAIN: &[u8] = b"GRAVITY::DEVNET::DETERMINISTIC_SEED";
+
+/// Marker written into genesis artifacts produced from a deterministic seed.
+pub const INSECURE_MARKER: &str =
+    "INSECURE: derived from --deterministic-seed; for local devnets and tests only";
+
+/// Parses a 32-byte hex seed, with or without `0x` prefix.
+pub fn parse_seed(seed: &str) -> Result<[u8; 32], anyhow::Error> {
+    let seed = seed.strip_prefix("0x").unwrap_or(seed);
+    let mut out = [0u8; 32];
+    hex::decode_to_slice(seed, &mut out)
+        .map_err(|e| anyhow::anyhow!("--deterministic-seed must be 64 hex characters: {e}"))?;
+    Ok(out)
+}
+
+/// Derives the seed for the `index`-th key of kind `label` (e.g. "validator", "account").
+pub fn derive_seed(master: &[u8; 32], label: &str, index: u32) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(DOMAIN);
+    hasher.update(master);
+    hasher.update((label.len() as u32).to_be_bytes());
+    hasher.update(label.as_bytes());
+    hasher.update(index.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Prints a loud warning to stderr so the seed cannot be used by accident.
+pub fn warn_insecure() {
+    eprintln!("WARNING: keys are derived from --deterministic-seed and are NOT secret.");
+    eprintln!("WARNING: only use the generated files for local devnets and CI fixtures.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derivation_is_stable_and_separated() {
+        let master = parse_seed(&"11".repeat(32)).unwrap();
+        assert_eq!(derive_seed(&master, "validator", 0), derive_seed(&master, "validator", 0));
+        assert_ne!(derive_seed(&master, "validator", 0), derive_seed(&master, "validator", 1));
+        assert_ne!(derive_seed(&master, "validator", 0), derive_seed(&master, "account", 0));
+    }
+
+    #[test]
+    fn parse_seed_accepts_prefix_and_rejects_short_input() {
+        let with_prefix = format!("0x{}", "ab".repeat(32));
+        assert_eq!(parse_seed(&with_prefix).unwrap(), [0xab; 32]);
+        assert!(parse_seed("abcd").is_err());
+    }
+}
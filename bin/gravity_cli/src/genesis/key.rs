@@ -5,12 +5,18 @@ use gaptos::{
 };
 use std::{fs, path::PathBuf};
 
-use crate::{command::Executable, genesis::secret_manager};
+use crate::{
+    command::Executable,
+    genesis::{deterministic, secret_manager},
+    output::{progress, OutputFormat},
+};
 
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 struct ValidatorIndentity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    insecure: Option<&'static str>,
     account_address: String,
     account_private_key: String,
     consensus_private_key: String,
@@ -27,6 +33,8 @@ struct ValidatorIndentity {
 /// require touching the full IdentityBlob ever.
 #[derive(Debug, Serialize)]
 struct ValidatorPublicMaterial {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    insecure: Option<&'static str>,
     account_address: String,
     consensus_public_key: String,
     consensus_pop: String,
@@ -41,8 +49,16 @@ pub struct GenerateKey {
     /// If a predictable random seed is used, the key that is produced will be insecure and easy
     /// to reproduce.  Please do not use this unless sufficient randomness is put into the random
     /// seed.
-    #[clap(long)]
+    #[clap(long, conflicts_with = "deterministic_seed")]
     random_seed: Option<String>,
+    /// Devnet master seed (64 hex characters). The key seed is derived from this value and
+    /// `--validator-index`, so a whole validator set can be regenerated identically across
+    /// machines and CI runs. Output files are marked `insecure`.
+    #[clap(long)]
+    deterministic_seed: Option<String>,
+    /// Index of the validator derived from `--deterministic-seed`.
+    #[clap(long, default_value_t = 0, requires = "deterministic_seed")]
+    validator_index: u32,
     /// Output file path. Mutually exclusive with --secret.
     #[clap(long, value_parser, conflicts_with = "secret")]
     pub output_file: Option<PathBuf>,
@@ -64,10 +80,28 @@ pub struct GenerateKey {
     pub public_output_file: Option<PathBuf>,
 }
 
+/// Result of `genesis generate-key` with `--output json`; never carries the private keys.
+#[derive(Debug, Serialize)]
+struct GenerateKeyResult {
+    #[serde(flatten)]
+    public: ValidatorPublicMaterial,
+    output_file: Option<PathBuf>,
+    /// Secret Manager version the identity was uploaded as.
+    secret_version: Option<String>,
+    public_output_file: Option<PathBuf>,
+}
+
 impl GenerateKey {
     /// Returns a key generator with the seed if given
     pub fn key_generator(&self) -> Result<KeyGen, anyhow::Error> {
-        if let Some(ref seed) = self.random_seed {
+        if let Some(ref seed) = self.deterministic_seed {
+            let master = deterministic::parse_seed(seed)?;
+            Ok(KeyGen::from_seed(deterministic::derive_seed(
+                &master,
+                "validator",
+                self.validator_index,
+            )))
+        } else if let Some(ref seed) = self.random_seed {
             // Strip 0x
             let seed = seed.strip_prefix("0x").unwrap_or(seed);
             let mut seed_slice = [0u8; 32];
@@ -82,16 +116,20 @@ impl GenerateKey {
 
 // TODO(gravity_lightman): account_private_key is aptos key， not reth
 impl Executable for GenerateKey {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         if self.output_file.is_none() && self.secret.is_none() {
             anyhow::bail!("must specify either --output-file <path> or --secret <resource>");
         }
 
-        println!("--- Generate Key Start ---");
+        progress!(format, "--- Generate Key Start ---");
+        let insecure = self.deterministic_seed.as_ref().map(|_| deterministic::INSECURE_MARKER);
+        if insecure.is_some() {
+            deterministic::warn_insecure();
+        }
         let mut key_gen = self.key_generator()?;
         let network_private_key = key_gen.generate_x25519_private_key()?;
         let consensus_private_key = key_gen.generate_bls12381_private_key();
-        println!("The consensus_public_key is {:?}", consensus_private_key.public_key());
+        progress!(format, "The consensus_public_key is {:?}", consensus_private_key.public_key());
 
         let account_private_key = key_gen.generate_ed25519_private_key();
 
@@ -109,8 +147,9 @@ impl Executable for GenerateKey {
             hasher.finalize(&mut output);
             hex::encode(output)
         };
-        println!("The account_address is {account_address}");
-        println!(
+        progress!(format, "The account_address is {account_address}");
+        progress!(
+            format,
             "The last 20bit account_address (ETH format) is 0x{}",
             &account_address[24..] // Last 20 bytes = 40 hex chars = offset 24
         );
@@ -121,6 +160,7 @@ impl Executable for GenerateKey {
         let consensus_public_key_hex = hex::encode(consensus_private_key.public_key().to_bytes());
         let network_public_key_hex = hex::encode(network_private_key.public_key().to_bytes());
         let indentity = ValidatorIndentity {
+            insecure,
             account_address: account_address.clone(),
             account_private_key: hex::encode(account_private_key.to_bytes()),
             consensus_private_key: hex::encode(consensus_private_key.to_bytes()),
@@ -132,39 +172,47 @@ impl Executable for GenerateKey {
 
         let yaml_string = serde_yaml::to_string(&indentity)?;
 
+        let mut secret_version = None;
         if let Some(path) = self.output_file.as_ref() {
-            println!("--- Write Output File ---");
+            progress!(format, "--- Write Output File ---");
             fs::write(path, &yaml_string)?;
         } else if let Some(resource) = self.secret.as_ref() {
-            println!("--- Push to GCP Secret Manager ---");
+            progress!(format, "--- Push to GCP Secret Manager ---");
             let version = secret_manager::push_secret(resource, yaml_string.as_bytes())?;
             // Drop the YAML and private-key fields ASAP. The struct itself
             // is not zeroized — that would require swapping in a
             // Zeroizing<String> wrapper — but at least the local copies of
             // the serialized form go out of scope here.
             drop(yaml_string);
-            println!("Uploaded as {version}");
-            println!();
-            println!("Public material (safe to share, e.g. for staking registration):");
-            println!("  account_address:      {account_address}");
-            println!("  consensus_public_key: {consensus_public_key_hex}");
-            println!("  consensus_pop:        {consensus_pop_hex}");
-            println!("  network_public_key:   {network_public_key_hex}");
+            progress!(format, "Uploaded as {version}");
+            progress!(format);
+            progress!(format, "Public material (safe to share, e.g. for staking registration):");
+            progress!(format, "  account_address:      {account_address}");
+            progress!(format, "  consensus_public_key: {consensus_public_key_hex}");
+            progress!(format, "  consensus_pop:        {consensus_pop_hex}");
+            progress!(format, "  network_public_key:   {network_public_key_hex}");
+            secret_version = Some(version);
         }
 
+        let public = ValidatorPublicMaterial {
+            insecure,
+            account_address,
+            consensus_public_key: consensus_public_key_hex,
+            consensus_pop: consensus_pop_hex,
+            network_public_key: network_public_key_hex,
+        };
         if let Some(public_path) = self.public_output_file.as_ref() {
-            let public = ValidatorPublicMaterial {
-                account_address: account_address.clone(),
-                consensus_public_key: consensus_public_key_hex.clone(),
-                consensus_pop: consensus_pop_hex.clone(),
-                network_public_key: network_public_key_hex.clone(),
-            };
             let public_yaml = serde_yaml::to_string(&public)?;
             fs::write(public_path, public_yaml)?;
-            println!("Wrote public sidecar: {}", public_path.display());
+            progress!(format, "Wrote public sidecar: {}", public_path.display());
         }
 
-        println!("--- Generate Key Success ---");
-        Ok(())
+        progress!(format, "--- Generate Key Success ---");
+        format.emit(&GenerateKeyResult {
+            public,
+            output_file: self.output_file,
+            secret_version,
+            public_output_file: self.public_output_file,
+        })
     }
 }
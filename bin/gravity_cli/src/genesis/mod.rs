@@ -1,11 +1,19 @@
 mod account;
+mod allocate;
+mod deterministic;
 mod key;
 mod secret_manager;
 mod waypoint;
 
 use clap::{Parser, Subcommand};
 
-use crate::genesis::{account::GenerateAccount, key::GenerateKey, waypoint::GenerateWaypoint};
+use crate::{
+    command::Executable,
+    genesis::{
+        account::GenerateAccount, allocate::Allocate, key::GenerateKey, waypoint::GenerateWaypoint,
+    },
+    output::OutputFormat,
+};
 
 #[derive(Debug, Parser)]
 pub struct GenesisCommand {
@@ -18,4 +26,17 @@ pub enum SubCommands {
     GenerateKey(GenerateKey),
     GenerateWaypoint(GenerateWaypoint),
     GenerateAccount(GenerateAccount),
+    /// Fund accounts (faucet, allocations file) in the genesis template
+    Allocate(Allocate),
+}
+
+impl Executable for GenesisCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::GenerateKey(gck) => gck.execute(format),
+            SubCommands::GenerateWaypoint(gw) => gw.execute(format),
+            SubCommands::GenerateAccount(generate_account) => generate_account.execute(format),
+            SubCommands::Allocate(allocate) => allocate.execute(format),
+        }
+    }
 }
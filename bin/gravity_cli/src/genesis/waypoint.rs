@@ -8,10 +8,14 @@ use gaptos::{
         validator_info::ValidatorInfo, waypoint::Waypoint,
     },
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{fs, path::PathBuf};
 
-use crate::command::Executable;
+use crate::{
+    command::Executable,
+    genesis::deterministic,
+    output::{progress, OutputFormat},
+};
 
 /// Validator entry matching the InitialValidator struct from genesis-tool
 #[derive(Debug, Deserialize)]
@@ -29,6 +33,9 @@ struct ValidatorEntry {
 /// GenesisConfig matching the format used by genesis-tool
 #[derive(Debug, Deserialize)]
 struct GenesisConfig {
+    /// Set by `aggregate_genesis.py` when a validator identity came from `--deterministic-seed`.
+    #[serde(default)]
+    insecure: Option<String>,
     validators: Vec<ValidatorEntry>,
 }
 
@@ -41,6 +48,18 @@ pub struct GenerateWaypoint {
     /// Output waypoint file path
     #[clap(long, value_parser)]
     pub output_file: PathBuf,
+
+    /// Write the waypoint even though the genesis config is marked `insecure` (its validator keys
+    /// were derived from `--deterministic-seed`). Only for local devnets and tests.
+    #[clap(long)]
+    pub insecure: bool,
+}
+
+/// Result of `genesis generate-waypoint` with `--output json`.
+#[derive(Debug, Serialize)]
+struct GenerateWaypointResult {
+    waypoint: String,
+    output_file: PathBuf,
 }
 
 impl GenerateWaypoint {
@@ -114,6 +133,16 @@ impl GenerateWaypoint {
     /// Generate a waypoint from the genesis configuration
     pub fn generate_waypoint(&self) -> Result<String, anyhow::Error> {
         let config = self.load_genesis_config()?;
+        if let Some(marker) = &config.insecure {
+            if !self.insecure {
+                anyhow::bail!(
+                    "{:?} is marked insecure ({marker}); pass --insecure to generate a waypoint \
+                     for a local devnet or test network anyway",
+                    self.input_file
+                );
+            }
+            deterministic::warn_insecure();
+        }
         let validator_set = self.generate_validator_set(&config)?;
 
         // For now, generate a simple waypoint hash
@@ -129,18 +158,21 @@ impl GenerateWaypoint {
 }
 
 impl Executable for GenerateWaypoint {
-    fn execute(self) -> Result<(), anyhow::Error> {
-        println!("--- Generate Waypoint Start ---");
-        println!("Reading input file: {:?}", self.input_file);
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        progress!(format, "--- Generate Waypoint Start ---");
+        progress!(format, "Reading input file: {:?}", self.input_file);
 
         let waypoint_string = self.generate_waypoint()?;
-        println!("Generated waypoint: {waypoint_string}");
+        progress!(format, "Generated waypoint: {waypoint_string}");
 
-        println!("--- Write Output File ---");
+        progress!(format, "--- Write Output File ---");
         fs::write(&self.output_file, &waypoint_string)?;
-        println!("Waypoint written to: {:?}", self.output_file);
-        println!("--- Generate Waypoint Success ---");
+        progress!(format, "Waypoint written to: {:?}", self.output_file);
+        progress!(format, "--- Generate Waypoint Success ---");
 
-        Ok(())
+        format.emit(&GenerateWaypointResult {
+            waypoint: waypoint_string,
+            output_file: self.output_file,
+        })
     }
 }
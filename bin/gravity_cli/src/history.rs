@@ -0,0 +1,249 @@
+//! Audit trail of state-changing CLI invocations, appended to `~/.gravity/history.jsonl`.
+//!
+//! `main` opens an [`AuditRecord`] before running a state-changing command and finishes it with
+//! the command's outcome. Commands that send transactions report their hashes through
+//! [`record_tx_hash`]. Writing the trail never fails the command itself.
+
+use crate::{command::Executable, config::GravityConfig, output::OutputFormat};
+use clap::Parser;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::Mutex,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+const REDACTED: &str = "<redacted>";
+
+/// Flags whose values are never written to the history file.
+const SECRET_FLAG_MARKERS: &[&str] =
+    &["private-key", "password", "passphrase", "secret", "seed", "mnemonic", "token"];
+
+static TX_HASHES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Records a transaction hash sent by the running command.
+pub fn record_tx_hash(hash: impl std::fmt::Display) {
+    if let Ok(mut hashes) = TX_HASHES.lock() {
+        hashes.push(hash.to_string());
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Unix timestamp (seconds) at which the command started.
+    pub timestamp: u64,
+    pub user: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Subcommand path, e.g. `validator join`.
+    pub command: String,
+    /// Full argument list with secret values redacted.
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub tx_hashes: Vec<String>,
+    /// `success` or `error`.
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// An in-flight audited invocation.
+pub struct AuditRecord {
+    command: String,
+    profile: Option<String>,
+    timestamp: u64,
+    started: Instant,
+}
+
+impl AuditRecord {
+    pub fn start(command: &str, profile: Option<String>) -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        Self { command: command.to_string(), profile, timestamp, started: Instant::now() }
+    }
+
+    /// Appends the entry to the history file, printing a warning if that fails.
+    pub fn finish(self, result: &Result<(), anyhow::Error>) {
+        let tx_hashes = TX_HASHES.lock().map(|mut h| std::mem::take(&mut *h)).unwrap_or_default();
+        let entry = HistoryEntry {
+            timestamp: self.timestamp,
+            user: std::env::var("USER").unwrap_or_else(|_| "unknown".to_string()),
+            profile: self.profile,
+            command: self.command,
+            args: redact_args(std::env::args().skip(1)),
+            tx_hashes,
+            outcome: if result.is_ok() { "success" } else { "error" }.to_string(),
+            error: result.as_ref().err().map(|e| format!("{e:#}")),
+            duration_ms: self.started.elapsed().as_millis() as u64,
+        };
+        if let Err(e) = append(&entry) {
+            eprintln!("{} Failed to write command history: {e}", "warning:".yellow().bold());
+        }
+    }
+}
+
+pub fn history_path() -> PathBuf {
+    GravityConfig::config_dir().join("history.jsonl")
+}
+
+fn append(entry: &HistoryEntry) -> Result<(), anyhow::Error> {
+    let path = history_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Replaces the values of secret-looking flags, in both `--flag value` and `--flag=value` form.
+fn redact_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    let is_secret =
+        |flag: &str| flag.starts_with('-') && SECRET_FLAG_MARKERS.iter().any(|m| flag.contains(m));
+    let mut redacted = Vec::new();
+    let mut redact_next = false;
+    for arg in args {
+        if redact_next {
+            redacted.push(REDACTED.to_string());
+            redact_next = false;
+        } else if let Some((flag, _)) = arg.split_once('=').filter(|(flag, _)| is_secret(flag)) {
+            redacted.push(format!("{flag}={REDACTED}"));
+        } else {
+            redact_next = is_secret(&arg);
+            redacted.push(arg);
+        }
+    }
+    redacted
+}
+
+/// Formats a unix timestamp as `YYYY-MM-DD HH:MM:SS` UTC.
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[derive(Debug, Parser)]
+pub struct HistoryCommand {
+    /// Show at most this many of the most recent entries
+    #[clap(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Only show entries whose command starts with this (e.g. "validator")
+    #[clap(long)]
+    pub command: Option<String>,
+
+    /// Only show failed invocations
+    #[clap(long)]
+    pub failed: bool,
+}
+
+impl Executable for HistoryCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let path = history_path();
+        let entries: Vec<HistoryEntry> = match fs::File::open(&path) {
+            Ok(file) => BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                // Skip lines a crashed writer may have truncated
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries: Vec<HistoryEntry> = entries
+            .into_iter()
+            .filter(|e| self.command.as_deref().map_or(true, |c| e.command.starts_with(c)))
+            .filter(|e| !self.failed || e.outcome != "success")
+            .collect();
+        let skip = entries.len().saturating_sub(self.limit);
+        entries.drain(..skip);
+
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&entries)?),
+            OutputFormat::Plain => {
+                if entries.is_empty() {
+                    println!("No history recorded in {}", path.display());
+                }
+                for entry in &entries {
+                    let outcome = if entry.outcome == "success" {
+                        entry.outcome.green()
+                    } else {
+                        entry.outcome.red()
+                    };
+                    println!(
+                        "{}  {}  {}  {} ({} ms)",
+                        format_timestamp(entry.timestamp),
+                        entry.user,
+                        entry.command.bold(),
+                        outcome,
+                        entry.duration_ms
+                    );
+                    println!("    args: {}", entry.args.join(" "));
+                    if let Some(profile) = &entry.profile {
+                        println!("    profile: {profile}");
+                    }
+                    for hash in &entry.tx_hashes {
+                        println!("    tx: {hash}");
+                    }
+                    if let Some(error) = &entry.error {
+                        println!("    error: {error}");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split(' ').map(str::to_string)
+    }
+
+    #[test]
+    fn redacts_secret_flag_values() {
+        assert_eq!(
+            redact_args(args("validator join --private-key 0xabc --rpc-url http://x")),
+            vec!["validator", "join", "--private-key", REDACTED, "--rpc-url", "http://x"]
+        );
+        assert_eq!(
+            redact_args(args("genesis generate-key --deterministic-seed=0x01")),
+            vec!["genesis", "generate-key", "--deterministic-seed=<redacted>"]
+        );
+    }
+
+    #[test]
+    fn formats_utc_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20");
+    }
+}
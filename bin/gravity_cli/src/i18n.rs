@@ -0,0 +1,330 @@
+//! Message catalog for user-facing CLI output.
+//!
+//! Every message has a stable id and one complete sentence per language, with named
+//! placeholders such as `{flag}` instead of fragments glued together at runtime, so a new
+//! language can be added by translating the English column as a whole. The id is printed next
+//! to hints and catalog errors, which lets support match a screenshot in any language back to
+//! its message.
+//!
+//! The catalog covers the CLI's own framing rather than every line it prints: the error,
+//! warning and hint labels, configuration and missing-option errors, the errors that come with
+//! a hint, confirmation prompts, and the lines every transaction command shares (transaction
+//! hash, dry-run outcome, revert). Command reports and tables, the remaining per-command
+//! progress and errors, and errors relayed from the node or RPC stay in English.
+
+use clap::ValueEnum;
+use std::{error::Error, fmt, sync::OnceLock};
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum Lang {
+    /// English (default)
+    #[default]
+    En,
+    /// Simplified Chinese
+    Zh,
+}
+
+impl Lang {
+    /// Language of a POSIX locale such as `zh_CN.UTF-8`, if the catalog has it.
+    fn from_locale(locale: &str) -> Option<Self> {
+        let language = locale.split(['_', '.', '@', '-']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            "zh" => Some(Lang::Zh),
+            _ => None,
+        }
+    }
+}
+
+/// Sets the output language: `--lang` (or `GRAVITY_LANG`) if given, else the locale from
+/// `LC_ALL`, `LC_MESSAGES` or `LANG`, else English.
+pub fn init(lang: Option<Lang>) {
+    let lang = lang.unwrap_or_else(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|locale| !locale.is_empty())
+            .and_then(|locale| Lang::from_locale(&locale))
+            .unwrap_or_default()
+    });
+    let _ = LANG.set(lang);
+}
+
+pub fn lang() -> Lang {
+    LANG.get().copied().unwrap_or_default()
+}
+
+macro_rules! catalog {
+    ($($name:ident = $id:literal { en: $en:literal, zh: $zh:literal $(,)? },)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Msg {
+            $($name,)*
+        }
+
+        impl Msg {
+            #[allow(dead_code)]
+            pub const ALL: &'static [Msg] = &[$(Msg::$name,)*];
+
+            /// Stable id of the message, identical in every language.
+            pub fn id(self) -> &'static str {
+                match self {
+                    $(Msg::$name => $id,)*
+                }
+            }
+
+            pub fn template(self, lang: Lang) -> &'static str {
+                match (self, lang) {
+                    $(
+                        (Msg::$name, Lang::En) => $en,
+                        (Msg::$name, Lang::Zh) => $zh,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+catalog! {
+    ErrorLabel = "L001" { en: "error:", zh: "错误：" },
+    CausedByLabel = "L002" { en: "caused by:", zh: "原因：" },
+    HintLabel = "L003" { en: "hint", zh: "提示" },
+    WarningLabel = "L004" { en: "warning:", zh: "警告：" },
+    ConfigLoadFailed = "E001" {
+        en: "Failed to load config: {error}",
+        zh: "加载配置失败：{error}",
+    },
+    MissingOption = "E002" {
+        en: "--{flag} is required. Set it via the CLI flag, the {env} environment variable, or ~/.gravity/config.toml.",
+        zh: "缺少必需参数 --{flag}。请通过命令行参数、环境变量 {env} 或 ~/.gravity/config.toml 设置。",
+    },
+    MissingOptionNoEnv = "E003" {
+        en: "--{flag} is required. Set it via the CLI flag or ~/.gravity/config.toml.",
+        zh: "缺少必需参数 --{flag}。请通过命令行参数或 ~/.gravity/config.toml 设置。",
+    },
+    MissingEndpoint = "E004" {
+        en: "At least one of --{first} or --{second} is required.",
+        zh: "--{first} 和 --{second} 至少需要提供一个。",
+    },
+    ProfileNotFound = "E005" {
+        en: "Profile '{profile}' not found in ~/.gravity/config.toml.",
+        zh: "在 ~/.gravity/config.toml 中未找到配置档案 '{profile}'。",
+    },
+    NotValidator = "E006" {
+        en: "StakePool is not registered as a validator.",
+        zh: "该质押池尚未注册为验证者。",
+    },
+    NotStakePool = "E007" {
+        en: "Address is not a valid StakePool.",
+        zh: "该地址不是有效的质押池（StakePool）。",
+    },
+    PrivateKeyUnreadable = "E008" {
+        en: "Failed to read private key: {error}",
+        zh: "读取私钥失败：{error}",
+    },
+    StartScriptNotFound = "E009" {
+        en: "Start script not found: {path}",
+        zh: "未找到启动脚本：{path}",
+    },
+    StopScriptNotFound = "E010" {
+        en: "Stop script not found: {path}",
+        zh: "未找到停止脚本：{path}",
+    },
+    ReceiptMissing = "E011" {
+        en: "Failed to get transaction receipt.",
+        zh: "获取交易回执失败。",
+    },
+    TxReverted = "E012" {
+        en: "Transaction {hash} reverted.",
+        zh: "交易 {hash} 执行失败，已回滚。",
+    },
+    DryRunReverted = "E013" {
+        en: "Dry run: {count} call(s) would revert.",
+        zh: "模拟执行：有 {count} 个调用将会回滚。",
+    },
+    ConfirmNeedsYes = "E014" {
+        en: "Pass --yes to confirm with --output json.",
+        zh: "使用 --output json 时请传入 --yes 进行确认。",
+    },
+    Aborted = "E015" {
+        en: "Aborted, no transaction was sent.",
+        zh: "已中止，未发送任何交易。",
+    },
+    ProceedPrompt = "P001" {
+        en: "   Proceed? [y/N]: ",
+        zh: "   是否继续？[y/N]：",
+    },
+    JoinPrompt = "P002" {
+        en: "Send the join? [y/N] ",
+        zh: "是否发送加入请求？[y/N] ",
+    },
+    JoinDryRunPrompt = "P003" {
+        en: "Simulate the join? [y/N] ",
+        zh: "是否模拟加入请求？[y/N] ",
+    },
+    TxHash = "T001" {
+        en: "   Transaction hash: {hash}",
+        zh: "   交易哈希：{hash}",
+    },
+    DryRunWouldSucceed = "T002" {
+        en: "   Dry run: {call} would use ~{gas} gas and emit {event}",
+        zh: "   模拟执行：{call} 预计消耗约 {gas} gas，并触发 {event} 事件",
+    },
+    DryRunWouldRevert = "T003" {
+        en: "   Dry run: {call} would revert: {reason}",
+        zh: "   模拟执行：{call} 将会回滚：{reason}",
+    },
+    DryRunComplete = "T004" {
+        en: "   Dry run complete, no transaction was sent",
+        zh: "   模拟执行完成，未发送任何交易",
+    },
+    HintNodeUnreachable = "H001" {
+        en: "Check that the node is running and the URL is correct. Use `gravity-cli node start` to start a node.",
+        zh: "请确认节点正在运行且 URL 正确。可使用 `gravity-cli node start` 启动节点。",
+    },
+    HintInvalidStakePool = "H002" {
+        en: "Verify the --stake-pool address. Use `gravity-cli stake get --owner <addr>` to find your pools.",
+        zh: "请核对 --stake-pool 地址。可使用 `gravity-cli stake get --owner <addr>` 查找您的质押池。",
+    },
+    HintNotValidator = "H003" {
+        en: "Register the validator first with `gravity-cli validator join`.",
+        zh: "请先使用 `gravity-cli validator join` 注册验证者。",
+    },
+    HintPrivateKey = "H004" {
+        en: "Ensure you enter a valid hex-encoded private key.",
+        zh: "请确认输入的是有效的十六进制私钥。",
+    },
+    HintDeployScripts = "H005" {
+        en: "Verify --deploy-path points to a valid deployment directory created by deploy.sh.",
+        zh: "请确认 --deploy-path 指向由 deploy.sh 创建的有效部署目录。",
+    },
+    HintRunInit = "H006" {
+        en: "Run `gravity-cli init` to set up your configuration file.",
+        zh: "请运行 `gravity-cli init` 创建配置文件。",
+    },
+    HintSetOption = "H007" {
+        en: "Set --{flag}, the {env} environment variable, or run `gravity-cli init`.",
+        zh: "请设置 --{flag} 或环境变量 {env}，或运行 `gravity-cli init`。",
+    },
+}
+
+/// The message in the current language, with its `{name}` placeholders filled from `args`.
+pub fn tr(msg: Msg, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut text = msg.template(lang()).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    text
+}
+
+/// An error whose text comes from the catalog. Hints are chosen by its message rather than by
+/// its wording, which differs between languages.
+#[derive(Debug)]
+pub struct CatalogError {
+    pub msg: Msg,
+    text: String,
+}
+
+impl CatalogError {
+    pub fn new(msg: Msg, args: &[(&str, &dyn fmt::Display)]) -> Self {
+        Self { msg, text: tr(msg, args) }
+    }
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Error for CatalogError {}
+
+/// A required option that was given neither on the command line, in the environment nor in the
+/// config profile.
+#[derive(Debug)]
+pub struct MissingOption {
+    /// Flag name without the leading dashes, e.g. `rpc-url`.
+    pub flag: String,
+    pub env: Option<String>,
+}
+
+impl MissingOption {
+    pub fn new(flag: &str, env: &str) -> Self {
+        Self { flag: flag.to_string(), env: Some(env.to_string()) }
+    }
+
+    /// For options that can't be set through an environment variable.
+    pub fn without_env(flag: &str) -> Self {
+        Self { flag: flag.to_string(), env: None }
+    }
+}
+
+impl fmt::Display for MissingOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.env {
+            Some(env) => {
+                f.write_str(&tr(Msg::MissingOption, &[("flag", &self.flag), ("env", env)]))
+            }
+            None => f.write_str(&tr(Msg::MissingOptionNoEnv, &[("flag", &self.flag)])),
+        }
+    }
+}
+
+impl Error for MissingOption {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn placeholders(template: &str) -> BTreeSet<&str> {
+        template
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn translations_keep_ids_and_placeholders() {
+        let ids = Msg::ALL.iter().map(|msg| msg.id()).collect::<BTreeSet<_>>();
+        assert_eq!(ids.len(), Msg::ALL.len());
+        for msg in Msg::ALL {
+            assert_eq!(
+                placeholders(msg.template(Lang::En)),
+                placeholders(msg.template(Lang::Zh)),
+                "placeholders of {}",
+                msg.id()
+            );
+        }
+    }
+
+    #[test]
+    fn detects_language_from_locale() {
+        assert_eq!(Lang::from_locale("zh_CN.UTF-8"), Some(Lang::Zh));
+        assert_eq!(Lang::from_locale("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("C"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("de_DE"), None);
+    }
+
+    #[test]
+    fn catalog_errors_pick_their_hint_in_any_language() {
+        let err = anyhow::Error::from(CatalogError::new(Msg::NotStakePool, &[]))
+            .context("failed to join");
+        let (hint, _) = crate::errors::suggest_fix(&err).unwrap();
+        assert_eq!(hint, Msg::HintInvalidStakePool);
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        let text = Msg::MissingOption
+            .template(Lang::En)
+            .replace("{flag}", "rpc-url")
+            .replace("{env}", "GRAVITY_RPC_URL");
+        assert_eq!(
+            tr(Msg::MissingOption, &[("flag", &"rpc-url"), ("env", &"GRAVITY_RPC_URL")]),
+            text
+        );
+    }
+}
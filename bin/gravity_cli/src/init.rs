@@ -4,6 +4,7 @@ use std::{collections::HashMap, fs, io::Write};
 use crate::{
     command::Executable,
     config::{GravityConfig, ProfileConfig},
+    output::OutputFormat,
 };
 
 #[derive(Debug, Parser)]
@@ -14,7 +15,7 @@ pub struct InitCommand {
 }
 
 impl Executable for InitCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, _: OutputFormat) -> Result<(), anyhow::Error> {
         let config_path = GravityConfig::config_path();
 
         if config_path.exists() && !self.non_interactive {
@@ -52,6 +53,10 @@ impl Executable for InitCommand {
             deploy_path: None,
             gas_limit: None,
             gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            keystore: None,
+            contracts: Default::default(),
         };
 
         if !deploy_path.is_empty() {
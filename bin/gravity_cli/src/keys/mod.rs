@@ -0,0 +1,34 @@
+mod recover;
+mod shamir;
+mod share_file;
+mod split;
+
+use clap::{Parser, Subcommand};
+
+use crate::{command::Executable, output::OutputFormat};
+
+pub use recover::RecoverKey;
+pub use split::SplitKey;
+
+#[derive(Debug, Parser)]
+pub struct KeysCommand {
+    #[command(subcommand)]
+    pub command: SubCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SubCommands {
+    /// Split the consensus private key into passphrase-encrypted Shamir shares
+    Split(SplitKey),
+    /// Recover the consensus private key from a threshold of shares
+    Recover(RecoverKey),
+}
+
+impl Executable for KeysCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::Split(split_cmd) => split_cmd.execute(format),
+            SubCommands::Recover(recover_cmd) => recover_cmd.execute(format),
+        }
+    }
+}
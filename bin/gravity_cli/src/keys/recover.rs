@@ -0,0 +1,104 @@
+use clap::Parser;
+use gaptos::aptos_crypto::{bls12381, PrivateKey, ValidCryptoMaterial};
+use serde::Serialize;
+use std::{fs::OpenOptions, io::Write, path::PathBuf};
+use zeroize::Zeroizing;
+
+use crate::{
+    command::Executable,
+    keys::{
+        shamir,
+        share_file::{prompt_passphrase, ShareFile},
+    },
+    output::{progress, OutputFormat},
+};
+
+#[derive(Debug, Parser)]
+pub struct RecoverKey {
+    /// Share file to use; repeat for each available share
+    #[clap(long = "share", value_parser, required = true)]
+    pub shares: Vec<PathBuf>,
+
+    /// File the recovered consensus private key (hex) is written to; must not exist yet
+    #[clap(long, value_parser)]
+    pub output_file: PathBuf,
+}
+
+/// Result of `keys recover` with `--output json`.
+#[derive(Debug, Serialize)]
+struct RecoverResult {
+    consensus_public_key: String,
+    output_file: PathBuf,
+}
+
+impl Executable for RecoverKey {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        progress!(format, "--- Recover Consensus Key Start ---");
+        let files =
+            self.shares.iter().map(|path| ShareFile::load(path)).collect::<Result<Vec<_>, _>>()?;
+        let first = &files[0];
+        for file in &files[1..] {
+            if file.consensus_public_key != first.consensus_public_key ||
+                file.threshold != first.threshold ||
+                file.total_shares != first.total_shares
+            {
+                anyhow::bail!(
+                    "share {} belongs to a different split than share {}",
+                    file.index,
+                    first.index
+                );
+            }
+        }
+        if files.len() < first.threshold as usize {
+            anyhow::bail!(
+                "{} shares given but {} are required to recover the key",
+                files.len(),
+                first.threshold
+            );
+        }
+
+        let mut shares = Zeroizing::new(Vec::with_capacity(files.len()));
+        for file in &files {
+            let passphrase = prompt_passphrase(
+                &format!("Enter passphrase for share {} ('{}'): ", file.index, file.custodian),
+                false,
+            )?;
+            shares.push((file.index, file.open(&passphrase)?));
+        }
+
+        let secret = Zeroizing::new(shamir::combine(&shares)?);
+        let private_key = bls12381::PrivateKey::try_from(secret.as_slice())
+            .map_err(|e| anyhow::anyhow!("recovered bytes are not a BLS private key: {e}"))?;
+        let public_key = hex::encode(private_key.public_key().to_bytes());
+        if public_key != first.consensus_public_key.trim_start_matches("0x") {
+            anyhow::bail!(
+                "recovered key does not match consensus public key {}",
+                first.consensus_public_key
+            );
+        }
+        progress!(format, "Recovered key matches consensus public key {public_key}");
+
+        // Never clobber an existing file, and never leave the key world-readable, not even
+        // briefly between creating the file and restricting it.
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut output = options.open(&self.output_file).map_err(|e| {
+            anyhow::anyhow!("cannot create key file {}: {e}", self.output_file.display())
+        })?;
+        let private_key_hex = Zeroizing::new(hex::encode(Zeroizing::new(private_key.to_bytes())));
+        output.write_all(private_key_hex.as_bytes())?;
+        output.sync_all()?;
+        drop(private_key);
+        progress!(format, "Wrote consensus private key to {}", self.output_file.display());
+        progress!(format, "--- Recover Consensus Key Success ---");
+        format.emit(&RecoverResult {
+            consensus_public_key: public_key,
+            output_file: self.output_file,
+        })
+    }
+}
@@ -0,0 +1,138 @@
+//! Byte-wise Shamir secret sharing over GF(2^8).
+//!
+//! Each byte of the secret is the constant term of an independent random polynomial of degree
+//! `threshold - 1`; share `x` holds the evaluation of every polynomial at `x` (1..=255).
+
+use rand_core::{OsRng, RngCore};
+
+/// Multiplication in GF(2^8) with the AES reduction polynomial x^8 + x^4 + x^3 + x + 1.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplicative inverse in GF(2^8), computed as a^254.
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exp = 254u8;
+    while exp != 0 {
+        if exp & 1 != 0 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Splits `secret` into `shares` shares, any `threshold` of which recover it.
+/// Returns `(x, y-bytes)` pairs with `x` in `1..=shares`.
+pub fn split(secret: &[u8], threshold: u8, shares: u8) -> anyhow::Result<Vec<(u8, Vec<u8>)>> {
+    if threshold < 2 {
+        anyhow::bail!("threshold must be at least 2");
+    }
+    if shares < threshold {
+        anyhow::bail!("shares ({shares}) must be >= threshold ({threshold})");
+    }
+
+    let mut coefficients = vec![0u8; (threshold as usize - 1) * secret.len()];
+    OsRng.fill_bytes(&mut coefficients);
+
+    let result = (1..=shares)
+        .map(|x| {
+            let ys = secret
+                .iter()
+                .enumerate()
+                .map(|(i, &s)| {
+                    // Horner evaluation of s + c1*x + ... + c_{t-1}*x^{t-1}
+                    let coeffs =
+                        &coefficients[i * (threshold as usize - 1)..][..threshold as usize - 1];
+                    let high = coeffs.iter().rev().fold(0u8, |acc, &c| gf_mul(acc, x) ^ c);
+                    gf_mul(high, x) ^ s
+                })
+                .collect();
+            (x, ys)
+        })
+        .collect();
+    coefficients.iter_mut().for_each(|c| *c = 0);
+    Ok(result)
+}
+
+/// Recovers the secret from at least `threshold` distinct shares via Lagrange interpolation at 0.
+pub fn combine(shares: &[(u8, Vec<u8>)]) -> anyhow::Result<Vec<u8>> {
+    let Some((_, first)) = shares.first() else {
+        anyhow::bail!("no shares given");
+    };
+    let len = first.len();
+    for (i, (x, ys)) in shares.iter().enumerate() {
+        if *x == 0 {
+            anyhow::bail!("invalid share index 0");
+        }
+        if ys.len() != len {
+            anyhow::bail!("shares have different lengths");
+        }
+        if shares[..i].iter().any(|(other, _)| other == x) {
+            anyhow::bail!("duplicate share index {x}");
+        }
+    }
+
+    // Lagrange basis polynomials evaluated at 0: l_j = prod_{m != j} x_m / (x_m - x_j)
+    let basis: Vec<u8> = shares
+        .iter()
+        .map(|(xj, _)| {
+            shares
+                .iter()
+                .filter(|(xm, _)| xm != xj)
+                .fold(1u8, |acc, (xm, _)| gf_mul(acc, gf_mul(*xm, gf_inv(xm ^ xj))))
+        })
+        .collect();
+
+    Ok((0..len)
+        .map(|i| shares.iter().zip(&basis).fold(0u8, |acc, ((_, ys), l)| acc ^ gf_mul(ys[i], *l)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_threshold_subset_recovers_secret() {
+        let secret: Vec<u8> = (0..32).collect();
+        let shares = split(&secret, 2, 3).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                if i != j {
+                    let subset = vec![shares[i].clone(), shares[j].clone()];
+                    assert_eq!(combine(&subset).unwrap(), secret);
+                }
+            }
+        }
+        assert_eq!(combine(&shares).unwrap(), secret);
+    }
+
+    #[test]
+    fn below_threshold_does_not_recover_secret() {
+        let secret = vec![0x42u8; 32];
+        let shares = split(&secret, 3, 5).unwrap();
+        assert_ne!(combine(&shares[..2]).unwrap(), secret);
+    }
+
+    #[test]
+    fn rejects_duplicate_shares() {
+        let shares = split(&[1, 2, 3], 2, 2).unwrap();
+        assert!(combine(&[shares[0].clone(), shares[0].clone()]).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! On-disk format of a consensus key share, encrypted with the custodian's passphrase.
+//!
+//! The share bytes are sealed with AES-256-GCM under a key derived by PBKDF2-HMAC-SHA256. The
+//! share metadata is bound as associated data, so a share cannot be silently relabelled.
+
+use openssl::{
+    hash::MessageDigest,
+    pkcs5::pbkdf2_hmac,
+    rand::rand_bytes,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+const SHARE_FILE_VERSION: u32 = 1;
+const KDF_NAME: &str = "pbkdf2-hmac-sha256";
+const KDF_ITERATIONS: u32 = 600_000;
+/// Most PBKDF2 iterations accepted from a share file, so a tampered file cannot stall recovery.
+const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShareFile {
+    pub version: u32,
+    pub custodian: String,
+    pub threshold: u8,
+    pub total_shares: u8,
+    pub index: u8,
+    /// Public key of the shared secret, used to verify a recovery.
+    pub consensus_public_key: String,
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    pub tag: String,
+}
+
+impl ShareFile {
+    pub fn seal(
+        custodian: &str,
+        threshold: u8,
+        total_shares: u8,
+        index: u8,
+        consensus_public_key: &str,
+        share: &[u8],
+        passphrase: &str,
+    ) -> anyhow::Result<Self> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        rand_bytes(&mut salt)?;
+        rand_bytes(&mut nonce)?;
+
+        let mut file = Self {
+            version: SHARE_FILE_VERSION,
+            custodian: custodian.to_string(),
+            threshold,
+            total_shares,
+            index,
+            consensus_public_key: consensus_public_key.to_string(),
+            kdf: KDF_NAME.to_string(),
+            iterations: KDF_ITERATIONS,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: String::new(),
+            tag: String::new(),
+        };
+        let key = derive_key(passphrase, &salt, KDF_ITERATIONS)?;
+        let mut tag = [0u8; 16];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &key,
+            Some(&nonce),
+            file.associated_data().as_bytes(),
+            share,
+            &mut tag,
+        )?;
+        file.ciphertext = hex::encode(ciphertext);
+        file.tag = hex::encode(tag);
+        Ok(file)
+    }
+
+    /// Decrypts the share, failing on a wrong passphrase or tampered file.
+    pub fn open(&self, passphrase: &str) -> anyhow::Result<Vec<u8>> {
+        if self.version != SHARE_FILE_VERSION || self.kdf != KDF_NAME {
+            anyhow::bail!("unsupported share file (version {}, kdf {})", self.version, self.kdf);
+        }
+        if self.iterations == 0 || self.iterations > MAX_KDF_ITERATIONS {
+            anyhow::bail!(
+                "share {} asks for {} key derivation iterations, expected 1 to {MAX_KDF_ITERATIONS}",
+                self.index,
+                self.iterations
+            );
+        }
+        let salt = hex::decode(&self.salt)?;
+        let nonce = hex::decode(&self.nonce)?;
+        let key = derive_key(passphrase, &salt, self.iterations)?;
+        decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &key,
+            Some(&nonce),
+            self.associated_data().as_bytes(),
+            &hex::decode(&self.ciphertext)?,
+            &hex::decode(&self.tag)?,
+        )
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "failed to decrypt share {} (wrong passphrase or corrupted file)",
+                self.index
+            )
+        })
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("failed to read share file {}: {e}", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn associated_data(&self) -> String {
+        format!(
+            "gravity-key-share:v{}:{}:{}/{}:{}",
+            self.version, self.consensus_public_key, self.index, self.total_shares, self.threshold
+        )
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> anyhow::Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    pbkdf2_hmac(
+        passphrase.as_bytes(),
+        salt,
+        iterations as usize,
+        MessageDigest::sha256(),
+        &mut key,
+    )?;
+    Ok(key)
+}
+
+/// Prompts for a passphrase on the terminal, optionally asking for confirmation. Prompts go to
+/// stderr, keeping stdout for the command's output.
+pub fn prompt_passphrase(prompt: &str, confirm: bool) -> anyhow::Result<String> {
+    let passphrase = rpassword::prompt_password_stderr(prompt)
+        .map_err(|e| anyhow::anyhow!("failed to read passphrase: {e}"))?;
+    if passphrase.is_empty() {
+        anyhow::bail!("passphrase must not be empty");
+    }
+    if confirm {
+        let again = rpassword::prompt_password_stderr("Confirm passphrase: ")
+            .map_err(|e| anyhow::anyhow!("failed to read passphrase: {e}"))?;
+        if again != passphrase {
+            anyhow::bail!("passphrases do not match");
+        }
+    }
+    Ok(passphrase)
+}
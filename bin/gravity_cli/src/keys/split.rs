@@ -0,0 +1,150 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+use crate::{
+    command::Executable,
+    keys::{
+        shamir,
+        share_file::{prompt_passphrase, ShareFile},
+    },
+    output::{progress, OutputFormat},
+};
+
+/// Subset of the identity file written by `genesis generate-key`.
+#[derive(Deserialize)]
+struct IdentityKeys {
+    consensus_private_key: String,
+    consensus_public_key: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct SplitKey {
+    /// Identity YAML file containing `consensus_private_key` (from `genesis generate-key`)
+    #[clap(long, value_parser)]
+    pub identity_file: PathBuf,
+
+    /// Number of shares required to recover the key
+    #[clap(long, default_value_t = 2)]
+    pub threshold: u8,
+
+    /// Total number of shares to create
+    #[clap(long, default_value_t = 3)]
+    pub shares: u8,
+
+    /// Comma-separated custodian names, one per share (default: custodian-1, custodian-2, ...)
+    #[clap(long, value_delimiter = ',')]
+    pub custodians: Vec<String>,
+
+    /// Directory the share files are written to
+    #[clap(long, value_parser)]
+    pub output_dir: PathBuf,
+}
+
+/// Result of `keys split` with `--output json`.
+#[derive(Debug, Serialize)]
+struct SplitResult {
+    threshold: u8,
+    consensus_public_key: String,
+    shares: Vec<ShareSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShareSummary {
+    index: u8,
+    custodian: String,
+    file: PathBuf,
+}
+
+/// Custodian names become part of the share file names, so they are limited to letters, digits,
+/// `.`, `_` and `-`, and may not start with `.`.
+fn validate_custodian(name: &str) -> anyhow::Result<()> {
+    let valid_chars =
+        name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if name.is_empty() || name.len() > 64 || name.starts_with('.') || !valid_chars {
+        anyhow::bail!(
+            "invalid custodian name '{name}': use up to 64 letters, digits, '.', '_' or '-', not \
+             starting with '.'"
+        );
+    }
+    Ok(())
+}
+
+impl Executable for SplitKey {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let custodians: Vec<String> = if self.custodians.is_empty() {
+            (1..=self.shares).map(|i| format!("custodian-{i}")).collect()
+        } else {
+            self.custodians.clone()
+        };
+        if custodians.len() != self.shares as usize {
+            anyhow::bail!(
+                "--custodians lists {} names but --shares is {}",
+                custodians.len(),
+                self.shares
+            );
+        }
+        for custodian in &custodians {
+            validate_custodian(custodian)?;
+        }
+
+        let identity: IdentityKeys =
+            serde_yaml::from_str(&fs::read_to_string(&self.identity_file)?)
+                .map_err(|e| anyhow::anyhow!("invalid identity file: {e}"))?;
+        let secret = hex::decode(identity.consensus_private_key.trim_start_matches("0x"))
+            .map_err(|e| anyhow::anyhow!("invalid consensus_private_key hex: {e}"))?;
+
+        progress!(format, "--- Split Consensus Key Start ---");
+        progress!(
+            format,
+            "Splitting into {} shares, any {} of which recover the key",
+            self.shares,
+            self.threshold
+        );
+        let shares = shamir::split(&secret, self.threshold, self.shares)?;
+
+        fs::create_dir_all(&self.output_dir)?;
+        let mut written = Vec::with_capacity(shares.len());
+        for ((index, share), custodian) in shares.iter().zip(&custodians) {
+            progress!(format, "\nShare {index} for custodian '{custodian}'");
+            let passphrase =
+                prompt_passphrase(&format!("Enter passphrase for '{custodian}': "), true)?;
+            let file = ShareFile::seal(
+                custodian,
+                self.threshold,
+                self.shares,
+                *index,
+                &identity.consensus_public_key,
+                share,
+                &passphrase,
+            )?;
+            let path = self.output_dir.join(format!("share-{index}-{custodian}.json"));
+            file.save(&path)?;
+            progress!(format, "Wrote {}", path.display());
+            written.push(ShareSummary { index: *index, custodian: custodian.clone(), file: path });
+        }
+
+        progress!(format, "\nHand each share file to its custodian and delete local copies.");
+        progress!(format, "--- Split Consensus Key Success ---");
+        format.emit(&SplitResult {
+            threshold: self.threshold,
+            consensus_public_key: identity.consensus_public_key,
+            shares: written,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custodian_names_cannot_leave_the_output_dir() {
+        for name in ["alice", "custodian-1", "ops_team.eu"] {
+            assert!(validate_custodian(name).is_ok(), "{name}");
+        }
+        for name in ["", "..", "../alice", "a/b", "a\\b", ".hidden", "bob smith"] {
+            assert!(validate_custodian(name).is_err(), "{name}");
+        }
+    }
+}
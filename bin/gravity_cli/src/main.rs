@@ -2,17 +2,27 @@ pub mod command;
 pub mod completions;
 pub mod config;
 pub mod contract;
+pub mod delegation;
 pub mod dkg;
 pub mod doctor;
 pub mod epoch;
 pub mod errors;
+pub mod fees;
 pub mod genesis;
+pub mod history;
+pub mod i18n;
 pub mod init;
+pub mod keys;
 pub mod node;
 pub mod output;
+pub mod plugin;
 pub mod signer;
+pub mod smoke;
 pub mod stake;
 pub mod status;
+pub mod top;
+pub mod transaction;
+pub mod tx;
 pub mod unwind;
 pub mod util;
 pub mod validator;
@@ -21,93 +31,178 @@ use clap::Parser;
 use colored::Colorize;
 use command::{Command, Executable};
 use config::GravityConfig;
+use i18n::{tr, Msg};
 
 fn main() {
     let mut cmd = Command::parse();
+    i18n::init(cmd.lang);
 
     // Load config and resolve profile
     let config = match GravityConfig::load() {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("{} Failed to load config: {e}", "warning:".yellow().bold());
+            eprintln!(
+                "{} {}",
+                tr(Msg::WarningLabel, &[]).yellow().bold(),
+                tr(Msg::ConfigLoadFailed, &[("error", &e)])
+            );
             None
         }
     };
     let profile = config.as_ref().and_then(|c| c.active_profile(cmd.profile.as_deref()).cloned());
+    // A profile named on the command line must exist, otherwise its settings would be silently
+    // replaced by flags, environment and defaults. `init` is what creates it.
+    if let (Some(name), None) = (&cmd.profile, &profile) {
+        if !matches!(cmd.command, command::SubCommands::Init(_)) {
+            eprintln!(
+                "{} {}",
+                tr(Msg::ErrorLabel, &[]).red().bold(),
+                tr(Msg::ProfileNotFound, &[("profile", name)])
+            );
+            std::process::exit(1);
+        }
+    }
     let output_format = cmd.output;
 
     // Inject config defaults into subcommands
     apply_config_defaults(&mut cmd, &profile);
 
+    // Record state-changing commands in the audit trail
+    let audit = audited_command(&cmd.command).map(|name| {
+        let profile_name =
+            cmd.profile.clone().or_else(|| config.as_ref().map(|c| c.active_profile.clone()));
+        history::AuditRecord::start(name, profile_name)
+    });
+
     let result = match cmd.command {
-        command::SubCommands::Genesis(genesis_cmd) => match genesis_cmd.command {
-            genesis::SubCommands::GenerateKey(gck) => gck.execute(),
-            genesis::SubCommands::GenerateWaypoint(gw) => gw.execute(),
-            genesis::SubCommands::GenerateAccount(generate_account) => generate_account.execute(),
-        },
-        command::SubCommands::Validator(validator_cmd) => match validator_cmd.command {
-            validator::SubCommands::Join(join_cmd) => join_cmd.execute(),
-            validator::SubCommands::Leave(leave_cmd) => leave_cmd.execute(),
-            validator::SubCommands::List(mut list_cmd) => {
-                list_cmd.output_format = output_format;
-                list_cmd.execute()
-            }
-        },
-        command::SubCommands::Stake(stake_cmd) => match stake_cmd.command {
-            stake::SubCommands::Create(mut create_cmd) => {
-                create_cmd.output_format = output_format;
-                create_cmd.execute()
-            }
-            stake::SubCommands::Get(mut get_cmd) => {
-                get_cmd.output_format = output_format;
-                get_cmd.execute()
-            }
-        },
-        command::SubCommands::Node(node_cmd) => match node_cmd.command {
-            node::SubCommands::Start(start_cmd) => start_cmd.execute(),
-            node::SubCommands::Stop(stop_cmd) => stop_cmd.execute(),
-        },
-        command::SubCommands::Dkg(dkg_cmd) => match dkg_cmd.command {
-            dkg::SubCommands::Status(mut status_cmd) => {
-                status_cmd.output_format = output_format;
-                status_cmd.execute()
-            }
-            dkg::SubCommands::Randomness(randomness_cmd) => randomness_cmd.execute(),
-        },
-        command::SubCommands::Unwind(unwind_cmd) => unwind_cmd.execute(),
-        command::SubCommands::Epoch(epoch_cmd) => match epoch_cmd.command {
-            epoch::SubCommands::Status(mut status_cmd) => {
-                status_cmd.output_format = output_format;
-                status_cmd.execute()
-            }
-        },
-        command::SubCommands::Status(mut status_cmd) => {
-            status_cmd.output_format = output_format;
-            status_cmd.execute()
+        command::SubCommands::Genesis(genesis_cmd) => genesis_cmd.execute(output_format),
+        command::SubCommands::Validator(validator_cmd) => validator_cmd.execute(output_format),
+        command::SubCommands::Keys(keys_cmd) => keys_cmd.execute(output_format),
+        command::SubCommands::Stake(stake_cmd) => stake_cmd.execute(output_format),
+        command::SubCommands::Delegation(delegation_cmd) => delegation_cmd.execute(output_format),
+        command::SubCommands::Node(node_cmd) => node_cmd.execute(output_format),
+        command::SubCommands::Dkg(dkg_cmd) => dkg_cmd.execute(output_format),
+        command::SubCommands::Unwind(unwind_cmd) => unwind_cmd.execute(output_format),
+        command::SubCommands::Epoch(epoch_cmd) => epoch_cmd.execute(output_format),
+        command::SubCommands::Status(status_cmd) => status_cmd.execute(output_format),
+        command::SubCommands::Completions(completions_cmd) => {
+            completions_cmd.execute(output_format)
         }
-        command::SubCommands::Completions(completions_cmd) => completions_cmd.execute(),
-        command::SubCommands::Init(init_cmd) => init_cmd.execute(),
-        command::SubCommands::Doctor(mut doctor_cmd) => {
-            doctor_cmd.output_format = output_format;
-            doctor_cmd.execute()
+        command::SubCommands::Init(init_cmd) => init_cmd.execute(output_format),
+        command::SubCommands::Doctor(doctor_cmd) => doctor_cmd.execute(output_format),
+        command::SubCommands::Smoke(smoke_cmd) => smoke_cmd.execute(output_format),
+        command::SubCommands::History(history_cmd) => history_cmd.execute(output_format),
+        command::SubCommands::Top(top_cmd) => top_cmd.execute(output_format),
+        command::SubCommands::Tx(tx_cmd) => tx_cmd.execute(output_format),
+        command::SubCommands::Plugins(plugins_cmd) => plugins_cmd.execute(output_format),
+        command::SubCommands::Plugin(args) => {
+            let context = plugin::SharedContext {
+                profile_name: cmd
+                    .profile
+                    .clone()
+                    .or_else(|| config.as_ref().map(|c| c.active_profile.clone())),
+                profile: profile.clone(),
+                output: output_format,
+                lang: i18n::lang(),
+            };
+            plugin::run(args, &context)
         }
     };
 
+    if let Some(audit) = audit {
+        audit.finish(&result);
+    }
+
     if let Err(e) = result {
-        eprintln!("{} {e}", "error:".red().bold());
+        // Catalog errors carry their id, like hints, so a report in either language can be
+        // matched to the same entry.
+        let id = e.chain().find_map(|cause| cause.downcast_ref::<i18n::CatalogError>());
+        let id = id.map(|error| format!(" [{}]", error.msg.id())).unwrap_or_default();
+        eprintln!("{} {e}{id}", tr(Msg::ErrorLabel, &[]).red().bold());
         for cause in e.chain().skip(1) {
-            eprintln!("  {} {cause}", "caused by:".yellow());
+            eprintln!("  {} {cause}", tr(Msg::CausedByLabel, &[]).yellow());
         }
-        if let Some(hint) = errors::suggest_fix(&e) {
-            eprintln!("\n{} {hint}", "hint:".cyan().bold());
+        if let Some((hint, text)) = errors::suggest_fix(&e) {
+            let label = format!("{}[{}]:", tr(Msg::HintLabel, &[]), hint.id());
+            eprintln!("\n{} {text}", label.cyan().bold());
         }
         std::process::exit(1);
     }
 }
 
+/// Returns the audit trail name of `command` if it changes on-chain, node or key state.
+fn audited_command(command: &command::SubCommands) -> Option<&'static str> {
+    match command {
+        command::SubCommands::Genesis(g) => Some(match g.command {
+            genesis::SubCommands::GenerateKey(_) => "genesis generate-key",
+            genesis::SubCommands::GenerateWaypoint(_) => "genesis generate-waypoint",
+            genesis::SubCommands::GenerateAccount(_) => "genesis generate-account",
+            genesis::SubCommands::Allocate(_) => "genesis allocate",
+        }),
+        command::SubCommands::Validator(v) => match v.command {
+            validator::SubCommands::Join(_) => Some("validator join"),
+            validator::SubCommands::Leave(_) => Some("validator leave"),
+            validator::SubCommands::AddStake(_) => Some("validator add-stake"),
+            validator::SubCommands::Unstake(_) => Some("validator unstake"),
+            validator::SubCommands::Withdraw(_) => Some("validator withdraw"),
+            validator::SubCommands::ExtendLockup(_) => Some("validator extend-lockup"),
+            validator::SubCommands::RotateKey(_) => Some("validator rotate-key"),
+            validator::SubCommands::SetFeeRecipient(_) => Some("validator set-fee-recipient"),
+            validator::SubCommands::SubmitEvidence(_) => Some("validator submit-evidence"),
+            validator::SubCommands::CompromiseResponse(_) => Some("validator compromise-response"),
+            validator::SubCommands::Topup(_) => Some("validator topup"),
+            validator::SubCommands::Apply(_) => Some("validator apply"),
+            validator::SubCommands::List(_) |
+            validator::SubCommands::Status(_) |
+            validator::SubCommands::Decentralization(_) |
+            validator::SubCommands::Notify(_) |
+            validator::SubCommands::Snapshot(_) |
+            validator::SubCommands::Compare(_) => None,
+        },
+        command::SubCommands::Keys(k) => Some(match k.command {
+            keys::SubCommands::Split(_) => "keys split",
+            keys::SubCommands::Recover(_) => "keys recover",
+        }),
+        command::SubCommands::Stake(s) => match s.command {
+            stake::SubCommands::Create(_) => Some("stake create"),
+            stake::SubCommands::Get(_) => None,
+        },
+        command::SubCommands::Delegation(d) => match d.command {
+            delegation::SubCommands::Delegate(_) => Some("delegation delegate"),
+            delegation::SubCommands::Undelegate(_) => Some("delegation undelegate"),
+            delegation::SubCommands::ListDelegations(_) => None,
+        },
+        command::SubCommands::Node(n) => match n.command {
+            node::SubCommands::Start(_) => Some("node start"),
+            node::SubCommands::Stop(_) => Some("node stop"),
+            node::SubCommands::Logs(_) => None,
+            node::SubCommands::Schedule(_) => Some("node schedule"),
+            node::SubCommands::Config(_) => None,
+            node::SubCommands::Migrate(_) => Some("node migrate"),
+            node::SubCommands::PerfReport(_) => None,
+        },
+        command::SubCommands::Unwind(_) => Some("unwind"),
+        command::SubCommands::Init(_) => Some("init"),
+        command::SubCommands::Smoke(_) => Some("smoke"),
+        command::SubCommands::Tx(t) => match t.command {
+            tx::SubCommands::Bench(_) => Some("tx bench"),
+        },
+        command::SubCommands::Dkg(_) |
+        command::SubCommands::Epoch(_) |
+        command::SubCommands::Status(_) |
+        command::SubCommands::Completions(_) |
+        command::SubCommands::Doctor(_) |
+        command::SubCommands::History(_) |
+        command::SubCommands::Top(_) |
+        command::SubCommands::Plugins(_) |
+        command::SubCommands::Plugin(_) => None,
+    }
+}
+
 /// Apply config profile defaults to command fields that are still None after CLI/env parsing.
 fn apply_config_defaults(cmd: &mut Command, profile: &Option<config::ProfileConfig>) {
     let Some(profile) = profile else { return };
+    contract::set_overrides(profile.contracts.clone());
 
     match &mut cmd.command {
         command::SubCommands::Validator(ref mut v) => match &mut v.command {
@@ -115,22 +210,92 @@ fn apply_config_defaults(cmd: &mut Command, profile: &Option<config::ProfileConf
                 if c.rpc_url.is_none() {
                     c.rpc_url.clone_from(&profile.rpc_url);
                 }
-                if c.gas_limit.is_none() {
-                    c.gas_limit = profile.gas_limit;
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::Leave(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
                 }
-                if c.gas_price.is_none() {
-                    c.gas_price = profile.gas_price;
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::AddStake(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
                 }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
             }
-            validator::SubCommands::Leave(ref mut c) => {
+            validator::SubCommands::Unstake(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::Withdraw(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::ExtendLockup(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::RotateKey(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::SetFeeRecipient(ref mut c) => {
                 if c.rpc_url.is_none() {
                     c.rpc_url.clone_from(&profile.rpc_url);
                 }
-                if c.gas_limit.is_none() {
-                    c.gas_limit = profile.gas_limit;
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::SubmitEvidence(ref mut c) => {
+                if c.api_url.is_none() {
+                    c.api_url.clone_from(&profile.server_url);
                 }
-                if c.gas_price.is_none() {
-                    c.gas_price = profile.gas_price;
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::CompromiseResponse(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::Topup(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::Apply(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            validator::SubCommands::Notify(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
                 }
             }
             validator::SubCommands::List(ref mut c) => {
@@ -138,20 +303,53 @@ fn apply_config_defaults(cmd: &mut Command, profile: &Option<config::ProfileConf
                     c.rpc_url.clone_from(&profile.rpc_url);
                 }
             }
+            validator::SubCommands::Status(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+            }
+            validator::SubCommands::Decentralization(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+            }
+            validator::SubCommands::Snapshot(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+            }
+            validator::SubCommands::Compare(_) => {}
         },
         command::SubCommands::Stake(ref mut s) => match &mut s.command {
             stake::SubCommands::Create(ref mut c) => {
                 if c.rpc_url.is_none() {
                     c.rpc_url.clone_from(&profile.rpc_url);
                 }
-                if c.gas_limit.is_none() {
-                    c.gas_limit = profile.gas_limit;
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            stake::SubCommands::Get(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
                 }
-                if c.gas_price.is_none() {
-                    c.gas_price = profile.gas_price;
+            }
+        },
+        command::SubCommands::Delegation(ref mut d) => match &mut d.command {
+            delegation::SubCommands::Delegate(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
                 }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
             }
-            stake::SubCommands::Get(ref mut c) => {
+            delegation::SubCommands::Undelegate(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+            delegation::SubCommands::ListDelegations(ref mut c) => {
                 if c.rpc_url.is_none() {
                     c.rpc_url.clone_from(&profile.rpc_url);
                 }
@@ -168,6 +366,33 @@ fn apply_config_defaults(cmd: &mut Command, profile: &Option<config::ProfileConf
                     c.deploy_path.clone_from(&profile.deploy_path);
                 }
             }
+            node::SubCommands::Logs(ref mut c) => {
+                if c.api_url.is_none() {
+                    c.api_url.clone_from(&profile.server_url);
+                }
+            }
+            node::SubCommands::Schedule(ref mut c) => {
+                if c.api_url.is_none() {
+                    c.api_url.clone_from(&profile.server_url);
+                }
+            }
+            node::SubCommands::Config(ref mut c) => match &mut c.command {
+                node::config::SubCommands::Diff(ref mut c) => {
+                    if c.api_url.is_none() {
+                        c.api_url.clone_from(&profile.server_url);
+                    }
+                }
+            },
+            node::SubCommands::Migrate(ref mut c) => {
+                if c.from.is_none() {
+                    c.from.clone_from(&profile.deploy_path);
+                }
+            }
+            node::SubCommands::PerfReport(ref mut c) => {
+                if c.api_url.is_none() {
+                    c.api_url.clone_from(&profile.server_url);
+                }
+            }
         },
         command::SubCommands::Dkg(ref mut d) => match &mut d.command {
             dkg::SubCommands::Status(ref mut c) => {
@@ -180,6 +405,16 @@ fn apply_config_defaults(cmd: &mut Command, profile: &Option<config::ProfileConf
                     c.server_url.clone_from(&profile.server_url);
                 }
             }
+            dkg::SubCommands::DecryptRequest(ref mut c) => {
+                if c.api_urls.is_empty() {
+                    c.api_urls.extend(profile.server_url.clone());
+                }
+            }
+            dkg::SubCommands::Params(ref mut c) => {
+                if c.api_url.is_none() {
+                    c.api_url.clone_from(&profile.server_url);
+                }
+            }
         },
         command::SubCommands::Epoch(ref mut ep) => match &mut ep.command {
             epoch::SubCommands::Status(ref mut c) => {
@@ -207,6 +442,36 @@ fn apply_config_defaults(cmd: &mut Command, profile: &Option<config::ProfileConf
                 c.deploy_path.clone_from(&profile.deploy_path);
             }
         }
+        command::SubCommands::Smoke(ref mut c) => {
+            if c.rpc_url.is_none() {
+                c.rpc_url.clone_from(&profile.rpc_url);
+            }
+            if c.api_url.is_none() {
+                c.api_url.clone_from(&profile.server_url);
+            }
+            c.fees.apply_profile(profile);
+            c.signer.apply_profile(profile);
+        }
+        command::SubCommands::Tx(ref mut t) => match &mut t.command {
+            tx::SubCommands::Bench(ref mut c) => {
+                if c.rpc_url.is_none() {
+                    c.rpc_url.clone_from(&profile.rpc_url);
+                }
+                if c.api_url.is_none() {
+                    c.api_url.clone_from(&profile.server_url);
+                }
+                c.fees.apply_profile(profile);
+                c.signer.apply_profile(profile);
+            }
+        },
+        command::SubCommands::Top(ref mut c) => {
+            if c.rpc_url.is_none() {
+                c.rpc_url.clone_from(&profile.rpc_url);
+            }
+            if c.api_url.is_none() {
+                c.api_url.clone_from(&profile.server_url);
+            }
+        }
         // Genesis, Unwind, Completions, Init don't use profile config
         _ => {}
     }
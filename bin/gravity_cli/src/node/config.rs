@@ -0,0 +1,241 @@
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    command::Executable,
+    i18n::MissingOption,
+    output::{progress, OutputFormat},
+    util::ErrorResponse,
+};
+
+/// Placeholder the node serves instead of secrets; such fields are never compared.
+const REDACTED: &str = "<redacted>";
+
+/// Config fields that also have a runtime control, so a change can be applied without a restart.
+/// Every other field is only read at startup.
+const LIVE_FIELDS: [(&str, &str); 2] =
+    [("logger.level", "POST /admin/log_level"), ("failpoints", "POST /set_failpoint")];
+
+#[derive(Debug, Parser)]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    pub command: SubCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SubCommands {
+    /// Compare a node config file with the config the running node uses
+    Diff(DiffCommand),
+}
+
+impl Executable for ConfigCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::Diff(diff_cmd) => diff_cmd.execute(format),
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct DiffCommand {
+    /// Node config file to roll out (YAML, or TOML with a .toml extension)
+    #[clap(long, value_parser)]
+    pub file: PathBuf,
+
+    /// Node API address and port (e.g., 127.0.0.1:1024)
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NodeConfigResponse {
+    config: Value,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Change {
+    path: String,
+    /// None when the running config has no such field.
+    running: Option<Value>,
+    new: Value,
+}
+
+/// Result of `node config diff` with `--output json`.
+#[derive(Debug, Serialize)]
+struct DiffResult<'a> {
+    file: String,
+    changes: Vec<ChangeSummary<'a>>,
+    /// Changes that only take effect after a restart.
+    restart_required: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ChangeSummary<'a> {
+    #[serde(flatten)]
+    change: &'a Change,
+    /// Runtime control applying the change without a restart, if any.
+    live_control: Option<&'static str>,
+}
+
+impl Change {
+    /// The runtime control applying this change, if any.
+    fn live_control(&self) -> Option<&'static str> {
+        LIVE_FIELDS.iter().find_map(|(field, control)| {
+            (self.path == *field || self.path.starts_with(&format!("{field}."))).then_some(*control)
+        })
+    }
+}
+
+/// Fields set in `new` whose value differs from `running`. Fields the file leaves out keep their
+/// defaults and are not compared, and objects are compared field by field.
+fn diff(path: &str, running: Option<&Value>, new: &Value, changes: &mut Vec<Change>) {
+    if running.and_then(Value::as_str) == Some(REDACTED) {
+        return;
+    }
+    match (running, new) {
+        (Some(Value::Object(running)), Value::Object(new)) => {
+            for (name, value) in new {
+                let path = if path.is_empty() { name.clone() } else { format!("{path}.{name}") };
+                diff(&path, running.get(name), value, changes);
+            }
+        }
+        (Some(running), new) if running == new => {}
+        (running, new) => changes.push(Change {
+            path: path.to_string(),
+            running: running.cloned(),
+            new: new.clone(),
+        }),
+    }
+}
+
+impl Executable for DiffCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl DiffCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    fn load_file(&self) -> Result<Value, anyhow::Error> {
+        let content = std::fs::read_to_string(&self.file)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", self.file.display()))?;
+        let parsed = if self.file.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str::<Value>(&content).map_err(anyhow::Error::from)
+        } else {
+            serde_yaml::from_str::<Value>(&content).map_err(anyhow::Error::from)
+        };
+        parsed.map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", self.file.display()))
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let api_url = self
+            .api_url
+            .as_deref()
+            .ok_or_else(|| MissingOption::new("api-url", "GRAVITY_SERVER_URL"))?;
+        let new = self.load_file()?;
+
+        let url = format!("{}/admin/config", Self::normalize_url(api_url));
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        let response = client.get(&url).send().await?;
+        let status_code = response.status();
+        if !status_code.is_success() {
+            let error_msg = match response.json::<ErrorResponse>().await {
+                Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
+                Err(_) => format!("HTTP {status_code}"),
+            };
+            return Err(anyhow::anyhow!("Failed to fetch the running node config: {error_msg}"));
+        }
+        let running = response.json::<NodeConfigResponse>().await?.config;
+
+        let mut changes = Vec::new();
+        diff("", Some(&running), &new, &mut changes);
+        if changes.is_empty() {
+            progress!(format, "{} matches the running node config", self.file.display());
+        }
+
+        let mut restart_required = 0;
+        for change in &changes {
+            let running = change
+                .running
+                .as_ref()
+                .map_or_else(|| "(not in running config)".to_string(), |value| value.to_string());
+            let note = match change.live_control() {
+                Some(control) => format!("[live: {control}]").green(),
+                None => {
+                    restart_required += 1;
+                    "[restart required]".yellow().bold()
+                }
+            };
+            progress!(
+                format,
+                "~ {}: {} -> {}  {note}",
+                change.path.bold(),
+                running.red(),
+                change.new.to_string().green()
+            );
+        }
+        if !changes.is_empty() {
+            progress!(
+                format,
+                "\n{} field(s) differ, {} of them only take effect after a restart",
+                changes.len(),
+                restart_required
+            );
+        }
+        format.emit(&DiffResult {
+            file: self.file.display().to_string(),
+            changes: changes
+                .iter()
+                .map(|change| ChangeSummary { change, live_control: change.live_control() })
+                .collect(),
+            restart_required,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diffs_only_fields_set_in_the_file() {
+        let running = json!({
+            "logger": { "level": "INFO", "chan_size": 10000 },
+            "storage": { "dir": "/opt/data" },
+            "validator_network": { "identity": { "key": REDACTED } },
+        });
+        let new = json!({
+            "logger": { "level": "DEBUG" },
+            "storage": { "dir": "/opt/data", "unknown": 1 },
+            "validator_network": { "identity": { "key": "abcd" } },
+        });
+        let mut changes = Vec::new();
+        diff("", Some(&running), &new, &mut changes);
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path, "logger.level");
+        assert_eq!(changes[0].live_control(), Some("POST /admin/log_level"));
+        assert_eq!(
+            changes[1],
+            Change { path: "storage.unknown".into(), running: None, new: json!(1) }
+        );
+        assert_eq!(changes[1].live_control(), None);
+    }
+}
@@ -0,0 +1,96 @@
+use clap::Parser;
+
+use crate::{command::Executable, i18n::MissingOption, output::OutputFormat, util::ErrorResponse};
+
+#[derive(Debug, Parser)]
+pub struct LogsCommand {
+    /// Node API address and port (e.g., 127.0.0.1:1024)
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+
+    /// Keep streaming new log lines until interrupted
+    #[clap(long, short = 'f')]
+    pub follow: bool,
+
+    /// Only show lines containing this text
+    #[clap(long)]
+    pub filter: Option<String>,
+
+    /// Number of existing lines to show first (the node caps this at 1000)
+    #[clap(long, short = 'n', default_value_t = 20)]
+    pub lines: usize,
+}
+
+impl Executable for LogsCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl LogsCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let api_url =
+            self.api_url.ok_or_else(|| MissingOption::new("api-url", "GRAVITY_SERVER_URL"))?;
+        let url = format!("{}/admin/logs/tail", Self::normalize_url(&api_url));
+
+        let mut query =
+            vec![("lines", self.lines.to_string()), ("follow", self.follow.to_string())];
+        if let Some(filter) = self.filter {
+            query.push(("filter", filter));
+        }
+
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .build()?;
+        let mut response = client
+            .get(&url)
+            .query(&query)
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await?;
+
+        let status_code = response.status();
+        if !status_code.is_success() {
+            let error_msg = match response.json::<ErrorResponse>().await {
+                Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
+                Err(_) => format!("HTTP {status_code}"),
+            };
+            return Err(anyhow::anyhow!("Failed to tail node logs: {error_msg}"));
+        }
+
+        // Server-sent events: `data:` lines carry the log lines, blank lines end an event and
+        // `:` lines are keep-alive comments.
+        let mut buffer = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+            while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim_end_matches(['\r', '\n']);
+                if let Some(data) = line.strip_prefix("data:") {
+                    let data = data.strip_prefix(' ').unwrap_or(data);
+                    // One JSON object per log line, as the stream may never end
+                    match format {
+                        OutputFormat::Json => println!("{}", serde_json::json!({ "line": data })),
+                        OutputFormat::Plain => println!("{data}"),
+                    }
+                }
+            }
+        }
+        if self.follow {
+            return Err(anyhow::anyhow!("Log stream closed by the node"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,497 @@
+use anyhow::{anyhow, Context};
+use aptos_consensus::{
+    consensusdb::{ConsensusDB, CONSENSUS_DB_NAME},
+    quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME,
+};
+use clap::Parser;
+use gaptos::aptos_storage_interface::DbReader;
+use serde::{Deserialize, Serialize};
+use sha2_0_10_6::{Digest, Sha256};
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    command::Executable,
+    i18n::MissingOption,
+    output::{progress, OutputFormat},
+};
+
+/// Config files and scripts whose paths are rewritten to the new layout.
+const CONFIG_FILES: [&str; 3] = ["validator.yaml", "reth_config.json", "relayer_config.json"];
+const SCRIPTS: [&str; 2] = ["start.sh", "stop.sh"];
+
+#[derive(Debug, Parser)]
+pub struct MigrateCommand {
+    /// Deployment directory of the stopped node, in the layout written by deploy.sh
+    #[clap(long, env = "GRAVITY_DEPLOY_PATH")]
+    pub from: Option<String>,
+
+    /// Layout file (TOML) describing where the migrated node keeps its state
+    #[clap(long, value_parser)]
+    pub to: PathBuf,
+
+    /// Print what would be copied and exit
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// Result of `node migrate` with `--output json`.
+#[derive(Debug, Serialize)]
+struct MigrateResult {
+    from: PathBuf,
+    to: PathBuf,
+    dry_run: bool,
+    /// `[from, to]` of every item copied, or that would be copied.
+    migrated: Vec<[PathBuf; 2]>,
+    not_migrated: Vec<PathBuf>,
+}
+
+/// Where a node keeps its state. Relative paths are resolved against `root`; fields left out of
+/// a layout file keep the deploy.sh locations.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct Layout {
+    root: PathBuf,
+    /// The node's `storage.dir`: ConsensusDB, QuorumStore DB, randomness DB and API state.
+    storage_dir: PathBuf,
+    /// Safety rules storage, holding the last vote.
+    secure_storage: PathBuf,
+    identity: PathBuf,
+    /// validator.yaml, reth_config.json, relayer_config.json and waypoint.txt.
+    config_dir: PathBuf,
+    reth_dir: PathBuf,
+    script_dir: PathBuf,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::new(),
+            storage_dir: "data".into(),
+            secure_storage: "data/secure_storage.json".into(),
+            identity: "config/identity.yaml".into(),
+            config_dir: "config".into(),
+            reth_dir: "data/reth".into(),
+            script_dir: "script".into(),
+        }
+    }
+}
+
+impl Layout {
+    fn deploy(root: PathBuf) -> Self {
+        Self { root, ..Self::default() }
+    }
+
+    /// Reads a layout file; a relative `root` is relative to the file.
+    fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read layout file {}", path.display()))?;
+        let mut layout: Layout = toml::from_str(&contents)
+            .with_context(|| format!("Invalid layout file {}", path.display()))?;
+        if layout.root.as_os_str().is_empty() {
+            return Err(anyhow!("Layout file {} must set `root`", path.display()));
+        }
+        if layout.root.is_relative() {
+            let dir = path.parent().unwrap_or(Path::new("."));
+            layout.root = fs::canonicalize(dir)?.join(&layout.root);
+        }
+        Ok(layout)
+    }
+
+    fn path(&self, relative: &Path) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    /// Old and new location of every piece of state, longest old path first, for rewriting
+    /// paths in config files.
+    fn relocations(&self, to: &Layout) -> Vec<(PathBuf, PathBuf)> {
+        let fields = |layout: &Layout| {
+            [
+                layout.path(&layout.secure_storage),
+                layout.path(&layout.identity),
+                layout.path(&layout.reth_dir),
+                layout.path(&layout.storage_dir),
+                layout.path(&layout.config_dir),
+                layout.path(&layout.script_dir),
+                layout.root.clone(),
+            ]
+        };
+        let mut relocations: Vec<_> = fields(self).into_iter().zip(fields(to)).collect();
+        relocations.sort_by_key(|(from, _)| std::cmp::Reverse(from.as_os_str().len()));
+        relocations
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Copied, then opened with this build's schema so column families it added are created.
+    ConsensusDb,
+    Dir,
+    File,
+    /// Key material; the copy is compared by checksum.
+    Key,
+    /// Paths of the old layout in the file are rewritten to the new one.
+    Config,
+}
+
+#[derive(Debug, PartialEq)]
+struct Artifact {
+    kind: Kind,
+    from: PathBuf,
+    to: PathBuf,
+}
+
+/// Everything to copy from `from` to `to`.
+fn plan(from: &Layout, to: &Layout) -> Vec<Artifact> {
+    let artifact = |kind, from, to| Artifact { kind, from, to };
+    let (from_storage, to_storage) = (from.path(&from.storage_dir), to.path(&to.storage_dir));
+    let (from_config, to_config) = (from.path(&from.config_dir), to.path(&to.config_dir));
+    let (from_script, to_script) = (from.path(&from.script_dir), to.path(&to.script_dir));
+    let mut artifacts = vec![
+        artifact(
+            Kind::ConsensusDb,
+            from_storage.join(CONSENSUS_DB_NAME),
+            to_storage.join(CONSENSUS_DB_NAME),
+        ),
+        artifact(
+            Kind::Dir,
+            from_storage.join(QUORUM_STORE_DB_NAME),
+            to_storage.join(QUORUM_STORE_DB_NAME),
+        ),
+        artifact(Kind::Dir, from_storage.join("rand_db"), to_storage.join("rand_db")),
+        artifact(Kind::Dir, from_storage.join("api_jobs"), to_storage.join("api_jobs")),
+        artifact(
+            Kind::File,
+            from_storage.join("api_route_usage.json"),
+            to_storage.join("api_route_usage.json"),
+        ),
+        artifact(Kind::Dir, from.path(&from.reth_dir), to.path(&to.reth_dir)),
+        artifact(Kind::Key, from.path(&from.secure_storage), to.path(&to.secure_storage)),
+        artifact(Kind::Key, from.path(&from.identity), to.path(&to.identity)),
+        artifact(
+            Kind::File,
+            from_config.join("identity.public.yaml"),
+            to_config.join("identity.public.yaml"),
+        ),
+        artifact(Kind::File, from_config.join("waypoint.txt"), to_config.join("waypoint.txt")),
+    ];
+    artifacts.extend(
+        CONFIG_FILES
+            .iter()
+            .map(|name| artifact(Kind::Config, from_config.join(name), to_config.join(name))),
+    );
+    artifacts.extend(
+        SCRIPTS
+            .iter()
+            .map(|name| artifact(Kind::Config, from_script.join(name), to_script.join(name))),
+    );
+    artifacts
+}
+
+/// Replaces every old path in `text` with its new location. A path only matches up to a path
+/// boundary, so `/node/data` is not rewritten inside `/node/data2`.
+fn rewrite_paths(text: &str, relocations: &[(PathBuf, PathBuf)]) -> String {
+    let relocations: Vec<(String, String)> = relocations
+        .iter()
+        .map(|(from, to)| (from.display().to_string(), to.display().to_string()))
+        .filter(|(from, _)| !from.is_empty())
+        .collect();
+    let is_path_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.');
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    'scan: while let Some(c) = rest.chars().next() {
+        let at_boundary = !out.chars().next_back().is_some_and(is_path_char);
+        if at_boundary {
+            for (from, to) in &relocations {
+                if let Some(tail) = rest.strip_prefix(from.as_str()) {
+                    if !tail.chars().next().is_some_and(is_path_char) {
+                        out.push_str(to);
+                        rest = tail;
+                        continue 'scan;
+                    }
+                }
+            }
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+impl Executable for MigrateCommand {
+    fn execute(self, output: OutputFormat) -> Result<(), anyhow::Error> {
+        let from = self.from.ok_or_else(|| MissingOption::new("from", "GRAVITY_DEPLOY_PATH"))?;
+        let from = fs::canonicalize(&from)
+            .with_context(|| format!("Deployment directory {from} does not exist"))?;
+        let from = Layout::deploy(from);
+        let to = Layout::load(&self.to)?;
+        if to.root == from.root {
+            return Err(anyhow!("--to must describe a different directory than --from"));
+        }
+        ensure_stopped(&from.path(&from.script_dir).join("node.pid"))?;
+
+        let artifacts: Vec<Artifact> =
+            plan(&from, &to).into_iter().filter(|a| a.from.exists()).collect();
+        if let Some(existing) = artifacts.iter().find(|a| a.to.exists()) {
+            return Err(anyhow!(
+                "{} already exists, refusing to overwrite it",
+                existing.to.display()
+            ));
+        }
+        progress!(output, "Migrating {} -> {}", from.root.display(), to.root.display());
+        for artifact in &artifacts {
+            progress!(output, "  {} -> {}", artifact.from.display(), artifact.to.display());
+        }
+        let not_migrated = unmigrated(&from, &artifacts)?;
+        for left_out in &not_migrated {
+            progress!(output, "  not migrated: {}", left_out.display());
+        }
+        let result = MigrateResult {
+            from: from.root.clone(),
+            to: to.root.clone(),
+            dry_run: self.dry_run,
+            migrated: artifacts.iter().map(|a| [a.from.clone(), a.to.clone()]).collect(),
+            not_migrated,
+        };
+        if self.dry_run {
+            progress!(output, "Dry run, nothing was copied.");
+            return output.emit(&result);
+        }
+
+        let relocations = from.relocations(&to);
+        let mut created = Vec::new();
+        let migrated = artifacts.iter().try_for_each(|artifact| {
+            migrate(artifact, &relocations, &mut created, output)
+                .with_context(|| format!("Failed to migrate {}", artifact.from.display()))
+        });
+        if let Err(e) = migrated {
+            rollback(&created, output);
+            return Err(e);
+        }
+        progress!(output, "Migrated {} items to {}.", artifacts.len(), to.root.display());
+        progress!(
+            output,
+            "{} was left untouched; start the old binary from it to roll back.",
+            from.root.display()
+        );
+        output.emit(&result)
+    }
+}
+
+/// Refuses to copy the state of a running node; RocksDB files copied mid-write are unusable.
+fn ensure_stopped(pid_path: &Path) -> Result<(), anyhow::Error> {
+    let Ok(pid) = fs::read_to_string(pid_path) else { return Ok(()) };
+    let pid = pid.trim();
+    if !pid.is_empty() && Command::new("ps").arg("-p").arg(pid).output()?.status.success() {
+        return Err(anyhow!(
+            "Node is still running with PID {pid}, stop it first with `gravity-cli node stop`"
+        ));
+    }
+    Ok(())
+}
+
+/// Entries of the storage directory the plan doesn't cover, so operators can move them by hand.
+fn unmigrated(from: &Layout, artifacts: &[Artifact]) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let storage = from.path(&from.storage_dir);
+    let Ok(entries) = fs::read_dir(&storage) else { return Ok(Vec::new()) };
+    let mut left_out = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !artifacts.iter().any(|a| a.from == path) {
+            left_out.push(path);
+        }
+    }
+    left_out.sort();
+    Ok(left_out)
+}
+
+fn migrate(
+    artifact: &Artifact,
+    relocations: &[(PathBuf, PathBuf)],
+    created: &mut Vec<PathBuf>,
+    output: OutputFormat,
+) -> Result<(), anyhow::Error> {
+    let (from, to) = (&artifact.from, &artifact.to);
+    if let Some(parent) = to.parent() {
+        // Remember the outermost directory created, so a rollback removes all of it.
+        let mut missing = parent;
+        while let Some(grandparent) = missing.parent().filter(|p| !p.exists()) {
+            missing = grandparent;
+        }
+        if !parent.exists() {
+            fs::create_dir_all(parent)?;
+            created.push(missing.to_path_buf());
+        }
+    }
+    created.push(to.clone());
+    match artifact.kind {
+        Kind::ConsensusDb => {
+            copy_dir(from, to)?;
+            verify_dir(from, to)?;
+            upgrade_consensus_db(to, output)?;
+        }
+        Kind::Dir => {
+            copy_dir(from, to)?;
+            verify_dir(from, to)?;
+        }
+        Kind::File => {
+            fs::copy(from, to)?;
+            verify_size(from, to)?;
+        }
+        Kind::Key => {
+            fs::copy(from, to)?;
+            if sha256(from)? != sha256(to)? {
+                return Err(anyhow!("checksum of the copy differs"));
+            }
+        }
+        Kind::Config => {
+            let contents = fs::read_to_string(from)?;
+            fs::write(to, rewrite_paths(&contents, relocations))?;
+            fs::set_permissions(to, fs::metadata(from)?.permissions())?;
+        }
+    }
+    Ok(())
+}
+
+/// Opens the copied ConsensusDB with this build's schema, creating the column families it is
+/// missing, and checks the committed head is still readable.
+fn upgrade_consensus_db(path: &Path, output: OutputFormat) -> Result<(), anyhow::Error> {
+    let storage_dir = path.parent().unwrap_or(path).to_path_buf();
+    // Opening panics instead of returning an error.
+    let head = panic::catch_unwind(AssertUnwindSafe(|| {
+        let consensus_db = ConsensusDB::new(&storage_dir, &PathBuf::new());
+        DbReader::get_latest_ledger_info(&consensus_db).map(|li| {
+            let li = li.ledger_info();
+            (li.epoch(), li.round(), li.block_number())
+        })
+    }))
+    .map_err(|_| anyhow!("the copied ConsensusDB could not be opened"))?
+    .map_err(|e| anyhow!("the copied ConsensusDB has no readable ledger info: {e:?}"))?;
+    progress!(
+        output,
+        "  ConsensusDB upgraded, committed head: epoch {}, round {}, block {}",
+        head.0,
+        head.1,
+        head.2
+    );
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), anyhow::Error> {
+    fs::create_dir(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let (source, target) = (entry.path(), to.join(entry.file_name()));
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&source, &target)?;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(fs::read_link(&source)?, &target)?;
+        } else {
+            fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Every file of `from` exists in `to` with the same size.
+fn verify_dir(from: &Path, to: &Path) -> Result<(), anyhow::Error> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let (source, target) = (entry.path(), to.join(entry.file_name()));
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            verify_dir(&source, &target)?;
+        } else if !file_type.is_symlink() {
+            verify_size(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn verify_size(from: &Path, to: &Path) -> Result<(), anyhow::Error> {
+    let expected = fs::metadata(from)?.len();
+    let actual = fs::metadata(to).map(|m| m.len()).unwrap_or(0);
+    if expected != actual {
+        return Err(anyhow!(
+            "{} has {actual} bytes after copying, expected {expected}",
+            to.display()
+        ));
+    }
+    Ok(())
+}
+
+fn sha256(path: &Path) -> Result<Vec<u8>, anyhow::Error> {
+    Ok(Sha256::digest(fs::read(path)?).to_vec())
+}
+
+/// Removes everything the migration created, newest first.
+fn rollback(created: &[PathBuf], output: OutputFormat) {
+    progress!(output, "Rolling back, removing the partial copy...");
+    for path in created.iter().rev() {
+        let removed = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+        if let Err(e) = removed {
+            if path.exists() {
+                eprintln!("  failed to remove {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(toml: &str) -> Layout {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn rewrites_paths_on_boundaries() {
+        let from = Layout::deploy("/srv/node".into());
+        let to = layout(
+            r#"
+            root = "/opt/node"
+            storage_dir = "db"
+            reth_dir = "db/reth"
+            secure_storage = "keys/secure_storage.json"
+            identity = "keys/identity.yaml"
+            "#,
+        );
+        let text = "data_dir: \"/srv/node/data\"\n\
+                    path: /srv/node/data/secure_storage.json\n\
+                    datadir: /srv/node/data/reth\n\
+                    log: /srv/node/consensus_log/validator.log\n\
+                    other: /srv/node2/data\n";
+        assert_eq!(
+            rewrite_paths(text, &from.relocations(&to)),
+            "data_dir: \"/opt/node/db\"\n\
+             path: /opt/node/keys/secure_storage.json\n\
+             datadir: /opt/node/db/reth\n\
+             log: /opt/node/consensus_log/validator.log\n\
+             other: /srv/node2/data\n"
+        );
+    }
+
+    #[test]
+    fn plans_key_relocation() {
+        let from = Layout::deploy("/srv/node".into());
+        let to = layout(
+            r#"
+            root = "/opt/node"
+            identity = "/secrets/identity.yaml"
+            "#,
+        );
+        let artifacts = plan(&from, &to);
+        let identity = artifacts.iter().find(|a| a.to.ends_with("identity.yaml")).unwrap();
+        assert_eq!(identity.kind, Kind::Key);
+        assert_eq!(identity.from, PathBuf::from("/srv/node/config/identity.yaml"));
+        assert_eq!(identity.to, PathBuf::from("/secrets/identity.yaml"));
+        assert!(artifacts.iter().any(|a| a.kind == Kind::ConsensusDb &&
+            a.to == Path::new("/opt/node/data").join(CONSENSUS_DB_NAME)));
+    }
+}
@@ -1,9 +1,22 @@
+pub mod config;
+mod logs;
+mod migrate;
+mod perf_report;
+mod schedule;
 mod start;
 mod stop;
 
 use clap::{Parser, Subcommand};
 
-use crate::node::{start::StartCommand, stop::StopCommand};
+use crate::{
+    command::Executable,
+    node::{
+        config::ConfigCommand, logs::LogsCommand, migrate::MigrateCommand,
+        perf_report::PerfReportCommand, schedule::ScheduleCommand, start::StartCommand,
+        stop::StopCommand,
+    },
+    output::OutputFormat,
+};
 
 #[derive(Debug, Parser)]
 pub struct NodeCommand {
@@ -15,4 +28,28 @@ pub struct NodeCommand {
 pub enum SubCommands {
     Start(StartCommand),
     Stop(StopCommand),
+    /// Stream the node's log file through the admin API
+    Logs(LogsCommand),
+    /// Run a maintenance command right after the next epoch transition
+    Schedule(ScheduleCommand),
+    /// Inspect the running node's config
+    Config(ConfigCommand),
+    /// Copy a stopped node's state into a new directory layout and upgrade its ConsensusDB
+    Migrate(MigrateCommand),
+    /// Profile the node for a while and write a report of where the time went
+    PerfReport(PerfReportCommand),
+}
+
+impl Executable for NodeCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::Start(start_cmd) => start_cmd.execute(format),
+            SubCommands::Stop(stop_cmd) => stop_cmd.execute(format),
+            SubCommands::Logs(logs_cmd) => logs_cmd.execute(format),
+            SubCommands::Schedule(schedule_cmd) => schedule_cmd.execute(format),
+            SubCommands::Config(config_cmd) => config_cmd.execute(format),
+            SubCommands::Migrate(migrate_cmd) => migrate_cmd.execute(format),
+            SubCommands::PerfReport(perf_report_cmd) => perf_report_cmd.execute(format),
+        }
+    }
 }
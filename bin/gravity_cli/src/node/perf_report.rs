@@ -0,0 +1,648 @@
+use clap::{Parser, ValueEnum};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    command::Executable,
+    i18n::MissingOption,
+    output::{progress, OutputFormat},
+    util::{parse_duration, ErrorResponse, API_V1},
+};
+
+/// Longest window the node's commit history and chain stats can cover.
+const MAX_DURATION: Duration = Duration::from_secs(3600);
+/// Interval between runtime samples during the window.
+const RUNTIME_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+/// Length of each runtime sample, taken by the node.
+const RUNTIME_SAMPLE_MS: u64 = 1000;
+/// Most commits `/consensus/commit_history` returns.
+const COMMIT_HISTORY_LIMIT: usize = 1000;
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default)]
+pub enum ReportFormat {
+    /// HTML page showing the flamegraphs inline
+    #[default]
+    Html,
+    /// Markdown, linking to the flamegraph files
+    Markdown,
+}
+
+#[derive(Debug, Parser)]
+pub struct PerfReportCommand {
+    /// Node API address and port (e.g., 127.0.0.1:1024)
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+
+    /// Admin API address, if the node serves admin routes on their own listener
+    /// (default: --api-url)
+    #[clap(long, env = "GRAVITY_ADMIN_URL")]
+    pub admin_url: Option<String>,
+
+    /// Length of the profiled window, e.g. 90s, 5m (at most 1h)
+    #[clap(long, default_value = "60s")]
+    pub duration: String,
+
+    /// CPU samples per second while profiling
+    #[clap(long, default_value_t = 99)]
+    pub frequency: i32,
+
+    /// Report format
+    #[clap(long, value_enum, default_value = "html")]
+    pub format: ReportFormat,
+
+    /// Directory to write the report and profiles to (default: ./perf-report-<unix time>)
+    #[clap(long)]
+    pub out_dir: Option<PathBuf>,
+
+    /// Number of entries in each "top" table
+    #[clap(long, default_value_t = 10)]
+    pub top: usize,
+}
+
+/// Result of `node perf-report` with `--output json`.
+#[derive(Debug, Serialize)]
+struct PerfReportResult {
+    report: PathBuf,
+    cpu_flamegraph: PathBuf,
+    heap_flamegraph: Option<PathBuf>,
+    start_epoch: u64,
+    start_block: u64,
+    commits: usize,
+}
+
+#[derive(Deserialize, Debug)]
+struct LedgerInfoResponse {
+    epoch: u64,
+    round: u64,
+    block_number: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct CommitEntry {
+    epoch: u64,
+    round: u64,
+    block_number: u64,
+    interval_ms: Option<f64>,
+    blocks: Option<u64>,
+    skipped_rounds: Option<u64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CommitHistoryResponse {
+    commits: Vec<CommitEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChainStatsResponse {
+    blocks: u64,
+    transactions: u64,
+    tps: f64,
+    avg_commit_latency_ms: Option<f64>,
+    p99_commit_latency_ms: Option<f64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RuntimeMetricsResponse {
+    num_alive_tasks: usize,
+    global_queue_depth: usize,
+    workers: Vec<WorkerMetrics>,
+}
+
+#[derive(Deserialize, Debug)]
+struct WorkerMetrics {
+    worker: usize,
+    busy_ratio: f64,
+}
+
+#[derive(Deserialize, Debug)]
+struct RuntimeInfoResponse {
+    rocksdb: Option<RocksDbInfo>,
+    jemalloc: Option<JemallocInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RocksDbInfo {
+    version: Option<String>,
+    options_file: String,
+    db_options: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct JemallocInfo {
+    allocated_bytes: usize,
+    resident_bytes: usize,
+}
+
+/// A frame of a flamegraph with the samples of all the stacks it appears in.
+#[derive(Debug, PartialEq)]
+struct HotFrame {
+    name: String,
+    samples: u64,
+    percent: f64,
+}
+
+/// A table of the report, rendered the same way in both formats.
+struct Table {
+    title: &'static str,
+    note: String,
+    headers: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+impl Executable for PerfReportCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+/// Frames of an inferno flamegraph SVG, summed by name and sorted by samples. Each frame is a
+/// `<title>name (1,234 samples, 5.67%)</title>`; the root frame `all` is left out.
+fn hot_frames(svg: &str) -> Vec<HotFrame> {
+    let mut frames: HashMap<String, (u64, f64)> = HashMap::new();
+    for title in svg.split("<title>").skip(1) {
+        let Some(title) = title.split("</title>").next() else { continue };
+        let Some((name, stats)) = title.rsplit_once(" (") else { continue };
+        let Some((samples, percent)) = stats.trim_end_matches(')').split_once(", ") else {
+            continue;
+        };
+        // The count is followed by its unit, `samples` for CPU and `bytes` for heap profiles.
+        let count = samples.split(' ').next().unwrap_or_default().replace(',', "");
+        let Ok(samples) = count.parse::<u64>() else { continue };
+        let percent = percent.trim_end_matches('%').parse::<f64>().unwrap_or_default();
+        let name = unescape_xml(name);
+        if name == "all" {
+            continue;
+        }
+        let entry = frames.entry(name).or_default();
+        entry.0 += samples;
+        entry.1 += percent;
+    }
+    let mut frames: Vec<HotFrame> = frames
+        .into_iter()
+        .map(|(name, (samples, percent))| HotFrame { name, samples, percent })
+        .collect();
+    frames.sort_by(|a, b| b.samples.cmp(&a.samples).then_with(|| a.name.cmp(&b.name)));
+    frames
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn format_mib(bytes: usize) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+impl PerfReportCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let api_url = self
+            .api_url
+            .as_deref()
+            .map(Self::normalize_url)
+            .ok_or_else(|| MissingOption::new("api-url", "GRAVITY_SERVER_URL"))?;
+        let admin_url =
+            self.admin_url.as_deref().map(Self::normalize_url).unwrap_or_else(|| api_url.clone());
+        let duration = parse_duration(&self.duration)?;
+        if duration.is_zero() || duration > MAX_DURATION {
+            return Err(anyhow::anyhow!(
+                "--duration must be between 1s and {}s",
+                MAX_DURATION.as_secs()
+            ));
+        }
+        let started_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let out_dir = self
+            .out_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("perf-report-{started_at}")));
+        std::fs::create_dir_all(&out_dir)?;
+
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .timeout(Duration::from_secs(60))
+            .build()?;
+
+        // 1. Baseline
+        progress!(format, "1. Reading baseline from {api_url}...");
+        let start: LedgerInfoResponse =
+            get_json(&client, &format!("{api_url}{API_V1}/consensus/latest_ledger_info")).await?;
+        let runtime_before: Option<RuntimeInfoResponse> =
+            optional(get_json(&client, &format!("{admin_url}/admin/runtime")).await);
+        progress!(
+            format,
+            "   Epoch {} round {} block {}\n",
+            start.epoch,
+            start.round,
+            start.block_number
+        );
+
+        // 2. Profile the window
+        progress!(format, "2. Profiling for {}s at {} Hz...", duration.as_secs(), self.frequency);
+        let cpu_url = format!("{admin_url}/cpu_prof");
+        post(&client, &cpu_url, json!({ "enable": true, "frequency": self.frequency })).await?;
+        let mut runtime_samples = Vec::new();
+        let deadline = Instant::now() + duration;
+        while Instant::now() + Duration::from_millis(RUNTIME_SAMPLE_MS) < deadline {
+            let url = format!("{admin_url}/debug/runtime?sample_ms={RUNTIME_SAMPLE_MS}");
+            match get_json::<RuntimeMetricsResponse>(&client, &url).await {
+                Ok(sample) => runtime_samples.push(sample),
+                Err(e) => eprintln!("   Warning: {e}"),
+            }
+            let next = (Instant::now() + RUNTIME_SAMPLE_INTERVAL).min(deadline);
+            tokio::time::sleep_until(next.into()).await;
+        }
+        tokio::time::sleep_until(deadline.into()).await;
+        let cpu_svg = post(&client, &cpu_url, json!({ "enable": false, "format": "flamegraph" }))
+            .await?
+            .text()
+            .await?;
+        std::fs::write(out_dir.join("cpu.svg"), &cpu_svg)?;
+        progress!(format, "   CPU profile: {} runtime samples taken\n", runtime_samples.len());
+
+        // 3. Collect the window's timeline and stats
+        progress!(format, "3. Collecting round timeline, heap profile and DB stats...");
+        let history: CommitHistoryResponse = get_json(
+            &client,
+            &format!("{api_url}{API_V1}/consensus/commit_history?limit={COMMIT_HISTORY_LIMIT}"),
+        )
+        .await?;
+        let commits: Vec<CommitEntry> = history
+            .commits
+            .into_iter()
+            .filter(|commit| commit.block_number > start.block_number)
+            .collect();
+        let stats: Option<ChainStatsResponse> = optional(
+            get_json(&client, &format!("{api_url}{API_V1}/stats/chain?window={}s", duration.as_secs()))
+                .await,
+        );
+        let heap_svg = match get(&client, &format!("{admin_url}/mem_prof/flamegraph")).await {
+            Ok(response) => Some(response.text().await?),
+            Err(e) => {
+                progress!(format, "   Heap profile unavailable: {e}");
+                None
+            }
+        };
+        if let Some(svg) = &heap_svg {
+            std::fs::write(out_dir.join("heap.svg"), svg)?;
+        }
+        let runtime_after: Option<RuntimeInfoResponse> =
+            optional(get_json(&client, &format!("{admin_url}/admin/runtime")).await);
+        progress!(format, "   {} commits in the window\n", commits.len());
+
+        // 4. Render the report
+        progress!(format, "4. Writing report...");
+        let mut summary = vec![
+            vec!["Window".to_string(), format!("{}s", duration.as_secs())],
+            vec![
+                "Start".to_string(),
+                format!("epoch {} round {} block {}", start.epoch, start.round, start.block_number),
+            ],
+            vec!["Commits".to_string(), commits.len().to_string()],
+            vec![
+                "Skipped rounds".to_string(),
+                commits.iter().filter_map(|c| c.skipped_rounds).sum::<u64>().to_string(),
+            ],
+        ];
+        if let Some(stats) = &stats {
+            summary.push(vec!["Blocks".to_string(), stats.blocks.to_string()]);
+            summary.push(vec!["Transactions".to_string(), stats.transactions.to_string()]);
+            summary.push(vec!["TPS".to_string(), format!("{:.1}", stats.tps)]);
+            if let (Some(avg), Some(p99)) =
+                (stats.avg_commit_latency_ms, stats.p99_commit_latency_ms)
+            {
+                summary.push(vec![
+                    "Commit latency".to_string(),
+                    format!("avg {avg:.0} ms, p99 {p99:.0} ms"),
+                ]);
+            }
+        }
+
+        let mut slow_commits: Vec<&CommitEntry> =
+            commits.iter().filter(|c| c.interval_ms.is_some()).collect();
+        slow_commits.sort_by(|a, b| {
+            b.interval_ms.unwrap_or_default().total_cmp(&a.interval_ms.unwrap_or_default())
+        });
+        let slow_commits = Table {
+            title: "Slowest commits",
+            note: "Time since the previous commit; skipped rounds point at timeouts rather than \
+                   slow execution."
+                .to_string(),
+            headers: &["Epoch", "Round", "Block", "Interval", "Blocks", "Skipped rounds"],
+            rows: slow_commits
+                .into_iter()
+                .take(self.top)
+                .map(|c| {
+                    vec![
+                        c.epoch.to_string(),
+                        c.round.to_string(),
+                        c.block_number.to_string(),
+                        format!("{:.0} ms", c.interval_ms.unwrap_or_default()),
+                        c.blocks.unwrap_or(1).to_string(),
+                        c.skipped_rounds.unwrap_or_default().to_string(),
+                    ]
+                })
+                .collect(),
+        };
+
+        let cpu_frames = Table {
+            title: "CPU hot spots",
+            note: "Frames by samples over all the stacks they appear in, see cpu.svg.".to_string(),
+            headers: &["Frame", "Samples", "Share"],
+            rows: hot_frames(&cpu_svg)
+                .into_iter()
+                .take(self.top)
+                .map(|f| vec![f.name, f.samples.to_string(), format!("{:.2}%", f.percent)])
+                .collect(),
+        };
+
+        let mut worker_busy: HashMap<usize, (f64, f64, usize)> = HashMap::new();
+        for sample in &runtime_samples {
+            for worker in &sample.workers {
+                let entry = worker_busy.entry(worker.worker).or_default();
+                entry.0 += worker.busy_ratio;
+                entry.1 = entry.1.max(worker.busy_ratio);
+                entry.2 += 1;
+            }
+        }
+        let mut worker_busy: Vec<_> = worker_busy.into_iter().collect();
+        worker_busy.sort_by(|a, b| (b.1 .0 / b.1 .2 as f64).total_cmp(&(a.1 .0 / a.1 .2 as f64)));
+        let max_queue = runtime_samples.iter().map(|s| s.global_queue_depth).max();
+        let max_tasks = runtime_samples.iter().map(|s| s.num_alive_tasks).max();
+        let runtime = Table {
+            title: "Busiest runtime workers",
+            note: format!(
+                "API runtime workers over {} samples; peak global queue depth {}, peak alive \
+                 tasks {}.",
+                runtime_samples.len(),
+                max_queue.map_or("n/a".to_string(), |d| d.to_string()),
+                max_tasks.map_or("n/a".to_string(), |t| t.to_string()),
+            ),
+            headers: &["Worker", "Average busy", "Peak busy"],
+            rows: worker_busy
+                .into_iter()
+                .take(self.top)
+                .map(|(worker, (sum, max, n))| {
+                    vec![
+                        worker.to_string(),
+                        format!("{:.0}%", sum / n as f64 * 100.0),
+                        format!("{:.0}%", max * 100.0),
+                    ]
+                })
+                .collect(),
+        };
+
+        let jemalloc = |runtime: &Option<RuntimeInfoResponse>| {
+            runtime
+                .as_ref()
+                .and_then(|r| r.jemalloc.as_ref())
+                .map(|j| (j.allocated_bytes, j.resident_bytes))
+        };
+        let mut heap_note = match (jemalloc(&runtime_before), jemalloc(&runtime_after)) {
+            (Some(before), Some(after)) => format!(
+                "Allocated {} -> {}, resident {} -> {} over the window. ",
+                format_mib(before.0),
+                format_mib(after.0),
+                format_mib(before.1),
+                format_mib(after.1),
+            ),
+            _ => String::new(),
+        };
+        heap_note.push_str(if heap_svg.is_some() {
+            "Live bytes by allocation site, see heap.svg."
+        } else {
+            "Heap profiling is not enabled on this node (build with api/jemalloc-profiling)."
+        });
+        let heap = Table {
+            title: "Heap hot spots",
+            note: heap_note,
+            headers: &["Frame", "Bytes", "Share"],
+            rows: heap_svg
+                .as_deref()
+                .map(hot_frames)
+                .unwrap_or_default()
+                .into_iter()
+                .take(self.top)
+                .map(|f| vec![f.name, f.samples.to_string(), format!("{:.2}%", f.percent)])
+                .collect(),
+        };
+
+        let db = match runtime_after.as_ref().and_then(|r| r.rocksdb.as_ref()) {
+            Some(rocksdb) => {
+                let mut options: Vec<_> = rocksdb.db_options.iter().collect();
+                options.sort();
+                Table {
+                    title: "ConsensusDB",
+                    note: format!(
+                        "RocksDB {} options from {}.",
+                        rocksdb.version.as_deref().unwrap_or("(unknown version)"),
+                        rocksdb.options_file
+                    ),
+                    headers: &["Option", "Value"],
+                    rows: options
+                        .into_iter()
+                        .filter(|(key, _)| {
+                            key.starts_with("max_background") ||
+                                key.contains("write_buffer") ||
+                                key.contains("cache") ||
+                                key.starts_with("max_open_files")
+                        })
+                        .map(|(key, value)| vec![key.clone(), value.clone()])
+                        .collect(),
+                }
+            }
+            None => Table {
+                title: "ConsensusDB",
+                note: "The node did not report its RocksDB options.".to_string(),
+                headers: &["Option", "Value"],
+                rows: Vec::new(),
+            },
+        };
+
+        let summary = Table {
+            title: "Summary",
+            note: format!("Node {api_url}, window starting at unix time {started_at}."),
+            headers: &["", ""],
+            rows: summary,
+        };
+        let tables = [summary, slow_commits, cpu_frames, runtime, heap, db];
+        let path = match self.format {
+            ReportFormat::Html => {
+                let path = out_dir.join("report.html");
+                std::fs::write(&path, render_html(&tables, heap_svg.is_some()))?;
+                path
+            }
+            ReportFormat::Markdown => {
+                let path = out_dir.join("report.md");
+                std::fs::write(&path, render_markdown(&tables, heap_svg.is_some()))?;
+                path
+            }
+        };
+        progress!(format, "   Report written to {}", path.display());
+        format.emit(&PerfReportResult {
+            report: path,
+            cpu_flamegraph: out_dir.join("cpu.svg"),
+            heap_flamegraph: heap_svg.is_some().then(|| out_dir.join("heap.svg")),
+            start_epoch: start.epoch,
+            start_block: start.block_number,
+            commits: commits.len(),
+        })
+    }
+}
+
+fn render_markdown(tables: &[Table], with_heap: bool) -> String {
+    let mut out = String::from("# Gravity node performance report\n");
+    for table in tables {
+        let _ = write!(out, "\n## {}\n\n{}\n\n", table.title, table.note);
+        if table.rows.is_empty() {
+            out.push_str("_No data._\n");
+            continue;
+        }
+        let _ = writeln!(out, "| {} |", table.headers.join(" | "));
+        let _ = writeln!(out, "|{}", " --- |".repeat(table.headers.len()));
+        for row in &table.rows {
+            let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+    }
+    out.push_str("\n## Flamegraphs\n\n- [CPU](cpu.svg)\n");
+    if with_heap {
+        out.push_str("- [Heap](heap.svg)\n");
+    }
+    out
+}
+
+fn render_html(tables: &[Table], with_heap: bool) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+         <title>Gravity node performance report</title>\n<style>\
+         body{font-family:sans-serif;margin:2em}table{border-collapse:collapse}\
+         td,th{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+         td{font-family:monospace}</style></head><body>\n\
+         <h1>Gravity node performance report</h1>\n",
+    );
+    for table in tables {
+        let _ = writeln!(
+            out,
+            "<h2>{}</h2>\n<p>{}</p>",
+            escape_html(table.title),
+            escape_html(&table.note)
+        );
+        if table.rows.is_empty() {
+            out.push_str("<p><em>No data.</em></p>\n");
+            continue;
+        }
+        out.push_str("<table><tr>");
+        for header in table.headers {
+            let _ = write!(out, "<th>{}</th>", escape_html(header));
+        }
+        out.push_str("</tr>\n");
+        for row in &table.rows {
+            out.push_str("<tr>");
+            for cell in row {
+                let _ = write!(out, "<td>{}</td>", escape_html(cell));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    // Flamegraphs are interactive SVGs, which only run their scripts when embedded as objects.
+    out.push_str("<h2>CPU flamegraph</h2>\n<object data=\"cpu.svg\" type=\"image/svg+xml\" width=\"100%\"></object>\n");
+    if with_heap {
+        out.push_str("<h2>Heap flamegraph</h2>\n<object data=\"heap.svg\" type=\"image/svg+xml\" width=\"100%\"></object>\n");
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+/// Logs a failed optional request and carries on without its data.
+fn optional<T>(result: Result<T, anyhow::Error>) -> Option<T> {
+    result.map_err(|e| eprintln!("   Skipping: {e}")).ok()
+}
+
+async fn get(client: &reqwest::Client, url: &str) -> Result<reqwest::Response, anyhow::Error> {
+    check(url, client.get(url).send().await?).await
+}
+
+async fn get_json<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<T, anyhow::Error> {
+    Ok(get(client, url).await?.json::<T>().await?)
+}
+
+async fn post(
+    client: &reqwest::Client,
+    url: &str,
+    body: serde_json::Value,
+) -> Result<reqwest::Response, anyhow::Error> {
+    check(url, client.post(url).json(&body).send().await?).await
+}
+
+async fn check(url: &str, response: reqwest::Response) -> Result<reqwest::Response, anyhow::Error> {
+    let status_code = response.status();
+    if status_code.is_success() {
+        return Ok(response);
+    }
+    let error_msg = match response.json::<ErrorResponse>().await {
+        Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
+        Err(_) => format!("HTTP {status_code}"),
+    };
+    Err(anyhow::anyhow!("Request to {url} failed: {error_msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("60s").unwrap(), Duration::from_secs(60));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+        assert!(parse_duration("1d").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn sums_flamegraph_frames_by_name() {
+        let svg = "<svg><g><title>all (1,000 samples, 100%)</title><rect/></g>\
+                   <g><title>tokio::run (600 samples, 60.00%)</title><rect/></g>\
+                   <g><title>Vec&lt;u8&gt;::push (150 samples, 15.00%)</title><rect/></g>\
+                   <g><title>Vec&lt;u8&gt;::push (50 samples, 5.00%)</title><rect/></g>\
+                   <g><title>main (1 sample, 0.10%)</title><rect/></g></svg>";
+        assert_eq!(
+            hot_frames(svg),
+            vec![
+                HotFrame { name: "tokio::run".into(), samples: 600, percent: 60.0 },
+                HotFrame { name: "Vec<u8>::push".into(), samples: 200, percent: 20.0 },
+                HotFrame { name: "main".into(), samples: 1, percent: 0.1 },
+            ]
+        );
+    }
+}
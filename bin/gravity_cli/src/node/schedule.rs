@@ -0,0 +1,159 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    command::Executable,
+    i18n::MissingOption,
+    output::{progress, OutputFormat},
+    util::{ErrorResponse, API_V1},
+};
+
+#[derive(Debug, Parser)]
+pub struct ScheduleCommand {
+    /// Node API address and port (e.g., 127.0.0.1:1024)
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+
+    /// Run the command right after the next epoch transition completes
+    #[clap(long)]
+    pub at_epoch_boundary: bool,
+
+    /// Shell command to run, e.g. "systemctl restart gravity-node"
+    #[clap(long)]
+    pub run: String,
+
+    /// Seconds between polls of the node's latest ledger info
+    #[clap(long, default_value_t = 5)]
+    pub poll_interval_secs: u64,
+
+    /// Seconds to wait after the new epoch is observed before running the command
+    #[clap(long, default_value_t = 10)]
+    pub settle_secs: u64,
+
+    /// Give up if no epoch transition is observed within this many seconds
+    #[clap(long)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LedgerInfoResponse {
+    epoch: u64,
+    block_number: u64,
+}
+
+/// Result of `node schedule` with `--output json`.
+#[derive(Debug, Serialize)]
+struct ScheduleResult {
+    /// Epoch the command ran in, and the block that started it.
+    epoch: u64,
+    block_number: u64,
+    command: String,
+}
+
+impl Executable for ScheduleCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl ScheduleCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        if !self.at_epoch_boundary {
+            return Err(anyhow::anyhow!(
+                "--at-epoch-boundary is required; it is currently the only supported trigger"
+            ));
+        }
+        let api_url = self
+            .api_url
+            .as_deref()
+            .ok_or_else(|| MissingOption::new("api-url", "GRAVITY_SERVER_URL"))?;
+        let url = format!("{}{API_V1}/consensus/latest_ledger_info", Self::normalize_url(api_url));
+
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        let start = fetch_ledger_info(&client, &url).await?;
+        progress!(
+            format,
+            "Current epoch {} at block {}. Waiting for the transition to epoch {}...",
+            start.epoch,
+            start.block_number,
+            start.epoch + 1
+        );
+
+        let started = Instant::now();
+        let poll_interval = Duration::from_secs(self.poll_interval_secs.max(1));
+        let transition = loop {
+            if let Some(timeout) = self.timeout_secs {
+                if started.elapsed() >= Duration::from_secs(timeout) {
+                    return Err(anyhow::anyhow!(
+                        "No epoch transition observed within {timeout}s, command not run"
+                    ));
+                }
+            }
+            tokio::time::sleep(poll_interval).await;
+            // The node may be briefly unreachable around the transition; keep polling.
+            match fetch_ledger_info(&client, &url).await {
+                Ok(latest) if latest.epoch > start.epoch => break latest,
+                Ok(_) => {}
+                Err(e) => eprintln!("Warning: {e}"),
+            }
+        };
+        progress!(format, "Epoch {} started (block {})", transition.epoch, transition.block_number);
+
+        if self.settle_secs > 0 {
+            progress!(format, "Waiting {}s for the new epoch to settle...", self.settle_secs);
+            tokio::time::sleep(Duration::from_secs(self.settle_secs)).await;
+        }
+
+        progress!(format, "Running: {}", self.run);
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(&self.run)
+            .env("GRAVITY_EPOCH", transition.epoch.to_string())
+            .env("GRAVITY_BLOCK_NUMBER", transition.block_number.to_string())
+            .status()?;
+        if !status.success() {
+            return Err(anyhow::anyhow!("Scheduled command failed with {status}"));
+        }
+        progress!(format, "Scheduled command completed");
+        format.emit(&ScheduleResult {
+            epoch: transition.epoch,
+            block_number: transition.block_number,
+            command: self.run,
+        })
+    }
+}
+
+async fn fetch_ledger_info(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<LedgerInfoResponse, anyhow::Error> {
+    let response = client.get(url).send().await?;
+    let status_code = response.status();
+    if !status_code.is_success() {
+        let error_msg = match response.json::<ErrorResponse>().await {
+            Ok(error_response) => format!("HTTP {}: {}", status_code, error_response.message),
+            Err(_) => format!("HTTP {status_code}"),
+        };
+        return Err(anyhow::anyhow!("Failed to fetch latest ledger info: {error_msg}"));
+    }
+    Ok(response.json::<LedgerInfoResponse>().await?)
+}
@@ -1,7 +1,12 @@
 use clap::Parser;
+use serde::Serialize;
 use std::{fs, path::PathBuf, process::Command};
 
-use crate::command::Executable;
+use crate::{
+    command::Executable,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat},
+};
 
 #[derive(Debug, Parser)]
 pub struct StartCommand {
@@ -10,6 +15,14 @@ pub struct StartCommand {
     pub deploy_path: Option<String>,
 }
 
+/// Result of `node start` with `--output json`.
+#[derive(Debug, Serialize)]
+struct StartResult {
+    script: String,
+    /// PID of the node, once the script has written its PID file.
+    pid: Option<String>,
+}
+
 impl StartCommand {
     /// Check if PID file exists and if the process is still running
     fn check_pid_file(pid_path: &PathBuf) -> Result<(), anyhow::Error> {
@@ -45,24 +58,26 @@ impl StartCommand {
 }
 
 impl Executable for StartCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
-        let deploy_path_str = self.deploy_path.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--deploy-path is required. Set via CLI flag, GRAVITY_DEPLOY_PATH env var, or ~/.gravity/config.toml"
-            )
-        })?;
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let deploy_path_str = self
+            .deploy_path
+            .ok_or_else(|| MissingOption::new("deploy-path", "GRAVITY_DEPLOY_PATH"))?;
         let deploy_path = PathBuf::from(&deploy_path_str);
         let script_path = deploy_path.join("script").join("start.sh");
         let pid_path = deploy_path.join("script").join("node.pid");
 
         if !script_path.exists() {
-            return Err(anyhow::anyhow!("Start script not found: {}", script_path.display()));
+            return Err(CatalogError::new(
+                Msg::StartScriptNotFound,
+                &[("path", &script_path.display())],
+            )
+            .into());
         }
 
         // Check PID file before starting
         Self::check_pid_file(&pid_path)?;
 
-        println!("Starting node from: {}", script_path.display());
+        progress!(format, "Starting node from: {}", script_path.display());
 
         // Use status() instead of output() to avoid waiting for output
         // The start.sh script starts the node in background and returns immediately
@@ -80,18 +95,20 @@ impl Executable for StartCommand {
         std::thread::sleep(std::time::Duration::from_millis(500));
 
         // Verify that PID file was created and process is running
+        let mut pid = None;
         if pid_path.exists() {
             let pid_str = fs::read_to_string(&pid_path)?;
             let pid_str = pid_str.trim();
             if !pid_str.is_empty() {
-                println!("Node started successfully (PID: {pid_str})");
+                progress!(format, "Node started successfully (PID: {pid_str})");
+                pid = Some(pid_str.to_string());
             } else {
-                println!("Node started successfully");
+                progress!(format, "Node started successfully");
             }
         } else {
-            println!("Node started successfully (PID file will be created by the script)");
+            progress!(format, "Node started successfully (PID file will be created by the script)");
         }
 
-        Ok(())
+        format.emit(&StartResult { script: script_path.display().to_string(), pid })
     }
 }
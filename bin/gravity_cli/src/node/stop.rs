@@ -1,7 +1,12 @@
 use clap::Parser;
+use serde::Serialize;
 use std::{path::PathBuf, process::Command};
 
-use crate::command::Executable;
+use crate::{
+    command::Executable,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat},
+};
 
 #[derive(Debug, Parser)]
 pub struct StopCommand {
@@ -10,21 +15,31 @@ pub struct StopCommand {
     pub deploy_path: Option<String>,
 }
 
+/// Result of `node stop` with `--output json`.
+#[derive(Debug, Serialize)]
+struct StopResult {
+    script: String,
+    /// What the stop script printed.
+    output: String,
+}
+
 impl Executable for StopCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
-        let deploy_path_str = self.deploy_path.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--deploy-path is required. Set via CLI flag, GRAVITY_DEPLOY_PATH env var, or ~/.gravity/config.toml"
-            )
-        })?;
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let deploy_path_str = self
+            .deploy_path
+            .ok_or_else(|| MissingOption::new("deploy-path", "GRAVITY_DEPLOY_PATH"))?;
         let deploy_path = PathBuf::from(&deploy_path_str);
         let script_path = deploy_path.join("script").join("stop.sh");
 
         if !script_path.exists() {
-            return Err(anyhow::anyhow!("Stop script not found: {}", script_path.display()));
+            return Err(CatalogError::new(
+                Msg::StopScriptNotFound,
+                &[("path", &script_path.display())],
+            )
+            .into());
         }
 
-        println!("Stopping node from: {}", script_path.display());
+        progress!(format, "Stopping node from: {}", script_path.display());
 
         let output =
             Command::new("bash").arg(script_path.as_os_str()).current_dir(&deploy_path).output()?;
@@ -35,11 +50,14 @@ impl Executable for StopCommand {
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        if !stdout.is_empty() {
+        if !stdout.is_empty() && !format.is_json() {
             print!("{stdout}");
         }
 
-        println!("Node stopped successfully");
-        Ok(())
+        progress!(format, "Node stopped successfully");
+        format.emit(&StopResult {
+            script: script_path.display().to_string(),
+            output: stdout.into_owned(),
+        })
     }
 }
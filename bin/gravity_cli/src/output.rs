@@ -1,6 +1,11 @@
+use alloy_primitives::U256;
+use alloy_rpc_types::eth::TransactionReceipt;
 use clap::ValueEnum;
+use serde::Serialize;
 
-#[derive(Debug, Clone, Copy, ValueEnum, Default)]
+use crate::util::format_ether;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
 pub enum OutputFormat {
     /// Human-readable plain text (default)
     #[default]
@@ -8,3 +13,67 @@ pub enum OutputFormat {
     /// JSON output for scripting
     Json,
 }
+
+impl OutputFormat {
+    pub fn is_json(self) -> bool {
+        self == OutputFormat::Json
+    }
+
+    /// Prints the result of a command with `--output json`; a no-op otherwise, where the
+    /// progress output already told the story.
+    pub fn emit<T: Serialize>(self, result: &T) -> Result<(), anyhow::Error> {
+        if self.is_json() {
+            println!("{}", serde_json::to_string_pretty(result)?);
+        }
+        Ok(())
+    }
+}
+
+/// `println!` for the step-by-step progress of a command, given its [`OutputFormat`]. Silent
+/// with `--output json`, where the command prints a single JSON result through
+/// [`OutputFormat::emit`] instead.
+macro_rules! progress {
+    ($format:expr) => {
+        if !$format.is_json() {
+            println!();
+        }
+    };
+    ($format:expr, $($arg:tt)*) => {
+        if !$format.is_json() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use progress;
+
+/// A confirmed transaction in a JSON result.
+#[derive(Debug, Serialize)]
+pub struct TxSummary {
+    pub tx_hash: String,
+    pub block_number: Option<u64>,
+    pub gas_used: u64,
+    /// Gas cost in ETH.
+    pub cost: String,
+    /// Fields of the event the contract emitted on success, if it emits one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<serde_json::Value>,
+}
+
+impl TxSummary {
+    pub fn new(receipt: &TransactionReceipt) -> Self {
+        Self {
+            tx_hash: format!("{}", receipt.transaction_hash),
+            block_number: receipt.block_number,
+            gas_used: receipt.gas_used,
+            cost: format_ether(
+                U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used),
+            ),
+            event: None,
+        }
+    }
+
+    pub fn with_event(mut self, event: serde_json::Value) -> Self {
+        self.event = Some(event);
+        self
+    }
+}
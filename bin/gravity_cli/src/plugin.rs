@@ -0,0 +1,297 @@
+//! External subcommands: `gravity-cli foo ...` runs a `gravity-cli-foo` executable found in
+//! `~/.gravity/plugins` or on `PATH`, so teams can ship their own commands without forking.
+//!
+//! Before running a plugin the CLI asks for its manifest by invoking it with
+//! `--gravity-plugin-manifest`, which should print one JSON object and exit 0:
+//!
+//! ```json
+//! { "protocol": 1, "version": "0.3.0", "about": "Bridge operations", "flags": ["profile", "output"] }
+//! ```
+//!
+//! The shared flags listed in `flags` (`profile`, `output`, `lang`) are passed on ahead of the
+//! user's arguments. Every plugin, with a manifest or not, also gets the resolved settings as
+//! `GRAVITY_*` variables: the profile's `rpc_url`, `server_url` and `deploy_path` (unless
+//! already set), `GRAVITY_PROFILE`, `GRAVITY_OUTPUT`, `GRAVITY_LANG` and
+//! `GRAVITY_PLUGIN_PROTOCOL`. The plugin's exit code becomes the CLI's.
+
+use crate::{
+    command::Executable,
+    config::{GravityConfig, ProfileConfig},
+    i18n::Lang,
+    output::OutputFormat,
+};
+use anyhow::{anyhow, bail, Context};
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Executable name prefix of plugins.
+pub const PLUGIN_PREFIX: &str = "gravity-cli-";
+/// Version of the manifest handshake and environment this CLI speaks.
+pub const PLUGIN_PROTOCOL: u32 = 1;
+const MANIFEST_FLAG: &str = "--gravity-plugin-manifest";
+/// Shared flags a plugin can ask to receive.
+const SHARED_FLAGS: &[&str] = &["profile", "output", "lang"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PluginManifest {
+    pub protocol: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub about: Option<String>,
+    /// Shared flags the plugin accepts, out of `profile`, `output` and `lang`.
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Plugin {
+    /// Subcommand name, i.e. the executable name without [`PLUGIN_PREFIX`].
+    pub name: String,
+    pub path: PathBuf,
+    /// `None` when the plugin doesn't answer the manifest handshake.
+    pub manifest: Option<PluginManifest>,
+}
+
+/// Settings of this invocation handed to plugins.
+pub struct SharedContext {
+    pub profile_name: Option<String>,
+    pub profile: Option<ProfileConfig>,
+    pub output: OutputFormat,
+    pub lang: Lang,
+}
+
+/// Directories searched for plugins, in order of precedence.
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![GravityConfig::config_dir().join("plugins")];
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+    dirs
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = path.metadata() else { return false };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+/// The subcommand name a plugin executable provides, if `file_name` is one.
+fn plugin_name(file_name: &OsStr) -> Option<&str> {
+    let name = file_name.to_str()?.strip_prefix(PLUGIN_PREFIX)?;
+    let name = name.strip_suffix(std::env::consts::EXE_SUFFIX).unwrap_or(name);
+    (!name.is_empty()).then_some(name)
+}
+
+/// Finds the executable of plugin `name`.
+pub fn find(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{PLUGIN_PREFIX}{name}{}", std::env::consts::EXE_SUFFIX);
+    search_dirs().into_iter().map(|dir| dir.join(&file_name)).find(|path| is_executable(path))
+}
+
+/// Every plugin installed, by name; a name found in several directories resolves like
+/// [`find`] does.
+pub fn discover() -> BTreeMap<String, PathBuf> {
+    let mut plugins = BTreeMap::new();
+    for dir in search_dirs() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Some(name) = plugin_name(&entry.file_name()) {
+                if !plugins.contains_key(name) && is_executable(&path) {
+                    plugins.insert(name.to_string(), path);
+                }
+            }
+        }
+    }
+    plugins
+}
+
+/// Asks the plugin at `path` for its manifest. Plugins that exit non-zero or print no manifest
+/// simply don't take part in the handshake.
+pub fn manifest(path: &Path) -> Option<PluginManifest> {
+    let output = Command::new(path).arg(MANIFEST_FLAG).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    serde_json::from_slice(&output.stdout).ok()
+}
+
+fn value_name<T: ValueEnum>(value: &T) -> String {
+    value.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default()
+}
+
+/// Arguments passed to the plugin: the shared flags it asked for, then the user's arguments.
+fn plugin_args(
+    manifest: Option<&PluginManifest>,
+    context: &SharedContext,
+    user_args: &[String],
+) -> Vec<String> {
+    let mut args = Vec::new();
+    for flag in manifest.map(|m| m.flags.as_slice()).unwrap_or_default() {
+        let value = match flag.as_str() {
+            "profile" => context.profile_name.clone(),
+            "output" => Some(value_name(&context.output)),
+            "lang" => Some(value_name(&context.lang)),
+            _ => None,
+        };
+        if let Some(value) = value {
+            args.push(format!("--{flag}"));
+            args.push(value);
+        }
+    }
+    args.extend(user_args.iter().cloned());
+    args
+}
+
+/// Runs `gravity-cli <name> <args...>` as plugin `name`, exiting with the plugin's exit code
+/// when it fails.
+pub fn run(external: Vec<String>, context: &SharedContext) -> Result<(), anyhow::Error> {
+    let (name, user_args) = external.split_first().context("missing plugin name")?;
+    let path = find(name).ok_or_else(|| {
+        anyhow!(
+            "unrecognized subcommand '{name}': no {PLUGIN_PREFIX}{name} plugin in {} or on PATH",
+            GravityConfig::config_dir().join("plugins").display()
+        )
+    })?;
+    let manifest = manifest(&path);
+    if let Some(manifest) = &manifest {
+        if manifest.protocol > PLUGIN_PROTOCOL {
+            bail!(
+                "plugin {name} needs plugin protocol {}, this gravity-cli speaks {PLUGIN_PROTOCOL}; \
+                 upgrade gravity-cli",
+                manifest.protocol
+            );
+        }
+        for flag in manifest.flags.iter().filter(|f| !SHARED_FLAGS.contains(&f.as_str())) {
+            eprintln!(
+                "{} plugin {name} asks for unknown shared flag --{flag}",
+                "warning:".yellow().bold()
+            );
+        }
+    }
+
+    let mut command = Command::new(&path);
+    command
+        .args(plugin_args(manifest.as_ref(), context, user_args))
+        .env("GRAVITY_PLUGIN_PROTOCOL", PLUGIN_PROTOCOL.to_string())
+        .env("GRAVITY_OUTPUT", value_name(&context.output))
+        .env("GRAVITY_LANG", value_name(&context.lang));
+    if let Some(profile_name) = &context.profile_name {
+        command.env("GRAVITY_PROFILE", profile_name);
+    }
+    if let Some(profile) = &context.profile {
+        for (env, value) in [
+            ("GRAVITY_RPC_URL", &profile.rpc_url),
+            ("GRAVITY_SERVER_URL", &profile.server_url),
+            ("GRAVITY_DEPLOY_PATH", &profile.deploy_path),
+        ] {
+            if let (None, Some(value)) = (std::env::var_os(env), value) {
+                command.env(env, value);
+            }
+        }
+    }
+    let status =
+        command.status().with_context(|| format!("failed to run plugin {}", path.display()))?;
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Parser)]
+pub struct PluginsCommand {}
+
+impl Executable for PluginsCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let plugins: Vec<Plugin> = discover()
+            .into_iter()
+            .map(|(name, path)| {
+                let manifest = manifest(&path);
+                Plugin { name, path, manifest }
+            })
+            .collect();
+
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&plugins)?),
+            OutputFormat::Plain => {
+                if plugins.is_empty() {
+                    println!(
+                        "No plugins found; install {PLUGIN_PREFIX}<name> executables in {} or on \
+                         PATH",
+                        GravityConfig::config_dir().join("plugins").display()
+                    );
+                }
+                for plugin in &plugins {
+                    let version =
+                        plugin.manifest.as_ref().and_then(|m| m.version.as_deref()).unwrap_or("-");
+                    println!("{}  {}  {}", plugin.name.bold(), version, plugin.path.display());
+                    match &plugin.manifest {
+                        Some(manifest) => {
+                            if let Some(about) = &manifest.about {
+                                println!("    {about}");
+                            }
+                            if manifest.protocol > PLUGIN_PROTOCOL {
+                                println!(
+                                    "    {} needs plugin protocol {}",
+                                    "incompatible:".red(),
+                                    manifest.protocol
+                                );
+                            }
+                        }
+                        None => println!("    (no manifest)"),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_plugin_executables() {
+        let exe = std::env::consts::EXE_SUFFIX;
+        assert_eq!(plugin_name(OsStr::new(&format!("gravity-cli-bridge{exe}"))), Some("bridge"));
+        assert_eq!(plugin_name(OsStr::new("gravity-cli-")), None);
+        assert_eq!(plugin_name(OsStr::new("gravity_cli")), None);
+    }
+
+    #[test]
+    fn passes_requested_shared_flags_before_user_args() {
+        let context = SharedContext {
+            profile_name: Some("mainnet".to_string()),
+            profile: None,
+            output: OutputFormat::Json,
+            lang: Lang::Zh,
+        };
+        let manifest = PluginManifest {
+            protocol: 1,
+            flags: vec!["output".to_string(), "profile".to_string()],
+            ..Default::default()
+        };
+        let user_args = vec!["withdraw".to_string(), "--amount".to_string(), "5".to_string()];
+        assert_eq!(
+            plugin_args(Some(&manifest), &context, &user_args),
+            ["--output", "json", "--profile", "mainnet", "withdraw", "--amount", "5"]
+        );
+        assert_eq!(plugin_args(None, &context, &user_args), user_args);
+    }
+}
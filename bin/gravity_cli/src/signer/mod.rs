@@ -1,10 +1,12 @@
 //! Shared "where does the EVM signing key come from?" plumbing for
 //! gravity_cli subcommands that submit on-chain transactions.
 //!
-//! Two sources are supported:
+//! Three sources are supported:
 //!
-//! 1. `--kms <resource>` — Cloud KMS (key never leaves the HSM).
-//! 2. _(default)_        — interactive `rpassword` prompt on stdin.
+//! 1. `--kms <resource>`   — Cloud KMS (key never leaves the HSM).
+//! 2. `--keystore <path>`  — encrypted JSON keystore, password prompted on stdin. Also taken from
+//!    the `keystore` of the config profile when neither flag is given.
+//! 3. _(default)_          — interactive `rpassword` prompt on stdin.
 //!
 //! There is deliberately no "read the hex key from an env var" option: a
 //! plaintext private key in an env var is visible in `/proc/<pid>/environ`,
@@ -37,6 +39,12 @@ use alloy_primitives::Address;
 use alloy_signer::k256::ecdsa::SigningKey;
 use alloy_signer_local::PrivateKeySigner;
 use clap::Args;
+use std::path::PathBuf;
+
+use crate::{
+    config::{expand_home, ProfileConfig},
+    i18n::{CatalogError, Msg},
+};
 
 mod kms;
 pub use kms::GcpKmsSigner;
@@ -55,6 +63,10 @@ pub struct SignerArgs {
     /// means the VM's attached service account (no static credentials).
     #[clap(long, value_name = "RESOURCE")]
     pub kms: Option<String>,
+
+    /// Sign with the key of an encrypted JSON keystore file, prompting for its password.
+    #[clap(long, env = "GRAVITY_KEYSTORE", value_name = "PATH", conflicts_with = "kms")]
+    pub keystore: Option<PathBuf>,
 }
 
 /// Output of [`SignerArgs::resolve`]: a wallet ready for `ProviderBuilder`,
@@ -65,21 +77,40 @@ pub struct ResolvedSigner {
 }
 
 impl SignerArgs {
+    /// Uses the keystore of the config profile when no key source was given.
+    pub fn apply_profile(&mut self, profile: &ProfileConfig) {
+        if self.kms.is_none() && self.keystore.is_none() {
+            self.keystore = profile.keystore.as_deref().map(expand_home);
+        }
+    }
+
     /// Construct the signer described by these args.
     ///
     /// `--kms` makes a network call to KMS to fetch the public key (so the
-    /// address can be derived). The default stdin path blocks on the prompt.
+    /// address can be derived). The keystore and default stdin paths block on
+    /// the prompt.
     pub async fn resolve(&self) -> anyhow::Result<ResolvedSigner> {
         if let Some(resource) = &self.kms {
             let resource = normalize_kms_resource(resource);
             let signer = GcpKmsSigner::new(resource).await?;
             let address = signer.address();
             Ok(ResolvedSigner { wallet: EthereumWallet::from(signer), address })
+        } else if let Some(path) = &self.keystore {
+            let password = rpassword::prompt_password_stdout(&format!(
+                "Enter password for keystore {}: ",
+                path.display()
+            ))
+            .map_err(|e| anyhow::anyhow!("failed to read keystore password: {e}"))?;
+            let signer = PrivateKeySigner::decrypt_keystore(path, password).map_err(|e| {
+                anyhow::anyhow!("failed to decrypt keystore {}: {e}", path.display())
+            })?;
+            let address = signer.address();
+            Ok(ResolvedSigner { wallet: EthereumWallet::from(signer), address })
         } else {
             let raw = rpassword::prompt_password_stdout(
                 "Enter private key (hex, with or without 0x prefix): ",
             )
-            .map_err(|e| anyhow::anyhow!("failed to read private key: {e}"))?;
+            .map_err(|e| CatalogError::new(Msg::PrivateKeyUnreadable, &[("error", &e)]))?;
             let hex = raw.trim();
             let bytes = hex::decode(hex.trim_start_matches("0x"))
                 .map_err(|e| anyhow::anyhow!("invalid private key hex: {e}"))?;
@@ -0,0 +1,442 @@
+use alloy_primitives::{TxKind, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::{BlockNumberOrTag, TransactionInput, TransactionRequest};
+use alloy_sol_types::SolCall;
+use clap::Parser;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    command::Executable,
+    contract::{validator_manager_address, ValidatorManagement},
+    fees::FeeArgs,
+    history,
+    i18n::MissingOption,
+    output::OutputFormat,
+    signer::SignerArgs,
+    util::API_V1,
+};
+
+/// Checks in the order they run; later ones build on the transaction of `submit_tx`.
+const CHECKS: [&str; 8] =
+    ["rpc", "api", "submit_tx", "receipt", "commit", "query_block", "randomness", "validator_set"];
+
+#[derive(Debug, Parser)]
+pub struct SmokeCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// Node API address and port (e.g., 127.0.0.1:1024)
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+
+    /// Seconds to wait for the test transaction to be mined and committed
+    #[clap(long, default_value_t = 60)]
+    pub timeout: u64,
+
+    /// Checks to skip, comma separated (rpc, api, submit_tx, receipt, commit, query_block,
+    /// randomness, validator_set), e.g. randomness on a network without DKG
+    #[clap(long, value_delimiter = ',')]
+    pub skip: Vec<String>,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    message: String,
+    /// Time the check took, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elapsed_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct SmokeReport {
+    passed: bool,
+    checks: Vec<Check>,
+}
+
+/// The node's `/consensus/latest_ledger_info` response.
+#[derive(Deserialize, Debug)]
+struct LedgerInfo {
+    epoch: u64,
+    block_number: u64,
+}
+
+/// The node's `/dkg/randomness/:block_number` response.
+#[derive(Deserialize, Debug)]
+struct Randomness {
+    randomness: Option<String>,
+}
+
+/// The node's `/consensus/validator_set/:epoch` response, as far as it is needed here.
+#[derive(Deserialize, Debug)]
+struct ValidatorSet {
+    validators: Vec<ValidatorInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ValidatorInfo {
+    public_key: String,
+}
+
+/// The test transaction, once mined.
+struct Mined {
+    tx_hash: alloy_primitives::TxHash,
+    block_number: u64,
+    block_hash: alloy_primitives::BlockHash,
+}
+
+impl Executable for SmokeCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+/// Runs the checks and records their outcome, skipping those that were asked to be skipped
+/// or whose prerequisites failed.
+struct Runner {
+    skip: Vec<String>,
+    checks: Vec<Check>,
+}
+
+impl Runner {
+    async fn run<T, F>(
+        &mut self,
+        name: &'static str,
+        ready: Result<(), String>,
+        check: F,
+    ) -> Option<T>
+    where
+        F: Future<Output = Result<(T, String), anyhow::Error>>,
+    {
+        if self.skip.iter().any(|skip| skip == name) {
+            self.record(name, Status::Skip, "skipped with --skip".to_string(), None);
+            return None;
+        }
+        if let Err(reason) = ready {
+            self.record(name, Status::Skip, reason, None);
+            return None;
+        }
+        let started = Instant::now();
+        let outcome = check.await;
+        let elapsed = Some(started.elapsed().as_millis() as u64);
+        match outcome {
+            Ok((value, message)) => {
+                self.record(name, Status::Pass, message, elapsed);
+                Some(value)
+            }
+            Err(e) => {
+                self.record(name, Status::Fail, format!("{e:#}"), elapsed);
+                None
+            }
+        }
+    }
+
+    fn record(
+        &mut self,
+        name: &'static str,
+        status: Status,
+        message: String,
+        elapsed_ms: Option<u64>,
+    ) {
+        self.checks.push(Check { name, status, message, elapsed_ms });
+    }
+}
+
+/// `Ok` when `value` is present, else the reason a dependent check is skipped.
+fn needs<T>(value: &Option<T>, check: &str) -> Result<(), String> {
+    value.as_ref().map(|_| ()).ok_or_else(|| format!("needs a passing {check} check"))
+}
+
+impl SmokeCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.clone().ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let api_url = self
+            .api_url
+            .as_deref()
+            .map(Self::normalize_url)
+            .ok_or_else(|| MissingOption::new("api-url", "GRAVITY_SERVER_URL"))?;
+        if let Some(unknown) = self.skip.iter().find(|skip| !CHECKS.contains(&skip.as_str())) {
+            return Err(anyhow::anyhow!(
+                "Unknown check '{unknown}' in --skip, expected one of {}",
+                CHECKS.join(", ")
+            ));
+        }
+        let timeout = Duration::from_secs(self.timeout);
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .timeout(timeout + Duration::from_secs(10))
+            .build()?;
+        let get = |path: String| {
+            let (client, url) = (client.clone(), format!("{api_url}{API_V1}{path}"));
+            async move {
+                let response = client.get(&url).send().await?;
+                if !response.status().is_success() {
+                    return Err(anyhow::anyhow!("GET {url}: HTTP {}", response.status()));
+                }
+                Ok::<_, anyhow::Error>(response)
+            }
+        };
+
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let mut runner = Runner { skip: self.skip.clone(), checks: Vec::new() };
+
+        // The chain is reachable and producing blocks
+        let chain = runner
+            .run("rpc", Ok(()), async {
+                let chain_id = provider.get_chain_id().await?;
+                let block = provider.get_block_number().await?;
+                Ok::<_, anyhow::Error>(((), format!("{rpc_url} chain_id={chain_id} block={block}")))
+            })
+            .await;
+
+        // The node API serves the committed ledger
+        let api = runner
+            .run("api", Ok(()), async {
+                let ledger: LedgerInfo =
+                    get("/consensus/latest_ledger_info".to_string()).await?.json().await?;
+                let message = format!(
+                    "{api_url} epoch={} committed block={}",
+                    ledger.epoch, ledger.block_number
+                );
+                Ok::<_, anyhow::Error>(((), message))
+            })
+            .await;
+
+        // A transaction is accepted: a zero-value transfer to the signer itself
+        let pending = runner
+            .run("submit_tx", needs(&chain, "rpc"), async {
+                let fees = self.fees.resolve(&provider).await?;
+                let pending = provider
+                    .send_transaction(fees.apply(TransactionRequest {
+                        from: Some(wallet_address),
+                        to: Some(TxKind::Call(wallet_address)),
+                        value: Some(U256::ZERO),
+                        input: TransactionInput::default(),
+                        ..Default::default()
+                    }))
+                    .await?;
+                let tx_hash = *pending.tx_hash();
+                history::record_tx_hash(tx_hash);
+                Ok::<_, anyhow::Error>((
+                    pending,
+                    format!("{tx_hash} from {wallet_address} ({})", fees.describe()),
+                ))
+            })
+            .await;
+
+        // It is mined and succeeds
+        let ready = needs(&pending, "submit_tx");
+        let mined = runner
+            .run("receipt", ready, async {
+                let pending = pending.expect("checked by needs");
+                let receipt = pending.with_timeout(Some(timeout)).get_receipt().await?;
+                anyhow::ensure!(
+                    receipt.status(),
+                    "transaction {} reverted",
+                    receipt.transaction_hash
+                );
+                let block_number =
+                    receipt.block_number.ok_or(anyhow::anyhow!("receipt without block number"))?;
+                let block_hash =
+                    receipt.block_hash.ok_or(anyhow::anyhow!("receipt without block hash"))?;
+                let message =
+                    format!("mined in block {block_number}, gas used {}", receipt.gas_used);
+                let mined = Mined { tx_hash: receipt.transaction_hash, block_number, block_hash };
+                Ok((mined, message))
+            })
+            .await;
+
+        // Consensus commits its block
+        let ready = needs(&mined, "receipt").and_then(|_| needs(&api, "api"));
+        let committed = runner
+            .run("commit", ready, async {
+                let block_number = mined.as_ref().expect("checked by needs").block_number;
+                let ledger: LedgerInfo = get(format!(
+                    "/consensus/latest_ledger_info?wait_for_block={block_number}&timeout={}s",
+                    timeout.as_secs()
+                ))
+                .await?
+                .json()
+                .await?;
+                anyhow::ensure!(
+                    ledger.block_number >= block_number,
+                    "block {block_number} not committed within {}s (committed up to {})",
+                    timeout.as_secs(),
+                    ledger.block_number
+                );
+                let message = format!(
+                    "committed by epoch {} up to block {}",
+                    ledger.epoch, ledger.block_number
+                );
+                Ok((ledger, message))
+            })
+            .await;
+
+        // The block reads back with the transaction in it
+        let ready = needs(&mined, "receipt");
+        runner
+            .run("query_block", ready, async {
+                let mined = mined.as_ref().expect("checked by needs");
+                let block = provider
+                    .get_block_by_number(BlockNumberOrTag::Number(mined.block_number))
+                    .await?
+                    .ok_or(anyhow::anyhow!("block {} not found", mined.block_number))?;
+                anyhow::ensure!(
+                    block.header.hash == mined.block_hash,
+                    "block {} has hash {}, the receipt says {}",
+                    mined.block_number,
+                    block.header.hash,
+                    mined.block_hash
+                );
+                anyhow::ensure!(
+                    block.transactions.hashes().any(|hash| hash == mined.tx_hash),
+                    "block {} does not contain {}",
+                    mined.block_number,
+                    mined.tx_hash
+                );
+                let message = format!(
+                    "block {} ({} transactions) contains the transaction",
+                    mined.block_number,
+                    block.transactions.len()
+                );
+                Ok(((), message))
+            })
+            .await;
+
+        // The committed block has on-chain randomness
+        let ready = needs(&committed, "commit");
+        runner
+            .run("randomness", ready, async {
+                let block_number = mined.as_ref().expect("checked by needs").block_number;
+                let response: Randomness =
+                    get(format!("/dkg/randomness/{block_number}")).await?.json().await?;
+                match response.randomness.filter(|randomness| !randomness.is_empty()) {
+                    Some(randomness) => {
+                        Ok(((), format!("block {block_number} randomness {randomness}")))
+                    }
+                    None => Err(anyhow::anyhow!(
+                        "no randomness for block {block_number}; is DKG enabled and complete?"
+                    )),
+                }
+            })
+            .await;
+
+        // Consensus runs with the validator set the contracts have active
+        let ready = needs(&chain, "rpc").and_then(|_| needs(&api, "api"));
+        runner
+            .run("validator_set", ready, async {
+                let ledger: LedgerInfo =
+                    get("/consensus/latest_ledger_info".to_string()).await?.json().await?;
+                let consensus: ValidatorSet =
+                    get(format!("/consensus/validator_set/{}", ledger.epoch)).await?.json().await?;
+                let result = provider
+                    .call(TransactionRequest {
+                        to: Some(TxKind::Call(validator_manager_address())),
+                        input: TransactionInput::new(
+                            ValidatorManagement::getActiveValidatorsCall {}.abi_encode().into(),
+                        ),
+                        ..Default::default()
+                    })
+                    .await?;
+                let on_chain =
+                    ValidatorManagement::getActiveValidatorsCall::abi_decode_returns(&result)
+                        .map_err(|e| anyhow::anyhow!("Failed to decode active validators: {e}"))?;
+
+                let consensus_keys: BTreeSet<String> = consensus
+                    .validators
+                    .iter()
+                    .map(|v| v.public_key.trim_start_matches("0x").to_ascii_lowercase())
+                    .collect();
+                let on_chain_keys: BTreeSet<String> =
+                    on_chain.iter().map(|v| hex::encode(&v.consensusPubkey)).collect();
+                let missing = on_chain_keys.difference(&consensus_keys).count();
+                let extra = consensus_keys.difference(&on_chain_keys).count();
+                anyhow::ensure!(
+                    missing == 0 && extra == 0,
+                    "epoch {} consensus set has {} validators, the contract {} active; {missing} \
+                     active on chain but not in consensus, {extra} the other way round (an epoch \
+                     change between the reads can cause this, rerun to confirm)",
+                    ledger.epoch,
+                    consensus_keys.len(),
+                    on_chain_keys.len()
+                );
+                Ok((
+                    (),
+                    format!("epoch {}: {} validators agree", ledger.epoch, consensus_keys.len()),
+                ))
+            })
+            .await;
+
+        let passed = runner.checks.iter().all(|check| check.status != Status::Fail);
+        let report = SmokeReport { passed, checks: runner.checks };
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            OutputFormat::Plain => print_plain(&report),
+        }
+        if !report.passed {
+            return Err(anyhow::anyhow!("Smoke test failed"));
+        }
+        Ok(())
+    }
+}
+
+fn print_plain(report: &SmokeReport) {
+    println!("{}", "=== gravity-cli smoke ===".bold());
+    println!();
+    for check in &report.checks {
+        let (icon, name) = match check.status {
+            Status::Pass => ("[✓]".green().bold().to_string(), check.name.green().to_string()),
+            Status::Fail => ("[✗]".red().bold().to_string(), check.name.red().to_string()),
+            Status::Skip => ("[-]".dimmed().to_string(), check.name.dimmed().to_string()),
+        };
+        let elapsed = check.elapsed_ms.map(|ms| format!(" ({ms} ms)")).unwrap_or_default();
+        println!("{icon} {name}: {}{}", check.message, elapsed.dimmed());
+    }
+    println!();
+    let count = |status| report.checks.iter().filter(|check| check.status == status).count();
+    println!(
+        "{} {}  |  {} passed  |  {} failed  |  {} skipped",
+        "result:".bold(),
+        if report.passed { "PASS".green().bold() } else { "FAIL".red().bold() },
+        count(Status::Pass).to_string().green(),
+        count(Status::Fail).to_string().red(),
+        count(Status::Skip).to_string().dimmed()
+    );
+}
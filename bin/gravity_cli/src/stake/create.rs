@@ -6,7 +6,10 @@ use clap::Parser;
 
 use crate::{
     command::Executable,
-    contract::{Staking, STAKING_ADDRESS},
+    contract::{staking_address, Staking},
+    fees::FeeArgs,
+    history,
+    i18n::MissingOption,
     output::OutputFormat,
     signer::SignerArgs,
     util::{format_ether, parse_ether},
@@ -18,14 +21,6 @@ pub struct CreateCommand {
     #[clap(long, env = "GRAVITY_RPC_URL")]
     pub rpc_url: Option<String>,
 
-    /// Gas limit for the transaction
-    #[clap(long, env = "GRAVITY_GAS_LIMIT")]
-    pub gas_limit: Option<u64>,
-
-    /// Gas price in wei
-    #[clap(long, env = "GRAVITY_GAS_PRICE")]
-    pub gas_price: Option<u128>,
-
     /// Stake amount in ETH
     #[clap(long)]
     pub stake_amount: String,
@@ -34,24 +29,23 @@ pub struct CreateCommand {
     #[clap(long, default_value = "2592000")]
     pub lockup_duration: u64,
 
-    /// Output format (injected from global flag)
-    #[clap(skip)]
-    pub output_format: OutputFormat,
+    #[clap(flatten)]
+    pub fees: FeeArgs,
 
     #[clap(flatten)]
     pub signer: SignerArgs,
 }
 
 impl Executable for CreateCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
 impl CreateCommand {
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
-        let is_json = matches!(self.output_format, OutputFormat::Json);
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let is_json = matches!(format, OutputFormat::Json);
 
         // 1. Initialize Provider and Wallet
         if !is_json {
@@ -59,13 +53,8 @@ impl CreateCommand {
             println!("1. Initializing connection...");
         }
 
-        let rpc_url = self.rpc_url.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--rpc-url is required. Set via CLI flag, GRAVITY_RPC_URL env var, or ~/.gravity/config.toml"
-            )
-        })?;
-        let gas_limit = self.gas_limit.unwrap_or(2_000_000);
-        let gas_price = self.gas_price.unwrap_or(100_000_000_000);
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
 
         if !is_json {
             println!("   RPC URL: {rpc_url}");
@@ -74,7 +63,7 @@ impl CreateCommand {
         let wallet_address = resolved.address;
         if !is_json {
             println!("   Wallet address: {wallet_address:?}");
-            println!("   Staking contract: {STAKING_ADDRESS:?}");
+            println!("   Staking contract: {:?}", staking_address());
         }
 
         // Create provider
@@ -82,8 +71,10 @@ impl CreateCommand {
             ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
 
         let chain_id = provider.get_chain_id().await?;
+        let fees = self.fees.resolve(&provider).await?;
         if !is_json {
             println!("   Chain ID: {chain_id}");
+            println!("   Fees: {}", fees.describe());
         }
         let balance = provider.get_balance(wallet_address).await?;
         if !is_json {
@@ -130,17 +121,16 @@ impl CreateCommand {
         };
         let input: Bytes = call.abi_encode().into();
         let pending_tx = provider
-            .send_transaction(TransactionRequest {
+            .send_transaction(fees.apply(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(STAKING_ADDRESS)),
+                to: Some(TxKind::Call(staking_address())),
                 input: TransactionInput::new(input),
                 value: Some(stake_wei),
-                gas: Some(gas_limit),
-                gas_price: Some(gas_price),
                 ..Default::default()
-            })
+            }))
             .await?;
         let tx_hash = *pending_tx.tx_hash();
+        history::record_tx_hash(tx_hash);
         if !is_json {
             println!("   Transaction hash: {tx_hash}");
         }
@@ -8,7 +8,8 @@ use std::str::FromStr;
 
 use crate::{
     command::Executable,
-    contract::{Staking, STAKING_ADDRESS},
+    contract::{staking_address, Staking},
+    i18n::MissingOption,
     output::OutputFormat,
     util::format_ether,
 };
@@ -41,10 +42,6 @@ pub struct GetCommand {
     /// Query voting power for each pool
     #[clap(long, default_value = "true")]
     pub show_voting_power: bool,
-
-    /// Output format (injected from global flag)
-    #[clap(skip)]
-    pub output_format: OutputFormat,
 }
 
 #[derive(Debug, Serialize)]
@@ -58,15 +55,15 @@ struct PoolInfo {
 const MAX_BLOCK_RANGE: u64 = 90_000;
 
 impl Executable for GetCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
 impl GetCommand {
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
-        let is_json = matches!(self.output_format, OutputFormat::Json);
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let is_json = matches!(format, OutputFormat::Json);
 
         if !is_json {
             println!("Querying StakePools for owner: {}\n", self.owner);
@@ -77,11 +74,8 @@ impl GetCommand {
         let owner_topic = format!("0x{:0>64}", hex::encode(owner_addr.as_slice()));
 
         // Create provider
-        let rpc_url = self.rpc_url.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--rpc-url is required. Set via CLI flag, GRAVITY_RPC_URL env var, or ~/.gravity/config.toml"
-            )
-        })?;
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
 
         let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
 
@@ -111,7 +105,7 @@ impl GetCommand {
         // topics[2] = pool (any)
         // topics[3] = owner (filtered)
         let filter = Filter::new()
-            .address(STAKING_ADDRESS)
+            .address(staking_address())
             .from_block(from_block)
             .to_block(to_block)
             .event_signature(POOL_CREATED_EVENT_SIGNATURE.parse::<B256>()?)
@@ -119,7 +113,7 @@ impl GetCommand {
 
         if !is_json {
             println!("Searching for PoolCreated events...");
-            println!("   Contract: {STAKING_ADDRESS:?}");
+            println!("   Contract: {:?}", staking_address());
             println!("   Owner: {owner_addr:?}");
             println!("   Block range: {from_block} to {to_block}\n");
         }
@@ -151,7 +145,7 @@ impl GetCommand {
                 let input: Bytes = call.abi_encode().into();
                 let result = provider
                     .call(TransactionRequest {
-                        to: Some(TxKind::Call(STAKING_ADDRESS)),
+                        to: Some(TxKind::Call(staking_address())),
                         input: TransactionInput::new(input),
                         ..Default::default()
                     })
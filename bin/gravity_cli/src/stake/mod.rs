@@ -3,7 +3,11 @@ mod get;
 
 use clap::{Parser, Subcommand};
 
-use crate::stake::{create::CreateCommand, get::GetCommand};
+use crate::{
+    command::Executable,
+    output::OutputFormat,
+    stake::{create::CreateCommand, get::GetCommand},
+};
 
 #[derive(Debug, Parser)]
 pub struct StakeCommand {
@@ -18,3 +22,12 @@ pub enum SubCommands {
     /// Query StakePools by owner address
     Get(GetCommand),
 }
+
+impl Executable for StakeCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::Create(create_cmd) => create_cmd.execute(format),
+            SubCommands::Get(get_cmd) => get_cmd.execute(format),
+        }
+    }
+}
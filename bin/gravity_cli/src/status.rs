@@ -8,11 +8,12 @@ use serde::{Deserialize, Serialize};
 use crate::{
     command::Executable,
     contract::{
-        EpochConfig, Reconfiguration, ValidatorManagement, EPOCH_CONFIG_ADDRESS,
-        RECONFIGURATION_ADDRESS, VALIDATOR_MANAGER_ADDRESS,
+        epoch_config_address, reconfiguration_address, validator_manager_address, EpochConfig,
+        Reconfiguration, ValidatorManagement,
     },
+    i18n::{tr, Msg},
     output::OutputFormat,
-    util::format_ether,
+    util::{format_ether, API_V1},
 };
 
 #[derive(Debug, Parser)]
@@ -24,10 +25,6 @@ pub struct StatusCommand {
     /// Server address for DKG queries (e.g., 127.0.0.1:1024)
     #[clap(long, env = "GRAVITY_SERVER_URL")]
     pub server_url: Option<String>,
-
-    /// Output format
-    #[clap(skip)]
-    pub output_format: OutputFormat,
 }
 
 #[derive(Serialize)]
@@ -64,18 +61,19 @@ struct DkgInfo {
 }
 
 impl Executable for StatusCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
 impl StatusCommand {
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         if self.rpc_url.is_none() && self.server_url.is_none() {
-            return Err(anyhow::anyhow!(
-                "At least one of --rpc-url or --server-url is required for status display"
-            ));
+            return Err(anyhow::anyhow!(tr(
+                Msg::MissingEndpoint,
+                &[("first", &"rpc-url"), ("second", &"server-url")]
+            )));
         }
 
         let mut combined = CombinedStatus { epoch: None, validators: None, dkg: None };
@@ -105,7 +103,7 @@ impl StatusCommand {
             }
         }
 
-        match self.output_format {
+        match format {
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(&combined)?);
             }
@@ -170,7 +168,7 @@ impl StatusCommand {
         let call = Reconfiguration::currentEpochCall {};
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(RECONFIGURATION_ADDRESS)),
+                to: Some(TxKind::Call(reconfiguration_address())),
                 input: TransactionInput::new(call.abi_encode().into()),
                 ..Default::default()
             })
@@ -181,7 +179,7 @@ impl StatusCommand {
         let call = Reconfiguration::lastReconfigurationTimeCall {};
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(RECONFIGURATION_ADDRESS)),
+                to: Some(TxKind::Call(reconfiguration_address())),
                 input: TransactionInput::new(call.abi_encode().into()),
                 ..Default::default()
             })
@@ -192,7 +190,7 @@ impl StatusCommand {
         let call = EpochConfig::epochIntervalMicrosCall {};
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(EPOCH_CONFIG_ADDRESS)),
+                to: Some(TxKind::Call(epoch_config_address())),
                 input: TransactionInput::new(call.abi_encode().into()),
                 ..Default::default()
             })
@@ -223,7 +221,7 @@ impl StatusCommand {
         let call = ValidatorManagement::getTotalVotingPowerCall {};
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(call.abi_encode().into()),
                 ..Default::default()
             })
@@ -235,7 +233,7 @@ impl StatusCommand {
         let call = ValidatorManagement::getActiveValidatorCountCall {};
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(call.abi_encode().into()),
                 ..Default::default()
             })
@@ -249,7 +247,7 @@ impl StatusCommand {
         let call = ValidatorManagement::getPendingActiveValidatorsCall {};
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(call.abi_encode().into()),
                 ..Default::default()
             })
@@ -261,7 +259,7 @@ impl StatusCommand {
         let call = ValidatorManagement::getPendingInactiveValidatorsCall {};
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(Bytes::from(call.abi_encode())),
                 ..Default::default()
             })
@@ -290,7 +288,7 @@ impl StatusCommand {
             .danger_accept_invalid_hostnames(true)
             .build()?;
 
-        let response = client.get(format!("{base_url}/dkg/status")).send().await?;
+        let response = client.get(format!("{base_url}{API_V1}/dkg/status")).send().await?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("DKG status request failed: HTTP {}", response.status()));
@@ -0,0 +1,464 @@
+use alloy_primitives::{Address, TxKind, U64};
+use alloy_provider::{Provider, RootProvider};
+use alloy_rpc_types::eth::{TransactionInput, TransactionRequest};
+use alloy_sol_types::SolCall;
+use clap::Parser;
+use ratatui::{
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame, Terminal,
+};
+use serde::Deserialize;
+use std::{
+    collections::VecDeque,
+    io::stdout,
+    str::FromStr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    command::Executable,
+    contract::{status_from_u8, validator_manager_address, ValidatorManagement},
+    i18n::{tr, Msg},
+    output::OutputFormat,
+    util::API_V1,
+};
+
+/// Alerts kept on screen, newest first.
+const MAX_ALERTS: usize = 50;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Parser)]
+pub struct TopCommand {
+    /// Node API address and port (e.g., 127.0.0.1:1024)
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address whose validator status is shown
+    #[clap(long)]
+    pub stake_pool: Option<String>,
+
+    /// Seconds between refreshes
+    #[clap(long, default_value_t = 2)]
+    pub refresh_secs: u64,
+
+    /// Alert when no new block is committed for this many seconds
+    #[clap(long, default_value_t = 30)]
+    pub stall_secs: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct LedgerInfo {
+    epoch: u64,
+    round: u64,
+    block_number: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct DkgStatus {
+    epoch: u64,
+    round: u64,
+    participating_nodes: usize,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ChainStats {
+    tps: f64,
+    avg_commit_latency_ms: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TxpoolStatus {
+    pending: U64,
+    queued: U64,
+}
+
+/// Everything shown on screen, updated on every refresh.
+#[derive(Default)]
+struct Dashboard {
+    ledger: Option<LedgerInfo>,
+    stats: Option<ChainStats>,
+    dkg: Option<DkgStatus>,
+    execution_height: Option<u64>,
+    peers: Option<u64>,
+    txpool: Option<TxpoolStatus>,
+    active_validators: Option<u64>,
+    validator_status: Option<String>,
+    rounds_per_sec: Option<f64>,
+    blocks_per_sec: Option<f64>,
+    /// Previous ledger info sample, for rates.
+    last_sample: Option<(Instant, LedgerInfo)>,
+    last_block_change: Option<Instant>,
+    last_refresh: Option<Instant>,
+    api_down: bool,
+    rpc_down: bool,
+    stalled: bool,
+    alerts: VecDeque<String>,
+}
+
+impl Dashboard {
+    fn alert(&mut self, message: impl Into<String>) {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let time = format!("{:02}:{:02}:{:02}", secs / 3600 % 24, secs / 60 % 60, secs % 60);
+        self.alerts.push_front(format!("{time} UTC  {}", message.into()));
+        self.alerts.truncate(MAX_ALERTS);
+    }
+
+    /// Raises an alert when `condition` starts holding and a recovery notice when it stops.
+    fn track(
+        &mut self,
+        flag: fn(&mut Self) -> &mut bool,
+        condition: bool,
+        raised: &str,
+        cleared: &str,
+    ) {
+        let was = std::mem::replace(flag(self), condition);
+        match (was, condition) {
+            (false, true) => self.alert(raised),
+            (true, false) => self.alert(cleared),
+            _ => {}
+        }
+    }
+
+    fn record_ledger(&mut self, ledger: LedgerInfo, now: Instant) {
+        if let Some((at, previous)) = &self.last_sample {
+            let elapsed = now.duration_since(*at).as_secs_f64();
+            if elapsed > 0.0 && previous.epoch == ledger.epoch {
+                self.rounds_per_sec =
+                    Some(ledger.round.saturating_sub(previous.round) as f64 / elapsed);
+            }
+            if elapsed > 0.0 {
+                self.blocks_per_sec = Some(
+                    ledger.block_number.saturating_sub(previous.block_number) as f64 / elapsed,
+                );
+            }
+            if previous.epoch != ledger.epoch {
+                self.alert(format!("Epoch changed {} -> {}", previous.epoch, ledger.epoch));
+            }
+            if ledger.block_number != previous.block_number {
+                self.last_block_change = Some(now);
+            }
+        } else {
+            self.last_block_change = Some(now);
+        }
+        self.last_sample = Some((now, ledger.clone()));
+        self.ledger = Some(ledger);
+    }
+}
+
+struct Sources {
+    client: reqwest::Client,
+    api_url: Option<String>,
+    provider: Option<RootProvider>,
+    stake_pool: Option<Address>,
+}
+
+impl Sources {
+    async fn get_json<T: for<'de> Deserialize<'de>>(&self, path: &str) -> anyhow::Result<T> {
+        let api_url = self.api_url.as_deref().ok_or_else(|| anyhow::anyhow!("no API URL"))?;
+        let response = self.client.get(format!("{api_url}{API_V1}{path}")).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("{path}: HTTP {}", response.status()));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn call<C: SolCall>(provider: &RootProvider, call: C) -> anyhow::Result<C::Return> {
+        let result = provider
+            .call(TransactionRequest {
+                to: Some(TxKind::Call(validator_manager_address())),
+                input: TransactionInput::new(call.abi_encode().into()),
+                ..Default::default()
+            })
+            .await?;
+        Ok(C::abi_decode_returns(&result)?)
+    }
+
+    async fn refresh(&self, dashboard: &mut Dashboard, stall_after: Duration) {
+        let now = Instant::now();
+        if self.api_url.is_some() {
+            let ledger = self.get_json::<LedgerInfo>("/consensus/latest_ledger_info").await;
+            dashboard.track(
+                |d| &mut d.api_down,
+                ledger.is_err(),
+                "Node API unreachable",
+                "Node API reachable again",
+            );
+            if let Ok(ledger) = ledger {
+                dashboard.record_ledger(ledger, now);
+            }
+            // Older nodes lack these endpoints; keep the last values on failure.
+            if let Ok(stats) = self.get_json::<ChainStats>("/stats/chain?window=1m").await {
+                dashboard.stats = Some(stats);
+            }
+            if let Ok(dkg) = self.get_json::<DkgStatus>("/dkg/status").await {
+                dashboard.dkg = Some(dkg);
+            }
+            let stalled = dashboard
+                .last_block_change
+                .is_some_and(|changed| now.duration_since(changed) >= stall_after);
+            dashboard.track(
+                |d| &mut d.stalled,
+                stalled,
+                &format!("No block committed for {}s", stall_after.as_secs()),
+                "Blocks are being committed again",
+            );
+        }
+
+        if let Some(provider) = &self.provider {
+            let height = provider.get_block_number().await;
+            dashboard.track(
+                |d| &mut d.rpc_down,
+                height.is_err(),
+                "RPC unreachable",
+                "RPC reachable again",
+            );
+            if let Ok(height) = height {
+                dashboard.execution_height = Some(height);
+            }
+            if let Ok(peers) = provider.raw_request::<_, U64>("net_peerCount".into(), ()).await {
+                dashboard.peers = Some(peers.to());
+            }
+            if let Ok(txpool) =
+                provider.raw_request::<_, TxpoolStatus>("txpool_status".into(), ()).await
+            {
+                dashboard.txpool = Some(txpool);
+            }
+            if let Ok(count) =
+                Self::call(provider, ValidatorManagement::getActiveValidatorCountCall {}).await
+            {
+                dashboard.active_validators = Some(count.try_into().unwrap_or(u64::MAX));
+            }
+            if let Some(stake_pool) = self.stake_pool {
+                let call = ValidatorManagement::getValidatorStatusCall { stakePool: stake_pool };
+                if let Ok(status) = Self::call(provider, call).await {
+                    let status = format!("{:?}", status_from_u8(status));
+                    if dashboard.validator_status.as_ref().is_some_and(|s| *s != status) {
+                        dashboard.alert(format!("Validator status changed to {status}"));
+                    }
+                    dashboard.validator_status = Some(status);
+                }
+            }
+        }
+        dashboard.last_refresh = Some(now);
+    }
+}
+
+/// Restores the terminal however the dashboard exits.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(stdout(), LeaveAlternateScreen);
+    }
+}
+
+impl Executable for TopCommand {
+    fn execute(self, _: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async())
+    }
+}
+
+impl TopCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn execute_async(self) -> Result<(), anyhow::Error> {
+        if self.api_url.is_none() && self.rpc_url.is_none() {
+            return Err(anyhow::anyhow!(tr(
+                Msg::MissingEndpoint,
+                &[("first", &"api-url"), ("second", &"rpc-url")]
+            )));
+        }
+        let sources = Sources {
+            client: reqwest::Client::builder()
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true)
+                .timeout(REQUEST_TIMEOUT)
+                .build()?,
+            api_url: self.api_url.as_deref().map(Self::normalize_url),
+            provider: match &self.rpc_url {
+                Some(rpc_url) => Some(RootProvider::new_http(rpc_url.parse()?)),
+                None => None,
+            },
+            stake_pool: self.stake_pool.as_deref().map(Address::from_str).transpose()?,
+        };
+        let refresh = Duration::from_secs(self.refresh_secs.max(1));
+        let stall_after = Duration::from_secs(self.stall_secs.max(1));
+
+        enable_raw_mode()?;
+        let _guard = TerminalGuard;
+        execute!(stdout(), EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(ratatui::backend::CrosstermBackend::new(stdout()))?;
+
+        let mut dashboard = Dashboard::default();
+        loop {
+            if dashboard.last_refresh.map_or(true, |at| at.elapsed() >= refresh) {
+                sources.refresh(&mut dashboard, stall_after).await;
+            }
+            terminal.draw(|frame| self.render(frame, &dashboard))?;
+            if event::poll(Duration::from_millis(200))? {
+                if let Event::Key(key) = event::read()? {
+                    let ctrl_c = key.code == KeyCode::Char('c') &&
+                        key.modifiers.contains(KeyModifiers::CONTROL);
+                    if key.kind == KeyEventKind::Press &&
+                        (ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc))
+                    {
+                        break;
+                    }
+                    if key.kind == KeyEventKind::Press && key.code == KeyCode::Char('r') {
+                        dashboard.last_refresh = None;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&self, frame: &mut Frame, dashboard: &Dashboard) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(8), Constraint::Min(3)])
+            .split(frame.area());
+
+        let source = match (&self.api_url, &self.rpc_url) {
+            (Some(api), Some(rpc)) => format!("api {api}  rpc {rpc}"),
+            (Some(api), None) => format!("api {api}"),
+            (None, Some(rpc)) => format!("rpc {rpc}"),
+            (None, None) => String::new(),
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(" gravity top ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(format!(" {source}  (q quit, r refresh)")),
+            ])),
+            rows[0],
+        );
+
+        let panels = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 4); 4])
+            .split(rows[1]);
+        let chain = dashboard.ledger.as_ref();
+        self.panel(
+            frame,
+            panels[0],
+            "Chain",
+            vec![
+                ("Block", opt(chain.map(|l| l.block_number))),
+                ("Exec height", opt(dashboard.execution_height)),
+                ("Epoch", opt(chain.map(|l| l.epoch))),
+                ("Round", opt(chain.map(|l| l.round))),
+                ("Rounds/s", rate(dashboard.rounds_per_sec)),
+                ("Blocks/s", rate(dashboard.blocks_per_sec)),
+            ],
+        );
+        self.panel(
+            frame,
+            panels[1],
+            "Node",
+            vec![
+                ("Peers", opt(dashboard.peers)),
+                ("Mempool pending", opt(dashboard.txpool.as_ref().map(|t| t.pending.to::<u64>()))),
+                ("Mempool queued", opt(dashboard.txpool.as_ref().map(|t| t.queued.to::<u64>()))),
+                ("TPS (1m)", rate(dashboard.stats.as_ref().map(|s| s.tps))),
+                (
+                    "Commit latency",
+                    dashboard
+                        .stats
+                        .as_ref()
+                        .and_then(|s| s.avg_commit_latency_ms)
+                        .map_or_else(|| "-".to_string(), |ms| format!("{ms:.0} ms")),
+                ),
+            ],
+        );
+        self.panel(
+            frame,
+            panels[2],
+            "Validators",
+            vec![
+                ("Active", opt(dashboard.active_validators)),
+                (
+                    "This validator",
+                    dashboard.validator_status.clone().unwrap_or_else(|| "-".to_string()),
+                ),
+            ],
+        );
+        let dkg = dashboard.dkg.as_ref();
+        let dkg_health = match (dkg, chain) {
+            (Some(dkg), Some(chain)) if dkg.epoch + 1 < chain.epoch => "lagging",
+            (Some(_), _) => "ok",
+            (None, _) => "-",
+        };
+        self.panel(
+            frame,
+            panels[3],
+            "DKG",
+            vec![
+                ("Epoch", opt(dkg.map(|d| d.epoch))),
+                ("Round", opt(dkg.map(|d| d.round))),
+                ("Participants", opt(dkg.map(|d| d.participating_nodes))),
+                ("Health", dkg_health.to_string()),
+            ],
+        );
+
+        let alerts: Vec<ListItem> = if dashboard.alerts.is_empty() {
+            vec![ListItem::new("No alerts")]
+        } else {
+            dashboard
+                .alerts
+                .iter()
+                .map(|alert| {
+                    ListItem::new(alert.as_str()).style(Style::default().fg(Color::Yellow))
+                })
+                .collect()
+        };
+        frame.render_widget(
+            List::new(alerts).block(Block::default().borders(Borders::ALL).title("Recent alerts")),
+            rows[2],
+        );
+    }
+
+    fn panel(&self, frame: &mut Frame, area: Rect, title: &str, rows: Vec<(&str, String)>) {
+        let lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{label}: "), Style::default().fg(Color::DarkGray)),
+                    Span::raw(value),
+                ])
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+            area,
+        );
+    }
+}
+
+fn opt(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+fn rate(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{v:.2}"))
+}
@@ -0,0 +1,224 @@
+//! Sending and simulating contract calls, shared by the staking, validator and delegation
+//! commands.
+
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::eth::{TransactionInput, TransactionReceipt, TransactionRequest};
+use alloy_sol_types::{SolCall, SolType, SolValue};
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    contract::{validator_manager_address, ValidatorManagement, ValidatorRecord},
+    fees::Fees,
+    history,
+    i18n::{tr, CatalogError, Msg},
+    output::{progress, OutputFormat},
+};
+
+pub async fn get_validator<P: Provider>(
+    provider: &P,
+    from: Address,
+    stake_pool: Address,
+) -> Result<ValidatorRecord, anyhow::Error> {
+    let call = ValidatorManagement::isValidatorCall { stakePool: stake_pool };
+    let input: Bytes = call.abi_encode().into();
+    let result = provider
+        .call(TransactionRequest {
+            from: Some(from),
+            to: Some(TxKind::Call(validator_manager_address())),
+            input: TransactionInput::new(input),
+            ..Default::default()
+        })
+        .await?;
+    let is_validator = bool::abi_decode(&result)
+        .map_err(|e| anyhow::anyhow!("Failed to decode isValidator result: {e}"))?;
+    if !is_validator {
+        return Err(CatalogError::new(Msg::NotValidator, &[]).into());
+    }
+
+    let call = ValidatorManagement::getValidatorCall { stakePool: stake_pool };
+    let input: Bytes = call.abi_encode().into();
+    let result = provider
+        .call(TransactionRequest {
+            from: Some(from),
+            to: Some(TxKind::Call(validator_manager_address())),
+            input: TransactionInput::new(input),
+            ..Default::default()
+        })
+        .await?;
+    <ValidatorRecord as SolType>::abi_decode(&result)
+        .map_err(|e| anyhow::anyhow!("Failed to decode validator record: {e}"))
+}
+
+pub async fn send<P: Provider>(
+    provider: &P,
+    from: Address,
+    input: Bytes,
+    fees: &Fees,
+    output: OutputFormat,
+) -> Result<TransactionReceipt, anyhow::Error> {
+    send_to(provider, from, validator_manager_address(), input, fees, output).await
+}
+
+/// Like [`send`], to contract `to` instead of the validator manager.
+pub async fn send_to<P: Provider>(
+    provider: &P,
+    from: Address,
+    to: Address,
+    input: Bytes,
+    fees: &Fees,
+    output: OutputFormat,
+) -> Result<TransactionReceipt, anyhow::Error> {
+    send_value(provider, from, to, input, None, fees, output).await
+}
+
+/// Like [`send_to`], transferring `value` wei with the call.
+pub async fn send_value<P: Provider>(
+    provider: &P,
+    from: Address,
+    to: Address,
+    input: Bytes,
+    value: Option<U256>,
+    fees: &Fees,
+    output: OutputFormat,
+) -> Result<TransactionReceipt, anyhow::Error> {
+    let pending_tx = provider
+        .send_transaction(fees.apply(TransactionRequest {
+            from: Some(from),
+            to: Some(TxKind::Call(to)),
+            input: TransactionInput::new(input),
+            value,
+            ..Default::default()
+        }))
+        .await?;
+    let tx_hash = *pending_tx.tx_hash();
+    history::record_tx_hash(tx_hash);
+    progress!(output, "{}", tr(Msg::TxHash, &[("hash", &tx_hash)]));
+    let _ = pending_tx
+        .with_required_confirmations(2)
+        .with_timeout(Some(std::time::Duration::from_secs(60)))
+        .watch()
+        .await?;
+    let receipt = provider
+        .get_transaction_receipt(tx_hash)
+        .await?
+        .ok_or_else(|| CatalogError::new(Msg::ReceiptMissing, &[]))?;
+    if !receipt.status() {
+        return Err(CatalogError::new(Msg::TxReverted, &[("hash", &tx_hash)]).into());
+    }
+    Ok(receipt)
+}
+
+/// CLI flag simulating the transactions of an on-chain command instead of sending them. Add to
+/// a subcommand by flattening, next to the fees and signer.
+#[derive(Debug, Clone, Default, Args)]
+pub struct DryRunArgs {
+    /// Simulate the transactions with eth_call and eth_estimateGas and print the estimated gas,
+    /// expected events and revert reasons, without sending anything
+    #[clap(long = "dry-run")]
+    pub enabled: bool,
+}
+
+/// Outcome of simulating one contract call with `--dry-run`.
+#[derive(Debug, Serialize)]
+pub struct Simulation {
+    pub call: &'static str,
+    pub gas_estimate: Option<u64>,
+    /// Event the contract emits when the call succeeds.
+    pub expected_event: &'static str,
+    /// Why the call would revert, if it would.
+    pub revert_reason: Option<String>,
+}
+
+/// Simulates a call of `to` with `eth_call` and `eth_estimateGas`, without broadcasting it. A
+/// revert is part of the outcome; only failures to reach the node are errors.
+pub async fn simulate<P: Provider>(
+    provider: &P,
+    from: Address,
+    to: Address,
+    input: Bytes,
+    call: &'static str,
+    expected_event: &'static str,
+    output: OutputFormat,
+) -> Result<Simulation, anyhow::Error> {
+    simulate_value(provider, from, to, input, None, call, expected_event, output).await
+}
+
+/// Like [`simulate`], transferring `value` wei with the call.
+pub async fn simulate_value<P: Provider>(
+    provider: &P,
+    from: Address,
+    to: Address,
+    input: Bytes,
+    value: Option<U256>,
+    call: &'static str,
+    expected_event: &'static str,
+    output: OutputFormat,
+) -> Result<Simulation, anyhow::Error> {
+    let request = TransactionRequest {
+        from: Some(from),
+        to: Some(TxKind::Call(to)),
+        input: TransactionInput::new(input),
+        value,
+        ..Default::default()
+    };
+    let outcome = match provider.call(request.clone()).await {
+        Ok(_) => provider.estimate_gas(request).await,
+        Err(e) => Err(e),
+    };
+    let simulation = match outcome {
+        Ok(gas) => {
+            progress!(
+                output,
+                "{}",
+                tr(
+                    Msg::DryRunWouldSucceed,
+                    &[("call", &call), ("gas", &gas), ("event", &expected_event)]
+                )
+            );
+            Simulation { call, gas_estimate: Some(gas), expected_event, revert_reason: None }
+        }
+        Err(e) => {
+            let Some(payload) = e.as_error_resp() else { return Err(e.into()) };
+            let reason = payload
+                .as_revert_data()
+                .and_then(|data| alloy_sol_types::decode_revert_reason(&data))
+                .unwrap_or_else(|| payload.message.to_string());
+            progress!(
+                output,
+                "{}",
+                tr(Msg::DryRunWouldRevert, &[("call", &call), ("reason", &reason)])
+            );
+            Simulation { call, gas_estimate: None, expected_event, revert_reason: Some(reason) }
+        }
+    };
+    Ok(simulation)
+}
+
+/// Result of a `--dry-run` with `--output json`.
+#[derive(Debug, Serialize)]
+struct DryRunResult<'a> {
+    stake_pool: String,
+    dry_run: bool,
+    simulations: &'a [Simulation],
+}
+
+/// Reports the simulations of a dry run, failing if any call would revert.
+pub fn finish_dry_run(
+    stake_pool: Address,
+    simulations: &[Simulation],
+    output: OutputFormat,
+) -> Result<(), anyhow::Error> {
+    output.emit(&DryRunResult {
+        stake_pool: format!("{stake_pool}"),
+        dry_run: true,
+        simulations,
+    })?;
+    let reverted = simulations.iter().filter(|s| s.revert_reason.is_some()).count();
+    if reverted > 0 {
+        return Err(CatalogError::new(Msg::DryRunReverted, &[("count", &reverted)]).into());
+    }
+    progress!(output, "{}\n", tr(Msg::DryRunComplete, &[]));
+    Ok(())
+}
@@ -0,0 +1,530 @@
+use alloy_eips::eip2718::Encodable2718;
+use alloy_network::{EthereumWallet, TransactionBuilder};
+use alloy_primitives::{Address, TxHash, TxKind, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::{BlockNumberOrTag, TransactionRequest};
+use alloy_signer::k256::ecdsa::SigningKey;
+use alloy_signer_local::PrivateKeySigner;
+use clap::Parser;
+use colored::Colorize;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::task::JoinSet;
+
+use crate::{
+    command::Executable,
+    fees::{FeeArgs, FeeKind, Fees},
+    history,
+    i18n::MissingOption,
+    output::{progress, OutputFormat},
+    signer::SignerArgs,
+    util::{format_ether, parse_duration, ErrorResponse, API_V1},
+};
+
+/// Most transactions the node takes in one `/tx/submit_batch` request.
+const MAX_BATCH_SIZE: usize = 1000;
+/// Gas of a plain transfer, the limit of the benchmark transactions unless `--gas-limit` is given.
+const TRANSFER_GAS: u64 = 21_000;
+/// How often the sender catches up with the target rate.
+const SEND_TICK: Duration = Duration::from_millis(50);
+/// How often new blocks are looked for, which bounds the precision of commit latencies.
+const BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Interval between progress lines while sending.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+/// Timeout of each `/tx/submit_batch` request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long the funding transfers may take to be mined.
+const FUNDING_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Parser)]
+pub struct BenchCommand {
+    /// RPC URL for gravity node, used to fund the accounts and watch blocks
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// Node API address and port (e.g., 127.0.0.1:1024), where transactions are submitted
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+
+    /// Target transactions per second
+    #[clap(long, default_value_t = 100)]
+    pub rate: u64,
+
+    /// How long to send, e.g. 60s, 5m or 1h
+    #[clap(long, default_value = "60s")]
+    pub duration: String,
+
+    /// Ephemeral accounts the transactions are sent from, funded by the signer beforehand
+    #[clap(long, default_value_t = 10)]
+    pub accounts: usize,
+
+    /// Transactions per /tx/submit_batch request (at most 1000)
+    #[clap(long, default_value_t = 100)]
+    pub batch_size: usize,
+
+    /// Seconds to wait after sending for the outstanding transactions to commit
+    #[clap(long, default_value_t = 30)]
+    pub drain_timeout: u64,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// An ephemeral sender. Its key only lives for the run.
+struct Account {
+    address: Address,
+    wallet: EthereumWallet,
+    nonce: u64,
+}
+
+/// What became of the transactions sent so far, shared by the sender, the submit requests and
+/// the block watcher.
+#[derive(Default)]
+struct Tally {
+    /// Transactions sent and not yet seen in a block, with the time they were sent.
+    pending: HashMap<TxHash, Instant>,
+    sent: u64,
+    accepted: u64,
+    /// Time from sending to the block being served, of each committed transaction.
+    latencies: Vec<Duration>,
+    last_commit: Option<Instant>,
+    /// Transactions the node did not accept, by error code.
+    errors: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize)]
+struct TxBatchRequest {
+    txs: Vec<Vec<u8>>,
+}
+
+/// The node's `/tx/submit_batch` response, as far as it is needed here.
+#[derive(Deserialize, Debug)]
+struct SubmitBatchResponse {
+    results: Vec<BatchTxResult>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BatchTxResult {
+    error: Option<ErrorResponse>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct Percentiles {
+    p50: f64,
+    p90: f64,
+    p99: f64,
+    max: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    target_rate: u64,
+    /// Seconds spent sending.
+    send_secs: f64,
+    accounts: usize,
+    sent: u64,
+    accepted: u64,
+    committed: u64,
+    /// Accepted but not seen in a block by the end of the drain timeout.
+    uncommitted: u64,
+    /// Transactions sent per second.
+    send_tps: f64,
+    /// Transactions committed per second, from the start until the last commit.
+    achieved_tps: f64,
+    /// Milliseconds from sending a transaction to its block being served over RPC.
+    commit_latency_ms: Option<Percentiles>,
+    /// Transactions not accepted, by the node's error code or the request failure.
+    errors: BTreeMap<String, u64>,
+}
+
+impl Executable for BenchCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl BenchCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.clone().ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let api_url = self
+            .api_url
+            .as_deref()
+            .map(Self::normalize_url)
+            .ok_or_else(|| MissingOption::new("api-url", "GRAVITY_SERVER_URL"))?;
+        let duration = parse_duration(&self.duration)?;
+        anyhow::ensure!(self.rate > 0, "--rate must be at least 1");
+        anyhow::ensure!(self.accounts > 0, "--accounts must be at least 1");
+        anyhow::ensure!(
+            (1..=MAX_BATCH_SIZE).contains(&self.batch_size),
+            "--batch-size must be between 1 and {MAX_BATCH_SIZE}"
+        );
+        let total = self.rate.saturating_mul(duration.as_secs());
+        anyhow::ensure!(total > 0, "--duration must be at least 1s");
+
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        let resolved = self.signer.resolve().await?;
+        let funder = resolved.address;
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        let fees = self.fees.resolve(&provider).await?;
+        let fees = Fees { gas_limit: self.fees.gas_limit.unwrap_or(TRANSFER_GAS), ..fees };
+
+        // Every account gets enough for its share of the transactions at the fee cap
+        let per_account = total.div_ceil(self.accounts as u64);
+        let max_fee = U256::from(fee_cap(&fees));
+        let fund = U256::from(per_account) * U256::from(fees.gas_limit) * max_fee;
+        let funding_fees = Fees { gas_limit: TRANSFER_GAS, ..fees };
+        let needed = (fund + U256::from(TRANSFER_GAS) * max_fee) * U256::from(self.accounts as u64);
+        let balance = provider.get_balance(funder).await?;
+        anyhow::ensure!(
+            balance >= needed,
+            "{funder} has {} ETH, funding {} accounts for {total} transactions needs {} ETH",
+            format_ether(balance),
+            self.accounts,
+            format_ether(needed)
+        );
+        progress!(
+            format,
+            "Funding {} accounts with {} ETH each from {funder} ({})",
+            self.accounts,
+            format_ether(fund),
+            fees.describe()
+        );
+        let mut accounts: Vec<Account> = (0..self.accounts)
+            .map(|_| {
+                let signer = PrivateKeySigner::from_signing_key(SigningKey::random(&mut OsRng));
+                Account {
+                    address: signer.address(),
+                    wallet: EthereumWallet::from(signer),
+                    nonce: 0,
+                }
+            })
+            .collect();
+        let nonce = provider.get_transaction_count(funder).pending().await?;
+        let mut funding = Vec::with_capacity(accounts.len());
+        for (i, account) in accounts.iter().enumerate() {
+            let pending = provider
+                .send_transaction(funding_fees.apply(TransactionRequest {
+                    from: Some(funder),
+                    to: Some(TxKind::Call(account.address)),
+                    value: Some(fund),
+                    nonce: Some(nonce + i as u64),
+                    ..Default::default()
+                }))
+                .await?;
+            history::record_tx_hash(*pending.tx_hash());
+            funding.push(pending);
+        }
+        for pending in funding {
+            let receipt = pending.with_timeout(Some(FUNDING_TIMEOUT)).get_receipt().await?;
+            anyhow::ensure!(
+                receipt.status(),
+                "funding transaction {} reverted",
+                receipt.transaction_hash
+            );
+        }
+
+        let tally = Arc::new(Mutex::new(Tally::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let start_block = provider.get_block_number().await?;
+        let watcher =
+            tokio::spawn(watch_blocks(provider.clone(), start_block, tally.clone(), stop.clone()));
+
+        progress!(
+            format,
+            "Sending {} tx/s for {}s to {api_url} in batches of {}",
+            self.rate,
+            duration.as_secs(),
+            self.batch_size
+        );
+        let submit_url = format!("{api_url}{API_V1}/tx/submit_batch");
+        let mut requests = JoinSet::new();
+        let mut ticker = tokio::time::interval(SEND_TICK);
+        let mut next_progress = PROGRESS_INTERVAL;
+        let (mut sent, mut turn) = (0u64, 0usize);
+        let started = Instant::now();
+        while sent < total {
+            ticker.tick().await;
+            let elapsed = started.elapsed();
+            let due = ((self.rate as f64 * elapsed.as_secs_f64()) as u64).min(total) - sent;
+            let mut batch = Vec::with_capacity(self.batch_size);
+            for _ in 0..due {
+                let account = &mut accounts[turn % self.accounts];
+                turn += 1;
+                // A zero-value transfer to the account itself
+                let envelope = fees
+                    .apply(TransactionRequest {
+                        from: Some(account.address),
+                        to: Some(TxKind::Call(account.address)),
+                        value: Some(U256::ZERO),
+                        nonce: Some(account.nonce),
+                        chain_id: Some(chain_id),
+                        ..Default::default()
+                    })
+                    .build(&account.wallet)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to sign transaction: {e}"))?;
+                account.nonce += 1;
+                batch.push((*envelope.tx_hash(), envelope.encoded_2718()));
+                if batch.len() == self.batch_size {
+                    let batch = std::mem::take(&mut batch);
+                    submit(&mut requests, &client, &submit_url, &tally, batch);
+                }
+            }
+            if !batch.is_empty() {
+                submit(&mut requests, &client, &submit_url, &tally, batch);
+            }
+            sent += due;
+            if elapsed >= next_progress {
+                next_progress += PROGRESS_INTERVAL;
+                let tally = tally.lock().expect("tally lock poisoned");
+                progress!(
+                    format,
+                    "  {:>4}s  sent {}  accepted {}  committed {}",
+                    elapsed.as_secs(),
+                    tally.sent,
+                    tally.accepted,
+                    tally.latencies.len()
+                );
+            }
+        }
+        let send_time = started.elapsed();
+        while let Some(result) = requests.join_next().await {
+            result?;
+        }
+
+        // Give the transactions still in flight time to commit
+        progress!(format, "Waiting up to {}s for outstanding transactions", self.drain_timeout);
+        let drain_deadline = Instant::now() + Duration::from_secs(self.drain_timeout);
+        while Instant::now() < drain_deadline &&
+            !tally.lock().expect("tally lock poisoned").is_done()
+        {
+            tokio::time::sleep(BLOCK_POLL_INTERVAL).await;
+        }
+        stop.store(true, Ordering::Relaxed);
+        watcher.await?;
+
+        let tally = tally.lock().expect("tally lock poisoned");
+        let report = summarize(&self, &tally, started, send_time);
+        match format {
+            OutputFormat::Json => format.emit(&report)?,
+            OutputFormat::Plain => print_report(&report),
+        }
+        anyhow::ensure!(report.committed > 0, "No transaction was committed");
+        Ok(())
+    }
+}
+
+/// Most a transaction can pay per gas under `fees`.
+fn fee_cap(fees: &Fees) -> u128 {
+    match fees.kind {
+        FeeKind::Legacy { gas_price } => gas_price,
+        FeeKind::Eip1559 { max_fee_per_gas, .. } => max_fee_per_gas,
+    }
+}
+
+impl Tally {
+    /// Whether every accepted transaction has been seen in a block.
+    fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Sends `batch` to `/tx/submit_batch` in the background and records the node's answer.
+fn submit(
+    requests: &mut JoinSet<()>,
+    client: &reqwest::Client,
+    url: &str,
+    tally: &Arc<Mutex<Tally>>,
+    batch: Vec<(TxHash, Vec<u8>)>,
+) {
+    let (hashes, txs): (Vec<TxHash>, Vec<Vec<u8>>) = batch.into_iter().unzip();
+    {
+        let mut tally = tally.lock().expect("tally lock poisoned");
+        let now = Instant::now();
+        tally.pending.extend(hashes.iter().map(|hash| (*hash, now)));
+        tally.sent += hashes.len() as u64;
+    }
+    let request = client.post(url).json(&TxBatchRequest { txs });
+    let tally = tally.clone();
+    requests.spawn(async move {
+        let outcome = async {
+            let response = request.send().await.map_err(|e| {
+                if e.is_timeout() {
+                    "timeout".to_string()
+                } else {
+                    "request_failed".to_string()
+                }
+            })?;
+            if !response.status().is_success() {
+                return Err(format!("http_{}", response.status().as_u16()));
+            }
+            response.json::<SubmitBatchResponse>().await.map_err(|_| "invalid_response".to_string())
+        }
+        .await;
+
+        let mut tally = tally.lock().expect("tally lock poisoned");
+        match outcome {
+            Ok(response) => {
+                for (hash, result) in hashes.iter().zip(response.results) {
+                    match result.error {
+                        Some(error) => {
+                            tally.pending.remove(hash);
+                            *tally.errors.entry(error.code).or_default() += 1;
+                        }
+                        None => tally.accepted += 1,
+                    }
+                }
+            }
+            Err(code) => {
+                for hash in &hashes {
+                    tally.pending.remove(hash);
+                }
+                *tally.errors.entry(code).or_default() += hashes.len() as u64;
+            }
+        }
+    });
+}
+
+/// Times commits by looking for the pending transactions in every block after `from`, until
+/// `stop` is set. RPC errors are retried on the next poll.
+async fn watch_blocks<P: Provider>(
+    provider: P,
+    from: u64,
+    tally: Arc<Mutex<Tally>>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut next = from + 1;
+    while !stop.load(Ordering::Relaxed) {
+        tokio::time::sleep(BLOCK_POLL_INTERVAL).await;
+        let Ok(head) = provider.get_block_number().await else { continue };
+        while next <= head {
+            let Ok(Some(block)) =
+                provider.get_block_by_number(BlockNumberOrTag::Number(next)).await
+            else {
+                break;
+            };
+            let seen = Instant::now();
+            let mut tally = tally.lock().expect("tally lock poisoned");
+            for hash in block.transactions.hashes() {
+                if let Some(sent) = tally.pending.remove(&hash) {
+                    tally.latencies.push(seen - sent);
+                    tally.last_commit = Some(seen);
+                }
+            }
+            next += 1;
+        }
+    }
+}
+
+fn summarize(
+    cmd: &BenchCommand,
+    tally: &Tally,
+    started: Instant,
+    send_time: Duration,
+) -> BenchReport {
+    let mut latencies: Vec<f64> =
+        tally.latencies.iter().map(|latency| latency.as_secs_f64() * 1000.0).collect();
+    latencies.sort_by(f64::total_cmp);
+    let committed = latencies.len() as u64;
+    let achieved_tps = tally
+        .last_commit
+        .map(|last| committed as f64 / (last - started).as_secs_f64())
+        .unwrap_or_default();
+    BenchReport {
+        target_rate: cmd.rate,
+        send_secs: send_time.as_secs_f64(),
+        accounts: cmd.accounts,
+        sent: tally.sent,
+        accepted: tally.accepted,
+        committed,
+        uncommitted: tally.accepted.saturating_sub(committed),
+        send_tps: tally.sent as f64 / send_time.as_secs_f64(),
+        achieved_tps,
+        commit_latency_ms: percentiles(&latencies),
+        errors: tally.errors.clone(),
+    }
+}
+
+/// Nearest-rank percentiles of `sorted`, None when it is empty.
+fn percentiles(sorted: &[f64]) -> Option<Percentiles> {
+    let rank = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).max(1) - 1];
+    let max = *sorted.last()?;
+    Some(Percentiles { p50: rank(0.5), p90: rank(0.9), p99: rank(0.99), max })
+}
+
+fn print_report(report: &BenchReport) {
+    println!();
+    println!("{}", "=== gravity-cli tx bench ===".bold());
+    println!(
+        "sent:       {} in {:.1}s ({:.1} tx/s, target {})",
+        report.sent, report.send_secs, report.send_tps, report.target_rate
+    );
+    println!("accepted:   {}", report.accepted);
+    println!(
+        "committed:  {} ({} not committed in time)",
+        report.committed.to_string().green(),
+        report.uncommitted
+    );
+    println!("achieved:   {}", format!("{:.1} tx/s", report.achieved_tps).bold());
+    if let Some(latency) = &report.commit_latency_ms {
+        println!(
+            "latency:    p50 {:.0} ms  p90 {:.0} ms  p99 {:.0} ms  max {:.0} ms",
+            latency.p50, latency.p90, latency.p99, latency.max
+        );
+    }
+    if report.errors.is_empty() {
+        println!("errors:     none");
+    } else {
+        println!("errors:");
+        for (code, count) in &report.errors {
+            println!("  {}  {count}", code.red());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        assert_eq!(percentiles(&[]), None);
+        assert_eq!(
+            percentiles(&[7.0]),
+            Some(Percentiles { p50: 7.0, p90: 7.0, p99: 7.0, max: 7.0 })
+        );
+        let sorted: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(
+            percentiles(&sorted),
+            Some(Percentiles { p50: 50.0, p90: 90.0, p99: 99.0, max: 100.0 })
+        );
+    }
+}
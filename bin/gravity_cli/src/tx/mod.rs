@@ -0,0 +1,26 @@
+use clap::Parser;
+
+use crate::{command::Executable, output::OutputFormat};
+
+pub mod bench;
+
+#[derive(Debug, Parser)]
+pub struct TxCommand {
+    #[command(subcommand)]
+    pub command: SubCommands,
+}
+
+#[derive(Debug, Parser)]
+pub enum SubCommands {
+    /// Load-test a network: stream transfers at a target rate and report the achieved TPS,
+    /// commit latency and errors
+    Bench(bench::BenchCommand),
+}
+
+impl Executable for TxCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::Bench(bench_cmd) => bench_cmd.execute(format),
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::Result;
 use aptos_consensus::consensusdb::ConsensusDB;
 use clap::Parser;
+use serde::Serialize;
 use std::path::PathBuf;
 
+use crate::output::{progress, OutputFormat};
+
 /// Unwind consensus state to a specific block number.
 /// This deletes all consensus data (blocks, QCs, ledger info, randomness, etc.)
 /// for blocks with block_number > target.
@@ -20,10 +23,20 @@ pub struct UnwindCommand {
     target: u64,
 }
 
+/// Result of `unwind` with `--output json`.
+#[derive(Debug, Serialize)]
+struct UnwindResult {
+    target: u64,
+    deleted_batches: usize,
+    cancelled_epochs: Vec<u64>,
+    /// secure.json and rand_db, when they were deleted.
+    deleted_paths: Vec<PathBuf>,
+}
+
 impl super::command::Executable for UnwindCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
-        println!("Unwinding consensus DB to block {}...", self.target);
-        println!("Consensus DB path: {:?}", self.consensus_db_path);
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        progress!(format, "Unwinding consensus DB to block {}...", self.target);
+        progress!(format, "Consensus DB path: {:?}", self.consensus_db_path);
 
         if !self.consensus_db_path.exists() {
             return Err(anyhow::anyhow!(
@@ -44,7 +57,7 @@ impl super::command::Executable for UnwindCommand {
             .unwind_to_block(self.target)
             .map_err(|e| anyhow::anyhow!("Failed to unwind consensus DB: {e:?}"))?;
 
-        println!("Successfully unwound consensus DB to block {}.", self.target);
+        progress!(format, "Successfully unwound consensus DB to block {}.", self.target);
 
         let max_retained_epoch = consensus_db.get_max_epoch();
         let mut all_cancelled_epochs = cancelled_epochs;
@@ -58,8 +71,10 @@ impl super::command::Executable for UnwindCommand {
             }
         }
 
+        let deleted_batches = batches_to_delete.len();
         if !batches_to_delete.is_empty() || !all_cancelled_epochs.is_empty() {
-            println!(
+            progress!(
+                format,
                 "Deleting {} unused QuorumStore batches and {} cancelled epochs...",
                 batches_to_delete.len(),
                 all_cancelled_epochs.len()
@@ -71,13 +86,13 @@ impl super::command::Executable for UnwindCommand {
                 })?;
             }
 
-            for epoch in all_cancelled_epochs {
+            for &epoch in &all_cancelled_epochs {
                 quorum_store_db.delete_batch_id(epoch).map_err(|e| {
                     anyhow::anyhow!("Failed to clean up QuorumStore epoch {epoch}: {e:?}")
                 })?;
             }
 
-            println!("Successfully cleaned up QuorumStore DB.");
+            progress!(format, "Successfully cleaned up QuorumStore DB.");
         }
 
         let data_dir = if self.consensus_db_path.ends_with("consensus_db") {
@@ -86,18 +101,26 @@ impl super::command::Executable for UnwindCommand {
             self.consensus_db_path.clone()
         };
 
+        let mut deleted_paths = Vec::new();
         let secure_json_path = data_dir.join("secure.json");
         if secure_json_path.exists() {
-            println!("Deleting secure.json at {secure_json_path:?}");
-            let _ = std::fs::remove_file(secure_json_path);
+            progress!(format, "Deleting secure.json at {secure_json_path:?}");
+            let _ = std::fs::remove_file(&secure_json_path);
+            deleted_paths.push(secure_json_path);
         }
 
         let rand_db_path = data_dir.join("rand_db");
         if rand_db_path.exists() {
-            println!("Deleting rand_db at {rand_db_path:?}");
-            let _ = std::fs::remove_dir_all(rand_db_path);
+            progress!(format, "Deleting rand_db at {rand_db_path:?}");
+            let _ = std::fs::remove_dir_all(&rand_db_path);
+            deleted_paths.push(rand_db_path);
         }
 
-        Ok(())
+        format.emit(&UnwindResult {
+            target: self.target,
+            deleted_batches,
+            cancelled_epochs: all_cancelled_epochs,
+            deleted_paths,
+        })
     }
 }
@@ -1,5 +1,24 @@
 use alloy_primitives::U256;
-use std::str::FromStr;
+use serde::Deserialize;
+use std::{io::Write, str::FromStr, time::Duration};
+
+use crate::{
+    i18n::{tr, CatalogError, Msg},
+    output::OutputFormat,
+};
+
+/// `{code, message}` error body returned by the node API; older nodes send `{error}` instead.
+#[derive(Deserialize, Debug)]
+pub struct ErrorResponse {
+    #[serde(default)]
+    pub code: String,
+    #[serde(alias = "error")]
+    pub message: String,
+}
+
+/// Version prefix of the node API paths the CLI calls, e.g. `/v1/dkg/status`. Admin endpoints
+/// are not versioned.
+pub const API_V1: &str = "/v1";
 
 /// Helper function: format ether amount from wei to ETH string
 pub fn format_ether(wei: U256) -> String {
@@ -13,6 +32,16 @@ pub fn format_ether(wei: U256) -> String {
     }
 }
 
+/// Helper function: format a per-gas fee from wei to gwei, without trailing zeros
+pub fn format_gwei(wei: u128) -> String {
+    let (gwei, rest) = (wei / 1_000_000_000, wei % 1_000_000_000);
+    if rest == 0 {
+        gwei.to_string()
+    } else {
+        format!("{gwei}.{}", format!("{rest:09}").trim_end_matches('0'))
+    }
+}
+
 /// Helper function: parse ether amount from ETH string to wei
 pub fn parse_ether(eth_amount: &str) -> Result<U256, anyhow::Error> {
     const DECIMALS: usize = 18; // 1 Ether = 10^18 Wei
@@ -48,3 +77,47 @@ pub fn parse_ether(eth_amount: &str) -> Result<U256, anyhow::Error> {
 
     U256::from_str(&wei_str).map_err(|e| anyhow::anyhow!("Failed to parse ether: {e}"))
 }
+
+/// Parses a duration like `90s`, `5m` or `1h`; a bare number is seconds.
+pub fn parse_duration(value: &str) -> Result<Duration, anyhow::Error> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => value.split_at(i),
+        None => (value, "s"),
+    };
+    let number: u64 = number.parse().map_err(|_| anyhow::anyhow!("Invalid duration '{value}'"))?;
+    let secs = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 3600,
+        _ => return Err(anyhow::anyhow!("Invalid duration '{value}', use e.g. 90s, 5m or 1h")),
+    };
+    Ok(Duration::from_secs(secs))
+}
+
+/// Decodes a hex string of exactly `len` bytes, with or without a 0x prefix.
+pub fn parse_hex(value: &str, len: usize, label: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    if value.len() != len * 2 {
+        return Err(anyhow::anyhow!(
+            "Invalid {label}: expected {} hex characters ({len} bytes), got {} characters",
+            len * 2,
+            value.len()
+        ));
+    }
+    hex::decode(value)
+        .map_err(|_| anyhow::anyhow!("Invalid {label}: contains non-hexadecimal characters"))
+}
+
+/// Asks `prompt` on the terminal and returns whether the answer is yes.
+pub fn confirm(prompt: Msg, output: OutputFormat) -> Result<bool, anyhow::Error> {
+    // The prompt would corrupt the JSON result, and scripts can't answer it anyway.
+    if output.is_json() {
+        return Err(CatalogError::new(Msg::ConfirmNeedsYes, &[]).into());
+    }
+    print!("{}", tr(prompt, &[]));
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim(), "y" | "Y" | "yes"))
+}
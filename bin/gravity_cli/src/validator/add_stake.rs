@@ -0,0 +1,235 @@
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::{TransactionInput, TransactionRequest};
+use alloy_sol_types::{SolCall, SolEvent, SolType, SolValue};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{
+        staking_address, validator_manager_address, StakePool, Staking, ValidatorManagement,
+    },
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{finish_dry_run, send_value, simulate_value, DryRunArgs},
+    util::{format_ether, parse_ether},
+};
+
+#[derive(Debug, Parser)]
+pub struct AddStakeCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address to add stake to
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Stake to add in ETH
+    #[clap(long)]
+    pub amount: String,
+
+    #[clap(flatten)]
+    pub dry_run: DryRunArgs,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `validator add-stake` with `--output json`.
+#[derive(Debug, Serialize)]
+struct AddStakeResult {
+    stake_pool: String,
+    /// Amount added in ETH.
+    amount: String,
+    transaction: TxSummary,
+    /// Voting power in ETH before and after the deposit.
+    voting_power_before: String,
+    voting_power_after: String,
+    /// Whether the pool backs a registered validator, whose bond follows at the next epoch.
+    is_validator: bool,
+}
+
+impl Executable for AddStakeCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+/// Calls a view function of `to` and decodes its single return value.
+async fn view<P: Provider, T: SolValue + From<<T::SolType as SolType>::RustType>>(
+    provider: &P,
+    from: Address,
+    to: Address,
+    input: Bytes,
+    what: &str,
+) -> Result<T, anyhow::Error> {
+    let result = provider
+        .call(TransactionRequest {
+            from: Some(from),
+            to: Some(TxKind::Call(to)),
+            input: TransactionInput::new(input),
+            ..Default::default()
+        })
+        .await?;
+    T::abi_decode(&result).map_err(|e| anyhow::anyhow!("Failed to decode {what}: {e}"))
+}
+
+impl AddStakeCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool).map_err(|e| {
+            anyhow::anyhow!("Invalid stake pool address '{}': {e}", self.stake_pool)
+        })?;
+        let amount = parse_ether(&self.amount)?;
+        if amount.is_zero() {
+            return Err(anyhow::anyhow!("--amount must be greater than zero"));
+        }
+
+        if self.dry_run.enabled {
+            progress!(format, "Dry run: the transaction is simulated, nothing is sent\n");
+        }
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}", fees.describe());
+        let balance = provider.get_balance(wallet_address).await?;
+        progress!(format, "   Wallet balance: {} ETH\n", format_ether(balance));
+        if balance < amount {
+            return Err(anyhow::anyhow!(
+                "Insufficient balance: adding {} ETH, wallet holds {} ETH",
+                format_ether(amount),
+                format_ether(balance)
+            ));
+        }
+
+        // 2. Validate existing StakePool
+        progress!(format, "2. Validating StakePool: {stake_pool:?}");
+        let is_pool: bool = view(
+            &provider,
+            wallet_address,
+            staking_address(),
+            Staking::isPoolCall { pool: stake_pool }.abi_encode().into(),
+            "isPool result",
+        )
+        .await?;
+        if !is_pool {
+            return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+        }
+        let voting_power_call: Bytes =
+            Staking::getPoolVotingPowerNowCall { pool: stake_pool }.abi_encode().into();
+        let voting_power_before: U256 = view(
+            &provider,
+            wallet_address,
+            staking_address(),
+            voting_power_call.clone(),
+            "voting power",
+        )
+        .await?;
+        progress!(format, "   Current voting power: {} ETH", format_ether(voting_power_before));
+        let is_validator: bool = view(
+            &provider,
+            wallet_address,
+            validator_manager_address(),
+            ValidatorManagement::isValidatorCall { stakePool: stake_pool }.abi_encode().into(),
+            "isValidator result",
+        )
+        .await?;
+        progress!(format, "   Is registered validator: {is_validator}\n");
+
+        // 3. Add stake
+        progress!(format, "3. Adding {} ETH of stake...", format_ether(amount));
+        let input: Bytes = StakePool::addStakeCall {}.abi_encode().into();
+        if self.dry_run.enabled {
+            let simulation = simulate_value(
+                &provider,
+                wallet_address,
+                stake_pool,
+                input,
+                Some(amount),
+                "addStake",
+                "StakeAdded",
+                format,
+            )
+            .await?;
+            return finish_dry_run(stake_pool, &[simulation], format);
+        }
+        let receipt =
+            send_value(&provider, wallet_address, stake_pool, input, Some(amount), &fees, format)
+                .await?;
+        progress!(
+            format,
+            "   Transaction confirmed, block number: {}",
+            receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
+        );
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
+            "   Transaction cost: {} ETH",
+            format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
+        );
+
+        // Check stake event
+        let mut found = None;
+        for log in receipt.logs() {
+            if let Ok(event) = StakePool::StakeAdded::decode_log(&log.inner) {
+                progress!(format, "   Stake added successfully!");
+                progress!(format, "   - StakePool: {}", event.pool);
+                progress!(format, "   - Amount: {} ETH", format_ether(event.amount));
+                found = Some(serde_json::json!({
+                    "pool": format!("{}", event.pool),
+                    "amount": format_ether(event.amount),
+                }));
+                break;
+            }
+        }
+        let Some(event) = found else {
+            progress!(format, "   Stake event not found\n");
+            return Err(anyhow::anyhow!("Failed to find StakeAdded event"));
+        };
+        progress!(format);
+
+        // 4. Report the new voting power
+        progress!(format, "4. Checking voting power...");
+        let voting_power_after: U256 =
+            view(&provider, wallet_address, staking_address(), voting_power_call, "voting power")
+                .await?;
+        progress!(
+            format,
+            "   Voting power: {} ETH -> {} ETH",
+            format_ether(voting_power_before),
+            format_ether(voting_power_after)
+        );
+        if is_validator {
+            progress!(format, "   The validator's bond follows at the next epoch\n");
+        } else {
+            progress!(format);
+        }
+        format.emit(&AddStakeResult {
+            stake_pool: format!("{stake_pool}"),
+            amount: format_ether(amount),
+            transaction: TxSummary::new(&receipt).with_event(event),
+            voting_power_before: format_ether(voting_power_before),
+            voting_power_after: format_ether(voting_power_after),
+            is_validator,
+        })
+    }
+}
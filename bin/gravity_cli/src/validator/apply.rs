@@ -0,0 +1,541 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::TransactionReceipt;
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    command::Executable,
+    contract::{
+        staking_address, status_from_u8, validator_manager_address, StakePool, Staking,
+        ValidatorManagement, ValidatorRecord, ValidatorStatus,
+    },
+    fees::{FeeArgs, Fees},
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{get_validator, send_to, send_value},
+    util::{format_ether, parse_ether, parse_hex},
+    validator::{status::view, validate_network_address},
+};
+
+#[derive(Debug, Parser)]
+pub struct ApplyCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// YAML manifest listing the validators to bring to the declared state
+    #[clap(long, value_name = "PATH")]
+    pub file: PathBuf,
+
+    /// Print the transactions each validator needs without sending any
+    #[clap(long)]
+    pub dry_run: bool,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// `validators.yaml`: the desired state of each validator.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    validators: Vec<ValidatorSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidatorSpec {
+    stake_pool: Address,
+    moniker: String,
+    /// identity.yaml, or its public sidecar, from `genesis generate-key`; relative to the
+    /// manifest. Replaces the three key fields below.
+    identity: Option<PathBuf>,
+    consensus_public_key: Option<String>,
+    consensus_pop: Option<String>,
+    network_public_key: Option<String>,
+    validator_network_address: String,
+    fullnode_network_address: String,
+    /// Active stake the pool should hold, in ETH; the difference is added with addStake.
+    stake: Option<String>,
+    fee_recipient: Option<Address>,
+    /// Request to join the validator set once registered.
+    #[serde(default = "default_join")]
+    join: bool,
+}
+
+fn default_join() -> bool {
+    true
+}
+
+/// Public keys of an identity file; private keys in it are never read.
+#[derive(Debug, Deserialize)]
+struct IdentityKeys {
+    consensus_public_key: String,
+    consensus_pop: String,
+    network_public_key: String,
+}
+
+/// A manifest entry after validation, before looking at the chain.
+struct Target {
+    stake_pool: Address,
+    moniker: String,
+    consensus_pubkey: Vec<u8>,
+    consensus_pop: Vec<u8>,
+    /// Full addresses, `/noise-ik/{network_public_key}/handshake/0` appended.
+    network_address: String,
+    fullnode_address: String,
+    stake: Option<U256>,
+    fee_recipient: Option<Address>,
+    join: bool,
+}
+
+/// One transaction bringing a validator closer to its declared state.
+enum Step {
+    AddStake(U256),
+    Register,
+    RotateConsensusKey,
+    UpdateNetworkAddresses,
+    SetFeeRecipient(Address),
+    Join,
+}
+
+/// Result of `validator apply` with `--output json`.
+#[derive(Debug, Serialize)]
+struct ApplyResult {
+    dry_run: bool,
+    validators: Vec<ValidatorResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidatorResult {
+    moniker: String,
+    stake_pool: String,
+    /// Steps done, or planned with `--dry-run`; empty when the validator already matched.
+    steps: Vec<StepResult>,
+    /// Status after the steps, absent if they failed.
+    status: Option<String>,
+    /// Differences that can't be applied on chain, e.g. a changed moniker.
+    warnings: Vec<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct StepResult {
+    action: String,
+    transaction: Option<TxSummary>,
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Step::AddStake(amount) => format!("Add {} ETH of stake", format_ether(*amount)),
+            Step::Register => "Register validator".to_string(),
+            Step::RotateConsensusKey => "Rotate consensus key".to_string(),
+            Step::UpdateNetworkAddresses => "Update network addresses".to_string(),
+            Step::SetFeeRecipient(recipient) => format!("Set fee recipient to {recipient}"),
+            Step::Join => "Join validator set".to_string(),
+        }
+    }
+
+    async fn send<P: Provider>(
+        &self,
+        provider: &P,
+        from: Address,
+        target: &Target,
+        fees: &Fees,
+        output: OutputFormat,
+    ) -> Result<TransactionReceipt, anyhow::Error> {
+        let stake_pool = target.stake_pool;
+        let input: Bytes = match self {
+            Step::AddStake(amount) => {
+                let input = StakePool::addStakeCall {}.abi_encode().into();
+                return send_value(provider, from, stake_pool, input, Some(*amount), fees, output)
+                    .await;
+            }
+            Step::Register => ValidatorManagement::registerValidatorCall {
+                stakePool: stake_pool,
+                moniker: target.moniker.clone(),
+                consensusPubkey: target.consensus_pubkey.clone().into(),
+                consensusPop: target.consensus_pop.clone().into(),
+                networkAddresses: bcs::to_bytes(&target.network_address)?.into(),
+                fullnodeAddresses: bcs::to_bytes(&target.fullnode_address)?.into(),
+            }
+            .abi_encode(),
+            Step::RotateConsensusKey => ValidatorManagement::rotateConsensusKeyCall {
+                stakePool: stake_pool,
+                newPubkey: target.consensus_pubkey.clone().into(),
+                newPop: target.consensus_pop.clone().into(),
+            }
+            .abi_encode(),
+            Step::UpdateNetworkAddresses => ValidatorManagement::updateNetworkAddressesCall {
+                stakePool: stake_pool,
+                networkAddresses: bcs::to_bytes(&target.network_address)?.into(),
+                fullnodeAddresses: bcs::to_bytes(&target.fullnode_address)?.into(),
+            }
+            .abi_encode(),
+            Step::SetFeeRecipient(recipient) => ValidatorManagement::setFeeRecipientCall {
+                stakePool: stake_pool,
+                newRecipient: *recipient,
+            }
+            .abi_encode(),
+            Step::Join => {
+                ValidatorManagement::joinValidatorSetCall { stakePool: stake_pool }.abi_encode()
+            }
+        }
+        .into();
+        let receipt =
+            send_to(provider, from, validator_manager_address(), input, fees, output).await?;
+        let found = match self {
+            Step::Register => receipt.logs().iter().any(|log| {
+                ValidatorManagement::ValidatorRegistered::decode_log(&log.inner).is_ok()
+            }),
+            Step::RotateConsensusKey => receipt.logs().iter().any(|log| {
+                ValidatorManagement::ConsensusKeyRotated::decode_log(&log.inner).is_ok()
+            }),
+            Step::SetFeeRecipient(_) => receipt.logs().iter().any(|log| {
+                ValidatorManagement::FeeRecipientUpdated::decode_log(&log.inner).is_ok()
+            }),
+            Step::Join => receipt.logs().iter().any(|log| {
+                ValidatorManagement::ValidatorJoinRequested::decode_log(&log.inner).is_ok()
+            }),
+            Step::AddStake(_) | Step::UpdateNetworkAddresses => true,
+        };
+        if !found {
+            return Err(anyhow::anyhow!("Expected event not found in the transaction receipt"));
+        }
+        Ok(receipt)
+    }
+}
+
+impl Executable for ApplyCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl ApplyCommand {
+    /// Reads and validates the whole manifest, so a typo in the last entry is caught before the
+    /// first transaction.
+    fn load(&self) -> Result<Vec<Target>, anyhow::Error> {
+        let content = fs::read_to_string(&self.file)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", self.file.display()))?;
+        let manifest: Manifest = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", self.file.display()))?;
+        let base = self.file.parent().unwrap_or(Path::new("."));
+        let mut pools = HashSet::new();
+        manifest
+            .validators
+            .into_iter()
+            .map(|spec| {
+                let moniker = spec.moniker.clone();
+                if !pools.insert(spec.stake_pool) {
+                    return Err(anyhow::anyhow!("StakePool {} is listed twice", spec.stake_pool));
+                }
+                target(spec, base).map_err(|e| anyhow::anyhow!("Validator '{moniker}': {e}"))
+            })
+            .collect()
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.clone().ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let targets = self.load()?;
+        if targets.is_empty() {
+            return Err(anyhow::anyhow!("{} lists no validators", self.file.display()));
+        }
+        if self.dry_run {
+            progress!(format, "Dry run: nothing is sent\n");
+        }
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}", fees.describe());
+        progress!(format, "   Manifest: {} validator(s)\n", targets.len());
+
+        // 2. Apply each validator; a failure is reported and the next one goes on
+        progress!(format, "2. Applying validators...");
+        let mut result = ApplyResult { dry_run: self.dry_run, validators: Vec::new() };
+        for (i, target) in targets.iter().enumerate() {
+            progress!(
+                format,
+                "   [{}/{}] {} ({})",
+                i + 1,
+                targets.len(),
+                target.moniker,
+                target.stake_pool
+            );
+            let mut validator = ValidatorResult {
+                moniker: target.moniker.clone(),
+                stake_pool: format!("{}", target.stake_pool),
+                steps: Vec::new(),
+                status: None,
+                warnings: Vec::new(),
+                error: None,
+            };
+            if let Err(e) =
+                self.apply(&provider, wallet_address, target, &fees, &mut validator, format).await
+            {
+                progress!(format, "      FAILED: {e}");
+                validator.error = Some(e.to_string());
+            }
+            result.validators.push(validator);
+        }
+        progress!(format);
+
+        // 3. Summary
+        progress!(format, "3. Summary");
+        progress!(format, "   {:<32} {:<44} {:<16} Steps", "Moniker", "StakePool", "Result");
+        for validator in &result.validators {
+            let outcome = match (&validator.error, &validator.status) {
+                (Some(_), _) => "FAILED".to_string(),
+                (None, Some(status)) => status.clone(),
+                (None, None) if validator.steps.is_empty() => "up to date".to_string(),
+                (None, None) => "planned".to_string(),
+            };
+            progress!(
+                format,
+                "   {:<32} {:<44} {:<16} {}",
+                validator.moniker,
+                validator.stake_pool,
+                outcome,
+                validator.steps.len()
+            );
+        }
+        format.emit(&result)?;
+        let failed = result.validators.iter().filter(|v| v.error.is_some()).count();
+        if failed > 0 {
+            return Err(anyhow::anyhow!("{failed} of {} validator(s) failed", targets.len()));
+        }
+        Ok(())
+    }
+
+    async fn apply<P: Provider>(
+        &self,
+        provider: &P,
+        wallet_address: Address,
+        target: &Target,
+        fees: &Fees,
+        result: &mut ValidatorResult,
+        format: OutputFormat,
+    ) -> Result<(), anyhow::Error> {
+        let steps = plan(provider, wallet_address, target, &mut result.warnings).await?;
+        for warning in &result.warnings {
+            progress!(format, "      Warning: {warning}");
+        }
+        if steps.is_empty() {
+            progress!(format, "      Up to date");
+        }
+        for step in &steps {
+            if self.dry_run {
+                progress!(format, "      Would: {}", step.describe());
+                result.steps.push(StepResult { action: step.describe(), transaction: None });
+                continue;
+            }
+            progress!(format, "      {}", step.describe());
+            let receipt = step.send(provider, wallet_address, target, fees, format).await?;
+            result.steps.push(StepResult {
+                action: step.describe(),
+                transaction: Some(TxSummary::new(&receipt)),
+            });
+        }
+        if !self.dry_run {
+            let status = view(
+                provider,
+                validator_manager_address(),
+                ValidatorManagement::getValidatorStatusCall { stakePool: target.stake_pool },
+            )
+            .await?;
+            result.status = Some(format!("{:?}", status_from_u8(status)));
+        }
+        Ok(())
+    }
+}
+
+/// Validates one manifest entry: its keys, moniker and network addresses.
+fn target(spec: ValidatorSpec, base: &Path) -> Result<Target, anyhow::Error> {
+    if spec.moniker.len() > 31 {
+        return Err(anyhow::anyhow!(
+            "Moniker too long: max 31 bytes, got {} bytes",
+            spec.moniker.len()
+        ));
+    }
+    let keys = match (
+        &spec.identity,
+        spec.consensus_public_key,
+        spec.consensus_pop,
+        spec.network_public_key,
+    ) {
+        (Some(path), None, None, None) => {
+            let path = base.join(path);
+            let content = fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+            serde_yaml::from_str(&content)
+                .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", path.display()))?
+        }
+        (None, Some(consensus_public_key), Some(consensus_pop), Some(network_public_key)) => {
+            IdentityKeys { consensus_public_key, consensus_pop, network_public_key }
+        }
+        _ => {
+            return Err(anyhow::anyhow!(
+                "set either identity or all of consensus_public_key, consensus_pop and \
+                 network_public_key"
+            ))
+        }
+    };
+    let network_pk = hex::encode(parse_hex(&keys.network_public_key, 32, "network public key")?);
+    validate_network_address(&spec.validator_network_address, "validator network")?;
+    validate_network_address(&spec.fullnode_network_address, "fullnode network")?;
+    Ok(Target {
+        stake_pool: spec.stake_pool,
+        moniker: spec.moniker,
+        consensus_pubkey: parse_hex(&keys.consensus_public_key, 48, "consensus public key")?,
+        consensus_pop: parse_hex(&keys.consensus_pop, 96, "consensus proof of possession")?,
+        network_address: format!(
+            "{}/noise-ik/{network_pk}/handshake/0",
+            spec.validator_network_address
+        ),
+        fullnode_address: format!(
+            "{}/noise-ik/{network_pk}/handshake/0",
+            spec.fullnode_network_address
+        ),
+        stake: spec.stake.as_deref().map(parse_ether).transpose()?,
+        fee_recipient: spec.fee_recipient,
+        join: spec.join,
+    })
+}
+
+/// The transactions that bring the validator from its on-chain state to `target`, in order.
+async fn plan<P: Provider>(
+    provider: &P,
+    wallet_address: Address,
+    target: &Target,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<Step>, anyhow::Error> {
+    let stake_pool = target.stake_pool;
+    if !view(provider, staking_address(), Staking::isPoolCall { pool: stake_pool }).await? {
+        return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+    }
+
+    let mut steps = Vec::new();
+    if let Some(stake) = target.stake {
+        let active =
+            view(provider, staking_address(), Staking::getPoolActiveStakeCall { pool: stake_pool })
+                .await?;
+        if stake > active {
+            steps.push(Step::AddStake(stake - active));
+        }
+    }
+
+    let registered = view(
+        provider,
+        validator_manager_address(),
+        ValidatorManagement::isValidatorCall { stakePool: stake_pool },
+    )
+    .await?;
+    if !registered {
+        steps.push(Step::Register);
+        steps.extend(target.fee_recipient.map(Step::SetFeeRecipient));
+        if target.join {
+            steps.push(Step::Join);
+        }
+        return Ok(steps);
+    }
+
+    let record: ValidatorRecord = get_validator(provider, wallet_address, stake_pool).await?;
+    if record.moniker != target.moniker {
+        warnings.push(format!(
+            "on-chain moniker is '{}', monikers can't be changed after registration",
+            record.moniker
+        ));
+    }
+    if record.consensusPubkey.as_ref() != target.consensus_pubkey.as_slice() {
+        steps.push(Step::RotateConsensusKey);
+    }
+    if record.networkAddresses.as_ref() != bcs::to_bytes(&target.network_address)?.as_slice() ||
+        record.fullnodeAddresses.as_ref() != bcs::to_bytes(&target.fullnode_address)?.as_slice()
+    {
+        steps.push(Step::UpdateNetworkAddresses);
+    }
+    if let Some(recipient) = target.fee_recipient {
+        // A change already requested takes effect at the next epoch.
+        let pending = record.pendingFeeRecipient;
+        if record.feeRecipient != recipient && pending != recipient {
+            steps.push(Step::SetFeeRecipient(recipient));
+        }
+    }
+    if target.join && matches!(status_from_u8(record.status), ValidatorStatus::INACTIVE) {
+        steps.push(Step::Join);
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+validators:
+  - stake_pool: "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+    moniker: node-1
+    consensus_public_key: "0x__BLS__"
+    consensus_pop: "__POP__"
+    network_public_key: "__NET__"
+    validator_network_address: /ip4/10.0.0.1/tcp/6180
+    fullnode_network_address: /dns/node-1.example.com/tcp/6182
+    stake: "1000"
+    join: false
+"#;
+
+    fn manifest(replace: &[(&str, &str)]) -> Manifest {
+        let mut yaml = MANIFEST
+            .replace("__BLS__", &"ab".repeat(48))
+            .replace("__POP__", &"cd".repeat(96))
+            .replace("__NET__", &"ef".repeat(32));
+        for (from, to) in replace {
+            yaml = yaml.replace(from, to);
+        }
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn validates_manifest_entries() {
+        let spec = manifest(&[]).validators.into_iter().next().unwrap();
+        let parsed = target(spec, Path::new(".")).unwrap();
+        assert_eq!(parsed.moniker, "node-1");
+        assert_eq!(parsed.consensus_pubkey, vec![0xab; 48]);
+        assert_eq!(
+            parsed.network_address,
+            format!("/ip4/10.0.0.1/tcp/6180/noise-ik/{}/handshake/0", "ef".repeat(32))
+        );
+        assert_eq!(parsed.stake, Some(parse_ether("1000").unwrap()));
+        assert!(!parsed.join && parsed.fee_recipient.is_none());
+
+        let spec = manifest(&[("/ip4/10.0.0.1/tcp/6180", "10.0.0.1:6180")]).validators.remove(0);
+        assert!(target(spec, Path::new(".")).is_err());
+        // Keys come from either an identity file or the manifest, not both.
+        let spec =
+            manifest(&[("    stake: ", "    identity: id.yaml\n    stake: ")]).validators.remove(0);
+        assert!(target(spec, Path::new(".")).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::TransactionReceipt;
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{status_from_u8, ValidatorManagement, ValidatorStatus},
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{get_validator, send},
+    util::{confirm, format_ether, parse_hex},
+    validator::validate_network_address,
+};
+
+#[derive(Debug, Parser)]
+pub struct CompromiseResponseCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address (validator identity)
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Replacement consensus public key (BLS key, 96 hex characters), e.g. from a fresh
+    /// `gravity_cli genesis generate-key`
+    #[clap(long)]
+    pub new_consensus_public_key: String,
+
+    /// Proof of possession for the replacement consensus key (192 hex characters)
+    #[clap(long)]
+    pub new_consensus_pop: String,
+
+    /// Replacement network public key (64 hex characters). Requires both network addresses.
+    #[clap(long, requires_all = ["validator_network_address", "fullnode_network_address"])]
+    pub network_public_key: Option<String>,
+
+    /// Replacement validator network address in /ip4/{host}/tcp/{port} or /dns/{domain}/tcp/{port}
+    /// format
+    #[clap(long, requires = "network_public_key")]
+    pub validator_network_address: Option<String>,
+
+    /// Replacement fullnode network address in /ip4/{host}/tcp/{port} or /dns/{domain}/tcp/{port}
+    /// format
+    #[clap(long, requires = "network_public_key")]
+    pub fullnode_network_address: Option<String>,
+
+    /// New fee recipient address, in case the old one is controlled by the attacker
+    #[clap(long)]
+    pub fee_recipient: Option<String>,
+
+    /// Also request to leave the validator set after the rotation
+    #[clap(long)]
+    pub leave: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(long)]
+    pub yes: bool,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `validator compromise-response` with `--output json`.
+#[derive(Debug, Serialize)]
+struct CompromiseResponseResult {
+    stake_pool: String,
+    /// Steps in order; the flow stops at the first failed one.
+    steps: Vec<StepResult>,
+    /// Validator record after the flow, absent if it stopped early.
+    validator: Option<ValidatorState>,
+}
+
+#[derive(Debug, Serialize)]
+struct StepResult {
+    action: String,
+    transaction: Option<TxSummary>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidatorState {
+    status: String,
+    consensus_public_key: String,
+    network_addresses: String,
+    fee_recipient: String,
+    pending_fee_recipient: String,
+}
+
+/// One transaction of the response flow, validated before anything is sent.
+enum Step {
+    RotateConsensusKey { pubkey: Vec<u8>, pop: Vec<u8> },
+    UpdateNetworkAddresses { validator: String, fullnode: String },
+    SetFeeRecipient(Address),
+    Leave,
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Step::RotateConsensusKey { pubkey, .. } => {
+                format!("Rotate consensus key to {}", hex::encode(pubkey))
+            }
+            Step::UpdateNetworkAddresses { validator, fullnode } => {
+                format!("Update network addresses to {validator} (fullnode: {fullnode})")
+            }
+            Step::SetFeeRecipient(recipient) => format!("Set fee recipient to {recipient}"),
+            Step::Leave => "Request to leave the validator set".to_string(),
+        }
+    }
+
+    fn call_data(&self, stake_pool: Address) -> Result<Bytes, anyhow::Error> {
+        Ok(match self {
+            Step::RotateConsensusKey { pubkey, pop } => {
+                ValidatorManagement::rotateConsensusKeyCall {
+                    stakePool: stake_pool,
+                    newPubkey: pubkey.clone().into(),
+                    newPop: pop.clone().into(),
+                }
+                .abi_encode()
+            }
+            Step::UpdateNetworkAddresses { validator, fullnode } => {
+                ValidatorManagement::updateNetworkAddressesCall {
+                    stakePool: stake_pool,
+                    networkAddresses: bcs::to_bytes(validator)?.into(),
+                    fullnodeAddresses: bcs::to_bytes(fullnode)?.into(),
+                }
+                .abi_encode()
+            }
+            Step::SetFeeRecipient(recipient) => ValidatorManagement::setFeeRecipientCall {
+                stakePool: stake_pool,
+                newRecipient: *recipient,
+            }
+            .abi_encode(),
+            Step::Leave => {
+                ValidatorManagement::leaveValidatorSetCall { stakePool: stake_pool }.abi_encode()
+            }
+        }
+        .into())
+    }
+
+    /// Checks the receipt for the event the contract emits on success, where there is one.
+    fn confirm(&self, receipt: &TransactionReceipt) -> Result<(), anyhow::Error> {
+        let found = match self {
+            Step::RotateConsensusKey { .. } => receipt.logs().iter().any(|log| {
+                ValidatorManagement::ConsensusKeyRotated::decode_log(&log.inner).is_ok()
+            }),
+            Step::SetFeeRecipient(_) => receipt.logs().iter().any(|log| {
+                ValidatorManagement::FeeRecipientUpdated::decode_log(&log.inner).is_ok()
+            }),
+            Step::Leave => receipt.logs().iter().any(|log| {
+                ValidatorManagement::ValidatorLeaveRequested::decode_log(&log.inner).is_ok()
+            }),
+            Step::UpdateNetworkAddresses { .. } => receipt.status(),
+        };
+        if !found {
+            return Err(anyhow::anyhow!("Expected event not found in the transaction receipt"));
+        }
+        Ok(())
+    }
+}
+
+impl Executable for CompromiseResponseCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl CompromiseResponseCommand {
+    /// Validates every input up front, so a typo is caught before the first transaction rather
+    /// than halfway through the flow.
+    fn plan(&self) -> Result<Vec<Step>, anyhow::Error> {
+        let mut steps = vec![Step::RotateConsensusKey {
+            pubkey: parse_hex(&self.new_consensus_public_key, 48, "consensus public key")?,
+            pop: parse_hex(&self.new_consensus_pop, 96, "consensus proof of possession")?,
+        }];
+
+        if let (Some(network_public_key), Some(validator), Some(fullnode)) = (
+            &self.network_public_key,
+            &self.validator_network_address,
+            &self.fullnode_network_address,
+        ) {
+            let network_pk = hex::encode(parse_hex(network_public_key, 32, "network public key")?);
+            validate_network_address(validator, "validator network")?;
+            validate_network_address(fullnode, "fullnode network")?;
+            steps.push(Step::UpdateNetworkAddresses {
+                validator: format!("{validator}/noise-ik/{network_pk}/handshake/0"),
+                fullnode: format!("{fullnode}/noise-ik/{network_pk}/handshake/0"),
+            });
+        }
+
+        if let Some(fee_recipient) = &self.fee_recipient {
+            steps.push(Step::SetFeeRecipient(Address::from_str(fee_recipient)?));
+        }
+        if self.leave {
+            steps.push(Step::Leave);
+        }
+        Ok(steps)
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.clone().ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool)?;
+        let steps = self.plan()?;
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
+
+        // 2. Check validator information
+        progress!(format, "2. Checking validator information...");
+        let record = get_validator(&provider, wallet_address, stake_pool).await?;
+        let status = status_from_u8(record.status);
+        progress!(format, "   - Validator: {}", record.validator);
+        progress!(format, "   - Moniker: {}", record.moniker);
+        progress!(format, "   - Status: {status:?}");
+        progress!(format, "   - Consensus public key: {}", hex::encode(&record.consensusPubkey));
+        progress!(format, "   - Fee recipient: {}\n", record.feeRecipient);
+
+        // 3. Confirm the plan
+        progress!(format, "3. The following transactions will be sent, in order:");
+        for (i, step) in steps.iter().enumerate() {
+            progress!(format, "   {}. {}", i + 1, step.describe());
+        }
+        if self.leave &&
+            matches!(status, ValidatorStatus::INACTIVE | ValidatorStatus::PENDING_INACTIVE)
+        {
+            progress!(
+                format,
+                "   Note: validator is already {status:?}, the leave request may be rejected"
+            );
+        }
+        if !self.yes && !confirm(Msg::ProceedPrompt, format)? {
+            return Err(CatalogError::new(Msg::Aborted, &[]).into());
+        }
+        progress!(format);
+
+        // 4. Execute
+        progress!(format, "4. Sending transactions...");
+        let mut result = CompromiseResponseResult {
+            stake_pool: format!("{stake_pool}"),
+            steps: Vec::with_capacity(steps.len()),
+            validator: None,
+        };
+        for (i, step) in steps.iter().enumerate() {
+            progress!(format, "   [{}/{}] {}", i + 1, steps.len(), step.describe());
+            let result = async {
+                let input = step.call_data(stake_pool)?;
+                let receipt = send(&provider, wallet_address, input, &fees, format).await?;
+                step.confirm(&receipt)?;
+                Ok::<_, anyhow::Error>(receipt)
+            }
+            .await;
+            match result {
+                Ok(receipt) => {
+                    progress!(
+                        format,
+                        "   Done in block {}, cost {} ETH",
+                        receipt.block_number.unwrap_or_default(),
+                        format_ether(
+                            U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used)
+                        )
+                    );
+                    result.steps.push(StepResult {
+                        action: step.describe(),
+                        transaction: Some(TxSummary::new(&receipt)),
+                        error: None,
+                    });
+                }
+                Err(e) => {
+                    result.steps.push(StepResult {
+                        action: step.describe(),
+                        transaction: None,
+                        error: Some(e.to_string()),
+                    });
+                    format.emit(&result)?;
+                    // The operator needs to know exactly where the flow stopped to finish by hand.
+                    eprintln!("   FAILED: {e}");
+                    for done in &steps[..i] {
+                        eprintln!("   Done: {}", done.describe());
+                    }
+                    for remaining in &steps[i..] {
+                        eprintln!("   Not done: {}", remaining.describe());
+                    }
+                    return Err(anyhow::anyhow!(
+                        "Compromise response stopped at step {} of {}",
+                        i + 1,
+                        steps.len()
+                    ));
+                }
+            }
+        }
+        progress!(format);
+
+        // 5. Final status check
+        progress!(format, "5. Final status check...");
+        let record = get_validator(&provider, wallet_address, stake_pool).await?;
+        let state = ValidatorState {
+            status: format!("{:?}", status_from_u8(record.status)),
+            consensus_public_key: hex::encode(&record.consensusPubkey),
+            network_addresses: bcs::from_bytes::<String>(&record.networkAddresses)
+                .unwrap_or_else(|_| hex::encode(&record.networkAddresses)),
+            fee_recipient: format!("{}", record.feeRecipient),
+            pending_fee_recipient: format!("{}", record.pendingFeeRecipient),
+        };
+        progress!(format, "   - Status: {}", state.status);
+        progress!(format, "   - Consensus public key: {}", state.consensus_public_key);
+        progress!(format, "   - Network addresses: {}", state.network_addresses);
+        progress!(format, "   - Fee recipient: {}", state.fee_recipient);
+        progress!(format, "   - Pending fee recipient: {}", state.pending_fee_recipient);
+        progress!(format, "   Key and fee recipient changes take effect at the next epoch.");
+        progress!(format, "   Restart the node with the new identity once the epoch has changed.");
+        result.validator = Some(state);
+        format.emit(&result)
+    }
+}
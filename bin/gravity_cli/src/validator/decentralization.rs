@@ -0,0 +1,224 @@
+use alloy_primitives::{Bytes, TxKind, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::{TransactionInput, TransactionRequest};
+use alloy_sol_types::SolCall;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{
+    command::Executable,
+    contract::{validator_manager_address, ValidatorManagement},
+    i18n::MissingOption,
+    output::OutputFormat,
+    util::format_ether,
+};
+
+#[derive(Debug, Parser)]
+pub struct DecentralizationCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// Report the voting power share of the N largest validators
+    #[clap(long, default_value_t = 5)]
+    pub top: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct DecentralizationReport {
+    current_epoch: u64,
+    active_count: usize,
+    total_voting_power: String,
+    /// Fewest validators holding more than 1/3 of the voting power, enough to halt the chain.
+    nakamoto_coefficient: usize,
+    /// Fewest validators holding more than 2/3 of the voting power, enough to commit alone.
+    nakamoto_coefficient_two_thirds: usize,
+    /// 0 when voting power is spread evenly, approaching 1 when one validator holds it all.
+    gini_coefficient: f64,
+    top_n: usize,
+    /// Percentage of the total voting power held by the `top_n` largest validators.
+    top_n_share_percent: f64,
+    validators: Vec<ValidatorShare>,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidatorShare {
+    validator: String,
+    voting_power: String,
+    share_percent: f64,
+}
+
+impl Executable for DecentralizationCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl DecentralizationCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+
+        let call = ValidatorManagement::getCurrentEpochCall {};
+        let input: Bytes = call.abi_encode().into();
+        let result = provider
+            .call(TransactionRequest {
+                to: Some(TxKind::Call(validator_manager_address())),
+                input: TransactionInput::new(input),
+                ..Default::default()
+            })
+            .await?;
+        let current_epoch = ValidatorManagement::getCurrentEpochCall::abi_decode_returns(&result)
+            .map_err(|e| anyhow::anyhow!("Failed to decode current epoch: {e}"))?;
+
+        let call = ValidatorManagement::getActiveValidatorsCall {};
+        let input: Bytes = call.abi_encode().into();
+        let result = provider
+            .call(TransactionRequest {
+                to: Some(TxKind::Call(validator_manager_address())),
+                input: TransactionInput::new(input),
+                ..Default::default()
+            })
+            .await?;
+        let mut active_validators =
+            ValidatorManagement::getActiveValidatorsCall::abi_decode_returns(&result)
+                .map_err(|e| anyhow::anyhow!("Failed to decode active validators: {e}"))?;
+        if active_validators.is_empty() {
+            return Err(anyhow::anyhow!("The active validator set is empty"));
+        }
+        active_validators.sort_by(|a, b| b.votingPower.cmp(&a.votingPower));
+
+        let powers: Vec<U256> = active_validators.iter().map(|v| v.votingPower).collect();
+        let total: U256 = powers.iter().fold(U256::ZERO, |acc, p| acc.saturating_add(*p));
+        let top_n = self.top.min(powers.len());
+        let report = DecentralizationReport {
+            current_epoch,
+            active_count: powers.len(),
+            total_voting_power: format_ether(total),
+            nakamoto_coefficient: nakamoto_coefficient(&powers, 1, 3),
+            nakamoto_coefficient_two_thirds: nakamoto_coefficient(&powers, 2, 3),
+            gini_coefficient: gini_coefficient(&powers),
+            top_n,
+            top_n_share_percent: share_percent(powers[..top_n].iter().copied(), total),
+            validators: active_validators
+                .iter()
+                .map(|v| ValidatorShare {
+                    validator: format!("{:?}", v.validator),
+                    voting_power: format_ether(v.votingPower),
+                    share_percent: share_percent([v.votingPower], total),
+                })
+                .collect(),
+        };
+
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            OutputFormat::Plain => {
+                println!(
+                    "Epoch: {}  |  Active: {}  |  Total Voting Power: {} ETH",
+                    report.current_epoch, report.active_count, report.total_voting_power
+                );
+                println!();
+                println!("Nakamoto coefficient (>1/3):  {}", report.nakamoto_coefficient);
+                println!(
+                    "Nakamoto coefficient (>2/3):  {}",
+                    report.nakamoto_coefficient_two_thirds
+                );
+                println!("Gini coefficient:             {:.4}", report.gini_coefficient);
+                println!(
+                    "Top {} share:                 {:.2}%",
+                    report.top_n, report.top_n_share_percent
+                );
+                println!();
+                println!("{:<44} {:<24} Share", "Validator", "Voting Power");
+                println!("{}", "-".repeat(78));
+                for v in &report.validators {
+                    println!("{:<44} {:<24} {:.2}%", v.validator, v.voting_power, v.share_percent);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fewest validators whose combined voting power exceeds `num/den` of the total.
+/// `powers` must be sorted in descending order.
+pub(super) fn nakamoto_coefficient(powers: &[U256], num: u64, den: u64) -> usize {
+    let total: U256 = powers.iter().fold(U256::ZERO, |acc, p| acc.saturating_add(*p));
+    let threshold = total.saturating_mul(U256::from(num));
+    let mut cumulative = U256::ZERO;
+    for (i, power) in powers.iter().enumerate() {
+        cumulative = cumulative.saturating_add(*power);
+        if cumulative.saturating_mul(U256::from(den)) > threshold {
+            return i + 1;
+        }
+    }
+    powers.len()
+}
+
+/// Gini coefficient of the voting power distribution.
+fn gini_coefficient(powers: &[U256]) -> f64 {
+    let mut values: Vec<f64> = powers.iter().map(|p| to_f64(*p)).collect();
+    values.sort_by(|a, b| a.total_cmp(b));
+    let n = values.len() as f64;
+    let sum: f64 = values.iter().sum();
+    if values.is_empty() || sum == 0.0 {
+        return 0.0;
+    }
+    // G = sum_i (2i - n - 1) * x_i / (n * sum), with x ascending and i starting at 1
+    let weighted: f64 =
+        values.iter().enumerate().map(|(i, x)| (2.0 * (i as f64 + 1.0) - n - 1.0) * x).sum();
+    weighted / (n * sum)
+}
+
+fn share_percent(powers: impl IntoIterator<Item = U256>, total: U256) -> f64 {
+    let total = to_f64(total);
+    if total == 0.0 {
+        return 0.0;
+    }
+    powers.into_iter().map(to_f64).sum::<f64>() * 100.0 / total
+}
+
+fn to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(f64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn powers(values: &[u64]) -> Vec<U256> {
+        values.iter().map(|v| U256::from(*v)).collect()
+    }
+
+    #[test]
+    fn nakamoto_coefficient_counts_validators_past_threshold() {
+        // Even split of 4: two validators hold exactly 1/2 (> 1/3), three hold 3/4 (> 2/3)
+        let even = powers(&[25, 25, 25, 25]);
+        assert_eq!(nakamoto_coefficient(&even, 1, 3), 2);
+        assert_eq!(nakamoto_coefficient(&even, 2, 3), 3);
+
+        let skewed = powers(&[50, 20, 20, 10]);
+        assert_eq!(nakamoto_coefficient(&skewed, 1, 3), 1);
+        assert_eq!(nakamoto_coefficient(&skewed, 2, 3), 2);
+
+        // Exactly 1/3 is not enough
+        assert_eq!(nakamoto_coefficient(&powers(&[1, 1, 1]), 1, 3), 2);
+    }
+
+    #[test]
+    fn gini_coefficient_bounds() {
+        assert_eq!(gini_coefficient(&powers(&[10, 10, 10, 10])), 0.0);
+        let concentrated = gini_coefficient(&powers(&[100, 0, 0, 0]));
+        assert!((concentrated - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn top_share_percent() {
+        let p = powers(&[50, 30, 20]);
+        let total = U256::from(100u64);
+        assert!((share_percent(p[..2].iter().copied(), total) - 80.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,213 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{
+        epoch_config_address, reconfiguration_address, staking_address, EpochConfig,
+        Reconfiguration, StakePool, Staking,
+    },
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{finish_dry_run, send_to, simulate, DryRunArgs},
+    util::format_ether,
+    validator::{
+        status::{format_duration, view},
+        unstake::lockup,
+    },
+};
+
+#[derive(Debug, Parser)]
+pub struct ExtendLockupCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address whose lockup to extend
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Seconds to extend the lockup by, from the current expiry or from now if it has passed
+    #[clap(long)]
+    pub duration: u64,
+
+    #[clap(flatten)]
+    pub dry_run: DryRunArgs,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `validator extend-lockup` with `--output json`.
+#[derive(Debug, Serialize)]
+struct ExtendLockupResult {
+    stake_pool: String,
+    transaction: TxSummary,
+    /// Lockup expiry before and after, in microseconds since the Unix epoch.
+    old_locked_until_micros: u64,
+    new_locked_until_micros: u64,
+}
+
+impl Executable for ExtendLockupCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl ExtendLockupCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool).map_err(|e| {
+            anyhow::anyhow!("Invalid stake pool address '{}': {e}", self.stake_pool)
+        })?;
+        if self.duration == 0 {
+            return Err(anyhow::anyhow!("--duration must be greater than zero"));
+        }
+        let duration_micros = self
+            .duration
+            .checked_mul(1_000_000)
+            .ok_or_else(|| anyhow::anyhow!("--duration {} is too large", self.duration))?;
+
+        if self.dry_run.enabled {
+            progress!(format, "Dry run: the transaction is simulated, nothing is sent\n");
+        }
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
+
+        // 2. Preflight: current lockup against the next epoch
+        progress!(format, "2. Checking StakePool: {stake_pool:?}");
+        if !view(&provider, staking_address(), Staking::isPoolCall { pool: stake_pool }).await? {
+            return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+        }
+        let (locked_until, now_micros) = lockup(&provider, stake_pool).await?;
+        progress!(format, "   getPoolLockedUntil: {locked_until}");
+        if locked_until > now_micros {
+            progress!(
+                format,
+                "   Lockup expires in {}",
+                format_duration((locked_until - now_micros) / 1_000_000)
+            );
+        } else {
+            eprintln!(
+                "   Warning: the lockup expired {} ago; the validator may be removed from the \
+                 active set at the next epoch",
+                format_duration((now_micros - locked_until) / 1_000_000)
+            );
+        }
+        let last_reconfiguration = view(
+            &provider,
+            reconfiguration_address(),
+            Reconfiguration::lastReconfigurationTimeCall {},
+        )
+        .await?;
+        let interval =
+            view(&provider, epoch_config_address(), EpochConfig::epochIntervalMicrosCall {})
+                .await?;
+        let next_epoch_micros = last_reconfiguration.saturating_add(interval);
+        let new_locked_until = locked_until.max(now_micros).saturating_add(duration_micros);
+        progress!(
+            format,
+            "   New lockup: {new_locked_until} (in {})",
+            format_duration(new_locked_until.saturating_sub(now_micros) / 1_000_000)
+        );
+        if new_locked_until <= next_epoch_micros.saturating_add(interval) {
+            eprintln!(
+                "   Warning: the new lockup ends within two epochs (epoch interval {}); extend \
+                 by more to stay in the active set for longer",
+                format_duration(interval / 1_000_000)
+            );
+        }
+        progress!(format);
+
+        // 3. Extend the lockup
+        progress!(format, "3. Extending the lockup by {}...", format_duration(self.duration));
+        let input: Bytes =
+            StakePool::renewLockUntilCall { durationMicros: duration_micros }.abi_encode().into();
+        if self.dry_run.enabled {
+            let simulation = simulate(
+                &provider,
+                wallet_address,
+                stake_pool,
+                input,
+                "renewLockUntil",
+                "LockupRenewed",
+                format,
+            )
+            .await?;
+            return finish_dry_run(stake_pool, &[simulation], format);
+        }
+        let receipt = send_to(&provider, wallet_address, stake_pool, input, &fees, format).await?;
+        progress!(
+            format,
+            "   Transaction confirmed, block number: {}",
+            receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
+        );
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
+            "   Transaction cost: {} ETH",
+            format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
+        );
+
+        // Check lockup event
+        let mut found = None;
+        for log in receipt.logs() {
+            if let Ok(event) = StakePool::LockupRenewed::decode_log(&log.inner) {
+                progress!(format, "   Lockup extended successfully!");
+                progress!(format, "   - StakePool: {}", event.pool);
+                progress!(
+                    format,
+                    "   - Locked until: {} -> {}",
+                    event.oldLockedUntil,
+                    event.newLockedUntil
+                );
+                found = Some((
+                    event.newLockedUntil,
+                    serde_json::json!({
+                        "pool": format!("{}", event.pool),
+                        "old_locked_until": event.oldLockedUntil,
+                        "new_locked_until": event.newLockedUntil,
+                    }),
+                ));
+                break;
+            }
+        }
+        let Some((new_locked_until, event)) = found else {
+            progress!(format, "   Lockup event not found\n");
+            return Err(anyhow::anyhow!("Failed to find LockupRenewed event"));
+        };
+        progress!(
+            format,
+            "   Lockup now expires in {}\n",
+            format_duration(new_locked_until.saturating_sub(now_micros) / 1_000_000)
+        );
+        format.emit(&ExtendLockupResult {
+            stake_pool: format!("{stake_pool}"),
+            transaction: TxSummary::new(&receipt).with_event(event),
+            old_locked_until_micros: locked_until,
+            new_locked_until_micros: new_locked_until,
+        })
+    }
+}
@@ -3,16 +3,23 @@ use alloy_provider::{Provider, ProviderBuilder};
 use alloy_rpc_types::eth::{TransactionInput, TransactionRequest};
 use alloy_sol_types::{SolCall, SolEvent, SolType, SolValue};
 use clap::Parser;
-use std::str::FromStr;
+use serde::Serialize;
+use std::{io::Write, str::FromStr};
 
 use crate::{
     command::Executable,
     contract::{
-        status_from_u8, Staking, ValidatorManagement, ValidatorRecord, ValidatorStatus,
-        STAKING_ADDRESS, VALIDATOR_MANAGER_ADDRESS,
+        staking_address, status_from_u8, validator_manager_address, StakePool, Staking,
+        ValidatorManagement, ValidatorRecord, ValidatorStatus,
     },
+    fees::FeeArgs,
+    history,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
     signer::SignerArgs,
-    util::format_ether,
+    transaction::{finish_dry_run, send_value, simulate, simulate_value, DryRunArgs},
+    util::{confirm, format_ether, parse_ether, parse_hex},
+    validator::{status::view, validate_network_address},
 };
 
 #[derive(Debug, Parser)]
@@ -21,89 +28,130 @@ pub struct JoinCommand {
     #[clap(long, env = "GRAVITY_RPC_URL")]
     pub rpc_url: Option<String>,
 
-    /// Gas limit for the transaction
-    #[clap(long, env = "GRAVITY_GAS_LIMIT")]
-    pub gas_limit: Option<u64>,
-
-    /// Gas price in wei
-    #[clap(long, env = "GRAVITY_GAS_PRICE")]
-    pub gas_price: Option<u128>,
-
     /// StakePool address to use for validator registration
-    #[clap(long)]
-    pub stake_pool: String,
+    #[clap(long, required_unless_present = "interactive")]
+    pub stake_pool: Option<String>,
 
     /// Moniker (display name, max 31 bytes)
     #[clap(long, default_value = "Gravity1")]
     pub moniker: String,
 
     /// Consensus public key (BLS key)
-    #[clap(long)]
-    pub consensus_public_key: String,
+    #[clap(long, required_unless_present = "interactive")]
+    pub consensus_public_key: Option<String>,
 
     /// Proof of possession for the BLS consensus key (192 hex characters, 96 bytes).
     /// This is a BLS signature over the public key, proving ownership of the private key.
     /// It can be found in the `consensus_pop` field of the identity.yaml
     /// file generated by `gravity_cli genesis generate-key`.
-    #[clap(long)]
-    pub consensus_pop: String,
+    #[clap(long, required_unless_present = "interactive")]
+    pub consensus_pop: Option<String>,
 
     /// Network public key (64 hex characters)
-    #[clap(long)]
-    pub network_public_key: String,
+    #[clap(long, required_unless_present = "interactive")]
+    pub network_public_key: Option<String>,
 
     /// Validator network address in /ip4/{host}/tcp/{port} or /dns/{domain}/tcp/{port} format
-    #[clap(long)]
-    pub validator_network_address: String,
+    #[clap(long, required_unless_present = "interactive")]
+    pub validator_network_address: Option<String>,
 
     /// Fullnode network address in /ip4/{host}/tcp/{port} or /dns/{domain}/tcp/{port} format
+    #[clap(long, required_unless_present = "interactive")]
+    pub fullnode_network_address: Option<String>,
+
+    #[clap(flatten)]
+    pub dry_run: DryRunArgs,
+
+    /// Ask for each input in turn with live validation of key lengths, address formats and the
+    /// wallet balance, optionally adding stake, before any transaction is sent. Values given as
+    /// flags are offered as defaults
     #[clap(long)]
-    pub fullnode_network_address: String,
+    pub interactive: bool,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
 
     #[clap(flatten)]
     pub signer: SignerArgs,
 }
 
+/// Result of `validator join` with `--output json`.
+#[derive(Debug, Serialize)]
+struct JoinResult {
+    stake_pool: String,
+    /// Stake added by the `--interactive` wizard before registering.
+    stake: Option<TxSummary>,
+    /// Absent when the StakePool was already registered.
+    registration: Option<TxSummary>,
+    /// Absent when the validator was not INACTIVE, e.g. already active or joining.
+    join: Option<TxSummary>,
+    status: String,
+}
+
+/// What to register and join with, from the flags or the `--interactive` wizard.
+struct JoinInputs {
+    stake_pool: Address,
+    /// Stake to add to the pool before registering, zero unless given to the wizard.
+    stake: U256,
+    moniker: String,
+    consensus_public_key: String,
+    consensus_pop: String,
+    network_public_key: String,
+    validator_network_address: String,
+    fullnode_network_address: String,
+}
+
 impl Executable for JoinCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
 impl JoinCommand {
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
-        let rpc_url = self.rpc_url.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--rpc-url is required. Set via CLI flag, GRAVITY_RPC_URL env var, or ~/.gravity/config.toml"
-            )
-        })?;
-        let gas_limit = self.gas_limit.unwrap_or(2_000_000);
-        let gas_price = self.gas_price.unwrap_or(100_000_000_000);
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+
+        if self.interactive && format.is_json() {
+            return Err(anyhow::anyhow!("--interactive can't be used with --output json"));
+        }
+        if self.dry_run.enabled {
+            progress!(format, "Dry run: transactions are simulated, nothing is sent\n");
+        }
 
         // 1. Initialize Provider and Wallet
-        println!("1. Initializing connection...");
+        progress!(format, "1. Initializing connection...");
 
-        println!("   RPC URL: {rpc_url}");
+        progress!(format, "   RPC URL: {rpc_url}");
         let resolved = self.signer.resolve().await?;
         let wallet_address = resolved.address;
-        println!("   Wallet address: {wallet_address:?}");
+        progress!(format, "   Wallet address: {wallet_address:?}");
 
-        println!("   ValidatorManagement: {VALIDATOR_MANAGER_ADDRESS:?}");
-        println!("   Staking: {STAKING_ADDRESS:?}");
+        progress!(format, "   ValidatorManagement: {:?}", validator_manager_address());
+        progress!(format, "   Staking: {:?}", staking_address());
 
         // Create provider
         let provider =
             ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
 
         let chain_id = provider.get_chain_id().await?;
-        println!("   Chain ID: {chain_id}");
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}", fees.describe());
         let balance = provider.get_balance(wallet_address).await?;
-        println!("   Wallet balance: {} ETH\n", format_ether(balance));
+        progress!(format, "   Wallet balance: {} ETH\n", format_ether(balance));
+
+        let inputs = if self.interactive {
+            self.wizard(&provider, balance, format).await?
+        } else {
+            self.inputs()?
+        };
+        let mut simulations = Vec::new();
 
         // 2. Validate existing StakePool
-        let stake_pool = Address::from_str(&self.stake_pool)?;
-        println!("2. Validating StakePool: {stake_pool:?}");
+        let stake_pool = inputs.stake_pool;
+        progress!(format, "2. Validating StakePool: {stake_pool:?}");
 
         // Verify it's a valid pool
         let call = Staking::isPoolCall { pool: stake_pool };
@@ -111,7 +159,7 @@ impl JoinCommand {
         let result = provider
             .call(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(STAKING_ADDRESS)),
+                to: Some(TxKind::Call(staking_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -119,7 +167,7 @@ impl JoinCommand {
         let is_pool = bool::abi_decode(&result)
             .map_err(|e| anyhow::anyhow!("Failed to decode isPool result: {e}"))?;
         if !is_pool {
-            return Err(anyhow::anyhow!("Address is not a valid StakePool"));
+            return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
         }
 
         // Check voting power
@@ -128,113 +176,100 @@ impl JoinCommand {
         let result = provider
             .call(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(STAKING_ADDRESS)),
+                to: Some(TxKind::Call(staking_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
             .await?;
         let voting_power = U256::abi_decode(&result)
             .map_err(|e| anyhow::anyhow!("Failed to decode voting power: {e}"))?;
-        println!("   Current voting power: {} ETH\n", format_ether(voting_power));
+        progress!(format, "   Current voting power: {} ETH", format_ether(voting_power));
+
+        let mut stake = None;
+        if !inputs.stake.is_zero() {
+            progress!(format, "   Adding {} ETH of stake...", format_ether(inputs.stake));
+            let input: Bytes = StakePool::addStakeCall {}.abi_encode().into();
+            if self.dry_run.enabled {
+                simulations.push(
+                    simulate_value(
+                        &provider,
+                        wallet_address,
+                        stake_pool,
+                        input,
+                        Some(inputs.stake),
+                        "addStake",
+                        "StakeAdded",
+                        format,
+                    )
+                    .await?,
+                );
+            } else {
+                let receipt = send_value(
+                    &provider,
+                    wallet_address,
+                    stake_pool,
+                    input,
+                    Some(inputs.stake),
+                    &fees,
+                    format,
+                )
+                .await?;
+                let event = receipt
+                    .logs()
+                    .iter()
+                    .find_map(|log| StakePool::StakeAdded::decode_log(&log.inner).ok())
+                    .ok_or_else(|| anyhow::anyhow!("Failed to find StakeAdded event"))?;
+                progress!(
+                    format,
+                    "   Stake added, block number: {}",
+                    receipt.block_number.unwrap_or_default()
+                );
+                stake = Some(TxSummary::new(&receipt).with_event(serde_json::json!({
+                    "pool": format!("{}", event.pool),
+                    "amount": format_ether(event.amount),
+                })));
+            }
+        }
+        progress!(format);
 
         // 3. Check if already registered as validator
-        println!("3. Checking if already registered as validator...");
+        progress!(format, "3. Checking if already registered as validator...");
         let call = ValidatorManagement::isValidatorCall { stakePool: stake_pool };
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
             .await?;
         let is_validator = bool::abi_decode(&result)
             .map_err(|e| anyhow::anyhow!("Failed to decode isValidator result: {e}"))?;
-        println!("   Is registered: {is_validator}");
+        progress!(format, "   Is registered: {is_validator}");
 
+        let mut registration = None;
         if is_validator {
-            println!("   Validator is already registered, skipping registration step\n");
+            progress!(format, "   Validator is already registered, skipping registration step\n");
         } else {
             // 4. Register validator
-            println!("4. Registering validator...");
+            progress!(format, "4. Registering validator...");
 
             // Validate moniker length (must not exceed 31 bytes, matching on-chain
             // MAX_MONIKER_LENGTH)
-            if self.moniker.len() > 31 {
-                return Err(anyhow::anyhow!(
-                    "Moniker too long: max 31 bytes, got {} bytes",
-                    self.moniker.len()
-                ));
-            }
+            check_moniker(&inputs.moniker)?;
 
-            // Validate consensus public key format: must be exactly 96 hex characters (48 bytes BLS
-            // key)
-            let consensus_pk =
-                self.consensus_public_key.strip_prefix("0x").unwrap_or(&self.consensus_public_key);
-            if consensus_pk.len() != 96 {
-                return Err(anyhow::anyhow!(
-                    "Invalid consensus public key: expected 96 hex characters (48 bytes), got {} characters",
-                    consensus_pk.len()
-                ));
-            }
-            if !consensus_pk.chars().all(|c| c.is_ascii_hexdigit()) {
-                return Err(anyhow::anyhow!(
-                    "Invalid consensus public key: contains non-hexadecimal characters"
-                ));
-            }
-
-            // Validate network public key: must be exactly 64 hex characters (32 bytes)
+            // Consensus public key: 48 bytes BLS key. Network public key: 32 bytes x25519 key.
+            // Consensus proof of possession: 96 bytes; cryptographic PoP verification is
+            // performed on-chain by ValidatorManagement, here we only enforce the wire format.
+            let consensus_pk = parse_hex(&inputs.consensus_public_key, 48, "consensus public key")?;
             let network_pk =
-                self.network_public_key.strip_prefix("0x").unwrap_or(&self.network_public_key);
-            if network_pk.len() != 64 {
-                return Err(anyhow::anyhow!(
-                    "Invalid network public key: expected 64 hex characters (32 bytes), got {} characters",
-                    network_pk.len()
-                ));
-            }
-            if !network_pk.chars().all(|c| c.is_ascii_hexdigit()) {
-                return Err(anyhow::anyhow!(
-                    "Invalid network public key: contains non-hexadecimal characters"
-                ));
-            }
-
-            // Validate consensus proof of possession: must be exactly 192 hex characters (96
-            // bytes). Cryptographic PoP verification is performed on-chain by
-            // ValidatorManagement; here we only enforce the wire format.
+                hex::encode(parse_hex(&inputs.network_public_key, 32, "network public key")?);
             let consensus_pop =
-                self.consensus_pop.strip_prefix("0x").unwrap_or(&self.consensus_pop);
-            if consensus_pop.len() != 192 {
-                return Err(anyhow::anyhow!(
-                    "Invalid consensus proof of possession: expected 192 hex characters (96 bytes), got {} characters",
-                    consensus_pop.len()
-                ));
-            }
-            if !consensus_pop.chars().all(|c| c.is_ascii_hexdigit()) {
-                return Err(anyhow::anyhow!(
-                    "Invalid consensus proof of possession: contains non-hexadecimal characters"
-                ));
-            }
+                parse_hex(&inputs.consensus_pop, 96, "consensus proof of possession")?;
 
-            // Validate address format: /ip4/{host}/tcp/{port} or /dns/{domain}/tcp/{port}
-            fn validate_network_address(addr: &str, label: &str) -> Result<(), anyhow::Error> {
-                let parts: Vec<&str> = addr.split('/').collect();
-                // Expected: ["", "ip4"|"dns"|"dns4"|"dns6", "{host}", "tcp", "{port}"]
-                if parts.len() != 5 ||
-                    !parts[0].is_empty() ||
-                    !matches!(parts[1], "ip4" | "dns" | "dns4" | "dns6") ||
-                    parts[2].is_empty() ||
-                    parts[3] != "tcp" ||
-                    parts[4].parse::<u16>().is_err()
-                {
-                    return Err(anyhow::anyhow!(
-                        "Invalid {label} address: expected /ip4/{{host}}/tcp/{{port}} or /dns/{{domain}}/tcp/{{port}} format, got '{addr}'"
-                    ));
-                }
-                Ok(())
-            }
-            validate_network_address(&self.validator_network_address, "validator network")?;
-            validate_network_address(&self.fullnode_network_address, "fullnode network")?;
+            validate_network_address(&inputs.validator_network_address, "validator network")?;
+            validate_network_address(&inputs.fullnode_network_address, "fullnode network")?;
 
             // Construct full addresses:
             // /{ip4|dns}/{host}/tcp/{port}/noise-ik/{network_public_key}/handshake/0
@@ -247,38 +282,67 @@ impl JoinCommand {
             // it under both addresses matches what the process actually listens with.
             // This is not key reuse across separable identities — there is only one.
             let validator_full_addr =
-                format!("{}/noise-ik/{}/handshake/0", self.validator_network_address, network_pk);
+                format!("{}/noise-ik/{}/handshake/0", inputs.validator_network_address, network_pk);
             let fullnode_full_addr =
-                format!("{}/noise-ik/{}/handshake/0", self.fullnode_network_address, network_pk);
-
-            println!("   Moniker: \"{}\"", self.moniker);
-            println!("   Consensus public key: {consensus_pk} ({} bytes)", consensus_pk.len() / 2);
-            println!("   Consensus PoP: {consensus_pop} ({} bytes)", consensus_pop.len() / 2);
-            println!("   Network public key: {network_pk}");
-            println!("   Validator address: {validator_full_addr}");
-            println!("   Fullnode address: {fullnode_full_addr}");
+                format!("{}/noise-ik/{}/handshake/0", inputs.fullnode_network_address, network_pk);
+
+            progress!(format, "   Moniker: \"{}\"", inputs.moniker);
+            progress!(
+                format,
+                "   Consensus public key: {} ({} bytes)",
+                hex::encode(&consensus_pk),
+                consensus_pk.len()
+            );
+            progress!(
+                format,
+                "   Consensus PoP: {} ({} bytes)",
+                hex::encode(&consensus_pop),
+                consensus_pop.len()
+            );
+            progress!(format, "   Network public key: {network_pk}");
+            progress!(format, "   Validator address: {validator_full_addr}");
+            progress!(format, "   Fullnode address: {fullnode_full_addr}");
 
             let call = ValidatorManagement::registerValidatorCall {
                 stakePool: stake_pool,
-                moniker: self.moniker.clone(),
-                consensusPubkey: hex::decode(consensus_pk)?.into(),
-                consensusPop: hex::decode(consensus_pop)?.into(),
+                moniker: inputs.moniker.clone(),
+                consensusPubkey: consensus_pk.into(),
+                consensusPop: consensus_pop.into(),
                 networkAddresses: bcs::to_bytes(&validator_full_addr)?.into(),
                 fullnodeAddresses: bcs::to_bytes(&fullnode_full_addr)?.into(),
             };
             let input: Bytes = call.abi_encode().into();
+            if self.dry_run.enabled {
+                simulations.push(
+                    simulate(
+                        &provider,
+                        wallet_address,
+                        validator_manager_address(),
+                        input,
+                        "registerValidator",
+                        "ValidatorRegistered",
+                        format,
+                    )
+                    .await?,
+                );
+                // The join reverts for an unregistered pool until the registration is mined.
+                progress!(
+                    format,
+                    "   joinValidatorSet can only be simulated once the registration is on chain"
+                );
+                return finish_dry_run(stake_pool, &simulations, format);
+            }
             let pending_tx = provider
-                .send_transaction(TransactionRequest {
+                .send_transaction(fees.apply(TransactionRequest {
                     from: Some(wallet_address),
-                    to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                    to: Some(TxKind::Call(validator_manager_address())),
                     input: TransactionInput::new(input),
-                    gas: Some(gas_limit),
-                    gas_price: Some(gas_price),
                     ..Default::default()
-                })
+                }))
                 .await?;
             let tx_hash = *pending_tx.tx_hash();
-            println!("   Transaction hash: {tx_hash}");
+            history::record_tx_hash(tx_hash);
+            progress!(format, "   Transaction hash: {tx_hash}");
             let _ = pending_tx
                 .with_required_confirmations(2)
                 .with_timeout(Some(std::time::Duration::from_secs(60)))
@@ -289,39 +353,44 @@ impl JoinCommand {
                 .get_transaction_receipt(tx_hash)
                 .await?
                 .ok_or(anyhow::anyhow!("Failed to get transaction receipt"))?;
-            println!(
+            progress!(
+                format,
                 "   Transaction confirmed, block number: {}",
                 receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
             );
-            println!("   Gas used: {}", receipt.gas_used);
+            progress!(format, "   Gas used: {}", receipt.gas_used);
 
             // Check registration event
-            let mut found = false;
+            let mut found = None;
             for log in receipt.logs() {
                 if let Ok(event) = ValidatorManagement::ValidatorRegistered::decode_log(&log.inner)
                 {
-                    println!("   Registration successful!");
-                    println!("   - StakePool: {}", event.stakePool);
-                    println!("   - Moniker: {}", event.moniker);
-                    found = true;
+                    progress!(format, "   Registration successful!");
+                    progress!(format, "   - StakePool: {}", event.stakePool);
+                    progress!(format, "   - Moniker: {}", event.moniker);
+                    found = Some(serde_json::json!({
+                        "stake_pool": format!("{}", event.stakePool),
+                        "moniker": event.moniker,
+                    }));
                     break;
                 }
             }
-            if !found {
-                println!("   Registration event not found\n");
+            let Some(event) = found else {
+                progress!(format, "   Registration event not found\n");
                 return Err(anyhow::anyhow!("Failed to find ValidatorRegistered event"));
-            }
-            println!();
+            };
+            registration = Some(TxSummary::new(&receipt).with_event(event));
+            progress!(format);
         }
 
         // 5. Check validator information
-        println!("5. Checking validator information...");
+        progress!(format, "5. Checking validator information...");
         let call = ValidatorManagement::getValidatorCall { stakePool: stake_pool };
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -329,46 +398,68 @@ impl JoinCommand {
         let validator_record = <ValidatorRecord as SolType>::abi_decode(&result)
             .map_err(|e| anyhow::anyhow!("Failed to decode validator record: {e}"))?;
         let status = status_from_u8(validator_record.status);
-        println!("   Validator information:");
-        println!("   - Validator: {}", validator_record.validator);
-        println!("   - Moniker: {}", validator_record.moniker);
-        println!("   - Status: {status:?}");
-        println!("   - Bond: {} ETH", format_ether(validator_record.bond));
-        println!("   - Fee recipient: {}", validator_record.feeRecipient);
-        println!("   - StakePool: {}", validator_record.stakingPool);
-        println!(
+        progress!(format, "   Validator information:");
+        progress!(format, "   - Validator: {}", validator_record.validator);
+        progress!(format, "   - Moniker: {}", validator_record.moniker);
+        progress!(format, "   - Status: {status:?}");
+        progress!(format, "   - Bond: {} ETH", format_ether(validator_record.bond));
+        progress!(format, "   - Fee recipient: {}", validator_record.feeRecipient);
+        progress!(format, "   - StakePool: {}", validator_record.stakingPool);
+        progress!(
+            format,
             "   - Network addresses: {}",
             bcs::from_bytes::<String>(&validator_record.networkAddresses)
                 .unwrap_or_else(|_| hex::encode(&validator_record.networkAddresses))
         );
-        println!(
+        progress!(
+            format,
             "   - Fullnode addresses: {}",
             bcs::from_bytes::<String>(&validator_record.fullnodeAddresses)
                 .unwrap_or_else(|_| hex::encode(&validator_record.fullnodeAddresses))
         );
 
         if !matches!(status, ValidatorStatus::INACTIVE) {
-            println!("   Validator status is not INACTIVE, skipping join step\n");
-            return Ok(());
+            progress!(format, "   Validator status is not INACTIVE, skipping join step\n");
+            return format.emit(&JoinResult {
+                stake_pool: format!("{stake_pool}"),
+                stake,
+                registration,
+                join: None,
+                status: format!("{status:?}"),
+            });
         }
-        println!();
+        progress!(format);
 
         // 6. Join validator set
-        println!("6. Joining validator set...");
+        progress!(format, "6. Joining validator set...");
         let call = ValidatorManagement::joinValidatorSetCall { stakePool: stake_pool };
         let input: Bytes = call.abi_encode().into();
+        if self.dry_run.enabled {
+            simulations.push(
+                simulate(
+                    &provider,
+                    wallet_address,
+                    validator_manager_address(),
+                    input,
+                    "joinValidatorSet",
+                    "ValidatorJoinRequested",
+                    format,
+                )
+                .await?,
+            );
+            return finish_dry_run(stake_pool, &simulations, format);
+        }
         let pending_tx = provider
-            .send_transaction(TransactionRequest {
+            .send_transaction(fees.apply(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
-                gas: Some(gas_limit),
-                gas_price: Some(gas_price),
                 ..Default::default()
-            })
+            }))
             .await?;
         let tx_hash = *pending_tx.tx_hash();
-        println!("   Transaction hash: {tx_hash}");
+        history::record_tx_hash(tx_hash);
+        progress!(format, "   Transaction hash: {tx_hash}");
         let _ = pending_tx
             .with_required_confirmations(2)
             .with_timeout(Some(std::time::Duration::from_secs(60)))
@@ -379,40 +470,43 @@ impl JoinCommand {
             .get_transaction_receipt(tx_hash)
             .await?
             .ok_or(anyhow::anyhow!("Failed to get transaction receipt"))?;
-        println!(
+        progress!(
+            format,
             "   Transaction confirmed, block number: {}",
             receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
         );
-        println!("   Gas used: {}", receipt.gas_used);
-        println!(
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
             "   Transaction cost: {} ETH",
             format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
         );
 
         // Check join event
-        let mut found = false;
+        let mut found = None;
         for log in receipt.logs() {
             if let Ok(event) = ValidatorManagement::ValidatorJoinRequested::decode_log(&log.inner) {
-                println!("   Join request successful!");
-                println!("   - StakePool: {}", event.stakePool);
-                found = true;
+                progress!(format, "   Join request successful!");
+                progress!(format, "   - StakePool: {}", event.stakePool);
+                found = Some(serde_json::json!({ "stake_pool": format!("{}", event.stakePool) }));
                 break;
             }
         }
-        if !found {
-            println!("   Join event not found\n");
+        let Some(event) = found else {
+            progress!(format, "   Join event not found\n");
             return Err(anyhow::anyhow!("Failed to find ValidatorJoinRequested event"));
-        }
-        println!();
+        };
+        let join = TxSummary::new(&receipt).with_event(event);
+        progress!(format);
 
         // 7. Final status check
-        println!("7. Final status check...");
+        progress!(format, "7. Final status check...");
         let call = ValidatorManagement::getValidatorStatusCall { stakePool: stake_pool };
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -421,18 +515,214 @@ impl JoinCommand {
         let validator_status = status_from_u8(status_u8);
         match validator_status {
             ValidatorStatus::PENDING_ACTIVE => {
-                println!("   Validator status is PENDING_ACTIVE");
-                println!("   Please wait for the next epoch to automatically become ACTIVE\n");
+                progress!(format, "   Validator status is PENDING_ACTIVE");
+                progress!(
+                    format,
+                    "   Please wait for the next epoch to automatically become ACTIVE\n"
+                );
             }
             ValidatorStatus::ACTIVE => {
-                println!("   Validator status is ACTIVE");
-                println!("   Successfully joined the validator set\n");
+                progress!(format, "   Validator status is ACTIVE");
+                progress!(format, "   Successfully joined the validator set\n");
             }
             _ => {
-                println!("   Validator status is {validator_status:?}, unexpected status\n");
+                progress!(
+                    format,
+                    "   Validator status is {validator_status:?}, unexpected status\n"
+                );
                 return Err(anyhow::anyhow!("Unexpected validator status: {validator_status:?}"));
             }
         }
-        Ok(())
+        format.emit(&JoinResult {
+            stake_pool: format!("{stake_pool}"),
+            stake,
+            registration,
+            join: Some(join),
+            status: format!("{validator_status:?}"),
+        })
+    }
+
+    /// The inputs given as flags; clap already requires them without `--interactive`.
+    fn inputs(&self) -> Result<JoinInputs, anyhow::Error> {
+        let required = |value: &Option<String>, flag: &str| {
+            value.clone().ok_or_else(|| anyhow::Error::from(MissingOption::without_env(flag)))
+        };
+        Ok(JoinInputs {
+            stake_pool: Address::from_str(&required(&self.stake_pool, "stake-pool")?)?,
+            stake: U256::ZERO,
+            moniker: self.moniker.clone(),
+            consensus_public_key: required(&self.consensus_public_key, "consensus-public-key")?,
+            consensus_pop: required(&self.consensus_pop, "consensus-pop")?,
+            network_public_key: required(&self.network_public_key, "network-public-key")?,
+            validator_network_address: required(
+                &self.validator_network_address,
+                "validator-network-address",
+            )?,
+            fullnode_network_address: required(
+                &self.fullnode_network_address,
+                "fullnode-network-address",
+            )?,
+        })
+    }
+
+    /// Asks for each input in turn and checks it before moving on, so a wrong key length, a
+    /// malformed address or a stake above the balance is caught before anything is sent.
+    async fn wizard<P: Provider>(
+        &self,
+        provider: &P,
+        balance: U256,
+        format: OutputFormat,
+    ) -> Result<JoinInputs, anyhow::Error> {
+        progress!(format, "Guided join: press Enter to accept the value in brackets\n");
+        let default = |value: &Option<String>| value.clone().unwrap_or_default();
+
+        let (stake_pool, registered) = loop {
+            let answer = ask("StakePool address", &default(&self.stake_pool))?;
+            let pool = match Address::from_str(&answer) {
+                Ok(pool) => pool,
+                Err(e) => {
+                    eprintln!("   Invalid address '{answer}': {e}");
+                    continue;
+                }
+            };
+            if !view(provider, staking_address(), Staking::isPoolCall { pool }).await? {
+                eprintln!("   Not a StakePool, create one with `gravity_cli stake create`");
+                continue;
+            }
+            let voting_power =
+                view(provider, staking_address(), Staking::getPoolVotingPowerNowCall { pool })
+                    .await?;
+            let registered = view(
+                provider,
+                validator_manager_address(),
+                ValidatorManagement::isValidatorCall { stakePool: pool },
+            )
+            .await?;
+            progress!(format, "   Voting power: {} ETH", format_ether(voting_power));
+            break (pool, registered);
+        };
+
+        let stake = ask_valid("Stake to add before joining, in ETH", "0", |answer| {
+            let amount = parse_ether(answer)?;
+            if amount > balance {
+                return Err(anyhow::anyhow!("The wallet holds only {} ETH", format_ether(balance)));
+            }
+            Ok(amount)
+        })?;
+
+        let mut inputs = JoinInputs {
+            stake_pool,
+            stake,
+            moniker: self.moniker.clone(),
+            consensus_public_key: default(&self.consensus_public_key),
+            consensus_pop: default(&self.consensus_pop),
+            network_public_key: default(&self.network_public_key),
+            validator_network_address: default(&self.validator_network_address),
+            fullnode_network_address: default(&self.fullnode_network_address),
+        };
+        if registered {
+            progress!(format, "   The StakePool is already registered, only the join is left");
+        } else {
+            inputs.moniker = ask_valid("Moniker (max 31 bytes)", &inputs.moniker, |answer| {
+                check_moniker(answer).map(|()| answer.to_string())
+            })?;
+            inputs.consensus_public_key = ask_hex(
+                "Consensus public key (BLS, 96 hex characters)",
+                &inputs.consensus_public_key,
+                48,
+                "consensus public key",
+            )?;
+            inputs.consensus_pop = ask_hex(
+                "Consensus proof of possession (192 hex characters)",
+                &inputs.consensus_pop,
+                96,
+                "consensus proof of possession",
+            )?;
+            inputs.network_public_key = ask_hex(
+                "Network public key (64 hex characters)",
+                &inputs.network_public_key,
+                32,
+                "network public key",
+            )?;
+            inputs.validator_network_address = ask_address(
+                "Validator network address (/ip4/{host}/tcp/{port})",
+                &inputs.validator_network_address,
+                "validator network",
+            )?;
+            inputs.fullnode_network_address = ask_address(
+                "Fullnode network address (/ip4/{host}/tcp/{port})",
+                &inputs.fullnode_network_address,
+                "fullnode network",
+            )?;
+        }
+
+        progress!(format, "\n   StakePool: {stake_pool:?}");
+        if !stake.is_zero() {
+            progress!(format, "   Stake to add: {} ETH", format_ether(stake));
+        }
+        if !registered {
+            progress!(format, "   Moniker: \"{}\"", inputs.moniker);
+            progress!(format, "   Validator address: {}", inputs.validator_network_address);
+            progress!(format, "   Fullnode address: {}", inputs.fullnode_network_address);
+        }
+        let question = if self.dry_run.enabled { Msg::JoinDryRunPrompt } else { Msg::JoinPrompt };
+        if !confirm(question, format)? {
+            return Err(CatalogError::new(Msg::Aborted, &[]).into());
+        }
+        progress!(format);
+        Ok(inputs)
+    }
+}
+
+fn check_moniker(moniker: &str) -> Result<(), anyhow::Error> {
+    if moniker.len() > 31 {
+        return Err(anyhow::anyhow!("Moniker too long: max 31 bytes, got {} bytes", moniker.len()));
+    }
+    Ok(())
+}
+
+/// Reads one answer from stdin, or `default` for an empty line.
+fn ask(question: &str, default: &str) -> Result<String, anyhow::Error> {
+    if default.is_empty() {
+        eprint!("{question}: ");
+    } else {
+        eprint!("{question} [{default}]: ");
+    }
+    std::io::stderr().flush()?;
+    let mut input = String::new();
+    if std::io::stdin().read_line(&mut input)? == 0 {
+        return Err(anyhow::anyhow!("Input closed before the join was complete"));
+    }
+    let input = input.trim();
+    Ok(if input.is_empty() { default.to_string() } else { input.to_string() })
+}
+
+/// Asks until `check` accepts the answer, printing why each rejected one is invalid.
+fn ask_valid<T>(
+    question: &str,
+    default: &str,
+    check: impl Fn(&str) -> Result<T, anyhow::Error>,
+) -> Result<T, anyhow::Error> {
+    loop {
+        let answer = ask(question, default)?;
+        match check(&answer) {
+            Ok(value) => return Ok(value),
+            Err(e) => eprintln!("   {e}"),
+        }
     }
 }
+
+fn ask_hex(
+    question: &str,
+    default: &str,
+    len: usize,
+    label: &str,
+) -> Result<String, anyhow::Error> {
+    ask_valid(question, default, |answer| parse_hex(answer, len, label).map(hex::encode))
+}
+
+fn ask_address(question: &str, default: &str, label: &str) -> Result<String, anyhow::Error> {
+    ask_valid(question, default, |answer| {
+        validate_network_address(answer, label).map(|()| answer.to_string())
+    })
+}
@@ -3,16 +3,23 @@ use alloy_provider::{Provider, ProviderBuilder};
 use alloy_rpc_types::eth::{TransactionInput, TransactionRequest};
 use alloy_sol_types::{SolCall, SolEvent, SolType, SolValue};
 use clap::Parser;
+use serde::Serialize;
 use std::str::FromStr;
 
 use crate::{
     command::Executable,
     contract::{
-        status_from_u8, ValidatorManagement, ValidatorRecord, ValidatorStatus,
-        VALIDATOR_MANAGER_ADDRESS,
+        status_from_u8, validator_manager_address, ValidatorManagement, ValidatorRecord,
+        ValidatorStatus,
     },
+    fees::FeeArgs,
+    history,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
     signer::SignerArgs,
+    transaction::{finish_dry_run, simulate, DryRunArgs},
     util::format_ether,
+    validator::decentralization::nakamoto_coefficient,
 };
 
 #[derive(Debug, Parser)]
@@ -21,58 +28,103 @@ pub struct LeaveCommand {
     #[clap(long, env = "GRAVITY_RPC_URL")]
     pub rpc_url: Option<String>,
 
-    /// Gas limit for the transaction
-    #[clap(long, env = "GRAVITY_GAS_LIMIT")]
-    pub gas_limit: Option<u64>,
-
-    /// Gas price in wei
-    #[clap(long, env = "GRAVITY_GAS_PRICE")]
-    pub gas_price: Option<u128>,
-
     /// StakePool address (validator identity)
     #[clap(long)]
     pub stake_pool: String,
 
+    /// Leave even if the remaining validator set would be too small or too concentrated to
+    /// tolerate a single outage
+    #[clap(long)]
+    pub force: bool,
+
+    /// Fewest active validators that must remain after the departure; 4 is the smallest BFT set
+    /// that tolerates one faulty validator
+    #[clap(long, default_value_t = 4)]
+    pub min_validators: usize,
+
+    #[clap(flatten)]
+    pub dry_run: DryRunArgs,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
     #[clap(flatten)]
     pub signer: SignerArgs,
 }
 
+/// Result of `validator leave` with `--output json`.
+#[derive(Debug, Serialize)]
+struct LeaveResult {
+    stake_pool: String,
+    /// Absent when the validator was already leaving or inactive.
+    transaction: Option<TxSummary>,
+    status: String,
+}
+
+/// Problems of the active set left after a departure, given its voting powers in descending
+/// order. Empty when the set can still tolerate a faulty validator.
+fn departure_risks(remaining: &[U256], min_validators: usize) -> Vec<String> {
+    let mut risks = Vec::new();
+    if remaining.is_empty() {
+        risks.push("No active validator would remain, the chain would halt".to_string());
+        return risks;
+    }
+    if remaining.len() < min_validators {
+        risks.push(format!(
+            "Only {} active validator(s) would remain, below the minimum of {min_validators}",
+            remaining.len()
+        ));
+    }
+    // Quorum needs more than 2/3 of the voting power, so validators holding more than 1/3 can
+    // halt the chain by going offline.
+    let halting = nakamoto_coefficient(remaining, 1, 3);
+    if halting < 2 {
+        risks.push(
+            "A single remaining validator would hold more than 1/3 of the voting power, so its \
+             outage alone would halt the chain"
+                .to_string(),
+        );
+    }
+    risks
+}
+
 impl Executable for LeaveCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
 impl LeaveCommand {
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
-        let rpc_url = self.rpc_url.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--rpc-url is required. Set via CLI flag, GRAVITY_RPC_URL env var, or ~/.gravity/config.toml"
-            )
-        })?;
-        let gas_limit = self.gas_limit.unwrap_or(2_000_000);
-        let gas_price = self.gas_price.unwrap_or(100_000_000_000);
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+
+        if self.dry_run.enabled {
+            progress!(format, "Dry run: transactions are simulated, nothing is sent\n");
+        }
 
         // 1. Initialize Provider and Wallet
-        println!("1. Initializing connection...");
+        progress!(format, "1. Initializing connection...");
 
-        println!("   RPC URL: {rpc_url}");
+        progress!(format, "   RPC URL: {rpc_url}");
         let resolved = self.signer.resolve().await?;
         let wallet_address = resolved.address;
-        println!("   Wallet address: {wallet_address:?}");
+        progress!(format, "   Wallet address: {wallet_address:?}");
 
-        println!("   Contract address: {VALIDATOR_MANAGER_ADDRESS:?}");
+        progress!(format, "   Contract address: {:?}", validator_manager_address());
 
         // Create provider
         let provider =
             ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
 
         let chain_id = provider.get_chain_id().await?;
-        println!("   Chain ID: {chain_id}\n");
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
 
         // 2. Check validator information
-        println!("2. Checking validator information...");
+        progress!(format, "2. Checking validator information...");
         let stake_pool = Address::from_str(&self.stake_pool)?;
 
         // First check if it's a registered validator
@@ -81,7 +133,7 @@ impl LeaveCommand {
         let result = provider
             .call(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -90,7 +142,7 @@ impl LeaveCommand {
             .map_err(|e| anyhow::anyhow!("Failed to decode isValidator result: {e}"))?;
 
         if !is_validator {
-            return Err(anyhow::anyhow!("StakePool is not registered as a validator"));
+            return Err(CatalogError::new(Msg::NotValidator, &[]).into());
         }
 
         // Get validator record
@@ -99,7 +151,7 @@ impl LeaveCommand {
         let result = provider
             .call(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -108,46 +160,124 @@ impl LeaveCommand {
             .map_err(|e| anyhow::anyhow!("Failed to decode validator record: {e}"))?;
         let status = status_from_u8(validator_record.status);
 
-        println!("   Validator information:");
-        println!("   - Validator: {}", validator_record.validator);
-        println!("   - Moniker: {}", validator_record.moniker);
-        println!("   - Status: {status:?}");
-        println!("   - Bond: {} ETH", format_ether(validator_record.bond));
+        progress!(format, "   Validator information:");
+        progress!(format, "   - Validator: {}", validator_record.validator);
+        progress!(format, "   - Moniker: {}", validator_record.moniker);
+        progress!(format, "   - Status: {status:?}");
+        progress!(format, "   - Bond: {} ETH", format_ether(validator_record.bond));
 
         // Check if validator status allows leaving
         match status {
             ValidatorStatus::PENDING_ACTIVE | ValidatorStatus::ACTIVE => {
-                println!("   Validator status allows leaving\n");
+                progress!(format, "   Validator status allows leaving\n");
             }
             ValidatorStatus::PENDING_INACTIVE => {
-                println!("   Validator is already PENDING_INACTIVE, no need to leave again\n");
-                return Ok(());
+                progress!(
+                    format,
+                    "   Validator is already PENDING_INACTIVE, no need to leave again\n"
+                );
+                return format.emit(&LeaveResult {
+                    stake_pool: format!("{stake_pool}"),
+                    transaction: None,
+                    status: format!("{status:?}"),
+                });
             }
             ValidatorStatus::INACTIVE => {
-                println!("   Validator is already INACTIVE, no need to leave\n");
-                return Ok(());
+                progress!(format, "   Validator is already INACTIVE, no need to leave\n");
+                return format.emit(&LeaveResult {
+                    stake_pool: format!("{stake_pool}"),
+                    transaction: None,
+                    status: format!("{status:?}"),
+                });
             }
             _ => {
                 return Err(anyhow::anyhow!("Validator status {status:?} does not allow leaving"));
             }
         }
 
+        // Guard against a departure that leaves the set unable to reach quorum through one outage.
+        // Validators already leaving are gone next epoch too; joining ones are not counted, as
+        // they may never come online.
+        progress!(format, "   Checking the validator set after the departure...");
+        let mut leaving: Vec<Address> = vec![stake_pool];
+        let result = provider
+            .call(TransactionRequest {
+                to: Some(TxKind::Call(validator_manager_address())),
+                input: TransactionInput::new(
+                    ValidatorManagement::getPendingInactiveValidatorsCall {}.abi_encode().into(),
+                ),
+                ..Default::default()
+            })
+            .await?;
+        leaving.extend(
+            ValidatorManagement::getPendingInactiveValidatorsCall::abi_decode_returns(&result)
+                .map_err(|e| anyhow::anyhow!("Failed to decode pending inactive validators: {e}"))?
+                .iter()
+                .map(|v| v.validator),
+        );
+        let result = provider
+            .call(TransactionRequest {
+                to: Some(TxKind::Call(validator_manager_address())),
+                input: TransactionInput::new(
+                    ValidatorManagement::getActiveValidatorsCall {}.abi_encode().into(),
+                ),
+                ..Default::default()
+            })
+            .await?;
+        let mut remaining: Vec<U256> =
+            ValidatorManagement::getActiveValidatorsCall::abi_decode_returns(&result)
+                .map_err(|e| anyhow::anyhow!("Failed to decode active validators: {e}"))?
+                .iter()
+                .filter(|v| !leaving.contains(&v.validator))
+                .map(|v| v.votingPower)
+                .collect();
+        remaining.sort_by(|a, b| b.cmp(a));
+        let total = remaining.iter().fold(U256::ZERO, |acc, p| acc.saturating_add(*p));
+        progress!(
+            format,
+            "   Remaining active validators: {} with {} ETH of voting power",
+            remaining.len(),
+            format_ether(total)
+        );
+        let risks = departure_risks(&remaining, self.min_validators);
+        for risk in &risks {
+            eprintln!("   Warning: {risk}");
+        }
+        if !risks.is_empty() && !self.force {
+            return Err(anyhow::anyhow!(
+                "Leaving could halt the chain; pass --force to leave anyway"
+            ));
+        }
+        progress!(format);
+
         // 3. Leave validator set
-        println!("3. Leaving validator set...");
+        progress!(format, "3. Leaving validator set...");
         let call = ValidatorManagement::leaveValidatorSetCall { stakePool: stake_pool };
         let input: Bytes = call.abi_encode().into();
+        if self.dry_run.enabled {
+            let simulation = simulate(
+                &provider,
+                wallet_address,
+                validator_manager_address(),
+                input,
+                "leaveValidatorSet",
+                "ValidatorLeaveRequested",
+                format,
+            )
+            .await?;
+            return finish_dry_run(stake_pool, &[simulation], format);
+        }
         let pending_tx = provider
-            .send_transaction(TransactionRequest {
+            .send_transaction(fees.apply(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
-                gas: Some(gas_limit),
-                gas_price: Some(gas_price),
                 ..Default::default()
-            })
+            }))
             .await?;
         let tx_hash = *pending_tx.tx_hash();
-        println!("   Transaction hash: {tx_hash}");
+        history::record_tx_hash(tx_hash);
+        progress!(format, "   Transaction hash: {tx_hash}");
         let _ = pending_tx
             .with_required_confirmations(2)
             .with_timeout(Some(std::time::Duration::from_secs(60)))
@@ -158,42 +288,46 @@ impl LeaveCommand {
             .get_transaction_receipt(tx_hash)
             .await?
             .ok_or(anyhow::anyhow!("Failed to get transaction receipt"))?;
-        println!(
+        progress!(
+            format,
             "   Transaction confirmed, block number: {}",
             receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
         );
-        println!("   Gas used: {}", receipt.gas_used);
-        println!(
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
             "   Transaction cost: {} ETH",
             format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
         );
 
         // Check leave event
-        let mut found_leave_event = false;
+        let mut leave_event = None;
         for log in receipt.logs() {
             if let Ok(event) = ValidatorManagement::ValidatorLeaveRequested::decode_log(&log.inner)
             {
-                println!("   Leave request successful!");
-                println!("   - StakePool: {}", event.stakePool);
-                found_leave_event = true;
+                progress!(format, "   Leave request successful!");
+                progress!(format, "   - StakePool: {}", event.stakePool);
+                leave_event =
+                    Some(serde_json::json!({ "stake_pool": format!("{}", event.stakePool) }));
                 break;
             }
         }
 
-        if !found_leave_event {
-            println!("   Leave event not found\n");
+        let Some(leave_event) = leave_event else {
+            progress!(format, "   Leave event not found\n");
             return Err(anyhow::anyhow!("Failed to find ValidatorLeaveRequested event"));
-        }
-        println!();
+        };
+        let transaction = TxSummary::new(&receipt).with_event(leave_event);
+        progress!(format);
 
         // 4. Final status check
-        println!("4. Final status check...");
+        progress!(format, "4. Final status check...");
         let call = ValidatorManagement::getValidatorStatusCall { stakePool: stake_pool };
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
                 from: Some(wallet_address),
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -203,18 +337,43 @@ impl LeaveCommand {
 
         match validator_status {
             ValidatorStatus::PENDING_INACTIVE => {
-                println!("   Validator status is PENDING_INACTIVE");
-                println!("   Will become INACTIVE in the next epoch\n");
+                progress!(format, "   Validator status is PENDING_INACTIVE");
+                progress!(format, "   Will become INACTIVE in the next epoch\n");
             }
             ValidatorStatus::INACTIVE => {
-                println!("   Validator status is INACTIVE");
-                println!("   Successfully left the validator set\n");
+                progress!(format, "   Validator status is INACTIVE");
+                progress!(format, "   Successfully left the validator set\n");
             }
             _ => {
-                println!("   Validator status is {validator_status:?}, unexpected status\n");
+                progress!(
+                    format,
+                    "   Validator status is {validator_status:?}, unexpected status\n"
+                );
                 return Err(anyhow::anyhow!("Unexpected validator status: {validator_status:?}"));
             }
         }
-        Ok(())
+        format.emit(&LeaveResult {
+            stake_pool: format!("{stake_pool}"),
+            transaction: Some(transaction),
+            status: format!("{validator_status:?}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn powers(values: &[u64]) -> Vec<U256> {
+        values.iter().map(|v| U256::from(*v)).collect()
+    }
+
+    #[test]
+    fn departure_risks_flags_small_or_concentrated_sets() {
+        assert!(departure_risks(&powers(&[25, 25, 25, 25]), 4).is_empty());
+        assert_eq!(departure_risks(&powers(&[25, 25, 25]), 4).len(), 1);
+        // One validator past 1/3 of the power can halt the chain alone
+        assert_eq!(departure_risks(&powers(&[40, 20, 20, 20]), 4).len(), 1);
+        assert_eq!(departure_risks(&[], 4).len(), 1);
     }
 }
@@ -7,7 +7,8 @@ use serde::Serialize;
 
 use crate::{
     command::Executable,
-    contract::{ValidatorManagement, ValidatorStatus, VALIDATOR_MANAGER_ADDRESS},
+    contract::{validator_manager_address, ValidatorManagement, ValidatorStatus},
+    i18n::MissingOption,
     output::OutputFormat,
     util::format_ether,
 };
@@ -17,10 +18,6 @@ pub struct ListCommand {
     /// RPC URL for gravity node
     #[clap(long, env = "GRAVITY_RPC_URL")]
     pub rpc_url: Option<String>,
-
-    /// Output format
-    #[clap(skip)]
-    pub output_format: OutputFormat,
 }
 
 // Serializable versions of the contract types
@@ -46,19 +43,16 @@ struct SerializableValidatorInfo {
 }
 
 impl Executable for ListCommand {
-    fn execute(self) -> Result<(), anyhow::Error> {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
         let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(self.execute_async())
+        rt.block_on(self.execute_async(format))
     }
 }
 
 impl ListCommand {
-    async fn execute_async(self) -> Result<(), anyhow::Error> {
-        let rpc_url = self.rpc_url.ok_or_else(|| {
-            anyhow::anyhow!(
-                "--rpc-url is required. Set via CLI flag, GRAVITY_RPC_URL env var, or ~/.gravity/config.toml"
-            )
-        })?;
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
 
         // Initialize Provider
         let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
@@ -68,7 +62,7 @@ impl ListCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -82,7 +76,7 @@ impl ListCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -96,7 +90,7 @@ impl ListCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -110,7 +104,7 @@ impl ListCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -124,7 +118,7 @@ impl ListCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -138,7 +132,7 @@ impl ListCommand {
         let input: Bytes = call.abi_encode().into();
         let result = provider
             .call(TransactionRequest {
-                to: Some(TxKind::Call(VALIDATOR_MANAGER_ADDRESS)),
+                to: Some(TxKind::Call(validator_manager_address())),
                 input: TransactionInput::new(input),
                 ..Default::default()
             })
@@ -169,7 +163,7 @@ impl ListCommand {
         };
 
         // Output based on format
-        match self.output_format {
+        match format {
             OutputFormat::Json => {
                 let json = serde_json::to_string_pretty(&serializable_set)?;
                 println!("{json}");
@@ -1,10 +1,46 @@
+mod add_stake;
+mod apply;
+mod compromise;
+mod decentralization;
+mod extend_lockup;
 mod join;
 mod leave;
 mod list;
+pub(crate) mod notify;
+mod rotate_key;
+mod set_fee_recipient;
+mod snapshot;
+pub(crate) mod status;
+mod submit_evidence;
+mod topup;
+pub(crate) mod unstake;
+mod withdraw;
 
 use clap::{Parser, Subcommand};
 
-use crate::validator::{join::JoinCommand, leave::LeaveCommand, list::ListCommand};
+use crate::{
+    command::Executable,
+    output::OutputFormat,
+    validator::{
+        add_stake::AddStakeCommand,
+        apply::ApplyCommand,
+        compromise::CompromiseResponseCommand,
+        decentralization::DecentralizationCommand,
+        extend_lockup::ExtendLockupCommand,
+        join::JoinCommand,
+        leave::LeaveCommand,
+        list::ListCommand,
+        notify::NotifyCommand,
+        rotate_key::RotateKeyCommand,
+        set_fee_recipient::SetFeeRecipientCommand,
+        snapshot::{CompareCommand, SnapshotCommand},
+        status::StatusCommand,
+        submit_evidence::SubmitEvidenceCommand,
+        topup::TopupCommand,
+        unstake::UnstakeCommand,
+        withdraw::WithdrawCommand,
+    },
+};
 
 #[derive(Debug, Parser)]
 pub struct ValidatorCommand {
@@ -16,6 +52,78 @@ pub struct ValidatorCommand {
 pub enum SubCommands {
     Join(JoinCommand),
     Leave(LeaveCommand),
+    /// Deposit additional stake into an existing StakePool
+    AddStake(AddStakeCommand),
+    /// Unlock stake of a StakePool that has left the validator set
+    Unstake(UnstakeCommand),
+    /// Withdraw unlocked stake to the pool owner once its lockup has expired
+    Withdraw(WithdrawCommand),
+    /// Renew the lockup of a StakePool before it expires
+    ExtendLockup(ExtendLockupCommand),
     List(ListCommand),
+    /// Show everything about one validator: record, StakePool, lockup and epoch
+    Status(StatusCommand),
+    Decentralization(DecentralizationCommand),
+    /// Rotate the consensus key of a validator
+    RotateKey(RotateKeyCommand),
+    /// Change the address receiving a validator's fees, from the next epoch on
+    SetFeeRecipient(SetFeeRecipientCommand),
+    /// Rotate keys and addresses of a compromised validator in one guided flow
+    CompromiseResponse(CompromiseResponseCommand),
+    /// Submit equivocation evidence recorded by a node to the slashing contract
+    SubmitEvidence(SubmitEvidenceCommand),
+    /// Watch a stake pool and post signed notifications of every change to a webhook
+    Notify(NotifyCommand),
+    /// Keep a validator at a voting-power rank by adding stake, within a per-epoch limit
+    Topup(TopupCommand),
+    /// Register, update and join every validator of a YAML manifest
+    Apply(ApplyCommand),
+    /// Save the validator set at a block to a JSON file, for later comparison
+    Snapshot(SnapshotCommand),
+    /// Diff two saved validator set snapshots: joins, leaves, power changes and key rotations
+    Compare(CompareCommand),
     // TODO: other commands
 }
+
+impl Executable for ValidatorCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        match self.command {
+            SubCommands::Join(join_cmd) => join_cmd.execute(format),
+            SubCommands::Leave(leave_cmd) => leave_cmd.execute(format),
+            SubCommands::AddStake(add_stake_cmd) => add_stake_cmd.execute(format),
+            SubCommands::Unstake(unstake_cmd) => unstake_cmd.execute(format),
+            SubCommands::Withdraw(withdraw_cmd) => withdraw_cmd.execute(format),
+            SubCommands::ExtendLockup(lockup_cmd) => lockup_cmd.execute(format),
+            SubCommands::List(list_cmd) => list_cmd.execute(format),
+            SubCommands::Status(status_cmd) => status_cmd.execute(format),
+            SubCommands::Decentralization(report_cmd) => report_cmd.execute(format),
+            SubCommands::RotateKey(rotate_cmd) => rotate_cmd.execute(format),
+            SubCommands::SetFeeRecipient(fee_cmd) => fee_cmd.execute(format),
+            SubCommands::CompromiseResponse(compromise_cmd) => compromise_cmd.execute(format),
+            SubCommands::SubmitEvidence(evidence_cmd) => evidence_cmd.execute(format),
+            SubCommands::Notify(notify_cmd) => notify_cmd.execute(format),
+            SubCommands::Topup(topup_cmd) => topup_cmd.execute(format),
+            SubCommands::Apply(apply_cmd) => apply_cmd.execute(format),
+            SubCommands::Snapshot(snapshot_cmd) => snapshot_cmd.execute(format),
+            SubCommands::Compare(compare_cmd) => compare_cmd.execute(format),
+        }
+    }
+}
+
+/// Validates a network address in /ip4/{host}/tcp/{port} or /dns/{domain}/tcp/{port} format.
+pub(crate) fn validate_network_address(addr: &str, label: &str) -> Result<(), anyhow::Error> {
+    let parts: Vec<&str> = addr.split('/').collect();
+    // Expected: ["", "ip4"|"dns"|"dns4"|"dns6", "{host}", "tcp", "{port}"]
+    if parts.len() != 5 ||
+        !parts[0].is_empty() ||
+        !matches!(parts[1], "ip4" | "dns" | "dns4" | "dns6") ||
+        parts[2].is_empty() ||
+        parts[3] != "tcp" ||
+        parts[4].parse::<u16>().is_err()
+    {
+        return Err(anyhow::anyhow!(
+            "Invalid {label} address: expected /ip4/{{host}}/tcp/{{port}} or /dns/{{domain}}/tcp/{{port}} format, got '{addr}'"
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,426 @@
+use alloy_primitives::{Address, TxKind, B256};
+use alloy_provider::{Provider, RootProvider};
+use alloy_rpc_types::eth::{Filter, Log, TransactionInput, TransactionRequest};
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2_0_10_6::Sha256;
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    command::Executable,
+    contract::{staking_address, validator_manager_address, Staking, ValidatorManagement},
+    i18n::MissingOption,
+    output::{progress, OutputFormat},
+};
+
+/// Most blocks scanned for events by one `eth_getLogs` call.
+const MAX_LOG_RANGE: u64 = 10_000;
+/// Header carrying `sha256=<hex HMAC of the body>`.
+pub(crate) const SIGNATURE_HEADER: &str = "X-Gravity-Signature";
+
+#[derive(Debug, Parser)]
+pub struct NotifyCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address (validator identity) to watch
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// URL the notifications are POSTed to as JSON
+    #[clap(long)]
+    pub webhook: String,
+
+    /// Shared secret signing every notification with HMAC-SHA256, sent in the
+    /// X-Gravity-Signature header as `sha256=<hex>`
+    #[clap(long, env = "GRAVITY_NOTIFY_SECRET", hide_env_values = true)]
+    pub webhook_secret: String,
+
+    /// Address allowed to change the pool, e.g. each signer or the Safe of a multi-sig (may be
+    /// repeated). Changes sent from any other address are flagged as unauthorized.
+    #[clap(long = "authorized-sender")]
+    pub authorized_senders: Vec<String>,
+
+    /// Keep watching until interrupted. Without it, report the changes since --from-block once.
+    #[clap(long)]
+    pub daemon: bool,
+
+    /// First block to scan for changes (default: the latest block)
+    #[clap(long)]
+    pub from_block: Option<u64>,
+
+    /// Seconds between polls in daemon mode
+    #[clap(long, default_value_t = 12)]
+    pub poll_interval_secs: u64,
+}
+
+/// Body of a webhook notification, also printed one JSON object per line with `--output json`.
+#[derive(Debug, Serialize)]
+struct Notification {
+    stake_pool: Address,
+    /// e.g. `fee_recipient_updated`, `consensus_key_rotated`, `operator_changed`
+    kind: &'static str,
+    detail: String,
+    block_number: Option<u64>,
+    tx_hash: Option<B256>,
+    /// Sender of the transaction that made the change.
+    sender: Option<Address>,
+    /// Whether `sender` is an authorized sender. Absent when no authorized senders are
+    /// configured, or when the change was found by comparing pool state and has no transaction.
+    authorized: Option<bool>,
+    timestamp: u64,
+}
+
+/// Pool settings that have no event of their own, compared between polls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PoolState {
+    owner: Address,
+    operator: Address,
+    network_addresses: Vec<u8>,
+    fullnode_addresses: Vec<u8>,
+}
+
+impl PoolState {
+    fn changes(&self, new: &PoolState) -> Vec<(&'static str, String)> {
+        let mut changes = Vec::new();
+        if self.owner != new.owner {
+            changes.push(("owner_changed", format!("owner {} -> {}", self.owner, new.owner)));
+        }
+        if self.operator != new.operator {
+            changes.push((
+                "operator_changed",
+                format!("operator {} -> {}", self.operator, new.operator),
+            ));
+        }
+        if self.network_addresses != new.network_addresses {
+            changes.push((
+                "network_addresses_changed",
+                format!(
+                    "network addresses {} -> {}",
+                    String::from_utf8_lossy(&self.network_addresses),
+                    String::from_utf8_lossy(&new.network_addresses)
+                ),
+            ));
+        }
+        if self.fullnode_addresses != new.fullnode_addresses {
+            changes.push((
+                "fullnode_addresses_changed",
+                format!(
+                    "fullnode addresses {} -> {}",
+                    String::from_utf8_lossy(&self.fullnode_addresses),
+                    String::from_utf8_lossy(&new.fullnode_addresses)
+                ),
+            ));
+        }
+        changes
+    }
+}
+
+/// Describes a ValidatorManagement event, if it is one the pool's managers care about.
+fn describe_event(log: &Log) -> Option<(&'static str, String)> {
+    use ValidatorManagement as VM;
+    if let Ok(event) = VM::FeeRecipientUpdated::decode_log(&log.inner) {
+        return Some(("fee_recipient_updated", format!("fee recipient -> {}", event.newRecipient)));
+    }
+    if let Ok(event) = VM::ConsensusKeyRotated::decode_log(&log.inner) {
+        let key = hex::encode(&event.newPubkey);
+        return Some(("consensus_key_rotated", format!("consensus key -> 0x{key}")));
+    }
+    if VM::ValidatorJoinRequested::decode_log(&log.inner).is_ok() {
+        return Some(("join_requested", "requested to join the validator set".to_string()));
+    }
+    if VM::ValidatorLeaveRequested::decode_log(&log.inner).is_ok() {
+        return Some(("leave_requested", "requested to leave the validator set".to_string()));
+    }
+    if let Ok(event) = VM::ValidatorActivated::decode_log(&log.inner) {
+        return Some((
+            "activated",
+            format!(
+                "activated with voting power {} at index {}",
+                event.votingPower, event.validatorIndex
+            ),
+        ));
+    }
+    if VM::ValidatorDeactivated::decode_log(&log.inner).is_ok() {
+        return Some(("deactivated", "removed from the validator set".to_string()));
+    }
+    None
+}
+
+/// `sha256=<hex>` HMAC of `body` under `secret`.
+pub(crate) fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+impl Executable for NotifyCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl NotifyCommand {
+    async fn call<C: SolCall>(
+        provider: &RootProvider,
+        to: Address,
+        call: C,
+    ) -> anyhow::Result<C::Return> {
+        let result = provider
+            .call(TransactionRequest {
+                to: Some(TxKind::Call(to)),
+                input: TransactionInput::new(call.abi_encode().into()),
+                ..Default::default()
+            })
+            .await?;
+        Ok(C::abi_decode_returns(&result)?)
+    }
+
+    async fn pool_state(provider: &RootProvider, pool: Address) -> anyhow::Result<PoolState> {
+        let owner =
+            Self::call(provider, staking_address(), Staking::getPoolOwnerCall { pool }).await?;
+        let operator =
+            Self::call(provider, staking_address(), Staking::getPoolOperatorCall { pool }).await?;
+        let record = Self::call(
+            provider,
+            validator_manager_address(),
+            ValidatorManagement::getValidatorCall { stakePool: pool },
+        )
+        .await?;
+        Ok(PoolState {
+            owner,
+            operator,
+            network_addresses: record.networkAddresses.to_vec(),
+            fullnode_addresses: record.fullnodeAddresses.to_vec(),
+        })
+    }
+
+    /// Notifications for the pool's events in `from..=to`.
+    async fn scan_events(
+        &self,
+        provider: &RootProvider,
+        pool: Address,
+        authorized: &[Address],
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Vec<Notification>> {
+        use ValidatorManagement as VM;
+        let signatures = vec![
+            VM::FeeRecipientUpdated::SIGNATURE_HASH,
+            VM::ConsensusKeyRotated::SIGNATURE_HASH,
+            VM::ValidatorJoinRequested::SIGNATURE_HASH,
+            VM::ValidatorLeaveRequested::SIGNATURE_HASH,
+            VM::ValidatorActivated::SIGNATURE_HASH,
+            VM::ValidatorDeactivated::SIGNATURE_HASH,
+        ];
+        let mut notifications = Vec::new();
+        let mut senders: HashMap<B256, Address> = HashMap::new();
+        let mut start = from;
+        while start <= to {
+            let end = to.min(start + MAX_LOG_RANGE - 1);
+            let filter = Filter::new()
+                .address(validator_manager_address())
+                .from_block(start)
+                .to_block(end)
+                .event_signature(signatures.clone())
+                .topic1(pool.into_word());
+            for log in provider.get_logs(&filter).await? {
+                let Some((kind, detail)) = describe_event(&log) else { continue };
+                let sender = match log.transaction_hash {
+                    Some(hash) => match senders.get(&hash) {
+                        Some(sender) => Some(*sender),
+                        None => {
+                            let sender =
+                                provider.get_transaction_receipt(hash).await?.map(|r| r.from);
+                            if let Some(sender) = sender {
+                                senders.insert(hash, sender);
+                            }
+                            sender
+                        }
+                    },
+                    None => None,
+                };
+                notifications.push(Notification {
+                    stake_pool: pool,
+                    kind,
+                    detail,
+                    block_number: log.block_number,
+                    tx_hash: log.transaction_hash,
+                    sender,
+                    authorized: (!authorized.is_empty())
+                        .then(|| sender.is_some_and(|s| authorized.contains(&s))),
+                    timestamp: now_secs(),
+                });
+            }
+            start = end + 1;
+        }
+        Ok(notifications)
+    }
+
+    async fn post(
+        &self,
+        client: &reqwest::Client,
+        notification: &Notification,
+    ) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(notification)?;
+        let response = client
+            .post(&self.webhook)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, sign(&self.webhook_secret, &body))
+            .body(body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("webhook returned HTTP {}", response.status());
+        }
+        Ok(())
+    }
+
+    async fn deliver(
+        &self,
+        client: &reqwest::Client,
+        notifications: &[Notification],
+        format: OutputFormat,
+    ) {
+        for notification in notifications {
+            let flag = match notification.authorized {
+                Some(false) => " [UNAUTHORIZED SENDER]",
+                _ => "",
+            };
+            match format {
+                OutputFormat::Json => match serde_json::to_string(notification) {
+                    Ok(line) => println!("{line}"),
+                    Err(e) => eprintln!("Warning: failed to encode {}: {e}", notification.kind),
+                },
+                OutputFormat::Plain => println!(
+                    "[block {}] {}: {}{flag}",
+                    notification.block_number.map_or_else(|| "?".to_string(), |b| b.to_string()),
+                    notification.kind,
+                    notification.detail
+                ),
+            }
+            if let Err(e) = self.post(client, notification).await {
+                eprintln!("Warning: failed to deliver {} notification: {e}", notification.kind);
+            }
+        }
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url = self
+            .rpc_url
+            .as_deref()
+            .ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        if self.webhook_secret.is_empty() {
+            anyhow::bail!("--webhook-secret must not be empty");
+        }
+        let pool = Address::from_str(&self.stake_pool)?;
+        let authorized = self
+            .authorized_senders
+            .iter()
+            .map(|s| Address::from_str(s).map_err(|e| anyhow::anyhow!("Invalid sender '{s}': {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let provider = RootProvider::new_http(rpc_url.parse()?);
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?;
+
+        let latest = provider.get_block_number().await?;
+        let mut next_block = self.from_block.unwrap_or(latest);
+        if next_block <= latest {
+            let notifications =
+                self.scan_events(&provider, pool, &authorized, next_block, latest).await?;
+            self.deliver(&client, &notifications, format).await;
+            next_block = latest + 1;
+        }
+        if !self.daemon {
+            return Ok(());
+        }
+
+        progress!(format, "Watching stake pool {pool} (Ctrl-C to stop)...");
+        let mut state = Self::pool_state(&provider, pool).await?;
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.poll_interval_secs.max(1)));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            // Keep watching through RPC hiccups; the block cursor only advances on success.
+            let latest = match provider.get_block_number().await {
+                Ok(latest) => latest,
+                Err(e) => {
+                    eprintln!("Warning: failed to get block number: {e}");
+                    continue;
+                }
+            };
+            if latest >= next_block {
+                match self.scan_events(&provider, pool, &authorized, next_block, latest).await {
+                    Ok(notifications) => {
+                        self.deliver(&client, &notifications, format).await;
+                        next_block = latest + 1;
+                    }
+                    Err(e) => eprintln!("Warning: failed to scan events: {e}"),
+                }
+            }
+            match Self::pool_state(&provider, pool).await {
+                Ok(new_state) => {
+                    let notifications: Vec<Notification> = state
+                        .changes(&new_state)
+                        .into_iter()
+                        .map(|(kind, detail)| Notification {
+                            stake_pool: pool,
+                            kind,
+                            detail,
+                            block_number: Some(latest),
+                            tx_hash: None,
+                            sender: None,
+                            authorized: None,
+                            timestamp: now_secs(),
+                        })
+                        .collect();
+                    self.deliver(&client, &notifications, format).await;
+                    state = new_state;
+                }
+                Err(e) => eprintln!("Warning: failed to read pool state: {e}"),
+            }
+        }
+    }
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_body_with_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn reports_role_and_address_changes() {
+        let before = PoolState {
+            owner: Address::repeat_byte(1),
+            operator: Address::repeat_byte(2),
+            network_addresses: b"/ip4/1.2.3.4/tcp/6180".to_vec(),
+            fullnode_addresses: vec![],
+        };
+        assert!(before.changes(&before.clone()).is_empty());
+        let after = PoolState { operator: Address::repeat_byte(3), ..before.clone() };
+        let changes = before.changes(&after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, "operator_changed");
+    }
+}
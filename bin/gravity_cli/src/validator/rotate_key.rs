@@ -0,0 +1,194 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{status_from_u8, validator_manager_address, ValidatorManagement, ValidatorStatus},
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{get_validator, send},
+    util::{confirm, format_ether, parse_hex},
+};
+
+#[derive(Debug, Parser)]
+pub struct RotateKeyCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address (validator identity)
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// New consensus public key (BLS key, 96 hex characters), e.g. from
+    /// `gravity_cli genesis generate-key`
+    #[clap(long)]
+    pub new_consensus_public_key: String,
+
+    /// Proof of possession for the new consensus key (192 hex characters)
+    #[clap(long, alias = "new-consensus-pop")]
+    pub new_pop: String,
+
+    /// Skip the confirmation prompt
+    #[clap(long)]
+    pub yes: bool,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `validator rotate-key` with `--output json`.
+#[derive(Debug, Serialize)]
+struct RotateKeyResult {
+    stake_pool: String,
+    /// Absent when the validator already had the new key.
+    transaction: Option<TxSummary>,
+    status: String,
+    /// Consensus key in the validator record after the command.
+    consensus_public_key: String,
+    /// Whether the new key only takes over at the next epoch.
+    pending: bool,
+}
+
+impl Executable for RotateKeyCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl RotateKeyCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool)?;
+        // Validate the key material before connecting, so a typo costs nothing
+        let new_pubkey = parse_hex(&self.new_consensus_public_key, 48, "consensus public key")?;
+        let new_pop = parse_hex(&self.new_pop, 96, "consensus proof of possession")?;
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        progress!(format, "   Contract address: {:?}", validator_manager_address());
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
+
+        // 2. Check validator information
+        progress!(format, "2. Checking validator information...");
+        let record = get_validator(&provider, wallet_address, stake_pool).await?;
+        let status = status_from_u8(record.status);
+        progress!(format, "   Validator information:");
+        progress!(format, "   - Validator: {}", record.validator);
+        progress!(format, "   - Moniker: {}", record.moniker);
+        progress!(format, "   - Status: {status:?}");
+        progress!(format, "   - Consensus public key: {}", hex::encode(&record.consensusPubkey));
+        if record.consensusPubkey.as_ref() == new_pubkey.as_slice() {
+            progress!(
+                format,
+                "   Consensus key is already {}, nothing to rotate\n",
+                hex::encode(&new_pubkey)
+            );
+            return format.emit(&RotateKeyResult {
+                stake_pool: format!("{stake_pool}"),
+                transaction: None,
+                status: format!("{status:?}"),
+                consensus_public_key: hex::encode(&new_pubkey),
+                pending: false,
+            });
+        }
+        if matches!(status, ValidatorStatus::INACTIVE | ValidatorStatus::PENDING_INACTIVE) {
+            progress!(
+                format,
+                "   Note: validator is {status:?}, the new key is used once it joins again"
+            );
+        }
+        progress!(format);
+
+        // 3. Confirm the rotation
+        progress!(format, "3. Rotating consensus key...");
+        progress!(format, "   New consensus public key: {}", hex::encode(&new_pubkey));
+        if !self.yes && !confirm(Msg::ProceedPrompt, format)? {
+            return Err(CatalogError::new(Msg::Aborted, &[]).into());
+        }
+        let input: Bytes = ValidatorManagement::rotateConsensusKeyCall {
+            stakePool: stake_pool,
+            newPubkey: new_pubkey.clone().into(),
+            newPop: new_pop.into(),
+        }
+        .abi_encode()
+        .into();
+        let receipt = send(&provider, wallet_address, input, &fees, format).await?;
+        progress!(
+            format,
+            "   Transaction confirmed, block number: {}",
+            receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
+        );
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
+            "   Transaction cost: {} ETH",
+            format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
+        );
+
+        // Check rotation event
+        let event = receipt
+            .logs()
+            .iter()
+            .find_map(|log| ValidatorManagement::ConsensusKeyRotated::decode_log(&log.inner).ok())
+            .ok_or(anyhow::anyhow!("Failed to find ConsensusKeyRotated event"))?;
+        progress!(format, "   Key rotation successful!");
+        progress!(format, "   - StakePool: {}", event.stakePool);
+        progress!(format, "   - New public key: {}", hex::encode(&event.newPubkey));
+        if event.newPubkey.as_ref() != new_pubkey.as_slice() {
+            return Err(anyhow::anyhow!(
+                "ConsensusKeyRotated event carries key {}, expected {}",
+                hex::encode(&event.newPubkey),
+                hex::encode(&new_pubkey)
+            ));
+        }
+        let transaction = TxSummary::new(&receipt).with_event(serde_json::json!({
+            "stake_pool": format!("{}", event.stakePool),
+            "new_pubkey": hex::encode(&event.newPubkey),
+        }));
+        progress!(format);
+
+        // 4. Final status check
+        progress!(format, "4. Final status check...");
+        let record = get_validator(&provider, wallet_address, stake_pool).await?;
+        progress!(format, "   - Status: {:?}", status_from_u8(record.status));
+        progress!(format, "   - Consensus public key: {}", hex::encode(&record.consensusPubkey));
+        let pending = record.consensusPubkey.as_ref() != new_pubkey.as_slice();
+        if pending {
+            progress!(format, "   Rotation is pending and takes effect at the next epoch.");
+        } else {
+            progress!(format, "   Consensus key rotated, it takes effect at the next epoch.");
+        }
+        progress!(
+            format,
+            "   Restart the node with the new consensus key once the epoch has changed.\n"
+        );
+        format.emit(&RotateKeyResult {
+            stake_pool: format!("{stake_pool}"),
+            transaction: Some(transaction),
+            status: format!("{:?}", status_from_u8(record.status)),
+            consensus_public_key: hex::encode(&record.consensusPubkey),
+            pending,
+        })
+    }
+}
@@ -0,0 +1,195 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{status_from_u8, validator_manager_address, ValidatorManagement},
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{get_validator, send},
+    util::{confirm, format_ether},
+};
+
+#[derive(Debug, Parser)]
+pub struct SetFeeRecipientCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address (validator identity)
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Address to receive the validator's fees from the next epoch on
+    #[clap(long)]
+    pub recipient: String,
+
+    /// Skip the confirmation prompt
+    #[clap(long)]
+    pub yes: bool,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `validator set-fee-recipient` with `--output json`.
+#[derive(Debug, Serialize)]
+struct SetFeeRecipientResult {
+    stake_pool: String,
+    /// Absent when the recipient was already set.
+    transaction: Option<TxSummary>,
+    fee_recipient: String,
+    pending_fee_recipient: Option<String>,
+}
+
+impl Executable for SetFeeRecipientCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl SetFeeRecipientCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool)?;
+        let recipient = Address::from_str(&self.recipient)
+            .map_err(|e| anyhow::anyhow!("Invalid recipient address '{}': {e}", self.recipient))?;
+        if recipient == Address::ZERO {
+            return Err(anyhow::anyhow!("Refusing to send fees to the zero address"));
+        }
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        progress!(format, "   Contract address: {:?}", validator_manager_address());
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
+
+        // 2. Check validator information
+        progress!(format, "2. Checking validator information...");
+        let record = get_validator(&provider, wallet_address, stake_pool).await?;
+        progress!(format, "   Validator information:");
+        progress!(format, "   - Validator: {}", record.validator);
+        progress!(format, "   - Moniker: {}", record.moniker);
+        progress!(format, "   - Status: {:?}", status_from_u8(record.status));
+        progress!(format, "   - Fee recipient: {}", record.feeRecipient);
+        progress!(
+            format,
+            "   - Pending fee recipient: {}",
+            describe_pending(record.pendingFeeRecipient)
+        );
+        if record.pendingFeeRecipient == recipient ||
+            (record.feeRecipient == recipient && record.pendingFeeRecipient == Address::ZERO)
+        {
+            progress!(format, "   Fee recipient is already set to {recipient}, nothing to do\n");
+            return format.emit(&SetFeeRecipientResult {
+                stake_pool: format!("{stake_pool}"),
+                transaction: None,
+                fee_recipient: format!("{}", record.feeRecipient),
+                pending_fee_recipient: pending_of(record.pendingFeeRecipient),
+            });
+        }
+        progress!(format);
+
+        // 3. Set fee recipient
+        progress!(format, "3. Setting fee recipient...");
+        progress!(format, "   New fee recipient: {recipient}");
+        if !self.yes && !confirm(Msg::ProceedPrompt, format)? {
+            return Err(CatalogError::new(Msg::Aborted, &[]).into());
+        }
+        let input: Bytes = ValidatorManagement::setFeeRecipientCall {
+            stakePool: stake_pool,
+            newRecipient: recipient,
+        }
+        .abi_encode()
+        .into();
+        let receipt = send(&provider, wallet_address, input, &fees, format).await?;
+        progress!(
+            format,
+            "   Transaction confirmed, block number: {}",
+            receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
+        );
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
+            "   Transaction cost: {} ETH",
+            format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
+        );
+
+        // Check fee recipient event
+        let event = receipt
+            .logs()
+            .iter()
+            .find_map(|log| ValidatorManagement::FeeRecipientUpdated::decode_log(&log.inner).ok())
+            .ok_or(anyhow::anyhow!("Failed to find FeeRecipientUpdated event"))?;
+        progress!(format, "   Fee recipient update successful!");
+        progress!(format, "   - StakePool: {}", event.stakePool);
+        progress!(format, "   - New recipient: {}", event.newRecipient);
+        if event.newRecipient != recipient {
+            return Err(anyhow::anyhow!(
+                "FeeRecipientUpdated event carries recipient {}, expected {recipient}",
+                event.newRecipient
+            ));
+        }
+        let transaction = TxSummary::new(&receipt).with_event(serde_json::json!({
+            "stake_pool": format!("{}", event.stakePool),
+            "new_recipient": format!("{}", event.newRecipient),
+        }));
+        progress!(format);
+
+        // 4. Final status check
+        progress!(format, "4. Final status check...");
+        let record = get_validator(&provider, wallet_address, stake_pool).await?;
+        progress!(format, "   - Current fee recipient: {}", record.feeRecipient);
+        progress!(
+            format,
+            "   - Pending fee recipient: {}",
+            describe_pending(record.pendingFeeRecipient)
+        );
+        if record.feeRecipient == recipient {
+            progress!(format, "   Fee recipient is now {recipient}\n");
+        } else if record.pendingFeeRecipient == recipient {
+            progress!(format, "   Fees go to {recipient} from the next epoch on\n");
+        } else {
+            return Err(anyhow::anyhow!(
+                "Validator record shows neither current nor pending fee recipient {recipient}"
+            ));
+        }
+        format.emit(&SetFeeRecipientResult {
+            stake_pool: format!("{stake_pool}"),
+            transaction: Some(transaction),
+            fee_recipient: format!("{}", record.feeRecipient),
+            pending_fee_recipient: pending_of(record.pendingFeeRecipient),
+        })
+    }
+}
+
+fn pending_of(pending: Address) -> Option<String> {
+    (pending != Address::ZERO).then(|| format!("{pending}"))
+}
+
+fn describe_pending(pending: Address) -> String {
+    if pending == Address::ZERO {
+        "none".to_string()
+    } else {
+        format!("{pending} (applied next epoch)")
+    }
+}
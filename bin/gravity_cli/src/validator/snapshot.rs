@@ -0,0 +1,540 @@
+use alloy_primitives::{Address, TxKind, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::{BlockId, TransactionInput, TransactionRequest};
+use alloy_sol_types::SolCall;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    command::Executable,
+    contract::{validator_manager_address, ValidatorConsensusInfo, ValidatorManagement},
+    i18n::MissingOption,
+    output::OutputFormat,
+    util::format_ether,
+};
+
+/// Bumped when the snapshot layout changes incompatibly.
+const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug, Parser)]
+pub struct SnapshotCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// Block to read the validator set at (default: latest)
+    #[clap(long)]
+    pub block: Option<u64>,
+
+    /// File to save the snapshot to, e.g. set-$(date +%F).json (default: stdout)
+    #[clap(long)]
+    pub out: Option<PathBuf>,
+
+    /// Overwrite --out if it already exists
+    #[clap(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct CompareCommand {
+    /// Older snapshot
+    pub from: PathBuf,
+
+    /// Newer snapshot
+    pub to: PathBuf,
+}
+
+/// The validator set as seen at one block, saved by `validator snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    chain_id: u64,
+    block_number: u64,
+    /// Unix seconds when the snapshot was taken.
+    taken_at: u64,
+    epoch: u64,
+    /// In wei, as a decimal string.
+    total_voting_power: String,
+    /// Active, pending active and pending inactive validators.
+    validators: Vec<SnapshotValidator>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SnapshotValidator {
+    validator: String,
+    moniker: String,
+    status: String,
+    /// In wei, as a decimal string.
+    voting_power: String,
+    validator_index: u64,
+    consensus_pubkey: String,
+    network_addresses: String,
+    fullnode_addresses: String,
+    fee_recipient: String,
+}
+
+impl SnapshotValidator {
+    fn voting_power(&self) -> U256 {
+        self.voting_power.parse().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SnapshotRef {
+    block_number: u64,
+    epoch: u64,
+    taken_at: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct Member {
+    validator: String,
+    moniker: String,
+    status: String,
+    voting_power: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Change {
+    validator: String,
+    moniker: String,
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PowerChange {
+    validator: String,
+    moniker: String,
+    from: String,
+    to: String,
+    /// Signed, in ETH, e.g. "-1.5"
+    delta: String,
+}
+
+/// Everything that differs between two snapshots, by validator address.
+#[derive(Debug, Serialize)]
+struct SnapshotDiff {
+    from: SnapshotRef,
+    to: SnapshotRef,
+    total_voting_power: PowerChange,
+    joined: Vec<Member>,
+    left: Vec<Member>,
+    status_changes: Vec<Change>,
+    power_changes: Vec<PowerChange>,
+    key_rotations: Vec<Change>,
+    network_address_changes: Vec<Change>,
+    fullnode_address_changes: Vec<Change>,
+    fee_recipient_changes: Vec<Change>,
+}
+
+impl SnapshotDiff {
+    fn is_empty(&self) -> bool {
+        self.joined.is_empty() &&
+            self.left.is_empty() &&
+            self.status_changes.is_empty() &&
+            self.power_changes.is_empty() &&
+            self.key_rotations.is_empty() &&
+            self.network_address_changes.is_empty() &&
+            self.fullnode_address_changes.is_empty() &&
+            self.fee_recipient_changes.is_empty()
+    }
+}
+
+impl Executable for SnapshotCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl Executable for CompareCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let from = load_snapshot(&self.from)?;
+        let to = load_snapshot(&self.to)?;
+        if from.chain_id != to.chain_id {
+            return Err(anyhow::anyhow!(
+                "Snapshots are from different chains ({} and {})",
+                from.chain_id,
+                to.chain_id
+            ));
+        }
+        let diff = diff_snapshots(&from, &to);
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+            _ => print_diff(&diff),
+        }
+        Ok(())
+    }
+}
+
+/// Calls a view function of the validator manager at `block`.
+async fn view<C: SolCall>(
+    provider: &impl Provider,
+    call: C,
+    block: BlockId,
+) -> Result<C::Return, anyhow::Error> {
+    let result = provider
+        .call(TransactionRequest {
+            to: Some(TxKind::Call(validator_manager_address())),
+            input: TransactionInput::new(call.abi_encode().into()),
+            ..Default::default()
+        })
+        .block(block)
+        .await?;
+    C::abi_decode_returns(&result)
+        .map_err(|e| anyhow::anyhow!("Failed to decode {}: {e}", C::SIGNATURE))
+}
+
+impl SnapshotCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        if let Some(out) = &self.out {
+            if out.exists() && !self.force {
+                return Err(anyhow::anyhow!(
+                    "{} already exists, pass --force to overwrite it",
+                    out.display()
+                ));
+            }
+        }
+
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        // Every call reads the same block, so the snapshot is consistent.
+        let block_number = match self.block {
+            Some(block_number) => block_number,
+            None => provider.get_block_number().await?,
+        };
+        let block = BlockId::number(block_number);
+
+        let epoch = view(&provider, ValidatorManagement::getCurrentEpochCall {}, block).await?;
+        let total_voting_power =
+            view(&provider, ValidatorManagement::getTotalVotingPowerCall {}, block).await?;
+        let sets = [
+            (
+                "ACTIVE",
+                view(&provider, ValidatorManagement::getActiveValidatorsCall {}, block).await?,
+            ),
+            (
+                "PENDING_ACTIVE",
+                view(&provider, ValidatorManagement::getPendingActiveValidatorsCall {}, block)
+                    .await?,
+            ),
+            (
+                "PENDING_INACTIVE",
+                view(&provider, ValidatorManagement::getPendingInactiveValidatorsCall {}, block)
+                    .await?,
+            ),
+        ];
+
+        let mut validators = Vec::new();
+        for (status, infos) in sets {
+            for info in infos {
+                let record = view(
+                    &provider,
+                    ValidatorManagement::getValidatorCall { stakePool: info.validator },
+                    block,
+                )
+                .await?;
+                validators.push(snapshot_validator(
+                    &info,
+                    status,
+                    &record.moniker,
+                    record.feeRecipient,
+                ));
+            }
+        }
+
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            chain_id,
+            block_number,
+            taken_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            epoch,
+            total_voting_power: total_voting_power.to_string(),
+            validators,
+        };
+        let json = serde_json::to_string_pretty(&snapshot)?;
+        match &self.out {
+            Some(out) => {
+                std::fs::write(out, json + "\n")?;
+                eprintln!(
+                    "Saved {} validators at block {} (epoch {}) to {}",
+                    snapshot.validators.len(),
+                    block_number,
+                    epoch,
+                    out.display()
+                );
+                format.emit(&serde_json::json!({
+                    "out": out,
+                    "block_number": block_number,
+                    "epoch": epoch,
+                    "validators": snapshot.validators.len(),
+                }))?;
+            }
+            None => println!("{json}"),
+        }
+        Ok(())
+    }
+}
+
+fn snapshot_validator(
+    info: &ValidatorConsensusInfo,
+    status: &str,
+    moniker: &str,
+    fee_recipient: Address,
+) -> SnapshotValidator {
+    SnapshotValidator {
+        validator: format!("{:?}", info.validator),
+        moniker: moniker.to_string(),
+        status: status.to_string(),
+        voting_power: info.votingPower.to_string(),
+        validator_index: info.validatorIndex,
+        consensus_pubkey: hex::encode(&info.consensusPubkey),
+        network_addresses: bcs::from_bytes::<String>(&info.networkAddresses)
+            .unwrap_or_else(|_| hex::encode(&info.networkAddresses)),
+        fullnode_addresses: bcs::from_bytes::<String>(&info.fullnodeAddresses)
+            .unwrap_or_else(|_| hex::encode(&info.fullnodeAddresses)),
+        fee_recipient: format!("{fee_recipient:?}"),
+    }
+}
+
+fn load_snapshot(path: &Path) -> Result<Snapshot, anyhow::Error> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+    let snapshot: Snapshot = serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("{} is not a validator snapshot: {e}", path.display()))?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(anyhow::anyhow!(
+            "{} has snapshot version {}, this gravity_cli reads version {SNAPSHOT_VERSION}",
+            path.display(),
+            snapshot.version
+        ));
+    }
+    Ok(snapshot)
+}
+
+fn signed_ether_delta(from: U256, to: U256) -> String {
+    if to >= from {
+        format!("+{}", format_ether(to - from))
+    } else {
+        format!("-{}", format_ether(from - to))
+    }
+}
+
+fn power_change(validator: &str, moniker: &str, from: U256, to: U256) -> PowerChange {
+    PowerChange {
+        validator: validator.to_string(),
+        moniker: moniker.to_string(),
+        from: format_ether(from),
+        to: format_ether(to),
+        delta: signed_ether_delta(from, to),
+    }
+}
+
+fn member(v: &SnapshotValidator) -> Member {
+    Member {
+        validator: v.validator.clone(),
+        moniker: v.moniker.clone(),
+        status: v.status.clone(),
+        voting_power: format_ether(v.voting_power()),
+    }
+}
+
+fn diff_snapshots(from: &Snapshot, to: &Snapshot) -> SnapshotDiff {
+    // Addresses are compared case-insensitively, in case a snapshot was edited by hand.
+    let by_address = |snapshot: &Snapshot| -> BTreeMap<String, SnapshotValidator> {
+        snapshot.validators.iter().map(|v| (v.validator.to_lowercase(), v.clone())).collect()
+    };
+    let (old, new) = (by_address(from), by_address(to));
+    let snapshot_ref = |s: &Snapshot| SnapshotRef {
+        block_number: s.block_number,
+        epoch: s.epoch,
+        taken_at: s.taken_at,
+    };
+    let mut diff = SnapshotDiff {
+        from: snapshot_ref(from),
+        to: snapshot_ref(to),
+        total_voting_power: power_change(
+            "total",
+            "",
+            from.total_voting_power.parse().unwrap_or_default(),
+            to.total_voting_power.parse().unwrap_or_default(),
+        ),
+        joined: new.iter().filter(|(a, _)| !old.contains_key(*a)).map(|(_, v)| member(v)).collect(),
+        left: old.iter().filter(|(a, _)| !new.contains_key(*a)).map(|(_, v)| member(v)).collect(),
+        status_changes: Vec::new(),
+        power_changes: Vec::new(),
+        key_rotations: Vec::new(),
+        network_address_changes: Vec::new(),
+        fullnode_address_changes: Vec::new(),
+        fee_recipient_changes: Vec::new(),
+    };
+
+    for (address, before) in &old {
+        let Some(after) = new.get(address) else { continue };
+        let change = |from: &str, to: &str| Change {
+            validator: after.validator.clone(),
+            moniker: after.moniker.clone(),
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        if before.status != after.status {
+            diff.status_changes.push(change(&before.status, &after.status));
+        }
+        if before.voting_power() != after.voting_power() {
+            diff.power_changes.push(power_change(
+                &after.validator,
+                &after.moniker,
+                before.voting_power(),
+                after.voting_power(),
+            ));
+        }
+        if before.consensus_pubkey != after.consensus_pubkey {
+            diff.key_rotations.push(change(&before.consensus_pubkey, &after.consensus_pubkey));
+        }
+        if before.network_addresses != after.network_addresses {
+            diff.network_address_changes
+                .push(change(&before.network_addresses, &after.network_addresses));
+        }
+        if before.fullnode_addresses != after.fullnode_addresses {
+            diff.fullnode_address_changes
+                .push(change(&before.fullnode_addresses, &after.fullnode_addresses));
+        }
+        if !before.fee_recipient.eq_ignore_ascii_case(&after.fee_recipient) {
+            diff.fee_recipient_changes.push(change(&before.fee_recipient, &after.fee_recipient));
+        }
+    }
+    diff
+}
+
+fn label(validator: &str, moniker: &str) -> String {
+    if moniker.is_empty() {
+        validator.to_string()
+    } else {
+        format!("{validator} ({moniker})")
+    }
+}
+
+fn print_changes(title: &str, changes: &[Change]) {
+    if changes.is_empty() {
+        return;
+    }
+    println!("{title}:");
+    for c in changes {
+        println!("  {}", label(&c.validator, &c.moniker));
+        println!("    - {}", c.from);
+        println!("    + {}", c.to);
+    }
+    println!();
+}
+
+fn print_diff(diff: &SnapshotDiff) {
+    println!(
+        "Block {} (epoch {}) -> block {} (epoch {})",
+        diff.from.block_number, diff.from.epoch, diff.to.block_number, diff.to.epoch
+    );
+    println!(
+        "Total voting power: {} -> {} ETH ({})",
+        diff.total_voting_power.from, diff.total_voting_power.to, diff.total_voting_power.delta
+    );
+    println!();
+    if diff.is_empty() {
+        println!("No changes to the validator set.");
+        return;
+    }
+    for (title, members) in [("Joined", &diff.joined), ("Left", &diff.left)] {
+        if members.is_empty() {
+            continue;
+        }
+        println!("{title}:");
+        for m in members {
+            println!("  {} [{}] {} ETH", label(&m.validator, &m.moniker), m.status, m.voting_power);
+        }
+        println!();
+    }
+    print_changes("Status changes", &diff.status_changes);
+    if !diff.power_changes.is_empty() {
+        println!("Voting power changes:");
+        for p in &diff.power_changes {
+            println!(
+                "  {}: {} -> {} ETH ({})",
+                label(&p.validator, &p.moniker),
+                p.from,
+                p.to,
+                p.delta
+            );
+        }
+        println!();
+    }
+    print_changes("Consensus key rotations", &diff.key_rotations);
+    print_changes("Network address changes", &diff.network_address_changes);
+    print_changes("Fullnode address changes", &diff.fullnode_address_changes);
+    print_changes("Fee recipient changes", &diff.fee_recipient_changes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator(address: &str, power: &str, key: &str) -> SnapshotValidator {
+        SnapshotValidator {
+            validator: address.to_string(),
+            moniker: String::new(),
+            status: "ACTIVE".to_string(),
+            voting_power: power.to_string(),
+            validator_index: 0,
+            consensus_pubkey: key.to_string(),
+            network_addresses: "/ip4/10.0.0.1/tcp/6180".to_string(),
+            fullnode_addresses: "/ip4/10.0.0.1/tcp/6182".to_string(),
+            fee_recipient: "0xfee".to_string(),
+        }
+    }
+
+    fn snapshot(validators: Vec<SnapshotValidator>) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            chain_id: 1,
+            block_number: 100,
+            taken_at: 0,
+            epoch: 5,
+            total_voting_power: "3000000000000000000".to_string(),
+            validators,
+        }
+    }
+
+    #[test]
+    fn reports_joins_leaves_power_and_keys() {
+        let from = snapshot(vec![
+            validator("0xaa", "2500000000000000000", "k1"),
+            validator("0xbb", "2000000000000000000", "k2"),
+        ]);
+        let to = snapshot(vec![
+            validator("0xAA", "1000000000000000000", "k1-rotated"),
+            validator("0xcc", "1000000000000000000", "k3"),
+        ]);
+        let diff = diff_snapshots(&from, &to);
+        assert_eq!(diff.joined.iter().map(|m| m.validator.as_str()).collect::<Vec<_>>(), ["0xcc"]);
+        assert_eq!(diff.left.iter().map(|m| m.validator.as_str()).collect::<Vec<_>>(), ["0xbb"]);
+        assert_eq!(diff.power_changes.len(), 1);
+        assert_eq!(diff.power_changes[0].delta, "-1.5");
+        assert_eq!(diff.key_rotations.len(), 1);
+        assert_eq!(diff.key_rotations[0].to, "k1-rotated");
+        assert!(diff.status_changes.is_empty());
+        assert!(diff.fee_recipient_changes.is_empty());
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let from = snapshot(vec![validator("0xaa", "1000000000000000000", "k1")]);
+        let diff = diff_snapshots(&from, &from.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.total_voting_power.from, diff.total_voting_power.to);
+    }
+}
@@ -0,0 +1,230 @@
+use alloy_primitives::{Address, Bytes, TxKind};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::{BlockNumberOrTag, TransactionInput, TransactionRequest};
+use alloy_sol_types::SolCall;
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{
+        epoch_config_address, reconfiguration_address, staking_address, status_from_u8,
+        EpochConfig, Reconfiguration, Staking,
+    },
+    i18n::MissingOption,
+    output::OutputFormat,
+    transaction::get_validator,
+    util::format_ether,
+};
+
+#[derive(Debug, Parser)]
+pub struct StatusCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address (validator identity)
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Print JSON, same as the global `--output json`
+    #[clap(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidatorStatusInfo {
+    stake_pool: String,
+    validator: String,
+    moniker: String,
+    status: String,
+    validator_index: u64,
+    bond: String,
+    consensus_pubkey: String,
+    network_addresses: String,
+    fullnode_addresses: String,
+    fee_recipient: String,
+    /// Fee recipient taking over at the next epoch, if one is pending.
+    pending_fee_recipient: Option<String>,
+    pool: PoolInfo,
+    epoch: EpochInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct PoolInfo {
+    owner: String,
+    operator: String,
+    voting_power: String,
+    active_stake: String,
+    /// Lockup expiry, in microseconds since the Unix epoch.
+    locked_until_micros: u64,
+    /// Seconds until the lockup expires; 0 once it has.
+    lockup_remaining_secs: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct EpochInfo {
+    current_epoch: u64,
+    interval_secs: u64,
+    /// Seconds until the next epoch is due; 0 when it is overdue.
+    remaining_secs: u64,
+}
+
+impl Executable for StatusCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+/// Calls view function `call` of contract `to`.
+pub(crate) async fn view<P: Provider, C: SolCall>(
+    provider: &P,
+    to: Address,
+    call: C,
+) -> Result<C::Return, anyhow::Error> {
+    let input: Bytes = call.abi_encode().into();
+    let result = provider
+        .call(TransactionRequest {
+            to: Some(TxKind::Call(to)),
+            input: TransactionInput::new(input),
+            ..Default::default()
+        })
+        .await?;
+    C::abi_decode_returns(&result)
+        .map_err(|e| anyhow::anyhow!("Failed to decode {} result: {e}", C::SIGNATURE))
+}
+
+pub(crate) fn format_duration(secs: u64) -> String {
+    let (d, h, m) = (secs / 86_400, (secs % 86_400) / 3600, (secs % 3600) / 60);
+    if d > 0 {
+        format!("{d}d {h}h {m}m")
+    } else if h > 0 {
+        format!("{h}h {m}m")
+    } else {
+        format!("{m}m {}s", secs % 60)
+    }
+}
+
+impl StatusCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool)?;
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+
+        let record = get_validator(&provider, Address::ZERO, stake_pool).await?;
+        let owner =
+            view(&provider, staking_address(), Staking::getPoolOwnerCall { pool: stake_pool })
+                .await?;
+        let operator =
+            view(&provider, staking_address(), Staking::getPoolOperatorCall { pool: stake_pool })
+                .await?;
+        let voting_power = view(
+            &provider,
+            staking_address(),
+            Staking::getPoolVotingPowerNowCall { pool: stake_pool },
+        )
+        .await?;
+        let active_stake = view(
+            &provider,
+            staking_address(),
+            Staking::getPoolActiveStakeCall { pool: stake_pool },
+        )
+        .await?;
+        let locked_until = view(
+            &provider,
+            staking_address(),
+            Staking::getPoolLockedUntilCall { pool: stake_pool },
+        )
+        .await?;
+        let current_epoch =
+            view(&provider, reconfiguration_address(), Reconfiguration::currentEpochCall {})
+                .await?;
+        let last_reconfiguration = view(
+            &provider,
+            reconfiguration_address(),
+            Reconfiguration::lastReconfigurationTimeCall {},
+        )
+        .await?;
+        let interval =
+            view(&provider, epoch_config_address(), EpochConfig::epochIntervalMicrosCall {})
+                .await?;
+        // Lockup and epoch deadlines are measured against chain time, not the local clock
+        let now_micros = provider
+            .get_block_by_number(BlockNumberOrTag::Latest)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to fetch latest block"))?
+            .header
+            .timestamp *
+            1_000_000;
+
+        let info = ValidatorStatusInfo {
+            stake_pool: format!("{stake_pool:?}"),
+            validator: format!("{:?}", record.validator),
+            moniker: record.moniker.clone(),
+            status: format!("{:?}", status_from_u8(record.status)),
+            validator_index: record.validatorIndex,
+            bond: format!("{} ETH", format_ether(record.bond)),
+            consensus_pubkey: hex::encode(&record.consensusPubkey),
+            network_addresses: String::from_utf8_lossy(&record.networkAddresses).into_owned(),
+            fullnode_addresses: String::from_utf8_lossy(&record.fullnodeAddresses).into_owned(),
+            fee_recipient: format!("{:?}", record.feeRecipient),
+            pending_fee_recipient: (record.pendingFeeRecipient != Address::ZERO)
+                .then(|| format!("{:?}", record.pendingFeeRecipient)),
+            pool: PoolInfo {
+                owner: format!("{owner:?}"),
+                operator: format!("{operator:?}"),
+                voting_power: format!("{} ETH", format_ether(voting_power)),
+                active_stake: format!("{} ETH", format_ether(active_stake)),
+                locked_until_micros: locked_until,
+                lockup_remaining_secs: locked_until.saturating_sub(now_micros) / 1_000_000,
+            },
+            epoch: EpochInfo {
+                current_epoch,
+                interval_secs: interval / 1_000_000,
+                remaining_secs: (last_reconfiguration + interval).saturating_sub(now_micros) /
+                    1_000_000,
+            },
+        };
+
+        if self.json || matches!(format, OutputFormat::Json) {
+            println!("{}", serde_json::to_string_pretty(&info)?);
+            return Ok(());
+        }
+
+        println!("Validator {} ({})", info.moniker, info.stake_pool);
+        println!("  Validator:          {}", info.validator);
+        println!("  Status:             {}", info.status);
+        println!("  Validator index:    {}", info.validator_index);
+        println!("  Bond:               {}", info.bond);
+        println!("  Consensus pubkey:   {}", info.consensus_pubkey);
+        println!("  Network addresses:  {}", info.network_addresses);
+        println!("  Fullnode addresses: {}", info.fullnode_addresses);
+        println!("  Fee recipient:      {}", info.fee_recipient);
+        if let Some(pending) = &info.pending_fee_recipient {
+            println!("  Pending recipient:  {pending} (applied next epoch)");
+        }
+        println!();
+        println!("StakePool");
+        println!("  Owner:              {}", info.pool.owner);
+        println!("  Operator:           {}", info.pool.operator);
+        println!("  Voting power:       {}", info.pool.voting_power);
+        println!("  Active stake:       {}", info.pool.active_stake);
+        if info.pool.lockup_remaining_secs > 0 {
+            println!(
+                "  Lockup:             expires in {}",
+                format_duration(info.pool.lockup_remaining_secs)
+            );
+        } else {
+            println!("  Lockup:             expired");
+        }
+        println!();
+        println!("Epoch");
+        println!("  Current epoch:      {}", info.epoch.current_epoch);
+        println!("  Interval:           {}", format_duration(info.epoch.interval_secs));
+        println!("  Next epoch in:      {}", format_duration(info.epoch.remaining_secs));
+        Ok(())
+    }
+}
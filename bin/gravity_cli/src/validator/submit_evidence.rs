@@ -0,0 +1,289 @@
+use alloy_primitives::{Address, Bytes, FixedBytes};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{str::FromStr, time::Duration};
+
+use crate::{
+    command::Executable,
+    contract::Slashing,
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::send_to,
+    util::{confirm, parse_hex, API_V1},
+};
+
+#[derive(Debug, Parser)]
+pub struct SubmitEvidenceCommand {
+    /// Node API address and port (e.g., 127.0.0.1:1024) to read recorded evidence from
+    #[clap(long, alias = "server-url", env = "GRAVITY_SERVER_URL")]
+    pub api_url: Option<String>,
+
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// Address of the slashing contract accepting the evidence
+    #[clap(long)]
+    pub contract: String,
+
+    /// Only submit evidence from this epoch
+    #[clap(long)]
+    pub epoch: Option<u64>,
+
+    /// Only submit evidence from this round
+    #[clap(long)]
+    pub round: Option<u64>,
+
+    /// Only submit evidence against this validator (hex account address)
+    #[clap(long)]
+    pub author: Option<String>,
+
+    /// Print the calldata of each submission instead of sending transactions
+    #[clap(long)]
+    pub calldata_only: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(long)]
+    pub yes: bool,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// One entry of the node's `/consensus/evidence` response.
+#[derive(Deserialize, Debug)]
+struct Evidence {
+    kind: String,
+    epoch: u64,
+    round: u64,
+    author: String,
+    first: String,
+    second: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct EvidenceResponse {
+    evidence: Vec<Evidence>,
+}
+
+/// Result of `validator submit-evidence` with `--output json`.
+#[derive(Debug, Serialize, Default)]
+struct SubmitEvidenceResult {
+    contract: String,
+    /// Calldata of each submission, with `--calldata-only`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    calldata: Option<Vec<String>>,
+    submissions: Vec<Submission>,
+}
+
+#[derive(Debug, Serialize)]
+struct Submission {
+    kind: String,
+    epoch: u64,
+    round: u64,
+    author: String,
+    transaction: Option<TxSummary>,
+    /// Whether the contract emitted `EquivocationEvidenceSubmitted`.
+    accepted: bool,
+    error: Option<String>,
+}
+
+impl Evidence {
+    fn calldata(&self) -> Result<Bytes, anyhow::Error> {
+        let kind = match self.kind.as_str() {
+            "vote" => 0,
+            "proposal" => 1,
+            kind => return Err(anyhow::anyhow!("Unknown evidence kind '{kind}'")),
+        };
+        Ok(Slashing::submitEquivocationEvidenceCall {
+            kind,
+            epoch: self.epoch,
+            round: self.round,
+            author: FixedBytes::from_slice(&parse_hex(&self.author, 32, "author")?),
+            first: hex::decode(&self.first)?.into(),
+            second: hex::decode(&self.second)?.into(),
+        }
+        .abi_encode()
+        .into())
+    }
+}
+
+impl Executable for SubmitEvidenceCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl SubmitEvidenceCommand {
+    fn normalize_url(url: &str) -> String {
+        let url = url.trim_end_matches('/');
+        if url.starts_with("https://") || url.starts_with("http://") {
+            url.to_string()
+        } else {
+            format!("http://{url}")
+        }
+    }
+
+    async fn fetch_evidence(&self, api_url: &str) -> Result<Vec<Evidence>, anyhow::Error> {
+        let mut url = format!("{}{API_V1}/consensus/evidence", Self::normalize_url(api_url));
+        if let Some(epoch) = self.epoch {
+            url.push_str(&format!("?epoch={epoch}"));
+        }
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        let response = client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch evidence from {url}: HTTP {}",
+                response.status()
+            ));
+        }
+        let author = self
+            .author
+            .as_deref()
+            .map(|author| parse_hex(author, 32, "author"))
+            .transpose()?
+            .map(hex::encode);
+        Ok(response
+            .json::<EvidenceResponse>()
+            .await?
+            .evidence
+            .into_iter()
+            .filter(|evidence| self.round.map_or(true, |round| evidence.round == round))
+            .filter(|evidence| author.as_ref().map_or(true, |author| &evidence.author == author))
+            .collect())
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let api_url = self
+            .api_url
+            .clone()
+            .ok_or_else(|| MissingOption::new("api-url", "GRAVITY_SERVER_URL"))?;
+        let contract = Address::from_str(&self.contract)
+            .map_err(|e| anyhow::anyhow!("Invalid contract address '{}': {e}", self.contract))?;
+
+        // 1. Collect recorded evidence
+        progress!(format, "1. Fetching evidence from {api_url}...");
+        let evidence = self.fetch_evidence(&api_url).await?;
+        if evidence.is_empty() {
+            progress!(format, "   No matching equivocation evidence recorded by this node\n");
+            return format.emit(&SubmitEvidenceResult {
+                contract: format!("{contract}"),
+                ..Default::default()
+            });
+        }
+        let mut submissions = Vec::with_capacity(evidence.len());
+        for evidence in &evidence {
+            progress!(
+                format,
+                "   - {} equivocation by 0x{} in epoch {} round {}",
+                evidence.kind,
+                evidence.author,
+                evidence.epoch,
+                evidence.round
+            );
+            submissions.push(evidence.calldata()?);
+        }
+        progress!(format);
+
+        if self.calldata_only {
+            progress!(format, "2. Calldata for {contract}:");
+            for calldata in &submissions {
+                progress!(format, "{calldata}");
+            }
+            return format.emit(&SubmitEvidenceResult {
+                contract: format!("{contract}"),
+                calldata: Some(submissions.iter().map(|calldata| format!("{calldata}")).collect()),
+                ..Default::default()
+            });
+        }
+
+        // 2. Initialize Provider and Wallet
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        progress!(format, "2. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        progress!(format, "   Contract address: {contract:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
+
+        // 3. Submit evidence
+        progress!(format, "3. Submitting {} piece(s) of evidence...", submissions.len());
+        if !self.yes && !confirm(Msg::ProceedPrompt, format)? {
+            return Err(CatalogError::new(Msg::Aborted, &[]).into());
+        }
+        let mut failed = 0;
+        let mut result =
+            SubmitEvidenceResult { contract: format!("{contract}"), ..Default::default() };
+        for (evidence, input) in evidence.iter().zip(submissions) {
+            progress!(
+                format,
+                "   Epoch {} round {} ({}):",
+                evidence.epoch,
+                evidence.round,
+                evidence.kind
+            );
+            let mut submission = Submission {
+                kind: evidence.kind.clone(),
+                epoch: evidence.epoch,
+                round: evidence.round,
+                author: evidence.author.clone(),
+                transaction: None,
+                accepted: false,
+                error: None,
+            };
+            match send_to(&provider, wallet_address, contract, input, &fees, format).await {
+                Ok(receipt) => {
+                    let accepted = receipt.logs().iter().any(|log| {
+                        Slashing::EquivocationEvidenceSubmitted::decode_log(&log.inner).is_ok()
+                    });
+                    progress!(
+                        format,
+                        "   {}",
+                        if accepted {
+                            "Evidence accepted"
+                        } else {
+                            "Transaction confirmed, but no EquivocationEvidenceSubmitted event"
+                        }
+                    );
+                    submission.transaction = Some(TxSummary::new(&receipt));
+                    submission.accepted = accepted;
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("   Submission failed: {e}");
+                    submission.error = Some(e.to_string());
+                }
+            }
+            result.submissions.push(submission);
+        }
+        progress!(format);
+
+        // 4. Summary
+        progress!(format, "4. Summary...");
+        progress!(format, "   Submitted: {}", evidence.len() - failed);
+        format.emit(&result)?;
+        if failed > 0 {
+            return Err(anyhow::anyhow!("{failed} submission(s) failed"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,405 @@
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::SolCall;
+use clap::Parser;
+use serde::Serialize;
+use std::{str::FromStr, time::Duration};
+
+use crate::{
+    command::Executable,
+    contract::{
+        staking_address, validator_manager_address, StakePool, Staking, ValidatorManagement,
+    },
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat},
+    signer::SignerArgs,
+    transaction::{send_value, simulate_value},
+    util::{format_ether, parse_ether},
+    validator::{
+        notify::{now_secs, sign, SIGNATURE_HEADER},
+        status::view,
+    },
+};
+
+#[derive(Debug, Parser)]
+pub struct TopupCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address (validator identity) to top up
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Voting-power rank to hold among active validators, 1 being the largest
+    #[clap(long)]
+    pub target_rank: usize,
+
+    /// Most stake in ETH added during one epoch
+    #[clap(long)]
+    pub max_per_epoch: String,
+
+    /// Keep checking the rank until interrupted. Without it, check and top up once.
+    #[clap(long)]
+    pub daemon: bool,
+
+    /// Seconds between checks in daemon mode
+    #[clap(long, default_value_t = 60)]
+    pub poll_interval_secs: u64,
+
+    /// Work out and simulate each top-up, without sending anything
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// URL every action is POSTed to as JSON
+    #[clap(long)]
+    pub webhook: Option<String>,
+
+    /// Shared secret signing every notification with HMAC-SHA256, sent in the
+    /// X-Gravity-Signature header as `sha256=<hex>`
+    #[clap(long, env = "GRAVITY_NOTIFY_SECRET", hide_env_values = true)]
+    pub webhook_secret: Option<String>,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Body of a webhook notification, sent for every top-up the policy decides on.
+#[derive(Debug, Serialize)]
+struct TopupNotification {
+    stake_pool: Address,
+    /// `topup_sent`, `topup_simulated`, `topup_failed` or `budget_exhausted`; `rank_held` is
+    /// only printed, never sent
+    kind: &'static str,
+    detail: String,
+    epoch: u64,
+    /// Stake added (or that would be added) in ETH.
+    amount: String,
+    /// Rank before the top-up; the new bond counts from the next epoch.
+    rank: usize,
+    target_rank: usize,
+    tx_hash: Option<B256>,
+    timestamp: u64,
+}
+
+/// Stake `own` needs on top of its voting power to reach `target_rank` among validators with
+/// voting power `others`, or `None` when it already holds that rank. Ties rank below `own`'s
+/// rivals, so the top-up always ends strictly above the validator it overtakes.
+fn required_topup(own: U256, others: &[U256], target_rank: usize) -> Option<U256> {
+    let mut others = others.to_vec();
+    others.sort_unstable_by(|a, b| b.cmp(a));
+    let rival = *others.get(target_rank.checked_sub(1)?)?;
+    (rival >= own).then(|| rival - own + U256::from(1))
+}
+
+/// 1-based rank of `own` among `others`, counting ties against it.
+fn rank_of(own: U256, others: &[U256]) -> usize {
+    others.iter().filter(|power| **power >= own).count() + 1
+}
+
+impl Executable for TopupCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+/// Stake added in the current epoch, reset when the epoch changes.
+struct EpochBudget {
+    epoch: u64,
+    spent: U256,
+    /// Whether running out of budget has been reported, so it is only reported once.
+    exhausted_reported: bool,
+}
+
+impl EpochBudget {
+    fn new(epoch: u64) -> Self {
+        Self { epoch, spent: U256::ZERO, exhausted_reported: false }
+    }
+}
+
+impl TopupCommand {
+    /// Prints `notification`, one JSON object per line with `--output json`.
+    fn report(&self, notification: &TopupNotification, format: OutputFormat) {
+        match format {
+            OutputFormat::Json => match serde_json::to_string(notification) {
+                Ok(line) => println!("{line}"),
+                Err(e) => eprintln!("Warning: failed to encode {}: {e}", notification.kind),
+            },
+            OutputFormat::Plain => println!(
+                "[epoch {}] {}: {}",
+                notification.epoch, notification.kind, notification.detail
+            ),
+        }
+    }
+
+    async fn notify(
+        &self,
+        client: &reqwest::Client,
+        notification: &TopupNotification,
+        format: OutputFormat,
+    ) {
+        self.report(notification, format);
+        let (Some(webhook), Some(secret)) = (&self.webhook, &self.webhook_secret) else {
+            return;
+        };
+        let result = async {
+            let body = serde_json::to_vec(notification)?;
+            let response = client
+                .post(webhook)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, sign(secret, &body))
+                .body(body)
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                anyhow::bail!("webhook returned HTTP {}", response.status());
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            eprintln!("Warning: failed to deliver {} notification: {e}", notification.kind);
+        }
+    }
+
+    /// Checks the rank once and tops up the pool if it has fallen behind.
+    #[allow(clippy::too_many_arguments)]
+    async fn check<P: Provider>(
+        &self,
+        provider: &P,
+        client: &reqwest::Client,
+        wallet_address: Address,
+        pool: Address,
+        max_per_epoch: U256,
+        budget: &mut EpochBudget,
+        format: OutputFormat,
+    ) -> Result<(), anyhow::Error> {
+        let epoch = view(
+            provider,
+            validator_manager_address(),
+            ValidatorManagement::getCurrentEpochCall {},
+        )
+        .await?;
+        if epoch != budget.epoch {
+            *budget = EpochBudget::new(epoch);
+        }
+        let own =
+            view(provider, staking_address(), Staking::getPoolVotingPowerNowCall { pool }).await?;
+        let others: Vec<U256> = view(
+            provider,
+            validator_manager_address(),
+            ValidatorManagement::getActiveValidatorsCall {},
+        )
+        .await?
+        .into_iter()
+        .filter(|v| v.validator != pool)
+        .map(|v| v.votingPower)
+        .collect();
+        let rank = rank_of(own, &others);
+        let notification =
+            |kind: &'static str, detail: String, amount: U256, tx_hash: Option<B256>| {
+                TopupNotification {
+                    stake_pool: pool,
+                    kind,
+                    detail,
+                    epoch,
+                    amount: format_ether(amount),
+                    rank,
+                    target_rank: self.target_rank,
+                    tx_hash,
+                    timestamp: now_secs(),
+                }
+            };
+        let Some(needed) = required_topup(own, &others, self.target_rank) else {
+            let detail = format!(
+                "rank {rank} of {} with {} ETH, target {} held",
+                others.len() + 1,
+                format_ether(own),
+                self.target_rank
+            );
+            self.report(&notification("rank_held", detail, U256::ZERO, None), format);
+            return Ok(());
+        };
+        let remaining = max_per_epoch.saturating_sub(budget.spent);
+        if remaining.is_zero() {
+            if budget.exhausted_reported {
+                return Ok(());
+            }
+            budget.exhausted_reported = true;
+            let detail = format!(
+                "rank {rank} needs {} ETH, but the {} ETH limit for this epoch is spent",
+                format_ether(needed),
+                format_ether(max_per_epoch)
+            );
+            self.notify(
+                client,
+                &notification("budget_exhausted", detail, U256::ZERO, None),
+                format,
+            )
+            .await;
+            return Ok(());
+        }
+        let amount = needed.min(remaining);
+        let capped = if amount < needed {
+            format!(" (capped by --max-per-epoch, {} ETH needed)", format_ether(needed))
+        } else {
+            String::new()
+        };
+        let balance = provider.get_balance(wallet_address).await?;
+        if balance < amount {
+            let detail = format!(
+                "rank {rank}: cannot add {} ETH, wallet holds {} ETH",
+                format_ether(amount),
+                format_ether(balance)
+            );
+            self.notify(client, &notification("topup_failed", detail, amount, None), format).await;
+            return Ok(());
+        }
+
+        let input: Bytes = StakePool::addStakeCall {}.abi_encode().into();
+        if self.dry_run {
+            let simulation = simulate_value(
+                provider,
+                wallet_address,
+                pool,
+                input,
+                Some(amount),
+                "addStake",
+                "StakeAdded",
+                format,
+            )
+            .await?;
+            let (kind, outcome) = match &simulation.revert_reason {
+                Some(reason) => ("topup_failed", format!("would revert: {reason}")),
+                None => (
+                    "topup_simulated",
+                    format!("estimated gas {}", simulation.gas_estimate.unwrap_or_default()),
+                ),
+            };
+            let detail = format!(
+                "rank {rank}: would add {} ETH{capped} to reach rank {}, {outcome}",
+                format_ether(amount),
+                self.target_rank
+            );
+            // A dry run spends nothing, but counts against the budget like a real top-up would.
+            budget.spent += amount;
+            self.notify(client, &notification(kind, detail, amount, None), format).await;
+            return Ok(());
+        }
+
+        let fees = self.fees.resolve(provider).await?;
+        match send_value(provider, wallet_address, pool, input, Some(amount), &fees, format).await {
+            Ok(receipt) => {
+                budget.spent += amount;
+                let detail = format!(
+                    "rank {rank}: added {} ETH{capped} to reach rank {} in block {}, the bond \
+                     follows at the next epoch",
+                    format_ether(amount),
+                    self.target_rank,
+                    receipt.block_number.unwrap_or_default()
+                );
+                let tx_hash = Some(receipt.transaction_hash);
+                self.notify(client, &notification("topup_sent", detail, amount, tx_hash), format)
+                    .await;
+            }
+            Err(e) => {
+                let detail =
+                    format!("rank {rank}: adding {} ETH failed: {e}", format_ether(amount));
+                self.notify(client, &notification("topup_failed", detail, amount, None), format)
+                    .await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url = self
+            .rpc_url
+            .as_deref()
+            .ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let pool = Address::from_str(&self.stake_pool).map_err(|e| {
+            anyhow::anyhow!("Invalid stake pool address '{}': {e}", self.stake_pool)
+        })?;
+        if self.target_rank == 0 {
+            anyhow::bail!("--target-rank must be at least 1");
+        }
+        let max_per_epoch = parse_ether(&self.max_per_epoch)?;
+        if max_per_epoch.is_zero() {
+            anyhow::bail!("--max-per-epoch must be greater than zero");
+        }
+        match (&self.webhook, &self.webhook_secret) {
+            (Some(_), None) => anyhow::bail!("--webhook needs --webhook-secret"),
+            (_, Some(secret)) if secret.is_empty() => {
+                anyhow::bail!("--webhook-secret must not be empty")
+            }
+            _ => {}
+        }
+
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        if !view(&provider, staking_address(), Staking::isPoolCall { pool }).await? {
+            return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+        }
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?;
+        let mut budget = EpochBudget::new(u64::MAX);
+
+        if self.dry_run {
+            progress!(format, "Dry run: top-ups are simulated, nothing is sent");
+        }
+        if !self.daemon {
+            return self
+                .check(&provider, &client, wallet_address, pool, max_per_epoch, &mut budget, format)
+                .await;
+        }
+
+        progress!(
+            format,
+            "Holding stake pool {pool} at rank {} with up to {} ETH per epoch from \
+             {wallet_address} (Ctrl-C to stop)...",
+            self.target_rank,
+            format_ether(max_per_epoch)
+        );
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.poll_interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            // Keep running through RPC hiccups; nothing is spent on a failed check.
+            if let Err(e) = self
+                .check(&provider, &client, wallet_address, pool, max_per_epoch, &mut budget, format)
+                .await
+            {
+                eprintln!("Warning: failed to check rank: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(n: u64) -> U256 {
+        U256::from(n)
+    }
+
+    #[test]
+    fn computes_stake_needed_for_target_rank() {
+        let others = [eth(50), eth(300), eth(100), eth(200)];
+        assert_eq!(rank_of(eth(150), &others), 3);
+        // Rank 2 means overtaking the second-largest rival, with 200.
+        assert_eq!(required_topup(eth(150), &others, 2), Some(eth(51)));
+        assert_eq!(required_topup(eth(150), &others, 3), None);
+        // A tie is not enough to hold the rank.
+        assert_eq!(required_topup(eth(100), &others, 3), Some(eth(1)));
+        // Fewer rivals than the target rank: held already.
+        assert_eq!(required_topup(eth(0), &others, 5), None);
+        assert_eq!(required_topup(eth(0), &others, 0), None);
+    }
+}
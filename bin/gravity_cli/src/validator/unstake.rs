@@ -0,0 +1,246 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::eth::BlockNumberOrTag;
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{
+        staking_address, status_from_u8, validator_manager_address, StakePool, Staking,
+        ValidatorManagement, ValidatorStatus,
+    },
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{finish_dry_run, send_to, simulate, DryRunArgs},
+    util::{format_ether, parse_ether},
+    validator::status::{format_duration, view},
+};
+
+#[derive(Debug, Parser)]
+pub struct UnstakeCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address to unstake from
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Stake to unlock in ETH
+    #[clap(long, required_unless_present = "all", conflicts_with = "all")]
+    pub amount: Option<String>,
+
+    /// Unlock the pool's whole active stake
+    #[clap(long)]
+    pub all: bool,
+
+    #[clap(flatten)]
+    pub dry_run: DryRunArgs,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `validator unstake` with `--output json`.
+#[derive(Debug, Serialize)]
+struct UnstakeResult {
+    stake_pool: String,
+    /// Amount unlocked in ETH.
+    amount: String,
+    transaction: TxSummary,
+    /// When the unlocked stake can be withdrawn, in microseconds since the Unix epoch.
+    withdrawable_at_micros: u64,
+}
+
+impl Executable for UnstakeCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+/// Lockup expiry of `stake_pool` and the chain time, both in microseconds. Lockups are measured
+/// against chain time, not the local clock.
+pub(crate) async fn lockup<P: Provider>(
+    provider: &P,
+    stake_pool: Address,
+) -> Result<(u64, u64), anyhow::Error> {
+    let locked_until =
+        view(provider, staking_address(), Staking::getPoolLockedUntilCall { pool: stake_pool })
+            .await?;
+    let now_micros = provider
+        .get_block_by_number(BlockNumberOrTag::Latest)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Failed to fetch latest block"))?
+        .header
+        .timestamp *
+        1_000_000;
+    Ok((locked_until, now_micros))
+}
+
+impl UnstakeCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool).map_err(|e| {
+            anyhow::anyhow!("Invalid stake pool address '{}': {e}", self.stake_pool)
+        })?;
+
+        if self.dry_run.enabled {
+            progress!(format, "Dry run: the transaction is simulated, nothing is sent\n");
+        }
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
+
+        // 2. Check the pool can be unstaked
+        progress!(format, "2. Checking StakePool: {stake_pool:?}");
+        if !view(&provider, staking_address(), Staking::isPoolCall { pool: stake_pool }).await? {
+            return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+        }
+        let status = status_from_u8(
+            view(
+                &provider,
+                validator_manager_address(),
+                ValidatorManagement::getValidatorStatusCall { stakePool: stake_pool },
+            )
+            .await?,
+        );
+        progress!(format, "   Validator status: {status:?}");
+        match status {
+            ValidatorStatus::INACTIVE => {}
+            ValidatorStatus::PENDING_INACTIVE => {
+                return Err(anyhow::anyhow!(
+                    "The validator is leaving the set; unstake once it is INACTIVE at the next \
+                     epoch"
+                ));
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "The validator is {status:?}; leave the validator set first with \
+                     `validator leave` and unstake once it is INACTIVE"
+                ));
+            }
+        }
+        let active_stake = view(
+            &provider,
+            staking_address(),
+            Staking::getPoolActiveStakeCall { pool: stake_pool },
+        )
+        .await?;
+        progress!(format, "   Active stake: {} ETH", format_ether(active_stake));
+        let amount = match &self.amount {
+            Some(amount) => parse_ether(amount)?,
+            None => active_stake,
+        };
+        if amount.is_zero() {
+            return Err(anyhow::anyhow!("Nothing to unstake: the amount is zero"));
+        }
+        if amount > active_stake {
+            return Err(anyhow::anyhow!(
+                "Cannot unstake {} ETH, the pool's active stake is {} ETH",
+                format_ether(amount),
+                format_ether(active_stake)
+            ));
+        }
+        let (locked_until, now_micros) = lockup(&provider, stake_pool).await?;
+        if locked_until > now_micros {
+            progress!(
+                format,
+                "   Lockup expires in {}",
+                format_duration((locked_until - now_micros) / 1_000_000)
+            );
+        } else {
+            progress!(
+                format,
+                "   Lockup has expired, the stake is withdrawable right after unstaking"
+            );
+        }
+        progress!(format);
+
+        // 3. Unstake
+        progress!(format, "3. Unstaking {} ETH...", format_ether(amount));
+        let input: Bytes = StakePool::unstakeCall { amount }.abi_encode().into();
+        if self.dry_run.enabled {
+            let simulation = simulate(
+                &provider,
+                wallet_address,
+                stake_pool,
+                input,
+                "unstake",
+                "StakeUnstaked",
+                format,
+            )
+            .await?;
+            return finish_dry_run(stake_pool, &[simulation], format);
+        }
+        let receipt = send_to(&provider, wallet_address, stake_pool, input, &fees, format).await?;
+        progress!(
+            format,
+            "   Transaction confirmed, block number: {}",
+            receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
+        );
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
+            "   Transaction cost: {} ETH",
+            format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
+        );
+
+        // Check unstake event
+        let mut found = None;
+        for log in receipt.logs() {
+            if let Ok(event) = StakePool::StakeUnstaked::decode_log(&log.inner) {
+                progress!(format, "   Unstake successful!");
+                progress!(format, "   - StakePool: {}", event.pool);
+                progress!(format, "   - Amount: {} ETH", format_ether(event.amount));
+                found = Some((
+                    event.lockedUntil,
+                    serde_json::json!({
+                        "pool": format!("{}", event.pool),
+                        "amount": format_ether(event.amount),
+                        "locked_until": event.lockedUntil,
+                    }),
+                ));
+                break;
+            }
+        }
+        let Some((withdrawable_at, event)) = found else {
+            progress!(format, "   Unstake event not found\n");
+            return Err(anyhow::anyhow!("Failed to find StakeUnstaked event"));
+        };
+        if withdrawable_at > now_micros {
+            progress!(
+                format,
+                "   Withdrawable in {}, then run `validator withdraw`\n",
+                format_duration((withdrawable_at - now_micros) / 1_000_000)
+            );
+        } else {
+            progress!(format, "   Withdrawable now with `validator withdraw`\n");
+        }
+        format.emit(&UnstakeResult {
+            stake_pool: format!("{stake_pool}"),
+            amount: format_ether(amount),
+            transaction: TxSummary::new(&receipt).with_event(event),
+            withdrawable_at_micros: withdrawable_at,
+        })
+    }
+}
@@ -0,0 +1,196 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_sol_types::{SolCall, SolEvent};
+use clap::Parser;
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::{
+    command::Executable,
+    contract::{staking_address, StakePool, Staking},
+    fees::FeeArgs,
+    i18n::{CatalogError, MissingOption, Msg},
+    output::{progress, OutputFormat, TxSummary},
+    signer::SignerArgs,
+    transaction::{finish_dry_run, send_to, simulate, DryRunArgs},
+    util::format_ether,
+    validator::{
+        status::{format_duration, view},
+        unstake::lockup,
+    },
+};
+
+#[derive(Debug, Parser)]
+pub struct WithdrawCommand {
+    /// RPC URL for gravity node
+    #[clap(long, env = "GRAVITY_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// StakePool address to withdraw from
+    #[clap(long)]
+    pub stake_pool: String,
+
+    /// Address receiving the funds (default: the pool owner)
+    #[clap(long)]
+    pub recipient: Option<String>,
+
+    #[clap(flatten)]
+    pub dry_run: DryRunArgs,
+
+    #[clap(flatten)]
+    pub fees: FeeArgs,
+
+    #[clap(flatten)]
+    pub signer: SignerArgs,
+}
+
+/// Result of `validator withdraw` with `--output json`.
+#[derive(Debug, Serialize)]
+struct WithdrawResult {
+    stake_pool: String,
+    recipient: String,
+    /// Amount withdrawn in ETH.
+    amount: String,
+    transaction: TxSummary,
+    /// Unstaked amount in ETH still locked after this withdrawal.
+    still_pending: String,
+}
+
+impl Executable for WithdrawCommand {
+    fn execute(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(self.execute_async(format))
+    }
+}
+
+impl WithdrawCommand {
+    async fn execute_async(self, format: OutputFormat) -> Result<(), anyhow::Error> {
+        let rpc_url =
+            self.rpc_url.ok_or_else(|| MissingOption::new("rpc-url", "GRAVITY_RPC_URL"))?;
+        let stake_pool = Address::from_str(&self.stake_pool).map_err(|e| {
+            anyhow::anyhow!("Invalid stake pool address '{}': {e}", self.stake_pool)
+        })?;
+
+        if self.dry_run.enabled {
+            progress!(format, "Dry run: the transaction is simulated, nothing is sent\n");
+        }
+
+        // 1. Initialize Provider and Wallet
+        progress!(format, "1. Initializing connection...");
+        progress!(format, "   RPC URL: {rpc_url}");
+        let resolved = self.signer.resolve().await?;
+        let wallet_address = resolved.address;
+        progress!(format, "   Wallet address: {wallet_address:?}");
+        let provider =
+            ProviderBuilder::new().wallet(resolved.wallet).connect_http(rpc_url.parse()?);
+        let chain_id = provider.get_chain_id().await?;
+        progress!(format, "   Chain ID: {chain_id}");
+        let fees = self.fees.resolve(&provider).await?;
+        progress!(format, "   Fees: {}\n", fees.describe());
+
+        // 2. Check there is something to withdraw
+        progress!(format, "2. Checking StakePool: {stake_pool:?}");
+        if !view(&provider, staking_address(), Staking::isPoolCall { pool: stake_pool }).await? {
+            return Err(CatalogError::new(Msg::NotStakePool, &[]).into());
+        }
+        let recipient = match &self.recipient {
+            Some(recipient) => Address::from_str(recipient)
+                .map_err(|e| anyhow::anyhow!("Invalid recipient address '{recipient}': {e}"))?,
+            None => {
+                view(&provider, staking_address(), Staking::getPoolOwnerCall { pool: stake_pool })
+                    .await?
+            }
+        };
+        progress!(format, "   Recipient: {recipient:?}");
+        let claimable = view(&provider, stake_pool, StakePool::getClaimableAmountCall {}).await?;
+        let pending = view(&provider, stake_pool, StakePool::getPendingWithdrawalCall {}).await?;
+        progress!(format, "   Withdrawable now: {} ETH", format_ether(claimable));
+        progress!(format, "   Still locked: {} ETH", format_ether(pending));
+        if claimable.is_zero() {
+            if pending.is_zero() {
+                return Err(anyhow::anyhow!(
+                    "Nothing to withdraw: no stake was unstaked, run `validator unstake` first"
+                ));
+            }
+            let (locked_until, now_micros) = lockup(&provider, stake_pool).await?;
+            return Err(anyhow::anyhow!(
+                "Lockup hasn't expired: {} ETH is locked for another {} (until {locked_until} \
+                 microseconds since the Unix epoch)",
+                format_ether(pending),
+                format_duration(locked_until.saturating_sub(now_micros) / 1_000_000)
+            ));
+        }
+        progress!(format);
+
+        // 3. Withdraw
+        progress!(format, "3. Withdrawing {} ETH to {recipient:?}...", format_ether(claimable));
+        let input: Bytes = StakePool::withdrawAvailableCall { recipient }.abi_encode().into();
+        if self.dry_run.enabled {
+            let simulation = simulate(
+                &provider,
+                wallet_address,
+                stake_pool,
+                input,
+                "withdrawAvailable",
+                "StakeWithdrawn",
+                format,
+            )
+            .await?;
+            return finish_dry_run(stake_pool, &[simulation], format);
+        }
+        let receipt = send_to(&provider, wallet_address, stake_pool, input, &fees, format).await?;
+        progress!(
+            format,
+            "   Transaction confirmed, block number: {}",
+            receipt.block_number.ok_or(anyhow::anyhow!("Failed to get block number"))?
+        );
+        progress!(format, "   Gas used: {}", receipt.gas_used);
+        progress!(
+            format,
+            "   Transaction cost: {} ETH",
+            format_ether(U256::from(receipt.effective_gas_price) * U256::from(receipt.gas_used))
+        );
+
+        // Check withdrawal event
+        let mut found = None;
+        for log in receipt.logs() {
+            if let Ok(event) = StakePool::StakeWithdrawn::decode_log(&log.inner) {
+                progress!(format, "   Withdrawal successful!");
+                progress!(format, "   - StakePool: {}", event.pool);
+                progress!(format, "   - Recipient: {}", event.recipient);
+                progress!(format, "   - Amount: {} ETH", format_ether(event.amount));
+                found = Some((
+                    event.amount,
+                    serde_json::json!({
+                        "pool": format!("{}", event.pool),
+                        "recipient": format!("{}", event.recipient),
+                        "amount": format_ether(event.amount),
+                    }),
+                ));
+                break;
+            }
+        }
+        let Some((amount, event)) = found else {
+            progress!(format, "   Withdrawal event not found\n");
+            return Err(anyhow::anyhow!("Failed to find StakeWithdrawn event"));
+        };
+        let still_pending =
+            view(&provider, stake_pool, StakePool::getPendingWithdrawalCall {}).await?;
+        if still_pending.is_zero() {
+            progress!(format);
+        } else {
+            progress!(
+                format,
+                "   {} ETH is still locked; withdraw it once its lockup expires\n",
+                format_ether(still_pending)
+            );
+        }
+        format.emit(&WithdrawResult {
+            stake_pool: format!("{stake_pool}"),
+            recipient: format!("{recipient}"),
+            amount: format_ether(amount),
+            transaction: TxSummary::new(&receipt).with_event(event),
+            still_pending: format_ether(still_pending),
+        })
+    }
+}
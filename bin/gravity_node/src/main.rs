@@ -4,6 +4,7 @@ use api::{
     check_bootstrap_config,
     config_storage::ConfigStorageWrapper,
     consensus_api::{ConsensusEngine, ConsensusEngineArgs},
+    load_api_config,
 };
 use consensus::mock_consensus::mock::MockConsensus;
 use gaptos::{
@@ -45,6 +46,7 @@ mod cli;
 mod consensus;
 mod mempool;
 mod node_metrics;
+mod priority_lane;
 pub mod relayer;
 mod reth_cli;
 mod reth_coordinator;
@@ -163,6 +165,7 @@ fn run_reth(
                             panic!("Block not found for block number {recover_block_number}")
                         });
                     let pool = handle.node.pool;
+                    tokio::spawn(mempool::publish_pool_events(pool.clone()));
 
                     let storage = BlockViewStorage::new(provider.clone());
                     let pipeline_api_v2 = reth_pipe_exec_layer_ext_v2::new_pipe_exec_layer_api(
@@ -302,6 +305,7 @@ fn main() {
     node_metrics::register_binary_info_metrics();
     let relayer_config_path = cli.gravity_node_config.relayer_config_path.clone();
     let gcei_config = check_bootstrap_config(cli.gravity_node_config.node_config_path.clone());
+    let api_config = load_api_config(cli.gravity_node_config.api_config_path.clone());
 
     let (shutdown_tx, _shutdown_rx) = broadcast::channel(1);
     let shutdown_tx_clone = shutdown_tx.clone();
@@ -381,6 +385,8 @@ fn main() {
                         config_storage: Some(Arc::new(ConfigStorageWrapper::new(Arc::new(
                             RethCliConfigStorage::new(client),
                         )))),
+                        api_routes: None,
+                        api_config,
                     },
                     pool,
                 )
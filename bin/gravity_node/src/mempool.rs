@@ -1,15 +1,17 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::Arc,
     time::{Duration, Instant},
 };
 
-use crate::{reth_cli::TxnCache, RethTransactionPool};
+use crate::{priority_lane::PriorityLane, reth_cli::TxnCache, RethTransactionPool};
 use alloy_consensus::{transaction::SignerRecoverable, Transaction};
 use alloy_eips::{Decodable2718, Encodable2718};
-use alloy_primitives::Address;
+use alloy_primitives::{Address, TxHash};
+use api::mempool_feed::{MempoolEvent, MEMPOOL_FEED};
 use block_buffer_manager::TxPool;
 use dashmap::DashMap;
+use futures::StreamExt;
 use gaptos::api_types::{
     account::{ExternalAccountAddress, ExternalChainId},
     u256_define::TxnHash,
@@ -18,8 +20,8 @@ use gaptos::api_types::{
 use greth::{
     reth_primitives::{Recovered, TransactionSigned},
     reth_transaction_pool::{
-        error::PoolErrorKind, BestTransactions, EthPooledTransaction, PoolTransaction,
-        TransactionPool, ValidPoolTransaction,
+        error::PoolErrorKind, BestTransactions, EthPooledTransaction, FullTransactionEvent,
+        PoolTransaction, TransactionPool, ValidPoolTransaction,
     },
 };
 
@@ -59,6 +61,9 @@ struct CachedBest {
     /// Track the last yielded nonce per sender to enforce nonce ordering.
     /// Cleared when the iterator is recreated (on TTL expiry).
     last_nonces: HashMap<Address, u64>,
+    /// Senders whose next transaction was held back for the priority lane and consumed from
+    /// the iterator without being proposed. Their later nonces wait for a fresh iterator.
+    held_back: HashSet<Address>,
 }
 
 impl CachedBest {
@@ -67,6 +72,7 @@ impl CachedBest {
             best_txns: None,
             created_at: Instant::now() - cache_ttl() - Duration::from_millis(1), // Start expired
             last_nonces: HashMap::new(),
+            held_back: HashSet::new(),
         }
     }
 
@@ -85,6 +91,7 @@ pub struct Mempool {
     runtime: Option<tokio::runtime::Runtime>,
     enable_broadcast: bool,
     chain_id: u64,
+    priority_lane: PriorityLane,
 }
 
 impl Drop for Mempool {
@@ -149,6 +156,7 @@ impl Mempool {
             runtime: Some(runtime),
             enable_broadcast,
             chain_id,
+            priority_lane: PriorityLane::from_env(),
         }
     }
 
@@ -193,6 +201,39 @@ fn to_verified_txn_from_reth_txn(
     )
 }
 
+/// Forwards the pool's transaction events to the API's `/ws/mempool` feed. Runs until the pool
+/// is dropped.
+pub async fn publish_pool_events(pool: RethTransactionPool) {
+    let mut events = pool.all_transactions_event_listener();
+    while let Some(event) = events.next().await {
+        if !MEMPOOL_FEED.has_subscribers() {
+            continue;
+        }
+        let event = match event {
+            FullTransactionEvent::Pending(hash) => admitted_event(&pool, hash, "pending"),
+            FullTransactionEvent::Queued(hash, ..) => admitted_event(&pool, hash, "queued"),
+            FullTransactionEvent::Replaced { transaction, replaced_by } => {
+                MempoolEvent::dropped(transaction.hash().0, "replaced")
+                    .with_sender(transaction.sender().into_array(), transaction.nonce())
+                    .with_replaced_by(replaced_by.0)
+            }
+            FullTransactionEvent::Discarded(hash) => MempoolEvent::dropped(hash.0, "discarded"),
+            FullTransactionEvent::Invalid(hash) => MempoolEvent::dropped(hash.0, "invalid"),
+            // Inclusion is reported by the consensus endpoints, propagation is not of interest.
+            _ => continue,
+        };
+        MEMPOOL_FEED.publish(event);
+    }
+}
+
+fn admitted_event(pool: &RethTransactionPool, hash: TxHash, reason: &str) -> MempoolEvent {
+    let event = MempoolEvent::admitted(hash.0, reason);
+    match pool.get(&hash) {
+        Some(txn) => event.with_sender(txn.sender().into_array(), txn.nonce()),
+        None => event,
+    }
+}
+
 impl TxPool for Mempool {
     fn best_txns(
         &self,
@@ -206,12 +247,14 @@ impl TxPool for Mempool {
                 best_txns: Some(self.pool.best_transactions()),
                 created_at: Instant::now(),
                 last_nonces: HashMap::new(),
+                held_back: HashSet::new(),
             };
         }
         let txn_cache = self.txn_cache.clone();
         let chain_id = self.chain_id;
         // Take last_nonces out to avoid borrow conflict with best_txns iterator
         let mut last_nonces = std::mem::take(&mut best_txns.last_nonces);
+        let mut held_back = std::mem::take(&mut best_txns.held_back);
         // Drive the cached iterator by hand so the byte budget is checked *before*
         // pulling the next item. An adapter like `take_while` would consume (and
         // record the nonce of) one extra txn past the budget, dropping it until the
@@ -219,6 +262,10 @@ impl TxPool for Mempool {
         let iter = best_txns.best_txns.as_mut().unwrap();
         let mut result: Vec<VerifiedTxn> = Vec::new();
         let mut total_bytes: u64 = 0;
+        let mut fill = self.priority_lane.fill(limit, max_bytes);
+        // User transactions held back while the user share is full, in pool order. Later
+        // transactions of their senders are skipped, so each one is next in its sender's order.
+        let mut deferred = Vec::new();
         while result.len() < limit && total_bytes < max_bytes {
             let pool_txn = match iter.next() {
                 Some(txn) => txn,
@@ -226,6 +273,9 @@ impl TxPool for Mempool {
             };
             let sender = pool_txn.sender();
             let nonce = pool_txn.nonce();
+            if held_back.contains(&sender) {
+                continue;
+            }
 
             // Enforce nonce ordering: skip transactions that are not consecutive
             if let Some(&last) = last_nonces.get(&sender) {
@@ -253,25 +303,53 @@ impl TxPool for Mempool {
             }
 
             let verified_txn = to_verified_txn(pool_txn.clone(), chain_id);
+            let txn_bytes = verified_txn.bytes().len() as u64;
+            if !fill.admit(self.priority_lane.lane_of(pool_txn.transaction.to()), txn_bytes) {
+                held_back.insert(sender);
+                deferred.push((verified_txn, pool_txn));
+                if fill.exhausted() {
+                    break;
+                }
+                continue;
+            }
             let tx_hash: [u8; 32] = pool_txn.transaction.transaction().inner().hash().0;
             // max_bytes is a prefetch hint: it caps how far we drain the cached
             // iterator. It measures payload bytes, which under-count the fully
             // serialized size the caller (get_batch_inner) enforces authoritatively,
             // so we keep the txn that crosses the budget and err toward extra
             // candidates rather than starving the block.
-            total_bytes += verified_txn.bytes().len() as u64;
+            total_bytes += txn_bytes;
             // Record the insertion time so the background sweeper can evict entries
             // that stay uncommitted past the TTL.
             txn_cache.insert(tx_hash, (Instant::now(), pool_txn));
             result.push(verified_txn);
         }
+        // The pool ran out of system transactions before the reserved share did, so deferred
+        // user transactions take up what is left. They come last, so the caller's
+        // authoritative byte limit trims them before anything admitted above.
+        for (verified_txn, pool_txn) in deferred {
+            if result.len() >= limit || total_bytes >= max_bytes {
+                break;
+            }
+            fill.backfill();
+            held_back.remove(&pool_txn.sender());
+            total_bytes += verified_txn.bytes().len() as u64;
+            let tx_hash: [u8; 32] = pool_txn.transaction.transaction().inner().hash().0;
+            txn_cache.insert(tx_hash, (Instant::now(), pool_txn));
+            result.push(verified_txn);
+        }
+        fill.record_metrics();
         // Put last_nonces back
         best_txns.last_nonces = last_nonces;
+        best_txns.held_back = held_back;
+        // Deferred transactions left out were consumed from the cached iterator; they come
+        // back with the next iterator, at the latest when the cache expires.
         if result.is_empty() {
             *best_txns = CachedBest {
                 best_txns: None,
                 created_at: Instant::now(),
                 last_nonces: HashMap::new(),
+                held_back: HashSet::new(),
             };
         }
         Box::new(result.into_iter())
@@ -0,0 +1,245 @@
+//! Priority lane for system transactions in proposals.
+//!
+//! Without it, a flood of user transactions paying higher tips pushes calls to the system
+//! contracts (validator operations, DKG, governance and upgrades) out of every block. Each pull
+//! from the pool reserves `GRAVITY_PRIORITY_LANE_FRACTION` of its transaction count and bytes for
+//! transactions sent to a system contract, or to an address listed in
+//! `GRAVITY_PRIORITY_LANE_ADDRESSES`. User transactions stop at the rest of the budget and only
+//! take up the reserved share when no system transaction is waiting to fill it. A pull is a whole
+//! block with direct mempool payloads and one batch with quorum store.
+//!
+//! The lane is off unless `GRAVITY_PRIORITY_LANE_FRACTION` is set. A pull stops looking for
+//! system transactions once the reserved share is full or it has held back `MAX_DEFERRED` user
+//! transactions, so a pool without system transactions costs little more than an unreserved one.
+
+use std::collections::HashSet;
+
+use alloy_primitives::Address;
+use gaptos::aptos_metrics_core::{
+    register_histogram, register_int_counter, register_int_counter_vec, Histogram, IntCounter,
+    IntCounterVec,
+};
+use once_cell::sync::Lazy;
+
+/// Share of each pull reserved for system transactions, from 0 (disabled, the default) to 1.
+pub(crate) const PRIORITY_LANE_FRACTION_ENV: &str = "GRAVITY_PRIORITY_LANE_FRACTION";
+/// Comma-separated addresses whose transactions also go in the lane, e.g. a governance timelock.
+pub(crate) const PRIORITY_LANE_ADDRESSES_ENV: &str = "GRAVITY_PRIORITY_LANE_ADDRESSES";
+const DEFAULT_FRACTION: f64 = 0.0;
+/// Most user transactions one pull holds back while looking for system transactions.
+const MAX_DEFERRED: usize = 128;
+/// System contracts are deployed at `0x00000000000000000000000000000001625Fxxxx`.
+const SYSTEM_CONTRACT_PREFIX: [u8; 18] =
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x62, 0x5F];
+
+static PRIORITY_LANE_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "gravity_priority_lane_txns_total",
+        "Number of transactions pulled for proposals, by lane",
+        &["lane"]
+    )
+    .unwrap()
+});
+
+static PRIORITY_LANE_DEFERRED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "gravity_priority_lane_deferred_total",
+        "Number of user transactions held back to keep the system lane's share free"
+    )
+    .unwrap()
+});
+
+static PRIORITY_LANE_UTILIZATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "gravity_priority_lane_utilization",
+        "Fraction of the reserved system lane filled by system transactions in each pull",
+        vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Lane {
+    System,
+    User,
+}
+
+pub(crate) struct PriorityLane {
+    fraction: f64,
+    addresses: HashSet<Address>,
+}
+
+impl PriorityLane {
+    pub(crate) fn new(fraction: f64, addresses: HashSet<Address>) -> Self {
+        Self { fraction: fraction.clamp(0.0, 1.0), addresses }
+    }
+
+    pub(crate) fn from_env() -> Self {
+        let fraction = match std::env::var(PRIORITY_LANE_FRACTION_ENV) {
+            Ok(value) => value.parse::<f64>().unwrap_or_else(|_| {
+                tracing::warn!("Ignoring invalid {PRIORITY_LANE_FRACTION_ENV}: {value}");
+                DEFAULT_FRACTION
+            }),
+            Err(_) => DEFAULT_FRACTION,
+        };
+        let addresses = std::env::var(PRIORITY_LANE_ADDRESSES_ENV)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| match s.parse::<Address>() {
+                Ok(address) => Some(address),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid {PRIORITY_LANE_ADDRESSES_ENV} entry {s}: {e}");
+                    None
+                }
+            })
+            .collect();
+        let lane = Self::new(fraction, addresses);
+        tracing::info!(
+            "Priority lane reserves {:.0}% of each pull for system transactions",
+            lane.fraction * 100.0
+        );
+        lane
+    }
+
+    pub(crate) fn lane_of(&self, to: Option<Address>) -> Lane {
+        match to {
+            Some(to)
+                if to.as_slice().starts_with(&SYSTEM_CONTRACT_PREFIX) ||
+                    self.addresses.contains(&to) =>
+            {
+                Lane::System
+            }
+            _ => Lane::User,
+        }
+    }
+
+    /// Starts accounting for a pull of at most `limit` transactions and `max_bytes`.
+    pub(crate) fn fill(&self, limit: usize, max_bytes: u64) -> LaneFill {
+        let reserved_txns = (limit as f64 * self.fraction).ceil() as usize;
+        let reserved_bytes = (max_bytes as f64 * self.fraction).ceil() as u64;
+        LaneFill {
+            reserved_txns,
+            user_txn_limit: limit.saturating_sub(reserved_txns),
+            user_byte_limit: max_bytes.saturating_sub(reserved_bytes),
+            system_txns: 0,
+            user_txns: 0,
+            user_bytes: 0,
+            deferred: 0,
+        }
+    }
+}
+
+/// What one pull has taken from each lane.
+#[derive(Debug)]
+pub(crate) struct LaneFill {
+    reserved_txns: usize,
+    user_txn_limit: usize,
+    user_byte_limit: u64,
+    system_txns: usize,
+    user_txns: usize,
+    user_bytes: u64,
+    deferred: usize,
+}
+
+impl LaneFill {
+    /// Whether a `lane` transaction of `bytes` fits in its share, counting it if so. The pull's
+    /// overall limits are the caller's to enforce.
+    pub(crate) fn admit(&mut self, lane: Lane, bytes: u64) -> bool {
+        match lane {
+            Lane::System => {
+                self.system_txns += 1;
+                true
+            }
+            Lane::User
+                if self.user_txns < self.user_txn_limit &&
+                    self.user_bytes < self.user_byte_limit =>
+            {
+                self.user_txns += 1;
+                self.user_bytes += bytes;
+                true
+            }
+            Lane::User => {
+                self.deferred += 1;
+                false
+            }
+        }
+    }
+
+    /// Whether scanning on could only hold back more user transactions: the user share is full,
+    /// and either the reserved share is full too or `MAX_DEFERRED` user transactions were held
+    /// back without the system transactions to fill it.
+    pub(crate) fn exhausted(&self) -> bool {
+        self.deferred > 0 &&
+            (self.system_txns >= self.reserved_txns || self.deferred >= MAX_DEFERRED)
+    }
+
+    /// Counts a deferred user transaction that made it in after all, once the system lane was
+    /// known to have room to spare.
+    pub(crate) fn backfill(&mut self) {
+        self.deferred -= 1;
+        self.user_txns += 1;
+    }
+
+    pub(crate) fn record_metrics(&self) {
+        PRIORITY_LANE_TXNS.with_label_values(&["system"]).inc_by(self.system_txns as u64);
+        PRIORITY_LANE_TXNS.with_label_values(&["user"]).inc_by(self.user_txns as u64);
+        PRIORITY_LANE_DEFERRED.inc_by(self.deferred as u64);
+        if self.reserved_txns > 0 {
+            PRIORITY_LANE_UTILIZATION
+                .observe((self.system_txns as f64 / self.reserved_txns as f64).min(1.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn reserves_share_for_system_transactions() {
+        let timelock = address!("00000000000000000000000000000000000000aa");
+        let lane = PriorityLane::new(0.25, HashSet::from([timelock]));
+        assert_eq!(
+            lane.lane_of(Some(address!("00000000000000000000000000000001625F2000"))),
+            Lane::System
+        );
+        assert_eq!(lane.lane_of(Some(timelock)), Lane::System);
+        assert_eq!(
+            lane.lane_of(Some(address!("00000000000000000000000000000002625F2000"))),
+            Lane::User
+        );
+        assert_eq!(lane.lane_of(None), Lane::User);
+
+        // 3 of 4 slots go to users, the last one is held for system transactions.
+        let mut fill = lane.fill(4, u64::MAX);
+        assert!((0..3).all(|_| fill.admit(Lane::User, 100)));
+        assert!(!fill.admit(Lane::User, 100));
+        assert!(fill.admit(Lane::System, 100));
+        assert_eq!((fill.user_txns, fill.system_txns, fill.deferred), (3, 1, 1));
+        // With the reserved slot taken, nothing more can be admitted from the user lane.
+        assert!(fill.exhausted());
+
+        // The byte share caps users as well.
+        let mut fill = lane.fill(100, 1000);
+        assert!(fill.admit(Lane::User, 800));
+        assert!(!fill.admit(Lane::User, 10));
+
+        let mut fill = PriorityLane::new(0.0, HashSet::new()).fill(2, u64::MAX);
+        assert!(fill.admit(Lane::User, 1) && fill.admit(Lane::User, 1));
+    }
+
+    #[test]
+    fn stops_looking_for_system_transactions_after_max_deferred() {
+        let mut fill = PriorityLane::new(0.5, HashSet::new()).fill(1000, u64::MAX);
+        assert!((0..500).all(|_| fill.admit(Lane::User, 1)));
+        for _ in 0..MAX_DEFERRED - 1 {
+            assert!(!fill.admit(Lane::User, 1));
+            assert!(!fill.exhausted());
+        }
+        assert!(!fill.admit(Lane::User, 1));
+        assert!(fill.exhausted());
+    }
+}
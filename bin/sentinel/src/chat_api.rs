@@ -0,0 +1,177 @@
+//! Bot APIs of Feishu and Slack, used to thread alert follow-ups.
+//!
+//! Incoming webhooks can only post top-level messages and don't say which message they
+//! created, so replies need an app (Feishu) or bot token (Slack) instead.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const FEISHU_API: &str = "https://open.feishu.cn/open-apis";
+const SLACK_POST_MESSAGE: &str = "https://slack.com/api/chat.postMessage";
+
+/// Tokens are refreshed this long before Feishu expires them.
+const FEISHU_TOKEN_MARGIN: Duration = Duration::from_secs(300);
+
+#[derive(Deserialize)]
+struct FeishuToken {
+    code: i64,
+    #[serde(default)]
+    msg: String,
+    tenant_access_token: Option<String>,
+    #[serde(default)]
+    expire: u64,
+}
+
+#[derive(Deserialize)]
+struct FeishuResponse {
+    code: i64,
+    #[serde(default)]
+    msg: String,
+    data: Option<FeishuMessage>,
+}
+
+#[derive(Deserialize)]
+struct FeishuMessage {
+    message_id: String,
+}
+
+/// Body of a Feishu message.
+pub enum FeishuContent {
+    Text(String),
+    /// An interactive card, see [`crate::feishu_card`].
+    Card(Value),
+}
+
+impl FeishuContent {
+    fn msg_type(&self) -> &'static str {
+        match self {
+            FeishuContent::Text(_) => "text",
+            FeishuContent::Card(_) => "interactive",
+        }
+    }
+
+    /// The `content` of the message APIs, a JSON document in a string.
+    fn content(&self) -> String {
+        match self {
+            FeishuContent::Text(text) => json!({ "text": text }).to_string(),
+            FeishuContent::Card(card) => card.to_string(),
+        }
+    }
+
+    /// Payload of an incoming webhook.
+    pub fn webhook_payload(&self) -> Value {
+        match self {
+            FeishuContent::Text(text) => json!({ "msg_type": "text", "content": { "text": text } }),
+            FeishuContent::Card(card) => json!({ "msg_type": "interactive", "card": card }),
+        }
+    }
+}
+
+/// A Feishu app that is a member of the alert chats.
+pub struct FeishuApp {
+    client: Client,
+    app_id: String,
+    app_secret: String,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl FeishuApp {
+    pub fn new(client: Client, app_id: String, app_secret: String) -> Self {
+        Self { client, app_id, app_secret, token: Mutex::new(None) }
+    }
+
+    async fn token(&self) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref() {
+            if Instant::now() < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+        let resp: FeishuToken = self
+            .client
+            .post(format!("{FEISHU_API}/auth/v3/tenant_access_token/internal"))
+            .json(&json!({ "app_id": self.app_id, "app_secret": self.app_secret }))
+            .send()
+            .await?
+            .json()
+            .await?;
+        anyhow::ensure!(resp.code == 0, "Feishu token request failed: {} {}", resp.code, resp.msg);
+        let token = resp.tenant_access_token.context("Feishu returned no tenant_access_token")?;
+        let lifetime = Duration::from_secs(resp.expire).saturating_sub(FEISHU_TOKEN_MARGIN);
+        *cached = Some((token.clone(), Instant::now() + lifetime));
+        Ok(token)
+    }
+
+    /// Posts `message` to `chat_id`, as a thread reply when `reply_to` is set, and returns the
+    /// id of the new message.
+    pub async fn send(
+        &self,
+        chat_id: &str,
+        message: &FeishuContent,
+        reply_to: Option<&str>,
+    ) -> Result<String> {
+        let (msg_type, content) = (message.msg_type(), message.content());
+        let request = match reply_to {
+            Some(message_id) => {
+                self.client.post(format!("{FEISHU_API}/im/v1/messages/{message_id}/reply")).json(
+                    &json!({ "msg_type": msg_type, "content": content, "reply_in_thread": true }),
+                )
+            }
+            None => self
+                .client
+                .post(format!("{FEISHU_API}/im/v1/messages?receive_id_type=chat_id"))
+                .json(&json!({ "receive_id": chat_id, "msg_type": msg_type, "content": content })),
+        };
+        let resp: FeishuResponse =
+            request.bearer_auth(self.token().await?).send().await?.json().await?;
+        anyhow::ensure!(resp.code == 0, "Feishu message failed: {} {}", resp.code, resp.msg);
+        Ok(resp.data.context("Feishu returned no message")?.message_id)
+    }
+}
+
+#[derive(Deserialize)]
+struct SlackResponse {
+    ok: bool,
+    error: Option<String>,
+    ts: Option<String>,
+}
+
+/// A Slack bot invited to the alert channels.
+pub struct SlackBot {
+    client: Client,
+    token: String,
+}
+
+impl SlackBot {
+    pub fn new(client: Client, token: String) -> Self {
+        Self { client, token }
+    }
+
+    /// Posts `text` to `channel`, in the thread of `thread_ts` when set, and returns the
+    /// timestamp identifying the new message.
+    pub async fn send(&self, channel: &str, text: &str, thread_ts: Option<&str>) -> Result<String> {
+        let mut payload = json!({ "channel": channel, "text": text });
+        if let Some(thread_ts) = thread_ts {
+            payload["thread_ts"] = json!(thread_ts);
+        }
+        let resp: SlackResponse = self
+            .client
+            .post(SLACK_POST_MESSAGE)
+            .bearer_auth(&self.token)
+            .json(&payload)
+            .send()
+            .await?
+            .json()
+            .await?;
+        anyhow::ensure!(
+            resp.ok,
+            "Slack chat.postMessage failed: {}",
+            resp.error.as_deref().unwrap_or("unknown error")
+        );
+        resp.ts.context("Slack returned no message ts")
+    }
+}
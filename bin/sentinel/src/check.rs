@@ -0,0 +1,382 @@
+use crate::{
+    analyzer::Analyzer,
+    config::{Config, Priority},
+    whitelist::{CheckResult, Whitelist},
+};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, FixedOffset};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// Arguments of `sentinel check --config <config.toml> [--replay <logfile>]`.
+pub struct CheckArgs {
+    config: String,
+    replay: Option<String>,
+}
+
+impl CheckArgs {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut config = None;
+        let mut replay = None;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value =
+                |flag: &str| iter.next().cloned().ok_or_else(|| anyhow!("{flag} requires a value"));
+            match arg.as_str() {
+                "--config" => config = Some(value("--config")?),
+                "--replay" => replay = Some(value("--replay")?),
+                other => bail!("Unknown argument '{other}'"),
+            }
+        }
+        let config = config.ok_or_else(|| anyhow!("--config is required"))?;
+        Ok(Self { config, replay })
+    }
+}
+
+/// Validates the config without starting any monitor or sending any webhook, then optionally
+/// replays a log file through the error pattern and whitelist rules.
+pub fn run(args: CheckArgs) -> Result<()> {
+    println!("Checking config {}", args.config);
+    let config = Config::load(&args.config).context("Failed to load config")?;
+
+    let mut problems = Vec::new();
+    validate(&config, &mut problems);
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("  ERROR: {problem}");
+        }
+        bail!("{} configuration problem(s) found", problems.len());
+    }
+    println!("Config OK");
+
+    if let Some(replay) = args.replay {
+        replay_log(&config, Path::new(&replay))?;
+    }
+    Ok(())
+}
+
+fn validate(config: &Config, problems: &mut Vec<String>) {
+    let webhooks = config.alerting.all_webhooks();
+    if webhooks.is_empty() {
+        problems.push("No webhooks configured".to_string());
+    }
+    for (kind, url) in webhooks {
+        if let Err(e) = reqwest::Url::parse(url) {
+            problems.push(format!("Invalid {kind} webhook URL '{url}': {e}"));
+        }
+    }
+    println!("  alerting: default priority {}", config.alerting.default_priority);
+    if let Some(threading) = &config.alerting.threading {
+        if threading.feishu_app_id.is_some() != threading.feishu_app_secret.is_some() {
+            problems.push(
+                "[alerting.threading] needs both feishu_app_id and feishu_app_secret".to_string(),
+            );
+        }
+        let feishu_app = threading.feishu_app_id.is_some() && threading.feishu_app_secret.is_some();
+        let slack_bot = threading.slack_bot_token.is_some();
+        // A priority without a chat falls back to its webhooks, but a bot with no chat at all
+        // is surely a mistake.
+        let priorities = config.alerting.priorities.values();
+        if feishu_app &&
+            threading.feishu_chat_id.is_none() &&
+            priorities.clone().all(|cfg| cfg.feishu_chat_id.is_none())
+        {
+            problems.push("Feishu app configured without any feishu_chat_id".to_string());
+        }
+        if slack_bot &&
+            threading.slack_channel.is_none() &&
+            priorities.clone().all(|cfg| cfg.slack_channel.is_none())
+        {
+            problems.push("Slack bot token configured without any slack_channel".to_string());
+        }
+        println!(
+            "  alerting: threaded via{}{}",
+            if feishu_app { " feishu" } else { "" },
+            if slack_bot { " slack" } else { "" }
+        );
+    }
+
+    if let Some(cards) = &config.alerting.feishu_cards {
+        let rule_urls =
+            cards.rules.iter().flat_map(|rule| [&rule.runbook_url, &rule.dashboard_url]);
+        for url in [&cards.ack_base_url, &cards.runbook_url, &cards.dashboard_url]
+            .into_iter()
+            .chain(rule_urls)
+            .flatten()
+        {
+            if let Err(e) = reqwest::Url::parse(url) {
+                problems.push(format!("Invalid URL '{url}' in [alerting.feishu_cards]: {e}"));
+            }
+        }
+        for rule in &cards.rules {
+            if let Err(e) = regex::Regex::new(&rule.pattern) {
+                problems.push(format!("Invalid card rule pattern '{}': {e}", rule.pattern));
+            }
+        }
+        let control_api = config.health.as_ref().is_some_and(|h| h.listen.is_some());
+        if cards.ack_base_url.is_some() && !control_api {
+            problems.push(
+                "[alerting.feishu_cards] ack_base_url needs the control API ([health] listen)"
+                    .to_string(),
+            );
+        }
+        println!("  alerting: feishu cards with {} rule(s)", cards.rules.len());
+    }
+
+    for probe in &config.probes {
+        match reqwest::Url::parse(&probe.url) {
+            Ok(_) => println!("  probe: {}", probe.url),
+            Err(e) => problems.push(format!("Invalid probe URL '{}': {e}", probe.url)),
+        }
+    }
+
+    for probe in &config.command_probes {
+        let Some(program) = probe.command.first() else {
+            problems.push("Command probe with an empty command".to_string());
+            continue;
+        };
+        if probe.timeout_seconds == 0 || probe.timeout_seconds > probe.check_interval_seconds {
+            problems.push(format!(
+                "Command probe '{program}': timeout_seconds must be 1..=check_interval_seconds ({})",
+                probe.check_interval_seconds
+            ));
+        } else if program.contains('/') && !Path::new(program).is_file() {
+            problems.push(format!("Command probe program '{program}' does not exist"));
+        } else {
+            println!("  command probe: {}", probe.command.join(" "));
+        }
+    }
+
+    if let Some(explorer) = &config.explorer_monitor {
+        match reqwest::Url::parse(&explorer.api_base) {
+            Ok(_) => println!("  explorer monitor: {}", explorer.api_base),
+            Err(e) => {
+                problems.push(format!("Invalid explorer api_base '{}': {e}", explorer.api_base))
+            }
+        }
+    }
+
+    if let Some(resource) = &config.resource_monitor {
+        if !Path::new(&resource.data_dir).is_dir() {
+            problems.push(format!(
+                "Resource monitor data_dir '{}' is not a directory",
+                resource.data_dir
+            ));
+        } else {
+            println!("  resource monitor: {}", resource.data_dir);
+        }
+    }
+
+    if let Some(network) = &config.network_monitor {
+        if !Path::new("/proc/net/dev").is_file() {
+            problems.push("Network monitor needs /proc/net/dev, which this host lacks".to_string());
+        } else if !(0.0..=100.0).contains(&network.saturation_threshold_percent) {
+            problems.push(format!(
+                "network_monitor.saturation_threshold_percent must be within 0..=100, got {}",
+                network.saturation_threshold_percent
+            ));
+        } else if network.trend_samples < 2 {
+            problems.push(format!(
+                "network_monitor.trend_samples must be at least 2, got {}",
+                network.trend_samples
+            ));
+        } else {
+            let interfaces = if network.interfaces.is_empty() {
+                "all interfaces".to_string()
+            } else {
+                network.interfaces.join(", ")
+            };
+            println!("  network monitor: {interfaces}");
+        }
+    }
+
+    if let Some(churn) = &config.validator_churn {
+        if let Err(e) = reqwest::Url::parse(&churn.api_base) {
+            problems.push(format!("Invalid validator_churn api_base '{}': {e}", churn.api_base));
+        } else if churn.epochs == 0 || churn.epochs > 100 {
+            problems.push(format!("validator_churn.epochs must be 1..=100, got {}", churn.epochs));
+        } else if !(0.0..=1.0).contains(&churn.max_turnover_ratio) {
+            problems.push(format!(
+                "validator_churn.max_turnover_ratio must be within 0..=1, got {}",
+                churn.max_turnover_ratio
+            ));
+        } else {
+            println!("  validator churn monitor: {}", churn.api_base);
+        }
+    }
+
+    if let Some(chain) = &config.chain_monitor {
+        for (name, url) in [
+            ("ethereum_rpc_url", &chain.ethereum_rpc_url),
+            ("gravity_rpc_url", &chain.gravity_rpc_url),
+        ] {
+            if let Err(e) = reqwest::Url::parse(url) {
+                problems.push(format!("Invalid chain_monitor.{name} '{url}': {e}"));
+            }
+        }
+        for (name, address) in [
+            ("gbridge_sender_address", &chain.gbridge_sender_address),
+            ("gravity_portal_address", &chain.gravity_portal_address),
+            ("gbridge_receiver_address", &chain.gbridge_receiver_address),
+            ("native_oracle_address", &chain.native_oracle_address),
+            ("owner_address", &chain.owner_address),
+            ("gtoken_address", &chain.gtoken_address),
+        ] {
+            if let Err(e) = alloy_primitives::Address::from_str(address) {
+                problems.push(format!("Invalid chain_monitor.{name} '{address}': {e}"));
+            }
+        }
+        println!("  chain monitor: {}", chain.gravity_rpc_url);
+    }
+
+    if let Some(health) = &config.health {
+        if let Some(listen) = &health.listen {
+            if let Err(e) = listen.parse::<std::net::SocketAddr>() {
+                problems.push(format!("Invalid health.listen '{listen}': {e}"));
+            }
+        }
+        for pattern in &health.optional {
+            if let Err(e) = glob::Pattern::new(pattern) {
+                problems.push(format!("Invalid health.optional pattern '{pattern}': {e}"));
+            }
+        }
+        if let Some(url) = &health.heartbeat_url {
+            if let Err(e) = reqwest::Url::parse(url) {
+                problems.push(format!("Invalid health.heartbeat_url '{url}': {e}"));
+            }
+        }
+        println!("  health export: {} optional pattern(s)", health.optional.len());
+    }
+
+    if let Some(storage) = &config.storage {
+        if storage.max_age_hours == 0 || storage.max_bytes == 0 {
+            problems
+                .push("storage.max_age_hours and storage.max_bytes must be positive".to_string());
+        } else if storage.compaction_interval_seconds == 0 {
+            problems.push("storage.compaction_interval_seconds must be positive".to_string());
+        } else {
+            println!(
+                "  storage: {} (max {}h, {} bytes per store)",
+                storage.dir, storage.max_age_hours, storage.max_bytes
+            );
+        }
+    }
+
+    match &config.monitoring {
+        Some(monitoring) => {
+            if let Err(e) = Analyzer::new(&monitoring.error_pattern) {
+                problems.push(format!("Invalid error_pattern '{}': {e}", monitoring.error_pattern));
+            }
+            for pattern in &monitoring.file_patterns {
+                if let Err(e) = glob::Pattern::new(pattern) {
+                    problems.push(format!("Invalid file pattern '{pattern}': {e}"));
+                }
+            }
+            if let Some(path) = &monitoring.whitelist_path {
+                println!("  loading whitelist from {path}");
+                if let Err(e) = Whitelist::load(path) {
+                    problems.push(format!("Failed to load whitelist '{path}': {e}"));
+                }
+            }
+            println!("  log monitoring: {} file pattern(s)", monitoring.file_patterns.len());
+        }
+        None => println!("  log monitoring: disabled (probes-only mode)"),
+    }
+}
+
+/// Feeds every line of `path` through the log monitoring rules and prints the alerts that would
+/// have been sent, including the ones the notifier's per-priority rate limit would have dropped.
+///
+/// Lines starting with an RFC 3339 timestamp are replayed on that clock, so whitelist frequency
+/// windows and `min_alert_interval` behave as they did live. Lines without one reuse the
+/// timestamp of the previous line.
+fn replay_log(config: &Config, path: &Path) -> Result<()> {
+    let monitoring = config
+        .monitoring
+        .as_ref()
+        .ok_or_else(|| anyhow!("--replay requires a [monitoring] section in the config"))?;
+    let analyzer = Analyzer::new(&monitoring.error_pattern)?;
+    let mut whitelist = match &monitoring.whitelist_path {
+        Some(whitelist_path) => {
+            Whitelist::load(whitelist_path).context("Failed to load whitelist")?
+        }
+        None => Whitelist::default(),
+    };
+    let min_alert_interval = Duration::from_secs(config.alerting.min_alert_interval);
+
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    println!("Replaying {}", path.display());
+
+    let start = Instant::now();
+    let mut first_ts: Option<DateTime<FixedOffset>> = None;
+    let mut now = start;
+    let mut last_alert: HashMap<Priority, Instant> = HashMap::new();
+    let (mut lines, mut errors, mut skipped) = (0u64, 0u64, 0u64);
+    let mut fired: HashMap<Priority, u64> = HashMap::new();
+    let mut rate_limited: HashMap<Priority, u64> = HashMap::new();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        lines += 1;
+        if let Some(ts) = leading_timestamp(&line) {
+            let first = *first_ts.get_or_insert(ts);
+            let offset = (ts - first).to_std().unwrap_or_default();
+            // Keep the replay clock monotonic when lines are slightly out of order
+            now = now.max(start + offset);
+        }
+
+        if !analyzer.is_error(&line) {
+            continue;
+        }
+        errors += 1;
+
+        let (message, priority) = match whitelist.check_at(&line, path, now) {
+            CheckResult::Skip => {
+                skipped += 1;
+                continue;
+            }
+            CheckResult::Alert { count, priority } => {
+                (format!("{line} [Frequency Alert: >{count}/5min]"), priority)
+            }
+            CheckResult::AlwaysAlert => (line.clone(), config.alerting.default_priority),
+        };
+
+        let line_no = index + 1;
+        if last_alert
+            .get(&priority)
+            .is_some_and(|last| now.duration_since(*last) < min_alert_interval)
+        {
+            *rate_limited.entry(priority).or_default() += 1;
+            println!("  line {line_no} [{priority}] (rate limited) {message}");
+            continue;
+        }
+        last_alert.insert(priority, now);
+        *fired.entry(priority).or_default() += 1;
+        println!("  line {line_no} [{priority}] {message}");
+    }
+
+    println!();
+    println!("Lines: {lines}  |  Errors: {errors}  |  Suppressed by whitelist: {skipped}");
+    for priority in [Priority::P0, Priority::P1, Priority::P2] {
+        println!(
+            "  {priority}: {} alert(s) would fire, {} rate limited",
+            fired.get(&priority).copied().unwrap_or_default(),
+            rate_limited.get(&priority).copied().unwrap_or_default(),
+        );
+    }
+    Ok(())
+}
+
+/// Parses an RFC 3339 timestamp from the first token of a log line, e.g.
+/// `2025-01-01T00:00:00.123Z` or `[2025-01-01T00:00:00Z]`.
+fn leading_timestamp(line: &str) -> Option<DateTime<FixedOffset>> {
+    let token = line.split_whitespace().next()?;
+    let token = token.trim_matches(|c| c == '[' || c == ']');
+    DateTime::parse_from_rfc3339(token).ok()
+}
@@ -0,0 +1,185 @@
+use crate::{config::CommandProbeConfig, health::HealthCheck, notifier::Notifier};
+use std::{process::Stdio, time::Duration};
+use tokio::{process::Command, time};
+
+/// PATH given to commands when sentinel itself runs without one.
+const DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// Result of one run of the command.
+enum Outcome {
+    Passed,
+    /// Why the run failed, with the captured output.
+    Failed {
+        reason: String,
+        output: String,
+    },
+}
+
+/// Keeps the last `max_bytes` of `bytes`, where the reason of a failure usually is.
+fn tail(bytes: &[u8], max_bytes: usize) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_end();
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut start = text.len() - max_bytes;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("...{}", &text[start..])
+}
+
+/// Runs a configured local command on a schedule, treating a non-zero exit (or a timeout) as a
+/// failure. The escape hatch for checks sentinel doesn't support natively.
+pub struct CommandProbe {
+    config: CommandProbeConfig,
+    notifier: Notifier,
+    health: HealthCheck,
+}
+
+impl CommandProbe {
+    pub fn new(config: CommandProbeConfig, notifier: Notifier, health: HealthCheck) -> Self {
+        Self { config, notifier, health }
+    }
+
+    /// Name of the probe's health check.
+    pub fn check_name(config: &CommandProbeConfig) -> String {
+        format!("command:{}", Self::label(config))
+    }
+
+    fn label(config: &CommandProbeConfig) -> &str {
+        config.tag.as_deref().or(config.command.first().map(String::as_str)).unwrap_or_default()
+    }
+
+    pub fn tag(&self) -> &str {
+        Self::label(&self.config)
+    }
+
+    /// The command with a sanitized environment: only PATH, the variables named in `pass_env`
+    /// and those set in `env`, so secrets in sentinel's environment don't leak into scripts.
+    fn command(&self) -> Option<Command> {
+        let (program, args) = self.config.command.split_first()?;
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .env_clear()
+            .env("PATH", std::env::var("PATH").unwrap_or_else(|_| DEFAULT_PATH.to_string()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // Dropping the child on timeout must not leave it running
+            .kill_on_drop(true);
+        for name in &self.config.pass_env {
+            if let Ok(value) = std::env::var(name) {
+                command.env(name, value);
+            }
+        }
+        command.envs(&self.config.env);
+        if let Some(dir) = &self.config.working_dir {
+            command.current_dir(dir);
+        }
+        Some(command)
+    }
+
+    async fn run_once(&self) -> Outcome {
+        let Some(mut command) = self.command() else {
+            return Outcome::Failed { reason: "empty command".to_string(), output: String::new() };
+        };
+        let child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Outcome::Failed {
+                    reason: format!("failed to start: {e}"),
+                    output: String::new(),
+                }
+            }
+        };
+        let timeout = Duration::from_secs(self.config.timeout_seconds);
+        let output = match time::timeout(timeout, child.wait_with_output()).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => {
+                return Outcome::Failed {
+                    reason: format!("failed to wait: {e}"),
+                    output: String::new(),
+                }
+            }
+            Err(_) => {
+                return Outcome::Failed {
+                    reason: format!("timed out after {}s", self.config.timeout_seconds),
+                    output: String::new(),
+                }
+            }
+        };
+        if output.status.success() {
+            return Outcome::Passed;
+        }
+        let max_bytes = self.config.max_output_bytes;
+        let sections = [("stdout", &output.stdout), ("stderr", &output.stderr)]
+            .into_iter()
+            .map(|(name, bytes)| (name, tail(bytes, max_bytes)))
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(name, text)| format!("[{name}]\n{text}"))
+            .collect::<Vec<_>>();
+        Outcome::Failed { reason: output.status.to_string(), output: sections.join("\n") }
+    }
+
+    pub async fn run(self) {
+        let alert_key = Self::check_name(&self.config);
+        let mut failures: u32 = 0;
+        let interval = Duration::from_secs(self.config.check_interval_seconds);
+        let mut timer = time::interval(interval);
+        // A slow command must not cause a burst of runs afterwards
+        timer.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+        // First tick completes immediately
+        timer.tick().await;
+
+        loop {
+            timer.tick().await;
+            let (reason, output) = match self.run_once().await {
+                Outcome::Passed => {
+                    self.health.pass();
+                    if failures > 0 {
+                        println!(
+                            "Command probe recovered: {} (after {} failures)",
+                            self.tag(),
+                            failures
+                        );
+                        let msg = format!(
+                            "Command probe recovered: {} (after {failures} failures)",
+                            self.tag()
+                        );
+                        if let Err(e) = self.notifier.resolved(&alert_key, &msg).await {
+                            eprintln!("Failed to send command probe recovery: {e:?}");
+                        }
+                        failures = 0;
+                    }
+                    continue;
+                }
+                Outcome::Failed { reason, output } => (reason, output),
+            };
+
+            failures += 1;
+            println!("Command probe failed: {} - {} (count: {})", self.tag(), reason, failures);
+            if failures < self.config.failure_threshold {
+                continue;
+            }
+            self.health.fail(format!("{failures} consecutive failures: {reason}"));
+            let output = if output.is_empty() { "(no output)".to_string() } else { output };
+            let msg = format!(
+                "Command probe failed {} times: {}\nCommand: {}\nLast failure: {}\nOutput:\n{}",
+                failures,
+                self.tag(),
+                self.config.command.join(" "),
+                reason,
+                output
+            );
+            println!("TRIGGERING ALERT: {msg}");
+            if let Err(e) =
+                self.notifier.firing(&alert_key, &msg, "COMMAND", self.config.priority).await
+            {
+                eprintln!("Failed to send command probe alert: {e:?}");
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use anyhow::Result;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt, fs, path::Path};
 
-/// Alert priority levels. P0 is the highest (most critical).
-#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+/// Alert priority levels. P0 is the highest (most critical), and compares as the smallest.
+#[derive(
+    Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 pub enum Priority {
     #[serde(alias = "p0", alias = "P0")]
     #[default]
@@ -31,10 +33,67 @@ pub struct Config {
     /// Multiple probe endpoints, each with its own URL, interval, and threshold.
     #[serde(default)]
     pub probes: Vec<ProbeConfig>,
+    /// Local commands run on a schedule, for checks sentinel doesn't support natively.
+    #[serde(default)]
+    pub command_probes: Vec<CommandProbeConfig>,
     /// Optional chain monitor configuration for on-chain bridge event monitoring.
     pub chain_monitor: Option<crate::chain_monitor::config::ChainMonitorConfig>,
     /// Optional explorer block-advance monitor (Blockscout v2 API).
     pub explorer_monitor: Option<ExplorerMonitorConfig>,
+    /// Optional disk usage and growth-forecast monitor for the node's data directory.
+    pub resource_monitor: Option<ResourceMonitorConfig>,
+    /// Optional NIC throughput, packet error and TCP connection monitor.
+    pub network_monitor: Option<NetworkMonitorConfig>,
+    /// Optional validator set churn monitor, fed by the gravity API.
+    pub validator_churn: Option<ValidatorChurnConfig>,
+    /// Optional export of the rolled-up node health computed from the monitors above.
+    pub health: Option<HealthConfig>,
+    /// Optional on-disk history of sent alerts and resource samples, kept within retention.
+    pub storage: Option<StorageConfig>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StorageConfig {
+    /// Directory of the `alerts.jsonl` and `samples.jsonl` stores, e.g. "/var/lib/sentinel"
+    pub dir: String,
+    /// Entries older than this are dropped at compaction.
+    #[serde(default = "default_storage_max_age_hours")]
+    pub max_age_hours: u64,
+    /// Upper bound on the size of each store; the oldest entries are dropped beyond it.
+    #[serde(default = "default_storage_max_bytes")]
+    pub max_bytes: u64,
+    #[serde(default = "default_compaction_interval")]
+    pub compaction_interval_seconds: u64,
+}
+
+fn default_storage_max_age_hours() -> u64 {
+    7 * 24
+}
+
+fn default_storage_max_bytes() -> u64 {
+    16 * 1024 * 1024
+}
+
+fn default_compaction_interval() -> u64 {
+    600
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct HealthConfig {
+    /// Control API address serving `/health` and `/metrics`, e.g. "127.0.0.1:9101"
+    pub listen: Option<String>,
+    /// Checks that are reported but don't make the node unhealthy, as glob patterns over check
+    /// names, e.g. ["resource:*", "explorer:*"]. Every other check is required.
+    #[serde(default)]
+    pub optional: Vec<String>,
+    /// URL the health report is POSTed to periodically (optional)
+    pub heartbeat_url: Option<String>,
+    #[serde(default = "default_heartbeat_interval")]
+    pub heartbeat_interval_seconds: u64,
+}
+
+fn default_heartbeat_interval() -> u64 {
+    30
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -55,6 +114,41 @@ fn default_probe_threshold() -> u32 {
     3
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct CommandProbeConfig {
+    /// Program and arguments, run without a shell, e.g. ["/opt/checks/peers.sh", "--min", "3"]
+    pub command: Vec<String>,
+    /// Label shown in alert messages
+    pub tag: Option<String>,
+    #[serde(default = "default_probe_interval")]
+    pub check_interval_seconds: u64,
+    /// The command is killed and the run counted as failed after this long.
+    #[serde(default = "default_command_timeout")]
+    pub timeout_seconds: u64,
+    #[serde(default = "default_probe_threshold")]
+    pub failure_threshold: u32,
+    /// Environment of the command. Apart from PATH, nothing is inherited from sentinel's
+    /// environment unless named in `pass_env`.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub pass_env: Vec<String>,
+    pub working_dir: Option<String>,
+    /// Bytes kept from the end of stdout and of stderr for the alert.
+    #[serde(default = "default_command_output_bytes")]
+    pub max_output_bytes: usize,
+    #[serde(default)]
+    pub priority: Priority,
+}
+
+fn default_command_timeout() -> u64 {
+    10
+}
+
+fn default_command_output_bytes() -> usize {
+    2048
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct ExplorerMonitorConfig {
     /// Blockscout v2 API base, e.g. "https://api.explorer-testnet.gravity.xyz"
@@ -83,15 +177,179 @@ fn default_explorer_api_failure_threshold() -> u32 {
     5
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct ResourceMonitorConfig {
+    /// Node data directory whose size is sampled, e.g. "/opt/gravity/data"
+    pub data_dir: String,
+    #[serde(default = "default_resource_check_interval")]
+    pub check_interval_seconds: u64,
+    /// Alert when the filesystem holding data_dir is at least this full.
+    #[serde(default = "default_usage_threshold_percent")]
+    pub usage_threshold_percent: f64,
+    /// Samples older than this are dropped from the growth regression.
+    #[serde(default = "default_forecast_window_hours")]
+    pub forecast_window_hours: u64,
+    /// Alert when the disk is projected to fill up within this many hours.
+    #[serde(default = "default_forecast_alert_hours")]
+    pub forecast_alert_hours: u64,
+    /// Samples required before forecasting, so a single burst doesn't trigger an alert.
+    #[serde(default = "default_forecast_min_samples")]
+    pub min_samples: usize,
+    #[serde(default = "default_resource_priority")]
+    pub priority: Priority,
+}
+
+fn default_resource_check_interval() -> u64 {
+    300
+}
+
+fn default_usage_threshold_percent() -> f64 {
+    90.0
+}
+
+fn default_forecast_window_hours() -> u64 {
+    6
+}
+
+fn default_forecast_alert_hours() -> u64 {
+    24
+}
+
+fn default_forecast_min_samples() -> usize {
+    6
+}
+
+fn default_resource_priority() -> Priority {
+    Priority::P1
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NetworkMonitorConfig {
+    /// Interfaces to sample, e.g. ["eth0"]. Empty means every interface but loopback.
+    #[serde(default)]
+    pub interfaces: Vec<String>,
+    #[serde(default = "default_network_check_interval")]
+    pub check_interval_seconds: u64,
+    /// Link speed used for interfaces whose driver doesn't report one in /sys/class/net.
+    pub link_speed_mbps: Option<u64>,
+    /// Alert when receive or transmit throughput reaches this share of the link speed.
+    #[serde(default = "default_saturation_threshold_percent")]
+    pub saturation_threshold_percent: f64,
+    /// Alert when errors and drops of an interface, both directions, exceed this rate.
+    #[serde(default = "default_max_packet_errors_per_second")]
+    pub max_packet_errors_per_second: f64,
+    /// Alert when the host has more established TCP connections than this (not checked if unset).
+    pub max_tcp_connections: Option<usize>,
+    /// Samples the throughput and connection trends are fitted over.
+    #[serde(default = "default_network_trend_samples")]
+    pub trend_samples: usize,
+    /// Alert when a trend reaches its threshold within this many minutes.
+    #[serde(default = "default_trend_alert_minutes")]
+    pub trend_alert_minutes: u64,
+    #[serde(default = "default_network_priority")]
+    pub priority: Priority,
+}
+
+fn default_network_check_interval() -> u64 {
+    10
+}
+
+fn default_saturation_threshold_percent() -> f64 {
+    80.0
+}
+
+fn default_max_packet_errors_per_second() -> f64 {
+    10.0
+}
+
+fn default_network_trend_samples() -> usize {
+    30
+}
+
+fn default_trend_alert_minutes() -> u64 {
+    15
+}
+
+fn default_network_priority() -> Priority {
+    Priority::P1
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ValidatorChurnConfig {
+    /// Gravity node API base, e.g. "http://127.0.0.1:1024"
+    pub api_base: String,
+    /// Label shown in alert messages
+    pub tag: Option<String>,
+    #[serde(default = "default_churn_poll_interval")]
+    pub poll_interval_seconds: u64,
+    /// Recent epoch changes fetched per poll, so changes between two polls are not missed.
+    #[serde(default = "default_churn_epochs")]
+    pub epochs: u64,
+    /// Alert when more validators than this join or leave in one epoch change.
+    #[serde(default = "default_max_changes_per_epoch")]
+    pub max_changes_per_epoch: usize,
+    /// Alert when more than this fraction of the voting power changes hands in one epoch change.
+    #[serde(default = "default_max_turnover_ratio")]
+    pub max_turnover_ratio: f64,
+    #[serde(default = "default_churn_priority")]
+    pub priority: Priority,
+}
+
+fn default_churn_poll_interval() -> u64 {
+    60
+}
+
+fn default_churn_epochs() -> u64 {
+    10
+}
+
+fn default_max_changes_per_epoch() -> usize {
+    3
+}
+
+fn default_max_turnover_ratio() -> f64 {
+    0.2
+}
+
+fn default_churn_priority() -> Priority {
+    Priority::P1
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct MonitoringConfig {
+    /// Glob patterns of log files to tail. May be empty when only the journal is monitored.
+    #[serde(default)]
     pub file_patterns: Vec<String>,
+    #[serde(default = "default_recent_file_threshold")]
     pub recent_file_threshold_seconds: u64,
     pub error_pattern: String,
     pub whitelist_path: Option<String>,
     /// Periodic interval (ms) to re-scan file_patterns for new log files.
     /// If omitted, only the initial set of files is monitored (no discovery of new files).
     pub check_interval_ms: Option<u64>,
+    /// Optional systemd journal source, analyzed alongside the files above.
+    pub journald: Option<JournaldConfig>,
+}
+
+fn default_recent_file_threshold() -> u64 {
+    86400
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JournaldConfig {
+    /// Only follow entries of these systemd units. Empty means the whole journal.
+    #[serde(default)]
+    pub units: Vec<String>,
+    /// journalctl priority filter, e.g. "err" or "warning" or a range like "0..4".
+    pub priority: Option<String>,
+    /// File storing the last processed journal cursor. Without it, sentinel starts at the tail.
+    pub cursor_path: Option<String>,
+    #[serde(default = "default_journalctl_path")]
+    pub journalctl_path: String,
+}
+
+fn default_journalctl_path() -> String {
+    "journalctl".to_string()
 }
 
 /// Per-priority webhook override.
@@ -99,6 +357,50 @@ pub struct MonitoringConfig {
 pub struct PriorityAlertConfig {
     pub feishu_webhook: Option<String>,
     pub slack_webhook: Option<String>,
+    /// Chat threaded alerts of this priority go to, overriding `[alerting.threading]`.
+    pub feishu_chat_id: Option<String>,
+    pub slack_channel: Option<String>,
+}
+
+/// Bot credentials for posting follow-ups of an alert (escalated, still firing, resolved) as
+/// replies to it. Webhooks can't reply, so without these every follow-up is a new message.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ThreadingConfig {
+    /// Feishu app that is a member of the alert chats.
+    pub feishu_app_id: Option<String>,
+    pub feishu_app_secret: Option<String>,
+    /// Chat threaded alerts go to, e.g. "oc_..." (used instead of the Feishu webhook)
+    pub feishu_chat_id: Option<String>,
+    /// Slack bot token ("xoxb-...") with chat:write, invited to the alert channels.
+    pub slack_bot_token: Option<String>,
+    /// Channel threaded alerts go to, e.g. "C0123456" (used instead of the Slack webhook)
+    pub slack_channel: Option<String>,
+}
+
+/// Feishu alerts as cards: a header colored by severity, the alert's fields as blocks, and
+/// buttons to a runbook, a dashboard and the control API's ack endpoint.
+#[derive(Debug, Deserialize, Clone)]
+pub struct FeishuCardsConfig {
+    /// Control API as reachable from the chat, e.g. "https://sentinel-0.example.com:9101".
+    /// Alerts that stay open get an Acknowledge button linking to its `/ack`.
+    pub ack_base_url: Option<String>,
+    /// Buttons of alerts no rule sets its own for.
+    pub runbook_url: Option<String>,
+    pub dashboard_url: Option<String>,
+    /// Checked in order; the first rule matching an alert decides its buttons.
+    #[serde(default)]
+    pub rules: Vec<CardRuleConfig>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CardRuleConfig {
+    /// Regex over the alert's key (e.g. "probe:rpc") and message
+    pub pattern: String,
+    pub runbook_url: Option<String>,
+    pub dashboard_url: Option<String>,
+    /// Send matching alerts as plain text instead of a card.
+    #[serde(default)]
+    pub plain_text: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -118,6 +420,13 @@ pub struct AlertingConfig {
     /// Per-priority webhook overrides. Key is the priority name (e.g. "p0", "p1", "p2").
     #[serde(default)]
     pub priorities: HashMap<Priority, PriorityAlertConfig>,
+    /// How often an alert that is still firing is brought up again, in its thread when
+    /// threading is configured.
+    #[serde(default = "default_still_firing_minutes")]
+    pub still_firing_minutes: u64,
+    pub threading: Option<ThreadingConfig>,
+    /// Feishu messages as interactive cards instead of plain text.
+    pub feishu_cards: Option<FeishuCardsConfig>,
 }
 
 impl AlertingConfig {
@@ -133,6 +442,20 @@ impl AlertingConfig {
         }
     }
 
+    /// Get the effective Feishu chat and Slack channel of threaded alerts for the given
+    /// priority, with the same fallback as [`Self::get_webhooks`].
+    pub fn get_thread_channels(&self, priority: Priority) -> (Option<&str>, Option<&str>) {
+        let Some(threading) = &self.threading else { return (None, None) };
+        let override_cfg = self.priorities.get(&priority);
+        let feishu = override_cfg
+            .and_then(|cfg| cfg.feishu_chat_id.as_deref())
+            .or(threading.feishu_chat_id.as_deref());
+        let slack = override_cfg
+            .and_then(|cfg| cfg.slack_channel.as_deref())
+            .or(threading.slack_channel.as_deref());
+        (feishu, slack)
+    }
+
     /// Collect all unique webhook URLs across default and per-priority configs.
     pub fn all_webhooks(&self) -> Vec<(&str, &str)> {
         use std::collections::HashSet;
@@ -167,6 +490,10 @@ fn default_min_alert_interval() -> u64 {
     5
 }
 
+fn default_still_firing_minutes() -> u64 {
+    30
+}
+
 impl Config {
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read_to_string(path)?;
@@ -1,12 +1,13 @@
 use crate::{
     config::{ExplorerMonitorConfig, Priority},
+    health::HealthCheck,
     notifier::Notifier,
 };
 use anyhow::{anyhow, Result};
 use reqwest::Client;
 use serde::Deserialize;
 use std::time::Duration;
-use tokio::time::{self, MissedTickBehavior};
+use tokio::time::{self, Instant, MissedTickBehavior};
 
 /// Blockscout v2 `/api/v2/stats` response fragment. `total_blocks` is a
 /// stringified integer in the Blockscout schema.
@@ -19,15 +20,16 @@ pub struct ExplorerMonitor {
     config: ExplorerMonitorConfig,
     client: Client,
     notifier: Notifier,
+    health: HealthCheck,
 }
 
 impl ExplorerMonitor {
-    pub fn new(config: ExplorerMonitorConfig, notifier: Notifier) -> Self {
+    pub fn new(config: ExplorerMonitorConfig, notifier: Notifier, health: HealthCheck) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(5))
             .build()
             .unwrap_or_else(|_| Client::new());
-        Self { config, client, notifier }
+        Self { config, client, notifier, health }
     }
 
     pub fn tag(&self) -> String {
@@ -48,10 +50,12 @@ impl ExplorerMonitor {
         let mut timer = time::interval(Duration::from_secs(self.config.poll_interval_seconds));
         timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
+        let stall_key = format!("explorer:{tag}:stall");
+        let api_key = format!("explorer:{tag}:api");
         let mut last_height: Option<u64> = None;
-        // Height for which a stall alert has already been sent; prevents
-        // repeating the same alert every tick while the chain is stuck.
-        let mut last_alerted_stall_height: Option<u64> = None;
+        // When the height stopped advancing. Reported every poll while the chain is stuck; the
+        // notifier keeps that to one alert and its follow-ups.
+        let mut stalled_since: Option<Instant> = None;
         let mut api_failures: u32 = 0;
         let mut api_alert_sent = false;
 
@@ -62,6 +66,10 @@ impl ExplorerMonitor {
                 Ok(h) => {
                     if api_alert_sent {
                         println!("Explorer API recovered for {tag} (height={h})");
+                        let msg = format!("Explorer API recovered for {tag} (height={h})");
+                        if let Err(e) = self.notifier.resolved(&api_key, &msg).await {
+                            eprintln!("Failed to send explorer API recovery: {e:?}");
+                        }
                         api_alert_sent = false;
                     }
                     api_failures = 0;
@@ -69,32 +77,48 @@ impl ExplorerMonitor {
                     match last_height {
                         None => {
                             last_height = Some(h);
+                            self.health.pass();
                         }
                         Some(prev) if h > prev => {
                             last_height = Some(h);
-                            last_alerted_stall_height = None;
+                            if let Some(since) = stalled_since.take() {
+                                let msg = format!(
+                                    "Explorer block height advancing again on {tag}: {prev} -> \
+                                     {h} after {}s",
+                                    since.elapsed().as_secs() + self.config.poll_interval_seconds
+                                );
+                                if let Err(e) = self.notifier.resolved(&stall_key, &msg).await {
+                                    eprintln!("Failed to send explorer recovery: {e:?}");
+                                }
+                            }
+                            self.health.pass();
                         }
                         Some(prev) => {
                             // h <= prev: height did not advance within the poll window.
-                            if last_alerted_stall_height != Some(prev) {
-                                let msg = format!(
-                                    "Explorer block height stalled on {tag}\n  \
-                                     height: {prev} (no advance in {}s)\n  \
-                                     api: {}",
-                                    self.config.poll_interval_seconds, self.config.api_base,
-                                );
+                            self.health.fail(format!("block height stalled at {prev}"));
+                            let first_stalled_poll = stalled_since.is_none();
+                            let since = *stalled_since.get_or_insert_with(Instant::now);
+                            // The first poll without progress already spans one interval.
+                            let stalled_secs =
+                                since.elapsed().as_secs() + self.config.poll_interval_seconds;
+                            let msg = format!(
+                                "Explorer block height stalled on {tag}\n  \
+                                 height: {prev} (no advance in {stalled_secs}s)\n  \
+                                 api: {}",
+                                self.config.api_base,
+                            );
+                            if first_stalled_poll {
                                 println!("TRIGGERING ALERT: {msg}");
-                                if let Err(e) = self
-                                    .notifier
-                                    .alert(&msg, "EXPLORER", self.config.priority)
-                                    .await
-                                {
-                                    eprintln!("Failed to send explorer alert: {e:?}");
-                                }
-                                last_alerted_stall_height = Some(prev);
                             }
-                            // Keep last_height unchanged so that when height finally
-                            // advances we reset last_alerted_stall_height above.
+                            if let Err(e) = self
+                                .notifier
+                                .firing(&stall_key, &msg, "EXPLORER", self.config.priority)
+                                .await
+                            {
+                                eprintln!("Failed to send explorer alert: {e:?}");
+                            }
+                            // Keep last_height unchanged so that the stall resolves once the
+                            // height finally advances past it.
                         }
                     }
                 }
@@ -104,14 +128,18 @@ impl ExplorerMonitor {
                         "Explorer API error for {tag} ({api_failures}/{}): {e}",
                         self.config.api_failure_threshold
                     );
-                    if !api_alert_sent && api_failures >= self.config.api_failure_threshold {
+                    if api_failures >= self.config.api_failure_threshold {
+                        self.health.fail(format!("API unreachable: {e}"));
                         let msg = format!(
                             "Explorer API unreachable on {tag} ({} consecutive failures)\n  \
                              api: {}\n  last error: {e}",
                             api_failures, self.config.api_base,
                         );
-                        println!("TRIGGERING ALERT: {msg}");
-                        if let Err(err) = self.notifier.alert(&msg, "EXPLORER", Priority::P0).await
+                        if !api_alert_sent {
+                            println!("TRIGGERING ALERT: {msg}");
+                        }
+                        if let Err(err) =
+                            self.notifier.firing(&api_key, &msg, "EXPLORER", Priority::P0).await
                         {
                             eprintln!("Failed to send explorer API alert: {err:?}");
                         }
@@ -0,0 +1,138 @@
+//! Feishu interactive cards for alerts, see `[alerting.feishu_cards]`.
+
+use crate::config::{FeishuCardsConfig, Priority};
+use anyhow::{Context, Result};
+use regex::Regex;
+use reqwest::Url;
+use serde_json::{json, Value};
+
+/// What an alert message says, in the structured form a card shows.
+pub struct Card<'a> {
+    pub title: String,
+    pub priority: Priority,
+    /// Resolved alerts get a green header whatever their priority.
+    pub resolved: bool,
+    /// Label and value of each field block, e.g. ("File", "/var/log/node.log").
+    pub fields: Vec<(&'static str, String)>,
+    pub message: &'a str,
+    /// Key of the open alert, which the Acknowledge button refers to.
+    pub key: Option<&'a str>,
+}
+
+struct CardRule {
+    pattern: Regex,
+    runbook_url: Option<String>,
+    dashboard_url: Option<String>,
+    plain_text: bool,
+}
+
+/// Renders cards with the buttons of the first matching rule.
+pub struct FeishuCards {
+    ack_base_url: Option<String>,
+    runbook_url: Option<String>,
+    dashboard_url: Option<String>,
+    rules: Vec<CardRule>,
+}
+
+impl FeishuCards {
+    pub fn new(config: &FeishuCardsConfig) -> Result<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                Ok(CardRule {
+                    pattern: Regex::new(&rule.pattern)
+                        .with_context(|| format!("Invalid card rule pattern '{}'", rule.pattern))?,
+                    runbook_url: rule.runbook_url.clone(),
+                    dashboard_url: rule.dashboard_url.clone(),
+                    plain_text: rule.plain_text,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            ack_base_url: config.ack_base_url.clone(),
+            runbook_url: config.runbook_url.clone(),
+            dashboard_url: config.dashboard_url.clone(),
+            rules,
+        })
+    }
+
+    /// The card JSON of `card`, or None when its rule asks for plain text.
+    pub fn render(&self, card: &Card) -> Option<Value> {
+        let rule = self.rules.iter().find(|rule| {
+            card.key.is_some_and(|key| rule.pattern.is_match(key)) ||
+                rule.pattern.is_match(card.message)
+        });
+        if rule.is_some_and(|rule| rule.plain_text) {
+            return None;
+        }
+        let runbook = rule.and_then(|rule| rule.runbook_url.as_ref()).or(self.runbook_url.as_ref());
+        let dashboard =
+            rule.and_then(|rule| rule.dashboard_url.as_ref()).or(self.dashboard_url.as_ref());
+
+        let mut buttons = Vec::new();
+        if let Some(url) = runbook {
+            buttons.push(button("Runbook", "default", url));
+        }
+        if let Some(url) = dashboard {
+            buttons.push(button("Dashboard", "default", url));
+        }
+        if let (Some(base), Some(key), false) = (&self.ack_base_url, card.key, card.resolved) {
+            match ack_url(base, key) {
+                Ok(url) => buttons.push(button("Acknowledge", "primary", url.as_str())),
+                Err(e) => eprintln!("Invalid ack_base_url '{base}': {e}"),
+            }
+        }
+
+        let fields: Vec<Value> = card
+            .fields
+            .iter()
+            .map(|(label, value)| {
+                json!({
+                    "is_short": true,
+                    "text": { "tag": "lark_md", "content": format!("**{label}**\n{value}") },
+                })
+            })
+            .collect();
+        let mut elements = vec![
+            json!({ "tag": "div", "fields": fields }),
+            json!({ "tag": "div", "text": { "tag": "plain_text", "content": card.message } }),
+        ];
+        if !buttons.is_empty() {
+            elements.push(json!({ "tag": "action", "actions": buttons }));
+        }
+        Some(json!({
+            "config": { "wide_screen_mode": true },
+            "header": {
+                "template": header_color(card.priority, card.resolved),
+                "title": { "tag": "plain_text", "content": card.title },
+            },
+            "elements": elements,
+        }))
+    }
+}
+
+fn header_color(priority: Priority, resolved: bool) -> &'static str {
+    match (resolved, priority) {
+        (true, _) => "green",
+        (false, Priority::P0) => "red",
+        (false, Priority::P1) => "orange",
+        (false, Priority::P2) => "yellow",
+    }
+}
+
+fn button(text: &str, kind: &str, url: &str) -> Value {
+    json!({
+        "tag": "button",
+        "text": { "tag": "plain_text", "content": text },
+        "type": kind,
+        "url": url,
+    })
+}
+
+/// `<base>/ack?key=<key>` on the control API.
+fn ack_url(base: &str, key: &str) -> Result<Url> {
+    let mut url = Url::parse(&format!("{}/ack", base.trim_end_matches('/')))?;
+    url.query_pairs_mut().append_pair("key", key);
+    Ok(url)
+}
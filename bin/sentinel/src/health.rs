@@ -0,0 +1,251 @@
+use crate::{
+    config::HealthConfig,
+    notifier::Notifier,
+    store::{self, Store},
+};
+use anyhow::{Context, Result};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::{self, MissedTickBehavior};
+
+/// A check that has not reported for this many of its intervals counts as failing, so a monitor
+/// task that died does not leave its last "healthy" in place forever.
+const STALE_INTERVALS: u32 = 3;
+/// Extra slack on top of the stale intervals, for monitors backing off after errors.
+const STALE_GRACE: Duration = Duration::from_secs(60);
+
+struct CheckState {
+    required: bool,
+    healthy: bool,
+    detail: Option<String>,
+    updated: Instant,
+    stale_after: Duration,
+}
+
+/// Rolled-up health of the node, computed from the checks reported by every monitor.
+///
+/// The node is healthy when every required check passes. Checks matching one of the
+/// `[health] optional` patterns are reported but don't affect the rolled-up state.
+#[derive(Clone)]
+pub struct Health {
+    checks: Arc<Mutex<BTreeMap<String, CheckState>>>,
+    optional: Arc<Vec<glob::Pattern>>,
+    /// Persisted stores whose size is exported on `/metrics`.
+    stores: Arc<Mutex<Vec<Store>>>,
+}
+
+/// Handle a monitor uses to report the state of its check.
+#[derive(Clone)]
+pub struct HealthCheck {
+    name: String,
+    health: Health,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub checks: Vec<CheckReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckReport {
+    pub name: String,
+    pub required: bool,
+    pub healthy: bool,
+    pub stale: bool,
+    pub detail: Option<String>,
+    pub seconds_since_update: u64,
+}
+
+impl Health {
+    pub fn new(config: Option<&HealthConfig>) -> Result<Self> {
+        let optional = config
+            .map(|c| c.optional.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|p| glob::Pattern::new(p).with_context(|| format!("Invalid optional check '{p}'")))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            checks: Arc::new(Mutex::new(BTreeMap::new())),
+            optional: Arc::new(optional),
+            stores: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    /// Exports the size of `store` on `/metrics`.
+    pub fn track_store(&self, store: Store) {
+        self.stores.lock().unwrap().push(store);
+    }
+
+    /// Registers a check reported every `interval`. It starts out passing.
+    pub fn register(&self, name: impl Into<String>, interval: Duration) -> HealthCheck {
+        let name = name.into();
+        let required = !self.optional.iter().any(|p| p.matches(&name));
+        self.checks.lock().unwrap().insert(
+            name.clone(),
+            CheckState {
+                required,
+                healthy: true,
+                detail: None,
+                updated: Instant::now(),
+                stale_after: interval * STALE_INTERVALS + STALE_GRACE,
+            },
+        );
+        HealthCheck { name, health: self.clone() }
+    }
+
+    pub fn report(&self) -> HealthReport {
+        let checks: Vec<CheckReport> = self
+            .checks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, state)| {
+                let age = state.updated.elapsed();
+                CheckReport {
+                    name: name.clone(),
+                    required: state.required,
+                    healthy: state.healthy,
+                    stale: age > state.stale_after,
+                    detail: state.detail.clone(),
+                    seconds_since_update: age.as_secs(),
+                }
+            })
+            .collect();
+        let healthy = checks.iter().all(|c| !c.required || (c.healthy && !c.stale));
+        HealthReport { healthy, checks }
+    }
+}
+
+impl HealthCheck {
+    pub fn pass(&self) {
+        self.set(true, None);
+    }
+
+    pub fn fail(&self, detail: impl Into<String>) {
+        self.set(false, Some(detail.into()));
+    }
+
+    fn set(&self, healthy: bool, detail: Option<String>) {
+        if let Some(state) = self.health.checks.lock().unwrap().get_mut(&self.name) {
+            if state.healthy != healthy {
+                let required = if state.required { "required" } else { "optional" };
+                match &detail {
+                    Some(detail) => {
+                        println!("Health check {} ({required}) failing: {detail}", self.name)
+                    }
+                    None => println!("Health check {} ({required}) passing again", self.name),
+                }
+            }
+            state.healthy = healthy;
+            state.detail = detail;
+            state.updated = Instant::now();
+        }
+    }
+}
+
+/// Prometheus text exposition of the rolled-up state and of every check.
+fn render_metrics(report: &HealthReport) -> String {
+    let mut out = String::new();
+    out.push_str("# HELP sentinel_node_healthy Whether every required check passes.\n");
+    out.push_str("# TYPE sentinel_node_healthy gauge\n");
+    let _ = writeln!(out, "sentinel_node_healthy {}", u8::from(report.healthy));
+    out.push_str("# HELP sentinel_check_healthy Whether a check passes and is not stale.\n");
+    out.push_str("# TYPE sentinel_check_healthy gauge\n");
+    for check in &report.checks {
+        let _ = writeln!(
+            out,
+            "sentinel_check_healthy{{check=\"{}\",required=\"{}\"}} {}",
+            check.name.replace('\\', "\\\\").replace('"', "\\\""),
+            check.required,
+            u8::from(check.healthy && !check.stale),
+        );
+    }
+    out
+}
+
+#[derive(Clone)]
+struct ControlState {
+    health: Health,
+    notifier: Notifier,
+}
+
+async fn get_health(State(state): State<ControlState>) -> impl IntoResponse {
+    let report = state.health.report();
+    let status = if report.healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
+async fn get_metrics(State(state): State<ControlState>) -> impl IntoResponse {
+    let mut metrics = render_metrics(&state.health.report());
+    store::render_metrics(&state.health.stores.lock().unwrap(), &mut metrics);
+    ([("content-type", "text/plain; version=0.0.4")], metrics)
+}
+
+#[derive(Deserialize)]
+struct AckQuery {
+    key: String,
+}
+
+/// Target of the Acknowledge button of Feishu cards, hence a GET opened in a browser.
+async fn get_ack(
+    State(state): State<ControlState>,
+    Query(query): Query<AckQuery>,
+) -> impl IntoResponse {
+    if state.notifier.acknowledge(&query.key).await {
+        println!("Alert {} acknowledged", query.key);
+        (StatusCode::OK, format!("Acknowledged {}", query.key))
+    } else {
+        (StatusCode::NOT_FOUND, format!("No open alert {}", query.key))
+    }
+}
+
+/// Serves `GET /health` (200 when healthy, 503 otherwise, with every check in the body),
+/// `GET /metrics` for Prometheus and `GET /ack?key=<alert>` to acknowledge an open alert.
+pub async fn serve(listen: String, health: Health, notifier: Notifier) -> Result<()> {
+    let app = Router::new()
+        .route("/health", get(get_health))
+        .route("/metrics", get(get_metrics))
+        .route("/ack", get(get_ack))
+        .with_state(ControlState { health, notifier });
+    let listener = tokio::net::TcpListener::bind(&listen)
+        .await
+        .with_context(|| format!("Failed to bind control API on {listen}"))?;
+    println!("Control API listening on {listen}");
+    axum::serve(listener, app).await.context("Control API stopped")
+}
+
+/// POSTs the health report to `url` every `interval`, so a dead-man's switch or failover tool
+/// notices both an unhealthy node and a sentinel that stopped running.
+pub async fn run_heartbeat(url: String, interval: Duration, health: Health) {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| Client::new());
+    let mut timer = time::interval(interval);
+    timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        timer.tick().await;
+        let report = health.report();
+        match client.post(&url).json(&report).send().await {
+            Ok(resp) if !resp.status().is_success() => {
+                eprintln!("Health heartbeat to {url} failed with status: {}", resp.status())
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Health heartbeat to {url} failed: {e}"),
+        }
+    }
+}
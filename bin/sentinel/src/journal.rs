@@ -0,0 +1,184 @@
+use crate::config::JournaldConfig;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::{
+    fs,
+    path::Path,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader, Lines},
+    process::{Child, ChildStdout, Command},
+    time,
+};
+
+/// How often the journal cursor is persisted while entries keep arriving without a pause.
+const CURSOR_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+/// Delay before restarting `journalctl` after it exits.
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// A single journal entry relevant to log analysis.
+pub struct JournalLine {
+    /// Systemd unit (or syslog identifier) that produced the entry.
+    pub unit: String,
+    pub message: String,
+}
+
+/// Tails the systemd journal by following `journalctl -o json`.
+///
+/// The cursor of the last processed entry is persisted to `cursor_path` (if configured) after
+/// each batch of entries and on shutdown, so a restarted sentinel resumes where it stopped
+/// instead of re-alerting or skipping entries.
+pub struct JournalReader {
+    config: JournaldConfig,
+    child: Child,
+    lines: Lines<BufReader<ChildStdout>>,
+    /// Cursor of the last entry read.
+    cursor: Option<String>,
+    /// Whether `cursor` changed since it was last saved.
+    dirty: bool,
+    last_saved: Instant,
+}
+
+impl JournalReader {
+    pub fn new(config: JournaldConfig) -> Result<Self> {
+        let cursor = config.cursor_path.as_deref().and_then(load_cursor);
+        let (child, lines) = spawn_journalctl(&config, cursor.as_deref())?;
+        Ok(Self { config, child, lines, cursor, dirty: false, last_saved: Instant::now() })
+    }
+
+    /// Returns the next journal entry. Restarts `journalctl` if it exits.
+    ///
+    /// Asking for the next entry means the previous one was processed, so its cursor is saved
+    /// once the entries `journalctl` already wrote are drained, and at least every
+    /// [`CURSOR_SAVE_INTERVAL`] while they keep coming.
+    pub async fn next_line(&mut self) -> JournalLine {
+        loop {
+            if self.last_saved.elapsed() >= CURSOR_SAVE_INTERVAL {
+                self.save_cursor();
+            }
+            // `next_line` is cancel safe, so it can be polled once to see whether a line is ready.
+            let next = tokio::select! {
+                biased;
+                next = self.lines.next_line() => next,
+                () = std::future::ready(()) => {
+                    // End of the batch: everything read so far has been processed.
+                    self.save_cursor();
+                    self.lines.next_line().await
+                }
+            };
+            match next {
+                Ok(Some(raw)) => {
+                    let Some(entry) = self.parse(&raw) else {
+                        continue;
+                    };
+                    return entry;
+                }
+                Ok(None) | Err(_) => {
+                    eprintln!("journalctl exited, restarting in {}s", RESTART_DELAY.as_secs());
+                    self.save_cursor();
+                    let _ = self.child.kill().await;
+                    time::sleep(RESTART_DELAY).await;
+                    match spawn_journalctl(&self.config, self.cursor.as_deref()) {
+                        Ok((child, lines)) => {
+                            self.child = child;
+                            self.lines = lines;
+                        }
+                        Err(e) => eprintln!("Failed to restart journalctl: {e:?}"),
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse(&mut self, raw: &str) -> Option<JournalLine> {
+        let value: Value = match serde_json::from_str(raw) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Failed to parse journal entry: {e}");
+                return None;
+            }
+        };
+        if let Some(cursor) = value.get("__CURSOR").and_then(Value::as_str) {
+            self.cursor = Some(cursor.to_string());
+            self.dirty = true;
+        }
+        let message = match value.get("MESSAGE")? {
+            Value::String(s) => s.clone(),
+            // Non-UTF-8 messages are emitted as byte arrays
+            Value::Array(bytes) => {
+                let bytes: Vec<u8> =
+                    bytes.iter().filter_map(|b| b.as_u64().map(|b| b as u8)).collect();
+                String::from_utf8_lossy(&bytes).into_owned()
+            }
+            _ => return None,
+        };
+        let unit = value
+            .get("_SYSTEMD_UNIT")
+            .or_else(|| value.get("SYSLOG_IDENTIFIER"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string();
+        Some(JournalLine { unit, message })
+    }
+
+    /// Persists the current cursor using an atomic write (temp file + rename). Called on
+    /// shutdown, once the last returned entry has been processed.
+    pub fn save_cursor(&mut self) {
+        self.last_saved = Instant::now();
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+        let (Some(path), Some(cursor)) = (self.config.cursor_path.as_deref(), &self.cursor) else {
+            return;
+        };
+        let tmp_path = format!("{path}.tmp");
+        if let Err(e) = fs::write(&tmp_path, cursor).and_then(|_| fs::rename(&tmp_path, path)) {
+            eprintln!("Failed to save journal cursor to {path}: {e:?}");
+        }
+    }
+}
+
+fn load_cursor(path: &str) -> Option<String> {
+    if !Path::new(path).exists() {
+        return None;
+    }
+    match fs::read_to_string(path) {
+        Ok(content) if !content.trim().is_empty() => {
+            println!("Resuming journal from cursor in {path}");
+            Some(content.trim().to_string())
+        }
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("Warning: Failed to read journal cursor {path}: {e}, starting from tail");
+            None
+        }
+    }
+}
+
+fn spawn_journalctl(
+    config: &JournaldConfig,
+    cursor: Option<&str>,
+) -> Result<(Child, Lines<BufReader<ChildStdout>>)> {
+    let mut cmd = Command::new(&config.journalctl_path);
+    cmd.args(["--follow", "--output=json", "--no-pager"]);
+    for unit in &config.units {
+        cmd.arg(format!("--unit={unit}"));
+    }
+    if let Some(priority) = &config.priority {
+        cmd.arg(format!("--priority={priority}"));
+    }
+    match cursor {
+        Some(cursor) => cmd.arg(format!("--after-cursor={cursor}")),
+        // Without a cursor only new entries are of interest
+        None => cmd.arg("--lines=0"),
+    };
+    cmd.stdout(Stdio::piped()).stderr(Stdio::inherit()).kill_on_drop(true);
+
+    let mut child =
+        cmd.spawn().with_context(|| format!("Failed to spawn {}", config.journalctl_path))?;
+    let stdout = child.stdout.take().context("journalctl stdout not captured")?;
+    Ok((child, BufReader::new(stdout).lines()))
+}
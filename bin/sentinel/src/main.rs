@@ -1,33 +1,57 @@
 mod analyzer;
 mod chain_monitor;
+mod chat_api;
+mod check;
+mod command_probe;
 mod config;
 mod explorer_monitor;
+mod feishu_card;
+mod health;
+mod journal;
+mod network_monitor;
 mod notifier;
 mod probe;
 mod reader;
+mod resource_monitor;
+mod store;
+mod validator_churn_monitor;
 mod watcher;
 mod whitelist;
 
 use crate::{
     analyzer::Analyzer,
+    command_probe::CommandProbe,
     config::Config,
     explorer_monitor::ExplorerMonitor,
+    health::Health,
+    journal::JournalReader,
+    network_monitor::NetworkMonitor,
     notifier::Notifier,
     probe::Probe,
     reader::Reader,
+    resource_monitor::ResourceMonitor,
+    store::Store,
+    validator_churn_monitor::ValidatorChurnMonitor,
     watcher::Watcher,
     whitelist::{CheckResult, Whitelist},
 };
 use anyhow::{Context, Result};
-use std::{env, time::Duration};
-use tokio::time;
+use std::{env, path::Path, time::Duration};
+use tokio::{sync::watch, task::JoinHandle, time};
 
-/// Spawn log monitoring as an independent task.
+/// How often whitelist frequency counters of idle log sources are dropped.
+const WHITELIST_GC_INTERVAL: Duration = Duration::from_secs(600);
+/// How long the log monitor may take to finish the line in hand on shutdown.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Spawn log monitoring as an independent task. It stops, saving the journal cursor, once
+/// `shutdown` turns true.
 fn spawn_log_monitor(
     monitoring: config::MonitoringConfig,
     alerting: config::AlertingConfig,
     notifier: Notifier,
-) -> Result<()> {
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<JoinHandle<()>> {
     let mut whitelist = if let Some(ref path) = monitoring.whitelist_path {
         println!("Loading whitelist from {path}");
         Whitelist::load(path).context("Failed to load whitelist")?
@@ -43,8 +67,19 @@ fn spawn_log_monitor(
 
     let check_interval_ms = monitoring.check_interval_ms;
 
-    tokio::spawn(async move {
+    let mut journal = match monitoring.journald.clone() {
+        Some(journald) => {
+            println!("Monitoring systemd journal (units: {:?})", journald.units);
+            Some(JournalReader::new(journald).context("Failed to start journal reader")?)
+        }
+        None => None,
+    };
+
+    let handle = tokio::spawn(async move {
         let mut reader = Reader::new().expect("Failed to create reader");
+        let mut whitelist_gc = time::interval(WHITELIST_GC_INTERVAL);
+        // linemux must not be polled without any watched file (see Reader::next_line).
+        let mut reading_files = !files.is_empty();
         for file in files {
             println!("Monitoring: {file:?}");
             if let Err(e) = reader.add_file(&file).await {
@@ -58,7 +93,7 @@ fn spawn_log_monitor(
 
         loop {
             tokio::select! {
-                Some(line_event) = reader.next_line() => {
+                Some(line_event) = reader.next_line(), if reading_files => {
                     let line = line_event.line();
                     let path = line_event.source();
 
@@ -85,6 +120,35 @@ fn spawn_log_monitor(
                         }
                     }
                 }
+                entry = async { journal.as_mut().unwrap().next_line().await }, if journal.is_some() => {
+                    if !analyzer.is_error(&entry.message) {
+                        continue;
+                    }
+
+                    let source = format!("journal:{}", entry.unit);
+                    match whitelist.check(&entry.message, Path::new(&source)) {
+                        CheckResult::Skip => continue,
+                        CheckResult::Alert { count, priority } => {
+                            let msg = format!("{} [Frequency Alert: >{count}/5min]", entry.message);
+                            println!("Frequency Alert in {source}: {msg}");
+                            if let Err(e) = notifier.alert(&msg, &source, priority).await {
+                                eprintln!("Failed to send alert: {e:?}");
+                            }
+                        }
+                        CheckResult::AlwaysAlert => {
+                            println!("Alert in {source}: {}", entry.message);
+                            if let Err(e) = notifier.alert(&entry.message, &source, alerting.default_priority).await {
+                                eprintln!("Failed to send alert: {e:?}");
+                            }
+                        }
+                    }
+                }
+                _ = whitelist_gc.tick() => {
+                    let dropped = whitelist.gc();
+                    if dropped > 0 {
+                        println!("Dropped {dropped} idle whitelist counters");
+                    }
+                }
                 _ = async { discovery_interval.as_mut().unwrap().tick().await }, if discovery_interval.is_some() => {
                     match watcher.discover() {
                         Ok(new_files) => {
@@ -92,17 +156,25 @@ fn spawn_log_monitor(
                                 println!("New file discovered: {file:?}");
                                 if let Err(e) = reader.add_file(&file).await {
                                     eprintln!("Failed to add file: {e:?}");
+                                } else {
+                                    reading_files = true;
                                 }
                             }
                         }
                         Err(e) => eprintln!("Discovery error: {e:?}"),
                     }
                 }
+                _ = shutdown.wait_for(|stop| *stop) => {
+                    if let Some(journal) = journal.as_mut() {
+                        journal.save_cursor();
+                    }
+                    break;
+                }
             }
         }
     });
 
-    Ok(())
+    Ok(handle)
 }
 
 #[tokio::main]
@@ -112,28 +184,78 @@ async fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
         eprintln!("Usage: {} <config.toml>", args[0]);
+        eprintln!("       {} check --config <config.toml> [--replay <logfile>]", args[0]);
         std::process::exit(1);
     }
 
+    if args[1] == "check" {
+        return check::run(check::CheckArgs::parse(&args[2..])?);
+    }
+
     let config_path = &args[1];
 
     println!("Loading config from {config_path}");
     let config = Config::load(config_path).context("Failed to load config")?;
 
-    let notifier = Notifier::new(config.alerting.clone());
+    let health = Health::new(config.health.as_ref()).context("Invalid [health] config")?;
+
+    // Persisted history (if configured), compacted in the background to stay within retention
+    let (alert_store, samples_store) = match &config.storage {
+        Some(storage) => {
+            let alerts = Store::open(storage, "alerts").context("Failed to open alert history")?;
+            let samples =
+                Store::open(storage, "samples").context("Failed to open metric samples")?;
+            println!(
+                "Storing alert history and samples in {} (max {}h, {} bytes each)",
+                storage.dir, storage.max_age_hours, storage.max_bytes
+            );
+            health.track_store(alerts.clone());
+            health.track_store(samples.clone());
+            tokio::spawn(store::run_compaction(
+                vec![alerts.clone(), samples.clone()],
+                Duration::from_secs(storage.compaction_interval_seconds),
+            ));
+            (Some(alerts), Some(samples))
+        }
+        None => (None, None),
+    };
+
+    let mut notifier =
+        Notifier::new(config.alerting.clone()).context("Invalid [alerting] config")?;
+    if let Some(alerts) = alert_store {
+        notifier = notifier.with_history(alerts);
+    }
+    tokio::spawn(notifier.clone().run_gc());
 
     // Verify webhook connectivity on startup
     notifier.verify_webhooks().await.context("Webhook verification failed")?;
 
     // Start Probes
     for probe_config in config.probes {
-        let probe = Probe::new(probe_config, notifier.clone());
+        let check = health.register(
+            Probe::check_name(&probe_config),
+            Duration::from_secs(probe_config.check_interval_seconds),
+        );
+        let probe = Probe::new(probe_config, notifier.clone(), check);
         println!("Starting health probe for {}...", probe.url());
         tokio::spawn(async move {
             probe.run().await;
         });
     }
 
+    // Start Command Probes
+    for command_config in config.command_probes {
+        let check = health.register(
+            CommandProbe::check_name(&command_config),
+            Duration::from_secs(command_config.check_interval_seconds),
+        );
+        let probe = CommandProbe::new(command_config, notifier.clone(), check);
+        println!("Starting command probe {}...", probe.tag());
+        tokio::spawn(async move {
+            probe.run().await;
+        });
+    }
+
     // Start Chain Monitors (if configured)
     if let Some(chain_config) = config.chain_monitor {
         println!("Starting chain monitors...");
@@ -144,23 +266,110 @@ async fn main() -> Result<()> {
 
     // Start Explorer Monitor (if configured)
     if let Some(explorer_cfg) = config.explorer_monitor {
-        let monitor = ExplorerMonitor::new(explorer_cfg, notifier.clone());
+        let check = health.register(
+            format!("explorer:{}", explorer_cfg.tag.as_deref().unwrap_or(&explorer_cfg.api_base)),
+            Duration::from_secs(explorer_cfg.poll_interval_seconds),
+        );
+        let monitor = ExplorerMonitor::new(explorer_cfg, notifier.clone(), check);
         println!("Starting explorer monitor for {}...", monitor.tag());
         tokio::spawn(async move {
             monitor.run().await;
         });
     }
 
+    // Start Resource Monitor (if configured)
+    if let Some(resource_cfg) = config.resource_monitor {
+        let check = health.register(
+            format!("resource:{}", resource_cfg.data_dir),
+            Duration::from_secs(resource_cfg.check_interval_seconds),
+        );
+        let mut monitor = ResourceMonitor::new(resource_cfg, notifier.clone(), check);
+        if let Some(samples) = samples_store {
+            monitor = monitor.with_samples_store(samples);
+        }
+        println!("Starting resource monitor for {}...", monitor.data_dir());
+        tokio::spawn(async move {
+            monitor.run().await;
+        });
+    }
+
+    // Start Network Monitor (if configured)
+    if let Some(network_cfg) = config.network_monitor {
+        let check =
+            health.register("network", Duration::from_secs(network_cfg.check_interval_seconds));
+        let monitor = NetworkMonitor::new(network_cfg, notifier.clone(), check);
+        println!("Starting network monitor for {}...", monitor.interfaces());
+        tokio::spawn(async move {
+            monitor.run().await;
+        });
+    }
+
+    // Start Validator Churn Monitor (if configured)
+    if let Some(churn_cfg) = config.validator_churn {
+        let check = health.register(
+            format!("validator_churn:{}", churn_cfg.tag.as_deref().unwrap_or(&churn_cfg.api_base)),
+            Duration::from_secs(churn_cfg.poll_interval_seconds),
+        );
+        let monitor = ValidatorChurnMonitor::new(churn_cfg, notifier.clone(), check);
+        println!("Starting validator churn monitor for {}...", monitor.tag());
+        tokio::spawn(async move {
+            monitor.run().await;
+        });
+    }
+
     // Start Log Monitoring (if configured)
-    if let Some(monitoring) = config.monitoring {
-        println!("Starting log monitoring...");
-        spawn_log_monitor(monitoring, config.alerting, notifier)?;
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let log_monitor = match config.monitoring {
+        Some(monitoring) => {
+            println!("Starting log monitoring...");
+            Some(spawn_log_monitor(monitoring, config.alerting, notifier.clone(), shutdown_rx)?)
+        }
+        None => None,
+    };
+
+    // Export the rolled-up health (if configured)
+    if let Some(health_cfg) = config.health {
+        if let Some(listen) = health_cfg.listen {
+            let health = health.clone();
+            tokio::spawn(async move {
+                if let Err(e) = health::serve(listen, health, notifier).await {
+                    eprintln!("{e:?}");
+                }
+            });
+        }
+        if let Some(url) = health_cfg.heartbeat_url {
+            println!("Sending health heartbeats to {url}");
+            let interval = Duration::from_secs(health_cfg.heartbeat_interval_seconds);
+            tokio::spawn(health::run_heartbeat(url, interval, health));
+        }
     }
 
     println!("Sentinel started...");
 
-    tokio::signal::ctrl_c().await?;
+    shutdown_signal().await?;
     println!("Shutting down...");
+    let _ = shutdown_tx.send(true);
+    if let Some(log_monitor) = log_monitor {
+        if time::timeout(SHUTDOWN_TIMEOUT, log_monitor).await.is_err() {
+            eprintln!("Log monitor did not stop within {}s", SHUTDOWN_TIMEOUT.as_secs());
+        }
+    }
+
+    Ok(())
+}
 
+/// Waits for Ctrl-C or, on Unix, SIGTERM (what systemd sends to stop the service).
+async fn shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
     Ok(())
 }
@@ -0,0 +1,377 @@
+use crate::{config::NetworkMonitorConfig, health::HealthCheck, notifier::Notifier};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    time::{Duration, Instant},
+};
+use tokio::time::{self, MissedTickBehavior};
+
+const PROC_NET_DEV: &str = "/proc/net/dev";
+const PROC_NET_TCP: [&str; 2] = ["/proc/net/tcp", "/proc/net/tcp6"];
+/// `st` column of an established socket in /proc/net/tcp.
+const TCP_ESTABLISHED: &str = "01";
+
+/// Cumulative counters of one interface, as found in /proc/net/dev.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Counters {
+    rx_bytes: u64,
+    rx_errors: u64,
+    rx_dropped: u64,
+    tx_bytes: u64,
+    tx_errors: u64,
+    tx_dropped: u64,
+}
+
+/// Per-second rates between two readings of an interface's counters.
+struct Rates {
+    rx_bits: f64,
+    tx_bits: f64,
+    errors: f64,
+}
+
+impl Counters {
+    /// Rates since `earlier`, or None when a counter went backwards (interface reset or
+    /// recreated), in which case the pair is skipped.
+    fn rates_since(&self, earlier: &Counters, elapsed: Duration) -> Option<Rates> {
+        let secs = elapsed.as_secs_f64();
+        let delta = |now: u64, before: u64| now.checked_sub(before).map(|d| d as f64 / secs);
+        if secs <= 0.0 {
+            return None;
+        }
+        Some(Rates {
+            rx_bits: delta(self.rx_bytes, earlier.rx_bytes)? * 8.0,
+            tx_bits: delta(self.tx_bytes, earlier.tx_bytes)? * 8.0,
+            errors: delta(self.rx_errors, earlier.rx_errors)? +
+                delta(self.rx_dropped, earlier.rx_dropped)? +
+                delta(self.tx_errors, earlier.tx_errors)? +
+                delta(self.tx_dropped, earlier.tx_dropped)?,
+        })
+    }
+}
+
+/// What is remembered of an interface between samples.
+struct Interface {
+    counters: Counters,
+    at: Instant,
+    /// Recent (time, busiest direction in bits/s) points the throughput trend is fitted over.
+    throughput: VecDeque<(Instant, f64)>,
+}
+
+/// Samples per-interface throughput and packet errors from /proc/net/dev, and established TCP
+/// connections from /proc/net/tcp{,6}, alerting on static thresholds and on trends heading
+/// for them.
+///
+/// A validator whose NIC saturates misses votes and proposals in ways that look like consensus
+/// bugs; these alerts put the network next to those symptoms. Throughput is compared with the
+/// link speed from /sys/class/net, or `link_speed_mbps` where the driver doesn't report one.
+pub struct NetworkMonitor {
+    config: NetworkMonitorConfig,
+    notifier: Notifier,
+    health: HealthCheck,
+}
+
+impl NetworkMonitor {
+    pub fn new(config: NetworkMonitorConfig, notifier: Notifier, health: HealthCheck) -> Self {
+        Self { config, notifier, health }
+    }
+
+    pub fn interfaces(&self) -> String {
+        if self.config.interfaces.is_empty() {
+            "all interfaces".to_string()
+        } else {
+            self.config.interfaces.join(", ")
+        }
+    }
+
+    pub async fn run(self) {
+        let interval = Duration::from_secs(self.config.check_interval_seconds);
+        let mut timer = time::interval(interval);
+        timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let trend_horizon = Duration::from_secs(self.config.trend_alert_minutes * 60);
+
+        let mut interfaces: HashMap<String, Interface> = HashMap::new();
+        let mut connections: VecDeque<(Instant, f64)> = VecDeque::new();
+        // Keys of the conditions currently alerted; like the resource monitor, each is reported on
+        // every sample while it holds and resolved once it clears.
+        let mut firing: HashSet<String> = HashSet::new();
+
+        loop {
+            timer.tick().await;
+            let now = Instant::now();
+            let counters = match self.read_counters() {
+                Ok(counters) => counters,
+                Err(e) => {
+                    eprintln!("Network monitor failed to sample interfaces: {e:?}");
+                    self.health.fail(format!("failed to sample interfaces: {e}"));
+                    continue;
+                }
+            };
+            let mut problems = Vec::new();
+
+            for (name, counters) in counters {
+                let Some(previous) = interfaces.get_mut(&name) else {
+                    interfaces
+                        .insert(name, Interface { counters, at: now, throughput: VecDeque::new() });
+                    continue;
+                };
+                let rates = counters.rates_since(&previous.counters, now - previous.at);
+                previous.counters = counters;
+                previous.at = now;
+                let Some(rates) = rates else {
+                    previous.throughput.clear();
+                    continue;
+                };
+                let busiest = rates.rx_bits.max(rates.tx_bits);
+                previous.throughput.push_back((now, busiest));
+                while previous.throughput.len() > self.config.trend_samples {
+                    previous.throughput.pop_front();
+                }
+
+                let capacity = link_speed_bits(&name)
+                    .or(self.config.link_speed_mbps.map(|mbps| mbps as f64 * 1_000_000.0));
+                let saturation_key = format!("network:{name}:saturation");
+                let trend_key = format!("network:{name}:trend");
+                match capacity {
+                    Some(capacity) => {
+                        let limit = capacity * self.config.saturation_threshold_percent / 100.0;
+                        let used = busiest * 100.0 / capacity;
+                        let alert = (busiest >= limit).then(|| {
+                            problems.push(format!("{name} at {used:.0}% of link"));
+                            format!(
+                                "Interface {name} at {used:.1}% of its {} link (threshold {}%)\n  \
+                                 rx: {}, tx: {}",
+                                format_bits(capacity),
+                                self.config.saturation_threshold_percent,
+                                format_bits(rates.rx_bits),
+                                format_bits(rates.tx_bits),
+                            )
+                        });
+                        let recovery = format!("Interface {name} back to {used:.1}% of its link");
+                        self.report(&mut firing, &saturation_key, alert, &recovery).await;
+
+                        let eta = (previous.throughput.len() >= self.config.trend_samples &&
+                            busiest < limit)
+                            .then(|| time_to_reach(&previous.throughput, limit))
+                            .flatten()
+                            .filter(|eta| *eta <= trend_horizon);
+                        let alert = eta.map(|eta| {
+                            problems.push(format!("{name} saturating in ~{}m", eta.as_secs() / 60));
+                            format!(
+                                "Interface {name} throughput trending to {}% of its link in \
+                                 ~{} minutes\n  now: {used:.1}% ({}) over the last {} samples",
+                                self.config.saturation_threshold_percent,
+                                eta.as_secs() / 60,
+                                format_bits(busiest),
+                                previous.throughput.len(),
+                            )
+                        });
+                        let recovery =
+                            format!("Interface {name} throughput no longer trending to saturation");
+                        self.report(&mut firing, &trend_key, alert, &recovery).await;
+                    }
+                    None => {
+                        self.report(&mut firing, &saturation_key, None, "").await;
+                        self.report(&mut firing, &trend_key, None, "").await;
+                    }
+                }
+
+                let errors_key = format!("network:{name}:errors");
+                let alert = (rates.errors > self.config.max_packet_errors_per_second).then(|| {
+                    problems.push(format!("{name} dropping {:.1} packets/s", rates.errors));
+                    format!(
+                        "Interface {name} has {:.1} packet errors and drops per second (max {})",
+                        rates.errors, self.config.max_packet_errors_per_second
+                    )
+                });
+                let recovery =
+                    format!("Interface {name} packet errors back to {:.1}/s", rates.errors);
+                self.report(&mut firing, &errors_key, alert, &recovery).await;
+            }
+
+            if let Some(max) = self.config.max_tcp_connections {
+                match established_connections() {
+                    Ok(established) => {
+                        connections.push_back((now, established as f64));
+                        while connections.len() > self.config.trend_samples {
+                            connections.pop_front();
+                        }
+                        let eta = (connections.len() >= self.config.trend_samples &&
+                            established <= max)
+                            .then(|| time_to_reach(&connections, max as f64))
+                            .flatten()
+                            .filter(|eta| *eta <= trend_horizon);
+                        let alert = if established > max {
+                            problems.push(format!("{established} TCP connections"));
+                            Some(format!("{established} established TCP connections exceed {max}"))
+                        } else {
+                            eta.map(|eta| {
+                                problems.push(format!(
+                                    "TCP connections reaching {max} in ~{}m",
+                                    eta.as_secs() / 60
+                                ));
+                                format!(
+                                    "{established} established TCP connections, trending to {max} \
+                                     in ~{} minutes",
+                                    eta.as_secs() / 60
+                                )
+                            })
+                        };
+                        let recovery = format!("Established TCP connections back to {established}");
+                        self.report(&mut firing, "network:connections", alert, &recovery).await;
+                    }
+                    Err(e) => {
+                        eprintln!("Network monitor failed to count connections: {e:?}");
+                        problems.push(format!("failed to count connections: {e}"));
+                    }
+                }
+            }
+
+            if problems.is_empty() {
+                self.health.pass();
+            } else {
+                self.health.fail(problems.join("; "));
+            }
+        }
+    }
+
+    /// Reports `alert` as firing under `key`, or resolves the key with `recovery` when it was
+    /// firing and `alert` is None.
+    async fn report(
+        &self,
+        firing: &mut HashSet<String>,
+        key: &str,
+        alert: Option<String>,
+        recovery: &str,
+    ) {
+        match alert {
+            Some(msg) => {
+                if firing.insert(key.to_string()) {
+                    println!("TRIGGERING ALERT: {msg}");
+                }
+                if let Err(e) =
+                    self.notifier.firing(key, &msg, "NETWORK", self.config.priority).await
+                {
+                    eprintln!("Failed to send network alert: {e:?}");
+                }
+            }
+            None if firing.remove(key) => {
+                println!("{recovery}");
+                if let Err(e) = self.notifier.resolved(key, recovery).await {
+                    eprintln!("Failed to send network recovery: {e:?}");
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Counters of the monitored interfaces. A configured interface that is missing is an
+    /// error; without configured ones, every interface but loopback is monitored.
+    fn read_counters(&self) -> Result<Vec<(String, Counters)>> {
+        let contents = fs::read_to_string(PROC_NET_DEV)
+            .with_context(|| format!("Failed to read {PROC_NET_DEV}"))?;
+        let mut all = parse_net_dev(&contents);
+        if self.config.interfaces.is_empty() {
+            all.remove("lo");
+            return Ok(all.into_iter().collect());
+        }
+        self.config
+            .interfaces
+            .iter()
+            .map(|name| {
+                all.remove(name)
+                    .map(|counters| (name.clone(), counters))
+                    .ok_or_else(|| anyhow!("interface {name} not found in {PROC_NET_DEV}"))
+            })
+            .collect()
+    }
+}
+
+/// Parses /proc/net/dev: two header lines, then `name: rx_bytes rx_packets rx_errs rx_drop
+/// rx_fifo rx_frame rx_compressed rx_multicast tx_bytes tx_packets tx_errs tx_drop ...`.
+fn parse_net_dev(contents: &str) -> HashMap<String, Counters> {
+    contents
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, fields) = line.split_once(':')?;
+            let fields: Vec<u64> =
+                fields.split_whitespace().map(|field| field.parse().ok()).collect::<Option<_>>()?;
+            let field = |index: usize| fields.get(index).copied();
+            Some((
+                name.trim().to_string(),
+                Counters {
+                    rx_bytes: field(0)?,
+                    rx_errors: field(2)?,
+                    rx_dropped: field(3)?,
+                    tx_bytes: field(8)?,
+                    tx_errors: field(10)?,
+                    tx_dropped: field(11)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Established TCP connections over IPv4 and IPv6, the `ESTAB` count of `netstat`/`ss`.
+fn established_connections() -> Result<usize> {
+    let mut established = 0;
+    for path in PROC_NET_TCP {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            // IPv6 may be disabled
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {path}")),
+        };
+        established += count_established(&contents);
+    }
+    Ok(established)
+}
+
+/// Sockets in the established state in a /proc/net/tcp table: `sl local rem st ...`.
+fn count_established(contents: &str) -> usize {
+    contents
+        .lines()
+        .skip(1)
+        .filter(|line| line.split_whitespace().nth(3) == Some(TCP_ESTABLISHED))
+        .count()
+}
+
+/// Link speed of `interface` in bits/s, if its driver reports one (virtual NICs often report
+/// -1 or nothing).
+fn link_speed_bits(interface: &str) -> Option<f64> {
+    let speed = fs::read_to_string(format!("/sys/class/net/{interface}/speed")).ok()?;
+    let mbps: i64 = speed.trim().parse().ok()?;
+    (mbps > 0).then(|| mbps as f64 * 1_000_000.0)
+}
+
+/// When the least-squares line through `points` reaches `limit`, if it is rising towards it.
+fn time_to_reach(points: &VecDeque<(Instant, f64)>, limit: f64) -> Option<Duration> {
+    let (first, latest) = (points.front()?.0, points.back()?);
+    let n = points.len() as f64;
+    let xy: Vec<(f64, f64)> =
+        points.iter().map(|(at, value)| (at.duration_since(first).as_secs_f64(), *value)).collect();
+    let mean_x = xy.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = xy.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (x, y) in &xy {
+        cov += (x - mean_x) * (y - mean_y);
+        var += (x - mean_x) * (x - mean_x);
+    }
+    let slope = (var > 0.0).then(|| cov / var).filter(|slope| *slope > 0.0)?;
+    // Project from the fitted value at the latest sample, so one spike doesn't decide the ETA
+    let now_x = latest.0.duration_since(first).as_secs_f64();
+    let fitted = mean_y + slope * (now_x - mean_x);
+    Some(Duration::from_secs_f64(((limit - fitted) / slope).max(0.0)))
+}
+
+fn format_bits(bits_per_sec: f64) -> String {
+    const UNITS: [&str; 4] = ["bit/s", "Kbit/s", "Mbit/s", "Gbit/s"];
+    let mut value = bits_per_sec;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
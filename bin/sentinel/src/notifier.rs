@@ -1,66 +1,243 @@
-use crate::config::{AlertingConfig, Priority};
+use crate::{
+    chat_api::{FeishuApp, FeishuContent, SlackBot},
+    config::{AlertingConfig, Priority},
+    feishu_card::{Card, FeishuCards},
+    store::Store,
+};
 use anyhow::Result;
 use reqwest::Client;
+use serde::Serialize;
 use serde_json::json;
 use std::{
     collections::HashMap,
-    sync::Mutex,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
+/// A chat that threaded alerts are posted to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Destination {
+    Feishu(String),
+    Slack(String),
+}
+
+/// Open alerts not reported for this long are dropped: the monitor that opened them stopped
+/// reporting, e.g. a log file that rotated away, and would otherwise hold them forever.
+const STALE_OPEN_ALERT: Duration = Duration::from_secs(24 * 3600);
+/// How often stale open alerts are looked for.
+const ALERT_GC_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Follow-up of an open alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FollowUp {
+    Escalated,
+    StillFiring,
+    Acknowledged,
+    Resolved,
+}
+
+/// An alert whose condition has not cleared yet.
+struct OpenAlert {
+    priority: Priority,
+    opened_at: Instant,
+    last_posted_at: Instant,
+    last_reported_at: Instant,
+    /// Acknowledged through the control API: no more "still firing" reminders until it
+    /// escalates.
+    acknowledged: bool,
+    /// The alert's first message in each chat it was posted to; follow-ups reply to it.
+    roots: HashMap<Destination, String>,
+}
+
 #[derive(Clone)]
 pub struct Notifier {
     client: Client,
     config: AlertingConfig,
     /// Per-priority rate limiting.
-    last_alert_times: std::sync::Arc<Mutex<HashMap<Priority, Instant>>>,
+    last_alert_times: Arc<Mutex<HashMap<Priority, Instant>>>,
+    feishu_app: Option<Arc<FeishuApp>>,
+    slack_bot: Option<Arc<SlackBot>>,
+    /// Feishu messages are cards when `[alerting.feishu_cards]` is set.
+    feishu_cards: Option<Arc<FeishuCards>>,
+    /// Open alerts by key, see [`Self::firing`].
+    open_alerts: Arc<Mutex<HashMap<String, OpenAlert>>>,
+    /// Where every message sent is recorded, if `[storage]` is configured.
+    history: Option<Store>,
+}
+
+/// An alert history entry.
+#[derive(Serialize)]
+struct SentAlert<'a> {
+    /// `alert` for a new alert, or the follow-up.
+    kind: &'a str,
+    /// Key of the open alert, for alerts reported through [`Notifier::firing`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<&'a str>,
+    priority: Priority,
+    message: &'a str,
 }
 
 impl Notifier {
-    pub fn new(config: AlertingConfig) -> Self {
-        Self {
-            client: Client::new(),
+    pub fn new(config: AlertingConfig) -> Result<Self> {
+        let client = Client::new();
+        let threading = config.threading.clone().unwrap_or_default();
+        let feishu_app = threading
+            .feishu_app_id
+            .zip(threading.feishu_app_secret)
+            .map(|(id, secret)| Arc::new(FeishuApp::new(client.clone(), id, secret)));
+        let slack_bot =
+            threading.slack_bot_token.map(|token| Arc::new(SlackBot::new(client.clone(), token)));
+        let feishu_cards = config
+            .feishu_cards
+            .as_ref()
+            .map(|cards| FeishuCards::new(cards).map(Arc::new))
+            .transpose()?;
+        Ok(Self {
+            client,
             config,
-            last_alert_times: std::sync::Arc::new(Mutex::new(HashMap::new())),
+            last_alert_times: Arc::new(Mutex::new(HashMap::new())),
+            feishu_app,
+            slack_bot,
+            feishu_cards,
+            open_alerts: Arc::new(Mutex::new(HashMap::new())),
+            history: None,
+        })
+    }
+
+    /// Records every alert sent in `history`.
+    pub fn with_history(mut self, history: Store) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    fn record(&self, kind: &str, key: Option<&str>, priority: Priority, message: &str) {
+        if let Some(history) = &self.history {
+            history.append(SentAlert { kind, key, priority, message });
+        }
+    }
+
+    /// Drops open alerts nobody reported for [`STALE_OPEN_ALERT`] and returns how many.
+    pub fn gc_stale_alerts(&self) -> usize {
+        let mut open_alerts = self.open_alerts.lock().unwrap();
+        let before = open_alerts.len();
+        open_alerts.retain(|_, alert| alert.last_reported_at.elapsed() < STALE_OPEN_ALERT);
+        before - open_alerts.len()
+    }
+
+    /// Periodically drops stale open alerts, see [`Self::gc_stale_alerts`].
+    pub async fn run_gc(self) {
+        let mut timer = tokio::time::interval(ALERT_GC_INTERVAL);
+        loop {
+            timer.tick().await;
+            let dropped = self.gc_stale_alerts();
+            if dropped > 0 {
+                println!("Dropped {dropped} stale open alerts");
+            }
+        }
+    }
+
+    /// `card` when cards are configured and its rule doesn't ask for plain text, else `text`.
+    fn feishu_content(&self, text: &str, card: &Card) -> FeishuContent {
+        match self.feishu_cards.as_ref().and_then(|cards| cards.render(card)) {
+            Some(card) => FeishuContent::Card(card),
+            None => FeishuContent::Text(text.to_string()),
         }
     }
 
+    async fn send_feishu_webhook(&self, url: &str, content: &FeishuContent) -> Result<()> {
+        let resp = self.client.post(url).json(&content.webhook_payload()).send().await?;
+        anyhow::ensure!(
+            resp.status().is_success(),
+            "Feishu webhook failed with status: {}",
+            resp.status()
+        );
+        Ok(())
+    }
+
+    async fn send_slack_webhook(&self, url: &str, text: &str) -> Result<()> {
+        let payload = json!({
+            "text": text,
+            "channel": "#alerts-devops",
+            "username": "System-Monitor"
+        });
+        let resp = self.client.post(url).json(&payload).send().await?;
+        anyhow::ensure!(
+            resp.status().is_success(),
+            "Slack webhook failed with status: {}",
+            resp.status()
+        );
+        Ok(())
+    }
+
     /// Send a message to the webhooks for the given priority.
-    async fn send(&self, text: &str, priority: Priority) -> Result<()> {
+    async fn send(&self, text: &str, card: &Card<'_>, priority: Priority) -> Result<()> {
         let (feishu, slack) = self.config.get_webhooks(priority);
 
-        if let Some(feishu_url) = feishu {
-            if !feishu_url.is_empty() {
-                let payload = json!({
-                    "msg_type": "text",
-                    "content": { "text": text }
-                });
-                let resp = self.client.post(feishu_url).json(&payload).send().await?;
-                anyhow::ensure!(
-                    resp.status().is_success(),
-                    "Feishu webhook failed with status: {}",
-                    resp.status()
-                );
+        if let Some(feishu_url) = feishu.filter(|url| !url.is_empty()) {
+            self.send_feishu_webhook(feishu_url, &self.feishu_content(text, card)).await?;
+        }
+        if let Some(slack_url) = slack.filter(|url| !url.is_empty()) {
+            self.send_slack_webhook(slack_url, text).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends `text` to every chat of `priority`. Where a bot is configured, it replies to the
+    /// alert's message in `roots` or, with none yet in that chat, starts a thread; the messages
+    /// that start threads are returned. Other chats get a plain webhook message. Feishu gets
+    /// `card` instead of `text` when cards are configured.
+    async fn send_threaded(
+        &self,
+        text: &str,
+        card: &Card<'_>,
+        priority: Priority,
+        roots: &HashMap<Destination, String>,
+    ) -> Vec<(Destination, String)> {
+        let (feishu_webhook, slack_webhook) = self.config.get_webhooks(priority);
+        let (feishu_chat, slack_channel) = self.config.get_thread_channels(priority);
+        let mut started = Vec::new();
+        let feishu_content = self.feishu_content(text, card);
+
+        match (&self.feishu_app, feishu_chat) {
+            (Some(app), Some(chat_id)) => {
+                let dest = Destination::Feishu(chat_id.to_string());
+                let root = roots.get(&dest).map(String::as_str);
+                match app.send(chat_id, &feishu_content, root).await {
+                    Ok(message_id) if root.is_none() => started.push((dest, message_id)),
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Failed to send Feishu message: {e:?}"),
+                }
+            }
+            _ => {
+                if let Some(url) = feishu_webhook.filter(|url| !url.is_empty()) {
+                    if let Err(e) = self.send_feishu_webhook(url, &feishu_content).await {
+                        eprintln!("Failed to send webhook: {e:?}");
+                    }
+                }
             }
         }
 
-        if let Some(slack_url) = slack {
-            if !slack_url.is_empty() {
-                let payload = json!({
-                    "text": text,
-                    "channel": "#alerts-devops",
-                    "username": "System-Monitor"
-                });
-                let resp = self.client.post(slack_url).json(&payload).send().await?;
-                anyhow::ensure!(
-                    resp.status().is_success(),
-                    "Slack webhook failed with status: {}",
-                    resp.status()
-                );
+        match (&self.slack_bot, slack_channel) {
+            (Some(bot), Some(channel)) => {
+                let dest = Destination::Slack(channel.to_string());
+                let root = roots.get(&dest).map(String::as_str);
+                match bot.send(channel, text, root).await {
+                    Ok(ts) if root.is_none() => started.push((dest, ts)),
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Failed to send Slack message: {e:?}"),
+                }
+            }
+            _ => {
+                if let Some(url) = slack_webhook.filter(|url| !url.is_empty()) {
+                    if let Err(e) = self.send_slack_webhook(url, text).await {
+                        eprintln!("Failed to send webhook: {e:?}");
+                    }
+                }
             }
         }
 
-        Ok(())
+        started
     }
 
     /// Send a startup message to verify all configured webhooks are reachable.
@@ -93,29 +270,227 @@ impl Notifier {
         Ok(())
     }
 
-    pub async fn alert(&self, message: &str, file: &str, priority: Priority) -> Result<()> {
-        // Per-priority rate limiting
-        {
-            let mut times = self.last_alert_times.lock().unwrap();
-            let now = Instant::now();
-
-            if let Some(last) = times.get(&priority) {
-                if now.duration_since(*last) < Duration::from_secs(self.config.min_alert_interval) {
-                    return Ok(());
-                }
+    /// Per-priority rate limiting; returns false if an alert of `priority` was sent too recently.
+    fn take_alert_slot(&self, priority: Priority) -> bool {
+        let mut times = self.last_alert_times.lock().unwrap();
+        let now = Instant::now();
+
+        if let Some(last) = times.get(&priority) {
+            if now.duration_since(*last) < Duration::from_secs(self.config.min_alert_interval) {
+                return false;
             }
-            times.insert(priority, now);
         }
+        times.insert(priority, now);
+        true
+    }
 
-        let text = format!(
-            "🚨 **Log Sentinel Alert** [{priority}] 🚨\nFile: `{file}`\nError:\n```\n{message}\n```"
-        );
+    pub async fn alert(&self, message: &str, file: &str, priority: Priority) -> Result<()> {
+        if !self.take_alert_slot(priority) {
+            return Ok(());
+        }
+
+        let text = alert_text(message, file, priority);
+        let card = alert_card(message, file, priority, None);
+        self.record("alert", None, priority, message);
 
         // Fire-and-forget: log but don't propagate send errors
-        if let Err(e) = self.send(&text, priority).await {
+        if let Err(e) = self.send(&text, &card, priority).await {
             eprintln!("Failed to send webhook: {e:?}");
         }
 
         Ok(())
     }
+    /// Reports that the condition identified by `key` holds, e.g. a probe failing. The first
+    /// report opens the alert. While it stays open, later reports post a reply in its thread
+    /// when the priority rises or every `still_firing_minutes`, and are dropped otherwise.
+    pub async fn firing(
+        &self,
+        key: &str,
+        message: &str,
+        file: &str,
+        priority: Priority,
+    ) -> Result<()> {
+        let now = Instant::now();
+        let reminder_interval = Duration::from_secs(self.config.still_firing_minutes * 60);
+        let (text, card, priority, roots) = {
+            let mut open_alerts = self.open_alerts.lock().unwrap();
+            match open_alerts.get_mut(key) {
+                None => {
+                    if !self.take_alert_slot(priority) {
+                        return Ok(());
+                    }
+                    open_alerts.insert(
+                        key.to_string(),
+                        OpenAlert {
+                            priority,
+                            opened_at: now,
+                            last_posted_at: now,
+                            last_reported_at: now,
+                            acknowledged: false,
+                            roots: HashMap::new(),
+                        },
+                    );
+                    self.record("alert", Some(key), priority, message);
+                    (
+                        alert_text(message, file, priority),
+                        alert_card(message, file, priority, Some(key)),
+                        priority,
+                        HashMap::new(),
+                    )
+                }
+                Some(alert) => {
+                    alert.last_reported_at = now;
+                    let follow_up = if priority < alert.priority {
+                        alert.priority = priority;
+                        alert.acknowledged = false;
+                        FollowUp::Escalated
+                    } else if !alert.acknowledged &&
+                        now.duration_since(alert.last_posted_at) >= reminder_interval
+                    {
+                        FollowUp::StillFiring
+                    } else {
+                        return Ok(());
+                    };
+                    alert.last_posted_at = now;
+                    self.record(follow_up.name(), Some(key), alert.priority, message);
+                    let open_for = now.duration_since(alert.opened_at);
+                    (
+                        follow_up_text(follow_up, key, message, alert.priority, open_for),
+                        follow_up_card(follow_up, key, message, alert.priority, open_for),
+                        alert.priority,
+                        alert.roots.clone(),
+                    )
+                }
+            }
+        };
+
+        let started = self.send_threaded(&text, &card, priority, &roots).await;
+        if let Some(alert) = self.open_alerts.lock().unwrap().get_mut(key) {
+            alert.roots.extend(started);
+        }
+        Ok(())
+    }
+
+    /// Reports that the condition identified by `key` cleared, closing its alert with a reply
+    /// in the thread. Does nothing if the alert is not open.
+    pub async fn resolved(&self, key: &str, message: &str) -> Result<()> {
+        let Some(alert) = self.open_alerts.lock().unwrap().remove(key) else {
+            return Ok(());
+        };
+        self.record(FollowUp::Resolved.name(), Some(key), alert.priority, message);
+        let open_for = alert.opened_at.elapsed();
+        let text = follow_up_text(FollowUp::Resolved, key, message, alert.priority, open_for);
+        let card = follow_up_card(FollowUp::Resolved, key, message, alert.priority, open_for);
+        self.send_threaded(&text, &card, alert.priority, &alert.roots).await;
+        Ok(())
+    }
+
+    /// Acknowledges the open alert `key`, which stops its "still firing" reminders until it
+    /// escalates, and says so in its thread. Returns false if the alert is not open.
+    pub async fn acknowledge(&self, key: &str) -> bool {
+        let (priority, open_for, roots) = {
+            let mut open_alerts = self.open_alerts.lock().unwrap();
+            let Some(alert) = open_alerts.get_mut(key) else { return false };
+            if alert.acknowledged {
+                return true;
+            }
+            alert.acknowledged = true;
+            (alert.priority, alert.opened_at.elapsed(), alert.roots.clone())
+        };
+        let message = "Acknowledged, reminders paused until it escalates or resolves";
+        self.record(FollowUp::Acknowledged.name(), Some(key), priority, message);
+        let text = follow_up_text(FollowUp::Acknowledged, key, message, priority, open_for);
+        let card = follow_up_card(FollowUp::Acknowledged, key, message, priority, open_for);
+        self.send_threaded(&text, &card, priority, &roots).await;
+        true
+    }
+}
+
+impl FollowUp {
+    fn name(self) -> &'static str {
+        match self {
+            FollowUp::Escalated => "escalated",
+            FollowUp::StillFiring => "still_firing",
+            FollowUp::Acknowledged => "acknowledged",
+            FollowUp::Resolved => "resolved",
+        }
+    }
+}
+
+fn alert_text(message: &str, file: &str, priority: Priority) -> String {
+    format!(
+        "🚨 **Log Sentinel Alert** [{priority}] 🚨\nFile: `{file}`\nError:\n```\n{message}\n```"
+    )
+}
+
+/// Follow-ups name their alert, since they are top-level messages where threading isn't set up.
+fn follow_up_text(
+    follow_up: FollowUp,
+    key: &str,
+    message: &str,
+    priority: Priority,
+    open_for: Duration,
+) -> String {
+    let minutes = open_for.as_secs() / 60;
+    match follow_up {
+        FollowUp::Escalated => {
+            format!("⬆️ **Escalated to [{priority}]** `{key}`\n```\n{message}\n```")
+        }
+        FollowUp::StillFiring => format!(
+            "⏳ **Still firing** [{priority}] `{key}` after {minutes} min\n```\n{message}\n```"
+        ),
+        FollowUp::Acknowledged => {
+            format!("👀 **Acknowledged** [{priority}] `{key}` after {minutes} min\n{message}")
+        }
+        FollowUp::Resolved => {
+            format!("✅ **Resolved** [{priority}] `{key}` after {minutes} min\n{message}")
+        }
+    }
+}
+
+fn alert_card<'a>(
+    message: &'a str,
+    file: &str,
+    priority: Priority,
+    key: Option<&'a str>,
+) -> Card<'a> {
+    let mut fields = vec![("Priority", priority.to_string()), ("Source", file.to_string())];
+    if let Some(key) = key {
+        fields.push(("Alert", key.to_string()));
+    }
+    Card {
+        title: format!("🚨 Log Sentinel Alert [{priority}]"),
+        priority,
+        resolved: false,
+        fields,
+        message,
+        key,
+    }
+}
+
+fn follow_up_card<'a>(
+    follow_up: FollowUp,
+    key: &'a str,
+    message: &'a str,
+    priority: Priority,
+    open_for: Duration,
+) -> Card<'a> {
+    let title = match follow_up {
+        FollowUp::Escalated => format!("⬆️ Escalated to [{priority}]"),
+        FollowUp::StillFiring => format!("⏳ Still firing [{priority}]"),
+        FollowUp::Acknowledged => format!("👀 Acknowledged [{priority}]"),
+        FollowUp::Resolved => format!("✅ Resolved [{priority}]"),
+    };
+    Card {
+        title,
+        priority,
+        resolved: follow_up == FollowUp::Resolved,
+        fields: vec![
+            ("Priority", priority.to_string()),
+            ("Alert", key.to_string()),
+            ("Open for", format!("{} min", open_for.as_secs() / 60)),
+        ],
+        message,
+        key: Some(key),
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{
     config::{Priority, ProbeConfig},
+    health::HealthCheck,
     notifier::Notifier,
 };
 use reqwest::Client;
@@ -42,10 +43,11 @@ pub struct Probe {
     config: ProbeConfig,
     client: Client,
     notifier: Notifier,
+    health: HealthCheck,
 }
 
 impl Probe {
-    pub fn new(config: ProbeConfig, notifier: Notifier) -> Self {
+    pub fn new(config: ProbeConfig, notifier: Notifier, health: HealthCheck) -> Self {
         Self {
             config,
             client: Client::builder()
@@ -53,6 +55,7 @@ impl Probe {
                 .build()
                 .unwrap_or_else(|_| Client::new()),
             notifier,
+            health,
         }
     }
 
@@ -60,7 +63,13 @@ impl Probe {
         &self.config.url
     }
 
+    /// Name of the probe's health check.
+    pub fn check_name(config: &ProbeConfig) -> String {
+        format!("probe:{}", config.tag.as_deref().unwrap_or(&config.url))
+    }
+
     pub async fn run(self) {
+        let alert_key = Self::check_name(&self.config);
         let mut failures: u32 = 0;
         let mut recent_errors: Vec<String> = Vec::new();
         let interval = Duration::from_secs(self.config.check_interval_seconds);
@@ -75,11 +84,17 @@ impl Probe {
             match self.client.get(&self.config.url).send().await {
                 Ok(_) => {
                     // Any HTTP response (even non-200) means the service is reachable
+                    self.health.pass();
                     if failures > 0 {
                         println!(
                             "Probe recovered: {} (after {} failures)",
                             self.config.url, failures
                         );
+                        let msg =
+                            format!("Probe recovered: {} (after {failures} failures)", self.url());
+                        if let Err(e) = self.notifier.resolved(&alert_key, &msg).await {
+                            eprintln!("Failed to send probe recovery: {e:?}");
+                        }
                         failures = 0;
                         recent_errors.clear();
                     }
@@ -92,7 +107,14 @@ impl Probe {
                         "Probe failed: {} after {}ms - {} (count: {})",
                         self.config.url, elapsed_ms, detail, failures
                     );
+                    if failures >= self.config.failure_threshold {
+                        self.health.fail(format!("{failures} consecutive failures: {detail}"));
+                    }
                     recent_errors.push(format!("#{failures} ({elapsed_ms}ms) {detail}"));
+                    let keep = self.config.failure_threshold.max(1) as usize;
+                    if recent_errors.len() > keep {
+                        recent_errors.drain(..recent_errors.len() - keep);
+                    }
                 }
             }
 
@@ -108,14 +130,12 @@ impl Probe {
                     failures, self.config.url, context, errors_block
                 );
                 println!("TRIGGERING ALERT: {msg}");
-                // Probe alerts are always P0
-                if let Err(e) = self.notifier.alert(&msg, "PROBE", Priority::P0).await {
+                // Probe alerts are always P0. While the probe keeps failing, the notifier turns
+                // repeated reports into "still firing" follow-ups instead of new alerts.
+                if let Err(e) = self.notifier.firing(&alert_key, &msg, "PROBE", Priority::P0).await
+                {
                     eprintln!("Failed to send probe alert: {e:?}");
                 }
-                // Reset failures to avoid spamming every cycle
-                // Let's reset to 0 to alert again if it persists for another N cycles.
-                failures = 0;
-                recent_errors.clear();
             }
         }
     }
@@ -0,0 +1,291 @@
+use crate::{
+    config::ResourceMonitorConfig,
+    health::HealthCheck,
+    notifier::Notifier,
+    store::{unix_now, Store},
+};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tokio::{
+    process::Command,
+    time::{self, MissedTickBehavior},
+};
+
+/// One observation of the data directory.
+struct Sample {
+    at: Instant,
+    /// Bytes used by the data directory itself.
+    dir_bytes: u64,
+}
+
+/// A sample as persisted in the samples store.
+#[derive(Serialize, Deserialize)]
+struct StoredSample {
+    data_dir: String,
+    dir_bytes: u64,
+}
+
+/// Capacity of the filesystem holding the data directory, as reported by `df`.
+struct FsUsage {
+    total_bytes: u64,
+    available_bytes: u64,
+}
+
+impl FsUsage {
+    fn used_percent(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        self.total_bytes.saturating_sub(self.available_bytes) as f64 * 100.0 /
+            self.total_bytes as f64
+    }
+}
+
+/// Watches the node's data directory and alerts on a static usage threshold and on the
+/// projected time until the disk is full.
+///
+/// The projection is a least-squares fit of the directory size over the samples in
+/// `forecast_window_hours`, so a fast backfill is flagged hours before the static threshold trips.
+pub struct ResourceMonitor {
+    config: ResourceMonitorConfig,
+    notifier: Notifier,
+    health: HealthCheck,
+    /// Where samples are persisted, so a restart doesn't throw away the forecast window.
+    samples_store: Option<Store>,
+}
+
+impl ResourceMonitor {
+    pub fn new(config: ResourceMonitorConfig, notifier: Notifier, health: HealthCheck) -> Self {
+        Self { config, notifier, health, samples_store: None }
+    }
+
+    /// Persists samples in `store` and resumes from the ones already in the forecast window.
+    pub fn with_samples_store(mut self, store: Store) -> Self {
+        self.samples_store = Some(store);
+        self
+    }
+
+    /// Samples of this data directory persisted within the last `window`.
+    fn load_samples(&self, window: Duration) -> VecDeque<Sample> {
+        let Some(store) = &self.samples_store else { return VecDeque::new() };
+        let (now, now_unix) = (Instant::now(), unix_now());
+        store
+            .load::<StoredSample>(now_unix.saturating_sub(window.as_secs()))
+            .into_iter()
+            .filter(|(_, sample)| sample.data_dir == self.config.data_dir)
+            .filter_map(|(ts, sample)| {
+                let at = now.checked_sub(Duration::from_secs(now_unix.saturating_sub(ts)))?;
+                Some(Sample { at, dir_bytes: sample.dir_bytes })
+            })
+            .collect()
+    }
+
+    pub fn data_dir(&self) -> &str {
+        &self.config.data_dir
+    }
+
+    pub async fn run(self) {
+        let mut timer = time::interval(Duration::from_secs(self.config.check_interval_seconds));
+        timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let window = Duration::from_secs(self.config.forecast_window_hours * 3600);
+
+        let mut samples = self.load_samples(window);
+        if !samples.is_empty() {
+            println!("Resuming disk growth forecast from {} stored samples", samples.len());
+        }
+        // Conditions are reported on every sample while they hold; the notifier turns that into
+        // one alert with "still firing" follow-ups, and resolves it once the condition clears.
+        let usage_key = format!("resource:{}:usage", self.data_dir());
+        let forecast_key = format!("resource:{}:forecast", self.data_dir());
+        let mut usage_alert_sent = false;
+        let mut forecast_alert_sent = false;
+
+        loop {
+            timer.tick().await;
+            let (dir_bytes, usage) = match self.measure().await {
+                Ok(measured) => measured,
+                Err(e) => {
+                    eprintln!("Resource monitor failed to measure {}: {e:?}", self.data_dir());
+                    self.health.fail(format!("failed to measure: {e}"));
+                    continue;
+                }
+            };
+            let now = Instant::now();
+            samples.push_back(Sample { at: now, dir_bytes });
+            if let Some(store) = &self.samples_store {
+                store.append(StoredSample { data_dir: self.config.data_dir.clone(), dir_bytes });
+            }
+            while samples.front().is_some_and(|s| now.duration_since(s.at) > window) {
+                samples.pop_front();
+            }
+
+            let used_percent = usage.used_percent();
+            if used_percent >= self.config.usage_threshold_percent {
+                let msg = format!(
+                    "Disk usage {used_percent:.1}% exceeds {}% for {}\n  available: {}",
+                    self.config.usage_threshold_percent,
+                    self.data_dir(),
+                    format_bytes(usage.available_bytes),
+                );
+                self.fire(&usage_key, &msg, !usage_alert_sent).await;
+                usage_alert_sent = true;
+            } else if usage_alert_sent {
+                let msg = format!("Disk usage back to {used_percent:.1}% for {}", self.data_dir());
+                self.resolve(&usage_key, &msg).await;
+                usage_alert_sent = false;
+            }
+
+            let hours_to_full = (samples.len() >= self.config.min_samples)
+                .then(|| growth_bytes_per_sec(&samples))
+                .flatten()
+                .filter(|rate| *rate > 0.0)
+                .map(|rate| usage.available_bytes as f64 / rate / 3600.0);
+            match hours_to_full {
+                Some(hours) if hours <= self.config.forecast_alert_hours as f64 => {
+                    let rate = growth_bytes_per_sec(&samples).unwrap_or_default();
+                    let msg = format!(
+                        "Disk full in ~{hours:.1} hours at current growth for {}\n  \
+                         growth: {}/hour over the last {} samples\n  \
+                         available: {} ({used_percent:.1}% used)",
+                        self.data_dir(),
+                        format_bytes((rate * 3600.0) as u64),
+                        samples.len(),
+                        format_bytes(usage.available_bytes),
+                    );
+                    self.fire(&forecast_key, &msg, !forecast_alert_sent).await;
+                    forecast_alert_sent = true;
+                }
+                _ if forecast_alert_sent => {
+                    let msg = format!(
+                        "Disk growth for {} no longer projected to fill it within {} hours",
+                        self.data_dir(),
+                        self.config.forecast_alert_hours
+                    );
+                    self.resolve(&forecast_key, &msg).await;
+                    forecast_alert_sent = false;
+                }
+                _ => {}
+            }
+
+            if usage_alert_sent {
+                self.health.fail(format!("disk usage {used_percent:.1}%"));
+            } else if let Some(hours) = hours_to_full.filter(|_| forecast_alert_sent) {
+                self.health.fail(format!("disk full in ~{hours:.1} hours"));
+            } else {
+                self.health.pass();
+            }
+        }
+    }
+
+    async fn fire(&self, key: &str, msg: &str, starting: bool) {
+        if starting {
+            println!("TRIGGERING ALERT: {msg}");
+        }
+        if let Err(e) = self.notifier.firing(key, msg, "RESOURCE", self.config.priority).await {
+            eprintln!("Failed to send resource alert: {e:?}");
+        }
+    }
+
+    async fn resolve(&self, key: &str, msg: &str) {
+        println!("{msg}");
+        if let Err(e) = self.notifier.resolved(key, msg).await {
+            eprintln!("Failed to send resource recovery: {e:?}");
+        }
+    }
+
+    async fn measure(&self) -> Result<(u64, FsUsage)> {
+        let dir = PathBuf::from(&self.config.data_dir);
+        let walk_dir = dir.clone();
+        let dir_bytes = tokio::task::spawn_blocking(move || dir_size(&walk_dir))
+            .await
+            .context("Directory walk panicked")??;
+        let usage = filesystem_usage(&dir).await?;
+        Ok((dir_bytes, usage))
+    }
+}
+
+/// Total size of the regular files under `path`. Entries that vanish mid-walk (compaction,
+/// rotation) are skipped rather than failing the whole sample.
+fn dir_size(path: &Path) -> Result<u64> {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if dir == path => return Err(e.into()),
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else { continue };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                total += metadata.len();
+            }
+        }
+    }
+    Ok(total)
+}
+
+/// Reads the capacity of the filesystem holding `path` from POSIX `df -Pk`.
+async fn filesystem_usage(path: &Path) -> Result<FsUsage> {
+    let output =
+        Command::new("df").arg("-Pk").arg(path).output().await.context("Failed to run df")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "df exited with {}: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let fields: Vec<&str> = stdout
+        .lines()
+        .nth(1)
+        .ok_or_else(|| anyhow!("Unexpected df output: {stdout}"))?
+        .split_whitespace()
+        .collect();
+    let kib = |index: usize| -> Result<u64> {
+        fields
+            .get(index)
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(|v| v * 1024)
+            .ok_or_else(|| anyhow!("Unexpected df output: {stdout}"))
+    };
+    Ok(FsUsage { total_bytes: kib(1)?, available_bytes: kib(3)? })
+}
+
+/// Slope of the least-squares line through the samples, in bytes per second.
+fn growth_bytes_per_sec(samples: &VecDeque<Sample>) -> Option<f64> {
+    let first = samples.front()?.at;
+    let n = samples.len() as f64;
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|s| (s.at.duration_since(first).as_secs_f64(), s.dir_bytes as f64))
+        .collect();
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (x, y) in &points {
+        cov += (x - mean_x) * (y - mean_y);
+        var += (x - mean_x) * (x - mean_x);
+    }
+    (var > 0.0).then(|| cov / var)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
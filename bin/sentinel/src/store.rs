@@ -0,0 +1,208 @@
+use crate::config::StorageConfig;
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fmt::Write as _,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time::{self, MissedTickBehavior};
+
+/// A persisted record: the unix timestamp it was written at, plus its fields.
+#[derive(Serialize, Deserialize)]
+struct Record<T> {
+    ts: u64,
+    #[serde(flatten)]
+    data: T,
+}
+
+/// Just the timestamp of a record, all compaction needs to read.
+#[derive(Deserialize)]
+struct Stamp {
+    ts: u64,
+}
+
+#[derive(Default)]
+struct StoreStats {
+    bytes: u64,
+    entries: u64,
+    /// Entries dropped by compaction since startup, for age or size.
+    dropped: u64,
+}
+
+struct StoreInner {
+    name: &'static str,
+    path: PathBuf,
+    max_age: Duration,
+    max_bytes: u64,
+    /// Serializes appends with compaction, which replaces the file.
+    stats: Mutex<StoreStats>,
+}
+
+/// Append-only JSON-lines file kept within a maximum age and size.
+///
+/// Compaction rewrites the file without the entries older than `max_age_hours`, then drops the
+/// oldest ones until it fits in `max_bytes`. It runs periodically and whenever an append pushes
+/// the file over `max_bytes`, so a sentinel left running for months on a small VM can't fill
+/// its disk.
+#[derive(Clone)]
+pub struct Store {
+    inner: Arc<StoreInner>,
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+impl Store {
+    /// Opens `<dir>/<name>.jsonl`, creating the directory if needed.
+    pub fn open(config: &StorageConfig, name: &'static str) -> Result<Self> {
+        fs::create_dir_all(&config.dir)
+            .with_context(|| format!("Failed to create storage dir {}", config.dir))?;
+        let store = Self {
+            inner: Arc::new(StoreInner {
+                name,
+                path: PathBuf::from(&config.dir).join(format!("{name}.jsonl")),
+                max_age: Duration::from_secs(config.max_age_hours * 3600),
+                max_bytes: config.max_bytes,
+                stats: Mutex::new(StoreStats::default()),
+            }),
+        };
+        store.compact().with_context(|| format!("Failed to compact {name} store"))?;
+        Ok(store)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.inner.name
+    }
+
+    /// Appends `data` stamped with the current time. Errors are logged, since losing a history
+    /// entry must never stop monitoring.
+    pub fn append<T: Serialize>(&self, data: T) {
+        if let Err(e) = self.try_append(data) {
+            eprintln!("Failed to append to {} store: {e:?}", self.inner.name);
+        }
+    }
+
+    fn try_append<T: Serialize>(&self, data: T) -> Result<()> {
+        let mut line = serde_json::to_vec(&Record { ts: unix_now(), data })?;
+        line.push(b'\n');
+        let over_limit = {
+            let mut stats = self.inner.stats.lock().unwrap();
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.inner.path)?
+                .write_all(&line)?;
+            stats.bytes += line.len() as u64;
+            stats.entries += 1;
+            stats.bytes > self.inner.max_bytes
+        };
+        if over_limit {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Entries written at or after unix time `since`, oldest first, with their timestamps.
+    /// Lines that don't parse as `T` are skipped.
+    pub fn load<T: DeserializeOwned>(&self, since: u64) -> Vec<(u64, T)> {
+        let _stats = self.inner.stats.lock().unwrap();
+        let Ok(content) = fs::read_to_string(&self.inner.path) else { return Vec::new() };
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Record<T>>(line).ok())
+            .filter(|record| record.ts >= since)
+            .map(|record| (record.ts, record.data))
+            .collect()
+    }
+
+    /// Rewrites the file within the retention limits and returns the number of entries dropped.
+    pub fn compact(&self) -> Result<usize> {
+        let mut stats = self.inner.stats.lock().unwrap();
+        let path = &self.inner.path;
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let cutoff = unix_now().saturating_sub(self.inner.max_age.as_secs());
+        let mut kept: Vec<&str> = content
+            .lines()
+            .filter(|line| {
+                serde_json::from_str::<Stamp>(line).is_ok_and(|stamp| stamp.ts >= cutoff)
+            })
+            .collect();
+        // Size limit: drop the oldest entries, leaving headroom so the next append doesn't
+        // trigger another compaction right away.
+        let target = self.inner.max_bytes - self.inner.max_bytes / 4;
+        let mut bytes: u64 = kept.iter().map(|line| line.len() as u64 + 1).sum();
+        let mut skip = 0;
+        if bytes > self.inner.max_bytes {
+            while bytes > target && skip < kept.len() {
+                bytes -= kept[skip].len() as u64 + 1;
+                skip += 1;
+            }
+        }
+        kept.drain(..skip);
+
+        let dropped = content.lines().count() - kept.len();
+        if dropped > 0 {
+            let mut out = String::with_capacity(bytes as usize);
+            for line in &kept {
+                out.push_str(line);
+                out.push('\n');
+            }
+            let tmp_path = path.with_extension("jsonl.tmp");
+            fs::write(&tmp_path, out)?;
+            fs::rename(&tmp_path, path)?;
+        }
+        stats.bytes = bytes;
+        stats.entries = kept.len() as u64;
+        stats.dropped += dropped as u64;
+        Ok(dropped)
+    }
+}
+
+/// Appends the size metrics of `stores` in the Prometheus text format.
+pub fn render_metrics(stores: &[Store], out: &mut String) {
+    let families: [(&str, &str, &str, fn(&StoreStats) -> u64); 3] = [
+        ("sentinel_store_bytes", "gauge", "Size of a persisted store on disk.", |s| s.bytes),
+        ("sentinel_store_entries", "gauge", "Entries in a persisted store.", |s| s.entries),
+        (
+            "sentinel_store_dropped_total",
+            "counter",
+            "Entries dropped by compaction for age or size.",
+            |s| s.dropped,
+        ),
+    ];
+    for (metric, kind, help, value) in families {
+        let _ = writeln!(out, "# HELP {metric} {help}");
+        let _ = writeln!(out, "# TYPE {metric} {kind}");
+        for store in stores {
+            let stats = store.inner.stats.lock().unwrap();
+            let _ = writeln!(out, "{metric}{{store=\"{}\"}} {}", store.inner.name, value(&stats));
+        }
+    }
+}
+
+/// Compacts `stores` every `interval`.
+pub async fn run_compaction(stores: Vec<Store>, interval: Duration) {
+    let mut timer = time::interval(interval);
+    timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        timer.tick().await;
+        for store in &stores {
+            match store.compact() {
+                Ok(0) => {}
+                Ok(dropped) => {
+                    println!("Compacted {} store, dropped {dropped} entries", store.name())
+                }
+                Err(e) => eprintln!("Failed to compact {} store: {e:?}", store.name()),
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{config::ValidatorChurnConfig, health::HealthCheck, notifier::Notifier};
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::{self, MissedTickBehavior};
+
+/// Version prefix of the gravity API paths called here.
+const API_V1: &str = "/v1";
+
+/// Fragment of the gravity API `/consensus/validator_churn` response.
+#[derive(Deserialize)]
+struct ChurnResp {
+    latest_epoch: u64,
+    /// Most recent transition first.
+    transitions: Vec<EpochChurn>,
+}
+
+#[derive(Deserialize)]
+struct EpochChurn {
+    epoch: u64,
+    validator_count: usize,
+    joined: Vec<String>,
+    left: Vec<String>,
+    turnover_ratio: f64,
+}
+
+impl EpochChurn {
+    /// Why this transition exceeds the configured thresholds, if it does.
+    fn violation(&self, config: &ValidatorChurnConfig) -> Option<String> {
+        let changes = self.joined.len() + self.left.len();
+        if changes > config.max_changes_per_epoch {
+            Some(format!(
+                "{changes} validators joined or left (max {})",
+                config.max_changes_per_epoch
+            ))
+        } else if self.turnover_ratio > config.max_turnover_ratio {
+            Some(format!(
+                "{:.1}% of voting power changed hands (max {:.1}%)",
+                self.turnover_ratio * 100.0,
+                config.max_turnover_ratio * 100.0
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Alerts when an epoch change replaces more of the validator set than usual. Sudden churn is an
+/// early indicator of network-level problems (mass slashing, a bad release, a cloud outage).
+pub struct ValidatorChurnMonitor {
+    config: ValidatorChurnConfig,
+    client: Client,
+    notifier: Notifier,
+    health: HealthCheck,
+}
+
+impl ValidatorChurnMonitor {
+    pub fn new(config: ValidatorChurnConfig, notifier: Notifier, health: HealthCheck) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self { config, client, notifier, health }
+    }
+
+    pub fn tag(&self) -> String {
+        self.config.tag.clone().unwrap_or_else(|| self.config.api_base.clone())
+    }
+
+    async fn fetch_churn(&self) -> Result<ChurnResp> {
+        let url = format!(
+            "{}{API_V1}/consensus/validator_churn?epochs={}",
+            self.config.api_base.trim_end_matches('/'),
+            self.config.epochs
+        );
+        Ok(self.client.get(&url).send().await?.error_for_status()?.json().await?)
+    }
+
+    pub async fn run(self) {
+        let tag = self.tag();
+        let mut timer = time::interval(Duration::from_secs(self.config.poll_interval_seconds));
+        timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        // Newest epoch already evaluated; transitions are alerted once, and the ones present at
+        // startup only seed this so a restart does not replay old alerts.
+        let mut last_seen_epoch: Option<u64> = None;
+        let mut api_failures: u32 = 0;
+
+        println!("Starting validator churn monitor for {tag}");
+        loop {
+            timer.tick().await;
+            let churn = match self.fetch_churn().await {
+                Ok(churn) => {
+                    api_failures = 0;
+                    churn
+                }
+                Err(e) => {
+                    api_failures += 1;
+                    eprintln!("Validator churn API error for {tag} ({api_failures}): {e}");
+                    if api_failures >= 3 {
+                        self.health.fail(format!("API unreachable: {e}"));
+                    }
+                    continue;
+                }
+            };
+
+            match churn.transitions.first().and_then(|t| t.violation(&self.config)) {
+                Some(reason) => self.health.fail(format!("epoch {}: {reason}", churn.latest_epoch)),
+                None => self.health.pass(),
+            }
+
+            let Some(seen) = last_seen_epoch.replace(churn.latest_epoch) else { continue };
+            for transition in churn.transitions.iter().rev().filter(|t| t.epoch > seen) {
+                let Some(reason) = transition.violation(&self.config) else { continue };
+                let msg = format!(
+                    "Validator set churn on {tag} at epoch {}\n  \
+                     {reason}\n  \
+                     validators: {} (+{} / -{})\n  \
+                     joined: {}\n  left: {}",
+                    transition.epoch,
+                    transition.validator_count,
+                    transition.joined.len(),
+                    transition.left.len(),
+                    list(&transition.joined),
+                    list(&transition.left),
+                );
+                println!("TRIGGERING ALERT: {msg}");
+                if let Err(e) =
+                    self.notifier.alert(&msg, "VALIDATOR_CHURN", self.config.priority).await
+                {
+                    eprintln!("Failed to send validator churn alert: {e:?}");
+                }
+            }
+        }
+    }
+}
+
+/// At most a handful of addresses, so a mass exit doesn't produce an unreadable alert.
+fn list(addresses: &[String]) -> String {
+    const SHOWN: usize = 5;
+    match addresses.len() {
+        0 => "-".to_string(),
+        n if n <= SHOWN => addresses.join(", "),
+        n => format!("{}, ... ({} more)", addresses[..SHOWN].join(", "), n - SHOWN),
+    }
+}
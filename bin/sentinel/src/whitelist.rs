@@ -125,9 +125,30 @@ impl Whitelist {
         Ok(Self { rules })
     }
 
+    /// Drops the frequency counters of sources with no matching line within the window, e.g.
+    /// rotated log files, so they don't pile up over a long run. Returns how many were dropped.
+    pub fn gc(&mut self) -> usize {
+        let now = Instant::now();
+        let mut dropped = 0;
+        for rule in &mut self.rules {
+            let before = rule.timestamps.len();
+            rule.timestamps.retain(|_, ts| {
+                ts.back().is_some_and(|last| now.duration_since(*last).as_secs() <= WINDOW_SECONDS)
+            });
+            dropped += before - rule.timestamps.len();
+        }
+        dropped
+    }
+
     /// Check a log line against whitelist rules.
     /// Frequency thresholds are counted per source file path.
     pub fn check(&mut self, line: &str, source: &Path) -> CheckResult {
+        self.check_at(line, source, Instant::now())
+    }
+
+    /// Same as [`Whitelist::check`], with the line observed at `now`. Used to replay
+    /// historical logs on their own clock.
+    pub fn check_at(&mut self, line: &str, source: &Path, now: Instant) -> CheckResult {
         for rule in &mut self.rules {
             if rule.matches(line) {
                 // Always skip if threshold is -1
@@ -135,7 +156,6 @@ impl Whitelist {
                     return CheckResult::Skip;
                 }
 
-                let now = Instant::now();
                 let ts = match rule.timestamps.get_mut(source) {
                     Some(ts) => ts,
                     None => rule.timestamps.entry(source.to_path_buf()).or_default(),
@@ -154,14 +154,32 @@ if p.exists():
     log_info "Step 3: Generating genesis from contract..."
     ABS_VAL_GENESIS_PATH="$(cd "$(dirname "$val_genesis_path")" && pwd)/$(basename "$val_genesis_path")"
     
-    # Prepare genesis template (inject faucet if present)
+    # Prepare genesis template (inject faucet and [genesis] allocations_file if present)
     local genesis_template="$GENESIS_CONTRACT_DIR/genesis-tool/config/genesis_template.json"
     local faucet_alloc="$GEN_CONFIG_DIR/faucet_alloc.json"
     local final_template="$GEN_CONFIG_DIR/genesis_template_merged.json"
-    
+    local allocations_file
+    allocations_file=$(echo "$config_json" | jq -r '.genesis.allocations_file // empty')
+    local alloc_args=()
+
     if [ -f "$faucet_alloc" ]; then
-        log_info "Injecting faucet allocation into template..."
-        jq -s '.[0] * {alloc: (.[0].alloc + .[1])}' "$genesis_template" "$faucet_alloc" > "$final_template"
+        alloc_args+=(--allocations-file "$faucet_alloc")
+    fi
+    if [ -n "$allocations_file" ]; then
+        # Relative paths are resolved against the genesis config file
+        if [[ "$allocations_file" != /* ]]; then
+            allocations_file="$(cd "$(dirname "$GENESIS_CONFIG_FILE")" && pwd)/$allocations_file"
+        fi
+        if [ ! -f "$allocations_file" ]; then
+            log_error "Allocations file not found: $allocations_file"
+            exit 1
+        fi
+        alloc_args+=(--allocations-file "$allocations_file")
+    fi
+    if [ ${#alloc_args[@]} -gt 0 ]; then
+        log_info "Injecting genesis allocations into template..."
+        "$GRAVITY_CLI" genesis allocate --template "$genesis_template" \
+            --output-file "$final_template" "${alloc_args[@]}"
         # Copy merged template to the expected location
         cp "$final_template" "$genesis_template"
     fi
@@ -248,6 +248,8 @@ def main():
     shadow_lookup = {sn['id']: sn for sn in config.get('shadow_nodes', [])}
 
     validators = []
+    # Markers of identities derived from `gravity_cli genesis generate-key --deterministic-seed`
+    insecure_markers = set()
 
     for node in genesis_nodes:
         node_id = node['id']
@@ -265,6 +267,9 @@ def main():
             sys.exit(1)
 
         identity = parse_simple_yaml(identity_path)
+        if identity.get('insecure'):
+            insecure_markers.add(identity['insecure'])
+            print(f"Warning: {node_id}: identity in {identity_path} is marked insecure")
 
         # Validation
         required_keys = ['account_address', 'consensus_public_key', 'network_public_key']
@@ -410,6 +415,10 @@ def main():
     # Build complete genesis config (matching GenesisConfig struct in genesis.rs)
     output = build_genesis_config(config, genesis_cfg)
     output["validators"] = validators
+    if insecure_markers:
+        # Carried into the genesis config so the artifacts built from it (e.g. the waypoint)
+        # cannot be produced for a real network by mistake.
+        output["insecure"] = sorted(insecure_markers)[0]
     
     # Write to validator_genesis.json in output_dir
     output_path = os.path.join(output_dir, "validator_genesis.json")
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
-use crate::network::extract_network_ids;
+use crate::{https::ApiConfig, network::extract_network_ids};
 use aptos_consensus::{
     consensusdb::{BlockNumberSchema, ConsensusDB},
     gravity_state_computer::ConsensusAdapterArgs,
@@ -70,6 +70,20 @@ pub fn check_bootstrap_config(node_config_path: Option<PathBuf>) -> NodeConfig {
     })
 }
 
+/// Loads the HTTP API config at `api_config_path`, or the defaults when no file is given.
+pub fn load_api_config(api_config_path: Option<PathBuf>) -> ApiConfig {
+    let Some(config_path) = api_config_path else {
+        return ApiConfig::default();
+    };
+    ApiConfig::load(&config_path).unwrap_or_else(|error| {
+        panic!(
+            "Failed to load the API config file! Given file path: {:?}. Error: {:?}",
+            config_path.display(),
+            error
+        )
+    })
+}
+
 pub fn dkg_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
     let direct_send_protocols: Vec<ProtocolId> =
         gaptos::aptos_dkg_runtime::network_interface::DIRECT_SEND.into();
@@ -1,4 +1,4 @@
-use std::{path::PathBuf, sync::Arc};
+use std::sync::Arc;
 
 use crate::{
     bootstrap::{
@@ -7,7 +7,10 @@ use crate::{
         start_node_inspection_service,
     },
     consensus_mempool_handler::{ConsensusToMempoolHandler, MempoolNotificationHandler},
-    https::https_server,
+    grpc::grpc_server,
+    https::{
+        cold_tier::ColdTier, AdmissionHook, ApiConfig, HttpsServer, HttpsServerBuilder, TxSubmitter,
+    },
     logger,
     network::{
         consensus_network_configuration, create_network_interfaces, create_network_runtime,
@@ -16,7 +19,11 @@ use crate::{
         ApplicationNetworkHandle,
     },
 };
-use aptos_consensus::{consensusdb::ConsensusDB, gravity_state_computer::ConsensusAdapterArgs};
+use aptos_consensus::{
+    consensusdb::ConsensusDB, gravity_state_computer::ConsensusAdapterArgs,
+    quorum_store::quorum_store_db::QuorumStoreDB,
+};
+use axum::Router;
 use block_buffer_manager::TxPool;
 use build_info::build_information;
 use futures::channel::mpsc;
@@ -25,7 +32,7 @@ use gaptos::{
     aptos_config::config::{NodeConfig, RoleType},
     aptos_dkg_runtime::DKGMessage,
     aptos_event_notifications::EventNotificationSender,
-    aptos_logger::{info, warn},
+    aptos_logger::{error, info, warn},
     aptos_network_builder::builder::NetworkBuilder,
     aptos_storage_interface::DbReaderWriter,
     aptos_telemetry::service::start_telemetry_service,
@@ -73,37 +80,30 @@ fn fail_point_check(node_config: &NodeConfig) {
     }
 }
 
-struct HttpsServerConfig {
-    address: String,
-    cert_pem: Option<PathBuf>,
-    key_pem: Option<PathBuf>,
-    consensus_db: Option<Arc<ConsensusDB>>,
-}
-
-fn prepare_https_server_config(
+/// The API server configured from the node config, or `None` when it has no address.
+fn https_server_builder(
     node_config: &NodeConfig,
     consensus_db: Arc<ConsensusDB>,
-) -> HttpsServerConfig {
-    let consensus_db_clone = Some(consensus_db);
-    let cert_pem = node_config
-        .https_cert_pem_path
-        .clone()
-        .to_str()
-        .filter(|s| !s.is_empty())
-        .map(|_| node_config.https_cert_pem_path.clone());
-    let key_pem = node_config
-        .https_key_pem_path
-        .clone()
-        .to_str()
-        .filter(|s| !s.is_empty())
-        .map(|_| node_config.https_key_pem_path.clone());
-
-    HttpsServerConfig {
-        address: node_config.https_server_address.clone(),
-        cert_pem,
-        key_pem,
-        consensus_db: consensus_db_clone,
+    quorum_store_db: Arc<QuorumStoreDB>,
+    tx_submitter: Arc<TxSubmitter>,
+) -> Option<HttpsServerBuilder> {
+    if node_config.https_server_address.is_empty() {
+        return None;
     }
+    let mut builder = HttpsServer::builder(node_config.https_server_address.clone())
+        .consensus_db(consensus_db)
+        .quorum_store_db(quorum_store_db)
+        .tx_submitter(tx_submitter)
+        .log_file(node_config.log_file_path.clone())
+        .jobs_dir(node_config.storage.dir().join("api_jobs"))
+        .route_usage_file(node_config.storage.dir().join("api_route_usage.json"))
+        .audit_log_file(node_config.storage.dir().join("api_admin_audit.jsonl"));
+    let cert_pem = &node_config.https_cert_pem_path;
+    let key_pem = &node_config.https_key_pem_path;
+    if !cert_pem.as_os_str().is_empty() && !key_pem.as_os_str().is_empty() {
+        builder = builder.tls_files(cert_pem.clone(), key_pem.clone());
+    }
+    Some(builder)
 }
 
 pub struct ConsensusEngineArgs {
@@ -111,12 +111,23 @@ pub struct ConsensusEngineArgs {
     pub chain_id: u64,
     pub latest_block_number: u64,
     pub config_storage: Option<Arc<dyn ConfigStorage>>,
+    /// Application routes served by the node's HTTP API next to its own, see
+    /// [`HttpsServerBuilder::merge`].
+    pub api_routes: Option<Router>,
+    /// Settings of the node's HTTP and gRPC API, from its `--api_config` file.
+    pub api_config: ApiConfig,
 }
 
 impl ConsensusEngine {
     pub async fn init(args: ConsensusEngineArgs, pool: Box<dyn TxPool>) -> Arc<Self> {
-        let ConsensusEngineArgs { node_config, chain_id, latest_block_number, config_storage } =
-            args;
+        let ConsensusEngineArgs {
+            node_config,
+            chain_id,
+            latest_block_number,
+            config_storage,
+            api_routes,
+            api_config,
+        } = args;
         // Setup panic handler
         gaptos::aptos_crash_handler::setup_panic_handler();
 
@@ -150,6 +161,14 @@ impl ConsensusEngine {
                 }
             }
         }
+        // Shared with the HTTP API so it can submit transactions into the same pool.
+        let pool: Arc<dyn TxPool> = Arc::from(pool);
+        #[cfg(debug_assertions)]
+        let tx_submitter = Arc::new(TxSubmitter::new(
+            pool.clone(),
+            chain_id,
+            api_config.admission_webhook.as_ref().map(AdmissionHook::from_config),
+        ));
         let chain_id = ChainId::from(chain_id);
         let network_configs = extract_network_configs(&node_config);
 
@@ -290,7 +309,7 @@ impl ConsensusEngine {
             consensus_to_mempool_receiver,
             mempool_listener,
             peers_and_metadata,
-            pool,
+            Box::new(pool),
         );
         runtimes.extend(mempool_runtime);
 
@@ -319,8 +338,24 @@ impl ConsensusEngine {
         init_block_buffer_manager(&consensus_db, latest_block_number)
             .await
             .expect("failed to initialize BlockBufferManager");
+        // Old epochs go to the cold tier, if one is configured. It is installed before consensus
+        // starts, so block retrieval reads the epochs already moved out.
+        if let Some(config) = &api_config.cold_tier {
+            match ColdTier::from_config(config, &consensus_db).map(Arc::new).and_then(|cold_tier| {
+                cold_tier.install(&consensus_db)?;
+                Ok(cold_tier)
+            }) {
+                Ok(cold_tier) => {
+                    let runtime =
+                        gaptos::aptos_runtimes::spawn_named_runtime("ColdTier".into(), None);
+                    runtime.spawn(cold_tier.run(consensus_db.clone()));
+                    runtimes.push(runtime);
+                }
+                Err(e) => error!("Cold tier disabled: {e:?}"),
+            }
+        }
         let mut args = ConsensusAdapterArgs::new(consensus_db.clone());
-        let (consensus_runtime, _, _) = start_consensus(
+        let (consensus_runtime, _, quorum_store_db) = start_consensus(
             &node_config,
             &mut event_subscription_service,
             consensus_interfaces,
@@ -341,6 +376,8 @@ impl ConsensusEngine {
             mempool_notification_handler,
             consensus_listener,
             event_subscription_service.clone(),
+            consensus_db.clone(),
+            api_config.tx_index.clone(),
         );
         let runtime = gaptos::aptos_runtimes::spawn_named_runtime("Con2Mempool".into(), None);
         runtime.spawn(async move {
@@ -352,21 +389,42 @@ impl ConsensusEngine {
         // Gate the entire server behind debug_assertions so it is not started in release builds.
         #[cfg(debug_assertions)]
         {
-            let https_config = prepare_https_server_config(&node_config, consensus_db.clone());
-            if !https_config.address.is_empty() {
+            crate::https::node_config::register_node_config(&node_config);
+            if let Some(mut builder) = https_server_builder(
+                &node_config,
+                consensus_db.clone(),
+                quorum_store_db.clone(),
+                tx_submitter.clone(),
+            ) {
+                builder = builder.config(api_config.clone());
+                if let Some(api_routes) = api_routes {
+                    builder = builder.merge(api_routes);
+                }
+                let server = builder.build();
                 let runtime = gaptos::aptos_runtimes::spawn_named_runtime("Http".into(), None);
                 runtime.spawn(async move {
-                    https_server(
-                        https_config.address,
-                        https_config.cert_pem,
-                        https_config.key_pem,
-                        https_config.consensus_db,
-                    )
-                    .await
+                    if let Err(e) = server.serve().await {
+                        error!("HTTP API server stopped: {e:?}");
+                    }
+                });
+                runtimes.push(runtime);
+            }
+            // The gRPC mirror of the API is opt-in through the API config.
+            if let Some(grpc_address) = api_config.grpc_address.clone() {
+                let consensus_db = consensus_db.clone();
+                let runtime = gaptos::aptos_runtimes::spawn_named_runtime("Grpc".into(), None);
+                runtime.spawn(async move {
+                    if let Err(e) =
+                        grpc_server(grpc_address, consensus_db, quorum_store_db, tx_submitter).await
+                    {
+                        error!("gRPC API server stopped: {e:?}");
+                    }
                 });
                 runtimes.push(runtime);
             }
         }
+        #[cfg(not(debug_assertions))]
+        drop((api_routes, api_config, quorum_store_db));
         let arc_consensus_engine = Arc::new(Self { runtimes });
         // process new round should be after init retƒh hash
         info!("pass latest_block_number: {:?} to event_subscription_service", latest_block_number);
@@ -3,6 +3,7 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
+use aptos_consensus::consensusdb::{CommittedTxn, ConsensusDB};
 use futures::StreamExt;
 use gaptos::{
     aptos_consensus_notifications::{
@@ -15,6 +16,8 @@ use gaptos::{
 };
 use tokio::sync::Mutex;
 
+use crate::https::{TxIndexConfig, TX_COMMIT_WAITERS, TX_RESULTS};
+
 /// A simple handler for sending notifications to mempool
 #[derive(Clone)]
 pub struct MempoolNotificationHandler<M: MempoolNotificationSender> {
@@ -49,6 +52,10 @@ pub struct ConsensusToMempoolHandler<M: MempoolNotificationSender> {
     mempool_notification_handler: MempoolNotificationHandler<M>,
     consensus_notification_listener: ConsensusNotificationListener,
     event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+    consensus_db: Arc<ConsensusDB>,
+    tx_index: TxIndexConfig,
+    /// Blocks below this one have had their index entries pruned since the node started.
+    tx_index_pruned_below: u64,
 }
 
 impl<M: MempoolNotificationSender> ConsensusToMempoolHandler<M> {
@@ -56,11 +63,36 @@ impl<M: MempoolNotificationSender> ConsensusToMempoolHandler<M> {
         mempool_notification_handler: MempoolNotificationHandler<M>,
         consensus_notification_listener: ConsensusNotificationListener,
         event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+        consensus_db: Arc<ConsensusDB>,
+        tx_index: TxIndexConfig,
     ) -> Self {
         Self {
             mempool_notification_handler,
             consensus_notification_listener,
             event_subscription_service,
+            consensus_db,
+            tx_index,
+            tx_index_pruned_below: 0,
+        }
+    }
+
+    /// Indexes the user transactions of `block_number` for `/tx/list`, if enabled, and prunes
+    /// the blocks that fell out of the retention window.
+    fn index_committed_txns(&mut self, block_number: u64, txns: &[CommittedTxn]) {
+        if !self.tx_index.enabled {
+            return;
+        }
+        if let Err(e) = self.consensus_db.put_committed_txns(block_number, txns) {
+            warn!("Failed to index committed transactions of block {}: {:?}", block_number, e);
+        }
+        let keep_from =
+            block_number.saturating_sub(self.tx_index.retention_blocks.saturating_sub(1));
+        if keep_from <= self.tx_index_pruned_below {
+            return;
+        }
+        match self.consensus_db.prune_committed_txns(self.tx_index_pruned_below, keep_from) {
+            Ok(_) => self.tx_index_pruned_below = keep_from,
+            Err(e) => warn!("Failed to prune committed transactions below {}: {:?}", keep_from, e),
         }
     }
 
@@ -71,6 +103,28 @@ impl<M: MempoolNotificationSender> ConsensusToMempoolHandler<M> {
     ) -> anyhow::Result<()> {
         // Handle the commit notification
         let committed_transactions = consensus_commit_notification.get_transactions().clone();
+        let block_number = consensus_commit_notification.get_block_number();
+        // Indexed before waiters are woken, so `/tx/list` already shows what they waited for.
+        let user_txns: Vec<CommittedTxn> = committed_transactions
+            .iter()
+            .filter_map(|txn| match txn {
+                Transaction::UserTransaction(txn) => Some(CommittedTxn {
+                    hash: txn.committed_hash(),
+                    sender: txn.sender(),
+                    sequence_number: txn.sequence_number(),
+                }),
+                _ => None,
+            })
+            .collect();
+        self.index_committed_txns(block_number, &user_txns);
+        TX_RESULTS.record_committed(block_number, user_txns.iter().map(|txn| txn.hash));
+        TX_COMMIT_WAITERS.notify_committed(
+            block_number,
+            committed_transactions.iter().filter_map(|txn| match txn {
+                Transaction::UserTransaction(txn) => Some(txn.committed_hash()),
+                _ => None,
+            }),
+        );
 
         // TODO(gravity_byteyue): ideally the block timestamp should come from
         // ConsensusCommitNotification rather than the local wall clock. For now, use
@@ -81,7 +135,6 @@ impl<M: MempoolNotificationSender> ConsensusToMempoolHandler<M> {
                 SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros() as u64,
             )
             .await?;
-        let block_number = consensus_commit_notification.get_block_number();
         let events = consensus_commit_notification.get_subscribable_events().clone();
         let mut event_subscription_service = self.event_subscription_service.lock().await;
         if let Err(error) = event_subscription_service.notify_events(block_number, events) {
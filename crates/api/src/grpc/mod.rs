@@ -0,0 +1,194 @@
+//! gRPC mirror of the HTTP API, for internal services that want typed and streaming access.
+//!
+//! The server is enabled by setting `grpc_address` in the API config (e.g. `127.0.0.1:50051`).
+
+pub mod proto;
+
+use crate::https::{
+    consensus::{get_block_by_round, get_qc_by_round},
+    error::{ApiError, ErrorResponse},
+    lookup_tx, tx_hash, TxSubmitter,
+};
+use aptos_consensus::{consensusdb::ConsensusDB, quorum_store::quorum_store_db::QuorumStoreDB};
+use futures::{stream, Stream};
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_logger::{error, info},
+    aptos_storage_interface::DbReader,
+};
+use proto::{
+    BlockInfo, CommitEvent, CommitEventsRequest, GetBlockRequest, GetQcRequest, GetTxByHashRequest,
+    GetTxByHashResponse, GravityApi, GravityApiServer, QcInfo, SubmitTxRequest, SubmitTxResponse,
+};
+use std::{net::SocketAddr, pin::Pin, sync::Arc};
+use tonic::{Code, Request, Response, Status};
+
+pub struct GravityApiService {
+    consensus_db: Arc<ConsensusDB>,
+    /// Holds the batches committed transactions are read back from.
+    quorum_store_db: Arc<QuorumStoreDB>,
+    /// The submitter behind `/tx/submit_tx`, so both APIs feed the same pool and admission policy.
+    tx_submitter: Arc<TxSubmitter>,
+}
+
+/// Maps an HTTP API error onto the closest gRPC status, keeping its message.
+fn status_from(error: ApiError) -> Status {
+    let code = match error.status().as_u16() {
+        400 => Code::InvalidArgument,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 => Code::NotFound,
+        409 | 422 => Code::FailedPrecondition,
+        429 => Code::ResourceExhausted,
+        501 => Code::Unimplemented,
+        503 => Code::Unavailable,
+        504 => Code::DeadlineExceeded,
+        _ => Code::Internal,
+    };
+    let response = ErrorResponse::from(error);
+    Status::new(code, format!("{}: {}", response.code, response.message))
+}
+
+impl GravityApiService {
+    pub fn new(
+        consensus_db: Arc<ConsensusDB>,
+        quorum_store_db: Arc<QuorumStoreDB>,
+        tx_submitter: Arc<TxSubmitter>,
+    ) -> Self {
+        Self { consensus_db, quorum_store_db, tx_submitter }
+    }
+}
+
+fn latest_commit_event(consensus_db: &ConsensusDB) -> Result<CommitEvent, Status> {
+    let info = DbReader::get_latest_ledger_info(consensus_db).map_err(|e| {
+        error!("Failed to get latest ledger info: {:?}", e);
+        Status::internal("Internal server error")
+    })?;
+    let ledger_info = info.ledger_info();
+    Ok(CommitEvent {
+        epoch: ledger_info.epoch(),
+        round: ledger_info.round(),
+        block_number: ledger_info.block_number(),
+        block_hash: hex::encode(ledger_info.block_hash().as_ref()),
+    })
+}
+
+#[async_trait::async_trait]
+impl GravityApi for GravityApiService {
+    async fn submit_tx(
+        &self,
+        request: Request<SubmitTxRequest>,
+    ) -> Result<Response<SubmitTxResponse>, Status> {
+        let tx = request.into_inner().tx;
+        let hash = tx_hash(&tx);
+        self.tx_submitter.add(hash, tx).await.map_err(status_from)?;
+        info!("submitted transaction {} over gRPC", hash);
+        Ok(Response::new(SubmitTxResponse { hash: hash.to_vec() }))
+    }
+
+    async fn get_tx_by_hash(
+        &self,
+        request: Request<GetTxByHashRequest>,
+    ) -> Result<Response<GetTxByHashResponse>, Status> {
+        let hash = HashValue::from_hex(request.into_inner().hash.trim_start_matches("0x"))
+            .map_err(|e| Status::invalid_argument(format!("Invalid hash: {e}")))?;
+        info!("get transaction by hash {}", hash);
+        let tx = lookup_tx(&hash, Some(&self.consensus_db), Some(&self.quorum_store_db));
+        Ok(Response::new(GetTxByHashResponse {
+            tx: tx.tx,
+            status: tx.status.map(|status| status.as_str().to_string()),
+            block_number: tx.block_number,
+        }))
+    }
+
+    async fn get_block(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> Result<Response<BlockInfo>, Status> {
+        let GetBlockRequest { epoch, round } = request.into_inner();
+        let block =
+            get_block_by_round(&self.consensus_db, epoch, round, false).ok_or_else(|| {
+                Status::not_found(format!("Block not found for epoch={epoch}, round={round}"))
+            })?;
+        Ok(Response::new(BlockInfo {
+            epoch: block.epoch,
+            round: block.round,
+            block_number: block.block_number,
+            block_id: block.block_id,
+            parent_id: block.parent_id,
+        }))
+    }
+
+    async fn get_qc(&self, request: Request<GetQcRequest>) -> Result<Response<QcInfo>, Status> {
+        let GetQcRequest { epoch, round } = request.into_inner();
+        let qc = get_qc_by_round(&self.consensus_db, epoch, round).ok_or_else(|| {
+            Status::not_found(format!("QC not found for epoch={epoch}, round={round}"))
+        })?;
+        Ok(Response::new(QcInfo {
+            epoch: qc.epoch,
+            round: qc.round,
+            block_number: qc.block_number,
+            certified_block_id: qc.certified_block_id,
+            commit_info_block_id: qc.commit_info_block_id,
+        }))
+    }
+
+    type CommitEventsStream = Pin<Box<dyn Stream<Item = Result<CommitEvent, Status>> + Send>>;
+
+    async fn commit_events(
+        &self,
+        _request: Request<CommitEventsRequest>,
+    ) -> Result<Response<Self::CommitEventsStream>, Status> {
+        let commits = self.consensus_db.subscribe_commits();
+        let consensus_db = self.consensus_db.clone();
+        let events = stream::unfold((commits, consensus_db), |(mut commits, db)| async move {
+            // The stream ends when the DB (and with it the sender) is dropped.
+            commits.changed().await.ok()?;
+            let event = latest_commit_event(&db);
+            Some((event, (commits, db)))
+        });
+        Ok(Response::new(Box::pin(events)))
+    }
+}
+
+/// Serves the gRPC API on `address` until the process exits. An invalid address or a failure to
+/// serve is returned rather than taking the node down.
+pub async fn grpc_server(
+    address: String,
+    consensus_db: Arc<ConsensusDB>,
+    quorum_store_db: Arc<QuorumStoreDB>,
+    tx_submitter: Arc<TxSubmitter>,
+) -> anyhow::Result<()> {
+    let addr: SocketAddr = address
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid gRPC bind address '{address}': {e}"))?;
+    info!("grpc server listen address {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(GravityApiServer::new(GravityApiService::new(
+            consensus_db,
+            quorum_store_db,
+            tx_submitter,
+        )))
+        .serve(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to serve grpc due to {e:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn api_errors_map_to_grpc_codes() {
+        let status = status_from(ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "tx_rejected",
+            "Transaction was rejected by the pool",
+        ));
+        assert_eq!(status.code(), Code::FailedPrecondition);
+        assert_eq!(status.message(), "tx_rejected: Transaction was rejected by the pool");
+        assert_eq!(status_from(ApiError::bad_request("x")).code(), Code::InvalidArgument);
+        assert_eq!(status_from(ApiError::internal()).code(), Code::Internal);
+    }
+}
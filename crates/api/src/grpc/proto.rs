@@ -0,0 +1,253 @@
+//! Message types and server plumbing for `proto/gravity_api.proto`.
+//!
+//! This mirrors what `tonic-build` would generate, written out by hand so the crate builds without
+//! `protoc`. Field tags must match the `.proto` file.
+
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tonic::{
+    codegen::{empty_body, http, Body, BoxFuture, StdError},
+    server::{Grpc, NamedService, ServerStreamingService, UnaryService},
+    Request, Response, Status,
+};
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubmitTxRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub tx: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubmitTxResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub hash: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetTxByHashRequest {
+    #[prost(string, tag = "1")]
+    pub hash: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetTxByHashResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub tx: Vec<u8>,
+    #[prost(string, optional, tag = "2")]
+    pub status: Option<String>,
+    #[prost(uint64, optional, tag = "3")]
+    pub block_number: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetBlockRequest {
+    #[prost(uint64, tag = "1")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "2")]
+    pub round: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BlockInfo {
+    #[prost(uint64, tag = "1")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "2")]
+    pub round: u64,
+    #[prost(uint64, optional, tag = "3")]
+    pub block_number: Option<u64>,
+    #[prost(string, tag = "4")]
+    pub block_id: String,
+    #[prost(string, tag = "5")]
+    pub parent_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetQcRequest {
+    #[prost(uint64, tag = "1")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "2")]
+    pub round: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QcInfo {
+    #[prost(uint64, tag = "1")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "2")]
+    pub round: u64,
+    #[prost(uint64, optional, tag = "3")]
+    pub block_number: Option<u64>,
+    #[prost(string, tag = "4")]
+    pub certified_block_id: String,
+    #[prost(string, tag = "5")]
+    pub commit_info_block_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CommitEventsRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CommitEvent {
+    #[prost(uint64, tag = "1")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "2")]
+    pub round: u64,
+    #[prost(uint64, tag = "3")]
+    pub block_number: u64,
+    #[prost(string, tag = "4")]
+    pub block_hash: String,
+}
+
+/// Server-side handlers of the `gravity.api.v1.GravityApi` service.
+#[async_trait::async_trait]
+pub trait GravityApi: Send + Sync + 'static {
+    async fn submit_tx(
+        &self,
+        request: Request<SubmitTxRequest>,
+    ) -> Result<Response<SubmitTxResponse>, Status>;
+
+    async fn get_tx_by_hash(
+        &self,
+        request: Request<GetTxByHashRequest>,
+    ) -> Result<Response<GetTxByHashResponse>, Status>;
+
+    async fn get_block(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> Result<Response<BlockInfo>, Status>;
+
+    async fn get_qc(&self, request: Request<GetQcRequest>) -> Result<Response<QcInfo>, Status>;
+
+    type CommitEventsStream: futures::Stream<Item = Result<CommitEvent, Status>> + Send + 'static;
+
+    async fn commit_events(
+        &self,
+        request: Request<CommitEventsRequest>,
+    ) -> Result<Response<Self::CommitEventsStream>, Status>;
+}
+
+pub const SERVICE_NAME: &str = "gravity.api.v1.GravityApi";
+
+/// Adapts an async closure into a tonic unary handler.
+struct Unary<F>(F);
+
+impl<F, Req, Resp, Fut> UnaryService<Req> for Unary<F>
+where
+    F: FnMut(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<Resp>, Status>> + Send + 'static,
+{
+    type Response = Resp;
+    type Future = BoxFuture<Response<Resp>, Status>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        Box::pin((self.0)(request))
+    }
+}
+
+/// Adapts an async closure into a tonic server-streaming handler.
+struct Streaming<F>(F);
+
+impl<F, Req, Resp, S, Fut> ServerStreamingService<Req> for Streaming<F>
+where
+    F: FnMut(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<S>, Status>> + Send + 'static,
+    S: futures::Stream<Item = Result<Resp, Status>> + Send + 'static,
+{
+    type Response = Resp;
+    type ResponseStream = S;
+    type Future = BoxFuture<Response<S>, Status>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        Box::pin((self.0)(request))
+    }
+}
+
+/// Routes gRPC requests to a [`GravityApi`] implementation.
+pub struct GravityApiServer<T> {
+    inner: Arc<T>,
+}
+
+impl<T> GravityApiServer<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner: Arc::new(inner) }
+    }
+}
+
+impl<T> Clone for GravityApiServer<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T> NamedService for GravityApiServer<T> {
+    const NAME: &'static str = SERVICE_NAME;
+}
+
+impl<T, B> tonic::codegen::Service<http::Request<B>> for GravityApiServer<T>
+where
+    T: GravityApi,
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::BoxBody>;
+    type Error = std::convert::Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let inner = self.inner.clone();
+        match req.uri().path() {
+            "/gravity.api.v1.GravityApi/SubmitTx" => Box::pin(async move {
+                let method = Unary(move |r| {
+                    let inner = inner.clone();
+                    async move { inner.submit_tx(r).await }
+                });
+                Ok(Grpc::new(tonic::codec::ProstCodec::default()).unary(method, req).await)
+            }),
+            "/gravity.api.v1.GravityApi/GetTxByHash" => Box::pin(async move {
+                let method = Unary(move |r| {
+                    let inner = inner.clone();
+                    async move { inner.get_tx_by_hash(r).await }
+                });
+                Ok(Grpc::new(tonic::codec::ProstCodec::default()).unary(method, req).await)
+            }),
+            "/gravity.api.v1.GravityApi/GetBlock" => Box::pin(async move {
+                let method = Unary(move |r| {
+                    let inner = inner.clone();
+                    async move { inner.get_block(r).await }
+                });
+                Ok(Grpc::new(tonic::codec::ProstCodec::default()).unary(method, req).await)
+            }),
+            "/gravity.api.v1.GravityApi/GetQc" => Box::pin(async move {
+                let method = Unary(move |r| {
+                    let inner = inner.clone();
+                    async move { inner.get_qc(r).await }
+                });
+                Ok(Grpc::new(tonic::codec::ProstCodec::default()).unary(method, req).await)
+            }),
+            "/gravity.api.v1.GravityApi/CommitEvents" => Box::pin(async move {
+                let method = Streaming(move |r| {
+                    let inner = inner.clone();
+                    async move { inner.commit_events(r).await }
+                });
+                Ok(Grpc::new(tonic::codec::ProstCodec::default())
+                    .server_streaming(method, req)
+                    .await)
+            }),
+            _ => Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header("grpc-status", tonic::Code::Unimplemented as i32)
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .unwrap())
+            }),
+        }
+    }
+}
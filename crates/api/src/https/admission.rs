@@ -0,0 +1,151 @@
+//! Pre-admission hook for `/tx/submit_tx` and `/tx/submit_batch`.
+//!
+//! When the API config has an `admission_webhook`, every submitted transaction is summarized and
+//! POSTed to that endpoint before it reaches the pool, so compliance filters and custom spam
+//! rules can run in an external policy engine. The engine answers `{"allow": bool, "reason":
+//! "..."}`; a denied transaction is rejected with `403 tx_denied` and the engine's reason. When
+//! the engine can't be reached, times out or answers anything else, the transaction is admitted
+//! (`open`, the default) or rejected with `503` (`closed`) depending on its `fail_mode`.
+
+use crate::https::{config::AdmissionWebhookConfig, error::ApiError};
+use axum::http::StatusCode;
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_logger::{info, warn},
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailMode {
+    /// Admit the transaction when the policy endpoint fails.
+    #[default]
+    Open,
+    /// Reject the transaction when the policy endpoint fails.
+    Closed,
+}
+
+/// What the policy endpoint receives for each transaction.
+#[derive(Serialize, Debug)]
+pub struct TxSummary {
+    /// 0x-prefixed keccak hash of the encoded transaction.
+    pub hash: String,
+    pub chain_id: u64,
+    /// EIP-2718 type, 0 for legacy transactions.
+    pub tx_type: u8,
+    pub size: usize,
+    /// 0x-prefixed EIP-2718 encoding, for engines that decode the sender, target or calldata.
+    pub raw: String,
+}
+
+impl TxSummary {
+    pub fn new(hash: HashValue, chain_id: u64, bytes: &[u8]) -> Self {
+        // Typed transactions start with their type, legacy ones with an RLP list header.
+        let tx_type = bytes.first().copied().filter(|byte| *byte < 0x7f).unwrap_or(0);
+        Self {
+            hash: format!("0x{}", hex::encode(hash.as_ref())),
+            chain_id,
+            tx_type,
+            size: bytes.len(),
+            raw: format!("0x{}", hex::encode(bytes)),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct PolicyDecision {
+    allow: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+pub struct AdmissionHook {
+    url: String,
+    fail_mode: FailMode,
+    client: reqwest::Client,
+}
+
+impl AdmissionHook {
+    pub fn new(url: String, timeout: Duration, fail_mode: FailMode) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { url, fail_mode, client }
+    }
+
+    pub fn from_config(config: &AdmissionWebhookConfig) -> Self {
+        let timeout = Duration::from_millis(config.timeout_ms);
+        info!(
+            "Transaction admission webhook enabled: {}, timeout={:?}, fail_mode={:?}",
+            config.url, timeout, config.fail_mode
+        );
+        Self::new(config.url.clone(), timeout, config.fail_mode)
+    }
+
+    /// Asks the policy endpoint whether the transaction may enter the pool.
+    pub async fn check(&self, summary: &TxSummary) -> Result<(), ApiError> {
+        let decision = match self.request(summary).await {
+            Ok(decision) => decision,
+            Err(e) => return self.on_failure(summary, e),
+        };
+        if decision.allow {
+            return Ok(());
+        }
+        let reason = decision.reason.unwrap_or_else(|| "denied by admission policy".to_string());
+        info!("Transaction {} denied by admission policy: {}", summary.hash, reason);
+        Err(ApiError::new(StatusCode::FORBIDDEN, "tx_denied", reason))
+    }
+
+    async fn request(&self, summary: &TxSummary) -> Result<PolicyDecision, String> {
+        let response = self.client.post(&self.url).json(summary).send().await.map_err(|e| {
+            if e.is_timeout() {
+                "timed out".to_string()
+            } else {
+                e.to_string()
+            }
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("responded with {status}"));
+        }
+        response.json::<PolicyDecision>().await.map_err(|e| format!("invalid response: {e}"))
+    }
+
+    fn on_failure(&self, summary: &TxSummary, error: String) -> Result<(), ApiError> {
+        warn!(
+            "Admission webhook failed for {} ({}), failing {:?}",
+            summary.hash, error, self.fail_mode
+        );
+        match self.fail_mode {
+            FailMode::Open => Ok(()),
+            FailMode::Closed => Err(ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "admission_unavailable",
+                "Transaction admission policy is unavailable",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_typed_and_legacy_transactions() {
+        let typed = TxSummary::new(HashValue::zero(), 7, &[0x02, 0xf8, 0x01]);
+        assert_eq!((typed.tx_type, typed.size, typed.raw.as_str()), (2, 3, "0x02f801"));
+        assert_eq!(TxSummary::new(HashValue::zero(), 7, &[0xf8, 0x6c]).tx_type, 0);
+    }
+
+    #[test]
+    fn parses_fail_mode() {
+        let parse = |yaml| serde_yaml::from_str::<AdmissionWebhookConfig>(yaml);
+        let config = parse("url: http://policy\nfail_mode: closed\n").unwrap();
+        assert_eq!((config.fail_mode, config.timeout_ms), (FailMode::Closed, 500));
+        assert_eq!(parse("url: http://policy\n").unwrap().fail_mode, FailMode::Open);
+        assert!(parse("url: http://policy\nfail_mode: sometimes\n").is_err());
+    }
+}
@@ -0,0 +1,201 @@
+//! Source-address allowlist for the admin route group.
+//!
+//! Entries are CIDRs (`10.0.0.0/8`), bare IPs or hostnames. Hostnames are resolved at startup
+//! and re-resolved periodically, so a bastion host behind a DNS name keeps access when its
+//! address changes. With no entries configured every client is allowed.
+
+use crate::https::error::ApiError;
+use arc_swap::ArcSwap;
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use gaptos::aptos_logger::{info, warn};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Paths under which the admin route group is served; API tokens never cover them.
+const ADMIN_ROUTE_PREFIXES: &[&str] =
+    &["/admin", "/set_failpoint", "/mem_prof", "/cpu_prof", "/debug"];
+
+/// Whether `route` belongs to the admin route group.
+pub(crate) fn is_admin_route(route: &str) -> bool {
+    ADMIN_ROUTE_PREFIXES.iter().any(|prefix| route.starts_with(prefix))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Parses `addr/len` or a bare address (a single-host network).
+    pub fn parse(s: &str) -> Option<Self> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (addr.parse::<IpAddr>().ok()?, Some(len.parse::<u8>().ok()?)),
+            None => (s.parse::<IpAddr>().ok()?, None),
+        };
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_len);
+        (prefix_len <= max_len).then_some(Self { network: addr, prefix_len })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct AdminAllowlist {
+    cidrs: Vec<Cidr>,
+    hostnames: Vec<String>,
+    /// Last successful resolution of each hostname.
+    resolved: ArcSwap<HashMap<String, Vec<IpAddr>>>,
+    refresh_interval: Duration,
+}
+
+impl AdminAllowlist {
+    pub fn new(entries: &[String], refresh_interval: Duration) -> Self {
+        let (mut cidrs, mut hostnames) = (vec![], vec![]);
+        for entry in entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            match Cidr::parse(entry) {
+                Some(cidr) => cidrs.push(cidr),
+                None => hostnames.push(entry.to_string()),
+            }
+        }
+        Self { cidrs, hostnames, resolved: ArcSwap::default(), refresh_interval }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.cidrs.is_empty() || !self.hostnames.is_empty()
+    }
+
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+        let ip = ip.to_canonical();
+        self.cidrs.iter().any(|cidr| cidr.contains(ip)) ||
+            self.resolved.load().values().flatten().any(|resolved| resolved.to_canonical() == ip)
+    }
+
+    /// Re-resolves every hostname, keeping the previous addresses of hosts that fail to resolve.
+    pub async fn refresh(&self) {
+        let previous = self.resolved.load_full();
+        let mut resolved = HashMap::with_capacity(self.hostnames.len());
+        for host in &self.hostnames {
+            match tokio::net::lookup_host((host.as_str(), 0)).await {
+                Ok(addrs) => {
+                    resolved.insert(host.clone(), addrs.map(|a| a.ip()).collect());
+                }
+                Err(e) => {
+                    warn!("Failed to resolve admin allowlist host {}: {}", host, e);
+                    if let Some(addrs) = previous.get(host) {
+                        resolved.insert(host.clone(), addrs.clone());
+                    }
+                }
+            }
+        }
+        self.resolved.store(Arc::new(resolved));
+    }
+
+    /// Resolves hostnames immediately and then every refresh interval.
+    pub async fn run_refresh(self: Arc<Self>) {
+        if self.hostnames.is_empty() {
+            return;
+        }
+        let mut ticker = tokio::time::interval(self.refresh_interval);
+        loop {
+            ticker.tick().await;
+            self.refresh().await;
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut entries: Vec<String> = self
+            .cidrs
+            .iter()
+            .map(|c| format!("{}/{}", c.network, c.prefix_len))
+            .chain(self.hostnames.iter().cloned())
+            .collect();
+        entries.sort();
+        entries.join(", ")
+    }
+}
+
+/// Rejects admin requests from addresses outside the allowlist with 403.
+pub async fn enforce_admin_allowlist(
+    State(allowlist): State<Arc<AdminAllowlist>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if !allowlist.allows(remote.ip()) {
+        info!("Rejected admin request {} from {}", req.uri().path(), remote);
+        return ApiError::new(
+            StatusCode::FORBIDDEN,
+            "forbidden",
+            "Client address is not allowed to access admin endpoints",
+        )
+        .into_response();
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn cidr_matching() {
+        let cidr = Cidr::parse("10.1.0.0/16").unwrap();
+        assert!(cidr.contains("10.1.200.3".parse().unwrap()));
+        assert!(!cidr.contains("10.2.0.1".parse().unwrap()));
+        // IPv4-mapped IPv6 clients match IPv4 networks
+        assert!(cidr.contains("::ffff:10.1.0.9".parse().unwrap()));
+
+        let host = Cidr::parse("192.168.1.5").unwrap();
+        assert!(host.contains("192.168.1.5".parse().unwrap()));
+        assert!(!host.contains("192.168.1.6".parse().unwrap()));
+
+        assert!(Cidr::parse("0.0.0.0/0").unwrap().contains("8.8.8.8".parse().unwrap()));
+        assert!(Cidr::parse("fd00::/8").unwrap().contains("fd12::1".parse().unwrap()));
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+    }
+
+    #[test]
+    fn hostnames_are_separated_from_cidrs() {
+        let allowlist = AdminAllowlist::new(
+            &["127.0.0.1".to_string(), "bastion.example.com".to_string()],
+            DEFAULT_REFRESH_INTERVAL,
+        );
+        assert!(allowlist.allows("127.0.0.1".parse().unwrap()));
+        assert!(!allowlist.allows("10.0.0.1".parse().unwrap()));
+        assert_eq!(allowlist.hostnames, vec!["bastion.example.com".to_string()]);
+    }
+
+    #[test]
+    fn empty_allowlist_allows_everyone() {
+        let allowlist = AdminAllowlist::new(&[], DEFAULT_REFRESH_INTERVAL);
+        assert!(allowlist.allows("203.0.113.7".parse().unwrap()));
+    }
+}
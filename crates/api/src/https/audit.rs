@@ -0,0 +1,425 @@
+//! Append-only audit log of admin API actions, for post-incident forensics on shared validator
+//! infrastructure.
+//!
+//! Every admin request, reads included, appends one JSON line with the time, the caller's
+//! address and `X-Client-Id`, the method, path, query and request body, and the status it got.
+//! Rejected calls are logged too. `GET /admin/audit` returns the entries, oldest first.
+//!
+//! The file is readable by the node's user only. Once it reaches 16 MiB it is rotated to
+//! `<file>.1`, shifting older files up to `<file>.8`, past which the oldest entries are dropped.
+//! Reads go through the files newest first and stop once they have enough entries.
+
+use crate::https::{
+    error::ApiError,
+    jobs::{now_ms, restrict_to_owner},
+    route_usage::CLIENT_ID_HEADER,
+};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, State},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json as JsonResponse, Response},
+};
+use gaptos::{
+    aptos_infallible::Mutex,
+    aptos_logger::{error, info, warn},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Size past which the audit file is rotated.
+const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+/// Rotated files kept next to the audit file, `<file>.1` being the newest.
+const ROTATED_FILES: usize = 8;
+/// Largest request body read; admin payloads are small JSON documents.
+const MAX_BODY_BYTES: usize = 1_048_576;
+/// Request bodies larger than this are logged by size only.
+const MAX_RECORDED_BODY_BYTES: usize = 16 * 1024;
+/// Entries kept when there is no audit file.
+const MEMORY_ENTRIES: usize = 10_000;
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 10_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// Unix timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    /// Address the request came from.
+    pub remote: String,
+    /// `X-Client-Id` sent by the caller, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// The request body, as JSON when it is JSON and as text otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+    pub status: u16,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AuditQuery {
+    /// Only entries at or after this unix timestamp in milliseconds.
+    pub since_ms: Option<u64>,
+    /// Only entries whose path starts with this, e.g. `/mem_prof`.
+    pub path: Option<String>,
+    /// Newest entries returned; 100 by default.
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.since_ms.map_or(true, |since_ms| entry.timestamp_ms >= since_ms) &&
+            self.path.as_deref().map_or(true, |path| entry.path.starts_with(path))
+    }
+}
+
+pub struct AuditLog {
+    /// File entries are appended to; without one they only live until the node stops.
+    path: Option<PathBuf>,
+    file: Mutex<Option<AuditFile>>,
+    max_file_bytes: u64,
+    recent: Mutex<VecDeque<AuditEntry>>,
+}
+
+struct AuditFile {
+    file: File,
+    len: u64,
+}
+
+fn open_file(path: &Path) -> std::io::Result<AuditFile> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(path)?;
+    // Files created before the mode was set keep theirs.
+    restrict_to_owner(path)?;
+    let len = file.metadata()?.len();
+    Ok(AuditFile { file, len })
+}
+
+/// `<path>.<n>`, the `n`-th newest rotated file; `path` itself for 0.
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    if n == 0 {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+impl AuditLog {
+    pub fn open(path: Option<PathBuf>) -> Self {
+        let file = path.as_ref().and_then(|path| {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            match open_file(path) {
+                Ok(file) => {
+                    info!("Admin actions are audited to {}", path.display());
+                    Some(file)
+                }
+                Err(e) => {
+                    error!("Cannot open audit log {}: {:?}", path.display(), e);
+                    None
+                }
+            }
+        });
+        if file.is_none() {
+            warn!("No audit log file, admin actions are only audited in memory");
+        }
+        let path = if file.is_some() { path } else { None };
+        Self {
+            path,
+            file: Mutex::new(file),
+            max_file_bytes: MAX_FILE_BYTES,
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Appends `entry`, rotating the file first if it would grow past its size cap. Blocks on
+    /// disk I/O.
+    pub fn append(&self, entry: AuditEntry) {
+        let mut file = self.file.lock();
+        let (Some(current), Some(path)) = (file.as_mut(), &self.path) else {
+            let mut recent = self.recent.lock();
+            if recent.len() == MEMORY_ENTRIES {
+                recent.pop_front();
+            }
+            recent.push_back(entry);
+            return;
+        };
+        let written =
+            serde_json::to_vec(&entry).map_err(anyhow::Error::from).and_then(|mut line| {
+                line.push(b'\n');
+                if current.len > 0 && current.len + line.len() as u64 > self.max_file_bytes {
+                    Self::rotate(path)?;
+                    *current = open_file(path)?;
+                }
+                current.file.write_all(&line)?;
+                current.len += line.len() as u64;
+                Ok(current.file.sync_data()?)
+            });
+        if let Err(e) = written {
+            error!("Failed to append to audit log: {:?} (entry: {:?})", e, entry);
+        }
+    }
+
+    /// Shifts `<path>.n` to `<path>.n+1`, dropping the oldest, and `path` to `<path>.1`.
+    fn rotate(path: &Path) -> std::io::Result<()> {
+        for n in (0..ROTATED_FILES).rev() {
+            let from = rotated_path(path, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(path, n + 1))?;
+            }
+        }
+        info!("Rotated audit log {}", path.display());
+        Ok(())
+    }
+
+    /// The last `limit` entries matching `query`, oldest first. Blocks on disk I/O.
+    pub fn read(&self, query: &AuditQuery, limit: usize) -> anyhow::Result<Vec<AuditEntry>> {
+        let Some(path) = &self.path else {
+            let recent = self.recent.lock();
+            let matching: Vec<&AuditEntry> =
+                recent.iter().filter(|entry| query.matches(entry)).collect();
+            let skip = matching.len().saturating_sub(limit);
+            return Ok(matching.into_iter().skip(skip).cloned().collect());
+        };
+        // Appends wait, so no file is rotated away halfway through.
+        let _file = self.file.lock();
+        let mut entries = VecDeque::with_capacity(limit);
+        for n in 0..=ROTATED_FILES {
+            let file_path = rotated_path(path, n);
+            if !file_path.exists() {
+                break;
+            }
+            let mut matching = vec![];
+            let mut oldest_ms = None;
+            let lines = BufReader::new(File::open(&file_path)?).lines().enumerate();
+            for (number, line) in lines {
+                match serde_json::from_str::<AuditEntry>(&line?) {
+                    Ok(entry) => {
+                        oldest_ms.get_or_insert(entry.timestamp_ms);
+                        if query.matches(&entry) {
+                            matching.push(entry);
+                        }
+                    }
+                    Err(e) => warn!(
+                        "Skipping line {} of audit log {}: {:?}",
+                        number + 1,
+                        file_path.display(),
+                        e
+                    ),
+                }
+            }
+            for entry in matching.into_iter().rev() {
+                if entries.len() == limit {
+                    break;
+                }
+                entries.push_front(entry);
+            }
+            let older_files_too_old = query
+                .since_ms
+                .zip(oldest_ms)
+                .is_some_and(|(since_ms, oldest_ms)| oldest_ms < since_ms);
+            if entries.len() == limit || older_files_too_old {
+                break;
+            }
+        }
+        Ok(entries.into())
+    }
+}
+
+fn recorded_body(bytes: &[u8]) -> Option<serde_json::Value> {
+    if bytes.is_empty() {
+        None
+    } else if bytes.len() > MAX_RECORDED_BODY_BYTES {
+        Some(format!("<{} bytes not recorded>", bytes.len()).into())
+    } else {
+        Some(
+            serde_json::from_slice(bytes)
+                .unwrap_or_else(|_| String::from_utf8_lossy(bytes).into_owned().into()),
+        )
+    }
+}
+
+/// Audits admin requests. Added outside the admin allowlist, so rejected calls are logged too.
+pub async fn audit_admin_actions(
+    State(audit): State<Arc<AuditLog>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return ApiError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                format!("Admin request bodies are limited to {MAX_BODY_BYTES} bytes"),
+            )
+            .into_response()
+        }
+    };
+    let mut entry = AuditEntry {
+        timestamp_ms: now_ms(),
+        remote: remote.ip().to_canonical().to_string(),
+        client_id: parts
+            .headers
+            .get(CLIENT_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|id| id.trim().to_string()),
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        query: parts.uri.query().map(str::to_string),
+        body: recorded_body(&bytes),
+        status: 0,
+    };
+    let response = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    entry.status = response.status().as_u16();
+    if let Err(e) = tokio::task::spawn_blocking(move || audit.append(entry)).await {
+        error!("Audit log append task failed: {:?}", e);
+    }
+    response
+}
+
+// example:
+// curl 'http://127.0.0.1:1024/admin/audit?path=/set_failpoint&since_ms=1735689600000'
+pub async fn get_audit_log(
+    audit: Arc<AuditLog>,
+    query: AuditQuery,
+) -> Result<JsonResponse<Vec<AuditEntry>>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let entries = tokio::task::spawn_blocking(move || audit.read(&query, limit))
+        .await
+        .unwrap_or_else(|e| Err(e.into()));
+    entries.map(JsonResponse).map_err(|e| {
+        error!("Failed to read audit log: {:?}", e);
+        ApiError::internal()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        middleware,
+        routing::{get, post},
+        Router,
+    };
+
+    fn entry(timestamp_ms: u64, path: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp_ms,
+            remote: "127.0.0.1".to_string(),
+            client_id: None,
+            method: "POST".to_string(),
+            path: path.to_string(),
+            query: None,
+            body: recorded_body(br#"{"name":"a","actions":"panic"}"#),
+            status: 200,
+        }
+    }
+
+    fn timestamps(entries: &[AuditEntry]) -> Vec<u64> {
+        entries.iter().map(|entry| entry.timestamp_ms).collect()
+    }
+
+    #[tokio::test]
+    async fn audits_every_admin_request() {
+        let audit = Arc::new(AuditLog::open(None));
+        let app = Router::new()
+            .route("/admin/runtime", get(|| async { "runtime" }))
+            .route("/admin/audit", get(|| async { "[]" }))
+            .route("/set_failpoint", post(|| async { "set" }))
+            .layer(middleware::from_fn_with_state(audit.clone(), audit_admin_actions));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await
+        });
+
+        let client = reqwest::Client::new();
+        client.get(format!("http://{address}/admin/runtime")).send().await.unwrap();
+        client.head(format!("http://{address}/admin/runtime")).send().await.unwrap();
+        client.get(format!("http://{address}/admin/audit?limit=5")).send().await.unwrap();
+        client
+            .post(format!("http://{address}/set_failpoint"))
+            .body(r#"{"name":"a","actions":"panic"}"#)
+            .send()
+            .await
+            .unwrap();
+
+        let query = AuditQuery { since_ms: None, path: None, limit: None };
+        let entries = audit.read(&query, 10).unwrap();
+        let calls: Vec<(&str, &str)> =
+            entries.iter().map(|entry| (entry.method.as_str(), entry.path.as_str())).collect();
+        assert_eq!(
+            calls,
+            [
+                ("GET", "/admin/runtime"),
+                ("HEAD", "/admin/runtime"),
+                ("GET", "/admin/audit"),
+                ("POST", "/set_failpoint"),
+            ]
+        );
+        assert_eq!(entries[2].query.as_deref(), Some("limit=5"));
+        assert_eq!(entries[3].body.as_ref().unwrap()["actions"], "panic");
+    }
+
+    #[test]
+    fn reads_latest_matching_entries() {
+        let audit = AuditLog::open(None);
+        for (timestamp_ms, path) in [(1, "/set_failpoint"), (2, "/mem_prof"), (3, "/set_failpoint")]
+        {
+            audit.append(entry(timestamp_ms, path));
+        }
+        let query = AuditQuery { since_ms: None, path: Some("/set_failpoint".into()), limit: None };
+        let entries = audit.read(&query, 1).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].timestamp_ms, 3);
+        assert_eq!(entries[0].body.as_ref().unwrap()["actions"], "panic");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rotates_owner_only_files_and_reads_across_them() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("audit_test_{}", rand::random::<u64>()));
+        let path = dir.join("audit.log");
+        let mut audit = AuditLog::open(Some(path.clone()));
+        audit.max_file_bytes = 400;
+        for timestamp_ms in 1..=10 {
+            audit.append(entry(timestamp_ms, "/set_failpoint"));
+        }
+        assert!(rotated_path(&path, 3).exists());
+        for n in 0..=3 {
+            let mode = fs::metadata(rotated_path(&path, n)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let all = AuditQuery { since_ms: None, path: None, limit: None };
+        assert_eq!(timestamps(&audit.read(&all, 100).unwrap()), (1..=10).collect::<Vec<_>>());
+        assert_eq!(timestamps(&audit.read(&all, 2).unwrap()), vec![9, 10]);
+        let recent = AuditQuery { since_ms: Some(8), path: None, limit: None };
+        assert_eq!(timestamps(&audit.read(&recent, 100).unwrap()), vec![8, 9, 10]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
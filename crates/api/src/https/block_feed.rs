@@ -0,0 +1,385 @@
+//! `/ws/blocks`: committed blocks with their payloads, pushed in commit order.
+//!
+//! This is the integration point for execution layers running outside the node. Every block is
+//! sent once per connection, in block number order, as soon as it is committed; a subscriber
+//! that reconnects with `?after=<block_number>` of the last block it processed resumes right
+//! after it, with nothing skipped or repeated.
+//!
+//! Subscribers acknowledge what they have processed with `{"ack": <block_number>}` (cumulative)
+//! and at most `window` blocks are sent unacknowledged, so a slow consumer holds the feed back
+//! instead of being overrun. Should the node unwind below blocks already sent, an `unwound`
+//! message names the new head and the feed continues from there.
+//!
+//! Blocks carry their user transactions in execution order, resolved from the QuorumStore
+//! batches the block references. Batches are only kept until they expire, so a subscriber
+//! resuming far behind gets an `error` once it reaches a block whose batches are gone.
+//!
+//! Connections are subject to the [`subscriptions`](crate::https::subscriptions) limits.
+
+use crate::https::{
+    dkg::DkgState,
+    error::ApiError,
+    subscriptions::{self, Keepalive, SubscriptionLimits, Subscriptions},
+};
+use aptos_consensus::{
+    consensusdb::{BlockNumberSchema, ConsensusDB, LedgerInfoSchema, RawBlockSchema},
+    quorum_store::{
+        quorum_store_db::{QuorumStoreDB, QuorumStoreStorage},
+        types::BatchKey,
+    },
+};
+use aptos_consensus_types::{block::Block, common::Payload};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::StatusCode,
+    response::Response,
+};
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_logger::{debug, error, info},
+    aptos_types::transaction::{SignedTransaction, TransactionPayload},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::OwnedSemaphorePermit;
+
+const DEFAULT_WINDOW: u64 = 64;
+const MAX_WINDOW: u64 = 1024;
+/// How far past a block to look for the ledger info committing it.
+const LOOKAHEAD_BLOCKS: u64 = 1024;
+
+/// Query parameters of `/ws/blocks`.
+#[derive(Deserialize, Debug)]
+pub struct BlockFeedQuery {
+    /// Last block number already processed; the feed starts right after it. Without it, the
+    /// feed starts with the next block committed.
+    pub after: Option<u64>,
+    /// Blocks sent ahead of the subscriber's acknowledgements.
+    pub window: Option<u64>,
+    /// Whether blocks carry their transactions, each as the hex-encoded BCS of the signed
+    /// transaction.
+    #[serde(default = "include_payload_default")]
+    pub include_payload: bool,
+}
+
+fn include_payload_default() -> bool {
+    true
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum FeedMessage {
+    Block {
+        block_number: u64,
+        epoch: u64,
+        round: u64,
+        timestamp_usecs: u64,
+        block_id: String,  // hex encoded
+        parent_id: String, // hex encoded
+        #[serde(skip_serializing_if = "Option::is_none")]
+        transactions: Option<Vec<String>>,
+    },
+    /// The node unwound to `block_number`; blocks after it that were sent are void and will
+    /// be sent again as they are recommitted.
+    Unwound { block_number: u64 },
+    /// The feed can't continue, e.g. because a block is no longer stored. The connection is
+    /// closed after it.
+    Error { message: String },
+}
+
+#[derive(Deserialize)]
+struct Ack {
+    ack: u64,
+}
+
+/// Looks up committed blocks by block number. Blocks are keyed by `(epoch, block_id)`, so the
+/// block numbers of the current epoch are loaded once and reused for the blocks that follow.
+struct BlockReader {
+    consensus_db: Arc<ConsensusDB>,
+    /// Holds the batches block payloads reference; only needed for transactions.
+    quorum_store_db: Option<Arc<QuorumStoreDB>>,
+    epoch_blocks: Option<(u64, BTreeMap<u64, HashValue>)>,
+}
+
+impl BlockReader {
+    /// Epoch of a committed block: that of the first ledger info at or after it.
+    fn epoch_of(&self, block_number: u64) -> Result<Option<u64>, String> {
+        let ledger_infos = self
+            .consensus_db
+            .get_range::<LedgerInfoSchema>(&block_number, &(block_number + LOOKAHEAD_BLOCKS))
+            .map_err(|e| format!("failed to read ledger infos: {e:?}"))?;
+        Ok(ledger_infos
+            .into_iter()
+            .next()
+            .map(|(_, ledger_info)| ledger_info.ledger_info().epoch()))
+    }
+
+    fn block_id(&mut self, block_number: u64) -> Result<Option<(u64, HashValue)>, String> {
+        if let Some((epoch, blocks)) = &self.epoch_blocks {
+            if let Some(block_id) = blocks.get(&block_number) {
+                return Ok(Some((*epoch, *block_id)));
+            }
+        }
+        let Some(epoch) = self.epoch_of(block_number)? else { return Ok(None) };
+        let blocks: BTreeMap<u64, HashValue> = self
+            .consensus_db
+            .get_range::<BlockNumberSchema>(
+                &(epoch, HashValue::zero()),
+                &(epoch, HashValue::new([u8::MAX; HashValue::LENGTH])),
+            )
+            .map_err(|e| format!("failed to read block numbers of epoch {epoch}: {e:?}"))?
+            .into_iter()
+            .map(|((_, block_id), number)| (number, block_id))
+            .collect();
+        let block_id = blocks.get(&block_number).copied();
+        self.epoch_blocks = Some((epoch, blocks));
+        Ok(block_id.map(|block_id| (epoch, block_id)))
+    }
+
+    fn block(&mut self, block_number: u64, include_payload: bool) -> Result<FeedMessage, String> {
+        let (epoch, block_id) = self
+            .block_id(block_number)?
+            .ok_or_else(|| format!("block {block_number} is not stored on this node"))?;
+        let raw_block = self
+            .consensus_db
+            .get::<RawBlockSchema>(&(epoch, block_id))
+            .map_err(|e| format!("failed to read block {block_number}: {e:?}"))?
+            .ok_or_else(|| format!("block {block_number} is not stored on this node"))?;
+        let header =
+            raw_block.header().map_err(|e| format!("invalid block {block_number}: {e:?}"))?;
+        let transactions = match (include_payload, &self.quorum_store_db) {
+            (true, Some(quorum_store_db)) => {
+                let block = raw_block
+                    .decode()
+                    .map_err(|e| format!("invalid block {block_number}: {e:?}"))?;
+                let transactions = transactions(&block, quorum_store_db)
+                    .map_err(|e| format!("block {block_number}: {e}"))?;
+                Some(
+                    transactions
+                        .iter()
+                        .map(|txn| bcs::to_bytes(txn).map(hex::encode))
+                        .collect::<Result<_, _>>()
+                        .map_err(|e| format!("failed to encode block {block_number}: {e:?}"))?,
+                )
+            }
+            _ => None,
+        };
+        Ok(FeedMessage::Block {
+            block_number,
+            epoch: header.epoch,
+            round: header.round,
+            timestamp_usecs: header.timestamp_usecs,
+            block_id: hex::encode(block_id.as_ref()),
+            parent_id: hex::encode(header.parent_id.as_ref()),
+            transactions,
+        })
+    }
+}
+
+/// The transaction `hash` committed in `block_number`, with the bytes it was submitted as. Read
+/// by `/tx/get_tx_by_hash`.
+pub(crate) fn committed_tx(
+    consensus_db: Arc<ConsensusDB>,
+    quorum_store_db: Arc<QuorumStoreDB>,
+    block_number: u64,
+    hash: HashValue,
+) -> Result<Option<Vec<u8>>, String> {
+    let mut reader = BlockReader { consensus_db, quorum_store_db: None, epoch_blocks: None };
+    let Some((epoch, block_id)) = reader.block_id(block_number)? else { return Ok(None) };
+    let Some(raw_block) = reader
+        .consensus_db
+        .get::<RawBlockSchema>(&(epoch, block_id))
+        .map_err(|e| format!("failed to read block {block_number}: {e:?}"))?
+    else {
+        return Ok(None);
+    };
+    let block = raw_block.decode().map_err(|e| format!("invalid block {block_number}: {e:?}"))?;
+    Ok(transactions(&block, &quorum_store_db)
+        .map_err(|e| format!("block {block_number}: {e}"))?
+        .into_iter()
+        .find(|txn| txn.committed_hash() == hash)
+        .and_then(|txn| match txn.payload() {
+            TransactionPayload::GTxnBytes(bytes) => Some(bytes.clone()),
+            _ => None,
+        }))
+}
+
+/// User transactions of `block` in the order they are executed: those of the batches the
+/// payload references, then its inline ones.
+fn transactions(
+    block: &Block,
+    quorum_store_db: &QuorumStoreDB,
+) -> Result<Vec<SignedTransaction>, String> {
+    let Some(payload) = block.payload() else { return Ok(vec![]) };
+    let (batches, inline): (Vec<(u64, HashValue)>, Vec<SignedTransaction>) = match payload {
+        Payload::DirectMempool(txns) => (vec![], txns.clone()),
+        Payload::InQuorumStore(proof_with_data) => (
+            proof_with_data.proofs.iter().map(|proof| (proof.epoch(), *proof.digest())).collect(),
+            vec![],
+        ),
+        Payload::InQuorumStoreWithLimit(proof_with_data) => (
+            proof_with_data
+                .proof_with_data
+                .proofs
+                .iter()
+                .map(|proof| (proof.epoch(), *proof.digest()))
+                .collect(),
+            vec![],
+        ),
+        Payload::QuorumStoreInlineHybrid(inline_batches, proof_with_data, _) => (
+            proof_with_data.proofs.iter().map(|proof| (proof.epoch(), *proof.digest())).collect(),
+            inline_batches.iter().flat_map(|(_, txns)| txns.clone()).collect(),
+        ),
+        Payload::OptQuorumStore(opt_qs_payload) => (
+            opt_qs_payload
+                .opt_batches()
+                .iter()
+                .map(|info| (info.epoch(), *info.digest()))
+                .chain(
+                    opt_qs_payload
+                        .proof_with_data()
+                        .iter()
+                        .map(|proof| (proof.epoch(), *proof.digest())),
+                )
+                .collect(),
+            opt_qs_payload.inline_batches().transactions(),
+        ),
+    };
+    let mut txns = Vec::new();
+    for (epoch, digest) in batches {
+        let batch = quorum_store_db
+            .get_batch(&BatchKey::new(epoch, digest))
+            .map_err(|e| format!("failed to read batch {digest}: {e:?}"))?
+            .ok_or_else(|| format!("batch {digest} is no longer stored on this node"))?;
+        let payload = batch
+            .payload()
+            .as_ref()
+            .ok_or_else(|| format!("batch {digest} is stored without its transactions"))?;
+        txns.extend(payload.iter().cloned());
+    }
+    txns.extend(inline);
+    Ok(txns)
+}
+
+// example:
+// websocat 'ws://127.0.0.1:1024/v1/ws/blocks?after=1024&window=128'
+pub async fn blocks_ws(
+    subscriptions: Arc<Subscriptions>,
+    quorum_store_db: Option<Arc<QuorumStoreDB>>,
+    State(dkg_state): State<Arc<DkgState>>,
+    ws: WebSocketUpgrade,
+    Query(query): Query<BlockFeedQuery>,
+) -> Result<Response, ApiError> {
+    let consensus_db = dkg_state.require_consensus_db()?.clone();
+    let window = query.window.unwrap_or(DEFAULT_WINDOW);
+    if window == 0 || window > MAX_WINDOW {
+        return Err(ApiError::bad_request(format!("window must be between 1 and {MAX_WINDOW}")));
+    }
+    if query.include_payload && quorum_store_db.is_none() {
+        return Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "quorum_store_db_unavailable",
+            "QuorumStoreDB is not initialized; subscribe with include_payload=false",
+        ));
+    }
+    let permit = subscriptions.acquire()?;
+    let limits = subscriptions.limits();
+    Ok(ws.on_upgrade(move |socket| {
+        let feed = Feed { after: query.after, window, include_payload: query.include_payload };
+        stream_blocks(socket, consensus_db, quorum_store_db, feed, limits, permit)
+    }))
+}
+
+/// What a subscriber asked for.
+struct Feed {
+    after: Option<u64>,
+    window: u64,
+    include_payload: bool,
+}
+
+async fn send(socket: &mut WebSocket, message: &FeedMessage, limits: &SubscriptionLimits) -> bool {
+    match serde_json::to_string(message) {
+        Ok(text) => subscriptions::send(socket, Message::Text(text), limits.send_timeout).await,
+        Err(e) => {
+            error!("Failed to encode block feed message: {:?}", e);
+            false
+        }
+    }
+}
+
+async fn stream_blocks(
+    mut socket: WebSocket,
+    consensus_db: Arc<ConsensusDB>,
+    quorum_store_db: Option<Arc<QuorumStoreDB>>,
+    feed: Feed,
+    limits: SubscriptionLimits,
+    // Frees the subscription slot when the connection ends.
+    _permit: OwnedSemaphorePermit,
+) {
+    let Feed { after, window, include_payload } = feed;
+    let mut commits = consensus_db.subscribe_commits();
+    // Next block to send, and the first one not acknowledged yet.
+    let mut next = after.map_or_else(|| *commits.borrow() + 1, |after| after + 1);
+    let mut unacked_from = next;
+    let mut reader = BlockReader { consensus_db, quorum_store_db, epoch_blocks: None };
+    let mut keepalive = Keepalive::new(&limits);
+    info!("Block feed subscriber connected, starting at block {}", next);
+
+    loop {
+        let latest = *commits.borrow_and_update();
+        if latest + 1 < next {
+            if !send(&mut socket, &FeedMessage::Unwound { block_number: latest }, &limits).await {
+                break;
+            }
+            next = latest + 1;
+            unacked_from = unacked_from.min(next);
+        }
+        if next <= latest && next - unacked_from < window {
+            let message = match reader.block(next, include_payload) {
+                Ok(block) => block,
+                Err(message) => {
+                    error!("Block feed stopped: {}", message);
+                    let _ = send(&mut socket, &FeedMessage::Error { message }, &limits).await;
+                    break;
+                }
+            };
+            if !send(&mut socket, &message, &limits).await {
+                break;
+            }
+            next += 1;
+            continue;
+        }
+
+        tokio::select! {
+            changed = commits.changed(), if next > latest => {
+                // The sender is dropped when the node shuts down.
+                if changed.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(message)) => {
+                    keepalive.seen();
+                    if let Message::Text(text) = message {
+                        match serde_json::from_str::<Ack>(&text) {
+                            Ok(Ack { ack }) => {
+                                unacked_from = unacked_from.max(ack.saturating_add(1).min(next))
+                            }
+                            Err(_) => debug!("Ignoring block feed message: {}", text),
+                        }
+                    }
+                }
+            },
+            alive = keepalive.tick() => {
+                if !subscriptions::ping(&mut socket, alive, limits.send_timeout).await {
+                    break;
+                }
+            }
+        }
+    }
+    debug!("Block feed subscriber disconnected at block {}", next);
+}
@@ -0,0 +1,167 @@
+//! Builder for [`HttpsServer`], the extension point for embedders of the SDK.
+//!
+//! Besides the settings of the built-in API, the builder accepts application routes and
+//! middleware, so a node embedding Gravity can serve its own endpoints on the API listener
+//! instead of running a second HTTP server on another port:
+//!
+//! ```ignore
+//! let server = HttpsServerBuilder::new("0.0.0.0:1024")
+//!     .consensus_db(consensus_db)
+//!     .merge(Router::new().route("/app/status", get(app_status)))
+//!     .map_router(|router| router.layer(CorsLayer::permissive()))
+//!     .build();
+//! server.serve().await?;
+//! ```
+
+use super::{ApiConfig, HttpsServer, TxSubmitter};
+use crate::https::{subscriptions::SubscriptionLimits, tls::TlsSettings};
+use aptos_consensus::{consensusdb::ConsensusDB, quorum_store::quorum_store_db::QuorumStoreDB};
+use axum::Router;
+use std::{path::PathBuf, sync::Arc};
+
+/// Wraps the finished public router, e.g. to add a layer around every route.
+pub(crate) type RouterHook = Box<dyn FnOnce(Router) -> Router + Send>;
+
+pub struct HttpsServerBuilder {
+    server: HttpsServer,
+}
+
+impl HttpsServerBuilder {
+    /// Starts from the default [`ApiConfig`]; [`Self::config`] and the setters below override
+    /// it.
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            server: HttpsServer {
+                address: address.into(),
+                cert_pem: None,
+                key_pem: None,
+                consensus_db: None,
+                quorum_store_db: None,
+                tx_submitter: None,
+                log_file: None,
+                jobs_dir: None,
+                route_usage_file: None,
+                audit_log_file: None,
+                config: ApiConfig::default(),
+                routes: Router::new(),
+                router_hooks: Vec::new(),
+            },
+        }
+    }
+
+    /// Takes every setting of the built-in API from `config`, e.g. the node's `--api_config`
+    /// file.
+    pub fn config(mut self, config: ApiConfig) -> Self {
+        self.server.config = config;
+        self
+    }
+
+    /// Serves HTTPS with this certificate and key; plain HTTP without them.
+    pub fn tls_files(mut self, cert_pem: PathBuf, key_pem: PathBuf) -> Self {
+        self.server.cert_pem = Some(cert_pem);
+        self.server.key_pem = Some(key_pem);
+        self
+    }
+
+    pub fn tls_settings(mut self, tls: TlsSettings) -> Self {
+        self.server.config.tls = tls;
+        self
+    }
+
+    pub fn consensus_db(mut self, consensus_db: Arc<ConsensusDB>) -> Self {
+        self.server.consensus_db = Some(consensus_db);
+        self
+    }
+
+    pub fn quorum_store_db(mut self, quorum_store_db: Arc<QuorumStoreDB>) -> Self {
+        self.server.quorum_store_db = Some(quorum_store_db);
+        self
+    }
+
+    pub fn tx_submitter(mut self, tx_submitter: Arc<TxSubmitter>) -> Self {
+        self.server.tx_submitter = Some(tx_submitter);
+        self
+    }
+
+    pub fn log_file(mut self, log_file: PathBuf) -> Self {
+        self.server.log_file = Some(log_file);
+        self
+    }
+
+    pub fn jobs_dir(mut self, jobs_dir: PathBuf) -> Self {
+        self.server.jobs_dir = Some(jobs_dir);
+        self
+    }
+
+    pub fn route_usage_file(mut self, route_usage_file: PathBuf) -> Self {
+        self.server.route_usage_file = Some(route_usage_file);
+        self
+    }
+
+    pub fn audit_log_file(mut self, audit_log_file: PathBuf) -> Self {
+        self.server.audit_log_file = Some(audit_log_file);
+        self
+    }
+
+    pub fn compression_min_size(mut self, bytes: u16) -> Self {
+        self.server.config.compression_min_size = bytes;
+        self
+    }
+
+    pub fn admin_address(mut self, admin_address: impl Into<String>) -> Self {
+        self.server.config.admin.address = Some(admin_address.into());
+        self
+    }
+
+    pub fn admin_allowlist(mut self, entries: Vec<String>) -> Self {
+        self.server.config.admin.allowlist = entries;
+        self
+    }
+
+    pub fn api_tokens_file(mut self, path: PathBuf) -> Self {
+        self.server.config.tokens.file = Some(path);
+        self
+    }
+
+    pub fn require_api_token(mut self, required: bool) -> Self {
+        self.server.config.tokens.required = required;
+        self
+    }
+
+    /// Whether the public routes are also served at their unprefixed paths, as deprecated
+    /// aliases of the `/v1` ones.
+    pub fn legacy_paths(mut self, enabled: bool) -> Self {
+        self.server.config.legacy_paths.enabled = enabled;
+        self
+    }
+
+    /// Whether validators answer `/dkg/decrypt_share`, for threshold decryption experiments.
+    pub fn dkg_decrypt_shares(mut self, enabled: bool) -> Self {
+        self.server.config.dkg_decrypt_shares = enabled;
+        self
+    }
+
+    /// Bounds on the WebSocket and SSE streams: open subscriptions, keepalive and lag.
+    pub fn subscription_limits(mut self, limits: SubscriptionLimits) -> Self {
+        self.server.config.subscriptions = limits;
+        self
+    }
+
+    /// Serves `routes` next to the built-in public routes, behind the same API token check,
+    /// usage accounting and tracing. Their paths must not overlap the built-in ones.
+    pub fn merge(mut self, routes: Router) -> Self {
+        self.server.routes = self.server.routes.merge(routes);
+        self
+    }
+
+    /// Applies `hook` to the finished public router, e.g. to add middleware around every
+    /// route. Hooks run in the order they were added, so the last one is outermost.
+    pub fn map_router(mut self, hook: impl FnOnce(Router) -> Router + Send + 'static) -> Self {
+        self.server.router_hooks.push(Box::new(hook));
+        self
+    }
+
+    pub fn build(self) -> HttpsServer {
+        self.server
+    }
+}
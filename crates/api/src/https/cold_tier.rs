@@ -0,0 +1,526 @@
+//! Cold-storage tiering of old consensus blocks and QCs.
+//!
+//! Blocks and QCs of epochs more than `cold_tier.after_epochs` behind the latest committed epoch
+//! are moved out of the ConsensusDB into one gzip-compressed archive per epoch, kept in a local
+//! directory (`cold_tier.dir`) or an object store reached with HTTP PUT and GET under a base URL
+//! (`cold_tier.url`, e.g. a GCS bucket's XML API or an S3-compatible gateway, with an optional
+//! bearer token in `cold_tier.token`). Block numbers and ledger infos stay in the DB.
+//!
+//! The node starts the cold tier and registers it with the ConsensusDB, which records the first
+//! epoch it still holds. DB reads of moved-out blocks and QCs, e.g. block retrieval for syncing
+//! peers, fall back to the cold tier, and unwinding refuses targets in moved-out epochs.
+//!
+//! An index of every archived epoch's rounds and block ids stays on local disk and in memory, so
+//! lookups that miss in the DB know without a fetch whether the cold tier has the data. Archives
+//! are fetched when read and the most recent ones are kept decoded. Responses served from an
+//! archived epoch carry `X-Gravity-Storage-Tier: cold`. An epoch is archived, checked by reading
+//! it back, indexed and only then deleted from the DB, so a crash at any point leaves it
+//! readable from one tier or the other.
+
+use crate::https::{config::ColdTierConfig, jobs::write_atomically};
+use aptos_consensus::consensusdb::{
+    BlockHeader, ColdBlockStore, ConsensusDB, QCSchema, RawBlock, RawBlockSchema, CONSENSUS_DB_NAME,
+};
+use aptos_consensus_types::quorum_cert::QuorumCert;
+use axum::{http::HeaderValue, response::Response};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_infallible::{Mutex, RwLock},
+    aptos_logger::{error, info, warn},
+    aptos_storage_interface::DbReader,
+};
+use lru::LruCache;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::{Read, Write},
+    ops::RangeInclusive,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Header marking responses served from the cold tier.
+pub(crate) const STORAGE_TIER_HEADER: &str = "x-gravity-storage-tier";
+
+/// Recovery only reads the current epoch; the previous one is kept while an epoch change settles.
+pub(crate) const MIN_AFTER_EPOCHS: u64 = 2;
+const OBJECT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// Decoded epoch archives kept in memory.
+const ARCHIVE_CACHE_CAPACITY: usize = 4;
+
+static COLD_TIER: OnceCell<Arc<ColdTier>> = OnceCell::new();
+
+/// The cold tier of this node, when one is configured.
+pub(crate) fn get() -> Option<&'static Arc<ColdTier>> {
+    COLD_TIER.get()
+}
+
+/// Marks `response` as served from the cold tier when `epoch` is archived.
+pub(crate) fn mark(mut response: Response, epoch: u64) -> Response {
+    if get().is_some_and(|tier| tier.contains_epoch(epoch)) {
+        response.headers_mut().insert(STORAGE_TIER_HEADER, HeaderValue::from_static("cold"));
+    }
+    response
+}
+
+/// Where the epoch archives are kept.
+enum Store {
+    Local(PathBuf),
+    Object { base_url: String, token: Option<String>, client: reqwest::Client },
+}
+
+impl Store {
+    fn describe(&self) -> String {
+        match self {
+            Store::Local(dir) => dir.display().to_string(),
+            Store::Object { base_url, .. } => base_url.clone(),
+        }
+    }
+
+    async fn put(&self, name: &str, bytes: Vec<u8>) -> anyhow::Result<()> {
+        match self {
+            Store::Local(dir) => {
+                let path = dir.join(name);
+                tokio::task::spawn_blocking(move || {
+                    write_atomically(&path, |file| Ok(file.write_all(&bytes)?))
+                })
+                .await?
+            }
+            Store::Object { base_url, token, client } => {
+                let mut request = client.put(format!("{base_url}/{name}")).body(bytes);
+                if let Some(token) = token {
+                    request = request.bearer_auth(token);
+                }
+                let response = request.send().await?;
+                anyhow::ensure!(
+                    response.status().is_success(),
+                    "object store returned {} for PUT {name}",
+                    response.status()
+                );
+                Ok(())
+            }
+        }
+    }
+
+    async fn get(&self, name: &str) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Store::Local(dir) => Ok(tokio::fs::read(dir.join(name)).await?),
+            Store::Object { base_url, token, client } => {
+                let mut request = client.get(format!("{base_url}/{name}"));
+                if let Some(token) = token {
+                    request = request.bearer_auth(token);
+                }
+                let response = request.send().await?;
+                anyhow::ensure!(
+                    response.status().is_success(),
+                    "object store returned {} for GET {name}",
+                    response.status()
+                );
+                Ok(response.bytes().await?.to_vec())
+            }
+        }
+    }
+
+    /// Like [`Self::get`], for the synchronous handlers.
+    fn get_blocking(&self, name: &str) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Store::Local(dir) => Ok(std::fs::read(dir.join(name))?),
+            Store::Object { .. } => {
+                let handle = tokio::runtime::Handle::try_current()?;
+                anyhow::ensure!(
+                    handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread,
+                    "fetching from the object store needs a multi-threaded runtime"
+                );
+                tokio::task::block_in_place(|| handle.block_on(self.get(name)))
+            }
+        }
+    }
+}
+
+/// Blocks and QCs of one epoch, as stored in the cold tier.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct EpochArchive {
+    epoch: u64,
+    /// Block ids with the stored BCS bytes of their blocks.
+    blocks: BTreeMap<HashValue, Vec<u8>>,
+    /// Certified block ids with the BCS bytes of their QCs.
+    qcs: BTreeMap<HashValue, Vec<u8>>,
+}
+
+impl EpochArchive {
+    fn compress(&self) -> anyhow::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bcs::to_bytes(self)?)?;
+        Ok(encoder.finish()?)
+    }
+
+    fn decompress(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut decoded = Vec::new();
+        GzDecoder::new(bytes).read_to_end(&mut decoded)?;
+        Ok(bcs::from_bytes(&decoded)?)
+    }
+}
+
+/// Index entry of one archived epoch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ArchivedEpoch {
+    /// Name of the archive in the store.
+    object: String,
+    compressed_bytes: u64,
+    /// Unix timestamp (seconds) of the archiving.
+    archived_at: u64,
+    /// `(round, id)` of each archived block; a round can have several blocks.
+    blocks: BTreeSet<(u64, HashValue)>,
+    /// `(round, id)` of the block each archived QC certifies.
+    qcs: BTreeSet<(u64, HashValue)>,
+}
+
+/// The hot index, saved next to the ConsensusDB.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ColdIndex {
+    /// First epoch not examined by tiering yet.
+    next_epoch: u64,
+    epochs: BTreeMap<u64, ArchivedEpoch>,
+}
+
+pub struct ColdTier {
+    store: Store,
+    after_epochs: u64,
+    interval: Duration,
+    index_path: PathBuf,
+    index: RwLock<ColdIndex>,
+    /// Epoch of every archived block id, derived from the index.
+    block_epochs: RwLock<HashMap<HashValue, u64>>,
+    archives: Mutex<LruCache<u64, Arc<EpochArchive>>>,
+}
+
+impl ColdTier {
+    /// Builds the cold tier from the `cold_tier` section of the API config, which
+    /// [`ApiConfig::load`](crate::https::ApiConfig::load) has checked.
+    pub fn from_config(
+        config: &ColdTierConfig,
+        consensus_db: &ConsensusDB,
+    ) -> anyhow::Result<Self> {
+        let store = match (&config.dir, &config.url) {
+            (Some(dir), _) => {
+                std::fs::create_dir_all(dir)?;
+                Store::Local(dir.clone())
+            }
+            (None, Some(url)) => Store::Object {
+                base_url: url.trim_end_matches('/').to_string(),
+                token: config.token.clone(),
+                client: reqwest::Client::builder().timeout(OBJECT_REQUEST_TIMEOUT).build()?,
+            },
+            (None, None) => anyhow::bail!("cold_tier needs a dir or a url"),
+        };
+        let index_path =
+            consensus_db.path().with_file_name(format!("{CONSENSUS_DB_NAME}.cold_index.json"));
+        Self::open(
+            store,
+            config.after_epochs,
+            Duration::from_secs(config.interval_secs),
+            index_path,
+        )
+    }
+
+    fn open(
+        store: Store,
+        after_epochs: u64,
+        interval: Duration,
+        index_path: PathBuf,
+    ) -> anyhow::Result<Self> {
+        let index: ColdIndex = match std::fs::read(&index_path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| {
+                anyhow::anyhow!("Corrupt cold tier index {}: {e}", index_path.display())
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => ColdIndex::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let block_epochs = index
+            .epochs
+            .iter()
+            .flat_map(|(epoch, entry)| entry.blocks.iter().map(|(_, id)| (*id, *epoch)))
+            .collect();
+        Ok(Self {
+            store,
+            after_epochs,
+            interval,
+            index_path,
+            index: RwLock::new(index),
+            block_epochs: RwLock::new(block_epochs),
+            archives: Mutex::new(LruCache::new(ARCHIVE_CACHE_CAPACITY)),
+        })
+    }
+
+    /// Makes `self` the node's cold tier, which the API and `consensus_db` read moved-out
+    /// epochs from. Call before consensus starts, so block retrieval never misses them.
+    pub fn install(self: &Arc<Self>, consensus_db: &ConsensusDB) -> anyhow::Result<()> {
+        COLD_TIER.set(self.clone()).map_err(|_| anyhow::anyhow!("a cold tier is already set"))?;
+        consensus_db.set_cold_store(self.clone())
+    }
+
+    /// Runs tiering rounds forever.
+    pub async fn run(self: Arc<Self>, consensus_db: Arc<ConsensusDB>) {
+        info!(
+            "Cold tier started in {}, epochs older than {} are moved, interval={:?}",
+            self.store.describe(),
+            self.after_epochs,
+            self.interval
+        );
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.tier_once(&consensus_db).await {
+                warn!("Cold tiering round failed: {:?}", e);
+            }
+        }
+    }
+
+    /// Moves every epoch that has fallen behind to the cold tier.
+    async fn tier_once(&self, consensus_db: &Arc<ConsensusDB>) -> anyhow::Result<()> {
+        let latest_epoch =
+            DbReader::get_latest_ledger_info(consensus_db.as_ref())?.ledger_info().epoch();
+        let cutoff = latest_epoch.saturating_sub(self.after_epochs);
+        let mut epoch = self.index.read().next_epoch;
+        while epoch < cutoff {
+            self.tier_epoch(consensus_db, epoch).await?;
+            epoch += 1;
+        }
+        Ok(())
+    }
+
+    async fn tier_epoch(&self, consensus_db: &Arc<ConsensusDB>, epoch: u64) -> anyhow::Result<()> {
+        let db = consensus_db.clone();
+        let archive = tokio::task::spawn_blocking(move || read_epoch(&db, epoch)).await??;
+        let Some(archive) = archive else {
+            self.index.write().next_epoch = epoch + 1;
+            return self.persist_index();
+        };
+
+        let object = format!("epoch-{epoch:020}.bcs.gz");
+        let compressed = tokio::task::spawn_blocking({
+            let archive = archive.clone();
+            move || archive.compress()
+        })
+        .await??;
+        let compressed_bytes = compressed.len() as u64;
+        self.store.put(&object, compressed).await?;
+        // Deleting from the DB must not depend on an archive that can't be read back.
+        let stored = EpochArchive::decompress(&self.store.get(&object).await?)?;
+        anyhow::ensure!(stored == *archive, "archive {object} differs from the DB once read back");
+
+        let entry = ArchivedEpoch {
+            object,
+            compressed_bytes,
+            archived_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+            blocks: rounds(&archive.blocks, |bytes| Ok(RawBlock(bytes.to_vec()).header()?.round))?,
+            qcs: rounds(&archive.qcs, |bytes| {
+                Ok(bcs::from_bytes::<QuorumCert>(bytes)?.certified_block().round())
+            })?,
+        };
+        let keys: Vec<(u64, HashValue)> = archive
+            .blocks
+            .keys()
+            .chain(archive.qcs.keys())
+            .map(|id| (epoch, *id))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        self.block_epochs.write().extend(entry.blocks.iter().map(|(_, id)| (*id, epoch)));
+        {
+            let mut index = self.index.write();
+            index.epochs.insert(epoch, entry.clone());
+            index.next_epoch = epoch + 1;
+        }
+        self.persist_index()?;
+        tokio::task::spawn_blocking({
+            let db = consensus_db.clone();
+            move || db.move_epoch_out(epoch, keys)
+        })
+        .await??;
+        info!(
+            "Moved epoch {} to the cold tier: {} blocks, {} QCs, {} bytes",
+            epoch,
+            entry.blocks.len(),
+            entry.qcs.len(),
+            entry.compressed_bytes
+        );
+        Ok(())
+    }
+
+    fn persist_index(&self) -> anyhow::Result<()> {
+        let bytes = serde_json::to_vec(&*self.index.read())?;
+        write_atomically(&self.index_path, |file| Ok(file.write_all(&bytes)?))
+    }
+
+    pub(crate) fn contains_epoch(&self, epoch: u64) -> bool {
+        self.index.read().epochs.contains_key(&epoch)
+    }
+
+    /// The archive of `epoch`, fetched unless it was read recently.
+    fn archive(&self, epoch: u64) -> Option<Arc<EpochArchive>> {
+        if let Some(archive) = self.archives.lock().get(&epoch) {
+            return Some(archive.clone());
+        }
+        let object = self.index.read().epochs.get(&epoch)?.object.clone();
+        let archive = self
+            .store
+            .get_blocking(&object)
+            .and_then(|bytes| EpochArchive::decompress(&bytes))
+            .map_err(|e| error!("Failed to read cold tier archive {}: {:?}", object, e))
+            .ok()?;
+        let archive = Arc::new(archive);
+        self.archives.lock().put(epoch, archive.clone());
+        Some(archive)
+    }
+
+    fn raw_block(&self, epoch: u64, block_id: HashValue) -> Option<(BlockHeader, RawBlock)> {
+        let archive = self.archive(epoch)?;
+        let raw_block = RawBlock(archive.blocks.get(&block_id)?.clone());
+        match raw_block.header() {
+            Ok(header) => Some((header, raw_block)),
+            Err(e) => {
+                error!("Failed to read header of cold block {}: {:?}", block_id, e);
+                None
+            }
+        }
+    }
+
+    /// The archived block of `epoch` at `round`, with its id and parsed header. Of several blocks
+    /// at the round the one with the lowest id is returned, like the DB lookup does.
+    pub(crate) fn block_by_round(
+        &self,
+        epoch: u64,
+        round: u64,
+    ) -> Option<(HashValue, BlockHeader, RawBlock)> {
+        let (_, block_id) =
+            *self.index.read().epochs.get(&epoch)?.blocks.range(round_keys(round)).next()?;
+        self.raw_block(epoch, block_id).map(|(header, raw_block)| (block_id, header, raw_block))
+    }
+
+    /// The archived block with id `block_id` and its parsed header.
+    pub(crate) fn block_by_id(&self, block_id: HashValue) -> Option<(BlockHeader, RawBlock)> {
+        let epoch = *self.block_epochs.read().get(&block_id)?;
+        self.raw_block(epoch, block_id)
+    }
+
+    /// The archived QC certifying the block of `epoch` at `round`.
+    pub(crate) fn qc_by_round(&self, epoch: u64, round: u64) -> Option<QuorumCert> {
+        let (_, block_id) =
+            *self.index.read().epochs.get(&epoch)?.qcs.range(round_keys(round)).next()?;
+        let archive = self.archive(epoch)?;
+        bcs::from_bytes(archive.qcs.get(&block_id)?)
+            .map_err(|e| error!("Failed to decode cold QC of block {}: {:?}", block_id, e))
+            .ok()
+    }
+
+    /// Every archived block of `epoch`, in id order like the DB returns them.
+    pub(crate) fn blocks_in_epoch(&self, epoch: u64) -> Option<Vec<(HashValue, RawBlock)>> {
+        let archive = self.archive(epoch)?;
+        Some(archive.blocks.iter().map(|(id, bytes)| (*id, RawBlock(bytes.clone()))).collect())
+    }
+}
+
+impl ColdBlockStore for ColdTier {
+    fn block_bytes(&self, epoch: u64, block_id: HashValue) -> Option<Vec<u8>> {
+        let archive = self.archive(epoch)?;
+        archive.blocks.get(&block_id).cloned()
+    }
+
+    fn qc_bytes(&self, epoch: u64, block_id: HashValue) -> Option<Vec<u8>> {
+        self.archive(epoch)?.qcs.get(&block_id).cloned()
+    }
+
+    fn epoch_qc_bytes(&self, epoch: u64) -> Vec<(HashValue, Vec<u8>)> {
+        let Some(archive) = self.archive(epoch) else { return Vec::new() };
+        archive.qcs.iter().map(|(id, bytes)| (*id, bytes.clone())).collect()
+    }
+}
+
+/// Reads the blocks and QCs of `epoch`; `None` when it has neither.
+fn read_epoch(consensus_db: &ConsensusDB, epoch: u64) -> anyhow::Result<Option<Arc<EpochArchive>>> {
+    let start_key = (epoch, HashValue::zero());
+    let end_key = (epoch, HashValue::new([u8::MAX; HashValue::LENGTH]));
+    let blocks: BTreeMap<HashValue, Vec<u8>> = consensus_db
+        .get_range::<RawBlockSchema>(&start_key, &end_key)?
+        .into_iter()
+        .map(|((_, id), raw_block)| (id, raw_block.0))
+        .collect();
+    let qcs = consensus_db
+        .get_range::<QCSchema>(&start_key, &end_key)?
+        .into_iter()
+        .map(|((_, id), qc)| Ok((id, bcs::to_bytes(&qc)?)))
+        .collect::<anyhow::Result<BTreeMap<_, _>>>()?;
+    if blocks.is_empty() && qcs.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Arc::new(EpochArchive { epoch, blocks, qcs })))
+}
+
+/// The `(round, id)` key of each entry, its round read by `round_of`.
+fn rounds(
+    entries: &BTreeMap<HashValue, Vec<u8>>,
+    round_of: impl Fn(&[u8]) -> anyhow::Result<u64>,
+) -> anyhow::Result<BTreeSet<(u64, HashValue)>> {
+    entries.iter().map(|(id, bytes)| Ok((round_of(bytes)?, *id))).collect()
+}
+
+/// Every `(round, id)` key of `round`.
+fn round_keys(round: u64) -> RangeInclusive<(u64, HashValue)> {
+    (round, HashValue::zero())..=(round, HashValue::new([u8::MAX; HashValue::LENGTH]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_INTERVAL: Duration = Duration::from_secs(600);
+
+    #[test]
+    fn archives_round_trip_and_index_reopens() {
+        let (block_id, other_block_id) = (HashValue::random(), HashValue::random());
+        let archive = EpochArchive {
+            epoch: 7,
+            blocks: BTreeMap::from([(block_id, vec![1; 200]), (other_block_id, vec![2; 90])]),
+            qcs: BTreeMap::from([(HashValue::random(), vec![3; 40])]),
+        };
+        let compressed = archive.compress().unwrap();
+        assert!(compressed.len() < bcs::to_bytes(&archive).unwrap().len());
+        assert_eq!(EpochArchive::decompress(&compressed).unwrap(), archive);
+
+        let dir = std::env::temp_dir().join(format!("cold_tier_test_{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let index_path = dir.join("index.json");
+        let tier =
+            ColdTier::open(Store::Local(dir.clone()), 2, DEFAULT_INTERVAL, index_path.clone())
+                .unwrap();
+        assert!(!tier.contains_epoch(7));
+        {
+            let mut index = tier.index.write();
+            index.next_epoch = 8;
+            index.epochs.insert(
+                7,
+                ArchivedEpoch {
+                    object: "epoch-7".to_string(),
+                    compressed_bytes: compressed.len() as u64,
+                    archived_at: 0,
+                    blocks: BTreeSet::from([(3, block_id), (3, other_block_id)]),
+                    qcs: BTreeSet::new(),
+                },
+            );
+        }
+        tier.persist_index().unwrap();
+
+        let reopened =
+            ColdTier::open(Store::Local(dir.clone()), 2, DEFAULT_INTERVAL, index_path).unwrap();
+        assert!(reopened.contains_epoch(7));
+        assert_eq!(reopened.index.read().next_epoch, 8);
+        assert_eq!(reopened.block_epochs.read().get(&block_id), Some(&7));
+        // Both blocks of round 3 stay indexed
+        assert_eq!(reopened.block_epochs.read().get(&other_block_id), Some(&7));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
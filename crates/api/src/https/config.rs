@@ -0,0 +1,311 @@
+//! Settings of the HTTP API, read from the YAML file the node is started with through
+//! `--api_config`.
+//!
+//! Every field has a default, so the file only lists what a deployment changes, e.g.
+//!
+//! ```yaml
+//! admin:
+//!   address: 127.0.0.1:1025
+//! tokens:
+//!   file: /opt/gravity/data/api_tokens.json
+//!   required: true
+//! tls:
+//!   min_version: "1.3"
+//! ```
+//!
+//! Admin routes (`/set_failpoint`, `/mem_prof`, `/admin/*`, ...) share the public listener and
+//! are open to every client unless moved with `admin.address` or restricted with
+//! `admin.allowlist`.
+
+use crate::https::{
+    admission::FailMode, cold_tier::MIN_AFTER_EPOCHS, subscriptions::SubscriptionLimits,
+    tls::TlsSettings,
+};
+use anyhow::{bail, Context};
+use axum::http::HeaderValue;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
+    pub admin: AdminConfig,
+    /// Responses smaller than this many bytes are sent uncompressed.
+    pub compression_min_size: u16,
+    /// Protocol versions, cipher suites and ALPN of the HTTPS listener.
+    pub tls: TlsSettings,
+    pub tokens: TokensConfig,
+    pub legacy_paths: LegacyPathsConfig,
+    /// Serve `/dkg/decrypt_share`. Anyone who can reach the API can then have ciphertexts
+    /// encrypted to the epoch's DKG key decrypted.
+    pub dkg_decrypt_shares: bool,
+    /// Bounds on the WebSocket and SSE streams.
+    pub subscriptions: SubscriptionLimits,
+    /// Jobs of the `/jobs` API that may run at the same time.
+    pub max_concurrent_jobs: usize,
+    pub tx_cache: TxCacheConfig,
+    pub tx_index: TxIndexConfig,
+    /// Policy endpoint submitted transactions are checked against; none by default.
+    pub admission_webhook: Option<AdmissionWebhookConfig>,
+    pub consistency: ConsistencyConfig,
+    /// Where scheduled heap dumps go when the request names no directory; defaults to
+    /// `<tmp>/gravity-heap-dumps`.
+    pub heap_dump_dir: Option<PathBuf>,
+    /// Listen address of the gRPC mirror of the API, e.g. `127.0.0.1:50051`; off without it.
+    pub grpc_address: Option<String>,
+    /// Where blocks and QCs of old epochs are archived; off without it.
+    pub cold_tier: Option<ColdTierConfig>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            admin: AdminConfig::default(),
+            compression_min_size: 1024,
+            tls: TlsSettings::default(),
+            tokens: TokensConfig::default(),
+            legacy_paths: LegacyPathsConfig::default(),
+            dkg_decrypt_shares: false,
+            subscriptions: SubscriptionLimits::default(),
+            max_concurrent_jobs: 2,
+            tx_cache: TxCacheConfig::default(),
+            tx_index: TxIndexConfig::default(),
+            admission_webhook: None,
+            consistency: ConsistencyConfig::default(),
+            heap_dump_dir: None,
+            grpc_address: None,
+            cold_tier: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    /// Separate plain HTTP listener for the admin routes, e.g. `127.0.0.1:1025`, so the public
+    /// address can be exposed without them.
+    pub address: Option<String>,
+    /// CIDRs, IPs and hostnames allowed to call the admin routes; others get 403. Empty allows
+    /// every client.
+    pub allowlist: Vec<String>,
+    /// Seconds between re-resolving allowlisted hostnames.
+    pub allowlist_refresh_secs: u64,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self { address: None, allowlist: vec![], allowlist_refresh_secs: 60 }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokensConfig {
+    /// Where API tokens issued through `/admin/tokens` are kept across restarts.
+    pub file: Option<PathBuf>,
+    /// Reject requests to the public routes without an API token.
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LegacyPathsConfig {
+    /// Also serve the public routes at their unprefixed pre-`/v1` paths, with deprecation
+    /// headers.
+    pub enabled: bool,
+    /// HTTP date after which the legacy paths may be removed, sent as their `Sunset` header,
+    /// e.g. `Wed, 01 Jul 2026 00:00:00 GMT`.
+    pub sunset: Option<String>,
+}
+
+impl Default for LegacyPathsConfig {
+    fn default() -> Self {
+        Self { enabled: true, sunset: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TxCacheConfig {
+    /// Seconds a transaction result is kept after its last update.
+    pub ttl_secs: u64,
+    /// Also keep the results in the ConsensusDB, so lookups answer across restarts.
+    pub persist: bool,
+}
+
+impl Default for TxCacheConfig {
+    fn default() -> Self {
+        Self { ttl_secs: 3600, persist: false }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TxIndexConfig {
+    /// Index committed transactions by position and sender in the ConsensusDB for `/tx/list`.
+    pub enabled: bool,
+    /// Blocks whose transactions stay indexed behind the latest committed one; older entries
+    /// are pruned as blocks commit.
+    pub retention_blocks: u64,
+}
+
+impl Default for TxIndexConfig {
+    fn default() -> Self {
+        Self { enabled: false, retention_blocks: 100_000 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdmissionWebhookConfig {
+    pub url: String,
+    /// Milliseconds to wait for the policy endpoint.
+    #[serde(default = "AdmissionWebhookConfig::default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Whether transactions are admitted when the endpoint fails.
+    #[serde(default)]
+    pub fail_mode: FailMode,
+}
+
+impl AdmissionWebhookConfig {
+    fn default_timeout_ms() -> u64 {
+        500
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsistencyConfig {
+    /// Peer API base URLs recent commits are cross-checked against; the checker is off without
+    /// any.
+    pub peers: Vec<String>,
+    /// Seconds between check rounds.
+    pub interval_secs: u64,
+}
+
+impl Default for ConsistencyConfig {
+    fn default() -> Self {
+        Self { peers: vec![], interval_secs: 30 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColdTierConfig {
+    /// Local directory holding the epoch archives.
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// Object store base URL the epoch archives are PUT under, instead of a local directory.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Bearer token sent to the object store.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Epochs kept in the ConsensusDB behind the latest committed one.
+    #[serde(default = "ColdTierConfig::default_after_epochs")]
+    pub after_epochs: u64,
+    /// Seconds between tiering rounds.
+    #[serde(default = "ColdTierConfig::default_interval_secs")]
+    pub interval_secs: u64,
+}
+
+impl ColdTierConfig {
+    fn default_after_epochs() -> u64 {
+        100
+    }
+
+    fn default_interval_secs() -> u64 {
+        600
+    }
+}
+
+impl ApiConfig {
+    /// Reads and checks the config file at `path`.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let config: Self = serde_yaml::from_str(&content)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        config.validate().with_context(|| format!("invalid {}", path.display()))?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let intervals = [
+            ("admin.allowlist_refresh_secs", self.admin.allowlist_refresh_secs),
+            ("tx_cache.ttl_secs", self.tx_cache.ttl_secs),
+            ("tx_index.retention_blocks", self.tx_index.retention_blocks),
+            ("consistency.interval_secs", self.consistency.interval_secs),
+            ("max_concurrent_jobs", self.max_concurrent_jobs as u64),
+        ];
+        if let Some((name, _)) = intervals.iter().find(|(_, value)| *value == 0) {
+            bail!("{name} must be positive");
+        }
+        self.subscriptions.validate()?;
+        if let Some(sunset) = &self.legacy_paths.sunset {
+            HeaderValue::from_str(sunset)
+                .with_context(|| format!("legacy_paths.sunset '{sunset}' is not a header value"))?;
+        }
+        if let Some(cold_tier) = &self.cold_tier {
+            if cold_tier.dir.is_some() == cold_tier.url.is_some() {
+                bail!("set exactly one of cold_tier.dir and cold_tier.url");
+            }
+            if cold_tier.interval_secs == 0 {
+                bail!("cold_tier.interval_secs must be positive");
+            }
+            if cold_tier.after_epochs < MIN_AFTER_EPOCHS {
+                bail!("cold_tier.after_epochs must be at least {MIN_AFTER_EPOCHS}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// (De)serializes a `Duration` as whole seconds.
+pub(crate) mod duration_secs {
+    use serde::{Deserialize, Deserializer};
+    use std::time::Duration;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::https::tls::TlsMinVersion;
+
+    #[test]
+    fn partial_files_keep_the_other_defaults() {
+        let config: ApiConfig = serde_yaml::from_str(
+            "admin:\n  address: 127.0.0.1:1025\ntls:\n  min_version: \"1.3\"\n\
+             subscriptions:\n  idle_timeout_secs: 5\ncold_tier:\n  dir: /tmp/cold\n",
+        )
+        .unwrap();
+        assert_eq!(config.admin.address.as_deref(), Some("127.0.0.1:1025"));
+        assert_eq!(config.admin.allowlist_refresh_secs, 60);
+        assert_eq!(config.tls.min_version, TlsMinVersion::Tls13);
+        assert_eq!(config.subscriptions.idle_timeout.as_secs(), 5);
+        assert_eq!(config.subscriptions.max_subscriptions, 256);
+        assert_eq!(config.cold_tier.as_ref().unwrap().after_epochs, 100);
+        assert!(config.legacy_paths.enabled);
+        assert!(!config.tx_index.enabled);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_invalid_values() {
+        assert!(serde_yaml::from_str::<ApiConfig>("admin:\n  adress: 127.0.0.1:1025\n").is_err());
+        assert!(serde_yaml::from_str::<ApiConfig>("tls:\n  min_version: \"1.1\"\n").is_err());
+
+        let config: ApiConfig = serde_yaml::from_str("tx_cache:\n  ttl_secs: 0\n").unwrap();
+        assert!(config.validate().is_err());
+        let config: ApiConfig =
+            serde_yaml::from_str("tx_index:\n  retention_blocks: 0\n").unwrap();
+        assert!(config.validate().is_err());
+        let config: ApiConfig =
+            serde_yaml::from_str("cold_tier:\n  dir: /tmp/cold\n  url: https://bucket\n").unwrap();
+        assert!(config.validate().is_err());
+    }
+}
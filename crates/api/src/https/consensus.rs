@@ -1,24 +1,78 @@
-use crate::https::dkg::DkgState;
+use crate::https::{
+    cold_tier,
+    dkg::DkgState,
+    error::ApiError,
+    negotiate::ResponseFormat,
+    otel::db_span,
+    response_cache::{self, CachedBlock, CachedQc},
+    subscriptions::Subscriptions,
+};
 use aptos_consensus::consensusdb::{
-    BlockNumberSchema, BlockSchema, ConsensusDB, EpochByBlockNumberSchema, LedgerInfoSchema,
+    BlockHeader, BlockNumberSchema, ConsensusDB, EpochByBlockNumberSchema, EquivocationEvidence,
+    EvidenceKind, LedgerInfoSchema, RawBlock, RawBlockSchema,
 };
+use aptos_consensus_types::quorum_cert::QuorumCert;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::{IntoResponse, Json as JsonResponse},
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
 };
 use bytes::Bytes;
+use futures::{stream, Stream};
 use gaptos::{
     api_types::config_storage::{OnChainConfig, GLOBAL_CONFIG_STORAGE},
+    aptos_consensus::counters,
     aptos_crypto::HashValue,
     aptos_logger::{error, info},
     aptos_storage_interface::DbReader,
-    aptos_types::on_chain_config::{OnChainConfig as OnChainConfigTrait, ValidatorSet},
+    aptos_types::{
+        epoch_change::EpochChangeProof,
+        ledger_info::LedgerInfoWithSignatures,
+        on_chain_config::{OnChainConfig as OnChainConfigTrait, ValidatorSet},
+    },
+    move_core_types::account_address::AccountAddress,
 };
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Default and upper bound for how long long-polling endpoints may hold a request open.
+pub(crate) const DEFAULT_LONG_POLL_TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const MAX_LONG_POLL_TIMEOUT: Duration = Duration::from_secs(60);
+/// Upper bound on epoch-ending ledger infos returned by one `/consensus/epoch_change_proof` call.
+const MAX_EPOCH_CHANGE_PROOF_LEN: u64 = 100;
+/// Interval of the heartbeat comments sent on an idle `/consensus/stream/ledger_info` stream.
+const LEDGER_INFO_STREAM_HEARTBEAT: Duration = Duration::from_secs(15);
+/// How far above a block the ledger info committing it is searched for.
+const COMMIT_LOOKAHEAD_BLOCKS: u64 = 1024;
+/// Default and upper bound for the epoch transitions summarized by `/consensus/validator_churn`.
+const DEFAULT_CHURN_EPOCHS: u64 = 10;
+const MAX_CHURN_EPOCHS: u64 = 100;
+/// A node this many rounds behind the highest known round is reported as catching up.
+const CATCHING_UP_ROUNDS: u64 = 20;
+/// Committed blocks sampled to estimate the average round duration for `/consensus/sync_status`.
+const ROUND_DURATION_SAMPLE_BLOCKS: u64 = 100;
+/// Default and upper bound for the commits returned by `/consensus/commit_history`.
+const DEFAULT_COMMIT_HISTORY_LIMIT: usize = 100;
+const MAX_COMMIT_HISTORY_LIMIT: usize = 1000;
+/// Blocks read per step when walking back for `/consensus/commit_history`, and how far back the
+/// walk goes at most.
+const COMMIT_HISTORY_SCAN_BLOCKS: u64 = 1024;
+const MAX_COMMIT_HISTORY_SCAN_BLOCKS: u64 = 64 * 1024;
+/// Default and upper bound for the commits counted by `/consensus/signature_inclusion`, and how
+/// far back from the end of the epoch its scan goes at most.
+const DEFAULT_SIGNATURE_INCLUSION_LIMIT: usize = 1000;
+const MAX_SIGNATURE_INCLUSION_LIMIT: usize = 10_000;
+const MAX_SIGNATURE_INCLUSION_SCAN_BLOCKS: u64 = 64 * 1024;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct LedgerInfoResponse {
     pub epoch: u64,
     pub round: u64,
@@ -26,6 +80,18 @@ pub struct LedgerInfoResponse {
     pub block_hash: String, // hex encoded
 }
 
+impl From<&LedgerInfoWithSignatures> for LedgerInfoResponse {
+    fn from(ledger_info: &LedgerInfoWithSignatures) -> Self {
+        let ledger_info = ledger_info.ledger_info();
+        Self {
+            epoch: ledger_info.epoch(),
+            round: ledger_info.round(),
+            block_number: ledger_info.block_number(),
+            block_hash: hex::encode(ledger_info.block_hash().as_ref()),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BlockInfo {
     pub epoch: u64,
@@ -33,6 +99,9 @@ pub struct BlockInfo {
     pub block_number: Option<u64>,
     pub block_id: String,  // hex encoded
     pub parent_id: String, // hex encoded
+    /// Hex-encoded BCS bytes of the stored block, only present with `include_payload=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -45,8 +114,72 @@ pub struct QCInfo {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct ErrorResponse {
-    pub error: String,
+pub struct CommitProofResponse {
+    /// The block, payload included.
+    pub block: BlockInfo,
+    /// QC certifying the block, with its hex-encoded BCS bytes.
+    pub qc: QCInfo,
+    pub qc_bcs: String,
+    /// Descendants of the block up to and including the block the ledger info commits, oldest
+    /// first, payloads included. Empty when the ledger info commits the block itself.
+    pub ancestry: Vec<BlockInfo>,
+    /// Ledger info committing the block, with the hex-encoded BCS bytes of the signed ledger info.
+    pub ledger_info: LedgerInfoResponse,
+    pub ledger_info_bcs: String,
+}
+
+/// Query parameters of `/consensus/latest_ledger_info`.
+#[derive(Deserialize, Debug, Default)]
+pub struct LatestLedgerInfoQuery {
+    /// Hold the request until the latest ledger info reaches this block number.
+    pub wait_for_block: Option<u64>,
+    /// How long to wait for `wait_for_block`, e.g. `30s`, `500ms` or `10` (seconds).
+    pub timeout: Option<String>,
+}
+
+/// Query parameters of `/consensus/block/:epoch/:round`.
+#[derive(Deserialize, Debug, Default)]
+pub struct BlockQuery {
+    /// Also return the block's BCS bytes, payload included.
+    #[serde(default)]
+    pub include_payload: bool,
+}
+
+/// `?at_block=N` (or `?at_version=N`), accepted by every consensus read endpoint.
+///
+/// Reads the call from a snapshot of the DB taken when block N was the latest committed one, so
+/// "latest" means latest as of N and everything written since, blocks and QCs included, is out of
+/// view. Snapshots are taken by operators with `POST /admin/consensus_snapshot`; dashboards
+/// composing several calls pass its `block_number` to each of them, so a commit landing between
+/// the calls can't tear their view.
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+pub struct AtBlockQuery {
+    #[serde(alias = "at_version")]
+    pub at_block: Option<u64>,
+}
+
+impl AtBlockQuery {
+    /// The DB to read from: the live one, or with a pin the snapshot taken at the pinned block.
+    pub(crate) fn consensus_db(&self, dkg_state: &DkgState) -> Result<Arc<ConsensusDB>, ApiError> {
+        let consensus_db = dkg_state.require_consensus_db()?;
+        match self.at_block {
+            Some(block_number) => dkg_state.snapshots().get(consensus_db, block_number),
+            None => Ok(consensus_db.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EpochChangeProofResponse {
+    pub start_epoch: u64,
+    /// Epoch the proof advances to; lower than requested when `more` is set.
+    pub end_epoch: u64,
+    /// Hex-encoded BCS `EpochChangeProof`, verifiable against the validator set of `start_epoch`.
+    pub proof: String,
+    /// Epoch-ending ledger infos in the proof, in order.
+    pub ledger_infos: Vec<LedgerInfoResponse>,
+    /// The requested range was truncated; ask again starting at `end_epoch`.
+    pub more: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -56,42 +189,503 @@ pub struct ValidatorCountResponse {
     pub validator_count: usize,
 }
 
-/// Get latest ledger info
-/// Example: GET /consensus/latest_ledger_info
-pub fn get_latest_ledger_info(dkg_state: Arc<DkgState>) -> impl IntoResponse {
-    info!("Getting latest ledger info");
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ValidatorInfoResponse {
+    pub address: String,    // hex encoded
+    pub public_key: String, // hex encoded BLS12-381 public key
+    pub voting_power: u64,
+}
 
-    // Get ConsensusDB
-    let consensus_db = match dkg_state.consensus_db() {
-        Some(db) => db,
-        None => {
-            error!("ConsensusDB is not initialized");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                JsonResponse(ErrorResponse { error: "ConsensusDB is not initialized".to_string() }),
-            )
-                .into_response();
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ValidatorSetResponse {
+    pub epoch: u64,
+    /// Block whose ledger info ended the previous epoch and carries this validator set.
+    pub block_number: u64,
+    pub total_voting_power: u128,
+    pub quorum_voting_power: u128,
+    /// Validators in the order their signatures appear in certificates.
+    pub validators: Vec<ValidatorInfoResponse>,
+}
+
+/// Query parameters of `/consensus/validator_churn`.
+#[derive(Deserialize, Debug, Default)]
+pub struct ValidatorChurnQuery {
+    /// Number of most recent epoch transitions to summarize (default 10, at most 100).
+    pub epochs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EpochChurn {
+    /// Epoch whose validator set is compared with the one of `epoch - 1`.
+    pub epoch: u64,
+    /// Block whose ledger info ended `epoch - 1` and carries this epoch's validator set.
+    pub block_number: u64,
+    pub validator_count: usize,
+    /// Addresses (hex encoded) that joined or left the set at this transition.
+    pub joined: Vec<String>,
+    pub left: Vec<String>,
+    pub total_voting_power: u128,
+    /// Voting power that changed hands: half the sum of the absolute per-validator changes,
+    /// so a validator swapping in for another of equal power counts once.
+    pub voting_power_turnover: u128,
+    /// `voting_power_turnover` over the previous epoch's total voting power.
+    pub turnover_ratio: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValidatorChurnResponse {
+    /// Current epoch, as of the latest (or pinned) ledger info.
+    pub latest_epoch: u64,
+    /// Most recent transitions first.
+    pub transitions: Vec<EpochChurn>,
+    pub total_joined: usize,
+    pub total_left: usize,
+    pub max_turnover_ratio: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Following consensus round by round.
+    Synced,
+    /// Following consensus, but more than `CATCHING_UP_ROUNDS` rounds behind.
+    CatchingUp,
+    /// Fetching committed state from peers (`sync_to`) instead of executing rounds.
+    StateSync,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SyncStatusResponse {
+    pub epoch: u64,
+    pub committed_round: u64,
+    pub committed_block_number: u64,
+    /// Round the local round manager is in; absent when this node does not run consensus rounds.
+    pub current_round: Option<u64>,
+    /// Highest round certified by a QC stored in the consensus DB for the current epoch.
+    pub highest_certified_round: Option<u64>,
+    /// Highest of the committed, current and certified rounds.
+    pub highest_known_round: u64,
+    pub rounds_behind: u64,
+    pub sync_mode: SyncMode,
+    /// `rounds_behind` times the average round duration of recently committed blocks.
+    pub estimated_lag_secs: f64,
+    /// Wall-clock time since the timestamp of the latest committed block.
+    pub last_commit_age_secs: f64,
+}
+
+/// Query parameters of `/consensus/commit_history`.
+#[derive(Deserialize, Debug, Default)]
+pub struct CommitHistoryQuery {
+    /// Number of most recent commits to return (default 100, at most 1000).
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CommitEntry {
+    pub epoch: u64,
+    pub round: u64,
+    pub block_number: u64,
+    pub block_hash: String, // hex encoded
+    /// Timestamp of the committed block, in microseconds since the Unix epoch.
+    pub timestamp_usecs: u64,
+    /// Time since the previous commit, in milliseconds. Absent for the oldest commit returned.
+    pub interval_ms: Option<f64>,
+    /// Blocks committed by this commit; more than one when commits were batched.
+    pub blocks: Option<u64>,
+    /// Rounds between the previous commit and this one that committed nothing, e.g. after
+    /// timeouts. Absent for the oldest commit and across epoch changes, where rounds restart.
+    pub skipped_rounds: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CommitHistoryResponse {
+    /// Most recent commits first.
+    pub commits: Vec<CommitEntry>,
+    pub total_skipped_rounds: u64,
+}
+
+/// Query parameters of `/consensus/signature_inclusion/:epoch`.
+#[derive(Deserialize, Debug, Default)]
+pub struct SignatureInclusionQuery {
+    /// Number of most recent commits of the epoch to count (default 1000, at most 10000).
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValidatorInclusion {
+    pub address: String, // hex encoded
+    pub voting_power: u64,
+    /// Commits whose aggregate signature includes this validator.
+    pub included: u64,
+    /// `included` over the commits counted, between 0 and 1.
+    pub inclusion_rate: f64,
+    /// Newest commit including this validator, if any was counted.
+    pub last_included_block: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SignatureInclusionResponse {
+    pub epoch: u64,
+    /// Block range of the commits counted; absent when the epoch has none stored.
+    pub first_block: Option<u64>,
+    pub last_block: Option<u64>,
+    pub commits: u64,
+    /// Validators in the order their signatures appear in certificates.
+    pub validators: Vec<ValidatorInclusion>,
+}
+
+/// Query parameters of `/consensus/evidence`.
+#[derive(Deserialize, Debug, Default)]
+pub struct EvidenceQuery {
+    /// Only evidence from this epoch.
+    pub epoch: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EvidenceEntry {
+    /// `vote` or `proposal`.
+    pub kind: String,
+    pub epoch: u64,
+    pub round: u64,
+    pub author: String, // hex encoded
+    /// BCS encoded signed messages (hex), the one received first and the conflicting one.
+    pub first: String,
+    pub second: String,
+    /// When this node detected the conflict, in microseconds since the Unix epoch.
+    pub detected_at_usecs: u64,
+}
+
+impl From<EquivocationEvidence> for EvidenceEntry {
+    fn from(evidence: EquivocationEvidence) -> Self {
+        Self {
+            kind: match evidence.kind {
+                EvidenceKind::Vote => "vote",
+                EvidenceKind::Proposal => "proposal",
+            }
+            .to_string(),
+            epoch: evidence.epoch,
+            round: evidence.round,
+            author: hex::encode(evidence.author.as_ref()),
+            first: hex::encode(&evidence.first),
+            second: hex::encode(&evidence.second),
+            detected_at_usecs: evidence.detected_at_usecs,
         }
-    };
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EvidenceResponse {
+    /// Oldest first.
+    pub evidence: Vec<EvidenceEntry>,
+}
+
+/// Counts, for each of `validators`, the commits whose signers include it. `commits` are
+/// `(block_number, signers)`, oldest first.
+fn count_inclusion(
+    validators: &[(AccountAddress, u64)],
+    commits: &[(u64, Vec<AccountAddress>)],
+) -> Vec<ValidatorInclusion> {
+    validators
+        .iter()
+        .map(|(address, voting_power)| {
+            let mut included = 0;
+            let mut last_included_block = None;
+            for (block_number, signers) in commits {
+                if signers.contains(address) {
+                    included += 1;
+                    last_included_block = Some(*block_number);
+                }
+            }
+            ValidatorInclusion {
+                address: hex::encode(address.as_ref()),
+                voting_power: *voting_power,
+                included,
+                inclusion_rate: if commits.is_empty() {
+                    0.0
+                } else {
+                    included as f64 / commits.len() as f64
+                },
+                last_included_block,
+            }
+        })
+        .collect()
+}
 
-    // Get latest ledger info using DbReader trait
-    match DbReader::get_latest_ledger_info(consensus_db.as_ref()) {
-        Ok(info) => {
-            let ledger_info = info.ledger_info();
-            let response = LedgerInfoResponse {
+/// Fills the gaps between consecutive commits, given oldest first.
+fn fill_commit_gaps(commits: &mut [CommitEntry]) {
+    for i in 1..commits.len() {
+        let (previous, current) = (&commits[i - 1], &commits[i]);
+        let interval_ms =
+            current.timestamp_usecs.saturating_sub(previous.timestamp_usecs) as f64 / 1000.0;
+        let blocks = current.block_number.saturating_sub(previous.block_number);
+        let skipped_rounds = (current.epoch == previous.epoch)
+            .then(|| current.round.saturating_sub(previous.round).saturating_sub(blocks));
+        let current = &mut commits[i];
+        current.interval_ms = Some(interval_ms);
+        current.blocks = Some(blocks);
+        current.skipped_rounds = skipped_rounds;
+    }
+}
+
+/// Recent commits with their timestamps and gaps
+/// Example: GET /consensus/commit_history?limit=100
+///
+/// Returns the last `limit` ledger infos with the wall-clock time and the number of rounds
+/// between consecutive commits, so dashboards can compute block time percentiles and spot
+/// round skipping (timeouts) without an indexer.
+pub fn get_commit_history(
+    State(dkg_state): State<Arc<DkgState>>,
+    Query(query): Query<CommitHistoryQuery>,
+) -> Result<axum::Json<CommitHistoryResponse>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_COMMIT_HISTORY_LIMIT);
+    if limit == 0 || limit > MAX_COMMIT_HISTORY_LIMIT {
+        return Err(ApiError::bad_request(format!(
+            "limit must be between 1 and {MAX_COMMIT_HISTORY_LIMIT}"
+        )));
+    }
+    let consensus_db = dkg_state.require_consensus_db()?;
+    let head = latest_ledger_info(consensus_db)?.ledger_info().block_number();
+
+    // One commit more than requested gives the gaps of the oldest one returned.
+    let mut ledger_infos = Vec::new();
+    let mut end = head + 1;
+    let floor = end.saturating_sub(MAX_COMMIT_HISTORY_SCAN_BLOCKS);
+    while ledger_infos.len() <= limit && end > floor {
+        let start = end.saturating_sub(COMMIT_HISTORY_SCAN_BLOCKS).max(floor);
+        let mut batch = db_span("consensus_db.get_ledger_infos", || {
+            consensus_db.get_range::<LedgerInfoSchema>(&start, &end)
+        })
+        .map_err(|e| {
+            error!("Failed to read ledger infos from {} to {}: {:?}", start, end, e);
+            ApiError::internal()
+        })?;
+        batch.append(&mut ledger_infos);
+        ledger_infos = batch;
+        end = start;
+    }
+    let skip = ledger_infos.len().saturating_sub(limit + 1);
+
+    let mut commits: Vec<CommitEntry> = ledger_infos[skip..]
+        .iter()
+        .map(|(_, ledger_info)| {
+            let ledger_info = ledger_info.ledger_info();
+            CommitEntry {
                 epoch: ledger_info.epoch(),
                 round: ledger_info.round(),
                 block_number: ledger_info.block_number(),
                 block_hash: hex::encode(ledger_info.block_hash().as_ref()),
-            };
-            JsonResponse(response).into_response()
-        }
-        Err(e) => {
-            error!("Failed to get latest ledger info: {:?}", e);
-            JsonResponse(ErrorResponse { error: "Internal server error".to_string() })
-                .into_response()
-        }
+                timestamp_usecs: ledger_info.timestamp_usecs(),
+                interval_ms: None,
+                blocks: None,
+                skipped_rounds: None,
+            }
+        })
+        .collect();
+    fill_commit_gaps(&mut commits);
+    let drop_oldest = commits.len().saturating_sub(limit);
+    commits.drain(..drop_oldest);
+    commits.reverse();
+
+    Ok(axum::Json(CommitHistoryResponse {
+        total_skipped_rounds: commits.iter().filter_map(|c| c.skipped_rounds).sum(),
+        commits,
+    }))
+}
+
+/// Per-validator signature inclusion in the commit certificates of an epoch
+/// Example: GET /v1/consensus/signature_inclusion/12?limit=500
+///
+/// Only a quorum of signatures makes it into each commit's aggregate signature. A validator that
+/// is chronically left out while online votes too late, typically because of network latency or
+/// clock and timing problems on its host, and loses rewards for it.
+pub fn get_signature_inclusion(
+    State(dkg_state): State<Arc<DkgState>>,
+    Path(epoch): Path<u64>,
+    Query(query): Query<SignatureInclusionQuery>,
+    Query(at): Query<AtBlockQuery>,
+) -> Result<axum::Json<SignatureInclusionResponse>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_SIGNATURE_INCLUSION_LIMIT);
+    if limit == 0 || limit > MAX_SIGNATURE_INCLUSION_LIMIT {
+        return Err(ApiError::bad_request(format!(
+            "limit must be between 1 and {MAX_SIGNATURE_INCLUSION_LIMIT}"
+        )));
+    }
+    info!("Getting signature inclusion for epoch={}", epoch);
+
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+    let previous_epoch = epoch.checked_sub(1).ok_or_else(|| {
+        ApiError::not_found("Epoch 0 has no epoch-ending ledger info carrying its validator set")
+    })?;
+    let epoch_endings = db_span("consensus_db.get_epoch_endings", || {
+        consensus_db.get_all::<EpochByBlockNumberSchema>()
+    })
+    .map_err(|e| {
+        error!("Failed to get epoch by block number: {:?}", e);
+        ApiError::internal()
+    })?;
+    let ending_of = |target: u64| {
+        epoch_endings
+            .iter()
+            .find(|(_, epoch_)| *epoch_ == target)
+            .map(|(block_number, _)| *block_number)
+    };
+    let start = ending_of(previous_epoch).ok_or_else(|| {
+        ApiError::not_found(format!("Cannot find block number for epoch {previous_epoch}"))
+    })?;
+    // A running epoch ends, for now, at the latest commit.
+    let end = match ending_of(epoch) {
+        Some(end) => end,
+        None => latest_ledger_info(consensus_db)?.ledger_info().block_number(),
+    };
+
+    let epoch_state =
+        db_span("consensus_db.get_ledger_info", || consensus_db.get::<LedgerInfoSchema>(&start))
+            .map_err(|e| {
+                error!("Failed to get ledger info for block_number={}: {:?}", start, e);
+                ApiError::internal()
+            })?
+            .and_then(|ledger_info| ledger_info.ledger_info().next_epoch_state().cloned())
+            .filter(|epoch_state| epoch_state.epoch == epoch)
+            .ok_or_else(|| {
+                ApiError::not_found(format!("Validator set not found for epoch {epoch}"))
+            })?;
+    let verifier = &epoch_state.verifier;
+    let addresses = verifier.get_ordered_account_addresses();
+
+    let scan_start = (start + 1).max((end + 1).saturating_sub(MAX_SIGNATURE_INCLUSION_SCAN_BLOCKS));
+    let ledger_infos = db_span("consensus_db.get_ledger_infos", || {
+        consensus_db.get_range::<LedgerInfoSchema>(&scan_start, &(end + 1))
+    })
+    .map_err(|e| {
+        error!("Failed to read ledger infos from {} to {}: {:?}", scan_start, end + 1, e);
+        ApiError::internal()
+    })?;
+    let mut commits: Vec<(u64, Vec<AccountAddress>)> = ledger_infos
+        .iter()
+        .filter(|(_, ledger_info)| ledger_info.ledger_info().epoch() == epoch)
+        .map(|(block_number, ledger_info)| {
+            (*block_number, ledger_info.signatures().get_signers_addresses(&addresses))
+        })
+        .collect();
+    let skip = commits.len().saturating_sub(limit);
+    commits.drain(..skip);
+
+    let validators: Vec<(AccountAddress, u64)> = addresses
+        .iter()
+        .map(|address| (*address, verifier.get_voting_power(address).unwrap_or_default()))
+        .collect();
+    Ok(axum::Json(SignatureInclusionResponse {
+        epoch,
+        first_block: commits.first().map(|(block_number, _)| *block_number),
+        last_block: commits.last().map(|(block_number, _)| *block_number),
+        commits: commits.len() as u64,
+        validators: count_inclusion(&validators, &commits),
+    }))
+}
+
+/// Get latest ledger info
+/// Example: GET /consensus/latest_ledger_info
+///
+/// With `?wait_for_block=N[&timeout=30s]` the request is held until a ledger info with
+/// `block_number >= N` is committed or the timeout elapses. Either way the latest ledger info is
+/// returned, so callers compare `block_number` against `N` to tell the two apart.
+///
+/// With `?at_block=N` the latest ledger info as of block N is returned instead.
+///
+/// Like the other `/consensus` reads, `Accept: application/x-bcs` returns the BCS bytes of the
+/// signed `LedgerInfoWithSignatures` instead of the JSON summary.
+pub async fn get_latest_ledger_info(
+    dkg_state: Arc<DkgState>,
+    query: LatestLedgerInfoQuery,
+    at: AtBlockQuery,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    info!(
+        "Getting latest ledger info, wait_for_block={:?}, at_block={:?}",
+        query.wait_for_block, at.at_block
+    );
+
+    if query.wait_for_block.is_some() && at.at_block.is_some() {
+        return Err(ApiError::bad_request("wait_for_block and at_block cannot be combined"));
+    }
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+
+    if let Some(target) = query.wait_for_block {
+        let timeout = query
+            .timeout
+            .as_deref()
+            .map(parse_timeout)
+            .transpose()
+            .map_err(ApiError::bad_request)?
+            .unwrap_or(DEFAULT_LONG_POLL_TIMEOUT)
+            .min(MAX_LONG_POLL_TIMEOUT);
+        let mut commits = consensus_db.subscribe_commits();
+        // Woken by the commit notification channel; a closed channel or an elapsed timeout
+        // falls through to returning whatever is latest.
+        let _ = tokio::time::timeout(timeout, commits.wait_for(|block| *block >= target)).await;
     }
+
+    let info = latest_ledger_info(consensus_db)?;
+    format.render(LedgerInfoResponse::from(&info), &info)
+}
+
+/// Stream the latest ledger info as server-sent events
+/// Example: GET /consensus/stream/ledger_info
+///
+/// For clients that can't use WebSockets. The current ledger info is sent right away, then a
+/// `ledger_info` event every time it advances, with the block number as the event id. A client
+/// that falls behind skips to the newest ledger info. Heartbeat comments keep idle connections
+/// open through proxies. Each stream holds a subscription slot until the client disconnects.
+pub(crate) async fn stream_ledger_info(
+    subscriptions: Arc<Subscriptions>,
+    State(dkg_state): State<Arc<DkgState>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let consensus_db = dkg_state.require_consensus_db()?.clone();
+    let permit = subscriptions.acquire()?;
+    let mut commits = consensus_db.subscribe_commits();
+    commits.borrow_and_update();
+    info!("Streaming ledger info updates, {} streams open", subscriptions.open());
+
+    let events = stream::unfold(
+        (consensus_db, commits, None::<u64>, permit),
+        |(consensus_db, mut commits, last_sent, permit)| async move {
+            loop {
+                if last_sent.is_some() {
+                    // The sender is dropped when the node shuts down, which ends the stream.
+                    commits.changed().await.ok()?;
+                }
+                let latest = match DbReader::get_latest_ledger_info(consensus_db.as_ref()) {
+                    Ok(latest) => LedgerInfoResponse::from(&latest),
+                    Err(e) => {
+                        error!("Failed to get latest ledger info: {:?}", e);
+                        if last_sent.is_none() {
+                            return None;
+                        }
+                        continue;
+                    }
+                };
+                // Unwinds also notify; only an advancing ledger info is sent.
+                if last_sent.is_some_and(|sent| latest.block_number <= sent) {
+                    continue;
+                }
+                let event = match Event::default()
+                    .event("ledger_info")
+                    .id(latest.block_number.to_string())
+                    .json_data(&latest)
+                {
+                    Ok(event) => event,
+                    Err(e) => {
+                        error!("Failed to encode ledger info event: {:?}", e);
+                        return None;
+                    }
+                };
+                let block_number = latest.block_number;
+                return Some((Ok(event), (consensus_db, commits, Some(block_number), permit)));
+            }
+        },
+    );
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::new().interval(LEDGER_INFO_STREAM_HEARTBEAT).text("heartbeat")))
 }
 
 /// Get ledger info by epoch
@@ -99,272 +693,787 @@ pub fn get_latest_ledger_info(dkg_state: Arc<DkgState>) -> impl IntoResponse {
 pub fn get_ledger_info_by_epoch(
     State(dkg_state): State<Arc<DkgState>>,
     Path(epoch): Path<u64>,
-) -> Result<(StatusCode, JsonResponse<LedgerInfoResponse>), (StatusCode, JsonResponse<ErrorResponse>)>
-{
+    Query(at): Query<AtBlockQuery>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
     info!("Getting ledger info for epoch={}", epoch);
 
-    let consensus_db = match dkg_state.consensus_db() {
-        Some(db) => db,
-        None => {
-            return Err(error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "ConsensusDB is not initialized",
-            ));
-        }
-    };
-
-    // Get all epoch by block number mappings
-    let all_epoch_blocks = match consensus_db.get_all::<EpochByBlockNumberSchema>() {
-        Ok(blocks) => blocks,
-        Err(e) => {
-            error!("Failed to get epoch by block number: {:?}", e);
-            return Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error"));
-        }
-    };
-
-    // Find the block number for the target epoch
-    let target_block_number = all_epoch_blocks
-        .into_iter()
-        .find(|(_, epoch_)| *epoch_ == epoch)
-        .map(|(block_number, _)| block_number)
-        .ok_or_else(|| {
-            error!("Cannot find block number for epoch {}", epoch);
-            error_response(
-                StatusCode::NOT_FOUND,
-                &format!("Cannot find block number for epoch {epoch}"),
-            )
-        })?;
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+    let target_block_number = block_number_for_epoch(consensus_db, epoch)?;
 
     // Get the ledger info for the target block number
-    match consensus_db.get::<LedgerInfoSchema>(&target_block_number) {
+    match db_span("consensus_db.get_ledger_info", || {
+        consensus_db.get::<LedgerInfoSchema>(&target_block_number)
+    }) {
         Ok(Some(ledger_info)) => {
-            let ledger_info_inner = ledger_info.ledger_info();
-            let response = LedgerInfoResponse {
-                epoch: ledger_info_inner.epoch(),
-                round: ledger_info_inner.round(),
-                block_number: ledger_info_inner.block_number(),
-                block_hash: hex::encode(ledger_info_inner.block_hash().as_ref()),
-            };
             info!(
                 "Successfully retrieved ledger info for epoch={}, block_number={}",
                 epoch, target_block_number
             );
-            Ok((StatusCode::OK, JsonResponse(response)))
+            format.render(LedgerInfoResponse::from(&ledger_info), &ledger_info)
         }
         Ok(None) => {
             error!(
                 "Ledger info not found for block_number={} (epoch={})",
                 target_block_number, epoch
             );
-            Err(error_response(
-                StatusCode::NOT_FOUND,
-                &format!(
-                    "Ledger info not found for block_number={target_block_number} (epoch={epoch})"
-                ),
-            ))
+            Err(ApiError::not_found(format!(
+                "Ledger info not found for block_number={target_block_number} (epoch={epoch})"
+            )))
         }
         Err(e) => {
             error!("Failed to get ledger info for block_number={}: {:?}", target_block_number, e);
-            Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error"))
+            Err(ApiError::internal())
+        }
+    }
+}
+
+/// Get the commit certificate (ledger info) persisted for a block number
+/// Example: GET /consensus/commit/:block_number
+pub fn get_commit_by_block_number(
+    State(dkg_state): State<Arc<DkgState>>,
+    Path(block_number): Path<u64>,
+    Query(at): Query<AtBlockQuery>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    info!("Getting commit for block_number={}", block_number);
+
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+
+    match db_span("consensus_db.get_ledger_info", || {
+        consensus_db.get::<LedgerInfoSchema>(&block_number)
+    }) {
+        Ok(Some(ledger_info)) => {
+            format.render(LedgerInfoResponse::from(&ledger_info), &ledger_info)
+        }
+        Ok(None) => {
+            Err(ApiError::not_found(format!("Commit not found for block_number={block_number}")))
+        }
+        Err(e) => {
+            error!("Failed to get ledger info for block_number={}: {:?}", block_number, e);
+            Err(ApiError::internal())
         }
     }
 }
 
 /// Get block by epoch and round
-/// Example: GET /consensus/block/:epoch/:round
+/// Example: GET /consensus/block/:epoch/:round[?include_payload=true]
+///
+/// With `Accept: application/x-bcs` the stored BCS bytes of the block are returned as they are.
+/// Committed blocks are cached and served with an `ETag` and an immutable `Cache-Control`.
 pub fn get_block(
     State(dkg_state): State<Arc<DkgState>>,
     Path((epoch, round)): Path<(u64, u64)>,
-) -> Result<(StatusCode, JsonResponse<BlockInfo>), (StatusCode, JsonResponse<ErrorResponse>)> {
+    Query(query): Query<BlockQuery>,
+    Query(at): Query<AtBlockQuery>,
+    headers: HeaderMap,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
     info!("Getting block for epoch={}, round={}", epoch, round);
 
-    let consensus_db = match dkg_state.consensus_db() {
-        Some(db) => db,
-        None => {
-            return Err(error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "ConsensusDB is not initialized",
-            ));
-        }
-    };
+    let consensus_db = &at.consensus_db(&dkg_state)?;
 
-    // Get block by epoch and round
-    match get_block_by_round(consensus_db, epoch, round) {
-        Some(block_info) => {
+    // Get block by epoch and round; the cache may hold blocks committed after a pin.
+    let cached = at.at_block.is_none().then(|| response_cache::block(epoch, round)).flatten();
+    let block = match cached {
+        Some(block) => Some((block, true)),
+        None => find_block_by_round(consensus_db, epoch, round).map(|(block_id, header, raw)| {
+            let block = Arc::new(CachedBlock {
+                block_id,
+                header,
+                raw_block: raw,
+                block_number: block_number_of(consensus_db, header.epoch, block_id),
+            });
+            let committed = response_cache::is_committed(consensus_db, block.block_number);
+            if committed {
+                response_cache::insert_block(epoch, round, block.clone());
+            }
+            (block, committed)
+        }),
+    };
+    match block {
+        Some((block, committed)) => {
             info!("Successfully retrieved block for epoch={}, round={}", epoch, round);
-            Ok((StatusCode::OK, JsonResponse(block_info)))
+            let render = || {
+                let block_info = block_info(
+                    block.block_id,
+                    block.header,
+                    block.block_number,
+                    &block.raw_block,
+                    query.include_payload,
+                );
+                Ok(format.render_bytes(block_info, block.raw_block.0.clone()))
+            };
+            if !committed {
+                return render().map(|response| cold_tier::mark(response, epoch));
+            }
+            let variant = if query.include_payload { "-payload" } else { "" };
+            response_cache::immutable(
+                &headers,
+                response_cache::etag("block", block.block_id, format, variant),
+                render,
+            )
+            .map(|response| cold_tier::mark(response, epoch))
         }
         None => {
             error!("Block not found for epoch={}, round={}", epoch, round);
-            Err(error_response(
-                StatusCode::NOT_FOUND,
-                &format!("Block not found for epoch={epoch}, round={round}"),
-            ))
+            Err(ApiError::not_found(format!("Block not found for epoch={epoch}, round={round}")))
+        }
+    }
+}
+
+/// Get block by its id, for callers that only know the block hash
+/// Example: GET /consensus/block_by_hash/:hash[?include_payload=true]
+pub fn get_block_by_hash(
+    State(dkg_state): State<Arc<DkgState>>,
+    Path(hash): Path<HashValue>,
+    Query(query): Query<BlockQuery>,
+    Query(at): Query<AtBlockQuery>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    info!("Getting block by hash {}", hash);
+
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+
+    let block = find_block_by_id(consensus_db, hash)?.map(|(header, raw)| {
+        (to_block_info(consensus_db, hash, header, &raw, query.include_payload), raw)
+    });
+    match block {
+        Some((block_info, raw_block)) => {
+            let epoch = block_info.epoch;
+            Ok(cold_tier::mark(format.render_bytes(block_info, raw_block.0), epoch))
         }
+        None => Err(ApiError::not_found(format!("Block not found for hash={hash}"))),
     }
 }
 
 /// Get QC by epoch and round
 /// Example: GET /consensus/qc/:epoch/:round
+///
+/// QCs of committed blocks are cached and served with an `ETag` and an immutable `Cache-Control`.
 pub fn get_qc(
     State(dkg_state): State<Arc<DkgState>>,
     Path((epoch, round)): Path<(u64, u64)>,
-) -> Result<(StatusCode, JsonResponse<QCInfo>), (StatusCode, JsonResponse<ErrorResponse>)> {
+    Query(at): Query<AtBlockQuery>,
+    headers: HeaderMap,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
     info!("Getting QC for epoch={}, round={}", epoch, round);
 
-    let consensus_db = match dkg_state.consensus_db() {
-        Some(db) => db,
-        None => {
-            return Err(error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "ConsensusDB is not initialized",
-            ));
-        }
-    };
+    let consensus_db = &at.consensus_db(&dkg_state)?;
 
-    // Get QC by epoch and round
-    match get_qc_by_round(consensus_db, epoch, round) {
-        Some(qc_info) => {
+    // Get QC by epoch and round; the cache may hold QCs committed after a pin.
+    let cached = at.at_block.is_none().then(|| response_cache::qc(epoch, round)).flatten();
+    let qc = match cached {
+        Some(qc) => Some((qc, true)),
+        None => find_qc_by_round(consensus_db, epoch, round).map(|qc| {
+            let block_number = block_number_of(consensus_db, epoch, qc.certified_block().id());
+            let qc = Arc::new(CachedQc { qc, block_number });
+            let committed = response_cache::is_committed(consensus_db, qc.block_number);
+            if committed {
+                response_cache::insert_qc(epoch, round, qc.clone());
+            }
+            (qc, committed)
+        }),
+    };
+    match qc {
+        Some((qc, committed)) => {
             info!("Successfully retrieved QC for epoch={}, round={}", epoch, round);
-            Ok((StatusCode::OK, JsonResponse(qc_info)))
+            let render = || format.render(qc_info(&qc.qc, qc.block_number), &qc.qc);
+            if !committed {
+                return render().map(|response| cold_tier::mark(response, epoch));
+            }
+            response_cache::immutable(
+                &headers,
+                response_cache::etag("qc", qc.qc.certified_block().id(), format, ""),
+                render,
+            )
+            .map(|response| cold_tier::mark(response, epoch))
         }
         None => {
             error!("QC not found for epoch={}, round={}", epoch, round);
-            Err(error_response(
-                StatusCode::NOT_FOUND,
-                &format!("QC not found for epoch={epoch}, round={round}"),
+            Err(ApiError::not_found(format!("QC not found for epoch={epoch}, round={round}")))
+        }
+    }
+}
+
+/// Get everything needed to verify that the block of `epoch` at `round` is committed
+/// Example: GET /consensus/commit_proof/:epoch/:round
+///
+/// Bundles the block, its QC, the blocks linking it to the committed block and the signed ledger
+/// info committing it, so external verifiers need one request instead of three. With
+/// `Accept: application/x-bcs` the body is the BCS encoding of
+/// `(block bytes, QuorumCert, Vec<ancestry block bytes>, LedgerInfoWithSignatures)`.
+pub fn get_commit_proof(
+    State(dkg_state): State<Arc<DkgState>>,
+    Path((epoch, round)): Path<(u64, u64)>,
+    Query(at): Query<AtBlockQuery>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    info!("Getting commit proof for epoch={}, round={}", epoch, round);
+
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+    let (block_id, header, raw_block) = find_block_by_round(consensus_db, epoch, round)
+        .ok_or_else(|| {
+            ApiError::not_found(format!("Block not found for epoch={epoch}, round={round}"))
+        })?;
+    let block = to_block_info(consensus_db, block_id, header, &raw_block, true);
+    let block_number = block.block_number.ok_or_else(|| {
+        ApiError::not_found(format!("Block for epoch={epoch}, round={round} is not committed"))
+    })?;
+    let qc = find_qc_by_round(consensus_db, epoch, round).ok_or_else(|| {
+        ApiError::not_found(format!("QC not found for epoch={epoch}, round={round}"))
+    })?;
+
+    // A ledger info is only stored for the last block of each commit, so the block is covered
+    // by the first one at or above its block number.
+    let end = block_number.saturating_add(COMMIT_LOOKAHEAD_BLOCKS);
+    let ledger_info = db_span("consensus_db.get_ledger_infos", || {
+        consensus_db.get_range::<LedgerInfoSchema>(&block_number, &end)
+    })
+    .map_err(|e| {
+        error!("Failed to read ledger infos from {} to {}: {:?}", block_number, end, e);
+        ApiError::internal()
+    })?
+    .into_iter()
+    .map(|(_, ledger_info)| ledger_info)
+    .next()
+    .filter(|ledger_info| ledger_info.ledger_info().epoch() == epoch)
+    .ok_or_else(|| {
+        ApiError::not_found(format!("No ledger info commits block {block_number} yet"))
+    })?;
+
+    let ancestry =
+        find_ancestry(consensus_db, epoch, block_id, ledger_info.ledger_info().commit_info().id())?;
+
+    let qc_bcs = bcs::to_bytes(&qc).map_err(|e| {
+        error!("Failed to serialize QC: {:?}", e);
+        ApiError::internal()
+    })?;
+    let ledger_info_bcs = bcs::to_bytes(&ledger_info).map_err(|e| {
+        error!("Failed to serialize ledger info: {:?}", e);
+        ApiError::internal()
+    })?;
+    let response = CommitProofResponse {
+        block,
+        qc: to_qc_info(consensus_db, epoch, &qc),
+        qc_bcs: hex::encode(qc_bcs),
+        ancestry: ancestry
+            .iter()
+            .map(|(id, header, raw)| to_block_info(consensus_db, *id, *header, raw, true))
+            .collect(),
+        ledger_info: LedgerInfoResponse::from(&ledger_info),
+        ledger_info_bcs: hex::encode(ledger_info_bcs),
+    };
+    let ancestry_bytes: Vec<&Vec<u8>> = ancestry.iter().map(|(_, _, raw)| &raw.0).collect();
+    format
+        .render(response, &(&raw_block.0, &qc, ancestry_bytes, &ledger_info))
+        .map(|response| cold_tier::mark(response, epoch))
+}
+
+/// Blocks from `committed_id` back to, but excluding, `block_id`, oldest first, found by
+/// following parent ids within `epoch`.
+fn find_ancestry(
+    consensus_db: &ConsensusDB,
+    epoch: u64,
+    block_id: HashValue,
+    committed_id: HashValue,
+) -> Result<Vec<(HashValue, BlockHeader, RawBlock)>, ApiError> {
+    if committed_id == block_id {
+        return Ok(Vec::new());
+    }
+    let mut blocks: BTreeMap<HashValue, RawBlock> =
+        raw_blocks_in_epoch(consensus_db, epoch)?.into_iter().collect();
+
+    let mut ancestry = Vec::new();
+    let mut current = committed_id;
+    while current != block_id {
+        let raw_block = blocks.remove(&current).ok_or_else(|| {
+            ApiError::not_found(format!(
+                "Block {current} linking {block_id} to its commit is no longer stored"
             ))
+        })?;
+        let header = raw_block.header().map_err(|e| {
+            error!("Failed to read header of block {}: {:?}", current, e);
+            ApiError::internal()
+        })?;
+        let parent_id = header.parent_id;
+        ancestry.push((current, header, raw_block));
+        current = parent_id;
+    }
+    ancestry.reverse();
+    Ok(ancestry)
+}
+
+/// Get the epoch-ending ledger infos that take a light client from `start_epoch` to `end_epoch`
+/// Example: GET /consensus/epoch_change_proof/:start_epoch/:end_epoch
+///
+/// Each ledger info ends one epoch and is signed by that epoch's validators, and carries the next
+/// epoch's validator set, so a client trusting `start_epoch` can verify them in sequence. At most
+/// `MAX_EPOCH_CHANGE_PROOF_LEN` epochs are returned per call.
+pub fn get_epoch_change_proof(
+    State(dkg_state): State<Arc<DkgState>>,
+    Path((start_epoch, end_epoch)): Path<(u64, u64)>,
+    Query(at): Query<AtBlockQuery>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    info!("Getting epoch change proof from epoch {} to {}", start_epoch, end_epoch);
+
+    if start_epoch >= end_epoch {
+        return Err(ApiError::bad_request(format!(
+            "start_epoch ({start_epoch}) must be lower than end_epoch ({end_epoch})"
+        )));
+    }
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+
+    let latest_epoch = latest_ledger_info(consensus_db)?.ledger_info().next_block_epoch();
+    if end_epoch > latest_epoch {
+        return Err(ApiError::not_found(format!(
+            "end_epoch {end_epoch} is ahead of the current epoch {latest_epoch}"
+        )));
+    }
+    let limit = end_epoch.min(start_epoch + MAX_EPOCH_CHANGE_PROOF_LEN);
+
+    let mut epoch_endings = db_span("consensus_db.get_epoch_endings", || {
+        consensus_db.get_all::<EpochByBlockNumberSchema>()
+    })
+    .map_err(|e| {
+        error!("Failed to get epoch by block number: {:?}", e);
+        ApiError::internal()
+    })?;
+    epoch_endings.retain(|(_, epoch)| (start_epoch..limit).contains(epoch));
+    epoch_endings.sort_by_key(|(_, epoch)| *epoch);
+
+    let mut ledger_infos = Vec::with_capacity(epoch_endings.len());
+    for (expected_epoch, (block_number, epoch)) in (start_epoch..).zip(&epoch_endings) {
+        if *epoch != expected_epoch {
+            break;
+        }
+        match db_span("consensus_db.get_ledger_info", || {
+            consensus_db.get::<LedgerInfoSchema>(block_number)
+        }) {
+            Ok(Some(ledger_info)) => ledger_infos.push(ledger_info),
+            Ok(None) => break,
+            Err(e) => {
+                error!("Failed to get ledger info for block_number={}: {:?}", block_number, e);
+                return Err(ApiError::internal());
+            }
         }
     }
+    if (ledger_infos.len() as u64) < limit - start_epoch {
+        let missing = start_epoch + ledger_infos.len() as u64;
+        return Err(ApiError::not_found(format!(
+            "Epoch-ending ledger info for epoch {missing} is not available"
+        )));
+    }
+
+    let more = limit < end_epoch;
+    let summaries = ledger_infos.iter().map(LedgerInfoResponse::from).collect();
+    let proof = EpochChangeProof::new(ledger_infos, more);
+    let proof_bytes = bcs::to_bytes(&proof).map_err(|e| {
+        error!("Failed to serialize epoch change proof: {:?}", e);
+        ApiError::internal()
+    })?;
+    let response = EpochChangeProofResponse {
+        start_epoch,
+        end_epoch: limit,
+        proof: hex::encode(&proof_bytes),
+        ledger_infos: summaries,
+        more,
+    };
+    Ok(format.render_bytes(response, proof_bytes))
 }
 
 /// Get validator count by epoch
 /// Example: GET /consensus/validator_count/:epoch
+///
+/// With `Accept: application/x-bcs` the on-chain `ValidatorSet` config bytes are returned.
 pub fn get_validator_count_by_epoch(
     State(dkg_state): State<Arc<DkgState>>,
     Path(epoch): Path<u64>,
-) -> Result<
-    (StatusCode, JsonResponse<ValidatorCountResponse>),
-    (StatusCode, JsonResponse<ErrorResponse>),
-> {
+    Query(at): Query<AtBlockQuery>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
     info!("Getting validator count for epoch={}", epoch);
 
-    let consensus_db = match dkg_state.consensus_db() {
-        Some(db) => db,
-        None => {
-            return Err(error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "ConsensusDB is not initialized",
-            ));
-        }
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+    let target_block_number = block_number_for_epoch(consensus_db, epoch)?;
+
+    // Get validator set from config storage using block_number
+    let config_storage = GLOBAL_CONFIG_STORAGE.get().ok_or_else(|| {
+        error!("GLOBAL_CONFIG_STORAGE is not initialized");
+        ApiError::config_storage_unavailable()
+    })?;
+    let config_bytes = config_storage
+        .fetch_config_bytes(OnChainConfig::ValidatorSet, target_block_number.into())
+        .ok_or_else(|| {
+            error!("ValidatorSet not found for block_number {}", target_block_number);
+            ApiError::not_found(format!(
+                "ValidatorSet not found for block_number {target_block_number}"
+            ))
+        })?;
+    let bytes: Bytes = config_bytes.try_into().map_err(|e| {
+        error!("Failed to convert config bytes: {:?}", e);
+        ApiError::internal()
+    })?;
+    let validator_set = ValidatorSet::deserialize_into_config(bytes.as_ref()).map_err(|e| {
+        error!("Failed to deserialize ValidatorSet: {:?}", e);
+        ApiError::internal()
+    })?;
+    let validator_count = validator_set.active_validators.len();
+    info!("Epoch {} validator count: {}", epoch, validator_count);
+
+    let response =
+        ValidatorCountResponse { epoch, block_number: target_block_number, validator_count };
+    Ok(format.render_bytes(response, bytes.to_vec()))
+}
+
+/// Get the validators of an epoch with their consensus keys and voting power
+/// Example: GET /consensus/validator_set/:epoch
+///
+/// The set is read from the ledger info that ended the previous epoch, so it is exactly what
+/// consensus verified signatures against during `epoch`. With `Accept: application/x-bcs` the
+/// BCS bytes of that `EpochState` are returned.
+pub fn get_validator_set_by_epoch(
+    State(dkg_state): State<Arc<DkgState>>,
+    Path(epoch): Path<u64>,
+    Query(at): Query<AtBlockQuery>,
+    format: ResponseFormat,
+) -> Result<Response, ApiError> {
+    info!("Getting validator set for epoch={}", epoch);
+
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+    let previous_epoch = epoch.checked_sub(1).ok_or_else(|| {
+        ApiError::not_found("Epoch 0 has no epoch-ending ledger info carrying its validator set")
+    })?;
+    let block_number = block_number_for_epoch(consensus_db, previous_epoch)?;
+    let ledger_info = consensus_db
+        .get::<LedgerInfoSchema>(&block_number)
+        .map_err(|e| {
+            error!("Failed to get ledger info for block_number={}: {:?}", block_number, e);
+            ApiError::internal()
+        })?
+        .ok_or_else(|| {
+            ApiError::not_found(format!("Ledger info not found for block_number {block_number}"))
+        })?;
+    let epoch_state = ledger_info
+        .ledger_info()
+        .next_epoch_state()
+        .filter(|epoch_state| epoch_state.epoch == epoch)
+        .ok_or_else(|| {
+            error!("Ledger info at block_number {} does not start epoch {}", block_number, epoch);
+            ApiError::not_found(format!("Validator set not found for epoch {epoch}"))
+        })?;
+
+    let verifier = &epoch_state.verifier;
+    let validators = verifier
+        .get_ordered_account_addresses_iter()
+        .map(|address| ValidatorInfoResponse {
+            address: hex::encode(address.as_ref()),
+            public_key: verifier
+                .get_public_key(&address)
+                .map(|public_key| hex::encode(public_key.to_bytes()))
+                .unwrap_or_default(),
+            voting_power: verifier.get_voting_power(&address).unwrap_or_default(),
+        })
+        .collect();
+
+    let response = ValidatorSetResponse {
+        epoch,
+        block_number,
+        total_voting_power: verifier.total_voting_power(),
+        quorum_voting_power: verifier.quorum_voting_power(),
+        validators,
     };
+    format.render(response, epoch_state)
+}
 
-    // Get block number for the target epoch
-    let all_epoch_blocks = match consensus_db.get_all::<EpochByBlockNumberSchema>() {
-        Ok(blocks) => blocks,
-        Err(e) => {
-            error!("Failed to get epoch by block number: {:?}", e);
-            return Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error"));
-        }
+/// Summarize validator set churn over recent epochs
+/// Example: GET /consensus/validator_churn?epochs=10
+///
+/// Compares the validator set of each of the last `epochs` epochs with the one before it, read
+/// from the epoch-ending ledger infos, and reports who joined or left and how much voting power
+/// changed hands. Sudden churn is an early sign of network-level trouble; sentinel polls this.
+pub fn get_validator_churn(
+    State(dkg_state): State<Arc<DkgState>>,
+    Query(query): Query<ValidatorChurnQuery>,
+    Query(at): Query<AtBlockQuery>,
+) -> Result<axum::Json<ValidatorChurnResponse>, ApiError> {
+    let epochs = query.epochs.unwrap_or(DEFAULT_CHURN_EPOCHS);
+    if epochs == 0 || epochs > MAX_CHURN_EPOCHS {
+        return Err(ApiError::bad_request(format!(
+            "epochs must be between 1 and {MAX_CHURN_EPOCHS}"
+        )));
+    }
+    info!("Getting validator churn over {} epochs", epochs);
+
+    let consensus_db = &at.consensus_db(&dkg_state)?;
+    let latest_epoch = latest_ledger_info(consensus_db)?.ledger_info().next_block_epoch();
+
+    let mut epoch_endings = db_span("consensus_db.get_epoch_endings", || {
+        consensus_db.get_all::<EpochByBlockNumberSchema>()
+    })
+    .map_err(|e| {
+        error!("Failed to get epoch by block number: {:?}", e);
+        ApiError::internal()
+    })?;
+    // The ending of epoch e carries the validator set of e + 1; one extra ending gives the
+    // baseline of the oldest transition.
+    epoch_endings.retain(|(_, epoch)| *epoch < latest_epoch);
+    epoch_endings.sort_by_key(|(_, epoch)| *epoch);
+    let skip = epoch_endings.len().saturating_sub(epochs as usize + 1);
+
+    let mut sets = Vec::with_capacity(epoch_endings.len() - skip);
+    for (block_number, _) in &epoch_endings[skip..] {
+        let ledger_info = db_span("consensus_db.get_ledger_info", || {
+            consensus_db.get::<LedgerInfoSchema>(block_number)
+        })
+        .map_err(|e| {
+            error!("Failed to get ledger info for block_number={}: {:?}", block_number, e);
+            ApiError::internal()
+        })?;
+        let Some(epoch_state) =
+            ledger_info.and_then(|li| li.ledger_info().next_epoch_state().cloned())
+        else {
+            continue;
+        };
+        let verifier = &epoch_state.verifier;
+        let powers: BTreeMap<String, u64> = verifier
+            .get_ordered_account_addresses_iter()
+            .map(|address| {
+                (
+                    hex::encode(address.as_ref()),
+                    verifier.get_voting_power(&address).unwrap_or_default(),
+                )
+            })
+            .collect();
+        sets.push((epoch_state.epoch, *block_number, powers));
+    }
+
+    let mut transitions: Vec<EpochChurn> = sets
+        .windows(2)
+        .filter(|pair| pair[1].0 == pair[0].0 + 1)
+        .map(|pair| churn_between(&pair[0].2, pair[1].0, pair[1].1, &pair[1].2))
+        .collect();
+    transitions.reverse();
+
+    Ok(axum::Json(ValidatorChurnResponse {
+        latest_epoch,
+        total_joined: transitions.iter().map(|t| t.joined.len()).sum(),
+        total_left: transitions.iter().map(|t| t.left.len()).sum(),
+        max_turnover_ratio: transitions.iter().map(|t| t.turnover_ratio).fold(0.0, f64::max),
+        transitions,
+    }))
+}
+
+/// Churn from the validator set `previous` to `current`, the set of `epoch`.
+fn churn_between(
+    previous: &BTreeMap<String, u64>,
+    epoch: u64,
+    block_number: u64,
+    current: &BTreeMap<String, u64>,
+) -> EpochChurn {
+    let joined = current.keys().filter(|a| !previous.contains_key(*a)).cloned().collect();
+    let left = previous.keys().filter(|a| !current.contains_key(*a)).cloned().collect();
+    let changed: u128 = previous
+        .keys()
+        .chain(current.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|address| {
+            let before = previous.get(address).copied().unwrap_or_default();
+            let after = current.get(address).copied().unwrap_or_default();
+            before.abs_diff(after) as u128
+        })
+        .sum();
+    let previous_total: u128 = previous.values().map(|power| *power as u128).sum();
+    let voting_power_turnover = changed / 2;
+    EpochChurn {
+        epoch,
+        block_number,
+        validator_count: current.len(),
+        joined,
+        left,
+        total_voting_power: current.values().map(|power| *power as u128).sum(),
+        voting_power_turnover,
+        turnover_ratio: if previous_total == 0 {
+            0.0
+        } else {
+            voting_power_turnover as f64 / previous_total as f64
+        },
+    }
+}
+
+/// Report how far the locally committed state is behind consensus
+/// Example: GET /consensus/sync_status
+///
+/// Compares the latest committed ledger info with the live round of the round manager and the
+/// highest QC stored for the epoch, so operators don't have to diff two ledger info polls.
+pub fn get_sync_status(
+    State(dkg_state): State<Arc<DkgState>>,
+) -> Result<axum::Json<SyncStatusResponse>, ApiError> {
+    let consensus_db = dkg_state.require_consensus_db()?;
+    let latest = latest_ledger_info(consensus_db)?;
+    let ledger_info = latest.ledger_info();
+    let epoch = ledger_info.epoch();
+    let committed_round = ledger_info.round();
+
+    let current_round =
+        u64::try_from(counters::CURRENT_ROUND.get()).ok().filter(|round| *round > 0);
+    let start_key = (epoch, HashValue::zero());
+    let end_key = (epoch, HashValue::new([u8::MAX; HashValue::LENGTH]));
+    let highest_certified_round =
+        db_span("consensus_db.get_qcs", || consensus_db.get_qc_range(&start_key, &end_key))
+            .map_err(|e| {
+                error!("Failed to get QCs for epoch {}: {:?}", epoch, e);
+                ApiError::internal()
+            })?
+            .iter()
+            .map(|qc| qc.certified_block().round())
+            .max();
+    let highest_known_round = [Some(committed_round), current_round, highest_certified_round]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(committed_round);
+    let rounds_behind = highest_known_round - committed_round;
+
+    let sync_mode = if counters::OP_COUNTERS.gauge("sync_to_running").get() > 0 {
+        SyncMode::StateSync
+    } else if rounds_behind > CATCHING_UP_ROUNDS {
+        SyncMode::CatchingUp
+    } else {
+        SyncMode::Synced
     };
 
+    let committed_usecs = ledger_info.timestamp_usecs();
+    let now_usecs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_micros() as u64)
+        .unwrap_or_default();
+    let round_secs = average_round_secs(consensus_db, &latest)?;
+
+    Ok(axum::Json(SyncStatusResponse {
+        epoch,
+        committed_round,
+        committed_block_number: ledger_info.block_number(),
+        current_round,
+        highest_certified_round,
+        highest_known_round,
+        rounds_behind,
+        sync_mode,
+        estimated_lag_secs: rounds_behind as f64 * round_secs,
+        last_commit_age_secs: now_usecs.saturating_sub(committed_usecs) as f64 / 1_000_000.0,
+    }))
+}
+
+/// Average seconds per round between `latest` and an earlier ledger info of the same epoch, or 0
+/// when there is none yet (e.g. right after an epoch change).
+fn average_round_secs(
+    consensus_db: &ConsensusDB,
+    latest: &LedgerInfoWithSignatures,
+) -> Result<f64, ApiError> {
+    let latest = latest.ledger_info();
+    let head = latest.block_number();
+    let start = head.saturating_sub(ROUND_DURATION_SAMPLE_BLOCKS);
+    let ledger_infos = db_span("consensus_db.get_ledger_infos", || {
+        consensus_db.get_range::<LedgerInfoSchema>(&start, &head)
+    })
+    .map_err(|e| {
+        error!("Failed to read ledger infos from {} to {}: {:?}", start, head, e);
+        ApiError::internal()
+    })?;
+    let earliest = ledger_infos
+        .iter()
+        .map(|(_, ledger_info)| ledger_info.ledger_info())
+        .find(|ledger_info| ledger_info.epoch() == latest.epoch());
+    Ok(match earliest {
+        Some(earliest) if latest.round() > earliest.round() => {
+            let elapsed_usecs = latest.timestamp_usecs().saturating_sub(earliest.timestamp_usecs());
+            elapsed_usecs as f64 / 1_000_000.0 / (latest.round() - earliest.round()) as f64
+        }
+        _ => 0.0,
+    })
+}
+
+fn latest_ledger_info(consensus_db: &ConsensusDB) -> Result<LedgerInfoWithSignatures, ApiError> {
+    db_span("consensus_db.get_latest_ledger_info", || {
+        DbReader::get_latest_ledger_info(consensus_db)
+    })
+    .map_err(|e| {
+        error!("Failed to get latest ledger info: {:?}", e);
+        ApiError::internal()
+    })
+}
+
+/// Looks up the block number at which `epoch` ended.
+pub(crate) fn block_number_for_epoch(
+    consensus_db: &ConsensusDB,
+    epoch: u64,
+) -> Result<u64, ApiError> {
+    // Get all epoch by block number mappings
+    let all_epoch_blocks = db_span("consensus_db.get_epoch_endings", || {
+        consensus_db.get_all::<EpochByBlockNumberSchema>()
+    })
+    .map_err(|e| {
+        error!("Failed to get epoch by block number: {:?}", e);
+        ApiError::internal()
+    })?;
+
     // Find the block number for the target epoch
-    let target_block_number = all_epoch_blocks
+    all_epoch_blocks
         .into_iter()
         .find(|(_, epoch_)| *epoch_ == epoch)
         .map(|(block_number, _)| block_number)
         .ok_or_else(|| {
             error!("Cannot find block number for epoch {}", epoch);
-            error_response(
-                StatusCode::NOT_FOUND,
-                &format!("Cannot find block number for epoch {epoch}"),
-            )
-        })?;
-
-    // Get validator set from config storage using block_number
-    let validator_count = match GLOBAL_CONFIG_STORAGE.get() {
-        Some(config_storage) => {
-            match config_storage
-                .fetch_config_bytes(OnChainConfig::ValidatorSet, target_block_number.into())
-            {
-                Some(config_bytes) => match config_bytes.try_into() {
-                    Ok(bytes) => {
-                        let bytes: Bytes = bytes;
-                        match ValidatorSet::deserialize_into_config(bytes.as_ref()) {
-                            Ok(validator_set) => {
-                                let count = validator_set.active_validators.len();
-                                info!("Epoch {} validator count: {}", epoch, count);
-                                count
-                            }
-                            Err(e) => {
-                                error!("Failed to deserialize ValidatorSet: {:?}", e);
-                                return Err(error_response(
-                                    StatusCode::INTERNAL_SERVER_ERROR,
-                                    "Internal server error",
-                                ));
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to convert config bytes: {:?}", e);
-                        return Err(error_response(
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            "Internal server error",
-                        ));
-                    }
-                },
-                None => {
-                    error!("ValidatorSet not found for block_number {}", target_block_number);
-                    return Err(error_response(
-                        StatusCode::NOT_FOUND,
-                        &format!("ValidatorSet not found for block_number {target_block_number}"),
-                    ));
-                }
-            }
-        }
-        None => {
-            error!("GLOBAL_CONFIG_STORAGE is not initialized");
-            return Err(error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "GLOBAL_CONFIG_STORAGE is not initialized",
-            ));
-        }
-    };
-
-    let response =
-        ValidatorCountResponse { epoch, block_number: target_block_number, validator_count };
-
-    Ok((StatusCode::OK, JsonResponse(response)))
+            ApiError::not_found(format!("Cannot find block number for epoch {epoch}"))
+        })
 }
 
 /// Helper function to get block by epoch and round
-fn get_block_by_round(consensus_db: &ConsensusDB, epoch: u64, round: u64) -> Option<BlockInfo> {
+///
+/// Blocks are read undecoded and only their header is parsed, so metadata lookups don't pay for
+/// decoding payloads. With `include_payload` the stored BCS bytes are returned hex encoded.
+pub(crate) fn get_block_by_round(
+    consensus_db: &ConsensusDB,
+    epoch: u64,
+    round: u64,
+    include_payload: bool,
+) -> Option<BlockInfo> {
+    find_block_by_round(consensus_db, epoch, round).map(|(block_id, header, raw_block)| {
+        to_block_info(consensus_db, block_id, header, &raw_block, include_payload)
+    })
+}
+
+/// The stored block of `epoch` at `round`, with its id and parsed header.
+fn find_block_by_round(
+    consensus_db: &ConsensusDB,
+    epoch: u64,
+    round: u64,
+) -> Option<(HashValue, BlockHeader, RawBlock)> {
     let start_key = (epoch, HashValue::zero());
     let end_key = (epoch, HashValue::new([u8::MAX; HashValue::LENGTH]));
 
     // Get all blocks in this epoch and filter by round
-    match consensus_db.get_range::<BlockSchema>(&start_key, &end_key) {
+    match db_span("consensus_db.get_blocks", || {
+        consensus_db.get_range::<RawBlockSchema>(&start_key, &end_key)
+    }) {
         Ok(blocks) => {
             // Find block with matching round
-            for ((_, _), block) in blocks {
-                if block.round() == round {
-                    // Try to get block number if not set
-                    let block_number = if block.block_number().is_none() {
-                        consensus_db.get::<BlockNumberSchema>(&(epoch, block.id())).ok().flatten()
-                    } else {
-                        block.block_number()
-                    };
-
-                    return Some(BlockInfo {
-                        epoch: block.epoch(),
-                        round: block.round(),
-                        block_number,
-                        block_id: hex::encode(block.id().as_ref()),
-                        parent_id: hex::encode(block.parent_id().as_ref()),
-                    });
+            for ((_, block_id), raw_block) in blocks {
+                let header = match raw_block.header() {
+                    Ok(header) => header,
+                    Err(e) => {
+                        error!("Failed to read header of block {}: {:?}", block_id, e);
+                        continue;
+                    }
+                };
+                if header.round == round {
+                    return Some((block_id, header, raw_block));
                 }
             }
-            None
+            cold_tier::get()?.block_by_round(epoch, round)
         }
         Err(e) => {
             error!("Failed to get blocks: {:?}", e);
@@ -373,34 +1482,152 @@ fn get_block_by_round(consensus_db: &ConsensusDB, epoch: u64, round: u64) -> Opt
     }
 }
 
+/// Helper function to get a block by its id
+///
+/// Blocks are keyed by `(epoch, block_id)`, so each epoch from the latest one down is probed
+/// with a point lookup until the block is found.
+pub(crate) fn get_block_by_id(
+    consensus_db: &ConsensusDB,
+    block_id: HashValue,
+    include_payload: bool,
+) -> Result<Option<BlockInfo>, ApiError> {
+    Ok(find_block_by_id(consensus_db, block_id)?.map(|(header, raw_block)| {
+        to_block_info(consensus_db, block_id, header, &raw_block, include_payload)
+    }))
+}
+
+/// The stored block with id `block_id` and its parsed header.
+fn find_block_by_id(
+    consensus_db: &ConsensusDB,
+    block_id: HashValue,
+) -> Result<Option<(BlockHeader, RawBlock)>, ApiError> {
+    let latest_epoch = db_span("consensus_db.get_latest_ledger_info", || {
+        DbReader::get_latest_ledger_info(consensus_db)
+    })
+    .map_err(|e| {
+        error!("Failed to get latest ledger info: {:?}", e);
+        ApiError::internal()
+    })?
+    .ledger_info()
+    .next_block_epoch();
+
+    for epoch in (0..=latest_epoch).rev() {
+        let raw_block = db_span("consensus_db.get_block", || {
+            consensus_db.get::<RawBlockSchema>(&(epoch, block_id))
+        })
+        .map_err(|e| {
+            error!("Failed to get block {} in epoch {}: {:?}", block_id, epoch, e);
+            ApiError::internal()
+        })?;
+        let Some(raw_block) = raw_block else { continue };
+        let header = raw_block.header().map_err(|e| {
+            error!("Failed to read header of block {}: {:?}", block_id, e);
+            ApiError::internal()
+        })?;
+        return Ok(Some((header, raw_block)));
+    }
+    Ok(cold_tier::get().and_then(|tier| tier.block_by_id(block_id)))
+}
+
+/// Every block stored for `epoch`, ordered by round.
+pub(crate) fn get_blocks_in_epoch(
+    consensus_db: &ConsensusDB,
+    epoch: u64,
+    include_payload: bool,
+) -> Result<Vec<BlockInfo>, ApiError> {
+    let blocks = raw_blocks_in_epoch(consensus_db, epoch)?;
+    let mut infos = Vec::with_capacity(blocks.len());
+    for (block_id, raw_block) in blocks {
+        match raw_block.header() {
+            Ok(header) => infos.push(to_block_info(
+                consensus_db,
+                block_id,
+                header,
+                &raw_block,
+                include_payload,
+            )),
+            Err(e) => error!("Failed to read header of block {}: {:?}", block_id, e),
+        }
+    }
+    infos.sort_by_key(|info| info.round);
+    Ok(infos)
+}
+
+/// Every block stored for `epoch` with its id, from the cold tier once the epoch was moved there.
+fn raw_blocks_in_epoch(
+    consensus_db: &ConsensusDB,
+    epoch: u64,
+) -> Result<Vec<(HashValue, RawBlock)>, ApiError> {
+    let start_key = (epoch, HashValue::zero());
+    let end_key = (epoch, HashValue::new([u8::MAX; HashValue::LENGTH]));
+    let blocks = db_span("consensus_db.get_blocks", || {
+        consensus_db.get_range::<RawBlockSchema>(&start_key, &end_key)
+    })
+    .map_err(|e| {
+        error!("Failed to get blocks of epoch {}: {:?}", epoch, e);
+        ApiError::internal()
+    })?;
+    if blocks.is_empty() {
+        if let Some(blocks) = cold_tier::get().and_then(|tier| tier.blocks_in_epoch(epoch)) {
+            return Ok(blocks);
+        }
+    }
+    Ok(blocks.into_iter().map(|((_, block_id), raw_block)| (block_id, raw_block)).collect())
+}
+
+fn to_block_info(
+    consensus_db: &ConsensusDB,
+    block_id: HashValue,
+    header: BlockHeader,
+    raw_block: &RawBlock,
+    include_payload: bool,
+) -> BlockInfo {
+    let block_number = block_number_of(consensus_db, header.epoch, block_id);
+    block_info(block_id, header, block_number, raw_block, include_payload)
+}
+
+fn block_info(
+    block_id: HashValue,
+    header: BlockHeader,
+    block_number: Option<u64>,
+    raw_block: &RawBlock,
+    include_payload: bool,
+) -> BlockInfo {
+    BlockInfo {
+        epoch: header.epoch,
+        round: header.round,
+        block_number,
+        block_id: hex::encode(block_id.as_ref()),
+        parent_id: hex::encode(header.parent_id.as_ref()),
+        payload: include_payload.then(|| hex::encode(&raw_block.0)),
+    }
+}
+
+/// Block number of the executed block `block_id` of `epoch`.
+fn block_number_of(consensus_db: &ConsensusDB, epoch: u64, block_id: HashValue) -> Option<u64> {
+    consensus_db.get::<BlockNumberSchema>(&(epoch, block_id)).ok().flatten()
+}
+
 /// Helper function to get QC by epoch and round
-fn get_qc_by_round(consensus_db: &ConsensusDB, epoch: u64, round: u64) -> Option<QCInfo> {
+pub(crate) fn get_qc_by_round(
+    consensus_db: &ConsensusDB,
+    epoch: u64,
+    round: u64,
+) -> Option<QCInfo> {
+    find_qc_by_round(consensus_db, epoch, round).map(|qc| to_qc_info(consensus_db, epoch, &qc))
+}
+
+/// The stored QC certifying the block of `epoch` at `round`.
+fn find_qc_by_round(consensus_db: &ConsensusDB, epoch: u64, round: u64) -> Option<QuorumCert> {
     let start_key = (epoch, HashValue::zero());
     let end_key = (epoch, HashValue::new([u8::MAX; HashValue::LENGTH]));
 
     // Get all QCs in this epoch and filter by round
-    match consensus_db.get_qc_range(&start_key, &end_key) {
-        Ok(qcs) => {
-            // Find QC with matching round
-            for qc in qcs {
-                if qc.certified_block().round() == round {
-                    // Try to get block number for the certified block
-                    let block_number = consensus_db
-                        .get::<BlockNumberSchema>(&(epoch, qc.certified_block().id()))
-                        .ok()
-                        .flatten();
-
-                    return Some(QCInfo {
-                        epoch: qc.certified_block().epoch(),
-                        round: qc.certified_block().round(),
-                        block_number,
-                        certified_block_id: hex::encode(qc.certified_block().id().as_ref()),
-                        commit_info_block_id: hex::encode(qc.commit_info().id().as_ref()),
-                    });
-                }
-            }
-            None
-        }
+    match db_span("consensus_db.get_qcs", || consensus_db.get_qc_range(&start_key, &end_key)) {
+        Ok(qcs) => qcs
+            .into_iter()
+            .find(|qc| qc.certified_block().round() == round)
+            .or_else(|| cold_tier::get()?.qc_by_round(epoch, round)),
         Err(e) => {
             error!("Failed to get QCs: {:?}", e);
             None
@@ -408,7 +1635,131 @@ fn get_qc_by_round(consensus_db: &ConsensusDB, epoch: u64, round: u64) -> Option
     }
 }
 
-/// Helper function to create error response
-fn error_response(status: StatusCode, message: &str) -> (StatusCode, JsonResponse<ErrorResponse>) {
-    (status, JsonResponse(ErrorResponse { error: message.to_string() }))
+/// Equivocation evidence recorded by this validator
+/// Example: GET /v1/consensus/evidence?epoch=12
+///
+/// Lists the double votes and conflicting proposals this node witnessed, with both signed
+/// messages, ready to be submitted with `gravity_cli validator submit-evidence`.
+pub fn get_evidence(
+    State(dkg_state): State<Arc<DkgState>>,
+    Query(query): Query<EvidenceQuery>,
+) -> Result<axum::Json<EvidenceResponse>, ApiError> {
+    let consensus_db = dkg_state.require_consensus_db()?;
+    let evidence =
+        db_span("consensus_db.list_evidence", || consensus_db.list_evidence(query.epoch)).map_err(
+            |e| {
+                error!("Failed to list equivocation evidence: {:?}", e);
+                ApiError::internal()
+            },
+        )?;
+    Ok(axum::Json(EvidenceResponse {
+        evidence: evidence.into_iter().map(EvidenceEntry::from).collect(),
+    }))
+}
+
+fn to_qc_info(consensus_db: &ConsensusDB, epoch: u64, qc: &QuorumCert) -> QCInfo {
+    // Try to get block number for the certified block
+    qc_info(qc, block_number_of(consensus_db, epoch, qc.certified_block().id()))
+}
+
+fn qc_info(qc: &QuorumCert, block_number: Option<u64>) -> QCInfo {
+    QCInfo {
+        epoch: qc.certified_block().epoch(),
+        round: qc.certified_block().round(),
+        block_number,
+        certified_block_id: hex::encode(qc.certified_block().id().as_ref()),
+        commit_info_block_id: hex::encode(qc.commit_info().id().as_ref()),
+    }
+}
+
+/// Parses a long-poll timeout such as `30s`, `1500ms` or a bare number of seconds.
+pub(crate) fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let parsed = if let Some(ms) = value.strip_suffix("ms") {
+        ms.parse().map(Duration::from_millis)
+    } else {
+        value.strip_suffix('s').unwrap_or(value).parse().map(Duration::from_secs)
+    };
+    parsed.map_err(|_| format!("Invalid timeout '{value}', expected e.g. 30s or 500ms"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(epoch: u64, round: u64, block_number: u64, timestamp_usecs: u64) -> CommitEntry {
+        CommitEntry {
+            epoch,
+            round,
+            block_number,
+            block_hash: String::new(),
+            timestamp_usecs,
+            interval_ms: None,
+            blocks: None,
+            skipped_rounds: None,
+        }
+    }
+
+    #[test]
+    fn inclusion_counts_each_validator_in_the_signer_sets() {
+        let (a, b, c) = (
+            AccountAddress::new([1; AccountAddress::LENGTH]),
+            AccountAddress::new([2; AccountAddress::LENGTH]),
+            AccountAddress::new([3; AccountAddress::LENGTH]),
+        );
+        let commits = vec![(10, vec![a, b]), (11, vec![a, b]), (13, vec![a, c]), (14, vec![a, b])];
+        let inclusion = count_inclusion(&[(a, 10), (b, 10), (c, 5)], &commits);
+
+        assert_eq!((inclusion[0].included, inclusion[0].inclusion_rate), (4, 1.0));
+        assert_eq!((inclusion[1].included, inclusion[1].last_included_block), (3, Some(14)));
+        assert_eq!(inclusion[2].inclusion_rate, 0.25);
+        assert_eq!(inclusion[2].last_included_block, Some(13));
+        assert_eq!(inclusion[2].voting_power, 5);
+        assert_eq!(count_inclusion(&[(a, 10)], &[])[0].inclusion_rate, 0.0);
+    }
+
+    #[test]
+    fn commit_gaps_count_skipped_rounds_within_an_epoch() {
+        let mut commits = vec![
+            commit(3, 10, 100, 1_000_000),
+            commit(3, 11, 101, 1_400_000),
+            // Rounds 12 and 13 timed out, then round 15 committed blocks of 14 and 15.
+            commit(3, 15, 103, 3_000_000),
+            commit(4, 1, 104, 3_500_000),
+        ];
+        fill_commit_gaps(&mut commits);
+        assert_eq!(commits[0].interval_ms, None);
+        assert_eq!(commits[1].interval_ms, Some(400.0));
+        assert_eq!((commits[2].blocks, commits[2].skipped_rounds), (Some(2), Some(2)));
+        assert_eq!((commits[3].blocks, commits[3].skipped_rounds), (Some(1), None));
+    }
+
+    #[test]
+    fn at_block_pins_need_a_consensus_db() {
+        let at: AtBlockQuery = serde_json::from_str(r#"{"at_version": 10}"#).unwrap();
+        assert_eq!(at.at_block, Some(10));
+        assert!(at.consensus_db(&DkgState::new(None)).is_err());
+    }
+
+    #[test]
+    fn churn_counts_swaps_and_power_shifts_once() {
+        let set = |entries: &[(&str, u64)]| -> BTreeMap<String, u64> {
+            entries.iter().map(|(a, p)| (a.to_string(), *p)).collect()
+        };
+        let previous = set(&[("aa", 10), ("bb", 10), ("cc", 20)]);
+        let current = set(&[("aa", 10), ("bb", 15), ("dd", 15)]);
+
+        let churn = churn_between(&previous, 5, 100, &current);
+        assert_eq!(churn.joined, vec!["dd".to_string()]);
+        assert_eq!(churn.left, vec!["cc".to_string()]);
+        assert_eq!(churn.validator_count, 3);
+        assert_eq!(churn.total_voting_power, 40);
+        // bb +5, cc -20, dd +15
+        assert_eq!(churn.voting_power_turnover, 20);
+        assert_eq!(churn.turnover_ratio, 0.5);
+
+        let unchanged = churn_between(&previous, 5, 100, &previous);
+        assert!(unchanged.joined.is_empty() && unchanged.left.is_empty());
+        assert_eq!(unchanged.turnover_ratio, 0.0);
+    }
 }
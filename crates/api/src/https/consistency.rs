@@ -0,0 +1,227 @@
+//! Background cross-check of recent commit certificates against peer nodes.
+//!
+//! Peers are the HTTP API base URLs of other gravity nodes (e.g. `http://10.0.0.2:1024`). Each
+//! round the checker takes the most recent commits both sides have and compares the committed
+//! block hash at the same block number via the peer's `/consensus/commit/:block_number`. A
+//! mismatch means a safety violation or corrupted local state; it is logged and kept in the
+//! report served by `/consensus/consistency` until the node restarts.
+
+use crate::https::{consensus::LedgerInfoResponse, versioning::API_V1};
+use aptos_consensus::consensusdb::{ConsensusDB, LedgerInfoSchema};
+use gaptos::{
+    aptos_infallible::Mutex,
+    aptos_logger::{error, info, warn},
+    aptos_storage_interface::DbReader,
+};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const PEER_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Number of most recent commits shared with a peer that are compared each round.
+const CHECK_DEPTH: usize = 8;
+/// Only commits this far below the local head are considered.
+const LOOKBACK_BLOCKS: u64 = 256;
+const MAX_RECORDED_DIVERGENCES: usize = 100;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Divergence {
+    pub peer: String,
+    pub block_number: u64,
+    pub local: LedgerInfoResponse,
+    pub remote: LedgerInfoResponse,
+    /// Unix timestamp (seconds) of the round that found the divergence.
+    pub detected_at: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PeerStatus {
+    pub peer: String,
+    pub reachable: bool,
+    pub error: Option<String>,
+    pub peer_block_number: Option<u64>,
+    /// Block numbers compared in the last round.
+    pub checked_block_numbers: Vec<u64>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ConsistencyReport {
+    /// False when no peers are configured.
+    pub enabled: bool,
+    /// False once any divergence has been observed since startup.
+    pub consistent: bool,
+    pub local_block_number: Option<u64>,
+    /// Unix timestamp (seconds) of the last completed round.
+    pub last_check: Option<u64>,
+    pub peers: Vec<PeerStatus>,
+    pub divergences: VecDeque<Divergence>,
+}
+
+pub struct ConsistencyChecker {
+    peers: Vec<String>,
+    interval: Duration,
+    client: reqwest::Client,
+    report: Mutex<ConsistencyReport>,
+}
+
+impl ConsistencyChecker {
+    pub fn new(peers: Vec<String>, interval: Duration) -> Self {
+        let peers: Vec<String> = peers
+            .into_iter()
+            .map(|p| p.trim().trim_end_matches('/').to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        let report = ConsistencyReport {
+            enabled: !peers.is_empty(),
+            consistent: true,
+            local_block_number: None,
+            last_check: None,
+            peers: vec![],
+            divergences: VecDeque::new(),
+        };
+        let client = reqwest::Client::builder()
+            .timeout(PEER_REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { peers, interval, client, report: Mutex::new(report) }
+    }
+
+    pub fn report(&self) -> ConsistencyReport {
+        self.report.lock().clone()
+    }
+
+    /// Runs check rounds forever; returns immediately when no peers are configured.
+    pub async fn run(self: Arc<Self>, consensus_db: Arc<ConsensusDB>) {
+        if self.peers.is_empty() {
+            return;
+        }
+        info!(
+            "Commit consistency checker started, peers={:?}, interval={:?}",
+            self.peers, self.interval
+        );
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            ticker.tick().await;
+            self.check_once(&consensus_db).await;
+        }
+    }
+
+    async fn check_once(&self, consensus_db: &ConsensusDB) {
+        let local = match recent_local_commits(consensus_db) {
+            Ok(local) => local,
+            Err(e) => {
+                warn!("Consistency check skipped, failed to read local commits: {}", e);
+                return;
+            }
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        let mut peers = Vec::with_capacity(self.peers.len());
+        let mut divergences = vec![];
+        for peer in &self.peers {
+            let (status, found) = self.check_peer(peer, &local, now).await;
+            peers.push(status);
+            divergences.extend(found);
+        }
+
+        let mut report = self.report.lock();
+        for divergence in divergences {
+            error!(
+                "Commit divergence with peer {} at block_number={}: local {:?}, peer {:?}",
+                divergence.peer, divergence.block_number, divergence.local, divergence.remote
+            );
+            report.consistent = false;
+            if report.divergences.len() == MAX_RECORDED_DIVERGENCES {
+                report.divergences.pop_front();
+            }
+            report.divergences.push_back(divergence);
+        }
+        report.local_block_number = local.last().map(|(block_number, _)| *block_number);
+        report.last_check = Some(now);
+        report.peers = peers;
+    }
+
+    async fn check_peer(
+        &self,
+        peer: &str,
+        local: &[(u64, LedgerInfoResponse)],
+        now: u64,
+    ) -> (PeerStatus, Vec<Divergence>) {
+        let mut status = PeerStatus {
+            peer: peer.to_string(),
+            reachable: false,
+            error: None,
+            peer_block_number: None,
+            checked_block_numbers: vec![],
+        };
+        let mut divergences = vec![];
+
+        let peer_head = match self.fetch(&format!("{peer}{API_V1}/consensus/latest_ledger_info")).await {
+            Ok(Some(head)) => head.block_number,
+            Ok(None) => {
+                status.error = Some("peer has no committed ledger info".to_string());
+                return (status, divergences);
+            }
+            Err(e) => {
+                status.error = Some(e);
+                return (status, divergences);
+            }
+        };
+        status.reachable = true;
+        status.peer_block_number = Some(peer_head);
+
+        let shared = local.iter().rev().filter(|(block_number, _)| *block_number <= peer_head);
+        for (block_number, local_commit) in shared.take(CHECK_DEPTH) {
+            match self.fetch(&format!("{peer}{API_V1}/consensus/commit/{block_number}")).await {
+                // The peer may have pruned or never persisted this height; nothing to compare.
+                Ok(None) => continue,
+                Ok(Some(remote)) => {
+                    status.checked_block_numbers.push(*block_number);
+                    if remote.block_hash != local_commit.block_hash {
+                        divergences.push(Divergence {
+                            peer: peer.to_string(),
+                            block_number: *block_number,
+                            local: local_commit.clone(),
+                            remote,
+                            detected_at: now,
+                        });
+                    }
+                }
+                Err(e) => {
+                    status.error = Some(e);
+                    break;
+                }
+            }
+        }
+        (status, divergences)
+    }
+
+    /// GETs a ledger info from a peer; `Ok(None)` when the peer answers 404.
+    async fn fetch(&self, url: &str) -> Result<Option<LedgerInfoResponse>, String> {
+        let response = self.client.get(url).send().await.map_err(|e| e.to_string())?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                response.json::<LedgerInfoResponse>().await.map(Some).map_err(|e| e.to_string())
+            }
+            status => Err(format!("{url} returned {status}")),
+        }
+    }
+}
+
+/// Commits persisted within `LOOKBACK_BLOCKS` of the local head, ascending by block number.
+fn recent_local_commits(
+    consensus_db: &ConsensusDB,
+) -> anyhow::Result<Vec<(u64, LedgerInfoResponse)>> {
+    let head = DbReader::get_latest_ledger_info(consensus_db)?.ledger_info().block_number();
+    let commits = consensus_db
+        .get_range::<LedgerInfoSchema>(&head.saturating_sub(LOOKBACK_BLOCKS), &(head + 1))?;
+    Ok(commits
+        .into_iter()
+        .map(|(block_number, ledger_info)| (block_number, LedgerInfoResponse::from(&ledger_info)))
+        .collect())
+}
@@ -0,0 +1,114 @@
+use crate::https::{error::ApiError, heap_profiler::ControlProfileResponse};
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use gaptos::aptos_logger::{error, info};
+use once_cell::sync::Lazy;
+use pprof::{protos::Message, ProfilerGuard, ProfilerGuardBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+const DEFAULT_FREQUENCY: i32 = 99;
+const MAX_FREQUENCY: i32 = 1000;
+
+/// The running CPU sampler, if any. Only one can run per process.
+static CPU_PROFILER: Lazy<Mutex<Option<ProfilerGuard<'static>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CpuProfileFormat {
+    /// pprof protobuf, for `go tool pprof` and similar viewers.
+    #[default]
+    Protobuf,
+    /// SVG flamegraph.
+    Flamegraph,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ControlCpuProfileRequest {
+    /// `true` starts sampling; `false` stops it and returns the profile.
+    enable: bool,
+    /// Samples per second while running (default 99).
+    frequency: Option<i32>,
+    /// Format of the profile returned when stopping.
+    #[serde(default)]
+    format: CpuProfileFormat,
+}
+
+// example:
+// curl -X POST -H "Content-Type:application/json" -d '{"enable": true}' http://127.0.0.1:1024/cpu_prof
+// curl -X POST -H "Content-Type:application/json" -d '{"enable": false, "format": "flamegraph"}' \
+//   -o cpu.svg http://127.0.0.1:1024/cpu_prof
+pub async fn control_cpu_profiler(request: ControlCpuProfileRequest) -> Result<Response, ApiError> {
+    let mut profiler = CPU_PROFILER.lock().map_err(|_| {
+        error!("CPU profiler lock is poisoned");
+        ApiError::internal()
+    })?;
+
+    if request.enable {
+        if profiler.is_some() {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "profiler_running",
+                "CPU profiling is already running",
+            ));
+        }
+        let frequency = request.frequency.unwrap_or(DEFAULT_FREQUENCY);
+        if !(1..=MAX_FREQUENCY).contains(&frequency) {
+            return Err(ApiError::bad_request(format!(
+                "frequency must be between 1 and {MAX_FREQUENCY}"
+            )));
+        }
+        let guard = ProfilerGuardBuilder::default()
+            .frequency(frequency)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .map_err(|e| {
+                ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "profiler_error", e.to_string())
+            })?;
+        *profiler = Some(guard);
+        info!("CPU profiling started at {} Hz", frequency);
+        return Ok(Json(ControlProfileResponse { response: "started".to_string() }).into_response());
+    }
+
+    let Some(guard) = profiler.take() else {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "profiler_not_running",
+            "CPU profiling is not running",
+        ));
+    };
+    let report = guard.report().build();
+    // Sampling stops when the guard is dropped.
+    drop(guard);
+    info!("CPU profiling stopped");
+    let report = report.map_err(|e| {
+        error!("Failed to build CPU profile: {:?}", e);
+        ApiError::internal()
+    })?;
+
+    let mut body = Vec::new();
+    let content_type = match request.format {
+        CpuProfileFormat::Protobuf => {
+            let profile = report.pprof().map_err(|e| {
+                error!("Failed to convert CPU profile to pprof: {:?}", e);
+                ApiError::internal()
+            })?;
+            profile.write_to_vec(&mut body).map_err(|e| {
+                error!("Failed to encode CPU profile: {:?}", e);
+                ApiError::internal()
+            })?;
+            "application/octet-stream"
+        }
+        CpuProfileFormat::Flamegraph => {
+            report.flamegraph(&mut body).map_err(|e| {
+                error!("Failed to render flamegraph: {:?}", e);
+                ApiError::internal()
+            })?;
+            "image/svg+xml"
+        }
+    };
+    Ok(([(header::CONTENT_TYPE, content_type)], body).into_response())
+}
@@ -0,0 +1,154 @@
+//! `/admin/db_snapshot`: a consistent copy of the ConsensusDB, streamed as a tar archive.
+//!
+//! A RocksDB checkpoint is taken next to the live DB, where it costs hard links rather than a
+//! copy, and is streamed with chunked transfer while the node keeps running. New nodes and
+//! backup jobs can pull consensus state this way without filesystem access to the validator
+//! host. Entries are under `consensus_db/`, so unpacking into a node's storage dir restores it.
+//! The checkpoint is removed once the stream ends or the client goes away, and one snapshot is
+//! taken at a time.
+
+use crate::https::{dkg::DkgState, error::ApiError};
+use aptos_consensus::consensusdb::CONSENSUS_DB_NAME;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use futures::stream;
+use gaptos::aptos_logger::{error, info, warn};
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::mpsc;
+
+/// Bytes of tar output sent per body chunk.
+const CHUNK_SIZE: usize = 256 * 1024;
+/// Chunks buffered ahead of a slow client.
+const CHUNKS_IN_FLIGHT: usize = 16;
+
+static SNAPSHOT_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// Removes the checkpoint and allows the next snapshot, however the stream ends.
+struct Checkpoint {
+    dir: PathBuf,
+}
+
+impl Drop for Checkpoint {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            if self.dir.exists() {
+                warn!("Failed to remove DB snapshot checkpoint {}: {:?}", self.dir.display(), e);
+            }
+        }
+        SNAPSHOT_IN_PROGRESS.store(false, Ordering::Release);
+    }
+}
+
+/// Sends everything written to it as body chunks of [`CHUNK_SIZE`].
+struct ChunkWriter {
+    chunks: mpsc::Sender<io::Result<Bytes>>,
+    buf: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn send_buffered(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::replace(&mut self.buf, Vec::with_capacity(CHUNK_SIZE)));
+        self.chunks
+            .blocking_send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "client disconnected"))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let len = data.len().min(CHUNK_SIZE - self.buf.len());
+        self.buf.extend_from_slice(&data[..len]);
+        if self.buf.len() == CHUNK_SIZE {
+            self.send_buffered()?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_buffered()
+    }
+}
+
+// example:
+// curl -o consensus_db.tar http://127.0.0.1:1024/admin/db_snapshot
+pub(crate) async fn get_db_snapshot(
+    State(dkg_state): State<Arc<DkgState>>,
+) -> Result<Response, ApiError> {
+    let consensus_db = dkg_state.require_consensus_db()?.clone();
+    if SNAPSHOT_IN_PROGRESS.swap(true, Ordering::AcqRel) {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "snapshot_in_progress",
+            "Another DB snapshot is being streamed, retry when it finishes",
+        ));
+    }
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    // Next to the live DB, so the checkpoint hard-links its files instead of copying them.
+    let checkpoint = Checkpoint {
+        dir: consensus_db.path().with_file_name(format!("{CONSENSUS_DB_NAME}.snapshot-{now_ms}")),
+    };
+    let checkpoint = tokio::task::spawn_blocking(move || {
+        consensus_db.create_checkpoint(&checkpoint.dir).map(|_| checkpoint)
+    })
+    .await
+    .map_err(|e| {
+        error!("DB snapshot task panicked: {:?}", e);
+        ApiError::internal()
+    })?
+    .map_err(|e| {
+        error!("Failed to create ConsensusDB checkpoint: {:?}", e);
+        ApiError::internal()
+    })?;
+    info!("Streaming ConsensusDB snapshot from {}", checkpoint.dir.display());
+
+    let (tx, rx) = mpsc::channel(CHUNKS_IN_FLIGHT);
+    tokio::task::spawn_blocking(move || {
+        let writer = ChunkWriter { chunks: tx.clone(), buf: Vec::with_capacity(CHUNK_SIZE) };
+        let mut archive = tar::Builder::new(writer);
+        let written = archive
+            .append_dir_all(CONSENSUS_DB_NAME, &checkpoint.dir)
+            .and_then(|_| archive.into_inner())
+            .and_then(|mut writer| writer.flush());
+        match written {
+            Ok(()) => info!("Finished streaming ConsensusDB snapshot"),
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                warn!("DB snapshot client disconnected before the end")
+            }
+            Err(e) => {
+                error!("Failed to stream ConsensusDB snapshot: {:?}", e);
+                // Fails the response instead of ending it like a complete archive.
+                let _ = tx.blocking_send(Err(e));
+            }
+        }
+        drop(checkpoint);
+    });
+    let chunks =
+        stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|chunk| (chunk, rx)) });
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-tar".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{CONSENSUS_DB_NAME}-{now_ms}.tar\""),
+            ),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
@@ -1,30 +1,50 @@
-use aptos_consensus::consensusdb::ConsensusDB;
-use axum::{
-    http::StatusCode,
-    response::{IntoResponse, Json as JsonResponse},
+use crate::https::{consensus::block_number_for_epoch, error::ApiError, snapshots::Snapshots};
+use aptos_consensus::{
+    consensusdb::ConsensusDB,
+    threshold_decrypt::{self, CiphertextProof, EncodedDecryptionShare},
 };
+use axum::response::Json as JsonResponse;
 use bytes::Bytes;
 use gaptos::{
     api_types::config_storage::{OnChainConfig, GLOBAL_CONFIG_STORAGE},
+    aptos_dkg::pvss::Player,
     aptos_logger::{error, info},
     aptos_storage_interface::DbReader,
-    aptos_types::{dkg::DKGState, on_chain_config::OnChainConfig as OnChainConfigTrait},
+    aptos_types::{
+        dkg::{DKGState, DKGTrait, DefaultDKG},
+        on_chain_config::{OnChainConfig as OnChainConfigTrait, OnChainRandomnessConfig},
+    },
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 pub struct DkgState {
     consensus_db: Option<Arc<ConsensusDB>>,
+    /// Point-in-time copies of the DB read by `?at_block`.
+    snapshots: Snapshots,
 }
 
 impl DkgState {
     pub fn new(consensus_db: Option<Arc<ConsensusDB>>) -> Self {
-        Self { consensus_db }
+        let snapshots = Snapshots::new(consensus_db.as_ref());
+        Self { consensus_db, snapshots }
     }
 
     pub fn consensus_db(&self) -> Option<&Arc<ConsensusDB>> {
         self.consensus_db.as_ref()
     }
+
+    /// Like [`Self::consensus_db`], but fails with a 503 when the DB is not initialized.
+    pub fn require_consensus_db(&self) -> Result<&Arc<ConsensusDB>, ApiError> {
+        self.consensus_db.as_ref().ok_or_else(|| {
+            error!("ConsensusDB is not initialized");
+            ApiError::consensus_db_unavailable()
+        })
+    }
+
+    pub(crate) fn snapshots(&self) -> &Snapshots {
+        &self.snapshots
+    }
 }
 
 #[allow(dead_code)]
@@ -61,49 +81,96 @@ pub struct DKGStatusResponse {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct RandomnessResponse {
+pub struct DKGTranscriptResponse {
+    /// Epoch whose randomness keys the transcript deals.
+    pub epoch: u64,
+    pub dealer_epoch: u64,
+    /// Block whose on-chain DKG state the transcript was read from.
     pub block_number: u64,
-    pub randomness: Option<String>, // hex encoded
+    pub start_time_us: u64,
+    /// Hex-encoded BCS `DKGSessionMetadata`: dealer and target validator sets and the randomness
+    /// config, needed to verify the transcript.
+    pub metadata: String,
+    /// Hex-encoded BCS transcript, as stored on chain.
+    pub transcript: String,
 }
 
+/// Curve of the default DKG's transcripts and randomness keys.
+const DKG_CURVE: &str = "BLS12-381";
+
 #[derive(Serialize, Deserialize, Debug)]
-pub struct ErrorResponse {
-    pub error: String,
+pub struct DKGParamsResponse {
+    /// Epoch of the latest ledger info.
+    pub current_epoch: u64,
+    /// Epoch whose randomness keys the last completed session dealt.
+    pub epoch: u64,
+    pub dealer_epoch: u64,
+    /// Block whose on-chain DKG state the parameters were read from.
+    pub block_number: u64,
+    pub curve: String,
+    pub randomness_enabled: bool,
+    /// Stake ratio that must not be able to reconstruct the randomness key.
+    pub secrecy_threshold: Option<f64>,
+    /// Stake ratio that can always reconstruct the randomness key.
+    pub reconstruct_threshold: Option<f64>,
+    pub fast_path_secrecy_threshold: Option<f64>,
+    pub participants: usize,
+    /// Shares dealt, after rounding the participants' stakes to weights.
+    pub total_weight: usize,
+    /// Shares needed to reconstruct the randomness key.
+    pub threshold_weight: usize,
+    pub fast_threshold_weight: Option<usize>,
+    pub transcript_bytes: usize,
+    /// Dealer epoch of a session still dealing, if any.
+    pub in_progress_dealer_epoch: Option<u64>,
+    pub weights: Vec<DKGParticipantWeight>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DKGParticipantWeight {
+    pub address: String, // hex encoded
+    pub voting_power: u64,
+    pub weight: usize,
+}
+
+/// Ciphertext header of a message encrypted to an epoch's dealt key.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DecryptShareRequest {
+    pub epoch: u64,
+    /// `r·ĝ`, compressed G2, hex encoded.
+    pub u: String,
+    /// Proof of knowledge of `r`, hex encoded.
+    pub proof: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DecryptShareResponse {
+    pub epoch: u64,
+    pub validator: String, // hex encoded
+    /// One share per DKG weight unit this validator holds; check them against the transcript.
+    pub shares: Vec<EncodedDecryptionShare>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RandomnessResponse {
+    pub block_number: u64,
+    pub randomness: Option<String>, // hex encoded
 }
 
 impl DkgState {
     /// Get DKG status (epoch, round, block, participating nodes)
     /// Example: curl https://127.0.0.1:1024/dkg/status
-    pub fn get_dkg_status(&self) -> impl IntoResponse {
+    pub fn get_dkg_status(&self) -> Result<JsonResponse<DKGStatusResponse>, ApiError> {
         info!("Getting DKG status");
 
-        // Get ConsensusDB
-        let consensus_db = match self.consensus_db.as_ref() {
-            Some(db) => db,
-            None => {
-                error!("ConsensusDB is not initialized");
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    JsonResponse(ErrorResponse {
-                        error: "ConsensusDB is not initialized".to_string(),
-                    }),
-                )
-                    .into_response();
-            }
-        };
+        let consensus_db = self.require_consensus_db()?;
 
         // Get latest ledger info using DbReader trait
-        let latest_ledger_info = match DbReader::get_latest_ledger_info(consensus_db.as_ref()) {
-            Ok(info) => info,
-            Err(e) => {
+        let latest_ledger_info =
+            DbReader::get_latest_ledger_info(consensus_db.as_ref()).map_err(|e| {
                 error!("Failed to get latest ledger info: {:?}", e);
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    JsonResponse(ErrorResponse { error: "Internal server error".to_string() }),
-                )
-                    .into_response();
-            }
-        };
+                ApiError::internal()
+            })?;
 
         let ledger_info = latest_ledger_info.ledger_info();
         let epoch = ledger_info.epoch();
@@ -111,81 +178,16 @@ impl DkgState {
         let block = ledger_info.block_number();
 
         // Get participating nodes count from DKGState last_completed session
-        let participating_nodes = if let Some(config_storage) = GLOBAL_CONFIG_STORAGE.get() {
-            if let Some(config_bytes) =
-                config_storage.fetch_config_bytes(OnChainConfig::DKGState, block.into())
-            {
-                match config_bytes.try_into() {
-                    Ok(bytes) => {
-                        let bytes: Bytes = bytes;
-                        match <DKGState as OnChainConfigTrait>::deserialize_into_config(
-                            bytes.as_ref(),
-                        ) {
-                            Ok(dkg_state) => {
-                                // participating_nodes is the count of target_validator_set from
-                                // last_completed session
-                                if let Some(session) = &dkg_state.last_completed {
-                                    session.metadata.target_validator_set.len()
-                                } else {
-                                    error!(
-                                        "No last_completed DKG session found at block {}",
-                                        block
-                                    );
-                                    return (
-                                        StatusCode::NOT_FOUND,
-                                        JsonResponse(ErrorResponse {
-                                            error: format!(
-                                                "No last_completed DKG session found at block {block}"
-                                            ),
-                                        }),
-                                    )
-                                        .into_response();
-                                }
-                            }
-                            Err(e) => {
-                                error!("Failed to deserialize DKG state: {:?}", e);
-                                return (
-                                    StatusCode::INTERNAL_SERVER_ERROR,
-                                    JsonResponse(ErrorResponse {
-                                        error: "Internal server error".to_string(),
-                                    }),
-                                )
-                                    .into_response();
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to convert config bytes: {:?}", e);
-                        return (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            JsonResponse(ErrorResponse {
-                                error: "Internal server error".to_string(),
-                            }),
-                        )
-                            .into_response();
-                    }
-                }
-            } else {
-                error!("Failed to fetch DKG state from config storage at block {}", block);
-                return (
-                    StatusCode::NOT_FOUND,
-                    JsonResponse(ErrorResponse {
-                        error: format!(
-                            "Failed to fetch DKG state from config storage at block {block}"
-                        ),
-                    }),
-                )
-                    .into_response();
+        let dkg_state = fetch_dkg_state(block)?;
+        // participating_nodes is the count of target_validator_set from last_completed session
+        let participating_nodes = match &dkg_state.last_completed {
+            Some(session) => session.metadata.target_validator_set.len(),
+            None => {
+                error!("No last_completed DKG session found at block {}", block);
+                return Err(ApiError::not_found(format!(
+                    "No last_completed DKG session found at block {block}"
+                )));
             }
-        } else {
-            error!("GLOBAL_CONFIG_STORAGE is not initialized");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                JsonResponse(ErrorResponse {
-                    error: "GLOBAL_CONFIG_STORAGE is not initialized".to_string(),
-                }),
-            )
-                .into_response();
         };
 
         let response = DKGStatusResponse { epoch, round, block_number: block, participating_nodes };
@@ -194,51 +196,201 @@ impl DkgState {
             "Successfully retrieved DKG status: epoch={}, round={}, block={}, nodes={}",
             epoch, round, block, participating_nodes
         );
-        JsonResponse(response).into_response()
+        Ok(JsonResponse(response))
+    }
+
+    /// Get the parameters of the last completed DKG session
+    /// Example: curl https://127.0.0.1:1024/v1/dkg/params
+    ///
+    /// Thresholds come from the randomness config the session ran with, and weights from
+    /// rounding the stakes of its target validator set the way dealing did.
+    pub fn get_dkg_params(&self) -> Result<JsonResponse<DKGParamsResponse>, ApiError> {
+        info!("Getting DKG parameters");
+
+        let consensus_db = self.require_consensus_db()?;
+        let latest_ledger_info =
+            DbReader::get_latest_ledger_info(consensus_db.as_ref()).map_err(|e| {
+                error!("Failed to get latest ledger info: {:?}", e);
+                ApiError::internal()
+            })?;
+        let ledger_info = latest_ledger_info.ledger_info();
+        let block_number = ledger_info.block_number();
+        let dkg_state = fetch_dkg_state(block_number)?;
+        let session = dkg_state.last_completed.ok_or_else(|| {
+            ApiError::not_found(format!(
+                "No last_completed DKG session found at block {block_number}"
+            ))
+        })?;
+        let metadata = &session.metadata;
+
+        let randomness_config =
+            OnChainRandomnessConfig::try_from(metadata.randomness_config.clone()).map_err(|e| {
+                error!("Failed to decode the session's randomness config: {:?}", e);
+                ApiError::internal()
+            })?;
+        let params = DefaultDKG::new_public_params(metadata);
+        let wconfig = &params.pvss_config.wconfig;
+        let weights = metadata
+            .target_validator_set
+            .iter()
+            .enumerate()
+            .map(|(id, validator)| DKGParticipantWeight {
+                address: hex::encode(validator.addr.as_ref()),
+                voting_power: validator.voting_power,
+                weight: wconfig.get_player_weight(&Player { id }),
+            })
+            .collect();
+
+        Ok(JsonResponse(DKGParamsResponse {
+            current_epoch: ledger_info.epoch(),
+            epoch: metadata.dealer_epoch + 1,
+            dealer_epoch: metadata.dealer_epoch,
+            block_number,
+            curve: DKG_CURVE.to_string(),
+            randomness_enabled: randomness_config.randomness_enabled(),
+            secrecy_threshold: randomness_config.secrecy_threshold().map(|t| t.to_num::<f64>()),
+            reconstruct_threshold: randomness_config
+                .reconstruct_threshold()
+                .map(|t| t.to_num::<f64>()),
+            fast_path_secrecy_threshold: randomness_config
+                .fast_path_secrecy_threshold()
+                .map(|t| t.to_num::<f64>()),
+            participants: metadata.target_validator_set.len(),
+            total_weight: wconfig.get_total_weight(),
+            threshold_weight: wconfig.get_threshold_weight(),
+            fast_threshold_weight: params
+                .pvss_config
+                .fast_wconfig
+                .as_ref()
+                .map(|fast| fast.get_threshold_weight()),
+            transcript_bytes: session.transcript.len(),
+            in_progress_dealer_epoch: dkg_state
+                .in_progress
+                .map(|session| session.metadata.dealer_epoch),
+            weights,
+        }))
+    }
+
+    /// Get the DKG transcript that dealt an epoch's randomness keys
+    /// Example: curl "https://127.0.0.1:1024/dkg/transcript/5"
+    ///
+    /// The session dealing epoch N completes during epoch N-1, so the transcript is read from the
+    /// on-chain DKG state at the block that ended epoch N-1.
+    pub fn get_transcript(
+        &self,
+        epoch: u64,
+    ) -> Result<JsonResponse<DKGTranscriptResponse>, ApiError> {
+        info!("Getting DKG transcript for epoch {}", epoch);
+
+        let consensus_db = self.require_consensus_db()?;
+        let dealer_epoch = epoch
+            .checked_sub(1)
+            .ok_or_else(|| ApiError::not_found("Epoch 0 keys are not dealt by a DKG session"))?;
+        let block_number = block_number_for_epoch(consensus_db, dealer_epoch)?;
+        let session = fetch_dkg_state(block_number)?
+            .last_completed
+            .filter(|session| session.metadata.dealer_epoch == dealer_epoch)
+            .ok_or_else(|| {
+                ApiError::not_found(format!(
+                    "No completed DKG session for epoch {epoch} at block {block_number}"
+                ))
+            })?;
+        let metadata = bcs::to_bytes(&session.metadata).map_err(|e| {
+            error!("Failed to serialize DKG session metadata: {:?}", e);
+            ApiError::internal()
+        })?;
+
+        Ok(JsonResponse(DKGTranscriptResponse {
+            epoch,
+            dealer_epoch,
+            block_number,
+            start_time_us: session.start_time_us,
+            metadata: hex::encode(metadata),
+            transcript: hex::encode(&session.transcript),
+        }))
+    }
+
+    /// Get this validator's decryption shares of a ciphertext encrypted to an epoch's DKG key
+    /// Example: curl -X POST -H "Content-Type:application/json" \
+    ///   -d '{"epoch":5,"u":"<hex>","proof":"<hex>"}' https://127.0.0.1:1024/v1/dkg/decrypt_share
+    ///
+    /// Only the epoch this node last dealt keys for is served.
+    pub fn decrypt_share(
+        &self,
+        request: DecryptShareRequest,
+    ) -> Result<JsonResponse<DecryptShareResponse>, ApiError> {
+        info!("Getting decryption shares for epoch {}", request.epoch);
+
+        let key_shares = threshold_decrypt::key_shares(request.epoch).ok_or_else(|| {
+            ApiError::not_found(format!("No DKG key shares held for epoch {}", request.epoch))
+        })?;
+        let u = threshold_decrypt::decode_g2(&request.u)
+            .map_err(|e| ApiError::bad_request(format!("Invalid u: {e}")))?;
+        let proof = CiphertextProof::from_hex(&request.proof)
+            .map_err(|e| ApiError::bad_request(format!("Invalid proof: {e}")))?;
+        let shares = key_shares
+            .decrypt(&u, &proof)
+            .map_err(|e| ApiError::bad_request(format!("Refusing to decrypt: {e}")))?;
+
+        Ok(JsonResponse(DecryptShareResponse {
+            epoch: request.epoch,
+            validator: hex::encode(key_shares.author.as_ref()),
+            shares: shares.iter().map(|share| share.encode()).collect(),
+        }))
     }
 
     /// Get randomness for a specific block number
     /// Example: curl "https://127.0.0.1:1024/dkg/randomness/100"
-    pub fn get_randomness(&self, block_number: u64) -> impl IntoResponse {
+    pub fn get_randomness(
+        &self,
+        block_number: u64,
+    ) -> Result<JsonResponse<RandomnessResponse>, ApiError> {
         info!("Getting randomness for block {}", block_number);
 
-        // Get ConsensusDB
-        let consensus_db = match self.consensus_db.as_ref() {
-            Some(db) => db,
-            None => {
-                error!("ConsensusDB is not initialized");
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    JsonResponse(ErrorResponse {
-                        error: "ConsensusDB is not initialized".to_string(),
-                    }),
-                )
-                    .into_response();
-            }
-        };
+        let consensus_db = self.require_consensus_db()?;
 
         match consensus_db.get_randomness(block_number) {
             Ok(Some(randomness)) => {
                 let response =
                     RandomnessResponse { block_number, randomness: Some(hex::encode(&randomness)) };
                 info!("Successfully retrieved randomness for block {}", block_number);
-                JsonResponse(response).into_response()
+                Ok(JsonResponse(response))
             }
             Ok(None) => {
                 // Return 200 with None randomness instead of 404
                 // This is more RESTful: the resource exists, but has no randomness data
                 let response = RandomnessResponse { block_number, randomness: None };
                 info!("No randomness found for block {}", block_number);
-                JsonResponse(response).into_response()
+                Ok(JsonResponse(response))
             }
             Err(e) => {
                 error!("Failed to get randomness for block {}: {:?}", block_number, e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    JsonResponse(ErrorResponse { error: "Internal server error".to_string() }),
-                )
-                    .into_response()
+                Err(ApiError::internal())
             }
         }
     }
 }
+
+/// Reads the on-chain DKG state as of `block_number` from the config storage.
+fn fetch_dkg_state(block_number: u64) -> Result<DKGState, ApiError> {
+    let config_storage = GLOBAL_CONFIG_STORAGE.get().ok_or_else(|| {
+        error!("GLOBAL_CONFIG_STORAGE is not initialized");
+        ApiError::config_storage_unavailable()
+    })?;
+    let config_bytes = config_storage
+        .fetch_config_bytes(OnChainConfig::DKGState, block_number.into())
+        .ok_or_else(|| {
+            error!("Failed to fetch DKG state from config storage at block {}", block_number);
+            ApiError::not_found(format!(
+                "Failed to fetch DKG state from config storage at block {block_number}"
+            ))
+        })?;
+    let bytes: Bytes = config_bytes.try_into().map_err(|e| {
+        error!("Failed to convert config bytes: {:?}", e);
+        ApiError::internal()
+    })?;
+    <DKGState as OnChainConfigTrait>::deserialize_into_config(bytes.as_ref()).map_err(|e| {
+        error!("Failed to deserialize DKG state: {:?}", e);
+        ApiError::internal()
+    })
+}
@@ -0,0 +1,109 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json as JsonResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+
+/// JSON body of every error returned by the HTTP API.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ErrorResponse {
+    /// Stable, machine-readable error code, e.g. `not_found`.
+    pub code: String,
+    pub message: String,
+}
+
+/// Error returned by HTTP handlers, rendered as an [`ErrorResponse`] with a matching status.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self { status, code, message: message.into() }
+    }
+
+    /// HTTP status the error is rendered with.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+
+    /// Internal failures are logged by the caller; the client only sees a generic message.
+    pub fn internal() -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", "Internal server error")
+    }
+
+    pub fn not_implemented(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_IMPLEMENTED, "not_implemented", message)
+    }
+
+    /// The node is running without a ConsensusDB (e.g. during bootstrap or in tests).
+    pub fn consensus_db_unavailable() -> Self {
+        Self::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "consensus_db_unavailable",
+            "ConsensusDB is not initialized",
+        )
+    }
+
+    /// GLOBAL_CONFIG_STORAGE has not been set up yet.
+    pub fn config_storage_unavailable() -> Self {
+        Self::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "config_storage_unavailable",
+            "GLOBAL_CONFIG_STORAGE is not initialized",
+        )
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.status, self.code, self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ApiError> for ErrorResponse {
+    fn from(error: ApiError) -> Self {
+        Self { code: error.code.to_string(), message: error.message }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status;
+        (status, JsonResponse(ErrorResponse::from(self))).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_status_and_json_body() {
+        let response = ApiError::not_found("Block not found").into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers().get(axum::http::header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+    }
+
+    #[test]
+    fn missing_consensus_db_is_service_unavailable() {
+        let response = ApiError::consensus_db_unavailable().into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,154 @@
+//! Sparse fieldsets: `?fields=block_number,timestamp,proposer`.
+//!
+//! Validator sets and blocks are heavyweight, while mobile and embedded clients often need only
+//! a couple of their fields. Any JSON response can be trimmed to the listed fields; nested ones
+//! are named with dots (`validators.address`), and a field naming an array applies to each of
+//! its elements. Unknown fields are left out rather than rejected, so one field list works
+//! across endpoints. BCS bodies, streams and errors are never filtered.
+
+use crate::https::error::ApiError;
+use axum::{
+    body::Body,
+    extract::Query,
+    http::{header, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use gaptos::aptos_logger::error;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Default)]
+struct FieldsQuery {
+    fields: Option<String>,
+}
+
+/// Fields to keep, each with the nested fields to keep of it, or `None` for all of them.
+#[derive(Debug, Default, PartialEq)]
+struct Fields(BTreeMap<String, Option<Fields>>);
+
+impl Fields {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut fields = Fields::default();
+        for path in spec.split(',').map(str::trim).filter(|path| !path.is_empty()) {
+            let path: Vec<&str> = path.split('.').collect();
+            if path.iter().any(|name| name.is_empty()) {
+                return Err(format!("invalid field '{}'", path.join(".")));
+            }
+            fields.insert(&path);
+        }
+        if fields.0.is_empty() {
+            return Err("fields must list at least one field".to_string());
+        }
+        Ok(fields)
+    }
+
+    fn insert(&mut self, path: &[&str]) {
+        let Some((name, rest)) = path.split_first() else { return };
+        if rest.is_empty() {
+            // The whole field is kept, whatever was listed of it before.
+            self.0.insert(name.to_string(), None);
+        } else if let Some(nested) =
+            self.0.entry(name.to_string()).or_insert_with(|| Some(Fields::default()))
+        {
+            nested.insert(rest);
+        }
+    }
+
+    fn apply(&self, value: Value) -> Value {
+        match value {
+            Value::Object(object) => Value::Object(
+                object
+                    .into_iter()
+                    .filter_map(|(name, value)| {
+                        let nested = self.0.get(&name)?;
+                        Some(match nested {
+                            Some(nested) => (name, nested.apply(value)),
+                            None => (name, value),
+                        })
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.apply(v)).collect()),
+            scalar => scalar,
+        }
+    }
+}
+
+/// Trims successful JSON responses to the fields listed in `?fields=`.
+pub(crate) async fn filter_fields(req: Request<Body>, next: Next) -> Response {
+    let query: FieldsQuery =
+        Query::try_from_uri(req.uri()).map(|Query(query)| query).unwrap_or_default();
+    let Some(spec) = query.fields else { return next.run(req).await };
+    let fields = match Fields::parse(&spec) {
+        Ok(fields) => fields,
+        Err(message) => return ApiError::bad_request(message).into_response(),
+    };
+
+    let response = next.run(req).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !response.status().is_success() || !is_json {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to read response body to filter fields: {:?}", e);
+            return ApiError::internal().into_response();
+        }
+    };
+    let Ok(value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    let Ok(filtered) = serde_json::to_vec(&fields.apply(value)) else {
+        return ApiError::internal().into_response();
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    // The tag identifies the full representation, which this response no longer is.
+    parts.headers.remove(header::ETAG);
+    Response::from_parts(parts, Body::from(filtered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_listed_and_nested_fields() {
+        let fields = Fields::parse("epoch, validators.address,block.round").unwrap();
+        let value = json!({
+            "epoch": 7,
+            "total_voting_power": 300,
+            "validators": [
+                {"address": "0x1", "voting_power": 100},
+                {"address": "0x2", "voting_power": 200},
+            ],
+            "block": {"round": 3, "payload": "..."},
+        });
+        assert_eq!(
+            fields.apply(value),
+            json!({
+                "epoch": 7,
+                "validators": [{"address": "0x1"}, {"address": "0x2"}],
+                "block": {"round": 3},
+            })
+        );
+    }
+
+    #[test]
+    fn whole_field_wins_over_nested_ones() {
+        assert_eq!(
+            Fields::parse("validators.address,validators").unwrap(),
+            Fields::parse("validators").unwrap()
+        );
+        assert!(Fields::parse(" , ").is_err());
+        assert!(Fields::parse("validators..address").is_err());
+    }
+}
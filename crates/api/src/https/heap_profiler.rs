@@ -1,10 +1,16 @@
-use axum::{response::IntoResponse, Json};
-use gaptos::aptos_logger::{info, warn};
-use once_cell::sync::Lazy;
+use crate::https::error::ApiError;
+use axum::{http::StatusCode, response::Response, Json};
+use gaptos::aptos_logger::{error, info, warn};
+use once_cell::sync::{Lazy, OnceCell};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     env,
+    ffi::{c_void, CString},
+    os::raw::c_char,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tikv_jemalloc_ctl::raw;
 
@@ -18,9 +24,57 @@ const PROF_ACTIVE: &[u8] = b"prof.active\0";
 #[allow(dead_code)]
 const PROF_THREAD_ACTIVE_INIT: &[u8] = b"prof.thread_active_init\0";
 
+#[allow(dead_code)]
+const PROF_DUMP: &[u8] = b"prof.dump\0";
+
 #[allow(dead_code)]
 pub static PROFILER: Lazy<HeapProfiler> = Lazy::new(HeapProfiler::new);
 
+/// Directory scheduled dumps are written to when the request names none, from the API config.
+static DEFAULT_DUMP_DIR: OnceCell<PathBuf> = OnceCell::new();
+const DUMP_FILE_PREFIX: &str = "gravity-heap-";
+const DUMP_FILE_SUFFIX: &str = ".prof";
+#[allow(dead_code)]
+const DEFAULT_KEEP_DUMPS: usize = 48;
+#[allow(dead_code)]
+const MIN_DUMP_INTERVAL: Duration = Duration::from_secs(60);
+#[allow(dead_code)]
+const MAX_SCHEDULE_DURATION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// The armed dump schedule, if any, and the task running it.
+#[allow(dead_code)]
+static DUMP_SCHEDULE: Lazy<
+    Mutex<Option<(Arc<Mutex<DumpScheduleStatus>>, tokio::task::AbortHandle)>>,
+> = Lazy::new(|| Mutex::new(None));
+
+/// Arms periodic heap dumps, e.g. every 15 minutes for 6 hours:
+/// `{"interval_secs": 900, "duration_secs": 21600}`.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ScheduleDumpsRequest {
+    pub interval_secs: u64,
+    pub duration_secs: u64,
+    /// Where the dumps are written; defaults to `heap_dump_dir` of the API config, else
+    /// `<tmp>/gravity-heap-dumps`.
+    pub dir: Option<PathBuf>,
+    /// Newest dumps kept in `dir`; older ones are deleted after each dump (default 48).
+    pub keep: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DumpScheduleStatus {
+    /// False once the schedule ended or was cancelled.
+    pub active: bool,
+    pub interval_secs: u64,
+    /// Unix timestamps in milliseconds.
+    pub started_ms: u64,
+    pub ends_ms: u64,
+    pub dir: PathBuf,
+    pub keep: usize,
+    pub dumps_taken: u64,
+    pub last_dump: Option<PathBuf>,
+    pub last_error: Option<String>,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct ControlProfileRequest {
     enable: bool,
@@ -33,14 +87,365 @@ pub struct ControlProfileResponse {
 
 /// User should use binary with feature api/jemalloc-profiling enabled.
 /// This feature can be enabled by ```Cargo build --features api/jemalloc-profiling```
-pub async fn control_profiler(_request: ControlProfileRequest) -> impl IntoResponse {
+pub async fn control_profiler(
+    _request: ControlProfileRequest,
+) -> Result<Json<ControlProfileResponse>, ApiError> {
     #[cfg(feature = "jemalloc-profiling")]
     match PROFILER.set_prof_active(_request.enable) {
-        Ok(_) => Json(ControlProfileResponse { response: "success".to_string() }),
-        Err(e) => Json(ControlProfileResponse { response: e }),
+        Ok(_) => Ok(Json(ControlProfileResponse { response: "success".to_string() })),
+        Err(e) => Err(ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "profiler_error", e)),
+    }
+    #[cfg(not(feature = "jemalloc-profiling"))]
+    Err(ApiError::new(
+        StatusCode::BAD_REQUEST,
+        "feature_disabled",
+        "jemalloc profiling is not enabled",
+    ))
+}
+
+// example:
+// curl -o heap.prof http://127.0.0.1:1024/mem_prof/dump
+pub async fn dump_heap_profile() -> Result<Response, ApiError> {
+    #[cfg(feature = "jemalloc-profiling")]
+    {
+        use axum::{http::header, response::IntoResponse};
+        let profile = run_blocking(|| PROFILER.dump()).await?;
+        Ok(([(header::CONTENT_TYPE, "application/octet-stream")], profile).into_response())
+    }
+    #[cfg(not(feature = "jemalloc-profiling"))]
+    Err(ApiError::new(
+        StatusCode::BAD_REQUEST,
+        "feature_disabled",
+        "jemalloc profiling is not enabled",
+    ))
+}
+
+/// Arms periodic heap dumps, replacing any schedule already armed. Profiling is activated for
+/// the dumps to have samples.
+// example:
+// curl -X POST http://127.0.0.1:1024/mem_prof/schedule -d '{"interval_secs":900,"duration_secs":21600}'
+pub async fn schedule_heap_dumps(
+    _request: ScheduleDumpsRequest,
+) -> Result<Json<DumpScheduleStatus>, ApiError> {
+    #[cfg(feature = "jemalloc-profiling")]
+    {
+        let interval = Duration::from_secs(_request.interval_secs);
+        let duration = Duration::from_secs(_request.duration_secs);
+        if interval < MIN_DUMP_INTERVAL || duration < interval || duration > MAX_SCHEDULE_DURATION {
+            return Err(ApiError::bad_request(format!(
+                "interval_secs must be at least {}, and duration_secs between interval_secs and {}",
+                MIN_DUMP_INTERVAL.as_secs(),
+                MAX_SCHEDULE_DURATION.as_secs()
+            )));
+        }
+        let keep = _request.keep.unwrap_or(DEFAULT_KEEP_DUMPS);
+        if keep == 0 {
+            return Err(ApiError::bad_request("keep must be at least 1"));
+        }
+        let dir = _request.dir.unwrap_or_else(default_dump_dir);
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            ApiError::bad_request(format!("cannot create dump directory {}: {e}", dir.display()))
+        })?;
+        PROFILER
+            .set_prof_active(true)
+            .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "profiler_error", e))?;
+
+        let started_ms = unix_ms();
+        let status = Arc::new(Mutex::new(DumpScheduleStatus {
+            active: true,
+            interval_secs: interval.as_secs(),
+            started_ms,
+            ends_ms: started_ms + duration.as_millis() as u64,
+            dir: dir.clone(),
+            keep,
+            dumps_taken: 0,
+            last_dump: None,
+            last_error: None,
+        }));
+        let task = tokio::spawn(run_dump_schedule(status.clone(), interval, duration, dir, keep));
+        let mut schedule = DUMP_SCHEDULE.lock().map_err(|_| ApiError::internal())?;
+        if let Some((_, previous)) = schedule.replace((status.clone(), task.abort_handle())) {
+            previous.abort();
+        }
+        info!("Scheduled heap dumps every {}s for {}s", interval.as_secs(), duration.as_secs());
+        let status = status.lock().map_err(|_| ApiError::internal())?.clone();
+        Ok(Json(status))
+    }
+    #[cfg(not(feature = "jemalloc-profiling"))]
+    Err(ApiError::new(
+        StatusCode::BAD_REQUEST,
+        "feature_disabled",
+        "jemalloc profiling is not enabled",
+    ))
+}
+
+/// Status of the last armed dump schedule.
+// example:
+// curl http://127.0.0.1:1024/mem_prof/schedule
+pub async fn get_heap_dump_schedule() -> Result<Json<DumpScheduleStatus>, ApiError> {
+    let schedule = DUMP_SCHEDULE.lock().map_err(|_| ApiError::internal())?;
+    let Some((status, _)) = schedule.as_ref() else {
+        return Err(ApiError::not_found("No heap dump schedule armed"));
+    };
+    let status = status.lock().map_err(|_| ApiError::internal())?.clone();
+    Ok(Json(status))
+}
+
+/// Cancels the armed dump schedule. Dumps already written are kept.
+// example:
+// curl -X DELETE http://127.0.0.1:1024/mem_prof/schedule
+pub async fn cancel_heap_dump_schedule() -> Result<Json<DumpScheduleStatus>, ApiError> {
+    let schedule = DUMP_SCHEDULE.lock().map_err(|_| ApiError::internal())?;
+    let Some((status, task)) = schedule.as_ref() else {
+        return Err(ApiError::not_found("No heap dump schedule armed"));
+    };
+    task.abort();
+    let mut status = status.lock().map_err(|_| ApiError::internal())?;
+    if status.active {
+        status.active = false;
+        info!("Cancelled heap dump schedule after {} dumps", status.dumps_taken);
+    }
+    Ok(Json(status.clone()))
+}
+
+#[allow(dead_code)]
+async fn run_dump_schedule(
+    status: Arc<Mutex<DumpScheduleStatus>>,
+    interval: Duration,
+    duration: Duration,
+    dir: PathBuf,
+    keep: usize,
+) {
+    let ends = tokio::time::Instant::now() + duration;
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick completes immediately; the first dump is one interval in.
+    ticker.tick().await;
+    while ticker.tick().await <= ends {
+        let path = dir.join(dump_file_name(unix_ms()));
+        let dump_path = path.clone();
+        let dump_dir = dir.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            PROFILER.dump_to(&dump_path)?;
+            prune_dumps(&dump_dir, keep)
+        })
+        .await
+        .unwrap_or_else(|e| Err(format!("heap dump task panicked: {e}")));
+        let Ok(mut status) = status.lock() else { return };
+        match result {
+            Ok(()) => {
+                status.dumps_taken += 1;
+                status.last_dump = Some(path);
+                status.last_error = None;
+            }
+            Err(e) => {
+                warn!("Scheduled heap dump failed: {}", e);
+                status.last_error = Some(e);
+            }
+        }
+    }
+    if let Ok(mut status) = status.lock() {
+        status.active = false;
+        info!("Heap dump schedule finished after {} dumps", status.dumps_taken);
+    }
+}
+
+/// Sets where scheduled dumps go when the request names no directory; only the first call
+/// takes effect.
+pub(crate) fn set_default_dump_dir(dir: PathBuf) {
+    let _ = DEFAULT_DUMP_DIR.set(dir);
+}
+
+#[allow(dead_code)]
+fn default_dump_dir() -> PathBuf {
+    DEFAULT_DUMP_DIR.get().cloned().unwrap_or_else(|| env::temp_dir().join("gravity-heap-dumps"))
+}
+
+#[allow(dead_code)]
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Dump file names sort in the order the dumps were taken.
+#[allow(dead_code)]
+fn dump_file_name(unix_ms: u64) -> String {
+    format!("{DUMP_FILE_PREFIX}{unix_ms:015}{DUMP_FILE_SUFFIX}")
+}
+
+/// Of the dump files among `names`, the ones beyond the newest `keep`.
+#[allow(dead_code)]
+fn dumps_to_remove(mut names: Vec<String>, keep: usize) -> Vec<String> {
+    names.retain(|name| name.starts_with(DUMP_FILE_PREFIX) && name.ends_with(DUMP_FILE_SUFFIX));
+    names.sort();
+    let excess = names.len().saturating_sub(keep);
+    names.truncate(excess);
+    names
+}
+
+/// Deletes all but the newest `keep` dumps in `dir`.
+#[allow(dead_code)]
+fn prune_dumps(dir: &Path, keep: usize) -> Result<(), String> {
+    let names = std::fs::read_dir(dir)
+        .map_err(|e| format!("failed to list {}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    for name in dumps_to_remove(names, keep) {
+        if let Err(e) = std::fs::remove_file(dir.join(&name)) {
+            warn!("Failed to delete old heap dump {}: {}", name, e);
+        }
+    }
+    Ok(())
+}
+
+/// Dumps the current heap profile, symbolizes it in-process and renders an SVG flamegraph of
+/// the bytes still allocated, so operators don't have to copy dumps off the box and run jeprof.
+// example:
+// curl -o heap.svg http://127.0.0.1:1024/mem_prof/flamegraph
+pub async fn heap_flamegraph() -> Result<Response, ApiError> {
+    #[cfg(feature = "jemalloc-profiling")]
+    {
+        use axum::{http::header, response::IntoResponse};
+        let svg = run_blocking(|| {
+            let profile = PROFILER.dump()?;
+            render_flamegraph(&String::from_utf8_lossy(&profile))
+        })
+        .await?;
+        Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg).into_response())
     }
     #[cfg(not(feature = "jemalloc-profiling"))]
-    Json(ControlProfileResponse { response: "jemalloc profiling is not enabled".to_string() })
+    Err(ApiError::new(
+        StatusCode::BAD_REQUEST,
+        "feature_disabled",
+        "jemalloc profiling is not enabled",
+    ))
+}
+
+/// Dumping and symbolizing walk the whole profile, keep them off the async workers.
+#[allow(dead_code)]
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, ApiError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| {
+            error!("Heap profile task panicked: {:?}", e);
+            ApiError::internal()
+        })?
+        .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "profiler_error", e))
+}
+
+/// One sampled allocation site of a jemalloc `heap_v2` profile.
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, Eq)]
+struct HeapSample {
+    /// Return addresses, innermost frame first.
+    stack: Vec<u64>,
+    /// Bytes allocated from this site that are still live.
+    bytes: u64,
+}
+
+/// Parses the stack traces of a jemalloc `heap_v2` profile:
+///
+/// ```text
+/// heap_v2/524288
+///   t*: 28106: 56637512 [0: 0]
+/// @ 0x55d3a1 0x55c0f2 0x7f31a9
+///   t*: 13: 6688 [0: 0]
+///   t0: 13: 6688 [0: 0]
+/// MAPPED_LIBRARIES:
+/// ```
+///
+/// The `t*` line after each `@` stack holds the totals across threads.
+#[allow(dead_code)]
+fn parse_heap_profile(profile: &str) -> Result<Vec<HeapSample>, String> {
+    let mut lines = profile.lines();
+    match lines.next() {
+        Some(header) if header.starts_with("heap_v2/") => {}
+        other => return Err(format!("unsupported heap profile header: {other:?}")),
+    }
+
+    let mut samples = Vec::new();
+    let mut stack: Option<Vec<u64>> = None;
+    for line in lines {
+        if line.starts_with("MAPPED_LIBRARIES:") {
+            break;
+        }
+        if let Some(addresses) = line.strip_prefix('@') {
+            let frames = addresses
+                .split_whitespace()
+                .map(|a| {
+                    u64::from_str_radix(a.trim_start_matches("0x"), 16)
+                        .map_err(|e| format!("invalid frame address {a}: {e}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            stack = Some(frames);
+        } else if let Some(totals) = line.trim_start().strip_prefix("t*:") {
+            // Totals before the first stack are the profile-wide summary.
+            let Some(frames) = stack.take() else { continue };
+            let bytes = totals
+                .split(':')
+                .nth(1)
+                .and_then(|v| v.split_whitespace().next())
+                .and_then(|v| v.parse::<u64>().ok())
+                .ok_or_else(|| format!("invalid sample line: {line}"))?;
+            if bytes > 0 {
+                samples.push(HeapSample { stack: frames, bytes });
+            }
+        }
+    }
+    Ok(samples)
+}
+
+/// Folds the samples into `root;...;leaf bytes` lines, resolving each address with `resolve`.
+/// A single address can expand to several frames when functions were inlined.
+#[allow(dead_code)]
+fn fold_samples(
+    samples: &[HeapSample],
+    mut resolve: impl FnMut(u64) -> Vec<String>,
+) -> Vec<String> {
+    let mut names: HashMap<u64, Vec<String>> = HashMap::new();
+    let mut folded: HashMap<String, u64> = HashMap::new();
+    for sample in samples {
+        let mut frames = Vec::new();
+        for address in sample.stack.iter().rev() {
+            let resolved = names.entry(*address).or_insert_with(|| resolve(*address));
+            // Resolvers report inlined frames innermost first, the fold is root first.
+            frames.extend(resolved.iter().rev().map(|f| f.replace(';', ":")));
+        }
+        *folded.entry(frames.join(";")).or_default() += sample.bytes;
+    }
+    let mut lines: Vec<String> =
+        folded.into_iter().map(|(stack, bytes)| format!("{stack} {bytes}")).collect();
+    lines.sort();
+    lines
+}
+
+/// Resolves a return address of this process to function names, innermost inlined frame first.
+#[allow(dead_code)]
+fn symbolize(address: u64) -> Vec<String> {
+    let mut names = Vec::new();
+    // Return addresses point past the call instruction, look up the call itself.
+    backtrace::resolve(address.saturating_sub(1) as *mut c_void, |symbol| {
+        if let Some(name) = symbol.name() {
+            names.push(format!("{name:#}"));
+        }
+    });
+    if names.is_empty() {
+        names.push(format!("{address:#x}"));
+    }
+    names
+}
+
+#[allow(dead_code)]
+fn render_flamegraph(profile: &str) -> Result<Vec<u8>, String> {
+    let samples = parse_heap_profile(profile)?;
+    let folded = fold_samples(&samples, symbolize);
+    let mut options = inferno::flamegraph::Options::default();
+    options.title = "Heap profile (in-use bytes)".to_string();
+    options.count_name = "bytes".to_string();
+    let mut svg = Vec::new();
+    inferno::flamegraph::from_lines(&mut options, folded.iter().map(String::as_str), &mut svg)
+        .map_err(|e| format!("failed to render flamegraph: {e}"))?;
+    Ok(svg)
 }
 
 impl HeapProfiler {
@@ -50,7 +455,7 @@ impl HeapProfiler {
 
     #[allow(dead_code)]
     pub fn set_prof_active(&self, prof: bool) -> Result<(), String> {
-        let _guard = self.mutex.lock().unwrap();
+        let _guard = self.mutex.lock().map_err(|_| "heap profiler lock is poisoned".to_string())?;
         if let Err(err) = unsafe { raw::write(PROF_ACTIVE, prof) } {
             let err = format!("jemalloc heap profiling active failed: {err}");
             warn!("{}", err);
@@ -68,4 +473,87 @@ impl HeapProfiler {
         }
         Ok(())
     }
+
+    /// Writes a jemalloc heap profile through `prof.dump` and returns its contents.
+    #[allow(dead_code)]
+    pub fn dump(&self) -> Result<Vec<u8>, String> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let path =
+            env::temp_dir().join(format!("gravity-heap-{}-{nanos}.prof", std::process::id()));
+        self.dump_to(&path)?;
+        let profile = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+        profile.map_err(|e| format!("failed to read heap dump {}: {e}", path.display()))
+    }
+
+    /// Writes a jemalloc heap profile to `path` through `prof.dump`.
+    #[allow(dead_code)]
+    pub fn dump_to(&self, path: &Path) -> Result<(), String> {
+        let _guard = self.mutex.lock().map_err(|_| "heap profiler lock is poisoned".to_string())?;
+        let c_path = CString::new(path.to_string_lossy().into_owned())
+            .map_err(|e| format!("invalid heap dump path: {e}"))?;
+        if let Err(err) = unsafe { raw::write(PROF_DUMP, c_path.as_ptr() as *const c_char) } {
+            let err = format!("jemalloc heap profile dump failed: {err}");
+            warn!("{}", err);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = "heap_v2/524288
+  t*: 30: 12288 [0: 0]
+  t0: 30: 12288 [0: 0]
+@ 0x30 0x20 0x10
+  t*: 20: 8192 [0: 0]
+  t0: 20: 8192 [0: 0]
+@ 0x31 0x20 0x10
+  t*: 10: 4096 [0: 0]
+@ 0x40 0x10
+  t*: 0: 0 [0: 0]
+
+MAPPED_LIBRARIES:
+55d3a1000000-55d3a2000000 r-xp 00000000 00:00 0 /usr/bin/gravity_node
+";
+
+    #[test]
+    fn parses_heap_v2_samples() {
+        let samples = parse_heap_profile(PROFILE).unwrap();
+        assert_eq!(
+            samples,
+            vec![
+                HeapSample { stack: vec![0x30, 0x20, 0x10], bytes: 8192 },
+                HeapSample { stack: vec![0x31, 0x20, 0x10], bytes: 4096 },
+            ]
+        );
+        assert!(parse_heap_profile("heap_v1/1").is_err());
+    }
+
+    #[test]
+    fn prunes_all_but_newest_dumps() {
+        let names = vec![
+            dump_file_name(3_000),
+            "notes.txt".to_string(),
+            dump_file_name(1_000),
+            dump_file_name(20_000),
+        ];
+        assert_eq!(dumps_to_remove(names.clone(), 2), vec![dump_file_name(1_000)]);
+        assert!(dumps_to_remove(names, 3).is_empty());
+    }
+
+    #[test]
+    fn folds_root_first_and_merges_sites() {
+        let samples = parse_heap_profile(PROFILE).unwrap();
+        let folded = fold_samples(&samples, |address| match address {
+            0x10 => vec!["main".to_string()],
+            // 0x20 is `inner` inlined into `outer`
+            0x20 => vec!["inner".to_string(), "outer".to_string()],
+            _ => vec!["alloc".to_string()],
+        });
+        assert_eq!(folded, vec!["main;outer;inner;alloc 12288".to_string()]);
+    }
 }
@@ -0,0 +1,544 @@
+//! Deferred execution of expensive queries.
+//!
+//! `POST /jobs` queues a query and answers right away with its id, `GET /jobs/:id` reports its
+//! progress and `GET /jobs/:id/result` downloads the JSON result once it has succeeded. Only a few
+//! jobs run at a time, so long scans can't starve the request handlers. Job records and results
+//! are kept in the jobs directory and reloaded on restart; jobs cut short by a restart are
+//! reported as failed.
+
+use crate::https::{
+    consensus::{get_blocks_in_epoch, LedgerInfoResponse},
+    error::ApiError,
+};
+use aptos_consensus::consensusdb::{ConsensusDB, LedgerInfoSchema};
+use axum::{
+    body::Body,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json as JsonResponse, Response},
+};
+use futures::stream;
+use gaptos::{
+    aptos_infallible::Mutex,
+    aptos_logger::{error, info, warn},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::{io::AsyncReadExt, sync::Semaphore};
+
+/// Submissions are refused while this many jobs are waiting for a slot.
+const MAX_QUEUED_JOBS: usize = 32;
+/// Finished jobs kept on disk; the oldest ones are deleted beyond this.
+const MAX_RETAINED_JOBS: usize = 100;
+/// Upper bound on the block range of a `ledger_infos` job.
+const MAX_LEDGER_INFO_RANGE: u64 = 10_000_000;
+/// Ledger infos are read and written out in batches of this many blocks.
+const LEDGER_INFO_BATCH: u64 = 10_000;
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Query run by a job, e.g. `{"kind": "epoch_blocks", "epoch": 3}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobSpec {
+    /// Every block stored for `epoch`, ordered by round.
+    EpochBlocks {
+        epoch: u64,
+        #[serde(default)]
+        include_payload: bool,
+    },
+    /// Commit ledger infos of blocks in `[start_block, end_block)`.
+    LedgerInfos { start_block: u64, end_block: u64 },
+}
+
+impl JobSpec {
+    fn validate(&self) -> Result<(), ApiError> {
+        match *self {
+            JobSpec::EpochBlocks { .. } => Ok(()),
+            JobSpec::LedgerInfos { start_block, end_block } => {
+                if start_block >= end_block {
+                    return Err(ApiError::bad_request(format!(
+                        "start_block ({start_block}) must be lower than end_block ({end_block})"
+                    )));
+                }
+                if end_block - start_block > MAX_LEDGER_INFO_RANGE {
+                    return Err(ApiError::bad_request(format!(
+                        "At most {MAX_LEDGER_INFO_RANGE} blocks can be scanned by one job"
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Succeeded | JobStatus::Failed)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JobInfo {
+    pub id: String,
+    pub spec: JobSpec,
+    pub status: JobStatus,
+    /// Unix timestamps in milliseconds.
+    pub submitted_at_ms: u64,
+    pub started_at_ms: Option<u64>,
+    pub finished_at_ms: Option<u64>,
+    /// Size of the result served by `/jobs/:id/result`, once the job has succeeded.
+    pub result_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Queues, runs and keeps track of jobs.
+pub struct JobManager {
+    dir: PathBuf,
+    consensus_db: Option<Arc<ConsensusDB>>,
+    slots: Arc<Semaphore>,
+    jobs: Mutex<HashMap<String, JobInfo>>,
+}
+
+impl JobManager {
+    /// Opens the jobs directory and reloads the jobs recorded there; at most `max_concurrent`
+    /// jobs run at the same time.
+    pub fn open(
+        dir: PathBuf,
+        consensus_db: Option<Arc<ConsensusDB>>,
+        max_concurrent: usize,
+    ) -> anyhow::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let manager = Self {
+            dir,
+            consensus_db,
+            slots: Arc::new(Semaphore::new(max_concurrent)),
+            jobs: Mutex::new(HashMap::new()),
+        };
+        manager.reload()?;
+        info!("Job API keeps results in {}, {} at a time", manager.dir.display(), max_concurrent);
+        Ok(manager)
+    }
+
+    fn reload(&self) -> anyhow::Result<()> {
+        let mut jobs = HashMap::new();
+        for entry in fs::read_dir(&self.dir)?.flatten() {
+            let path = entry.path();
+            if !path.to_string_lossy().ends_with(".job.json") {
+                continue;
+            }
+            let mut job: JobInfo = match fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| Ok(serde_json::from_slice(&bytes)?))
+            {
+                Ok(job) => job,
+                Err(e) => {
+                    warn!("Skipping unreadable job record {}: {:?}", path.display(), e);
+                    continue;
+                }
+            };
+            if !job.status.is_finished() {
+                job.status = JobStatus::Failed;
+                job.finished_at_ms = Some(now_ms());
+                job.error = Some("Interrupted by a node restart".to_string());
+                let _ = fs::remove_file(self.result_path(&job.id));
+                self.persist(&job);
+            }
+            jobs.insert(job.id.clone(), job);
+        }
+        *self.jobs.lock() = jobs;
+        self.prune();
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobInfo> {
+        self.jobs.lock().get(id).cloned()
+    }
+
+    pub fn submit(self: &Arc<Self>, spec: JobSpec) -> Result<JobInfo, ApiError> {
+        spec.validate()?;
+        let consensus_db =
+            self.consensus_db.clone().ok_or_else(ApiError::consensus_db_unavailable)?;
+
+        let job = {
+            let mut jobs = self.jobs.lock();
+            let queued = jobs.values().filter(|j| j.status == JobStatus::Queued).count();
+            if queued >= MAX_QUEUED_JOBS {
+                return Err(ApiError::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "too_many_jobs",
+                    format!("{queued} jobs are already waiting, try again later"),
+                ));
+            }
+            let id = loop {
+                let id = format!("{:016x}", rand::random::<u64>());
+                if !jobs.contains_key(&id) {
+                    break id;
+                }
+            };
+            let job = JobInfo {
+                id: id.clone(),
+                spec,
+                status: JobStatus::Queued,
+                submitted_at_ms: now_ms(),
+                started_at_ms: None,
+                finished_at_ms: None,
+                result_bytes: None,
+                error: None,
+            };
+            jobs.insert(id, job.clone());
+            job
+        };
+        self.persist(&job);
+        info!("Queued job {}: {:?}", job.id, job.spec);
+        tokio::spawn(self.clone().run(job.id.clone(), consensus_db));
+        Ok(job)
+    }
+
+    async fn run(self: Arc<Self>, id: String, consensus_db: Arc<ConsensusDB>) {
+        let Ok(_slot) = self.slots.clone().acquire_owned().await else { return };
+        let Some(spec) = self.update(&id, |job| {
+            job.status = JobStatus::Running;
+            job.started_at_ms = Some(now_ms());
+        }) else {
+            return;
+        };
+
+        let result_path = self.result_path(&id);
+        let result =
+            tokio::task::spawn_blocking(move || execute(&consensus_db, &spec, &result_path))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+
+        self.update(&id, |job| {
+            job.finished_at_ms = Some(now_ms());
+            match &result {
+                Ok(bytes) => {
+                    job.status = JobStatus::Succeeded;
+                    job.result_bytes = Some(*bytes);
+                }
+                Err(e) => {
+                    error!("Job {} failed: {:?}", job.id, e);
+                    job.status = JobStatus::Failed;
+                    job.error = Some(e.to_string());
+                }
+            }
+        });
+        self.prune();
+    }
+
+    /// Applies `f` to a job and persists it, returning the job's spec.
+    fn update(&self, id: &str, f: impl FnOnce(&mut JobInfo)) -> Option<JobSpec> {
+        let job = {
+            let mut jobs = self.jobs.lock();
+            let job = jobs.get_mut(id)?;
+            f(job);
+            job.clone()
+        };
+        self.persist(&job);
+        Some(job.spec)
+    }
+
+    /// Deletes the oldest finished jobs beyond `MAX_RETAINED_JOBS`.
+    fn prune(&self) {
+        let expired: Vec<String> = {
+            let mut jobs = self.jobs.lock();
+            let mut finished: Vec<(u64, String)> = jobs
+                .values()
+                .filter(|j| j.status.is_finished())
+                .map(|j| (j.finished_at_ms.unwrap_or_default(), j.id.clone()))
+                .collect();
+            if finished.len() <= MAX_RETAINED_JOBS {
+                return;
+            }
+            finished.sort();
+            let excess = finished.len() - MAX_RETAINED_JOBS;
+            finished
+                .into_iter()
+                .take(excess)
+                .map(|(_, id)| id)
+                .inspect(|id| {
+                    jobs.remove(id);
+                })
+                .collect()
+        };
+        for id in expired {
+            let _ = fs::remove_file(self.record_path(&id));
+            let _ = fs::remove_file(self.result_path(&id));
+        }
+    }
+
+    fn persist(&self, job: &JobInfo) {
+        let path = self.record_path(&job.id);
+        let written = serde_json::to_vec_pretty(job)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| write_atomically(&path, |file| Ok(file.write_all(&bytes)?)));
+        if let Err(e) = written {
+            error!("Failed to persist job {} to {}: {:?}", job.id, path.display(), e);
+        }
+    }
+
+    fn record_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.job.json"))
+    }
+
+    fn result_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.result.json"))
+    }
+}
+
+/// Runs the query and writes its JSON result to `path`, returning the result size.
+fn execute(consensus_db: &ConsensusDB, spec: &JobSpec, path: &Path) -> anyhow::Result<u64> {
+    write_atomically(path, |out| {
+        match *spec {
+            JobSpec::EpochBlocks { epoch, include_payload } => {
+                let blocks = get_blocks_in_epoch(consensus_db, epoch, include_payload)?;
+                serde_json::to_writer(out, &blocks)?;
+            }
+            JobSpec::LedgerInfos { start_block, end_block } => {
+                // Written as one JSON array, one batch at a time, to keep memory bounded.
+                out.write_all(b"[")?;
+                let mut first = true;
+                let mut batch_start = start_block;
+                while batch_start < end_block {
+                    let batch_end = end_block.min(batch_start.saturating_add(LEDGER_INFO_BATCH));
+                    let ledger_infos = consensus_db
+                        .get_range::<LedgerInfoSchema>(&batch_start, &batch_end)
+                        .map_err(|e| anyhow::anyhow!("Failed to read ledger infos: {e:?}"))?;
+                    for (_, ledger_info) in &ledger_infos {
+                        if !std::mem::take(&mut first) {
+                            out.write_all(b",")?;
+                        }
+                        serde_json::to_writer(&mut *out, &LedgerInfoResponse::from(ledger_info))?;
+                    }
+                    batch_start = batch_end;
+                }
+                out.write_all(b"]")?;
+            }
+        }
+        Ok(())
+    })?;
+    Ok(fs::metadata(path)?.len())
+}
+
+/// Writes through a temporary file so readers never see a partial file.
+pub(super) fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<fs::File>) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    write_through_tmp(path, options, write)
+}
+
+/// Like [`write_atomically`], for files holding secrets: the temporary file is created with mode
+/// 0600, so the content is never readable by other users, not even before the rename.
+pub(super) fn write_private_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<fs::File>) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    // A temporary file left behind by a crash would keep its own mode.
+    let _ = fs::remove_file(path.with_extension("tmp"));
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    write_through_tmp(path, options, write)
+}
+
+/// Takes away the group and other permissions of an existing file holding secrets, e.g. one
+/// written before the node created such files 0600.
+pub(super) fn restrict_to_owner(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)?.permissions().mode();
+        if mode & 0o077 != 0 {
+            fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o700))?;
+            info!("Restricted {} to its owner, it was mode {:o}", path.display(), mode & 0o777);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+fn write_through_tmp(
+    path: &Path,
+    options: fs::OpenOptions,
+    write: impl FnOnce(&mut BufWriter<fs::File>) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut out = BufWriter::new(options.open(&tmp)?);
+    let written = write(&mut out).and_then(|()| Ok(out.into_inner()?.sync_all()?));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+pub(super) fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+fn require_jobs(jobs: Option<Arc<JobManager>>) -> Result<Arc<JobManager>, ApiError> {
+    jobs.ok_or_else(|| ApiError::not_implemented("The job API is not enabled on this node"))
+}
+
+fn job_not_found(id: &str) -> ApiError {
+    ApiError::not_found(format!("Job {id} not found"))
+}
+
+// example:
+// curl -X POST -H "Content-Type:application/json" -d '{"kind": "epoch_blocks", "epoch": 3}' http://127.0.0.1:1024/jobs
+pub async fn submit_job(
+    jobs: Option<Arc<JobManager>>,
+    spec: JobSpec,
+) -> Result<(StatusCode, JsonResponse<JobInfo>), ApiError> {
+    let job = require_jobs(jobs)?.submit(spec)?;
+    Ok((StatusCode::ACCEPTED, JsonResponse(job)))
+}
+
+// example:
+// curl http://127.0.0.1:1024/jobs/5f3c2a9d8e7b6a41
+pub async fn get_job(
+    jobs: Option<Arc<JobManager>>,
+    id: String,
+) -> Result<JsonResponse<JobInfo>, ApiError> {
+    let job = require_jobs(jobs)?.get(&id).ok_or_else(|| job_not_found(&id))?;
+    Ok(JsonResponse(job))
+}
+
+// example:
+// curl -o blocks.json http://127.0.0.1:1024/jobs/5f3c2a9d8e7b6a41/result
+pub async fn get_job_result(
+    jobs: Option<Arc<JobManager>>,
+    id: String,
+) -> Result<Response, ApiError> {
+    let jobs = require_jobs(jobs)?;
+    let job = jobs.get(&id).ok_or_else(|| job_not_found(&id))?;
+    match job.status {
+        JobStatus::Succeeded => {}
+        JobStatus::Failed => {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "job_failed",
+                format!("Job {id} failed: {}", job.error.unwrap_or_default()),
+            ))
+        }
+        JobStatus::Queued | JobStatus::Running => {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "job_not_finished",
+                format!("Job {id} has not finished yet"),
+            ))
+        }
+    }
+
+    let path = jobs.result_path(&id);
+    let file = tokio::fs::File::open(&path).await.map_err(|e| {
+        error!("Failed to open result of job {} at {}: {:?}", id, path.display(), e);
+        ApiError::internal()
+    })?;
+    let chunks = stream::try_unfold(file, |mut file| async move {
+        let mut chunk = vec![0u8; DOWNLOAD_CHUNK_SIZE];
+        let read = file.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok::<_, std::io::Error>(None);
+        }
+        chunk.truncate(read);
+        Ok(Some((chunk, file)))
+    });
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"job-{id}.json\"")),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_jobs_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gravity-jobs-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn job(id: &str, status: JobStatus, finished_at_ms: Option<u64>) -> JobInfo {
+        JobInfo {
+            id: id.to_string(),
+            spec: JobSpec::EpochBlocks { epoch: 1, include_payload: false },
+            status,
+            submitted_at_ms: 0,
+            started_at_ms: None,
+            finished_at_ms,
+            result_bytes: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn reload_fails_interrupted_jobs() {
+        let dir = temp_jobs_dir("reload");
+        let manager = JobManager::open(dir.clone(), None, 2).unwrap();
+        manager.persist(&job("a", JobStatus::Running, None));
+        manager.persist(&job("b", JobStatus::Succeeded, Some(5)));
+
+        let reopened = JobManager::open(dir.clone(), None, 2).unwrap();
+        let interrupted = reopened.get("a").unwrap();
+        assert_eq!(interrupted.status, JobStatus::Failed);
+        assert!(interrupted.error.is_some());
+        assert_eq!(reopened.get("b").unwrap().status, JobStatus::Succeeded);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn prune_keeps_newest_finished_jobs() {
+        let dir = temp_jobs_dir("prune");
+        let manager = JobManager::open(dir.clone(), None, 2).unwrap();
+        {
+            let mut jobs = manager.jobs.lock();
+            for i in 0..MAX_RETAINED_JOBS as u64 + 2 {
+                let id = format!("{i}");
+                jobs.insert(id.clone(), job(&id, JobStatus::Succeeded, Some(i)));
+            }
+            jobs.insert("queued".to_string(), job("queued", JobStatus::Queued, None));
+        }
+        manager.prune();
+        assert!(manager.get("0").is_none() && manager.get("1").is_none());
+        assert!(manager.get("2").is_some() && manager.get("queued").is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_empty_ledger_info_range() {
+        let spec = JobSpec::LedgerInfos { start_block: 10, end_block: 10 };
+        assert!(spec.validate().is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use crate::https::error::ApiError;
+use axum::{http::StatusCode, response::Json as JsonResponse};
+use gaptos::aptos_logger::{error, filter::Filter, info, AptosData, Level};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+const RUST_LOG: &str = "RUST_LOG";
+const LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// The node's logger and the filter directives currently applied to it.
+struct LogFilterState {
+    logger: Arc<AptosData>,
+    directives: Mutex<String>,
+}
+
+static LOG_FILTER: OnceCell<LogFilterState> = OnceCell::new();
+
+/// Registers the node's logger so `/admin/log_level` can change its filter at runtime. The
+/// starting directives are `RUST_LOG` if set, otherwise the configured level.
+pub(crate) fn register_logger(logger: Arc<AptosData>, default_level: Level) {
+    let directives =
+        env::var(RUST_LOG).unwrap_or_else(|_| default_level.to_string().to_lowercase());
+    let _ = LOG_FILTER.set(LogFilterState { logger, directives: Mutex::new(directives) });
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct LogLevelRequest {
+    /// One of off, error, warn, info, debug or trace.
+    level: Option<String>,
+    /// Module path the level applies to, e.g. `aptos_consensus::round_manager`. Without it the
+    /// global level is changed and per-module overrides are kept.
+    module: Option<String>,
+    /// Full `RUST_LOG`-style directives replacing the current ones, e.g.
+    /// `info,aptos_consensus=debug`. Cannot be combined with `level`.
+    filter: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct LogLevelResponse {
+    /// Directives in effect after the request.
+    pub filter: String,
+}
+
+// example:
+// curl http://127.0.0.1:1024/admin/log_level
+pub async fn get_log_level() -> Result<JsonResponse<LogLevelResponse>, ApiError> {
+    let state = log_filter_state()?;
+    let directives = state.directives.lock().map_err(|_| ApiError::internal())?;
+    Ok(JsonResponse(LogLevelResponse { filter: directives.clone() }))
+}
+
+/// Changes the logger filter without restarting the node, so a stall can be debugged while it
+/// is still happening.
+// example:
+// curl -X POST -H "Content-Type:application/json" \
+//   -d '{"level": "debug", "module": "aptos_consensus"}' http://127.0.0.1:1024/admin/log_level
+pub async fn set_log_level(
+    request: LogLevelRequest,
+) -> Result<JsonResponse<LogLevelResponse>, ApiError> {
+    let state = log_filter_state()?;
+    let mut directives = state.directives.lock().map_err(|_| {
+        error!("Log filter lock is poisoned");
+        ApiError::internal()
+    })?;
+
+    let updated = match (request.filter, request.level) {
+        (Some(_), Some(_)) => {
+            return Err(ApiError::bad_request("filter and level cannot be combined"));
+        }
+        (Some(filter), None) => {
+            if request.module.is_some() {
+                return Err(ApiError::bad_request("module requires level"));
+            }
+            filter.trim().to_string()
+        }
+        (None, Some(level)) => {
+            let level = level.trim().to_lowercase();
+            if !LEVELS.contains(&level.as_str()) {
+                return Err(ApiError::bad_request(format!(
+                    "Invalid level '{level}', expected one of {}",
+                    LEVELS.join(", ")
+                )));
+            }
+            merge_directive(&directives, request.module.as_deref(), &level)
+        }
+        (None, None) => return Err(ApiError::bad_request("Either level or filter is required")),
+    };
+    if updated.is_empty() {
+        return Err(ApiError::bad_request("filter must not be empty"));
+    }
+
+    state.logger.set_local_filter(Filter::builder().parse(&updated).build());
+    // The logger's filter updater periodically rebuilds the filter from RUST_LOG, keep it in
+    // sync so the change survives the next refresh.
+    env::set_var(RUST_LOG, &updated);
+    info!("Log filter changed from '{}' to '{}'", directives, updated);
+    *directives = updated.clone();
+    Ok(JsonResponse(LogLevelResponse { filter: updated }))
+}
+
+fn log_filter_state() -> Result<&'static LogFilterState, ApiError> {
+    LOG_FILTER.get().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "logger_unavailable",
+            "The node logger is not initialized",
+        )
+    })
+}
+
+/// Sets `level` for `module` (or the global level without one) in comma-separated
+/// `RUST_LOG`-style directives, keeping every other directive.
+fn merge_directive(directives: &str, module: Option<&str>, level: &str) -> String {
+    let module = module.map(str::trim).filter(|m| !m.is_empty());
+    let mut merged: Vec<String> = Vec::new();
+    let mut replaced = false;
+    for directive in directives.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        let target = match directive.split_once('=') {
+            Some((target, _)) => Some(target.trim()),
+            // A bare level is the global directive, a bare module name enables it at trace.
+            None if LEVELS.contains(&directive.to_lowercase().as_str()) => None,
+            None => Some(directive),
+        };
+        if target == module {
+            if !replaced {
+                merged.push(format_directive(module, level));
+                replaced = true;
+            }
+        } else {
+            merged.push(directive.to_string());
+        }
+    }
+    if !replaced {
+        let directive = format_directive(module, level);
+        // Keep the global level first, as RUST_LOG is usually written.
+        if module.is_none() {
+            merged.insert(0, directive);
+        } else {
+            merged.push(directive);
+        }
+    }
+    merged.join(",")
+}
+
+fn format_directive(module: Option<&str>, level: &str) -> String {
+    match module {
+        Some(module) => format!("{module}={level}"),
+        None => level.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_global_and_module_levels() {
+        assert_eq!(merge_directive("info", None, "debug"), "debug");
+        assert_eq!(
+            merge_directive("info,aptos_consensus=warn", Some("aptos_consensus"), "trace"),
+            "info,aptos_consensus=trace"
+        );
+        assert_eq!(
+            merge_directive("info,aptos_consensus=warn", None, "error"),
+            "error,aptos_consensus=warn"
+        );
+        assert_eq!(
+            merge_directive("aptos_mempool=debug", None, "info"),
+            "info,aptos_mempool=debug"
+        );
+        assert_eq!(merge_directive("", Some("api"), "debug"), "api=debug");
+    }
+}
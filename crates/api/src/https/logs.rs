@@ -0,0 +1,155 @@
+//! Live tail of the node's log file, streamed as server-sent events by `/admin/logs/tail`.
+//!
+//! Each event's `data` is one log line. The file is polled for appended lines; when it shrinks
+//! or is replaced (log rotation) the tail restarts from the beginning of the new file.
+
+use crate::https::error::ApiError;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::{stream, Stream};
+use gaptos::aptos_logger::warn;
+use serde::Deserialize;
+use std::{
+    collections::VecDeque, convert::Infallible, io::SeekFrom, path::PathBuf, time::Duration,
+};
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, AsyncSeekExt, BufReader},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const DEFAULT_BACKLOG_LINES: usize = 20;
+const MAX_BACKLOG_LINES: usize = 1000;
+/// Only this much of the end of the file is scanned for backlog lines.
+const BACKLOG_SCAN_BYTES: u64 = 256 * 1024;
+
+/// Query parameters of `/admin/logs/tail`.
+#[derive(Deserialize, Debug)]
+pub struct LogTailQuery {
+    /// Only lines containing this substring are sent.
+    pub filter: Option<String>,
+    /// Number of existing lines to send before following (default 20, at most 1000).
+    pub lines: Option<usize>,
+    /// Keep the stream open for new lines; with `false` only the backlog is sent.
+    #[serde(default = "default_follow")]
+    pub follow: bool,
+}
+
+fn default_follow() -> bool {
+    true
+}
+
+struct LogTail {
+    path: PathBuf,
+    filter: Option<String>,
+    follow: bool,
+    reader: BufReader<File>,
+    /// Bytes of the current file consumed so far.
+    offset: u64,
+    /// Bytes of an incomplete trailing line, kept until its newline is written.
+    partial: String,
+    pending: VecDeque<String>,
+}
+
+impl LogTail {
+    fn matches(&self, line: &str) -> bool {
+        self.filter.as_deref().map_or(true, |filter| line.contains(filter))
+    }
+
+    /// Reads whatever complete lines have been appended since the last call.
+    async fn read_appended(&mut self) -> std::io::Result<()> {
+        loop {
+            let read = self.reader.read_line(&mut self.partial).await?;
+            if read == 0 {
+                return Ok(());
+            }
+            self.offset += read as u64;
+            if !self.partial.ends_with('\n') {
+                continue;
+            }
+            let line = std::mem::take(&mut self.partial);
+            let line = line.trim_end_matches(['\r', '\n']);
+            if self.matches(line) {
+                self.pending.push_back(line.to_string());
+            }
+        }
+    }
+
+    /// Reopens the file from the start if it was truncated or rotated away.
+    async fn reopen_if_rotated(&mut self) -> std::io::Result<()> {
+        let len = tokio::fs::metadata(&self.path).await?.len();
+        if len < self.offset {
+            self.reader = BufReader::new(File::open(&self.path).await?);
+            self.offset = 0;
+            self.partial.clear();
+        }
+        Ok(())
+    }
+
+    async fn next_line(&mut self) -> Option<String> {
+        loop {
+            if let Some(line) = self.pending.pop_front() {
+                return Some(line);
+            }
+            if !self.follow {
+                return None;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if let Err(e) = self.reopen_if_rotated().await {
+                // The writer may be between rotating and creating the new file.
+                warn!("Log tail could not stat {}: {}", self.path.display(), e);
+                continue;
+            }
+            if let Err(e) = self.read_appended().await {
+                warn!("Log tail stopped reading {}: {}", self.path.display(), e);
+                return None;
+            }
+        }
+    }
+}
+
+/// Opens the log file and queues the last `lines` matching lines as the backlog.
+async fn open_tail(path: PathBuf, query: LogTailQuery) -> std::io::Result<LogTail> {
+    let mut file = File::open(&path).await?;
+    let len = file.metadata().await?.len();
+    let start = len.saturating_sub(BACKLOG_SCAN_BYTES);
+    file.seek(SeekFrom::Start(start)).await?;
+
+    let mut tail = LogTail {
+        path,
+        filter: query.filter.filter(|f| !f.is_empty()),
+        follow: query.follow,
+        reader: BufReader::new(file),
+        offset: start,
+        partial: String::new(),
+        pending: VecDeque::new(),
+    };
+    if start > 0 {
+        // Drop the first, most likely cut, line of the scanned window.
+        let mut skipped = String::new();
+        tail.offset += tail.reader.read_line(&mut skipped).await? as u64;
+    }
+    tail.read_appended().await?;
+    let backlog = query.lines.unwrap_or(DEFAULT_BACKLOG_LINES).min(MAX_BACKLOG_LINES);
+    let excess = tail.pending.len().saturating_sub(backlog);
+    tail.pending.drain(..excess);
+    Ok(tail)
+}
+
+// example:
+// curl -N 'http://127.0.0.1:1024/admin/logs/tail?filter=consensus&lines=50'
+pub async fn tail_logs(
+    log_file: Option<PathBuf>,
+    query: LogTailQuery,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let log_file =
+        log_file.ok_or_else(|| ApiError::not_found("This node does not log to a file"))?;
+    let tail = open_tail(log_file.clone(), query).await.map_err(|e| {
+        warn!("Failed to open log file {}: {}", log_file.display(), e);
+        ApiError::not_found(format!("Log file {} is not readable", log_file.display()))
+    })?;
+    let events = stream::unfold(tail, |mut tail| async move {
+        let line = tail.next_line().await?;
+        Some((Ok(Event::default().data(line)), tail))
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
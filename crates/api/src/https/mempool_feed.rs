@@ -0,0 +1,238 @@
+//! `/ws/mempool`: a live feed of transactions entering and leaving the pool.
+//!
+//! The execution layer publishes an event whenever a transaction is admitted to its pool or
+//! dropped from it without being included (replaced, discarded or found invalid). Subscribers
+//! get them as JSON text messages, so searchers and monitoring tools can follow pending flow
+//! instead of diffing pool snapshots. Nothing is buffered for a subscriber that falls behind:
+//! it is told how many events it missed and continues with the newest ones, until it has missed
+//! more than the `max_lag` of the [`subscriptions`](crate::https::subscriptions) limits and is
+//! closed with [`CLOSE_LAGGING`].
+
+use crate::https::{
+    error::ApiError,
+    subscriptions::{self, Keepalive, SubscriptionLimits, Subscriptions, CLOSE_LAGGING},
+};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
+    response::Response,
+};
+use gaptos::aptos_logger::{debug, info};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    OwnedSemaphorePermit,
+};
+
+/// Events buffered per subscriber before it is considered lagging.
+const FEED_CAPACITY: usize = 4096;
+
+/// Feed the execution layer publishes pool events to.
+pub static MEMPOOL_FEED: Lazy<MempoolFeed> = Lazy::new(MempoolFeed::new);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolEventKind {
+    Admitted,
+    Dropped,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MempoolEvent {
+    pub event: MempoolEventKind,
+    /// 0x-prefixed transaction hash.
+    pub hash: String,
+    /// Why the transaction was admitted (`pending`, `queued`) or dropped (`replaced`,
+    /// `discarded`, `invalid`). A queued transaction that becomes executable is admitted again
+    /// as `pending`.
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// Hash of the transaction that replaced this one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+}
+
+impl MempoolEvent {
+    pub fn admitted(hash: [u8; 32], reason: &str) -> Self {
+        Self::new(MempoolEventKind::Admitted, hash, reason)
+    }
+
+    pub fn dropped(hash: [u8; 32], reason: &str) -> Self {
+        Self::new(MempoolEventKind::Dropped, hash, reason)
+    }
+
+    fn new(event: MempoolEventKind, hash: [u8; 32], reason: &str) -> Self {
+        Self {
+            event,
+            hash: format!("0x{}", hex::encode(hash)),
+            reason: reason.to_string(),
+            sender: None,
+            nonce: None,
+            replaced_by: None,
+        }
+    }
+
+    pub fn with_sender(mut self, sender: [u8; 20], nonce: u64) -> Self {
+        self.sender = Some(format!("0x{}", hex::encode(sender)));
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn with_replaced_by(mut self, hash: [u8; 32]) -> Self {
+        self.replaced_by = Some(format!("0x{}", hex::encode(hash)));
+        self
+    }
+}
+
+pub struct MempoolFeed {
+    sender: broadcast::Sender<Arc<MempoolEvent>>,
+}
+
+impl MempoolFeed {
+    fn new() -> Self {
+        Self { sender: broadcast::channel(FEED_CAPACITY).0 }
+    }
+
+    /// Whether anyone is listening, so publishers can skip building events nobody reads.
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    pub fn publish(&self, event: MempoolEvent) {
+        // Fails only when there are no subscribers.
+        let _ = self.sender.send(Arc::new(event));
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Arc<MempoolEvent>> {
+        self.sender.subscribe()
+    }
+}
+
+/// Query parameters of `/ws/mempool`.
+#[derive(Deserialize, Debug, Default)]
+pub struct MempoolFeedQuery {
+    /// Comma-separated event kinds to receive, e.g. `dropped`. All kinds by default.
+    pub events: Option<String>,
+}
+
+impl MempoolFeedQuery {
+    fn kinds(&self) -> Result<Vec<MempoolEventKind>, String> {
+        let Some(events) = self.events.as_deref() else {
+            return Ok(vec![MempoolEventKind::Admitted, MempoolEventKind::Dropped]);
+        };
+        events
+            .split(',')
+            .map(str::trim)
+            .filter(|kind| !kind.is_empty())
+            .map(|kind| {
+                serde_json::from_value(serde_json::Value::String(kind.to_string()))
+                    .map_err(|_| format!("unknown mempool event '{kind}'"))
+            })
+            .collect()
+    }
+}
+
+/// Sent in place of the events a lagging subscriber missed.
+#[derive(Serialize)]
+struct Lagged {
+    event: &'static str,
+    skipped: u64,
+}
+
+// example:
+// websocat 'ws://127.0.0.1:1024/ws/mempool?events=dropped'
+pub(crate) async fn mempool_ws(
+    subscriptions: Arc<Subscriptions>,
+    ws: WebSocketUpgrade,
+    Query(query): Query<MempoolFeedQuery>,
+) -> Result<Response, ApiError> {
+    let kinds = query.kinds().map_err(ApiError::bad_request)?;
+    let permit = subscriptions.acquire()?;
+    let limits = subscriptions.limits();
+    info!("Mempool feed subscriber connecting, {} streams open", subscriptions.open());
+    Ok(ws.on_upgrade(move |socket| stream_events(socket, kinds, limits, permit)))
+}
+
+async fn stream_events(
+    mut socket: WebSocket,
+    kinds: Vec<MempoolEventKind>,
+    limits: SubscriptionLimits,
+    // Frees the subscription slot when the connection ends.
+    _permit: OwnedSemaphorePermit,
+) {
+    let mut events = MEMPOOL_FEED.subscribe();
+    let mut keepalive = Keepalive::new(&limits);
+    let mut missed: u64 = 0;
+    loop {
+        let message = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) if kinds.contains(&event.event) => serde_json::to_string(&*event),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    missed = missed.saturating_add(skipped);
+                    if missed > limits.max_lag {
+                        debug!("Mempool feed subscriber dropped after missing {} events", missed);
+                        subscriptions::close(&mut socket, CLOSE_LAGGING, "subscriber lagging")
+                            .await;
+                        break;
+                    }
+                    serde_json::to_string(&Lagged { event: "lagged", skipped })
+                }
+                // The feed lives as long as the process.
+                Err(RecvError::Closed) => break,
+            },
+            alive = keepalive.tick() => {
+                if !subscriptions::ping(&mut socket, alive, limits.send_timeout).await {
+                    break;
+                }
+                continue;
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Clients have nothing to say beyond pongs, which only show they are alive.
+                Some(Ok(_)) => {
+                    keepalive.seen();
+                    continue;
+                }
+            },
+        };
+        let Ok(message) = message else { continue };
+        if !subscriptions::send(&mut socket, Message::Text(message), limits.send_timeout).await {
+            break;
+        }
+    }
+    debug!("Mempool feed subscriber disconnected");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_event_filter() {
+        let query = MempoolFeedQuery { events: Some("dropped, admitted".to_string()) };
+        assert_eq!(
+            query.kinds().unwrap(),
+            vec![MempoolEventKind::Dropped, MempoolEventKind::Admitted]
+        );
+        assert_eq!(MempoolFeedQuery::default().kinds().unwrap().len(), 2);
+        assert!(MempoolFeedQuery { events: Some("mined".to_string()) }.kinds().is_err());
+    }
+
+    #[test]
+    fn serializes_dropped_event() {
+        let event = MempoolEvent::dropped([1; 32], "replaced").with_replaced_by([2; 32]);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "dropped");
+        assert_eq!(json["reason"], "replaced");
+        assert!(json.get("sender").is_none());
+        assert_eq!(json["replaced_by"], format!("0x{}", "02".repeat(32)));
+    }
+}
@@ -1,62 +1,160 @@
+mod admission;
+pub mod allowlist;
+mod audit;
+mod block_feed;
+mod builder;
+pub mod cold_tier;
+mod config;
 pub mod consensus;
+pub mod consistency;
+pub mod cpu_profiler;
+mod db_snapshot;
 pub mod dkg;
+pub mod error;
+mod fields;
 pub mod heap_profiler;
+pub(crate) mod jobs;
+pub(crate) mod log_level;
+mod logs;
+pub mod mempool_feed;
+pub mod negotiate;
+pub(crate) mod node_config;
+mod otel;
+mod response_cache;
+mod route_usage;
+mod rpc;
+mod runtime_info;
+mod runtime_metrics;
 mod set_failpoints;
+mod snapshots;
+mod stats;
+mod subscriptions;
+mod tls;
+mod tokens;
 mod tx;
-use std::{net::SocketAddr, path::PathBuf, sync::Arc};
-
-use aptos_consensus::consensusdb::ConsensusDB;
+mod tx_results;
+mod versioning;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+
+pub(crate) use admission::AdmissionHook;
+pub use admission::FailMode;
+use allowlist::{enforce_admin_allowlist, AdminAllowlist};
+use aptos_consensus::{consensusdb::ConsensusDB, quorum_store::quorum_store_db::QuorumStoreDB};
+use audit::{audit_admin_actions, AuditLog};
 use axum::{
     body::Body,
-    extract::{DefaultBodyLimit, Path, State},
-    http::Request,
+    extract::{ws::WebSocketUpgrade, DefaultBodyLimit, Path, Query, State},
+    http::{HeaderMap, Request},
     middleware::{self, Next},
-    response::Response,
-    routing::{get, post},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
     Json, Router,
 };
-use axum_server::tls_rustls::RustlsConfig;
-use dkg::DkgState;
-use gaptos::{aptos_crypto::HashValue, aptos_logger::info};
-use heap_profiler::control_profiler;
+pub use builder::HttpsServerBuilder;
+use builder::RouterHook;
+use bytes::Bytes;
+pub use config::{
+    AdminConfig, AdmissionWebhookConfig, ApiConfig, ColdTierConfig, ConsistencyConfig,
+    LegacyPathsConfig, TokensConfig, TxCacheConfig, TxIndexConfig,
+};
+use consistency::ConsistencyChecker;
+use cpu_profiler::{control_cpu_profiler, ControlCpuProfileRequest};
+use dkg::{DecryptShareRequest, DkgState};
+use error::ApiError;
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_logger::{error, info},
+};
+use heap_profiler::{
+    cancel_heap_dump_schedule, control_profiler, dump_heap_profile, get_heap_dump_schedule,
+    heap_flamegraph, schedule_heap_dumps,
+};
+use jobs::{get_job, get_job_result, submit_job, JobManager, JobSpec};
+use logs::{tail_logs, LogTailQuery};
+use negotiate::ResponseFormat;
+use route_usage::{account_client_bytes, record_route_usage, RouteUsage};
 use set_failpoints::{set_failpoint, FailpointConf};
-use tx::{get_tx_by_hash, submit_tx, TxRequest};
+pub use subscriptions::SubscriptionLimits;
+use subscriptions::Subscriptions;
+pub use tls::{TlsMinVersion, TlsSettings};
+use tokens::TokenStore;
+use tower_http::compression::{
+    predicate::{NotForContentType, Predicate, SizeAbove},
+    CompressionLayer,
+};
+pub use tx::TxSubmitter;
+use tx::{
+    get_tx_by_hash, list_txs, submit_batch, submit_tx, SubmitTxQuery, TxBatchRequest, TxRequest,
+};
+pub(crate) use tx::{lookup_tx, tx_hash, TX_COMMIT_WAITERS};
+pub(crate) use tx_results::TX_RESULTS;
+pub use versioning::API_V1;
 
 pub struct HttpsServer {
     pub address: String,
     pub cert_pem: Option<PathBuf>,
     pub key_pem: Option<PathBuf>,
     pub consensus_db: Option<Arc<ConsensusDB>>,
+    /// Resolves the transactions of the blocks sent by `/ws/blocks`.
+    pub quorum_store_db: Option<Arc<QuorumStoreDB>>,
+    /// Backs `/tx/submit_tx` and `/tx/submit_batch`; submission is disabled without it.
+    pub tx_submitter: Option<Arc<TxSubmitter>>,
+    /// Node log file streamed by `/admin/logs/tail`.
+    pub log_file: Option<PathBuf>,
+    /// Where `/jobs` keeps job records and results; the job API is disabled without it.
+    pub jobs_dir: Option<PathBuf>,
+    /// Where `/admin/route_usage` counters are saved across restarts.
+    pub route_usage_file: Option<PathBuf>,
+    /// Where admin actions are audited, read back by `/admin/audit`; in memory without it.
+    pub audit_log_file: Option<PathBuf>,
+    /// Settings of the built-in API, from the `--api_config` file.
+    pub config: ApiConfig,
+    /// Application routes of the embedder, served next to the public ones.
+    pub routes: Router,
+    router_hooks: Vec<RouterHook>,
 }
 
 async fn ensure_https(req: Request<Body>, next: Next) -> Response {
     if req.uri().scheme_str() != Some("https") {
-        return Response::builder().status(400).body("HTTPS required".into()).unwrap();
+        return ApiError::bad_request("HTTPS required").into_response();
     }
     next.run(req).await
 }
 
 impl HttpsServer {
-    pub fn new(
-        address: String,
-        cert_pem: Option<PathBuf>,
-        key_pem: Option<PathBuf>,
-        consensus_db: Option<Arc<ConsensusDB>>,
-    ) -> Self {
-        Self { address, cert_pem, key_pem, consensus_db }
+    pub fn builder(address: impl Into<String>) -> HttpsServerBuilder {
+        HttpsServerBuilder::new(address)
     }
 
-    pub async fn serve(self) {
-        rustls::crypto::ring::default_provider().install_default().unwrap();
+    pub async fn serve(mut self) -> anyhow::Result<()> {
+        // Fails only if a provider is already installed for this process, which is fine.
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        otel::init_from_env();
+        if let Some(dir) = self.config.heap_dump_dir.clone() {
+            heap_profiler::set_default_dump_dir(dir);
+        }
 
         let consensus_db = self.consensus_db.clone();
         let dkg_state = DkgState::new(consensus_db);
 
+        let tx_submitter = self.tx_submitter.clone();
         let submit_tx_lambda =
-            |Json(request): Json<TxRequest>| async move { submit_tx(request).await };
+            move |State(state): State<Arc<DkgState>>,
+                  Query(query): Query<SubmitTxQuery>,
+                  Json(request): Json<TxRequest>| async move {
+                submit_tx(tx_submitter, state, request, query).await
+            };
+
+        let tx_submitter = self.tx_submitter.clone();
+        let submit_batch_lambda = move |Json(request): Json<TxBatchRequest>| async move {
+            submit_batch(tx_submitter, request).await
+        };
 
+        let quorum_store_db = self.quorum_store_db.clone();
         let get_tx_by_hash_lambda =
-            |Path(request): Path<HashValue>| async move { get_tx_by_hash(request).await };
+            move |State(state): State<Arc<DkgState>>, Path(request): Path<HashValue>| async move {
+                get_tx_by_hash(state, quorum_store_db, request).await
+            };
 
         let set_fail_point_lambda =
             |Json(request): Json<FailpointConf>| async move { set_failpoint(request).await };
@@ -65,94 +163,506 @@ impl HttpsServer {
             heap_profiler::ControlProfileRequest,
         >| async move { control_profiler(request).await };
 
+        let schedule_heap_dumps_lambda = |Json(request): Json<
+            heap_profiler::ScheduleDumpsRequest,
+        >| async move { schedule_heap_dumps(request).await };
+
+        let control_cpu_profiler_lambda = |Json(request): Json<ControlCpuProfileRequest>| async move {
+            control_cpu_profiler(request).await
+        };
+
+        let set_log_level_lambda = |Json(request): Json<log_level::LogLevelRequest>| async move {
+            log_level::set_log_level(request).await
+        };
+
+        let log_file = self.log_file.clone();
+        let tail_logs_lambda = move |Query(query): Query<LogTailQuery>| async move {
+            tail_logs(log_file, query).await
+        };
+
+        let max_concurrent_jobs = self.config.max_concurrent_jobs;
+        let jobs = self.jobs_dir.clone().and_then(|dir| {
+            match JobManager::open(dir.clone(), self.consensus_db.clone(), max_concurrent_jobs) {
+                Ok(jobs) => Some(Arc::new(jobs)),
+                Err(e) => {
+                    error!("Job API disabled, cannot use {}: {e:?}", dir.display());
+                    None
+                }
+            }
+        });
+        let submit_job_jobs = jobs.clone();
+        let submit_job_lambda =
+            move |Json(spec): Json<JobSpec>| async move { submit_job(submit_job_jobs, spec).await };
+        let get_job_jobs = jobs.clone();
+        let get_job_lambda =
+            move |Path(id): Path<String>| async move { get_job(get_job_jobs, id).await };
+        let get_job_result_lambda =
+            move |Path(id): Path<String>| async move { get_job_result(jobs, id).await };
+
+        let route_usage = Arc::new(RouteUsage::open(self.route_usage_file.clone()));
+        tokio::spawn(route_usage.clone().run_persist());
+        let get_route_usage_usage = route_usage.clone();
+        let get_route_usage_lambda =
+            move || async move { route_usage::get_route_usage(get_route_usage_usage).await };
+
+        let audit_log = Arc::new(AuditLog::open(self.audit_log_file.clone()));
+        let get_audit_log_audit = audit_log.clone();
+        let get_audit_log_lambda = move |Query(query): Query<audit::AuditQuery>| async move {
+            audit::get_audit_log(get_audit_log_audit, query).await
+        };
+
+        let tokens = Arc::new(TokenStore::open(
+            self.config.tokens.file.clone(),
+            self.config.tokens.required,
+        ));
+        let create_token_tokens = tokens.clone();
+        let create_token_lambda = move |Json(request): Json<tokens::CreateTokenRequest>| async move {
+            tokens::create_token(create_token_tokens, request).await
+        };
+        let create_delegated_token_tokens = tokens.clone();
+        let create_delegated_token_lambda =
+            move |Json(request): Json<tokens::CreateDelegatedTokenRequest>| async move {
+                tokens::create_delegated_token(create_delegated_token_tokens, request).await
+            };
+        let list_tokens_tokens = tokens.clone();
+        let list_tokens_lambda =
+            move || async move { tokens::list_tokens(list_tokens_tokens).await };
+        let revoke_token_tokens = tokens.clone();
+        let revoke_token_lambda = move |Path(id): Path<String>| async move {
+            tokens::revoke_token(revoke_token_tokens, id).await
+        };
+
+        // Shared by the WebSocket and SSE streams, which each hold a slot while open.
+        let subscriptions = Arc::new(Subscriptions::new(self.config.subscriptions));
+        let stream_ledger_info_subscriptions = subscriptions.clone();
+        let stream_ledger_info_lambda = move |State(state): State<Arc<DkgState>>| async move {
+            consensus::stream_ledger_info(stream_ledger_info_subscriptions, State(state)).await
+        };
+        let mempool_ws_subscriptions = subscriptions.clone();
+        let mempool_ws_lambda =
+            move |ws: WebSocketUpgrade, Query(query): Query<mempool_feed::MempoolFeedQuery>| async move {
+                mempool_feed::mempool_ws(mempool_ws_subscriptions, ws, Query(query)).await
+            };
+        let quorum_store_db = self.quorum_store_db.clone();
+        let blocks_ws_lambda =
+            move |State(state): State<Arc<DkgState>>,
+                  ws: WebSocketUpgrade,
+                  Query(query): Query<block_feed::BlockFeedQuery>| async move {
+                block_feed::blocks_ws(
+                    subscriptions,
+                    quorum_store_db,
+                    State(state),
+                    ws,
+                    Query(query),
+                )
+                .await
+            };
+
         let get_dkg_status_lambda =
             |State(state): State<Arc<DkgState>>| async move { state.get_dkg_status() };
 
-        let get_latest_ledger_info_lambda = |State(state): State<Arc<DkgState>>| async move {
-            consensus::get_latest_ledger_info(state)
-        };
+        let get_latest_ledger_info_lambda =
+            |State(state): State<Arc<DkgState>>,
+             Query(query): Query<consensus::LatestLedgerInfoQuery>,
+             Query(at): Query<consensus::AtBlockQuery>,
+             format: ResponseFormat| async move {
+                consensus::get_latest_ledger_info(state, query, at, format).await
+            };
+
+        let get_dkg_params_lambda =
+            |State(state): State<Arc<DkgState>>| async move { state.get_dkg_params() };
 
         let get_randomness_lambda =
             |State(state): State<Arc<DkgState>>, Path(block_number): Path<u64>| async move {
                 state.get_randomness(block_number)
             };
 
+        let get_transcript_lambda = |State(state): State<Arc<DkgState>>, Path(epoch): Path<u64>| async move {
+            state.get_transcript(epoch)
+        };
+
+        let decrypt_share_lambda =
+            |State(state): State<Arc<DkgState>>, Json(request): Json<DecryptShareRequest>| async move {
+                state.decrypt_share(request)
+            };
+
         let get_ledger_info_by_epoch_lambda =
-            |State(state): State<Arc<DkgState>>, Path(epoch): Path<u64>| async move {
-                consensus::get_ledger_info_by_epoch(State(state), Path(epoch))
+            |State(state): State<Arc<DkgState>>,
+             Path(epoch): Path<u64>,
+             Query(at): Query<consensus::AtBlockQuery>,
+             format: ResponseFormat| async move {
+                consensus::get_ledger_info_by_epoch(State(state), Path(epoch), Query(at), format)
             };
 
-        let get_block_lambda =
-            |State(state): State<Arc<DkgState>>, Path((epoch, round)): Path<(u64, u64)>| async move {
-                consensus::get_block(State(state), Path((epoch, round)))
+        let get_block_lambda = |State(state): State<Arc<DkgState>>,
+                                Path((epoch, round)): Path<(u64, u64)>,
+                                Query(query): Query<consensus::BlockQuery>,
+                                Query(at): Query<consensus::AtBlockQuery>,
+                                headers: HeaderMap,
+                                format: ResponseFormat| async move {
+            consensus::get_block(
+                State(state),
+                Path((epoch, round)),
+                Query(query),
+                Query(at),
+                headers,
+                format,
+            )
+        };
+
+        let get_block_by_hash_lambda =
+            |State(state): State<Arc<DkgState>>,
+             Path(hash): Path<HashValue>,
+             Query(query): Query<consensus::BlockQuery>,
+             Query(at): Query<consensus::AtBlockQuery>,
+             format: ResponseFormat| async move {
+                consensus::get_block_by_hash(
+                    State(state),
+                    Path(hash),
+                    Query(query),
+                    Query(at),
+                    format,
+                )
             };
 
         let get_qc_lambda = |State(state): State<Arc<DkgState>>,
-                             Path((epoch, round)): Path<(u64, u64)>| async move {
-            consensus::get_qc(State(state), Path((epoch, round)))
+                             Path((epoch, round)): Path<(u64, u64)>,
+                             Query(at): Query<consensus::AtBlockQuery>,
+                             headers: HeaderMap,
+                             format: ResponseFormat| async move {
+            consensus::get_qc(State(state), Path((epoch, round)), Query(at), headers, format)
         };
 
         let get_validator_count_lambda =
-            |State(state): State<Arc<DkgState>>, Path(epoch): Path<u64>| async move {
-                consensus::get_validator_count_by_epoch(State(state), Path(epoch))
+            |State(state): State<Arc<DkgState>>,
+             Path(epoch): Path<u64>,
+             Query(at): Query<consensus::AtBlockQuery>,
+             format: ResponseFormat| async move {
+                consensus::get_validator_count_by_epoch(
+                    State(state),
+                    Path(epoch),
+                    Query(at),
+                    format,
+                )
+            };
+
+        let get_validator_set_lambda =
+            |State(state): State<Arc<DkgState>>,
+             Path(epoch): Path<u64>,
+             Query(at): Query<consensus::AtBlockQuery>,
+             format: ResponseFormat| async move {
+                consensus::get_validator_set_by_epoch(State(state), Path(epoch), Query(at), format)
+            };
+
+        let get_validator_churn_lambda =
+            |State(state): State<Arc<DkgState>>,
+             Query(query): Query<consensus::ValidatorChurnQuery>,
+             Query(at): Query<consensus::AtBlockQuery>| async move {
+                consensus::get_validator_churn(State(state), Query(query), Query(at))
+            };
+
+        let get_sync_status_lambda = |State(state): State<Arc<DkgState>>| async move {
+            consensus::get_sync_status(State(state))
+        };
+
+        let get_evidence_lambda =
+            |State(state): State<Arc<DkgState>>, Query(query): Query<consensus::EvidenceQuery>| async move {
+                consensus::get_evidence(State(state), Query(query))
+            };
+
+        let get_signature_inclusion_lambda =
+            |State(state): State<Arc<DkgState>>,
+             Path(epoch): Path<u64>,
+             Query(query): Query<consensus::SignatureInclusionQuery>,
+             Query(at): Query<consensus::AtBlockQuery>| async move {
+                consensus::get_signature_inclusion(
+                    State(state),
+                    Path(epoch),
+                    Query(query),
+                    Query(at),
+                )
+            };
+
+        let get_commit_history_lambda =
+            |State(state): State<Arc<DkgState>>,
+             Query(query): Query<consensus::CommitHistoryQuery>| async move {
+                consensus::get_commit_history(State(state), Query(query))
             };
 
+        let get_commit_proof_lambda =
+            |State(state): State<Arc<DkgState>>,
+             Path(params): Path<(u64, u64)>,
+             Query(at): Query<consensus::AtBlockQuery>,
+             format: ResponseFormat| async move {
+                consensus::get_commit_proof(State(state), Path(params), Query(at), format)
+            };
+
+        let get_epoch_change_proof_lambda =
+            |State(state): State<Arc<DkgState>>,
+             Path(epochs): Path<(u64, u64)>,
+             Query(at): Query<consensus::AtBlockQuery>,
+             format: ResponseFormat| async move {
+                consensus::get_epoch_change_proof(State(state), Path(epochs), Query(at), format)
+            };
+
+        let get_commit_lambda = |State(state): State<Arc<DkgState>>,
+                                 Path(block_number): Path<u64>,
+                                 Query(at): Query<consensus::AtBlockQuery>,
+                                 format: ResponseFormat| async move {
+            consensus::get_commit_by_block_number(
+                State(state),
+                Path(block_number),
+                Query(at),
+                format,
+            )
+        };
+
+        // Cross-checks recent commits against the configured peers, if there are any.
+        let consistency_checker = Arc::new(ConsistencyChecker::new(
+            self.config.consistency.peers.clone(),
+            Duration::from_secs(self.config.consistency.interval_secs),
+        ));
+        if let Some(consensus_db) = self.consensus_db.clone() {
+            tokio::spawn(consistency_checker.clone().run(consensus_db));
+        }
+        TX_RESULTS.set_ttl(Duration::from_secs(self.config.tx_cache.ttl_secs));
+        if let Some(consensus_db) = self.consensus_db.clone() {
+            tokio::spawn(TX_RESULTS.follow_unwinds(consensus_db));
+        }
+        // Keeps transaction results across restarts when asked to.
+        if self.config.tx_cache.persist {
+            if let Some(consensus_db) = self.consensus_db.clone() {
+                tokio::spawn(TX_RESULTS.persist(consensus_db));
+            }
+        }
+        let get_consistency_lambda = move || {
+            let report = consistency_checker.report();
+            async move { Json(report) }
+        };
+
+        // The index behind `/tx/list` is only written when enabled.
+        let tx_index_enabled = self.config.tx_index.enabled;
+        let list_txs_lambda = move |State(state): State<Arc<DkgState>>,
+                                    Query(query): Query<tx::TxListQuery>| async move {
+            if !tx_index_enabled {
+                return Err(ApiError::not_implemented(
+                    "The transaction index is disabled, set tx_index.enabled to list transactions",
+                ));
+            }
+            list_txs(State(state), Query(query)).await
+        };
+
+        let rpc_lambda = |State(state): State<Arc<DkgState>>, body: Bytes| async move {
+            rpc::handle_rpc(state, body)
+        };
+
         let dkg_state_arc = Arc::new(dkg_state);
         let has_tls = self.cert_pem.is_some() && self.key_pem.is_some();
 
+        // Public routes are served under /v1, and the ones that predate it also at their legacy
+        // paths unless turned off.
         let https_routes = Router::new()
             .route("/tx/submit_tx", post(submit_tx_lambda))
             .route("/tx/get_tx_by_hash/:hash_value", get(get_tx_by_hash_lambda))
+            .route("/tx/list", get(list_txs_lambda));
+        let https_routes = versioning::versioned(https_routes, &self.config.legacy_paths)
+            .merge(versioning::v1_only(
+                Router::new().route("/tx/submit_batch", post(submit_batch_lambda)),
+            ))
             .layer(middleware::from_fn(ensure_https));
         let http_routes = Router::new()
             .route("/dkg/status", get(get_dkg_status_lambda))
             .route("/dkg/randomness/:block_number", get(get_randomness_lambda))
+            .route("/dkg/transcript/:epoch", get(get_transcript_lambda))
             .route("/consensus/latest_ledger_info", get(get_latest_ledger_info_lambda))
+            .route("/consensus/stream/ledger_info", get(stream_ledger_info_lambda))
             .route("/consensus/ledger_info/:epoch", get(get_ledger_info_by_epoch_lambda))
             .route("/consensus/block/:epoch/:round", get(get_block_lambda))
+            .route("/consensus/block_by_hash/:hash", get(get_block_by_hash_lambda))
             .route("/consensus/qc/:epoch/:round", get(get_qc_lambda))
             .route("/consensus/validator_count/:epoch", get(get_validator_count_lambda))
+            .route("/consensus/validator_set/:epoch", get(get_validator_set_lambda))
+            .route("/consensus/validator_churn", get(get_validator_churn_lambda))
+            .route("/consensus/sync_status", get(get_sync_status_lambda))
+            .route("/consensus/commit/:block_number", get(get_commit_lambda))
+            .route("/consensus/commit_history", get(get_commit_history_lambda))
+            .route("/consensus/commit_proof/:epoch/:round", get(get_commit_proof_lambda))
+            .route(
+                "/consensus/epoch_change_proof/:start_epoch/:end_epoch",
+                get(get_epoch_change_proof_lambda),
+            )
+            .route("/consensus/consistency", get(get_consistency_lambda))
+            .route("/stats/chain", get(stats::get_chain_stats))
+            .route("/jobs", post(submit_job_lambda))
+            .route("/jobs/:id", get(get_job_lambda))
+            .route("/jobs/:id/result", get(get_job_result_lambda))
+            .route("/rpc", post(rpc_lambda))
+            .route("/ws/mempool", get(mempool_ws_lambda));
+        let v1_routes = Router::new()
+            .route("/dkg/params", get(get_dkg_params_lambda))
+            .route("/consensus/evidence", get(get_evidence_lambda))
+            .route("/consensus/signature_inclusion/:epoch", get(get_signature_inclusion_lambda))
+            .route("/ws/blocks", get(blocks_ws_lambda));
+        let v1_routes = if self.config.dkg_decrypt_shares {
+            v1_routes.route("/dkg/decrypt_share", post(decrypt_share_lambda))
+        } else {
+            v1_routes
+        };
+        // Embedder routes are theirs to version.
+        let http_routes = versioning::versioned(http_routes, &self.config.legacy_paths)
+            .merge(versioning::v1_only(v1_routes))
+            .merge(std::mem::take(&mut self.routes).with_state(()));
+
+        // Admin endpoints are restricted to the allowlist when it has entries.
+        let admin_address = self.config.admin.address.clone();
+        let admin_allowlist = Arc::new(AdminAllowlist::new(
+            &self.config.admin.allowlist,
+            Duration::from_secs(self.config.admin.allowlist_refresh_secs),
+        ));
+        if admin_allowlist.is_enabled() {
+            info!("Admin endpoints restricted to: {}", admin_allowlist.describe());
+            admin_allowlist.refresh().await;
+            tokio::spawn(admin_allowlist.clone().run_refresh());
+        }
+        let admin_routes = Router::new()
             .route("/set_failpoint", post(set_fail_point_lambda))
-            .route("/mem_prof", post(control_profiler_lambda));
+            .route("/mem_prof", post(control_profiler_lambda))
+            .route("/mem_prof/dump", get(dump_heap_profile))
+            .route("/mem_prof/flamegraph", get(heap_flamegraph))
+            .route(
+                "/mem_prof/schedule",
+                get(get_heap_dump_schedule)
+                    .post(schedule_heap_dumps_lambda)
+                    .delete(cancel_heap_dump_schedule),
+            )
+            .route("/cpu_prof", post(control_cpu_profiler_lambda))
+            .route("/debug/runtime", get(runtime_metrics::get_runtime_metrics))
+            .route("/admin/logs/tail", get(tail_logs_lambda))
+            .route("/admin/log_level", get(log_level::get_log_level).post(set_log_level_lambda))
+            .route("/admin/route_usage", get(get_route_usage_lambda))
+            .route("/admin/config", get(node_config::get_node_config))
+            .route("/admin/runtime", get(runtime_info::get_runtime_info))
+            .route("/admin/db_snapshot", get(db_snapshot::get_db_snapshot))
+            .route("/admin/consensus_snapshot", post(snapshots::take_snapshot))
+            .route("/admin/tokens", get(list_tokens_lambda).post(create_token_lambda))
+            .route("/admin/tokens/delegated", post(create_delegated_token_lambda))
+            .route("/admin/tokens/:id", delete(revoke_token_lambda))
+            .route("/admin/audit", get(get_audit_log_lambda))
+            .layer(middleware::from_fn_with_state(admin_allowlist, enforce_admin_allowlist))
+            .layer(middleware::from_fn_with_state(audit_log, audit_admin_actions));
+        // Tokens only guard the public routes; admin routes stay reachable without one.
+        let (http_routes, admin_routes) = match &admin_address {
+            Some(_) => {
+                (tokens::guard_public_routes(http_routes, None, tokens.clone()), Some(admin_routes))
+            }
+            None => {
+                (tokens::guard_public_routes(http_routes, Some(admin_routes), tokens.clone()), None)
+            }
+        };
+        // Usage and traces are recorded per matched route pattern, hence `route_layer`.
+        // Tokens are checked inside the usage and tracing layers, so rejections are counted too.
+        let http_routes = http_routes
+            .route_layer(middleware::from_fn_with_state(route_usage.clone(), record_route_usage))
+            .route_layer(middleware::from_fn(otel::trace_request));
+        let https_routes = tokens::guard_public_routes(https_routes, None, tokens)
+            .route_layer(middleware::from_fn_with_state(route_usage.clone(), record_route_usage))
+            .route_layer(middleware::from_fn(otel::trace_request));
+
+        let compression_min_size = self.config.compression_min_size;
+        let finish = |router: Router<Arc<DkgState>>| {
+            router
+                // Inside compression, so the trimmed body is what gets compressed.
+                .layer(middleware::from_fn(fields::filter_fields))
+                .layer(DefaultBodyLimit::max(1_048_576)) // GSDK-011: 1 MB max request body
+                // gzip/brotli negotiated via Accept-Encoding; block and QC payloads can be large.
+                .layer(
+                    CompressionLayer::new().gzip(true).br(true).compress_when(
+                        SizeAbove::new(compression_min_size)
+                            .and(NotForContentType::GRPC)
+                            .and(NotForContentType::IMAGES)
+                            .and(NotForContentType::SSE),
+                    ),
+                )
+                // Outermost, so response bodies are counted as sent.
+                .layer(middleware::from_fn_with_state(route_usage.clone(), account_client_bytes))
+                .with_state(dkg_state_arc.clone())
+        };
 
         // GSDK-013: Only register sensitive https_routes when TLS is configured
-        let app = if has_tls {
+        let app = finish(if has_tls {
             Router::new().merge(https_routes).merge(http_routes)
         } else {
             info!("WARNING: TLS not configured. Consensus/DKG sensitive endpoints are disabled. Only serving public HTTP routes.");
             Router::new().merge(http_routes)
-        }
-        .layer(DefaultBodyLimit::max(1_048_576)) // GSDK-011: 1 MB max request body
-        .with_state(dkg_state_arc);
+        });
+        let app =
+            std::mem::take(&mut self.router_hooks).into_iter().fold(app, |app, hook| hook(app));
 
         let addr: SocketAddr = self
             .address
             .parse()
-            .unwrap_or_else(|e| panic!("Invalid bind address '{}': {e}", self.address)); // GSDK-014
+            .map_err(|e| anyhow::anyhow!("Invalid bind address '{}': {e}", self.address))?; // GSDK-014
+
+        let admin = match (admin_address, admin_routes) {
+            (Some(admin_address), Some(admin_routes)) => {
+                let admin_addr: SocketAddr = admin_address.parse().map_err(|e| {
+                    anyhow::anyhow!("Invalid admin bind address '{admin_address}': {e}")
+                })?;
+                let admin_app = finish(
+                    admin_routes
+                        .route_layer(middleware::from_fn_with_state(
+                            route_usage.clone(),
+                            record_route_usage,
+                        ))
+                        .route_layer(middleware::from_fn(otel::trace_request)),
+                );
+                info!("admin http server listen address {}", admin_addr);
+                Some(
+                    axum_server::bind(admin_addr)
+                        .serve(admin_app.into_make_service_with_connect_info::<SocketAddr>()),
+                )
+            }
+            _ => None,
+        };
+        let admin = async move {
+            match admin {
+                Some(server) => server
+                    .await
+                    .map_err(|e| anyhow::anyhow!("failed to bind admin http due to {e:?}")),
+                None => Ok(()),
+            }
+        };
+
+        let public = self.serve_public(addr, app);
+        tokio::try_join!(public, admin).map(|_| ())
+    }
 
+    async fn serve_public(&self, addr: SocketAddr, app: Router) -> anyhow::Result<()> {
         match (self.cert_pem.clone(), self.key_pem.clone()) {
             (Some(cert_path), Some(key_path)) => {
-                // configure certificate and private key used by https
-                let config =
-                    RustlsConfig::from_pem_file(cert_path, key_path).await.unwrap_or_else(|e| {
-                        panic!(
-                            "error {:?}, cert {:?}, key {:?} doesn't work",
-                            e, self.cert_pem, self.key_pem
-                        )
-                    });
+                // configure certificate, private key and protocol settings used by https
+                let tls = &self.config.tls;
+                runtime_info::register_tls(tls);
+                let config = tls.rustls_config(&cert_path, &key_path).await.map_err(|e| {
+                    anyhow::anyhow!(
+                        "error {:?}, cert {:?}, key {:?} doesn't work",
+                        e,
+                        self.cert_pem,
+                        self.key_pem
+                    )
+                })?;
                 info!("https server listen address {}", addr);
                 axum_server::bind_rustls(addr, config)
-                    .serve(app.into_make_service())
+                    .serve(app.into_make_service_with_connect_info::<SocketAddr>())
                     .await
-                    .unwrap_or_else(|e| {
-                        panic!("failed to bind rustls due to {e:?}");
-                    });
+                    .map_err(|e| anyhow::anyhow!("failed to bind rustls due to {e:?}"))
             }
             _ => {
                 info!("http server listen address {}", addr);
-                axum_server::bind(addr).serve(app.into_make_service()).await.unwrap_or_else(|e| {
-                    panic!("failed to bind http due to {e:?}");
-                });
+                axum_server::bind(addr)
+                    .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+                    .await
+                    .map_err(|e| anyhow::anyhow!("failed to bind http due to {e:?}"))
             }
         }
     }
@@ -163,21 +673,76 @@ pub async fn https_server(
     cert_pem: Option<PathBuf>,
     key_pem: Option<PathBuf>,
     consensus_db: Option<Arc<ConsensusDB>>,
+    tx_submitter: Option<Arc<TxSubmitter>>,
+    log_file: Option<PathBuf>,
+    jobs_dir: Option<PathBuf>,
+    route_usage_file: Option<PathBuf>,
+    config: ApiConfig,
 ) {
-    let server = HttpsServer::new(address, cert_pem, key_pem, consensus_db);
-    server.serve().await;
+    let mut builder = HttpsServer::builder(address).config(config);
+    if let (Some(cert_pem), Some(key_pem)) = (cert_pem, key_pem) {
+        builder = builder.tls_files(cert_pem, key_pem);
+    }
+    if let Some(consensus_db) = consensus_db {
+        builder = builder.consensus_db(consensus_db);
+    }
+    if let Some(tx_submitter) = tx_submitter {
+        builder = builder.tx_submitter(tx_submitter);
+    }
+    if let Some(log_file) = log_file {
+        builder = builder.log_file(log_file);
+    }
+    if let Some(jobs_dir) = jobs_dir {
+        builder = builder.jobs_dir(jobs_dir);
+    }
+    if let Some(route_usage_file) = route_usage_file {
+        builder = builder.route_usage_file(route_usage_file);
+    }
+    let server = builder.build();
+    if let Err(e) = server.serve().await {
+        error!("HTTP API server stopped: {e:?}");
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use block_buffer_manager::TxPool;
     use fail::fail_point;
+    use gaptos::api_types::{account::ExternalAccountAddress, u256_define::TxnHash, VerifiedTxn};
     use rcgen::generate_simple_self_signed;
     use reqwest::ClientBuilder;
-    use std::{collections::HashMap, fs, path::PathBuf};
+    use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
 
     use crate::https::tx::TxResponse;
 
-    use super::https_server;
+    use super::{https_server, tx_hash, ApiConfig, TxSubmitter};
+
+    /// Takes every transaction it is handed.
+    struct AcceptingPool;
+
+    impl TxPool for AcceptingPool {
+        fn best_txns(
+            &self,
+            _filter: Option<Box<dyn Fn((ExternalAccountAddress, u64, TxnHash)) -> bool>>,
+            _limit: usize,
+            _max_bytes: u64,
+        ) -> Box<dyn Iterator<Item = VerifiedTxn>> {
+            Box::new(std::iter::empty())
+        }
+
+        fn get_broadcast_txns(
+            &self,
+            _filter: Option<Box<dyn Fn((ExternalAccountAddress, u64, TxnHash)) -> bool>>,
+        ) -> Box<dyn Iterator<Item = VerifiedTxn>> {
+            Box::new(std::iter::empty())
+        }
+
+        fn add_external_txn(&self, _txn: VerifiedTxn) -> bool {
+            true
+        }
+
+        fn remove_txns(&self, _txns: Vec<VerifiedTxn>) {}
+    }
 
     fn test_fail_point() -> Option<()> {
         fail_point!("unit_test_fail_point", |_| {
@@ -202,7 +767,18 @@ mod test {
         let address = "127.0.0.1:5425".to_owned();
         let cert_pem = Some(PathBuf::from(dir.clone() + "/src/https/test/cert.pem"));
         let key_pem = Some(PathBuf::from(dir.clone() + "/src/https/test/key.pem"));
-        let _handler = tokio::spawn(https_server(address, cert_pem, key_pem, None));
+        let tx_submitter = Arc::new(TxSubmitter::new(Arc::new(AcceptingPool), 1, None));
+        let _handler = tokio::spawn(https_server(
+            address,
+            cert_pem,
+            key_pem,
+            None,
+            Some(tx_submitter),
+            None,
+            None,
+            None,
+            ApiConfig::default(),
+        ));
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
         // read a local binary pem encoded certificate
         let pem = std::fs::read(dir.clone() + "/src/https/test/cert.pem").unwrap();
@@ -240,6 +816,8 @@ mod test {
         map.insert("tx", vec![1, 2, 3, 4]);
         let res =
             client.post("https://127.0.0.1:5425/tx/submit_tx").json(&map).send().await.unwrap();
-        assert!(res.status().is_success());
+        assert!(res.status().is_success(), "res is {res:?}");
+        let body = res.json::<serde_json::Value>().await.unwrap();
+        assert_eq!(body["hash"], serde_json::json!(tx_hash(&[1, 2, 3, 4]).to_vec()));
     }
 }
@@ -0,0 +1,156 @@
+//! Response format negotiation for the consensus read endpoints.
+//!
+//! JSON summaries lose the byte-exact form of ledger infos, certificates and blocks. A client
+//! sending `Accept: application/x-bcs` gets the canonical BCS bytes of the underlying structure
+//! instead, so it can verify signatures without a JSON round trip. Clients that need both, e.g.
+//! to show a ledger info and check its signatures, pass `?canonical=true` and get the JSON with
+//! the base64 BCS bytes next to it. Errors are always JSON.
+
+use crate::https::error::ApiError;
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{header, request::Parts, HeaderValue},
+    response::{IntoResponse, Json as JsonResponse, Response},
+};
+use gaptos::aptos_logger::error;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+
+pub const BCS_CONTENT_TYPE: &str = "application/x-bcs";
+
+/// Body format picked from the request's `Accept` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    Bcs,
+    /// JSON with the BCS bytes alongside, from `?canonical=true`.
+    Canonical,
+}
+
+#[derive(Deserialize, Default)]
+struct CanonicalQuery {
+    #[serde(default)]
+    canonical: bool,
+}
+
+/// Body of a `?canonical=true` response.
+#[derive(Serialize)]
+struct CanonicalResponse<J> {
+    json: J,
+    /// Base64 of the exact BCS bytes that were signed or stored.
+    bcs: String,
+}
+
+impl ResponseFormat {
+    /// BCS when `application/x-bcs` is accepted with at least the quality of JSON, so
+    /// `Accept: application/x-bcs, application/json;q=0.5` gets BCS and browsers keep JSON.
+    pub fn from_accept(accept: &str) -> Self {
+        let (mut bcs, mut json) = (0.0f32, 0.0f32);
+        for range in accept.split(',') {
+            let mut params = range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default().to_ascii_lowercase();
+            let quality = params
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            match media_type.as_str() {
+                BCS_CONTENT_TYPE => bcs = bcs.max(quality),
+                "application/json" | "application/*" | "*/*" => json = json.max(quality),
+                _ => {}
+            }
+        }
+        if bcs > 0.0 && bcs >= json {
+            ResponseFormat::Bcs
+        } else {
+            ResponseFormat::Json
+        }
+    }
+
+    /// Renders `json`, or the BCS encoding of `canonical`.
+    pub fn render<J, C>(self, json: J, canonical: &C) -> Result<Response, ApiError>
+    where
+        J: Serialize,
+        C: Serialize + ?Sized,
+    {
+        match self {
+            ResponseFormat::Json => Ok(self.render_bytes(json, Vec::new())),
+            ResponseFormat::Bcs | ResponseFormat::Canonical => {
+                let bytes = bcs::to_bytes(canonical).map_err(|e| {
+                    error!("Failed to serialize BCS response: {:?}", e);
+                    ApiError::internal()
+                })?;
+                Ok(self.render_bytes(json, bytes))
+            }
+        }
+    }
+
+    /// Renders `json`, or `bcs_bytes` as they are, for structures already stored as BCS.
+    pub fn render_bytes<J: Serialize>(self, json: J, bcs_bytes: Vec<u8>) -> Response {
+        let mut response = match self {
+            ResponseFormat::Json => JsonResponse(json).into_response(),
+            ResponseFormat::Canonical => {
+                JsonResponse(CanonicalResponse { json, bcs: base64::encode(bcs_bytes) })
+                    .into_response()
+            }
+            ResponseFormat::Bcs => {
+                ([(header::CONTENT_TYPE, HeaderValue::from_static(BCS_CONTENT_TYPE))], bcs_bytes)
+                    .into_response()
+            }
+        };
+        // The body depends on Accept, so caches must key on it.
+        response.headers_mut().insert(header::VARY, HeaderValue::from_static("accept"));
+        response
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query: CanonicalQuery =
+            Query::try_from_uri(&parts.uri).map(|Query(query)| query).unwrap_or_default();
+        if query.canonical {
+            return Ok(ResponseFormat::Canonical);
+        }
+        Ok(parts
+            .headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .map(ResponseFormat::from_accept)
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_from_accept_header() {
+        assert_eq!(ResponseFormat::from_accept("application/x-bcs"), ResponseFormat::Bcs);
+        assert_eq!(
+            ResponseFormat::from_accept("application/x-bcs, application/json;q=0.5"),
+            ResponseFormat::Bcs
+        );
+        assert_eq!(
+            ResponseFormat::from_accept("application/json, application/x-bcs;q=0.5"),
+            ResponseFormat::Json
+        );
+        assert_eq!(ResponseFormat::from_accept("application/x-bcs;q=0"), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_accept("*/*"), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_accept("text/html"), ResponseFormat::Json);
+    }
+
+    #[test]
+    fn canonical_puts_bcs_next_to_json() {
+        let response =
+            ResponseFormat::Canonical.render(serde_json::json!({"epoch": 3}), &3u64).unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let Query(query): Query<CanonicalQuery> =
+            Query::try_from_uri(&"/consensus/qc/1/2?canonical=true".parse().unwrap()).unwrap();
+        assert!(query.canonical);
+    }
+}
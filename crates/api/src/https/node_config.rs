@@ -0,0 +1,88 @@
+use crate::https::error::ApiError;
+use axum::response::Json as JsonResponse;
+use gaptos::{
+    aptos_config::config::NodeConfig,
+    aptos_logger::{error, warn},
+};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Placeholder replacing secrets in the config served by `/admin/config`.
+pub const REDACTED: &str = "<redacted>";
+
+/// Field names whose values are never served, e.g. the inline identity key of a validator.
+const SECRET_FIELDS: [&str; 5] = ["key", "private_key", "secret", "password", "token"];
+
+static NODE_CONFIG: OnceCell<Value> = OnceCell::new();
+
+/// Registers the config the node was started with, so `/admin/config` can serve it.
+pub(crate) fn register_node_config(node_config: &NodeConfig) {
+    match serde_json::to_value(node_config) {
+        Ok(mut value) => {
+            redact(&mut value);
+            let _ = NODE_CONFIG.set(value);
+        }
+        Err(e) => warn!("/admin/config disabled, failed to serialize the node config: {:?}", e),
+    }
+}
+
+/// Replaces the value of every field named like a secret with [`REDACTED`].
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                let name = name.to_ascii_lowercase();
+                if SECRET_FIELDS
+                    .iter()
+                    .any(|secret| name == *secret || name.ends_with(&format!("_{secret}")))
+                {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct NodeConfigResponse {
+    /// The effective node config, defaults included, with secrets redacted.
+    pub config: Value,
+}
+
+// example:
+// curl http://127.0.0.1:1024/admin/config
+pub async fn get_node_config() -> Result<JsonResponse<NodeConfigResponse>, ApiError> {
+    let config = NODE_CONFIG.get().ok_or_else(|| {
+        error!("Node config is not registered");
+        ApiError::not_found("The node config is not available")
+    })?;
+    Ok(JsonResponse(NodeConfigResponse { config: config.clone() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_secret_fields_only() {
+        let mut config = json!({
+            "validator_network": { "identity": { "type": "from_config", "key": "abcd", "peer_id": "01" } },
+            "auth_token": "t",
+            "keys_dir": "/opt/keys",
+            "seeds": [{ "private_key": "x", "addr": "y" }],
+        });
+        redact(&mut config);
+        assert_eq!(config["validator_network"]["identity"]["key"], REDACTED);
+        assert_eq!(config["validator_network"]["identity"]["peer_id"], "01");
+        assert_eq!(config["auth_token"], REDACTED);
+        assert_eq!(config["keys_dir"], "/opt/keys");
+        assert_eq!(config["seeds"][0]["private_key"], REDACTED);
+        assert_eq!(config["seeds"][0]["addr"], "y");
+    }
+}
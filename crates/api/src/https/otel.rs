@@ -0,0 +1,125 @@
+//! OpenTelemetry tracing of API requests.
+//!
+//! Every matched request gets a server span with its route, status and latency, parented to the
+//! W3C `traceparent` sent by the caller (e.g. the load balancer), and consensus DB reads made
+//! while handling it are recorded as child spans. Spans are exported over OTLP/HTTP when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` (or `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`) is set; otherwise the
+//! tracer is a no-op.
+
+use axum::{
+    body::Body,
+    extract::MatchedPath,
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use gaptos::aptos_logger::{error, info};
+use opentelemetry::{
+    global,
+    propagation::Extractor,
+    trace::{FutureExt, Span, SpanKind, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator, runtime, trace::TracerProvider, Resource,
+};
+use std::time::Instant;
+
+const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+const OTLP_TRACES_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+const SERVICE_NAME_ENV: &str = "OTEL_SERVICE_NAME";
+const DEFAULT_SERVICE_NAME: &str = "gravity-node";
+const TRACER_NAME: &str = "gravity-api";
+
+/// Installs the OTLP exporter and the W3C trace context propagator if an OTLP endpoint is
+/// configured. Returns whether tracing is enabled.
+pub fn init_from_env() -> bool {
+    let endpoint =
+        std::env::var(OTLP_TRACES_ENDPOINT_ENV).or_else(|_| std::env::var(OTLP_ENDPOINT_ENV));
+    let Ok(endpoint) = endpoint else { return false };
+
+    // The exporter reads the endpoint, headers and timeout from the standard OTEL_* variables.
+    let exporter = match opentelemetry_otlp::SpanExporter::builder().with_http().build() {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            error!("API tracing disabled, failed to build the OTLP exporter: {:?}", e);
+            return false;
+        }
+    };
+    let service_name =
+        std::env::var(SERVICE_NAME_ENV).unwrap_or_else(|_| DEFAULT_SERVICE_NAME.to_string());
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", service_name)]))
+        .build();
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    global::set_tracer_provider(provider);
+    info!("API request tracing exported to {}", endpoint);
+    true
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Wraps the request in a server span. Must be added with `route_layer` so the matched route is
+/// known, which keeps span names low-cardinality.
+pub async fn trace_request(req: Request<Body>, next: Next) -> Response {
+    let parent = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(req.headers()))
+    });
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| req.uri().path().to_string(), |route| route.as_str().to_string());
+
+    let tracer = global::tracer(TRACER_NAME);
+    let span = tracer
+        .span_builder(format!("{method} {route}"))
+        .with_kind(SpanKind::Server)
+        .with_attributes([
+            KeyValue::new("http.request.method", method),
+            KeyValue::new("http.route", route),
+            KeyValue::new("url.path", req.uri().path().to_string()),
+        ])
+        .start_with_context(&tracer, &parent);
+    let cx = parent.with_span(span);
+
+    let started = Instant::now();
+    let response = next.run(req).with_context(cx.clone()).await;
+
+    let span = cx.span();
+    let status = response.status();
+    span.set_attribute(KeyValue::new("http.response.status_code", status.as_u16() as i64));
+    span.set_attribute(KeyValue::new(
+        "http.server.latency_ms",
+        started.elapsed().as_secs_f64() * 1000.0,
+    ));
+    if status.is_server_error() {
+        span.set_status(Status::error(status.to_string()));
+    }
+    span.end();
+    response
+}
+
+/// Runs a consensus DB read in a child span of the current request span. Outside of a traced
+/// request the read runs as is, so background tasks don't emit root spans per read.
+pub(crate) fn db_span<T>(operation: &'static str, read: impl FnOnce() -> T) -> T {
+    if !Context::current().has_active_span() {
+        return read();
+    }
+    let tracer = global::tracer(TRACER_NAME);
+    let mut span = tracer.start(operation);
+    span.set_attribute(KeyValue::new("db.system", "rocksdb"));
+    let _guard = Context::current_with_span(span).attach();
+    read()
+}
@@ -0,0 +1,141 @@
+//! Caching of immutable consensus data.
+//!
+//! Blocks and QCs of committed rounds never change, so repeated reads of them, e.g. by explorer
+//! backfills, are served from an in-process LRU cache instead of scanning the epoch in RocksDB
+//! again. Their responses carry a strong `ETag` and an immutable `Cache-Control`, and a request
+//! whose `If-None-Match` matches gets `304 Not Modified`. Rounds that are not committed yet are
+//! neither cached nor marked cacheable, as a fork may still replace them.
+
+use crate::https::{error::ApiError, negotiate::ResponseFormat, otel::db_span};
+use aptos_consensus::consensusdb::{BlockHeader, ConsensusDB, RawBlock};
+use aptos_consensus_types::quorum_cert::QuorumCert;
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use gaptos::{aptos_crypto::HashValue, aptos_infallible::Mutex, aptos_storage_interface::DbReader};
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+/// Entries kept per cache. Blocks carry their payload, so fewer of them are kept.
+const BLOCK_CACHE_CAPACITY: usize = 1024;
+const QC_CACHE_CAPACITY: usize = 4096;
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// A committed block as read from the consensus DB.
+pub(crate) struct CachedBlock {
+    pub block_id: HashValue,
+    pub header: BlockHeader,
+    pub raw_block: RawBlock,
+    pub block_number: Option<u64>,
+}
+
+/// A QC certifying a committed block.
+pub(crate) struct CachedQc {
+    pub qc: QuorumCert,
+    pub block_number: Option<u64>,
+}
+
+static BLOCKS: Lazy<Mutex<LruCache<(u64, u64), Arc<CachedBlock>>>> =
+    Lazy::new(|| Mutex::new(LruCache::new(BLOCK_CACHE_CAPACITY)));
+static QCS: Lazy<Mutex<LruCache<(u64, u64), Arc<CachedQc>>>> =
+    Lazy::new(|| Mutex::new(LruCache::new(QC_CACHE_CAPACITY)));
+
+/// The cached block of `epoch` at `round`.
+pub(crate) fn block(epoch: u64, round: u64) -> Option<Arc<CachedBlock>> {
+    BLOCKS.lock().get(&(epoch, round)).cloned()
+}
+
+/// Caches the committed block of `epoch` at `round`.
+pub(crate) fn insert_block(epoch: u64, round: u64, block: Arc<CachedBlock>) {
+    BLOCKS.lock().put((epoch, round), block);
+}
+
+/// The cached QC certifying the block of `epoch` at `round`.
+pub(crate) fn qc(epoch: u64, round: u64) -> Option<Arc<CachedQc>> {
+    QCS.lock().get(&(epoch, round)).cloned()
+}
+
+/// Caches the QC certifying the committed block of `epoch` at `round`.
+pub(crate) fn insert_qc(epoch: u64, round: u64, qc: Arc<CachedQc>) {
+    QCS.lock().put((epoch, round), qc);
+}
+
+/// Whether `block_number` is at or below the latest committed block.
+pub(crate) fn is_committed(consensus_db: &ConsensusDB, block_number: Option<u64>) -> bool {
+    let Some(block_number) = block_number else { return false };
+    db_span("consensus_db.get_latest_ledger_info", || {
+        DbReader::get_latest_ledger_info(consensus_db)
+    })
+    .is_ok_and(|latest| block_number <= latest.ledger_info().block_number())
+}
+
+/// Strong ETag of a `kind` of data identified by `id`. Every representation of the data gets
+/// its own tag, as the body differs between them.
+pub(crate) fn etag(kind: &str, id: HashValue, format: ResponseFormat, variant: &str) -> String {
+    let format = match format {
+        ResponseFormat::Json => "json",
+        ResponseFormat::Bcs => "bcs",
+        ResponseFormat::Canonical => "canonical",
+    };
+    format!("\"{kind}-{}-{format}{variant}\"", hex::encode(id.as_ref()))
+}
+
+/// Whether `If-None-Match` lists `etag`, or is `*`.
+fn not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers.get_all(header::IF_NONE_MATCH).iter().filter_map(|value| value.to_str().ok()).any(
+        |value| {
+            value.split(',').map(str::trim).any(|tag| {
+                // Weak comparison, as required for If-None-Match.
+                tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag
+            })
+        },
+    )
+}
+
+/// Responds with immutable data tagged `etag`: `304 Not Modified` when the client already has
+/// it, otherwise the rendered response with caching headers.
+pub(crate) fn immutable(
+    headers: &HeaderMap,
+    etag: String,
+    render: impl FnOnce() -> Result<Response, ApiError>,
+) -> Result<Response, ApiError> {
+    let Ok(etag_value) = HeaderValue::from_str(&etag) else { return render() };
+    let mut response = if not_modified(headers, &etag) {
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        response.headers_mut().insert(header::VARY, HeaderValue::from_static("accept"));
+        response
+    } else {
+        render()?
+    };
+    let headers = response.headers_mut();
+    headers.insert(header::ETAG, etag_value);
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(IMMUTABLE_CACHE_CONTROL));
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_if_none_match() {
+        let etag = etag("block", HashValue::zero(), ResponseFormat::Json, "");
+        let mut headers = HeaderMap::new();
+        assert!(!not_modified(&headers, &etag));
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"x\", W/{etag}")).unwrap(),
+        );
+        assert!(not_modified(&headers, &etag));
+        assert!(!not_modified(
+            &headers,
+            &super::etag("block", HashValue::zero(), ResponseFormat::Bcs, "")
+        ));
+
+        let response = immutable(&headers, etag.clone(), || unreachable!()).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+    }
+}
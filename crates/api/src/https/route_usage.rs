@@ -0,0 +1,441 @@
+//! Per-route usage counters, to know who still calls an endpoint before deprecating or changing
+//! it.
+//!
+//! Every matched route records its call count, the distinct clients calling it and when it was
+//! last used. Clients are identified by the `X-Client-Id` header when they send one, otherwise by
+//! their address. The counters are saved to a file periodically and reloaded on restart.
+//!
+//! Each client's request and response body bytes are counted too, so heavy users of large
+//! responses (block ranges, snapshots) can be told apart from chatty ones. Response bytes are
+//! counted as sent, i.e. after compression.
+
+use crate::https::jobs::{now_ms, write_atomically};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::{Json as JsonResponse, Response},
+};
+use bytes::Bytes;
+use gaptos::{
+    aptos_infallible::Mutex,
+    aptos_logger::{error, info, warn},
+    aptos_metrics_core::{register_int_counter_vec, IntCounterVec},
+};
+use http_body::{Frame, SizeHint};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::Write,
+    net::SocketAddr,
+    path::PathBuf,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+/// Header a client can send to be counted under a stable name instead of its address.
+pub const CLIENT_ID_HEADER: &str = "x-client-id";
+const PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+/// Distinct clients remembered per route; more are counted in `clients_dropped`.
+const MAX_CLIENTS_PER_ROUTE: usize = 1000;
+const MAX_CLIENT_ID_LEN: usize = 128;
+/// Distinct clients with their own byte counters; later ones are added up under
+/// `OVERFLOW_CLIENT`.
+const MAX_CLIENTS: usize = 10_000;
+const OVERFLOW_CLIENT: &str = "(other)";
+/// Metric label of clients without an `X-Client-Id`, so addresses don't become label values.
+const ANONYMOUS_CLIENT: &str = "anonymous";
+
+static API_CLIENT_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "gravity_api_client_bytes_total",
+        "Request and response body bytes of API clients, by X-Client-Id",
+        &["client", "direction"]
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Request bodies received.
+    Ingress,
+    /// Response bodies sent.
+    Egress,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Ingress => "ingress",
+            Direction::Egress => "egress",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteStats {
+    pub calls: u64,
+    /// Unix timestamps in milliseconds.
+    pub first_used_ms: u64,
+    pub last_used_ms: u64,
+    pub clients: BTreeSet<String>,
+    /// Calls from clients not remembered because the route already had too many.
+    #[serde(default)]
+    pub clients_dropped: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientStats {
+    pub requests: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    /// Unix timestamp in milliseconds.
+    pub last_used_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct UsageFile {
+    since_ms: u64,
+    routes: BTreeMap<String, RouteStats>,
+    #[serde(default)]
+    clients: BTreeMap<String, ClientStats>,
+}
+
+impl UsageFile {
+    fn client_mut(&mut self, client: &str) -> &mut ClientStats {
+        let client = if self.clients.contains_key(client) || self.clients.len() < MAX_CLIENTS {
+            client
+        } else {
+            OVERFLOW_CLIENT
+        };
+        self.clients.entry(client.to_string()).or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RouteUsageResponse {
+    /// When counting started, possibly before earlier restarts.
+    pub since_ms: u64,
+    pub routes: Vec<RouteUsageEntry>,
+    /// Body bytes per client, heaviest egress first.
+    pub clients: Vec<ClientUsageEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClientUsageEntry {
+    pub client: String,
+    #[serde(flatten)]
+    pub stats: ClientStats,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RouteUsageEntry {
+    /// Route pattern, e.g. `/tx/get_tx_by_hash/:hash_value`.
+    pub route: String,
+    pub distinct_clients: usize,
+    #[serde(flatten)]
+    pub stats: RouteStats,
+}
+
+pub struct RouteUsage {
+    /// File the counters are saved to; without one they only live until the node stops.
+    path: Option<PathBuf>,
+    usage: Mutex<UsageFile>,
+    dirty: AtomicBool,
+}
+
+impl RouteUsage {
+    /// Reloads the counters saved at `path`, starting afresh if there are none.
+    pub fn open(path: Option<PathBuf>) -> Self {
+        let usage = path
+            .as_ref()
+            .filter(|path| path.exists())
+            .and_then(|path| {
+                match fs::read(path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|bytes| Ok(serde_json::from_slice::<UsageFile>(&bytes)?))
+                {
+                    Ok(usage) => Some(usage),
+                    Err(e) => {
+                        warn!("Ignoring unreadable route usage file {}: {:?}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .unwrap_or_else(|| UsageFile { since_ms: now_ms(), ..Default::default() });
+        if let Some(path) = &path {
+            info!("Route usage is saved to {}", path.display());
+        }
+        Self { path, usage: Mutex::new(usage), dirty: AtomicBool::new(false) }
+    }
+
+    pub fn record(&self, route: &str, client: &str, now_ms: u64) {
+        let mut usage = self.usage.lock();
+        let stats = usage
+            .routes
+            .entry(route.to_string())
+            .or_insert_with(|| RouteStats { first_used_ms: now_ms, ..Default::default() });
+        stats.calls += 1;
+        stats.last_used_ms = now_ms;
+        if !stats.clients.contains(client) {
+            if stats.clients.len() < MAX_CLIENTS_PER_ROUTE {
+                stats.clients.insert(client.to_string());
+            } else {
+                stats.clients_dropped += 1;
+            }
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Counts a request of `client`, whose body bytes are then added with `record_bytes`.
+    pub fn record_request(&self, client: &str, now_ms: u64) {
+        let mut usage = self.usage.lock();
+        let stats = usage.client_mut(client);
+        stats.requests += 1;
+        stats.last_used_ms = now_ms;
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    pub fn record_bytes(&self, client: &str, direction: Direction, bytes: u64) {
+        let mut usage = self.usage.lock();
+        let stats = usage.client_mut(client);
+        match direction {
+            Direction::Ingress => stats.bytes_in += bytes,
+            Direction::Egress => stats.bytes_out += bytes,
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Label of `client` in metrics: its `X-Client-Id` while it has its own counters.
+    fn metric_label(&self, client: &str, identified: bool) -> String {
+        if !identified {
+            ANONYMOUS_CLIENT.to_string()
+        } else if self.usage.lock().clients.contains_key(client) {
+            client.to_string()
+        } else {
+            OVERFLOW_CLIENT.to_string()
+        }
+    }
+
+    pub fn report(&self) -> RouteUsageResponse {
+        let usage = self.usage.lock();
+        let mut clients: Vec<_> = usage
+            .clients
+            .iter()
+            .map(|(client, stats)| ClientUsageEntry {
+                client: client.clone(),
+                stats: stats.clone(),
+            })
+            .collect();
+        clients.sort_by(|a, b| b.stats.bytes_out.cmp(&a.stats.bytes_out));
+        RouteUsageResponse {
+            since_ms: usage.since_ms,
+            routes: usage
+                .routes
+                .iter()
+                .map(|(route, stats)| RouteUsageEntry {
+                    route: route.clone(),
+                    distinct_clients: stats.clients.len(),
+                    stats: stats.clone(),
+                })
+                .collect(),
+            clients,
+        }
+    }
+
+    /// Saves the counters if they changed since the last save.
+    pub fn persist(&self) {
+        let Some(path) = &self.path else { return };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        let written = serde_json::to_vec(&*self.usage.lock())
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| write_atomically(path, |file| Ok(file.write_all(&bytes)?)));
+        if let Err(e) = written {
+            self.dirty.store(true, Ordering::Relaxed);
+            error!("Failed to save route usage to {}: {:?}", path.display(), e);
+        }
+    }
+
+    /// Saves the counters every minute, so at most a minute of usage is lost on a restart.
+    pub async fn run_persist(self: Arc<Self>) {
+        if self.path.is_none() {
+            return;
+        }
+        let mut ticker = tokio::time::interval(PERSIST_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let usage = self.clone();
+            let _ = tokio::task::spawn_blocking(move || usage.persist()).await;
+        }
+    }
+}
+
+/// Counts the request against its route pattern. Must be added with `route_layer` so the
+/// matched route is known.
+pub async fn record_route_usage(
+    State(usage): State<Arc<RouteUsage>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if let Some(route) = req.extensions().get::<MatchedPath>() {
+        let (client, _) = client_of(&req, remote);
+        usage.record(route.as_str(), &client, now_ms());
+    }
+    next.run(req).await
+}
+
+/// The client's `X-Client-Id`, or else its address, and whether it sent an id.
+fn client_of(req: &Request<Body>, remote: SocketAddr) -> (String, bool) {
+    req.headers()
+        .get(CLIENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_CLIENT_ID_LEN)
+        .map(|id| (id.to_string(), true))
+        .unwrap_or_else(|| (remote.ip().to_canonical().to_string(), false))
+}
+
+/// Counts the request and response body bytes of the client. Added outside the compression
+/// layer so responses are counted as sent; unmatched requests count too, as they use bandwidth
+/// all the same.
+pub async fn account_client_bytes(
+    State(usage): State<Arc<RouteUsage>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (client, identified) = client_of(&req, remote);
+    usage.record_request(&client, now_ms());
+    let meter =
+        Arc::new(ByteMeter { label: usage.metric_label(&client, identified), client, usage });
+
+    let ingress = meter.clone();
+    let req = req
+        .map(|body| count_body_bytes(body, move |bytes| ingress.record(Direction::Ingress, bytes)));
+    next.run(req)
+        .await
+        .map(|body| count_body_bytes(body, move |bytes| meter.record(Direction::Egress, bytes)))
+}
+
+struct ByteMeter {
+    usage: Arc<RouteUsage>,
+    client: String,
+    label: String,
+}
+
+impl ByteMeter {
+    fn record(&self, direction: Direction, bytes: u64) {
+        self.usage.record_bytes(&self.client, direction, bytes);
+        API_CLIENT_BYTES.with_label_values(&[&self.label, direction.as_str()]).inc_by(bytes);
+    }
+}
+
+/// Wraps `body` to pass its frames through while calling `count` with the data bytes of each.
+pub(crate) fn count_body_bytes(body: Body, count: impl Fn(u64) + Send + Sync + 'static) -> Body {
+    Body::new(CountingBody { inner: body, count: Box::new(count) })
+}
+
+struct CountingBody {
+    inner: Body,
+    count: Box<dyn Fn(u64) + Send + Sync>,
+}
+
+impl http_body::Body for CountingBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let frame = ready!(Pin::new(&mut this.inner).poll_frame(cx));
+        if let Some(data) = frame.as_ref().and_then(|f| f.as_ref().ok()).and_then(Frame::data_ref) {
+            (this.count)(data.len() as u64);
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// example:
+// curl http://127.0.0.1:1024/admin/route_usage
+pub async fn get_route_usage(usage: Arc<RouteUsage>) -> JsonResponse<RouteUsageResponse> {
+    JsonResponse(usage.report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_calls_and_distinct_clients() {
+        let usage = RouteUsage::open(None);
+        usage.record("/tx/get_tx_by_hash/:hash_value", "10.0.0.1", 5);
+        usage.record("/tx/get_tx_by_hash/:hash_value", "indexer", 7);
+        usage.record("/tx/get_tx_by_hash/:hash_value", "10.0.0.1", 9);
+        usage.record("/dkg/status", "10.0.0.2", 8);
+
+        let report = usage.report();
+        assert_eq!(report.routes.len(), 2);
+        let tx = report.routes.iter().find(|r| r.route.starts_with("/tx")).unwrap();
+        assert_eq!(tx.stats.calls, 3);
+        assert_eq!(tx.distinct_clients, 2);
+        assert_eq!((tx.stats.first_used_ms, tx.stats.last_used_ms), (5, 9));
+    }
+
+    #[test]
+    fn accounts_bytes_per_client() {
+        let usage = RouteUsage::open(None);
+        usage.record_request("indexer", 1);
+        usage.record_bytes("indexer", Direction::Ingress, 100);
+        usage.record_bytes("indexer", Direction::Egress, 5000);
+        usage.record_request("10.0.0.1", 2);
+        usage.record_bytes("10.0.0.1", Direction::Egress, 20);
+
+        let report = usage.report();
+        assert_eq!(report.clients[0].client, "indexer");
+        assert_eq!(report.clients[0].stats.requests, 1);
+        assert_eq!(
+            (report.clients[0].stats.bytes_in, report.clients[0].stats.bytes_out),
+            (100, 5000)
+        );
+        assert_eq!(report.clients[1].stats.bytes_out, 20);
+    }
+
+    #[test]
+    fn reloads_saved_usage() {
+        let path =
+            std::env::temp_dir().join(format!("gravity-route-usage-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let usage = RouteUsage::open(Some(path.clone()));
+        usage.record("/dkg/status", "10.0.0.2", 8);
+        usage.persist();
+
+        let reloaded = RouteUsage::open(Some(path.clone()));
+        let report = reloaded.report();
+        assert_eq!(report.since_ms, usage.report().since_ms);
+        assert_eq!(report.routes[0].route, "/dkg/status");
+        assert_eq!(report.routes[0].stats.calls, 1);
+        let _ = fs::remove_file(&path);
+    }
+}
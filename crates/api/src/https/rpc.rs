@@ -0,0 +1,289 @@
+//! JSON-RPC 2.0 endpoint exposing the consensus and DKG queries of the HTTP API.
+//!
+//! Example:
+//! curl -X POST -H "Content-Type:application/json" \
+//!   -d '{"jsonrpc":"2.0","id":1,"method":"consensus_getBlock","params":[1, 10]}' \
+//!   http://127.0.0.1:1024/rpc
+//!
+//! Params may be positional (`[epoch, round]`) or named (`{"epoch":1,"round":10}`). Batches are
+//! supported by sending an array of requests.
+
+use crate::https::{
+    consensus::{get_block_by_id, get_block_by_round, get_qc_by_round, LedgerInfoResponse},
+    dkg::{DkgState, RandomnessResponse},
+};
+use aptos_consensus::consensusdb::ConsensusDB;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json as JsonResponse, Response},
+};
+use bytes::Bytes;
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_logger::{error, info},
+    aptos_storage_interface::DbReader,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Upper bound on the number of calls in one batch request.
+const MAX_BATCH_SIZE: usize = 100;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Deserialize, Debug)]
+struct RpcRequest {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    /// Absent for notifications, which get no response. An explicit `null` id is `Some(Null)`
+    /// and is answered like any other id.
+    #[serde(default, deserialize_with = "present")]
+    id: Option<Value>,
+}
+
+/// Deserializes a field that is present, even as `null`, into `Some`; `default` leaves it `None`
+/// when the field is missing.
+fn present<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+#[derive(Serialize, Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RpcResponse {
+    pub jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    pub id: Value,
+}
+
+impl RpcResponse {
+    fn result(id: Value, result: Value) -> Self {
+        Self { jsonrpc: "2.0", result: Some(result), error: None, id }
+    }
+
+    fn error(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(RpcError { code, message: message.into() }),
+            id,
+        }
+    }
+}
+
+/// Handles a single or batch JSON-RPC 2.0 request posted to `/rpc`.
+pub fn handle_rpc(dkg_state: Arc<DkgState>, body: Bytes) -> Response {
+    let request: Value = match serde_json::from_slice(&body) {
+        Ok(value) => value,
+        Err(e) => {
+            return JsonResponse(RpcResponse::error(Value::Null, PARSE_ERROR, e.to_string()))
+                .into_response();
+        }
+    };
+
+    match request {
+        Value::Array(calls) => {
+            if calls.is_empty() {
+                return JsonResponse(RpcResponse::error(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    "Empty batch",
+                ))
+                .into_response();
+            }
+            if calls.len() > MAX_BATCH_SIZE {
+                return JsonResponse(RpcResponse::error(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    format!("Batch exceeds {MAX_BATCH_SIZE} calls"),
+                ))
+                .into_response();
+            }
+            let responses: Vec<RpcResponse> =
+                calls.into_iter().filter_map(|call| handle_call(&dkg_state, call)).collect();
+            if responses.is_empty() {
+                // A batch of notifications only
+                StatusCode::NO_CONTENT.into_response()
+            } else {
+                JsonResponse(responses).into_response()
+            }
+        }
+        call => match handle_call(&dkg_state, call) {
+            Some(response) => JsonResponse(response).into_response(),
+            None => StatusCode::NO_CONTENT.into_response(),
+        },
+    }
+}
+
+fn handle_call(dkg_state: &DkgState, call: Value) -> Option<RpcResponse> {
+    let request: RpcRequest = match serde_json::from_value(call) {
+        Ok(request) => request,
+        Err(e) => return Some(RpcResponse::error(Value::Null, INVALID_REQUEST, e.to_string())),
+    };
+    let id = request.id.clone()?;
+    if request.jsonrpc != "2.0" {
+        return Some(RpcResponse::error(id, INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+    }
+    info!("JSON-RPC call {}", request.method);
+
+    let consensus_db = match dkg_state.consensus_db() {
+        Some(db) => db,
+        None => {
+            error!("ConsensusDB is not initialized");
+            return Some(RpcResponse::error(id, INTERNAL_ERROR, "ConsensusDB is not initialized"));
+        }
+    };
+
+    let outcome = match request.method.as_str() {
+        "consensus_getLatestLedgerInfo" => latest_ledger_info(consensus_db),
+        "consensus_getBlock" => epoch_and_round(&request.params).map(|(epoch, round)| {
+            serde_json::to_value(get_block_by_round(consensus_db, epoch, round, false))
+                .unwrap_or(Value::Null)
+        }),
+        "consensus_getBlockByHash" => hash_param(&request.params, 0, "hash")
+            .and_then(|hash| block_by_hash(consensus_db, hash)),
+        "consensus_getQc" => epoch_and_round(&request.params).map(|(epoch, round)| {
+            serde_json::to_value(get_qc_by_round(consensus_db, epoch, round)).unwrap_or(Value::Null)
+        }),
+        "dkg_getRandomness" => u64_param(&request.params, 0, "block_number")
+            .and_then(|block_number| randomness(consensus_db, block_number)),
+        method => Err((METHOD_NOT_FOUND, format!("Method not found: {method}"))),
+    };
+
+    Some(match outcome {
+        Ok(result) => RpcResponse::result(id, result),
+        Err((code, message)) => RpcResponse::error(id, code, message),
+    })
+}
+
+type CallResult = Result<Value, (i64, String)>;
+
+fn latest_ledger_info(consensus_db: &ConsensusDB) -> CallResult {
+    let info = DbReader::get_latest_ledger_info(consensus_db).map_err(|e| {
+        error!("Failed to get latest ledger info: {:?}", e);
+        (INTERNAL_ERROR, "Internal server error".to_string())
+    })?;
+    serde_json::to_value(LedgerInfoResponse::from(&info))
+        .map_err(|e| (INTERNAL_ERROR, e.to_string()))
+}
+
+fn randomness(consensus_db: &ConsensusDB, block_number: u64) -> CallResult {
+    let randomness = consensus_db.get_randomness(block_number).map_err(|e| {
+        error!("Failed to get randomness for block {}: {:?}", block_number, e);
+        (INTERNAL_ERROR, "Internal server error".to_string())
+    })?;
+    let response = RandomnessResponse { block_number, randomness: randomness.map(hex::encode) };
+    serde_json::to_value(response).map_err(|e| (INTERNAL_ERROR, e.to_string()))
+}
+
+fn block_by_hash(consensus_db: &ConsensusDB, hash: HashValue) -> CallResult {
+    let block = get_block_by_id(consensus_db, hash, false)
+        .map_err(|_| (INTERNAL_ERROR, "Internal server error".to_string()))?;
+    serde_json::to_value(block).map_err(|e| (INTERNAL_ERROR, e.to_string()))
+}
+
+fn epoch_and_round(params: &Value) -> Result<(u64, u64), (i64, String)> {
+    Ok((u64_param(params, 0, "epoch")?, u64_param(params, 1, "round")?))
+}
+
+/// Reads a u64 param either by position or by name.
+fn u64_param(params: &Value, index: usize, name: &str) -> Result<u64, (i64, String)> {
+    let value = match params {
+        Value::Array(values) => values.get(index),
+        Value::Object(values) => values.get(name),
+        _ => None,
+    };
+    value
+        .and_then(Value::as_u64)
+        .ok_or_else(|| (INVALID_PARAMS, format!("Missing or invalid param '{name}'")))
+}
+
+/// Reads a hex-encoded hash param either by position or by name.
+fn hash_param(params: &Value, index: usize, name: &str) -> Result<HashValue, (i64, String)> {
+    let value = match params {
+        Value::Array(values) => values.get(index),
+        Value::Object(values) => values.get(name),
+        _ => None,
+    };
+    value
+        .and_then(Value::as_str)
+        .and_then(|s| HashValue::from_hex(s.trim_start_matches("0x")).ok())
+        .ok_or_else(|| (INVALID_PARAMS, format!("Missing or invalid param '{name}'")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn call(body: Value) -> (StatusCode, Value) {
+        let dkg_state = Arc::new(DkgState::new(None));
+        let response = handle_rpc(dkg_state, Bytes::from(body.to_string()));
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, if body.is_empty() { Value::Null } else { serde_json::from_slice(&body).unwrap() })
+    }
+
+    #[tokio::test]
+    async fn notifications_get_no_response_but_null_ids_do() {
+        let notification = json!({"jsonrpc": "2.0", "method": "consensus_getLatestLedgerInfo"});
+        assert_eq!(call(notification).await, (StatusCode::NO_CONTENT, Value::Null));
+
+        let null_id =
+            json!({"jsonrpc": "2.0", "id": null, "method": "consensus_getLatestLedgerInfo"});
+        let (status, body) = call(null_id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], Value::Null);
+        assert_eq!(body["error"]["code"], INTERNAL_ERROR);
+    }
+
+    #[tokio::test]
+    async fn batches_answer_every_call_but_notifications() {
+        let (status, body) = call(json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "consensus_getQc", "params": [1, 2]},
+            {"jsonrpc": "2.0", "method": "consensus_getQc", "params": [1, 2]},
+            {"jsonrpc": "1.0", "id": "b", "method": "consensus_getQc"},
+            {"id": 3},
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let responses = body.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["id"], "b");
+        assert_eq!(responses[1]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[2]["id"], Value::Null);
+        assert_eq!(responses[2]["error"]["code"], INVALID_REQUEST);
+
+        let (status, _) =
+            call(json!([{"jsonrpc": "2.0", "method": "consensus_getLatestLedgerInfo"}])).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn malformed_requests_get_error_responses() {
+        let dkg_state = Arc::new(DkgState::new(None));
+        let response = handle_rpc(dkg_state, Bytes::from_static(b"{not json"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], PARSE_ERROR);
+        assert_eq!(body["id"], Value::Null);
+
+        let (_, body) = call(json!([])).await;
+        assert_eq!(body["error"]["code"], INVALID_REQUEST);
+    }
+}
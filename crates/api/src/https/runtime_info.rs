@@ -0,0 +1,210 @@
+//! `/admin/runtime`: the runtime configuration actually in effect on the node.
+//!
+//! Performance investigations often start by confirming how a node is really running: how many
+//! tokio workers it got, which RocksDB version and options the consensus DB was opened with,
+//! what the HTTPS listener negotiates and how jemalloc is configured. Those are reported here
+//! together with the build information.
+
+use crate::https::{
+    dkg::DkgState,
+    runtime_metrics::{blocking_pool, local_queue_depth},
+    tls::{TlsMinVersion, TlsSettings},
+};
+use axum::{extract::State, response::Json as JsonResponse};
+use gaptos::aptos_logger::{error, warn};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+use tikv_jemalloc_ctl::{arenas, background_thread, epoch, stats, version};
+use tokio::runtime::Handle;
+
+/// RocksDB options reported from the `[DBOptions]` section of the OPTIONS file. Column family
+/// options are left out, as they repeat per column family.
+const DB_OPTIONS_SECTION: &str = "DBOptions";
+
+static TLS_INFO: OnceCell<TlsInfo> = OnceCell::new();
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RuntimeInfoResponse {
+    /// Version, commit, toolchain and profile the binary was built with.
+    pub build: BTreeMap<String, String>,
+    pub tokio: TokioInfo,
+    /// Absent when the consensus DB is not open or its OPTIONS file can't be read.
+    pub rocksdb: Option<RocksDbInfo>,
+    /// Absent when the API is served over plain HTTP.
+    pub tls: Option<TlsInfo>,
+    /// Absent when jemalloc is not the global allocator.
+    pub jemalloc: Option<JemallocInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TokioInfo {
+    pub num_workers: usize,
+    pub num_alive_tasks: usize,
+    pub global_queue_depth: usize,
+    /// Whether the binary was built with `--cfg tokio_unstable`, without which the blocking pool
+    /// and per-worker queue depths are not available.
+    pub tokio_unstable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocking_threads: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_blocking_threads: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocking_queue_depth: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_queue_depths: Option<Vec<usize>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct RocksDbInfo {
+    pub version: Option<String>,
+    /// OPTIONS file the options were read from.
+    pub options_file: String,
+    pub db_options: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TlsInfo {
+    pub provider: String,
+    pub min_version: String,
+    pub cipher_suites: Vec<String>,
+    pub alpn_protocols: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JemallocInfo {
+    pub version: String,
+    pub background_thread: bool,
+    pub arenas: u32,
+    pub allocated_bytes: usize,
+    pub active_bytes: usize,
+    pub resident_bytes: usize,
+    pub mapped_bytes: usize,
+    pub retained_bytes: usize,
+    pub metadata_bytes: usize,
+}
+
+/// Registers the settings of the HTTPS listener once it is configured.
+pub(crate) fn register_tls(settings: &TlsSettings) {
+    let cipher_suites = match settings.effective_cipher_suites() {
+        Ok(cipher_suites) => cipher_suites,
+        Err(e) => {
+            warn!("Failed to list the TLS cipher suites: {:?}", e);
+            return;
+        }
+    };
+    let min_version = match settings.min_version {
+        TlsMinVersion::Tls12 => "1.2",
+        TlsMinVersion::Tls13 => "1.3",
+    };
+    let _ = TLS_INFO.set(TlsInfo {
+        provider: "ring".to_string(),
+        min_version: min_version.to_string(),
+        cipher_suites,
+        alpn_protocols: settings.effective_alpn_protocols(),
+    });
+}
+
+/// Parses the version and `[DBOptions]` of a RocksDB OPTIONS file.
+fn parse_rocksdb_options(options_file: String, content: &str) -> RocksDbInfo {
+    let mut section = "";
+    let mut version = None;
+    let mut db_options = BTreeMap::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            section = name;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        match section {
+            "Version" if key == "rocksdb_version" => version = Some(value.to_string()),
+            DB_OPTIONS_SECTION => {
+                db_options.insert(key.to_string(), value.to_string());
+            }
+            _ => {}
+        }
+    }
+    RocksDbInfo { version, options_file, db_options }
+}
+
+fn rocksdb_info(dkg_state: &DkgState) -> Option<RocksDbInfo> {
+    let consensus_db = dkg_state.consensus_db()?;
+    match consensus_db.rocksdb_options_file() {
+        Ok(Some((name, content))) => Some(parse_rocksdb_options(name, &content)),
+        Ok(None) => None,
+        Err(e) => {
+            error!("Failed to read the RocksDB OPTIONS file: {:?}", e);
+            None
+        }
+    }
+}
+
+fn jemalloc_info() -> Option<JemallocInfo> {
+    // Statistics are cached by jemalloc until the epoch is advanced.
+    epoch::advance().ok()?;
+    Some(JemallocInfo {
+        version: version::read().ok()?.to_string(),
+        background_thread: background_thread::read().ok()?,
+        arenas: arenas::narenas::read().ok()?,
+        allocated_bytes: stats::allocated::read().ok()?,
+        active_bytes: stats::active::read().ok()?,
+        resident_bytes: stats::resident::read().ok()?,
+        mapped_bytes: stats::mapped::read().ok()?,
+        retained_bytes: stats::retained::read().ok()?,
+        metadata_bytes: stats::metadata::read().ok()?,
+    })
+}
+
+fn tokio_info() -> TokioInfo {
+    let metrics = Handle::current().metrics();
+    let (blocking_threads, idle_blocking_threads, blocking_queue_depth) = blocking_pool(&metrics);
+    let local_queue_depths = (0..metrics.num_workers())
+        .map(|worker| local_queue_depth(&metrics, worker))
+        .collect::<Option<Vec<_>>>();
+    TokioInfo {
+        num_workers: metrics.num_workers(),
+        num_alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        tokio_unstable: cfg!(tokio_unstable),
+        blocking_threads,
+        idle_blocking_threads,
+        blocking_queue_depth,
+        local_queue_depths,
+    }
+}
+
+/// Runtime configuration of the node
+// example:
+// curl http://127.0.0.1:1024/admin/runtime
+pub async fn get_runtime_info(
+    State(dkg_state): State<Arc<DkgState>>,
+) -> JsonResponse<RuntimeInfoResponse> {
+    JsonResponse(RuntimeInfoResponse {
+        build: build_info::get_build_information(),
+        tokio: tokio_info(),
+        rocksdb: rocksdb_info(&dkg_state),
+        tls: TLS_INFO.get().cloned(),
+        jemalloc: jemalloc_info(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_version_and_db_options() {
+        let content = "# RocksDB option file.\n[Version]\n  rocksdb_version=10.4.2\n  \
+                       options_file_version=1.1\n\n[DBOptions]\n  max_open_files=-1\n  \
+                       create_if_missing=true\n\n[CFOptions \"default\"]\n  \
+                       write_buffer_size=67108864\n";
+        let info = parse_rocksdb_options("OPTIONS-000007".to_string(), content);
+        assert_eq!(info.version.as_deref(), Some("10.4.2"));
+        assert_eq!(info.db_options.len(), 2);
+        assert_eq!(info.db_options["max_open_files"], "-1");
+        assert!(!info.db_options.contains_key("write_buffer_size"));
+    }
+}
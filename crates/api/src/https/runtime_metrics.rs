@@ -0,0 +1,130 @@
+use crate::https::error::ApiError;
+use axum::{extract::Query, response::Json as JsonResponse};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::runtime::{Handle, RuntimeMetrics};
+
+const DEFAULT_SAMPLE: Duration = Duration::from_millis(1000);
+const MAX_SAMPLE: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize, Debug, Default)]
+pub struct RuntimeMetricsQuery {
+    /// How long worker busy time is sampled for, in milliseconds (default 1000, at most 10000).
+    pub sample_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RuntimeMetricsResponse {
+    pub num_workers: usize,
+    pub num_alive_tasks: usize,
+    /// Tasks waiting in the runtime's shared injection queue.
+    pub global_queue_depth: usize,
+    pub sample_ms: u64,
+    /// Whether the binary was built with `--cfg tokio_unstable`. Without it the blocking pool
+    /// and per-worker queue metrics are not available and are omitted.
+    pub tokio_unstable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocking_threads: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_blocking_threads: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocking_queue_depth: Option<usize>,
+    pub workers: Vec<WorkerMetrics>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WorkerMetrics {
+    pub worker: usize,
+    /// Fraction of the sample the worker spent running tasks, between 0 and 1.
+    pub busy_ratio: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_queue_depth: Option<usize>,
+}
+
+/// Metrics of the tokio runtime serving the API, to correlate latency spikes with executor
+/// saturation.
+// example:
+// curl http://127.0.0.1:1024/debug/runtime?sample_ms=2000
+pub async fn get_runtime_metrics(
+    Query(query): Query<RuntimeMetricsQuery>,
+) -> Result<JsonResponse<RuntimeMetricsResponse>, ApiError> {
+    let sample = query.sample_ms.map(Duration::from_millis).unwrap_or(DEFAULT_SAMPLE);
+    if sample.is_zero() || sample > MAX_SAMPLE {
+        return Err(ApiError::bad_request(format!(
+            "sample_ms must be between 1 and {}",
+            MAX_SAMPLE.as_millis()
+        )));
+    }
+
+    let metrics = Handle::current().metrics();
+    // Busy time is a cumulative counter, so the ratio comes from two readings.
+    let before = busy_durations(&metrics);
+    let started = Instant::now();
+    tokio::time::sleep(sample).await;
+    let after = busy_durations(&metrics);
+    let elapsed = started.elapsed();
+
+    let workers = before
+        .iter()
+        .zip(&after)
+        .enumerate()
+        .map(|(worker, (before, after))| WorkerMetrics {
+            worker,
+            busy_ratio: busy_ratio(*before, *after, elapsed),
+            local_queue_depth: local_queue_depth(&metrics, worker),
+        })
+        .collect();
+
+    let (blocking_threads, idle_blocking_threads, blocking_queue_depth) = blocking_pool(&metrics);
+    Ok(JsonResponse(RuntimeMetricsResponse {
+        num_workers: metrics.num_workers(),
+        num_alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        sample_ms: elapsed.as_millis() as u64,
+        tokio_unstable: cfg!(tokio_unstable),
+        blocking_threads,
+        idle_blocking_threads,
+        blocking_queue_depth,
+        workers,
+    }))
+}
+
+fn busy_durations(metrics: &RuntimeMetrics) -> Vec<Duration> {
+    (0..metrics.num_workers()).map(|worker| metrics.worker_total_busy_duration(worker)).collect()
+}
+
+fn busy_ratio(before: Duration, after: Duration, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        return 0.0;
+    }
+    (after.saturating_sub(before).as_secs_f64() / elapsed.as_secs_f64()).min(1.0)
+}
+
+#[cfg(tokio_unstable)]
+pub(super) fn local_queue_depth(metrics: &RuntimeMetrics, worker: usize) -> Option<usize> {
+    Some(metrics.worker_local_queue_depth(worker))
+}
+
+#[cfg(not(tokio_unstable))]
+pub(super) fn local_queue_depth(_metrics: &RuntimeMetrics, _worker: usize) -> Option<usize> {
+    None
+}
+
+/// Threads, idle threads and queued tasks of the blocking pool.
+#[cfg(tokio_unstable)]
+pub(super) fn blocking_pool(
+    metrics: &RuntimeMetrics,
+) -> (Option<usize>, Option<usize>, Option<usize>) {
+    (
+        Some(metrics.num_blocking_threads()),
+        Some(metrics.num_idle_blocking_threads()),
+        Some(metrics.blocking_queue_depth()),
+    )
+}
+
+#[cfg(not(tokio_unstable))]
+pub(super) fn blocking_pool(
+    _metrics: &RuntimeMetrics,
+) -> (Option<usize>, Option<usize>, Option<usize>) {
+    (None, None, None)
+}
@@ -1,10 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::https::error::ApiError;
 #[allow(unused_imports)]
 use anyhow::{format_err, Result};
-use axum::response::IntoResponse;
-#[cfg(feature = "failpoints")]
 use axum::Json;
 #[cfg(feature = "failpoints")]
 use gaptos::aptos_logger::prelude::*;
@@ -23,25 +22,25 @@ pub struct FailpointConfResponse {
 }
 
 #[cfg(feature = "failpoints")]
-pub async fn set_failpoint(request: FailpointConf) -> impl IntoResponse {
+pub async fn set_failpoint(
+    request: FailpointConf,
+) -> Result<Json<FailpointConfResponse>, ApiError> {
     match fail::cfg(&request.name, &request.actions) {
         Ok(_) => {
             info!("Configured failpoint {} to {}", request.name, request.actions);
             let response = format!("Set failpoint {}", request.name);
-            Json(FailpointConfResponse { response }).into_response()
-        }
-        Err(e) => {
-            (axum::http::StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to set failpoint: {e}"))
-                .into_response()
+            Ok(Json(FailpointConfResponse { response }))
         }
+        // fail::cfg only rejects malformed action strings
+        Err(e) => Err(ApiError::bad_request(format!("Failed to set failpoint: {e}"))),
     }
 }
 
 #[cfg(not(feature = "failpoints"))]
-pub async fn set_failpoint(_: FailpointConf) -> impl IntoResponse {
-    (
+pub async fn set_failpoint(_: FailpointConf) -> Result<Json<FailpointConfResponse>, ApiError> {
+    Err(ApiError::new(
         axum::http::StatusCode::BAD_REQUEST,
-        "Failpoints are not enabled at a feature level".to_string(),
-    )
-        .into_response()
+        "feature_disabled",
+        "Failpoints are not enabled at a feature level",
+    ))
 }
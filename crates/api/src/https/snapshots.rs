@@ -0,0 +1,247 @@
+//! Point-in-time views of the ConsensusDB read by `?at_block=N`.
+//!
+//! `POST /admin/consensus_snapshot` takes a RocksDB checkpoint next to the live DB, where it
+//! costs hard links rather than a copy, and opens it as a DB of its own keyed by the latest block
+//! committed in it. Reads pinned to that block go to the copy, so a dashboard composing several
+//! calls sees one state however many commits land in between. Only that admin route takes
+//! snapshots, at most one per [`MIN_SNAPSHOT_INTERVAL`]; a read pinned to a block without one is
+//! refused. Snapshots expire [`SNAPSHOT_TTL`] after their last use, at most [`MAX_SNAPSHOTS`] are
+//! kept, and a directory is removed once the last request reading from it has finished.
+
+use crate::https::{dkg::DkgState, error::ApiError};
+use aptos_consensus::consensusdb::{ConsensusDB, CONSENSUS_DB_NAME};
+use axum::{extract::State, http::StatusCode};
+use gaptos::{
+    aptos_infallible::Mutex,
+    aptos_logger::{error, info, warn},
+    aptos_storage_interface::DbReader,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// How long a snapshot is kept after it was last read.
+const SNAPSHOT_TTL: Duration = Duration::from_secs(60);
+/// Snapshots kept at once; taking one more drops the least recently read.
+const MAX_SNAPSHOTS: usize = 8;
+/// Shortest time between two snapshots, so checkpoints and DB opens can't be churned.
+const MIN_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10);
+/// Prefix of the snapshot directories, next to the live DB.
+const SNAPSHOT_DIR_PREFIX: &str = "pinned-";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SnapshotResponse {
+    /// Pass as `?at_block=` to read from the snapshot.
+    pub block_number: u64,
+    pub expires_in_secs: u64,
+}
+
+struct Snapshot {
+    db: Arc<ConsensusDB>,
+    last_used: Instant,
+}
+
+#[derive(Default)]
+struct Registry {
+    live: BTreeMap<u64, Snapshot>,
+    /// Expired snapshots whose directory is removed once no request reads from them anymore.
+    retired: Vec<Arc<ConsensusDB>>,
+    /// When the last snapshot was taken.
+    last_taken: Option<Instant>,
+}
+
+#[derive(Default)]
+pub(crate) struct Snapshots {
+    registry: Mutex<Registry>,
+    /// Held while a checkpoint is taken, so concurrent requests are rate limited one by one.
+    taking: Mutex<()>,
+}
+
+impl Snapshots {
+    /// Removes the snapshot directories a previous run left behind next to `consensus_db`.
+    pub(crate) fn new(consensus_db: Option<&Arc<ConsensusDB>>) -> Self {
+        if let Some(parent) = consensus_db.and_then(|db| db.path().parent()) {
+            let prefix = format!("{CONSENSUS_DB_NAME}.{SNAPSHOT_DIR_PREFIX}");
+            for entry in std::fs::read_dir(parent).into_iter().flatten().flatten() {
+                if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                    remove_dir(&entry.path());
+                }
+            }
+        }
+        Self::default()
+    }
+
+    /// The snapshot taken at `block_number`. Reads never take one themselves.
+    pub(crate) fn get(
+        &self,
+        consensus_db: &ConsensusDB,
+        block_number: u64,
+    ) -> Result<Arc<ConsensusDB>, ApiError> {
+        if let Some(db) = self.touch(block_number) {
+            return Ok(db);
+        }
+        let head = latest_block_number(consensus_db)?;
+        if block_number > head {
+            return Err(ApiError::not_found(format!(
+                "Block {block_number} is not committed yet, the latest committed block is {head}"
+            )));
+        }
+        Err(ApiError::not_found(format!(
+            "No snapshot is pinned at block {block_number}; have an operator take one with POST \
+             /admin/consensus_snapshot and pass its block_number as at_block"
+        )))
+    }
+
+    /// Takes a snapshot of the state committed now, returning the block it is pinned at. Refused
+    /// within [`MIN_SNAPSHOT_INTERVAL`] of the previous one.
+    pub(crate) fn take(
+        &self,
+        consensus_db: &ConsensusDB,
+    ) -> Result<(u64, Arc<ConsensusDB>), ApiError> {
+        if let Some(last_taken) = self.registry.lock().last_taken {
+            let since = last_taken.elapsed();
+            if since < MIN_SNAPSHOT_INTERVAL {
+                return Err(ApiError::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "snapshot_rate_limited",
+                    format!(
+                        "A snapshot was taken {}ms ago, retry in {}ms",
+                        since.as_millis(),
+                        (MIN_SNAPSHOT_INTERVAL - since).as_millis()
+                    ),
+                ));
+            }
+        }
+        self.registry.lock().last_taken = Some(Instant::now());
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let dir = consensus_db
+            .path()
+            .with_file_name(format!("{CONSENSUS_DB_NAME}.{SNAPSHOT_DIR_PREFIX}{now_ms}"));
+        let db = consensus_db.open_snapshot(&dir).map_err(|e| {
+            error!("Failed to take ConsensusDB snapshot in {}: {:?}", dir.display(), e);
+            remove_dir(&dir);
+            ApiError::internal()
+        })?;
+        let db = Arc::new(db);
+        let block_number = latest_block_number(&db)?;
+        info!("Took ConsensusDB snapshot at block {} in {}", block_number, dir.display());
+
+        let mut registry = self.registry.lock();
+        let replaced = registry
+            .live
+            .insert(block_number, Snapshot { db: db.clone(), last_used: Instant::now() });
+        registry.retired.extend(replaced.map(|snapshot| snapshot.db));
+        registry.sweep();
+        Ok((block_number, db))
+    }
+
+    fn touch(&self, block_number: u64) -> Option<Arc<ConsensusDB>> {
+        let mut registry = self.registry.lock();
+        registry.sweep();
+        let snapshot = registry.live.get_mut(&block_number)?;
+        snapshot.last_used = Instant::now();
+        Some(snapshot.db.clone())
+    }
+}
+
+impl Registry {
+    /// Retires expired snapshots and the least recently read ones beyond [`MAX_SNAPSHOTS`], and
+    /// removes the retired ones no request holds anymore.
+    fn sweep(&mut self) {
+        let expired: Vec<u64> = self
+            .live
+            .iter()
+            .filter(|(_, snapshot)| snapshot.last_used.elapsed() > SNAPSHOT_TTL)
+            .map(|(block_number, _)| *block_number)
+            .collect();
+        for block_number in expired {
+            self.retire(block_number);
+        }
+        while self.live.len() > MAX_SNAPSHOTS {
+            let Some(oldest) = self
+                .live
+                .iter()
+                .min_by_key(|(_, snapshot)| snapshot.last_used)
+                .map(|(block_number, _)| *block_number)
+            else {
+                break;
+            };
+            self.retire(oldest);
+        }
+        let (idle, busy): (Vec<_>, Vec<_>) =
+            self.retired.drain(..).partition(|db| Arc::strong_count(db) == 1);
+        self.retired = busy;
+        for db in idle {
+            let dir = db.path().to_path_buf();
+            // Closes the DB before its files go.
+            drop(db);
+            remove_dir(&dir);
+        }
+    }
+
+    fn retire(&mut self, block_number: u64) {
+        if let Some(snapshot) = self.live.remove(&block_number) {
+            self.retired.push(snapshot.db);
+        }
+    }
+}
+
+fn latest_block_number(consensus_db: &ConsensusDB) -> Result<u64, ApiError> {
+    DbReader::get_latest_ledger_info(consensus_db)
+        .map(|ledger_info| ledger_info.ledger_info().block_number())
+        .map_err(|e| {
+            error!("Failed to get latest ledger info: {:?}", e);
+            ApiError::internal()
+        })
+}
+
+fn remove_dir(dir: &Path) {
+    if let Err(e) = std::fs::remove_dir_all(dir) {
+        if dir.exists() {
+            warn!("Failed to remove ConsensusDB snapshot {}: {:?}", dir.display(), e);
+        }
+    }
+}
+
+/// Pin the state committed now for `?at_block` reads
+/// Example: POST /admin/consensus_snapshot
+pub(crate) async fn take_snapshot(
+    State(dkg_state): State<Arc<DkgState>>,
+) -> Result<axum::Json<SnapshotResponse>, ApiError> {
+    let consensus_db = dkg_state.require_consensus_db()?.clone();
+    let (block_number, _) = tokio::task::spawn_blocking(move || {
+        let snapshots = dkg_state.snapshots();
+        let _taking = snapshots.taking.lock();
+        snapshots.take(&consensus_db)
+    })
+    .await
+    .map_err(|e| {
+        error!("ConsensusDB snapshot task panicked: {:?}", e);
+        ApiError::internal()
+    })??;
+    Ok(axum::Json(SnapshotResponse { block_number, expires_in_secs: SNAPSHOT_TTL.as_secs() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_are_rate_limited_and_never_taken_by_reads() {
+        let dir = std::env::temp_dir().join(format!("snapshots_test_{}", rand::random::<u64>()));
+        let consensus_db = ConsensusDB::new(&dir, &std::path::PathBuf::new());
+        let snapshots = Snapshots::default();
+
+        assert!(snapshots.get(&consensus_db, 0).is_err());
+        assert!(snapshots.registry.lock().last_taken.is_none());
+
+        snapshots.registry.lock().last_taken = Some(Instant::now());
+        let error = snapshots.take(&consensus_db).unwrap_err();
+        assert_eq!(error.status(), StatusCode::TOO_MANY_REQUESTS);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
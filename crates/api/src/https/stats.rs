@@ -0,0 +1,334 @@
+use crate::https::{dkg::DkgState, error::ApiError};
+use aptos_consensus::consensusdb::{BlockNumberSchema, ConsensusDB, RawBlockSchema};
+use axum::{
+    extract::{Query, State},
+    response::Json as JsonResponse,
+};
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_logger::{error, info},
+    aptos_storage_interface::DbReader,
+};
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+
+const DEFAULT_STATS_WINDOW: Duration = Duration::from_secs(600);
+/// Every block in the window is decoded, so the window is capped to keep a request cheap.
+const MAX_STATS_WINDOW: Duration = Duration::from_secs(3600);
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ChainStatsQuery {
+    /// Window ending at the latest committed block, e.g. `90s`, `10m` or `1h` (default 10m).
+    pub window: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChainStatsResponse {
+    pub epoch: u64,
+    /// Requested window length.
+    pub window_secs: u64,
+    /// Part of the window actually covered by stored blocks; shorter than `window_secs` on a
+    /// node with less history, and used as the denominator of the rates below.
+    pub covered_secs: f64,
+    pub first_block: Option<u64>,
+    pub last_block: Option<u64>,
+    pub blocks: u64,
+    pub transactions: u64,
+    pub blocks_per_sec: f64,
+    pub tps: f64,
+    /// Time from a block's proposal until the first later proposal carrying its commit
+    /// certificate, over the committed blocks in the window.
+    pub avg_commit_latency_ms: Option<f64>,
+    pub p99_commit_latency_ms: Option<f64>,
+}
+
+/// What the statistics need from one stored block.
+#[derive(Debug, Clone)]
+struct StatsBlock {
+    epoch: u64,
+    round: u64,
+    timestamp_usecs: u64,
+    block_number: Option<u64>,
+    txns: u64,
+    /// Round committed by the quorum cert this block carries, if it commits a block of the same
+    /// epoch.
+    commits_round: Option<u64>,
+}
+
+/// Summary statistics over a recent window of committed blocks
+/// Example: GET /stats/chain?window=10m
+///
+/// Computed from the blocks stored in the consensus DB, so it answers "how fast is the chain
+/// right now" without a metrics stack.
+pub async fn get_chain_stats(
+    State(dkg_state): State<Arc<DkgState>>,
+    Query(query): Query<ChainStatsQuery>,
+) -> Result<JsonResponse<ChainStatsResponse>, ApiError> {
+    let window = query
+        .window
+        .as_deref()
+        .map(parse_window)
+        .transpose()
+        .map_err(ApiError::bad_request)?
+        .unwrap_or(DEFAULT_STATS_WINDOW);
+    if window.is_zero() || window > MAX_STATS_WINDOW {
+        return Err(ApiError::bad_request(format!(
+            "window must be between 1s and {}s",
+            MAX_STATS_WINDOW.as_secs()
+        )));
+    }
+    info!("Computing chain stats over {:?}", window);
+
+    // Decoding a window of blocks is too slow for an async worker.
+    tokio::task::spawn_blocking(move || {
+        let consensus_db = dkg_state.require_consensus_db()?;
+        chain_stats(consensus_db, window).map(JsonResponse)
+    })
+    .await
+    .map_err(|e| {
+        error!("Chain stats task panicked: {:?}", e);
+        ApiError::internal()
+    })?
+}
+
+fn chain_stats(
+    consensus_db: &ConsensusDB,
+    window: Duration,
+) -> Result<ChainStatsResponse, ApiError> {
+    let latest = DbReader::get_latest_ledger_info(consensus_db).map_err(|e| {
+        error!("Failed to get latest ledger info: {:?}", e);
+        ApiError::internal()
+    })?;
+    let head_block = latest.ledger_info().block_number();
+    let end_usecs = latest.ledger_info().timestamp_usecs();
+    let start_usecs = end_usecs.saturating_sub(window.as_micros() as u64);
+
+    let mut blocks = Vec::new();
+    let mut oldest_usecs = end_usecs;
+    let mut epoch = latest.ledger_info().next_block_epoch();
+    loop {
+        let (epoch_blocks, epoch_oldest) = read_epoch_blocks(consensus_db, epoch, start_usecs)?;
+        let Some(epoch_oldest) = epoch_oldest else { break };
+        oldest_usecs = oldest_usecs.min(epoch_oldest);
+        blocks.extend(epoch_blocks);
+        if epoch_oldest <= start_usecs || epoch == 0 {
+            break;
+        }
+        epoch -= 1;
+    }
+
+    Ok(summarize(
+        latest.ledger_info().epoch(),
+        blocks,
+        window,
+        start_usecs.max(oldest_usecs),
+        end_usecs,
+        head_block,
+    ))
+}
+
+/// Decodes the blocks of `epoch` proposed at or after `start_usecs`. Also returns the oldest
+/// timestamp stored for the epoch, or `None` if it has no blocks (e.g. pruned).
+fn read_epoch_blocks(
+    consensus_db: &ConsensusDB,
+    epoch: u64,
+    start_usecs: u64,
+) -> Result<(Vec<StatsBlock>, Option<u64>), ApiError> {
+    let start_key = (epoch, HashValue::zero());
+    let end_key = (epoch, HashValue::new([u8::MAX; HashValue::LENGTH]));
+    let raw_blocks =
+        consensus_db.get_range::<RawBlockSchema>(&start_key, &end_key).map_err(|e| {
+            error!("Failed to get blocks of epoch {}: {:?}", epoch, e);
+            ApiError::internal()
+        })?;
+
+    let mut oldest = None;
+    let mut blocks = Vec::new();
+    for ((_, block_id), raw_block) in raw_blocks {
+        // Headers are cheap; only blocks inside the window are fully decoded.
+        let header = match raw_block.header() {
+            Ok(header) => header,
+            Err(e) => {
+                error!("Failed to read header of block {}: {:?}", block_id, e);
+                continue;
+            }
+        };
+        oldest =
+            Some(oldest.map_or(header.timestamp_usecs, |o: u64| o.min(header.timestamp_usecs)));
+        if header.timestamp_usecs < start_usecs {
+            continue;
+        }
+        let block = match raw_block.decode() {
+            Ok(block) => block,
+            Err(e) => {
+                error!("Failed to decode block {}: {:?}", block_id, e);
+                continue;
+            }
+        };
+        let commit_info = block.quorum_cert().commit_info();
+        blocks.push(StatsBlock {
+            epoch,
+            round: header.round,
+            timestamp_usecs: header.timestamp_usecs,
+            block_number: consensus_db.get::<BlockNumberSchema>(&(epoch, block_id)).ok().flatten(),
+            txns: block.payload().map_or(0, |payload| payload.len() as u64),
+            commits_round: (commit_info.epoch() == epoch && commit_info.id() != HashValue::zero())
+                .then(|| commit_info.round()),
+        });
+    }
+    Ok((blocks, oldest))
+}
+
+/// Rates over the committed blocks in `[start_usecs, end_usecs]`. Uncommitted blocks only serve
+/// as carriers of commit certificates for the latency estimate.
+fn summarize(
+    epoch: u64,
+    mut blocks: Vec<StatsBlock>,
+    window: Duration,
+    start_usecs: u64,
+    end_usecs: u64,
+    head_block: u64,
+) -> ChainStatsResponse {
+    blocks.sort_by_key(|b| (b.epoch, b.round));
+    let is_committed = |b: &StatsBlock| {
+        b.block_number.is_some_and(|n| n <= head_block) &&
+            (start_usecs..=end_usecs).contains(&b.timestamp_usecs)
+    };
+
+    let mut latencies = Vec::new();
+    let (mut count, mut transactions) = (0u64, 0u64);
+    let (mut first_block, mut last_block) = (None::<u64>, None::<u64>);
+    for (i, block) in blocks.iter().enumerate() {
+        if !is_committed(block) {
+            continue;
+        }
+        count += 1;
+        transactions += block.txns;
+        let number = block.block_number.unwrap_or_default();
+        first_block = Some(first_block.map_or(number, |n| n.min(number)));
+        last_block = Some(last_block.map_or(number, |n| n.max(number)));
+
+        // Commits are transitive, so the first later proposal committing this round or a
+        // descendant carries this block's commit certificate.
+        let carrier = blocks[i + 1..]
+            .iter()
+            .take_while(|c| c.epoch == block.epoch)
+            .find(|c| c.commits_round.is_some_and(|r| r >= block.round));
+        if let Some(carrier) = carrier {
+            latencies.push(carrier.timestamp_usecs.saturating_sub(block.timestamp_usecs));
+        }
+    }
+
+    let covered_secs = (end_usecs.saturating_sub(start_usecs) as f64 / 1_000_000.0).max(1.0);
+    latencies.sort_unstable();
+    let avg_commit_latency_ms = (!latencies.is_empty())
+        .then(|| latencies.iter().sum::<u64>() as f64 / latencies.len() as f64 / 1000.0);
+    let p99_commit_latency_ms = percentile(&latencies, 99).map(|usecs| usecs as f64 / 1000.0);
+
+    ChainStatsResponse {
+        epoch,
+        window_secs: window.as_secs(),
+        covered_secs,
+        first_block,
+        last_block,
+        blocks: count,
+        transactions,
+        blocks_per_sec: count as f64 / covered_secs,
+        tps: transactions as f64 / covered_secs,
+        avg_commit_latency_ms,
+        p99_commit_latency_ms,
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[u64], pct: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+/// Parses a window such as `90s`, `10m`, `1h` or a bare number of seconds.
+fn parse_window(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit) = match value.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => value.split_at(i),
+        None => (value, "s"),
+    };
+    let secs_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return Err(format!("Invalid window '{value}', expected e.g. 90s, 10m or 1h")),
+    };
+    number
+        .parse::<u64>()
+        .map(|n| Duration::from_secs(n.saturating_mul(secs_per_unit)))
+        .map_err(|_| format!("Invalid window '{value}', expected e.g. 90s, 10m or 1h"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(
+        round: u64,
+        secs: u64,
+        number: Option<u64>,
+        txns: u64,
+        commits: Option<u64>,
+    ) -> StatsBlock {
+        StatsBlock {
+            epoch: 1,
+            round,
+            timestamp_usecs: secs * 1_000_000,
+            block_number: number,
+            txns,
+            commits_round: commits,
+        }
+    }
+
+    #[test]
+    fn parses_windows() {
+        assert_eq!(parse_window("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_window("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_window("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_window("45").unwrap(), Duration::from_secs(45));
+        assert!(parse_window("10d").is_err());
+        assert!(parse_window("m").is_err());
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        assert_eq!(percentile(&[], 99), None);
+        assert_eq!(percentile(&[5], 99), Some(5));
+        let values: Vec<u64> = (1..=200).collect();
+        assert_eq!(percentile(&values, 99), Some(198));
+        assert_eq!(percentile(&values, 50), Some(100));
+    }
+
+    #[test]
+    fn summarizes_committed_blocks_in_window() {
+        // Rounds 1..=4 committed; round 5 is proposed but not committed yet. The QC in the block
+        // of round r commits round r - 2.
+        let blocks = vec![
+            block(1, 100, Some(10), 4, None),
+            block(2, 101, Some(11), 6, None),
+            block(3, 102, Some(12), 0, Some(1)),
+            block(4, 103, Some(13), 10, Some(2)),
+            block(5, 104, Some(14), 3, Some(3)),
+            // Outside the window
+            block(0, 50, Some(9), 100, None),
+        ];
+        let stats = summarize(1, blocks, Duration::from_secs(10), 100_000_000, 110_000_000, 13);
+        assert_eq!(stats.blocks, 4);
+        assert_eq!(stats.transactions, 20);
+        assert_eq!((stats.first_block, stats.last_block), (Some(10), Some(13)));
+        assert!((stats.tps - 2.0).abs() < 1e-9);
+        assert!((stats.blocks_per_sec - 0.4).abs() < 1e-9);
+        // Rounds 1..=3 each see their commit certificate 2s later; round 4 has none yet.
+        assert_eq!(stats.avg_commit_latency_ms, Some(2000.0));
+        assert_eq!(stats.p99_commit_latency_ms, Some(2000.0));
+    }
+}
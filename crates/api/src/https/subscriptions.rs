@@ -0,0 +1,182 @@
+//! Limits on the streaming endpoints: `/ws/blocks`, `/ws/mempool` and
+//! `/consensus/stream/ledger_info`.
+//!
+//! Every open stream holds one of `max_subscriptions` slots, and new ones are refused with
+//! `503 too_many_subscriptions` until a slot frees up. WebSocket subscribers are pinged every
+//! `ping_interval`; one that sends nothing back, not even a pong, for `idle_timeout` is closed
+//! with [`CLOSE_IDLE`]. A subscriber that can't keep up, because a message waited longer than
+//! `send_timeout` to be written or because it missed more than `max_lag` events of a shared
+//! feed, is closed with [`CLOSE_LAGGING`]. Together they bound what streaming clients can hold
+//! on to, so a crowd of slow or abandoned connections can't exhaust the node's memory.
+
+use crate::https::{config::duration_secs, error::ApiError};
+use axum::{
+    extract::ws::{CloseFrame, Message, WebSocket},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::{Instant, Interval},
+};
+
+/// Close code of a subscriber dropped for not keeping up with its feed.
+pub const CLOSE_LAGGING: u16 = 4000;
+/// Close code of a subscriber dropped for not answering pings.
+pub const CLOSE_IDLE: u16 = 4001;
+
+/// How long to try delivering a close frame to a subscriber that is being dropped.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubscriptionLimits {
+    /// Streams that can be open at once, across all streaming endpoints.
+    pub max_subscriptions: usize,
+    /// How long a WebSocket subscriber may stay silent, pongs included, before it is closed.
+    #[serde(rename = "idle_timeout_secs", with = "duration_secs")]
+    pub idle_timeout: Duration,
+    /// Time between pings to WebSocket subscribers.
+    #[serde(rename = "ping_interval_secs", with = "duration_secs")]
+    pub ping_interval: Duration,
+    /// How long a message may wait to be written to a subscriber before it is considered lagging.
+    #[serde(rename = "send_timeout_secs", with = "duration_secs")]
+    pub send_timeout: Duration,
+    /// Events of a shared feed a subscriber may miss in total before it is closed.
+    pub max_lag: u64,
+}
+
+impl Default for SubscriptionLimits {
+    fn default() -> Self {
+        Self {
+            max_subscriptions: 256,
+            idle_timeout: Duration::from_secs(120),
+            ping_interval: Duration::from_secs(30),
+            send_timeout: Duration::from_secs(10),
+            max_lag: 4096,
+        }
+    }
+}
+
+impl SubscriptionLimits {
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        if self.max_subscriptions == 0 {
+            anyhow::bail!("subscriptions.max_subscriptions must be positive");
+        }
+        if [self.idle_timeout, self.ping_interval, self.send_timeout].contains(&Duration::ZERO) {
+            anyhow::bail!("subscription timeouts and intervals must be positive");
+        }
+        Ok(())
+    }
+}
+
+/// The subscription slots of a server.
+pub(crate) struct Subscriptions {
+    limits: SubscriptionLimits,
+    slots: Arc<Semaphore>,
+}
+
+impl Subscriptions {
+    pub fn new(limits: SubscriptionLimits) -> Self {
+        Self { limits, slots: Arc::new(Semaphore::new(limits.max_subscriptions)) }
+    }
+
+    pub fn limits(&self) -> SubscriptionLimits {
+        self.limits
+    }
+
+    /// Takes a slot for a new stream, held until the permit is dropped with the stream.
+    pub fn acquire(&self) -> Result<OwnedSemaphorePermit, ApiError> {
+        self.slots.clone().try_acquire_owned().map_err(|_| {
+            ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "too_many_subscriptions",
+                format!(
+                    "At most {} streams can be open at once, retry later",
+                    self.limits.max_subscriptions
+                ),
+            )
+        })
+    }
+
+    /// Streams currently open.
+    pub fn open(&self) -> usize {
+        self.limits.max_subscriptions - self.slots.available_permits()
+    }
+}
+
+/// Pings a WebSocket subscriber and tracks when it was last heard from.
+pub(crate) struct Keepalive {
+    ping: Interval,
+    idle_timeout: Duration,
+    last_seen: Instant,
+}
+
+impl Keepalive {
+    pub fn new(limits: &SubscriptionLimits) -> Self {
+        let first_ping = Instant::now() + limits.ping_interval;
+        Self {
+            ping: tokio::time::interval_at(first_ping, limits.ping_interval),
+            idle_timeout: limits.idle_timeout,
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// Records a frame received from the subscriber.
+    pub fn seen(&mut self) {
+        self.last_seen = Instant::now();
+    }
+
+    /// Waits until the next ping is due, and tells whether the subscriber was heard from
+    /// within the idle timeout.
+    pub async fn tick(&mut self) -> bool {
+        self.ping.tick().await;
+        self.last_seen.elapsed() < self.idle_timeout
+    }
+}
+
+/// Pings a subscriber after a [`Keepalive::tick`], or closes it with [`CLOSE_IDLE`] when it
+/// went silent. Returns whether the connection is still usable.
+pub(crate) async fn ping(socket: &mut WebSocket, alive: bool, send_timeout: Duration) -> bool {
+    if !alive {
+        close(socket, CLOSE_IDLE, "idle timeout").await;
+        return false;
+    }
+    send(socket, Message::Ping(Vec::new()), send_timeout).await
+}
+
+/// Sends `message`, giving up after `send_timeout`. A subscriber whose connection can't take
+/// the message in time is closed with [`CLOSE_LAGGING`]. Returns whether it was sent.
+pub(crate) async fn send(socket: &mut WebSocket, message: Message, send_timeout: Duration) -> bool {
+    match tokio::time::timeout(send_timeout, socket.send(message)).await {
+        Ok(result) => result.is_ok(),
+        Err(_) => {
+            close(socket, CLOSE_LAGGING, "subscriber lagging").await;
+            false
+        }
+    }
+}
+
+/// Sends a close frame, best effort: a subscriber that stopped reading won't read it either.
+pub(crate) async fn close(socket: &mut WebSocket, code: u16, reason: &'static str) {
+    let frame = CloseFrame { code, reason: reason.into() };
+    let _ = tokio::time::timeout(CLOSE_TIMEOUT, socket.send(Message::Close(Some(frame)))).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_subscriptions_past_the_limit() {
+        let subscriptions =
+            Subscriptions::new(SubscriptionLimits { max_subscriptions: 2, ..Default::default() });
+        let first = subscriptions.acquire().unwrap();
+        let _second = subscriptions.acquire().unwrap();
+        assert!(subscriptions.acquire().is_err());
+        assert_eq!(subscriptions.open(), 2);
+        drop(first);
+        assert!(subscriptions.acquire().is_ok());
+    }
+}
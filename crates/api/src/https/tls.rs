@@ -0,0 +1,178 @@
+//! rustls settings of the public HTTPS listener.
+//!
+//! By default the listener accepts TLS 1.2 and 1.3 with every cipher suite of the ring provider
+//! and negotiates `h2` or `http/1.1`. Deployments under compliance regimes narrow this down with
+//! the `tls` section of the API config instead of patching the crate.
+
+use anyhow::{anyhow, bail, Context};
+use axum_server::tls_rustls::RustlsConfig;
+use rustls::{
+    crypto::{ring, CryptoProvider},
+    pki_types::{
+        CertificateDer, PrivateKeyDer, PrivatePkcs1KeyDer, PrivatePkcs8KeyDer, PrivateSec1KeyDer,
+    },
+    ServerConfig, SupportedProtocolVersion,
+};
+use serde::{Deserialize, Deserializer};
+use std::{fs::File, io::BufReader, path::Path, sync::Arc};
+
+const DEFAULT_ALPN_PROTOCOLS: [&str; 2] = ["h2", "http/1.1"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TlsMinVersion {
+    #[default]
+    Tls12,
+    Tls13,
+}
+
+impl std::str::FromStr for TlsMinVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().trim_start_matches("tls").trim_start_matches('v') {
+            "1.2" | "12" => Ok(TlsMinVersion::Tls12),
+            "1.3" | "13" => Ok(TlsMinVersion::Tls13),
+            _ => bail!("unsupported TLS version '{s}', expected 1.2 or 1.3"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TlsMinVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TlsSettings {
+    /// `1.2` or `1.3`.
+    pub min_version: TlsMinVersion,
+    /// Allowed cipher suites by IANA name, e.g. `TLS13_AES_256_GCM_SHA384`; empty allows every
+    /// suite of the provider.
+    pub cipher_suites: Vec<String>,
+    /// ALPN protocols in preference order, e.g. `[http/1.1]` to disable HTTP/2; empty means `h2`
+    /// then `http/1.1`.
+    pub alpn_protocols: Vec<String>,
+}
+
+impl TlsSettings {
+    fn provider(&self) -> anyhow::Result<CryptoProvider> {
+        let provider = ring::default_provider();
+        if self.cipher_suites.is_empty() {
+            return Ok(provider);
+        }
+        let name_of = |suite: &rustls::SupportedCipherSuite| format!("{:?}", suite.suite());
+        if let Some(unknown) = self
+            .cipher_suites
+            .iter()
+            .find(|name| !provider.cipher_suites.iter().any(|suite| name_of(suite) == **name))
+        {
+            let known: Vec<String> = provider.cipher_suites.iter().map(name_of).collect();
+            bail!("unknown cipher suite '{unknown}', supported: {}", known.join(", "));
+        }
+        let cipher_suites = provider
+            .cipher_suites
+            .iter()
+            .filter(|suite| self.cipher_suites.contains(&name_of(suite)))
+            .copied()
+            .collect();
+        Ok(CryptoProvider { cipher_suites, ..provider })
+    }
+
+    fn protocol_versions(&self) -> &'static [&'static SupportedProtocolVersion] {
+        match self.min_version {
+            TlsMinVersion::Tls12 => rustls::ALL_VERSIONS,
+            TlsMinVersion::Tls13 => &[&rustls::version::TLS13],
+        }
+    }
+
+    /// Names of the cipher suites the listener offers.
+    pub fn effective_cipher_suites(&self) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .provider()?
+            .cipher_suites
+            .iter()
+            .map(|suite| format!("{:?}", suite.suite()))
+            .collect())
+    }
+
+    /// ALPN protocols the listener offers, in order of preference.
+    pub fn effective_alpn_protocols(&self) -> Vec<String> {
+        if self.alpn_protocols.is_empty() {
+            DEFAULT_ALPN_PROTOCOLS.iter().map(|p| p.to_string()).collect()
+        } else {
+            self.alpn_protocols.clone()
+        }
+    }
+
+    /// Builds the listener config from PEM files, e.g. rejecting suites that none of the allowed
+    /// versions can use.
+    pub async fn rustls_config(&self, cert: &Path, key: &Path) -> anyhow::Result<RustlsConfig> {
+        let certs = load_certs(cert)?;
+        let key = load_key(key)?;
+        let mut config = ServerConfig::builder_with_provider(Arc::new(self.provider()?))
+            .with_protocol_versions(self.protocol_versions())
+            .map_err(|e| anyhow!("TLS settings {self:?} are not usable: {e}"))?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)?;
+        config.alpn_protocols = if self.alpn_protocols.is_empty() {
+            DEFAULT_ALPN_PROTOCOLS.iter().map(|p| p.as_bytes().to_vec()).collect()
+        } else {
+            self.alpn_protocols.iter().map(|p| p.as_bytes().to_vec()).collect()
+        };
+        Ok(RustlsConfig::from_config(Arc::new(config)))
+    }
+}
+
+fn load_certs(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("cannot open cert {}", path.display()))?,
+    );
+    let certs = rustls_pemfile::certs(&mut reader)
+        .with_context(|| format!("cannot parse cert {}", path.display()))?;
+    if certs.is_empty() {
+        bail!("no certificate found in {}", path.display());
+    }
+    Ok(certs.into_iter().map(CertificateDer::from).collect())
+}
+
+fn load_key(path: &Path) -> anyhow::Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("cannot open key {}", path.display()))?,
+    );
+    for item in rustls_pemfile::read_all(&mut reader)
+        .with_context(|| format!("cannot parse key {}", path.display()))?
+    {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key) => return Ok(PrivatePkcs8KeyDer::from(key).into()),
+            rustls_pemfile::Item::RSAKey(key) => return Ok(PrivatePkcs1KeyDer::from(key).into()),
+            rustls_pemfile::Item::ECKey(key) => return Ok(PrivateSec1KeyDer::from(key).into()),
+            _ => {}
+        }
+    }
+    bail!("no private key found in {}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions_and_restricts_suites() {
+        assert_eq!("1.3".parse::<TlsMinVersion>().unwrap(), TlsMinVersion::Tls13);
+        assert_eq!("TLSv1.2".parse::<TlsMinVersion>().unwrap(), TlsMinVersion::Tls12);
+        assert!("1.1".parse::<TlsMinVersion>().is_err());
+
+        let settings = TlsSettings {
+            cipher_suites: vec!["TLS13_AES_256_GCM_SHA384".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(settings.provider().unwrap().cipher_suites.len(), 1);
+        let unknown = TlsSettings {
+            cipher_suites: vec!["TLS_RSA_WITH_RC4".to_string()],
+            ..Default::default()
+        };
+        assert!(unknown.provider().is_err());
+    }
+}
@@ -0,0 +1,904 @@
+//! API tokens, so an RPC provider can serve the public routes to several customers with
+//! separate limits.
+//!
+//! Tokens are issued and revoked through `/admin/tokens`. Each one carries the routes it may
+//! call, a rate limit and a daily quota, and is sent as `Authorization: Bearer <token>` or in
+//! the `X-Api-Key` header. Only a hash of each token is kept, in the file named by
+//! `tokens.file` of the API config so tokens survive restarts. Request counts live in memory.
+//! Routes are named without the `/v1` prefix and cover the legacy paths as well.
+//!
+//! Delegated tokens, minted through `/admin/tokens/delegated`, give auditors or partner teams
+//! temporary access without sharing a main token. They are read-only, limited to route groups
+//! or patterns, and expire. Their claims travel in the token, signed with a key the node keeps
+//! in the token file, so the holder can read their scope and the node rejects altered copies.
+//! The file therefore has to stay readable by the node only.
+//!
+//! An unknown, revoked or expired token gets 401, a route outside the token's permissions 403, and
+//! a request over the rate limit or quota 429 with `Retry-After`. Requests without a token are
+//! served as before unless `tokens.required` is set.
+
+use crate::https::{
+    allowlist::is_admin_route,
+    error::ApiError,
+    jobs::{now_ms, restrict_to_owner, write_private_atomically},
+    route_usage::{count_body_bytes, Direction},
+    versioning,
+};
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json as JsonResponse, Response},
+    Router,
+};
+use gaptos::{
+    aptos_infallible::Mutex,
+    aptos_logger::{error, info, warn},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::Write,
+    path::PathBuf,
+    sync::Arc,
+};
+use tiny_keccak::{Hasher, Sha3};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+const TOKEN_PREFIX: &str = "gvt_";
+const DELEGATED_TOKEN_PREFIX: &str = "gvd_";
+const MS_PER_DAY: u64 = 86_400_000;
+const MAX_NAME_LEN: usize = 128;
+const MAX_DELEGATED_LIFETIME_SECS: u64 = 30 * 86_400;
+/// Route groups delegated tokens are scoped to, with the routes each one covers.
+const ROUTE_GROUPS: &[(&str, &str)] = &[
+    ("consensus", "/consensus/*"),
+    ("dkg", "/dkg/*"),
+    ("tx", "/tx/*"),
+    ("stats", "/stats/*"),
+    ("jobs", "/jobs/*"),
+    ("streams", "/ws/*"),
+];
+
+/// What a token may do.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenPolicy {
+    /// Route patterns the token may call, e.g. `/tx/get_tx_by_hash/:hash_value`, or a prefix
+    /// ending in `*` such as `/consensus/*`. Empty allows every public route. Admin routes are
+    /// never covered.
+    #[serde(default)]
+    pub routes: Vec<String>,
+    /// Sustained requests per second, with bursts of up to one second's worth.
+    #[serde(default)]
+    pub requests_per_second: Option<u32>,
+    /// Requests per UTC day.
+    #[serde(default)]
+    pub daily_quota: Option<u64>,
+    /// Only `GET` and `HEAD` requests are allowed. Always set for delegated tokens.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl TokenPolicy {
+    fn allows_route(&self, route: &str) -> bool {
+        if is_admin_route(route) {
+            return false;
+        }
+        self.routes.is_empty() ||
+            self.routes.iter().any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => route.starts_with(prefix),
+                None => route == pattern,
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredToken {
+    name: String,
+    /// Hex SHA3-256 of the token.
+    hash: String,
+    created_ms: u64,
+    #[serde(default)]
+    revoked_ms: Option<u64>,
+    #[serde(default)]
+    expires_ms: Option<u64>,
+    #[serde(default)]
+    delegated: bool,
+    #[serde(flatten)]
+    policy: TokenPolicy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct TokenFile {
+    /// Hex key delegated tokens are signed with, generated on first start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signing_key: Option<String>,
+    /// Tokens by id.
+    tokens: BTreeMap<String, StoredToken>,
+}
+
+/// What a delegated token grants, carried in the token itself.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct DelegatedClaims {
+    id: String,
+    name: String,
+    issued_ms: u64,
+    expires_ms: u64,
+    #[serde(flatten)]
+    policy: TokenPolicy,
+}
+
+/// Requests and body bytes of a token, counted in memory.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TokenUsage {
+    pub total_requests: u64,
+    pub requests_today: u64,
+    /// Requests rejected for exceeding the rate limit or quota.
+    pub rejected: u64,
+    /// Request and response body bytes of the admitted requests, the latter as sent.
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    /// Unix timestamp in milliseconds.
+    pub last_used_ms: u64,
+    #[serde(skip)]
+    day: u64,
+    #[serde(skip)]
+    bucket: f64,
+    #[serde(skip)]
+    bucket_updated_ms: u64,
+}
+
+impl TokenUsage {
+    /// Counts a request against `policy`, or returns after how many seconds to retry.
+    fn admit(&mut self, policy: &TokenPolicy, now_ms: u64) -> Result<(), u64> {
+        let today = now_ms / MS_PER_DAY;
+        if self.day != today {
+            self.day = today;
+            self.requests_today = 0;
+        }
+        if let Some(quota) = policy.daily_quota {
+            if self.requests_today >= quota {
+                self.rejected += 1;
+                return Err(((today + 1) * MS_PER_DAY - now_ms).div_ceil(1000));
+            }
+        }
+        if let Some(rate) = policy.requests_per_second.map(f64::from) {
+            // Token bucket holding up to one second of requests, full when first used.
+            self.bucket = if self.bucket_updated_ms == 0 {
+                rate
+            } else {
+                let elapsed = now_ms.saturating_sub(self.bucket_updated_ms) as f64 / 1000.0;
+                (self.bucket + elapsed * rate).min(rate)
+            };
+            self.bucket_updated_ms = now_ms;
+            if self.bucket < 1.0 {
+                self.rejected += 1;
+                return Err(if rate > 0.0 { ((1.0 - self.bucket) / rate).ceil() as u64 } else { 1 });
+            }
+            self.bucket -= 1.0;
+        }
+        self.requests_today += 1;
+        self.total_requests += 1;
+        self.last_used_ms = now_ms;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateTokenRequest {
+    /// Customer or purpose the token is issued to.
+    pub name: String,
+    #[serde(flatten)]
+    pub policy: TokenPolicy,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateTokenResponse {
+    pub id: String,
+    /// The token itself. It is only shown here; the node keeps a hash.
+    pub token: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub policy: TokenPolicy,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateDelegatedTokenRequest {
+    /// Auditor or team the token is delegated to.
+    pub name: String,
+    /// Route groups the token may read: `consensus`, `dkg`, `tx`, `stats`, `jobs` or `streams`.
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// Further route patterns, as in [`TokenPolicy::routes`].
+    #[serde(default)]
+    pub routes: Vec<String>,
+    #[serde(default)]
+    pub requests_per_second: Option<u32>,
+    #[serde(default)]
+    pub daily_quota: Option<u64>,
+    /// Lifetime of the token, at most 30 days.
+    pub expires_in_secs: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateDelegatedTokenResponse {
+    pub id: String,
+    /// The signed token. It is only shown here; the node keeps a hash.
+    pub token: String,
+    pub name: String,
+    /// Unix timestamp in milliseconds.
+    pub expires_ms: u64,
+    #[serde(flatten)]
+    pub policy: TokenPolicy,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TokenEntry {
+    pub id: String,
+    pub name: String,
+    pub created_ms: u64,
+    pub revoked_ms: Option<u64>,
+    pub expires_ms: Option<u64>,
+    pub delegated: bool,
+    #[serde(flatten)]
+    pub policy: TokenPolicy,
+    pub usage: TokenUsage,
+}
+
+/// Why a request was refused.
+#[derive(Debug, PartialEq, Eq)]
+enum Rejection {
+    Unauthorized(&'static str),
+    Forbidden,
+    RateLimited { retry_after_secs: u64 },
+}
+
+pub struct TokenStore {
+    path: Option<PathBuf>,
+    required: bool,
+    signing_key: [u8; 32],
+    tokens: Mutex<TokenFile>,
+    usage: Mutex<HashMap<String, TokenUsage>>,
+}
+
+fn hash_token(token: &str) -> String {
+    let mut hasher = Sha3::v256();
+    hasher.update(token.as_bytes());
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hex::encode(hash)
+}
+
+/// Keyed SHA3-256 of `payload`. SHA3 is not open to length extension, so prefixing the key is
+/// a sound MAC.
+fn sign(key: &[u8; 32], payload: &str) -> [u8; 32] {
+    let mut hasher = Sha3::v256();
+    hasher.update(key);
+    hasher.update(payload.as_bytes());
+    let mut signature = [0u8; 32];
+    hasher.finalize(&mut signature);
+    signature
+}
+
+/// Compares secrets in a time that doesn't depend on where they first differ, so response times
+/// don't reveal how much of a forged signature is right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Checks the name and route patterns of a token request, returning the trimmed name.
+fn validate(name: &str, policy: &TokenPolicy) -> Result<String, ApiError> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(ApiError::bad_request(format!(
+            "name must be between 1 and {MAX_NAME_LEN} characters"
+        )));
+    }
+    if policy.routes.iter().any(|route| !route.starts_with('/')) {
+        return Err(ApiError::bad_request("routes must start with '/'"));
+    }
+    if let Some(route) =
+        policy.routes.iter().find(|route| is_admin_route(route.trim_end_matches('*')))
+    {
+        return Err(ApiError::bad_request(format!(
+            "route '{route}' is an admin route, which API tokens can't be issued for"
+        )));
+    }
+    Ok(name)
+}
+
+impl TokenStore {
+    /// Reloads the tokens saved at `path`, starting without tokens if there are none.
+    pub fn open(path: Option<PathBuf>, required: bool) -> Self {
+        let mut tokens = path
+            .as_ref()
+            .filter(|path| path.exists())
+            .and_then(|path| {
+                match fs::read(path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|bytes| Ok(serde_json::from_slice::<TokenFile>(&bytes)?))
+                {
+                    Ok(tokens) => Some(tokens),
+                    Err(e) => {
+                        error!("Ignoring unreadable API token file {}: {:?}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .unwrap_or_default();
+        if let Some(path) = &path {
+            info!("API tokens are kept in {}", path.display());
+            if path.exists() {
+                if let Err(e) = restrict_to_owner(path) {
+                    warn!("Failed to restrict API token file {}: {:?}", path.display(), e);
+                }
+            }
+        }
+        if required {
+            info!("Public API routes require an API token");
+        }
+        let saved_key = tokens
+            .signing_key
+            .as_deref()
+            .and_then(|key| hex::decode(key).ok())
+            .and_then(|key| <[u8; 32]>::try_from(key).ok());
+        let signing_key = saved_key.unwrap_or_else(rand::random);
+        tokens.signing_key = Some(hex::encode(signing_key));
+        let store = Self {
+            path,
+            required,
+            signing_key,
+            tokens: Mutex::new(tokens),
+            usage: Mutex::new(HashMap::new()),
+        };
+        if saved_key.is_none() {
+            if let Err(e) = store.persist(&store.tokens.lock()) {
+                error!("Failed to save the API token signing key: {:?}", e);
+            }
+        }
+        store
+    }
+
+    fn persist(&self, tokens: &TokenFile) -> anyhow::Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let bytes = serde_json::to_vec_pretty(tokens)?;
+        write_private_atomically(path, |file| Ok(file.write_all(&bytes)?))
+    }
+
+    /// Saves a newly issued token, forgetting it again if it can't be saved.
+    fn insert(&self, id: &str, stored: StoredToken) -> Result<(), ApiError> {
+        let mut tokens = self.tokens.lock();
+        tokens.tokens.insert(id.to_string(), stored);
+        if let Err(e) = self.persist(&tokens) {
+            tokens.tokens.remove(id);
+            error!("Failed to save API tokens: {:?}", e);
+            return Err(ApiError::internal());
+        }
+        Ok(())
+    }
+
+    pub fn create(&self, request: CreateTokenRequest) -> Result<CreateTokenResponse, ApiError> {
+        let name = validate(&request.name, &request.policy)?;
+        let id = format!("{:016x}", rand::random::<u64>());
+        let token = format!("{TOKEN_PREFIX}{id}_{}", hex::encode(rand::random::<[u8; 32]>()));
+        let stored = StoredToken {
+            name: name.clone(),
+            hash: hash_token(&token),
+            created_ms: now_ms(),
+            revoked_ms: None,
+            expires_ms: None,
+            delegated: false,
+            policy: request.policy.clone(),
+        };
+        self.insert(&id, stored)?;
+        info!("Issued API token {} to {}", id, name);
+        Ok(CreateTokenResponse { id, token, name, policy: request.policy })
+    }
+
+    /// Mints a read-only token limited to the requested route groups and patterns.
+    pub fn create_delegated(
+        &self,
+        request: CreateDelegatedTokenRequest,
+    ) -> Result<CreateDelegatedTokenResponse, ApiError> {
+        let mut routes = request.routes;
+        for group in &request.groups {
+            let Some((_, pattern)) = ROUTE_GROUPS.iter().find(|(name, _)| name == group) else {
+                let known: Vec<_> = ROUTE_GROUPS.iter().map(|(name, _)| *name).collect();
+                return Err(ApiError::bad_request(format!(
+                    "unknown route group '{group}', expected one of: {}",
+                    known.join(", ")
+                )));
+            };
+            routes.push(pattern.to_string());
+        }
+        if routes.is_empty() {
+            return Err(ApiError::bad_request("delegated tokens need at least one group or route"));
+        }
+        if request.expires_in_secs == 0 || request.expires_in_secs > MAX_DELEGATED_LIFETIME_SECS {
+            return Err(ApiError::bad_request(format!(
+                "expires_in_secs must be between 1 and {MAX_DELEGATED_LIFETIME_SECS}"
+            )));
+        }
+        let policy = TokenPolicy {
+            routes,
+            requests_per_second: request.requests_per_second,
+            daily_quota: request.daily_quota,
+            read_only: true,
+        };
+        let name = validate(&request.name, &policy)?;
+
+        let issued_ms = now_ms();
+        let claims = DelegatedClaims {
+            id: format!("{:016x}", rand::random::<u64>()),
+            name,
+            issued_ms,
+            expires_ms: issued_ms + request.expires_in_secs * 1000,
+            policy,
+        };
+        let payload = base64::encode_config(
+            serde_json::to_vec(&claims).map_err(|_| ApiError::internal())?,
+            base64::URL_SAFE_NO_PAD,
+        );
+        let signature = hex::encode(sign(&self.signing_key, &payload));
+        let token = format!("{DELEGATED_TOKEN_PREFIX}{payload}.{signature}");
+        let stored = StoredToken {
+            name: claims.name.clone(),
+            hash: hash_token(&token),
+            created_ms: claims.issued_ms,
+            revoked_ms: None,
+            expires_ms: Some(claims.expires_ms),
+            delegated: true,
+            policy: claims.policy.clone(),
+        };
+        self.insert(&claims.id, stored)?;
+        info!(
+            "Delegated read-only API token {} to {} until {}",
+            claims.id, claims.name, claims.expires_ms
+        );
+        Ok(CreateDelegatedTokenResponse {
+            id: claims.id,
+            token,
+            name: claims.name,
+            expires_ms: claims.expires_ms,
+            policy: claims.policy,
+        })
+    }
+
+    /// The claims of delegated token `token` without its prefix, if the node signed them.
+    fn verify_delegated(&self, token: &str) -> Option<DelegatedClaims> {
+        let (payload, signature) = token.split_once('.')?;
+        let signature = hex::decode(signature).ok()?;
+        if !constant_time_eq(&sign(&self.signing_key, payload), &signature) {
+            return None;
+        }
+        let claims = base64::decode_config(payload, base64::URL_SAFE_NO_PAD).ok()?;
+        serde_json::from_slice(&claims).ok()
+    }
+
+    /// Revokes token `id`. Returns whether it existed.
+    pub fn revoke(&self, id: &str) -> Result<bool, ApiError> {
+        let mut tokens = self.tokens.lock();
+        let Some(token) = tokens.tokens.get_mut(id) else { return Ok(false) };
+        if token.revoked_ms.is_none() {
+            token.revoked_ms = Some(now_ms());
+            if let Err(e) = self.persist(&tokens) {
+                error!("Failed to save API tokens: {:?}", e);
+                return Err(ApiError::internal());
+            }
+            info!("Revoked API token {}", id);
+        }
+        Ok(true)
+    }
+
+    pub fn list(&self) -> Vec<TokenEntry> {
+        let usage = self.usage.lock();
+        self.tokens
+            .lock()
+            .tokens
+            .iter()
+            .map(|(id, token)| TokenEntry {
+                id: id.clone(),
+                name: token.name.clone(),
+                created_ms: token.created_ms,
+                revoked_ms: token.revoked_ms,
+                expires_ms: token.expires_ms,
+                delegated: token.delegated,
+                policy: token.policy.clone(),
+                usage: usage.get(id).cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// The id of `token`, read from it or from its signed claims.
+    fn token_id(&self, token: &str) -> Result<String, Rejection> {
+        match token.strip_prefix(DELEGATED_TOKEN_PREFIX) {
+            Some(rest) => Ok(self
+                .verify_delegated(rest)
+                .ok_or(Rejection::Unauthorized("Malformed or altered API token"))?
+                .id),
+            None => token
+                .strip_prefix(TOKEN_PREFIX)
+                .and_then(|rest| rest.split_once('_'))
+                .map(|(id, _)| id.to_string())
+                .ok_or(Rejection::Unauthorized("Malformed API token")),
+        }
+    }
+
+    /// Adds body bytes to the usage of token `id`.
+    fn record_bytes(&self, id: &str, direction: Direction, bytes: u64) {
+        if let Some(usage) = self.usage.lock().get_mut(id) {
+            match direction {
+                Direction::Ingress => usage.bytes_in += bytes,
+                Direction::Egress => usage.bytes_out += bytes,
+            }
+        }
+    }
+
+    /// Checks `token` for a `method` request to `route`, counting it against the token's limits.
+    fn authorize(
+        &self,
+        token: Option<&str>,
+        method: &Method,
+        route: &str,
+        now_ms: u64,
+    ) -> Result<(), Rejection> {
+        let Some(token) = token else {
+            return if self.required {
+                Err(Rejection::Unauthorized("An API token is required"))
+            } else {
+                Ok(())
+            };
+        };
+        let id = self.token_id(token)?;
+        let policy = {
+            let tokens = self.tokens.lock();
+            match tokens.tokens.get(&id) {
+                Some(stored)
+                    if constant_time_eq(stored.hash.as_bytes(), hash_token(token).as_bytes()) &&
+                        stored.revoked_ms.is_none() =>
+                {
+                    if stored.expires_ms.is_some_and(|expires_ms| now_ms >= expires_ms) {
+                        return Err(Rejection::Unauthorized("API token has expired"));
+                    }
+                    stored.policy.clone()
+                }
+                _ => return Err(Rejection::Unauthorized("Unknown or revoked API token")),
+            }
+        };
+        if !policy.allows_route(route) ||
+            (policy.read_only && !matches!(*method, Method::GET | Method::HEAD))
+        {
+            return Err(Rejection::Forbidden);
+        }
+        self.usage
+            .lock()
+            .entry(id)
+            .or_default()
+            .admit(&policy, now_ms)
+            .map_err(|retry_after_secs| Rejection::RateLimited { retry_after_secs })
+    }
+}
+
+/// The token sent as `Authorization: Bearer <token>` or `X-Api-Key: <token>`.
+fn token_of(req: &Request<Body>) -> Option<&str> {
+    let headers = req.headers();
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| headers.get(API_KEY_HEADER).and_then(|value| value.to_str().ok()))
+        .map(str::trim)
+}
+
+/// Puts the token check on the `public` routes, then adds the `admin` ones, if any, without it,
+/// so the first token can be issued through `/admin/tokens` when tokens are required. The
+/// admin routes are guarded by the admin allowlist instead.
+pub(crate) fn guard_public_routes<S>(
+    public: Router<S>,
+    admin: Option<Router<S>>,
+    store: Arc<TokenStore>,
+) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let public = public.route_layer(middleware::from_fn_with_state(store, enforce_api_tokens));
+    match admin {
+        Some(admin) => public.merge(admin),
+        None => public,
+    }
+}
+
+/// Enforces API tokens on the public routes. Must be added with `route_layer` so permissions
+/// are checked against the matched route pattern.
+pub async fn enforce_api_tokens(
+    State(store): State<Arc<TokenStore>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| req.uri().path().to_string(), |route| route.as_str().to_string());
+    let method = req.method().clone();
+    let token = token_of(&req);
+    match store.authorize(token, &method, versioning::unversioned(&route), now_ms()) {
+        Ok(()) => {
+            let Some(id) = token.and_then(|token| store.token_id(token).ok()) else {
+                return next.run(req).await;
+            };
+            let (ingress_store, ingress_id) = (store.clone(), id.clone());
+            let req = req.map(|body| {
+                count_body_bytes(body, move |bytes| {
+                    ingress_store.record_bytes(&ingress_id, Direction::Ingress, bytes)
+                })
+            });
+            next.run(req).await.map(|body| {
+                count_body_bytes(body, move |bytes| {
+                    store.record_bytes(&id, Direction::Egress, bytes)
+                })
+            })
+        }
+        Err(Rejection::Unauthorized(message)) => {
+            ApiError::new(StatusCode::UNAUTHORIZED, "unauthorized", message).into_response()
+        }
+        Err(Rejection::Forbidden) => ApiError::new(
+            StatusCode::FORBIDDEN,
+            "forbidden",
+            format!("API token is not allowed to {method} {route}"),
+        )
+        .into_response(),
+        Err(Rejection::RateLimited { retry_after_secs }) => {
+            warn!("API token request to {} rate limited", route);
+            let mut response = ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limited",
+                "API token rate limit or daily quota exceeded",
+            )
+            .into_response();
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+            response
+        }
+    }
+}
+
+// example:
+// curl -X POST http://127.0.0.1:1024/admin/tokens \
+//   -d '{"name":"acme","routes":["/consensus/*"],"requests_per_second":20,"daily_quota":100000}'
+pub async fn create_token(
+    store: Arc<TokenStore>,
+    request: CreateTokenRequest,
+) -> Result<JsonResponse<CreateTokenResponse>, ApiError> {
+    store.create(request).map(JsonResponse)
+}
+
+// example:
+// curl -X POST http://127.0.0.1:1024/admin/tokens/delegated \
+//   -d '{"name":"auditor","groups":["consensus","dkg"],"expires_in_secs":604800}'
+pub async fn create_delegated_token(
+    store: Arc<TokenStore>,
+    request: CreateDelegatedTokenRequest,
+) -> Result<JsonResponse<CreateDelegatedTokenResponse>, ApiError> {
+    store.create_delegated(request).map(JsonResponse)
+}
+
+// example:
+// curl http://127.0.0.1:1024/admin/tokens
+pub async fn list_tokens(store: Arc<TokenStore>) -> JsonResponse<Vec<TokenEntry>> {
+    JsonResponse(store.list())
+}
+
+// example:
+// curl -X DELETE http://127.0.0.1:1024/admin/tokens/0123456789abcdef
+pub async fn revoke_token(store: Arc<TokenStore>, id: String) -> Result<StatusCode, ApiError> {
+    if store.revoke(&id)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::not_found(format!("API token {id} not found")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(store: &TokenStore, policy: TokenPolicy) -> CreateTokenResponse {
+        store.create(CreateTokenRequest { name: "acme".to_string(), policy }).unwrap()
+    }
+
+    #[test]
+    fn checks_tokens_and_routes() {
+        let store = TokenStore::open(None, true);
+        assert_eq!(
+            store.authorize(None, &Method::GET, "/dkg/status", 1),
+            Err(Rejection::Unauthorized("An API token is required"))
+        );
+
+        let policy = TokenPolicy { routes: vec!["/consensus/*".to_string()], ..Default::default() };
+        let created = issue(&store, policy);
+        assert_eq!(
+            store.authorize(Some(&created.token), &Method::GET, "/consensus/qc/:epoch/:round", 1),
+            Ok(())
+        );
+        assert_eq!(
+            store.authorize(Some(&created.token), &Method::GET, "/dkg/status", 1),
+            Err(Rejection::Forbidden)
+        );
+        let forged = format!("{TOKEN_PREFIX}{}_00", created.id);
+        assert!(matches!(
+            store.authorize(Some(&forged), &Method::GET, "/consensus/qc/:epoch/:round", 1),
+            Err(Rejection::Unauthorized(_))
+        ));
+
+        assert!(store.revoke(&created.id).unwrap());
+        assert!(matches!(
+            store.authorize(Some(&created.token), &Method::GET, "/consensus/qc/:epoch/:round", 1),
+            Err(Rejection::Unauthorized(_))
+        ));
+        assert_eq!(store.list()[0].usage.total_requests, 1);
+    }
+
+    #[test]
+    fn tokens_never_cover_admin_routes() {
+        let store = TokenStore::open(None, true);
+        for route in ["/admin/*", "/admin/tokens", "/set_failpoint", "/mem_prof*"] {
+            let policy = TokenPolicy { routes: vec![route.to_string()], ..Default::default() };
+            let request = CreateTokenRequest { name: "acme".to_string(), policy };
+            assert!(store.create(request).is_err(), "{route} was accepted");
+        }
+
+        let unscoped = issue(&store, TokenPolicy::default());
+        let wildcard =
+            issue(&store, TokenPolicy { routes: vec!["/*".to_string()], ..Default::default() });
+        for token in [&unscoped.token, &wildcard.token] {
+            assert_eq!(store.authorize(Some(token), &Method::GET, "/dkg/status", 1), Ok(()));
+            assert_eq!(
+                store.authorize(Some(token), &Method::POST, "/admin/tokens", 1),
+                Err(Rejection::Forbidden)
+            );
+            assert_eq!(
+                store.authorize(Some(token), &Method::POST, "/set_failpoint", 1),
+                Err(Rejection::Forbidden)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn first_token_can_be_issued_when_tokens_are_required() {
+        let store = Arc::new(TokenStore::open(None, true));
+        let public = Router::new().route("/dkg/status", axum::routing::get(|| async { "status" }));
+        let admin =
+            Router::new().route("/admin/tokens", axum::routing::post(|| async { "issued" }));
+        let app = guard_public_routes(public, Some(admin), store);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let status = client.get(format!("http://{address}/dkg/status")).send().await.unwrap();
+        assert_eq!(status.status(), StatusCode::UNAUTHORIZED);
+        let issued = client.post(format!("http://{address}/admin/tokens")).send().await.unwrap();
+        assert_eq!(issued.status(), StatusCode::OK);
+        assert_eq!(issued.text().await.unwrap(), "issued");
+    }
+
+    #[test]
+    fn counts_body_bytes_per_token() {
+        let store = TokenStore::open(None, true);
+        let created = issue(&store, TokenPolicy::default());
+        assert_eq!(
+            store.authorize(Some(&created.token), &Method::POST, "/tx/submit_tx", 1),
+            Ok(())
+        );
+        let id = store.token_id(&created.token).unwrap();
+        assert_eq!(id, created.id);
+        store.record_bytes(&id, Direction::Ingress, 120);
+        store.record_bytes(&id, Direction::Egress, 4000);
+        store.record_bytes(&id, Direction::Egress, 96);
+        store.record_bytes("unknown", Direction::Egress, 1);
+
+        let usage = &store.list()[0].usage;
+        assert_eq!((usage.bytes_in, usage.bytes_out), (120, 4096));
+    }
+
+    #[test]
+    fn delegated_tokens_are_read_only_scoped_and_expire() {
+        let store = TokenStore::open(None, true);
+        let created = store
+            .create_delegated(CreateDelegatedTokenRequest {
+                name: "auditor".to_string(),
+                groups: vec!["dkg".to_string()],
+                routes: vec![],
+                requests_per_second: None,
+                daily_quota: None,
+                expires_in_secs: 60,
+            })
+            .unwrap();
+        assert!(created.policy.read_only);
+        let now = created.expires_ms - 1000;
+        assert_eq!(store.authorize(Some(&created.token), &Method::GET, "/dkg/status", now), Ok(()));
+        assert_eq!(
+            store.authorize(Some(&created.token), &Method::POST, "/dkg/status", now),
+            Err(Rejection::Forbidden)
+        );
+        assert_eq!(
+            store.authorize(Some(&created.token), &Method::GET, "/consensus/sync_status", now),
+            Err(Rejection::Forbidden)
+        );
+        assert_eq!(
+            store.authorize(Some(&created.token), &Method::GET, "/dkg/status", created.expires_ms),
+            Err(Rejection::Unauthorized("API token has expired"))
+        );
+
+        // Widening the scope in the claims breaks the signature.
+        let signed = created.token.strip_prefix(DELEGATED_TOKEN_PREFIX).unwrap();
+        let mut claims = store.verify_delegated(signed).unwrap();
+        let (_, signature) = signed.split_once('.').unwrap();
+        claims.policy.routes = vec!["/consensus/*".to_string()];
+        let altered =
+            base64::encode_config(serde_json::to_vec(&claims).unwrap(), base64::URL_SAFE_NO_PAD);
+        assert_eq!(
+            store.authorize(
+                Some(&format!("{DELEGATED_TOKEN_PREFIX}{altered}.{signature}")),
+                &Method::GET,
+                "/consensus/sync_status",
+                now
+            ),
+            Err(Rejection::Unauthorized("Malformed or altered API token"))
+        );
+
+        let unknown_group = CreateDelegatedTokenRequest {
+            name: "auditor".to_string(),
+            groups: vec!["admin".to_string()],
+            routes: vec![],
+            requests_per_second: None,
+            daily_quota: None,
+            expires_in_secs: 60,
+        };
+        assert!(store.create_delegated(unknown_group).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn token_file_is_readable_by_the_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("tokens_test_{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("api_tokens.json");
+        let mode = |path: &PathBuf| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        drop(TokenStore::open(Some(path.clone()), false));
+        assert_eq!(mode(&path), 0o600);
+
+        // A file saved before tokens were written 0600 is tightened when it is loaded.
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        let store = TokenStore::open(Some(path.clone()), false);
+        assert_eq!(mode(&path), 0o600);
+        issue(&store, TokenPolicy::default());
+        assert_eq!(mode(&path), 0o600);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn enforces_rate_limit_and_daily_quota() {
+        let policy = TokenPolicy {
+            requests_per_second: Some(2),
+            daily_quota: Some(3),
+            ..Default::default()
+        };
+        let mut usage = TokenUsage::default();
+        let now = 10 * MS_PER_DAY;
+        assert_eq!(usage.admit(&policy, now), Ok(()));
+        assert_eq!(usage.admit(&policy, now), Ok(()));
+        assert_eq!(usage.admit(&policy, now), Err(1));
+        // Half a second refills one request.
+        assert_eq!(usage.admit(&policy, now + 500), Ok(()));
+        // The quota is used up until the next UTC day.
+        assert_eq!(usage.admit(&policy, now + 5000), Err(MS_PER_DAY.div_ceil(1000) - 5));
+        assert_eq!(usage.admit(&policy, now + MS_PER_DAY), Ok(()));
+        assert_eq!(usage.rejected, 2);
+    }
+}
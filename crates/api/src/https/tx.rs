@@ -1,6 +1,124 @@
-use axum::{http::StatusCode, response::Json as JsonResponse};
-use gaptos::{aptos_crypto::HashValue, aptos_logger::info};
+use crate::https::{
+    admission::{AdmissionHook, TxSummary},
+    block_feed::committed_tx,
+    consensus::{
+        parse_timeout, LedgerInfoResponse, DEFAULT_LONG_POLL_TIMEOUT, MAX_LONG_POLL_TIMEOUT,
+    },
+    dkg::DkgState,
+    error::{ApiError, ErrorResponse},
+    tx_results::TX_RESULTS,
+};
+use aptos_consensus::{
+    consensusdb::{ConsensusDB, LedgerInfoSchema, TxStatus, TxnPosition},
+    quorum_store::quorum_store_db::QuorumStoreDB,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json as JsonResponse,
+};
+use block_buffer_manager::TxPool;
+use gaptos::{
+    api_types::{
+        account::{ExternalAccountAddress, ExternalChainId},
+        VerifiedTxn,
+    },
+    aptos_crypto::HashValue,
+    aptos_infallible::Mutex,
+    aptos_logger::{error, info},
+    move_core_types::account_address::AccountAddress,
+};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tiny_keccak::{Hasher, Keccak};
+use tokio::{sync::oneshot, time::Instant};
+
+/// Submitters waiting for their transactions to commit, fed by the consensus commit handler.
+pub(crate) static TX_COMMIT_WAITERS: Lazy<TxCommitWaiters> = Lazy::new(TxCommitWaiters::default);
+
+/// Commit notifications older than a waiter are never replayed, so waiters must be registered
+/// before the transaction reaches the pool.
+#[derive(Default)]
+pub(crate) struct TxCommitWaiters {
+    waiters: Mutex<HashMap<HashValue, Vec<oneshot::Sender<u64>>>>,
+}
+
+impl TxCommitWaiters {
+    /// Returns a receiver resolved with the block number that commits `hash`.
+    fn register(&self, hash: HashValue) -> oneshot::Receiver<u64> {
+        let (tx, rx) = oneshot::channel();
+        self.waiters.lock().entry(hash).or_default().push(tx);
+        rx
+    }
+
+    /// Drops waiters whose requests have gone away, e.g. after a timeout.
+    fn prune(&self, hash: &HashValue) {
+        let mut waiters = self.waiters.lock();
+        if let Some(senders) = waiters.get_mut(hash) {
+            senders.retain(|s| !s.is_closed());
+            if senders.is_empty() {
+                waiters.remove(hash);
+            }
+        }
+    }
+
+    pub(crate) fn notify_committed(
+        &self,
+        block_number: u64,
+        hashes: impl IntoIterator<Item = HashValue>,
+    ) {
+        let mut waiters = self.waiters.lock();
+        if waiters.is_empty() {
+            return;
+        }
+        for hash in hashes {
+            for sender in waiters.remove(&hash).into_iter().flatten() {
+                let _ = sender.send(block_number);
+            }
+        }
+    }
+}
+
+/// Hands transactions received over HTTP to the node's transaction pool.
+pub struct TxSubmitter {
+    pool: Arc<dyn TxPool>,
+    chain_id: u64,
+    /// External policy consulted before a transaction enters the pool, if configured.
+    admission: Option<AdmissionHook>,
+}
+
+impl TxSubmitter {
+    pub fn new(pool: Arc<dyn TxPool>, chain_id: u64, admission: Option<AdmissionHook>) -> Self {
+        Self { pool, chain_id, admission }
+    }
+
+    /// Checks `tx` against the admission policy, if any, and hands it to the pool.
+    pub(crate) async fn add(&self, hash: HashValue, tx: Vec<u8>) -> Result<(), ApiError> {
+        if let Some(admission) = &self.admission {
+            admission.check(&TxSummary::new(hash, self.chain_id, &tx)).await?;
+        }
+        // The pool decodes the signed transaction and recovers its sender and nonce itself.
+        let txn = VerifiedTxn::new(
+            tx.clone(),
+            ExternalAccountAddress::new([0u8; 32]),
+            0,
+            ExternalChainId::new(self.chain_id),
+        );
+        if !self.pool.add_external_txn(txn) {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "tx_rejected",
+                "Transaction was rejected by the pool",
+            ));
+        }
+        TX_RESULTS.record_pending([(hash, tx)]);
+        Ok(())
+    }
+}
+
+/// Upper bound on the number of transactions in one `/tx/submit_batch` request.
+const MAX_SUBMIT_BATCH: usize = 1000;
 
 #[derive(Serialize, Deserialize)]
 pub struct TxRequest {
@@ -9,28 +127,369 @@ pub struct TxRequest {
     //    authenticator: (),
 }
 
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitFor {
+    Committed,
+}
+
+/// Query parameters of `/tx/submit_tx`.
+#[derive(Deserialize, Debug, Default)]
+pub struct SubmitTxQuery {
+    /// `committed` holds the request until the transaction is committed.
+    pub wait_for: Option<WaitFor>,
+    /// How long to wait, e.g. `30s`, `500ms` or `10` (seconds).
+    pub timeout: Option<String>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SubmitResponse {
     hash: [u8; 32],
+    /// Set when `wait_for=committed` was requested and the commit was observed in time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    committed: Option<CommitInfo>,
     //    Public key and signature to authenticate
     //    authenticator: (),
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct TxBatchRequest {
+    txs: Vec<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SubmitBatchResponse {
+    /// One per submitted transaction, in request order.
+    results: Vec<BatchTxResult>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatchTxResult {
+    hash: [u8; 32],
+    /// Why the transaction was not accepted, as `/tx/submit_tx` would have answered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    /// Block that includes the transaction.
+    pub block_number: u64,
+    /// First commit certificate covering that block; its `round` is the commit round. Absent if
+    /// the certificate was not persisted before the timeout.
+    pub certificate: Option<LedgerInfoResponse>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TxResponse {
+    /// The transaction as submitted; empty when it is unknown or no longer stored.
     pub tx: Vec<u8>,
-    // tx status
+    /// Absent when the transaction is unknown or its result has expired from the cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<TxState>,
+    /// Block that committed the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TxState {
+    Pending,
+    Committed,
+}
+
+impl TxState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Committed => "committed",
+        }
+    }
+}
+
+/// Hash of an EIP-2718 encoded transaction, as reported by the execution layer.
+pub(crate) fn tx_hash(bytes: &[u8]) -> HashValue {
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    HashValue::new(hash)
 }
 
 // example:
 // curl -X POST -H "Content-Type:application/json" -d '{"tx": [1, 2, 3, 4]}' https://127.0.0.1:1024/tx/submit_tx
-pub async fn submit_tx(_request: TxRequest) -> Result<JsonResponse<SubmitResponse>, StatusCode> {
-    todo!()
+//
+// With `?wait_for=committed[&timeout=30s]` the request is held until the transaction is committed
+// or the timeout elapses. On timeout the response has no `committed` field; the transaction may
+// still commit later.
+//
+// With an admission webhook configured, the transaction is first checked against the external
+// admission policy and rejected with 403 `tx_denied` if the policy denies it.
+pub async fn submit_tx(
+    submitter: Option<Arc<TxSubmitter>>,
+    dkg_state: Arc<DkgState>,
+    request: TxRequest,
+    query: SubmitTxQuery,
+) -> Result<JsonResponse<SubmitResponse>, ApiError> {
+    let Some(submitter) = submitter else {
+        return Err(ApiError::not_implemented("Transaction submission is not enabled on this node"));
+    };
+    let timeout = query
+        .timeout
+        .as_deref()
+        .map(parse_timeout)
+        .transpose()
+        .map_err(ApiError::bad_request)?
+        .unwrap_or(DEFAULT_LONG_POLL_TIMEOUT)
+        .min(MAX_LONG_POLL_TIMEOUT);
+    // Checked up front so a waiting client never gets a 503 after its transaction went out.
+    let consensus_db = match query.wait_for {
+        Some(WaitFor::Committed) => Some(dkg_state.require_consensus_db()?.clone()),
+        None => None,
+    };
+
+    let hash = tx_hash(&request.tx);
+    let commit_rx = consensus_db.is_some().then(|| TX_COMMIT_WAITERS.register(hash));
+    if let Err(e) = submitter.add(hash, request.tx).await {
+        drop(commit_rx);
+        TX_COMMIT_WAITERS.prune(&hash);
+        return Err(e);
+    }
+    info!("submitted transaction {}, wait_for={:?}", hash, query.wait_for);
+
+    let (Some(consensus_db), Some(commit_rx)) = (consensus_db, commit_rx) else {
+        return Ok(JsonResponse(SubmitResponse { hash: *hash, committed: None }));
+    };
+    let deadline = Instant::now() + timeout;
+    let committed = match tokio::time::timeout_at(deadline, commit_rx).await {
+        Ok(Ok(block_number)) => {
+            // Transactions are reported at pre-commit; the ledger info lands shortly after.
+            let mut commits = consensus_db.subscribe_commits();
+            let _ =
+                tokio::time::timeout_at(deadline, commits.wait_for(|b| *b >= block_number)).await;
+            Some(commit_info(&consensus_db, block_number))
+        }
+        _ => {
+            TX_COMMIT_WAITERS.prune(&hash);
+            None
+        }
+    };
+    Ok(JsonResponse(SubmitResponse { hash: *hash, committed }))
+}
+
+// example:
+// curl -X POST -H "Content-Type:application/json" -d '{"txs": [[1, 2], [3, 4]]}' https://127.0.0.1:1024/v1/tx/submit_batch
+//
+// Submits up to 1000 transactions in order, each as `/tx/submit_tx` without waiting would. The
+// request fails only when the batch itself is invalid; transactions that are not accepted carry
+// the error `/tx/submit_tx` would have returned, the others went to the pool.
+pub async fn submit_batch(
+    submitter: Option<Arc<TxSubmitter>>,
+    request: TxBatchRequest,
+) -> Result<JsonResponse<SubmitBatchResponse>, ApiError> {
+    let Some(submitter) = submitter else {
+        return Err(ApiError::not_implemented("Transaction submission is not enabled on this node"));
+    };
+    if request.txs.is_empty() {
+        return Err(ApiError::bad_request("Empty batch"));
+    }
+    if request.txs.len() > MAX_SUBMIT_BATCH {
+        return Err(ApiError::bad_request(format!("Batch exceeds {MAX_SUBMIT_BATCH} transactions")));
+    }
+
+    let mut results = Vec::with_capacity(request.txs.len());
+    for tx in request.txs {
+        let hash = tx_hash(&tx);
+        let error = submitter.add(hash, tx).await.err().map(ErrorResponse::from);
+        results.push(BatchTxResult { hash: *hash, error });
+    }
+    let rejected = results.iter().filter(|result| result.error.is_some()).count();
+    info!("submitted batch of {} transactions, {} not accepted", results.len(), rejected);
+    Ok(JsonResponse(SubmitBatchResponse { results }))
+}
+
+/// Looks up the first commit certificate at or above `block_number`.
+fn commit_info(consensus_db: &ConsensusDB, block_number: u64) -> CommitInfo {
+    const LOOKAHEAD_BLOCKS: u64 = 1024;
+    let certificate = consensus_db
+        .get_range::<LedgerInfoSchema>(&block_number, &(block_number + LOOKAHEAD_BLOCKS))
+        .map_err(|e| error!("Failed to read ledger infos from {}: {:?}", block_number, e))
+        .ok()
+        .and_then(|infos| infos.into_iter().next())
+        .map(|(_, ledger_info)| LedgerInfoResponse::from(&ledger_info));
+    CommitInfo { block_number, certificate }
 }
 
 // example:
 // curl https://127.0.0.1:1024/tx/get_tx_by_hash/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
-pub async fn get_tx_by_hash(request: HashValue) -> Result<JsonResponse<TxResponse>, StatusCode> {
+//
+// Answers with the status and block of transactions submitted or committed recently, from the
+// transaction result cache (see `tx_results`), and with the transaction itself.
+pub async fn get_tx_by_hash(
+    dkg_state: Arc<DkgState>,
+    quorum_store_db: Option<Arc<QuorumStoreDB>>,
+    request: HashValue,
+) -> Result<JsonResponse<TxResponse>, ApiError> {
     info!("get transaction by hash {}", request);
-    Ok(JsonResponse(TxResponse { tx: vec![] }))
+    Ok(JsonResponse(lookup_tx(&request, dkg_state.consensus_db(), quorum_store_db.as_ref())))
+}
+
+/// What the transaction result cache knows of `hash`, shared by the HTTP and gRPC APIs. The bytes
+/// of a committed transaction are read from its block, which needs both DBs.
+pub(crate) fn lookup_tx(
+    hash: &HashValue,
+    consensus_db: Option<&Arc<ConsensusDB>>,
+    quorum_store_db: Option<&Arc<QuorumStoreDB>>,
+) -> TxResponse {
+    let (status, block_number, tx) = match TX_RESULTS.get(hash).map(|result| result.status) {
+        Some(TxStatus::Pending) => (Some(TxState::Pending), None, TX_RESULTS.submitted_tx(hash)),
+        Some(TxStatus::Committed { block_number }) => {
+            let tx = match (consensus_db, quorum_store_db) {
+                (Some(consensus_db), Some(quorum_store_db)) => {
+                    committed_tx(consensus_db.clone(), quorum_store_db.clone(), block_number, *hash)
+                        .map_err(|e| error!("Failed to read transaction {}: {}", hash, e))
+                        .ok()
+                        .flatten()
+                }
+                _ => None,
+            };
+            (Some(TxState::Committed), Some(block_number), tx)
+        }
+        None => (None, None, None),
+    };
+    TxResponse { tx: tx.unwrap_or_default(), status, block_number }
+}
+
+const DEFAULT_TX_LIST_LIMIT: usize = 100;
+const MAX_TX_LIST_LIMIT: usize = 1000;
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Query parameters of `/tx/list`.
+#[derive(Deserialize, Debug, Default)]
+pub struct TxListQuery {
+    /// `next_cursor` of the previous page; the page starts right after it.
+    pub after: Option<String>,
+    /// Only transactions sent by this account, e.g. `0x1`.
+    pub sender: Option<String>,
+    pub limit: Option<usize>,
+    /// `asc` (oldest first, the default) or `desc` (newest first).
+    #[serde(default)]
+    pub order: ListOrder,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ListedTx {
+    pub hash: String, // hex encoded
+    pub sender: String,
+    pub sequence_number: u64,
+    pub block_number: u64,
+    /// Index of the transaction among the user transactions of its block.
+    pub index: u32,
+    pub cursor: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TxListResponse {
+    pub transactions: Vec<ListedTx>,
+    /// Pass as `after` to get the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// A cursor is the position of a transaction in the chain, `<block_number>-<index>`, so it
+/// stays valid while new blocks commit.
+fn format_cursor((block_number, index): TxnPosition) -> String {
+    format!("{block_number}-{index}")
+}
+
+fn parse_cursor(cursor: &str) -> Result<TxnPosition, String> {
+    cursor
+        .split_once('-')
+        .and_then(|(block_number, index)| Some((block_number.parse().ok()?, index.parse().ok()?)))
+        .ok_or_else(|| format!("invalid cursor '{cursor}', expected <block_number>-<index>"))
+}
+
+// example:
+// curl "https://127.0.0.1:1024/tx/list?sender=0x1&limit=50"
+// curl "https://127.0.0.1:1024/tx/list?sender=0x1&limit=50&after=1024-3"
+//
+// Lists committed user transactions in chain order, `?order=desc` for newest first. Pages are
+// chained through `next_cursor`; a cursor keeps its place however many blocks commit meanwhile.
+pub async fn list_txs(
+    State(dkg_state): State<Arc<DkgState>>,
+    Query(query): Query<TxListQuery>,
+) -> Result<JsonResponse<TxListResponse>, ApiError> {
+    let consensus_db = dkg_state.require_consensus_db()?;
+    let after =
+        query.after.as_deref().map(parse_cursor).transpose().map_err(ApiError::bad_request)?;
+    let sender = query
+        .sender
+        .as_deref()
+        .map(|sender| {
+            AccountAddress::from_hex_literal(sender)
+                .map_err(|_| ApiError::bad_request(format!("invalid sender '{sender}'")))
+        })
+        .transpose()?;
+    let limit = query.limit.unwrap_or(DEFAULT_TX_LIST_LIMIT).clamp(1, MAX_TX_LIST_LIMIT);
+
+    // One extra entry tells whether another page follows.
+    let mut txns = consensus_db
+        .list_committed_txns(sender, after, query.order == ListOrder::Desc, limit + 1)
+        .map_err(|e| {
+            error!("Failed to list committed transactions: {:?}", e);
+            ApiError::internal()
+        })?;
+    let has_more = txns.len() > limit;
+    txns.truncate(limit);
+    let transactions: Vec<ListedTx> = txns
+        .into_iter()
+        .map(|(position, txn)| ListedTx {
+            hash: hex::encode(txn.hash.as_ref()),
+            sender: txn.sender.to_hex_literal(),
+            sequence_number: txn.sequence_number,
+            block_number: position.0,
+            index: position.1,
+            cursor: format_cursor(position),
+        })
+        .collect();
+    let next_cursor = if has_more { transactions.last().map(|tx| tx.cursor.clone()) } else { None };
+    Ok(JsonResponse(TxListResponse { transactions, next_cursor }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn waiters_are_notified_once() {
+        let waiters = TxCommitWaiters::default();
+        let hash = tx_hash(&[1, 2, 3]);
+        let rx = waiters.register(hash);
+        waiters.notify_committed(7, [HashValue::zero(), hash]);
+        assert_eq!(rx.await.unwrap(), 7);
+        assert!(waiters.waiters.lock().is_empty());
+    }
+
+    #[test]
+    fn pruning_drops_abandoned_waiters() {
+        let waiters = TxCommitWaiters::default();
+        let hash = tx_hash(&[4]);
+        drop(waiters.register(hash));
+        waiters.prune(&hash);
+        assert!(waiters.waiters.lock().is_empty());
+    }
+
+    #[test]
+    fn cursors_round_trip() {
+        assert_eq!(parse_cursor(&format_cursor((1024, 3))), Ok((1024, 3)));
+        assert!(parse_cursor("1024").is_err());
+        assert!(parse_cursor("1024-x").is_err());
+    }
 }
@@ -0,0 +1,293 @@
+//! Transaction result cache (hash → status and block) answering `/tx/get_tx_by_hash`.
+//!
+//! Transactions are recorded as pending when submitted through the API and as committed when
+//! consensus commits them, however they reached the pool. Results are kept in memory for
+//! `tx_cache.ttl_secs` of the API config after their last update (an hour by default). With
+//! `tx_cache.persist` set, they are also written to the ConsensusDB `tx_result`
+//! column family and expired ones are pruned from it periodically, so lookups keep answering
+//! for recent transactions across restarts. Commits the node unwinds are forgotten.
+//!
+//! The bytes of transactions submitted through the API are kept in memory until they commit;
+//! committed transactions are read back from their block.
+
+use aptos_consensus::consensusdb::{ConsensusDB, TxResult, TxStatus};
+use gaptos::{
+    aptos_crypto::HashValue,
+    aptos_infallible::Mutex,
+    aptos_logger::{error, info, warn},
+};
+use lru::LruCache;
+use once_cell::sync::{Lazy, OnceCell};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::broadcast::error::RecvError;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(3600);
+/// Results kept in memory; when persisted, older ones are still found on disk.
+const MEMORY_CAPACITY: usize = 100_000;
+/// Interval between prunings of the persisted results.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+
+pub(crate) static TX_RESULTS: Lazy<TxResultCache> = Lazy::new(|| TxResultCache::new(DEFAULT_TTL));
+
+pub(crate) struct TxResultCache {
+    /// Seconds a result is kept after its last update, set by the API server from its config.
+    ttl_secs: AtomicU64,
+    memory: Mutex<LruCache<HashValue, TxResult>>,
+    /// Bytes of the pending transactions submitted through the API.
+    submitted: Mutex<LruCache<HashValue, Vec<u8>>>,
+    /// Where results are persisted, once the API server has turned persistence on.
+    db: OnceCell<Arc<ConsensusDB>>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+impl TxResultCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl_secs: AtomicU64::new(ttl.as_secs()),
+            memory: Mutex::new(LruCache::new(MEMORY_CAPACITY)),
+            submitted: Mutex::new(LruCache::new(MEMORY_CAPACITY)),
+            db: OnceCell::new(),
+        }
+    }
+
+    pub(crate) fn set_ttl(&self, ttl: Duration) {
+        self.ttl_secs.store(ttl.as_secs(), Ordering::Relaxed);
+    }
+
+    fn ttl_secs(&self) -> u64 {
+        self.ttl_secs.load(Ordering::Relaxed)
+    }
+
+    /// Writes results to `consensus_db` from now on and prunes the expired ones every
+    /// [`PRUNE_INTERVAL`]. Runs until the process exits.
+    pub(crate) async fn persist(&'static self, consensus_db: Arc<ConsensusDB>) {
+        if self.db.set(consensus_db.clone()).is_err() {
+            return;
+        }
+        info!("Persisting transaction results for {}s", self.ttl_secs());
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let expired_before = now_secs().saturating_sub(self.ttl_secs());
+            let db = consensus_db.clone();
+            match tokio::task::spawn_blocking(move || db.prune_tx_results(expired_before)).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(pruned)) => info!("Pruned {} expired transaction results", pruned),
+                Ok(Err(e)) => error!("Failed to prune transaction results: {:?}", e),
+                Err(e) => error!("Transaction result pruning panicked: {:?}", e),
+            }
+        }
+    }
+
+    /// Records transactions that entered the pool, with their bytes. A result already committed
+    /// is kept, whether it is still in memory or only on disk.
+    pub(crate) fn record_pending(&self, txs: impl IntoIterator<Item = (HashValue, Vec<u8>)>) {
+        let result = TxResult { status: TxStatus::Pending, updated_at_secs: now_secs() };
+        let txs: Vec<(HashValue, Vec<u8>)> = txs.into_iter().collect();
+        // Results not in memory are looked up on disk before the locks are taken.
+        let not_in_memory: Vec<HashValue> = {
+            let memory = self.memory.lock();
+            txs.iter().map(|(hash, _)| *hash).filter(|hash| !memory.contains(hash)).collect()
+        };
+        let committed_on_disk: HashSet<HashValue> =
+            not_in_memory.into_iter().filter(|hash| self.committed_on_disk(hash)).collect();
+
+        let mut memory = self.memory.lock();
+        let mut submitted = self.submitted.lock();
+        let mut results = Vec::new();
+        for (hash, tx) in txs {
+            let committed = match memory.peek(&hash) {
+                Some(cached) => cached.status != TxStatus::Pending,
+                None => committed_on_disk.contains(&hash),
+            };
+            if !committed {
+                memory.put(hash, result);
+                submitted.put(hash, tx);
+                results.push((hash, result));
+            }
+        }
+        drop((memory, submitted));
+        self.store(&results);
+    }
+
+    /// Records the transactions committed in `block_number`.
+    pub(crate) fn record_committed(
+        &self,
+        block_number: u64,
+        hashes: impl IntoIterator<Item = HashValue>,
+    ) {
+        let result =
+            TxResult { status: TxStatus::Committed { block_number }, updated_at_secs: now_secs() };
+        let results: Vec<(HashValue, TxResult)> =
+            hashes.into_iter().map(|hash| (hash, result)).collect();
+        let mut memory = self.memory.lock();
+        let mut submitted = self.submitted.lock();
+        for (hash, result) in &results {
+            memory.put(*hash, *result);
+            submitted.pop(hash);
+        }
+        drop((memory, submitted));
+        self.store(&results);
+    }
+
+    /// Forgets the commits of blocks after `block_number`, which the node unwound to. The DB
+    /// drops its copies in the unwind itself.
+    pub(crate) fn forget_commits_after(&self, block_number: u64) {
+        let mut memory = self.memory.lock();
+        let unwound: Vec<HashValue> = memory
+            .iter()
+            .filter(|(_, result)| {
+                matches!(result.status, TxStatus::Committed { block_number: committed }
+                    if committed > block_number)
+            })
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in &unwound {
+            memory.pop(hash);
+        }
+        if !unwound.is_empty() {
+            info!(
+                "Forgot {} transaction results unwound past block {}",
+                unwound.len(),
+                block_number
+            );
+        }
+    }
+
+    /// Forgets the unwound commits of every unwind of `consensus_db`. Runs until the node shuts
+    /// down.
+    pub(crate) async fn follow_unwinds(&'static self, consensus_db: Arc<ConsensusDB>) {
+        let mut unwinds = consensus_db.subscribe_unwinds();
+        loop {
+            match unwinds.recv().await {
+                Ok(block_number) => self.forget_commits_after(block_number),
+                Err(RecvError::Lagged(missed)) => {
+                    // The missed targets are unknown, so no remembered commit can be trusted
+                    warn!(
+                        "Missed {} unwinds, forgetting every committed transaction result",
+                        missed
+                    );
+                    self.forget_commits_after(0);
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// The bytes of the pending transaction `hash`, when it was submitted through the API.
+    pub(crate) fn submitted_tx(&self, hash: &HashValue) -> Option<Vec<u8>> {
+        self.submitted.lock().peek(hash).cloned()
+    }
+
+    /// The latest unexpired result of `hash`, from memory or else from disk.
+    pub(crate) fn get(&self, hash: &HashValue) -> Option<TxResult> {
+        let expired_before = now_secs().saturating_sub(self.ttl_secs());
+        let cached = self.memory.lock().get(hash).copied();
+        let result = match (cached, self.db.get()) {
+            (Some(result), _) => Some(result),
+            (None, Some(db)) => db
+                .get_tx_result(hash)
+                .map_err(|e| error!("Failed to read the result of transaction {}: {:?}", hash, e))
+                .ok()
+                .flatten(),
+            (None, None) => None,
+        };
+        result.filter(|result| result.updated_at_secs >= expired_before)
+    }
+
+    /// Whether the persisted result of `hash` is a commit.
+    fn committed_on_disk(&self, hash: &HashValue) -> bool {
+        let Some(db) = self.db.get() else { return false };
+        db.get_tx_result(hash)
+            .map_err(|e| error!("Failed to read the result of transaction {}: {:?}", hash, e))
+            .ok()
+            .flatten()
+            .is_some_and(|result| result.status != TxStatus::Pending)
+    }
+
+    fn store(&self, results: &[(HashValue, TxResult)]) {
+        let Some(db) = self.db.get() else { return };
+        if let Err(e) = db.put_tx_results(results) {
+            warn!("Failed to persist {} transaction results: {:?}", results.len(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn committed_results_are_not_reset_to_pending() {
+        let cache = TxResultCache::new(DEFAULT_TTL);
+        let (hash, other) = (HashValue::random(), HashValue::random());
+        cache.record_pending([(hash, vec![1])]);
+        assert_eq!(cache.get(&hash).map(|result| result.status), Some(TxStatus::Pending));
+        assert_eq!(cache.submitted_tx(&hash), Some(vec![1]));
+
+        cache.record_committed(9, [hash]);
+        cache.record_pending([(hash, vec![1]), (other, vec![2])]);
+        assert_eq!(
+            cache.get(&hash).map(|result| result.status),
+            Some(TxStatus::Committed { block_number: 9 })
+        );
+        assert_eq!(cache.submitted_tx(&hash), None);
+        assert_eq!(cache.get(&other).map(|result| result.status), Some(TxStatus::Pending));
+    }
+
+    #[test]
+    fn persisted_commits_are_not_reset_to_pending() {
+        let dir = std::env::temp_dir().join(format!("tx_results_test_{}", rand::random::<u64>()));
+        let cache = TxResultCache::new(DEFAULT_TTL);
+        let _ = cache.db.set(Arc::new(ConsensusDB::new(&dir, &std::path::PathBuf::new())));
+        let hash = HashValue::random();
+        cache.record_committed(4, [hash]);
+        // Evicted from memory, e.g. after a restart
+        cache.memory.lock().pop(&hash);
+
+        cache.record_pending([(hash, vec![1])]);
+        assert_eq!(
+            cache.get(&hash).map(|result| result.status),
+            Some(TxStatus::Committed { block_number: 4 })
+        );
+        drop(cache);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unwound_commits_are_forgotten() {
+        let cache = TxResultCache::new(DEFAULT_TTL);
+        let (kept, unwound, pending) =
+            (HashValue::random(), HashValue::random(), HashValue::random());
+        cache.record_committed(3, [kept]);
+        cache.record_committed(5, [unwound]);
+        cache.record_pending([(pending, vec![1])]);
+
+        cache.forget_commits_after(4);
+        assert!(cache.get(&kept).is_some());
+        assert!(cache.get(&unwound).is_none());
+        assert!(cache.get(&pending).is_some());
+    }
+
+    #[test]
+    fn expired_results_are_not_returned() {
+        let cache = TxResultCache::new(DEFAULT_TTL);
+        let hash = HashValue::random();
+        let stale = now_secs() - DEFAULT_TTL.as_secs() - 1;
+        cache
+            .memory
+            .lock()
+            .put(hash, TxResult { status: TxStatus::Pending, updated_at_secs: stale });
+        assert_eq!(cache.get(&hash), None);
+    }
+}
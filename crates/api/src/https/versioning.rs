@@ -0,0 +1,116 @@
+//! Versioned route prefixes.
+//!
+//! The public routes are served under `/v1`, e.g. `/v1/consensus/latest_ledger_info`. The
+//! unprefixed paths they had before versioning still work, answering exactly like `/v1`, but
+//! carry `Deprecation`, `Link` (to the `/v1` path) and `Warning` headers, plus `Sunset` once a
+//! removal date is announced through `legacy_paths.sunset` of the API config.
+//! `/admin/route_usage` counts the two separately, which shows who still has to migrate.
+//! Routes added since are served under `/v1` only, and the node's own clients (peer consistency
+//! checks, gravity-cli, sentinel) call `/v1` paths, so they keep working without legacy paths.
+//!
+//! A breaking change to a response shape goes into a new prefix; the handler producing the
+//! old shape stays registered under the older one as its compatibility shim. Admin endpoints
+//! are operator-facing and not versioned.
+
+use crate::https::config::LegacyPathsConfig;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderName, HeaderValue, Request},
+    middleware::{self, Next},
+    response::Response,
+    Router,
+};
+use gaptos::aptos_logger::warn;
+use std::sync::Arc;
+
+/// Prefix of the current API version.
+pub const API_V1: &str = "/v1";
+
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+struct Deprecation {
+    sunset: Option<HeaderValue>,
+}
+
+impl Deprecation {
+    fn new(config: &LegacyPathsConfig) -> Self {
+        let sunset = config.sunset.as_deref().and_then(|s| match HeaderValue::from_str(s) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!("Ignoring legacy_paths.sunset: '{s}' is not a valid header value");
+                None
+            }
+        });
+        Self { sunset }
+    }
+}
+
+/// `route` without its version prefix, e.g. `/consensus/*` for `/v1/consensus/*`. Settings
+/// that name routes, such as token permissions, use these so they cover every version.
+pub(crate) fn unversioned(route: &str) -> &str {
+    match route.strip_prefix(API_V1) {
+        Some(rest) if rest.starts_with('/') => rest,
+        _ => route,
+    }
+}
+
+/// Serves `routes` under [`API_V1`] only; for routes that never had an unprefixed path.
+pub(crate) fn v1_only<S>(routes: Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new().nest(API_V1, routes)
+}
+
+/// Serves `routes` under [`API_V1`] and, when `legacy_paths` are enabled, also at their
+/// unprefixed paths marked as deprecated.
+pub(crate) fn versioned<S>(routes: Router<S>, legacy_paths: &LegacyPathsConfig) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let current = v1_only(routes.clone());
+    if !legacy_paths.enabled {
+        return current;
+    }
+    let deprecation = Arc::new(Deprecation::new(legacy_paths));
+    current.merge(routes.layer(middleware::from_fn_with_state(deprecation, deprecate_legacy_path)))
+}
+
+async fn deprecate_legacy_path(
+    State(deprecation): State<Arc<Deprecation>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let successor = format!("<{API_V1}{}>; rel=\"successor-version\"", req.uri().path());
+    let mut response = next.run(req).await;
+    let headers = response.headers_mut();
+    headers.insert(DEPRECATION, HeaderValue::from_static("true"));
+    if let Ok(link) = HeaderValue::from_str(&successor) {
+        headers.insert(header::LINK, link);
+    }
+    headers.insert(
+        header::WARNING,
+        HeaderValue::from_static("299 - \"Deprecated API path, use the /v1 prefix\""),
+    );
+    if let Some(sunset) = &deprecation.sunset {
+        headers.insert(SUNSET, sunset.clone());
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_only_the_version_prefix() {
+        assert_eq!(
+            unversioned("/v1/consensus/block/:epoch/:round"),
+            "/consensus/block/:epoch/:round"
+        );
+        assert_eq!(unversioned("/consensus/sync_status"), "/consensus/sync_status");
+        assert_eq!(unversioned("/v1x/app"), "/v1x/app");
+    }
+}
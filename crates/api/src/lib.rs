@@ -2,13 +2,19 @@ mod bootstrap;
 pub mod config_storage;
 pub mod consensus_api;
 mod consensus_mempool_handler;
+mod grpc;
 mod https;
 mod logger;
 mod network;
 
-pub use bootstrap::check_bootstrap_config;
+pub use bootstrap::{check_bootstrap_config, load_api_config};
 use clap::Parser;
 pub use gaptos::aptos_config::config::NodeConfig;
+pub use https::{
+    mempool_feed, AdminConfig, AdmissionWebhookConfig, ApiConfig, ColdTierConfig,
+    ConsistencyConfig, FailMode, LegacyPathsConfig, SubscriptionLimits, TlsMinVersion, TlsSettings,
+    TokensConfig, TxCacheConfig,
+};
 use std::path::PathBuf;
 
 /// Runs an Gravity validator or fullnode
@@ -22,4 +28,8 @@ pub struct GravityNodeArgs {
     #[arg(long = "relayer_config", value_name = "RELAYER_CONFIG", global = true)]
     /// Path to relayer configuration file (JSON format with URI to RPC URL mappings).
     pub relayer_config_path: Option<PathBuf>,
+
+    #[arg(long = "api_config", value_name = "API_CONFIG", global = true)]
+    /// Path to the HTTP API configuration file (YAML); every setting has a default without it.
+    pub api_config_path: Option<PathBuf>,
 }
@@ -61,6 +61,7 @@ pub fn create_logger(
 
     // Create the logger and the logger filter updater
     let logger = logger_builder.build();
+    crate::https::log_level::register_logger(logger.clone(), node_config.logger.level);
     let logger_filter_updater: LoggerFilterUpdater =
         LoggerFilterUpdater::new(logger, logger_builder);
 
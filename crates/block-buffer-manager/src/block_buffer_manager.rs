@@ -67,6 +67,33 @@ pub trait TxPool: Send + Sync + 'static {
     fn remove_txns(&self, txns: Vec<VerifiedTxn>);
 }
 
+/// Lets one pool be shared between the mempool and other submitters such as the HTTP API.
+impl<T: TxPool + ?Sized> TxPool for Arc<T> {
+    fn best_txns(
+        &self,
+        filter: Option<TxFilterFn>,
+        limit: usize,
+        max_bytes: u64,
+    ) -> Box<dyn Iterator<Item = VerifiedTxn>> {
+        (**self).best_txns(filter, limit, max_bytes)
+    }
+
+    fn get_broadcast_txns(
+        &self,
+        filter: Option<TxFilterFn>,
+    ) -> Box<dyn Iterator<Item = VerifiedTxn>> {
+        (**self).get_broadcast_txns(filter)
+    }
+
+    fn add_external_txn(&self, txns: VerifiedTxn) -> bool {
+        (**self).add_external_txn(txns)
+    }
+
+    fn remove_txns(&self, txns: Vec<VerifiedTxn>) {
+        (**self).remove_txns(txns)
+    }
+}
+
 pub struct EmptyTxPool {}
 
 impl EmptyTxPool {